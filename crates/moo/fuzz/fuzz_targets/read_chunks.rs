@@ -0,0 +1,38 @@
+#![no_main]
+
+use binrw::BinRead;
+use libfuzzer_sys::fuzz_target;
+use moo::{
+    prelude::*,
+    types::{
+        chunks::{MooBytesChunk, MooChunkHeader, MooFileHeader, MooLicenseChunk, MooNameChunk, MooTagsChunk},
+        ram::MooRamEntries,
+        MooException,
+        MooFileMetadata,
+        MooTestGenMetadata,
+    },
+};
+use std::io::Cursor;
+
+// The first byte selects a chunk payload type; the rest is parsed as that payload.
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, payload)) = data.split_first()
+    else {
+        return;
+    };
+    let mut reader = Cursor::new(payload);
+    match selector % 12 {
+        0 => _ = MooChunkHeader::read(&mut reader),
+        1 => _ = MooFileHeader::read(&mut reader),
+        2 => _ = MooFileMetadata::read(&mut reader),
+        3 => _ = MooNameChunk::read(&mut reader),
+        4 => _ = MooBytesChunk::read(&mut reader),
+        5 => _ = MooTagsChunk::read(&mut reader),
+        6 => _ = MooLicenseChunk::read(&mut reader),
+        7 => _ = MooRamEntries::read(&mut reader),
+        8 => _ = MooRegisters16::read(&mut reader),
+        9 => _ = MooRegisters32::read(&mut reader),
+        10 => _ = MooException::read(&mut reader),
+        _ => _ = MooTestGenMetadata::read(&mut reader),
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use moo::prelude::*;
+use std::io::Cursor;
+
+// Any file that reads successfully must also write back out.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(test_file) = MooTestFile::read(&mut Cursor::new(data)) {
+        let mut buf = Cursor::new(Vec::new());
+        test_file
+            .write(&mut buf, true)
+            .expect("Failed to write a file that was read");
+    }
+});
@@ -0,0 +1,30 @@
+#![no_main]
+
+use arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use moo::{prelude::*, types::MooCpuType};
+use std::io::Cursor;
+
+fn write(test_file: &MooTestFile) -> Vec<u8> {
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).expect("Failed to write test file");
+    buf.into_inner()
+}
+
+// A generated test must read back and write out to the same bytes.
+fuzz_target!(|data: &[u8]| {
+    let Ok(test) = MooTest::arbitrary(&mut Unstructured::new(data))
+    else {
+        return;
+    };
+    let cpu_type = match test.initial_state().regs() {
+        MooRegisters::Sixteen(_) => MooCpuType::Intel80286,
+        MooRegisters::ThirtyTwo(_) => MooCpuType::Intel80386Ex,
+    };
+    let mut test_file = MooTestFile::new(1, 0, cpu_type, 1);
+    test_file.add_test(test);
+
+    let bytes = write(&test_file);
+    let read_back = MooTestFile::read(&mut Cursor::new(bytes.clone())).expect("Failed to read test file");
+    assert_eq!(write(&read_back), bytes);
+});
@@ -0,0 +1,156 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Traits for converting between MOO test states and an emulator's own state types.
+//!
+//! A test harness implements [FromMooRegisters] and [IntoMooRegisters] for its CPU model, usually
+//! via the [impl_moo_registers](crate::impl_moo_registers) macro, and [FromMooState] and
+//! [IntoMooState] to give access to memory. A test can then be loaded with
+//! [FromMooState::apply_moo_state] and checked with [IntoMooState::compare_with_test].
+
+use crate::{
+    registers::{MooRegister, MooRegisters},
+    types::{MooCompareOptions, MooComparison, MooCycleState, MooRamEntry, MooTest, MooTestState},
+};
+
+/// A type whose registers can be set from [MooRegisters], one register at a time.
+pub trait FromMooRegisters {
+    /// Set a single register. Registers the type does not model may be ignored.
+    fn set_register(&mut self, reg: MooRegister, value: u32);
+
+    /// Set each register present in the mask of `regs`.
+    fn apply_moo_registers(&mut self, regs: &MooRegisters) {
+        regs.visit_set(|reg, value| self.set_register(reg, value));
+    }
+}
+
+/// A type whose registers can be read out as [MooRegisters].
+pub trait IntoMooRegisters {
+    /// Return the value of a single register, or `None` if the type does not model it.
+    fn register_value(&self, reg: MooRegister) -> Option<u32>;
+
+    /// Build a [MooRegisters] of the given width from every register the type models.
+    fn to_moo_registers(&self, wide: bool) -> MooRegisters {
+        let (names, registers): (&[&str], &[MooRegister]) = if wide {
+            (&MooRegisters::NAMES32, &MooRegisters::REGISTERS32)
+        }
+        else {
+            (&MooRegisters::NAMES16, &MooRegisters::REGISTERS16)
+        };
+        let values = names
+            .iter()
+            .zip(registers)
+            .filter_map(|(name, reg)| self.register_value(*reg).map(|value| (*name, value)));
+        MooRegisters::from_named_values(wide, values).expect("register names are valid")
+    }
+}
+
+/// A type that can be loaded with the contents of a [MooTestState].
+pub trait FromMooState: FromMooRegisters {
+    /// Write a byte of memory.
+    fn write_memory(&mut self, address: u32, value: u8);
+
+    /// Load the instruction queue. The default implementation ignores it.
+    fn set_queue(&mut self, _queue: &[u8]) {}
+
+    /// Load the registers, memory and instruction queue of `state`.
+    fn apply_moo_state(&mut self, state: &MooTestState) {
+        self.apply_moo_registers(state.regs());
+        for entry in state.ram() {
+            self.write_memory(entry.address, entry.value);
+        }
+        self.set_queue(state.queue());
+    }
+}
+
+/// A type whose state can be compared against a test's final state.
+pub trait IntoMooState: IntoMooRegisters {
+    /// Read a byte of memory, or `None` if the address is not available.
+    fn read_memory(&self, address: u32) -> Option<u8>;
+
+    /// Return the bus cycles of the last execution, if recorded. The default implementation
+    /// returns `None`, so cycles are not compared.
+    fn cycles(&self) -> Option<Vec<MooCycleState>> {
+        None
+    }
+
+    /// Read the memory at each address in `addresses`, skipping unavailable addresses.
+    fn to_moo_ram(&self, addresses: impl IntoIterator<Item = u32>) -> Vec<MooRamEntry> {
+        addresses
+            .into_iter()
+            .filter_map(|address| self.read_memory(address).map(|value| MooRamEntry { address, value }))
+            .collect()
+    }
+
+    /// Compare this state against the final state of `test`. See [MooTest::compare_final_state].
+    fn compare_with_test(&self, test: &MooTest, options: &MooCompareOptions) -> Vec<MooComparison> {
+        let wide = matches!(test.initial_state().regs(), MooRegisters::ThirtyTwo(_));
+        let regs = self.to_moo_registers(wide);
+        let ram = self.to_moo_ram(test.final_state().ram().iter().map(|e| e.address));
+        let cycles = self.cycles();
+        test.compare_final_state(&regs, &ram, cycles.as_deref(), options)
+    }
+}
+
+/// Implement [FromMooRegisters] and [IntoMooRegisters] for a struct by mapping each
+/// [MooRegister] to an integer field. Values are converted with `as`, so a 16-bit field receives
+/// the low word of a 32-bit register.
+///
+/// ```
+/// #[derive(Default)]
+/// struct Cpu {
+///     ax: u16,
+///     ip: u16,
+///     flags: u16,
+/// }
+///
+/// moo::impl_moo_registers!(Cpu {
+///     AX => ax,
+///     IP => ip,
+///     FLAGS => flags,
+/// });
+/// ```
+#[macro_export]
+macro_rules! impl_moo_registers {
+    ($ty:ty { $($reg:ident => $field:ident),* $(,)? }) => {
+        impl $crate::adapter::FromMooRegisters for $ty {
+            fn set_register(&mut self, reg: $crate::registers::MooRegister, value: u32) {
+                match reg {
+                    $($crate::registers::MooRegister::$reg => self.$field = value as _,)*
+                    #[allow(unreachable_patterns)]
+                    _ => {}
+                }
+            }
+        }
+
+        impl $crate::adapter::IntoMooRegisters for $ty {
+            fn register_value(&self, reg: $crate::registers::MooRegister) -> Option<u32> {
+                match reg {
+                    $($crate::registers::MooRegister::$reg => Some(self.$field as u32),)*
+                    #[allow(unreachable_patterns)]
+                    _ => None,
+                }
+            }
+        }
+    };
+}
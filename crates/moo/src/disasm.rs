@@ -0,0 +1,102 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A pluggable disassembler interface.
+//!
+//! The `moo` crate does not decode instructions itself. Tools that validate or rename tests
+//! implement [MooDisassembler] over the decoder of their choice, selecting the instruction set by
+//! [MooCpuType] so that each CPU family is decoded correctly. [MooDisassemblyNamer] adapts any
+//! disassembler into a [MooNameFormatter] for [MooTestFile::regenerate_names](crate::prelude::MooTestFile::regenerate_names).
+
+use crate::{
+    test::moo_test::MooTest,
+    test_file::names::MooNameFormatter,
+    types::{errors::MooError, MooCpuType},
+};
+
+/// Words that may precede the mnemonic in formatted disassembly.
+const PREFIX_WORDS: [&str; 7] = ["lock", "rep", "repe", "repz", "repne", "repnz", "o32"];
+
+/// A [MooDecodedInstruction] is a single instruction decoded by a [MooDisassembler].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooDecodedInstruction {
+    /// The length of the instruction in bytes, including prefixes.
+    pub length: usize,
+    /// The lowercase mnemonic of the instruction, without prefixes.
+    pub mnemonic: String,
+    /// The formatted instruction text, as used for test names.
+    pub text: String,
+}
+
+impl MooDecodedInstruction {
+    /// Create a [MooDecodedInstruction] from formatted text, taking the mnemonic from the first
+    /// word that is not a prefix such as `lock` or `rep`. For decoders that do not report the
+    /// mnemonic separately.
+    pub fn from_text(length: usize, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let mnemonic = text
+            .split_whitespace()
+            .find(|word| !PREFIX_WORDS.contains(&word.to_ascii_lowercase().as_str()))
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self { length, mnemonic, text }
+    }
+}
+
+/// A [MooDisassembler] decodes instruction bytes for a given [MooCpuType].
+pub trait MooDisassembler {
+    /// Decode the first instruction in `bytes` for `cpu_type`, located at linear address `ip`.
+    /// Returns an error if the bytes do not hold a complete, valid instruction.
+    fn decode(&mut self, cpu_type: MooCpuType, bytes: &[u8], ip: u32) -> Result<MooDecodedInstruction, MooError>;
+}
+
+/// A [MooDisassemblyNamer] is a [MooNameFormatter] that names each test with the disassembly of
+/// its instruction from a [MooDisassembler].
+pub struct MooDisassemblyNamer<D> {
+    disassembler: D,
+    style: String,
+}
+
+impl<D: MooDisassembler> MooDisassemblyNamer<D> {
+    /// Create a [MooDisassemblyNamer] from a disassembler and the name of its output style.
+    pub fn new(disassembler: D, style: impl Into<String>) -> Self {
+        Self {
+            disassembler,
+            style: style.into(),
+        }
+    }
+}
+
+impl<D: MooDisassembler> MooNameFormatter for MooDisassemblyNamer<D> {
+    fn style(&self) -> &str {
+        &self.style
+    }
+
+    fn format_name(&mut self, cpu_type: MooCpuType, test: &MooTest) -> Option<String> {
+        let ip = test.initial_state.csip_linear().unwrap_or(0);
+        self.disassembler
+            .decode(cpu_type, &test.bytes, ip)
+            .ok()
+            .map(|instruction| instruction.text)
+    }
+}
@@ -51,10 +51,17 @@
 /// The maximum major version number of the MOO file format supported by this crate
 pub const MOO_MAJOR_VERSION: u8 = 1;
 /// The maximum minor version number of the MOO file format supported by this crate
-pub const MOO_MINOR_VERSION: u8 = 1;
+pub const MOO_MINOR_VERSION: u8 = 2;
 
+pub mod adapter;
+pub mod disasm;
 pub mod prelude;
 pub mod registers;
+pub mod results;
+#[cfg(any(test, doc, feature = "samples"))]
+pub mod samples;
+pub mod schema;
+pub mod stats;
 mod test;
 pub mod test_file;
 pub mod types;
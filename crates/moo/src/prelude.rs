@@ -22,6 +22,8 @@
 */
 
 pub use crate::{
+    adapter::{FromMooRegisters, FromMooState, IntoMooRegisters, IntoMooState},
+    disasm::{MooDecodedInstruction, MooDisassembler},
     registers::{
         MooRegister,
         MooRegisters,
@@ -31,7 +33,56 @@ pub use crate::{
         MooRegisters32Init,
         MooRegistersInit,
     },
+    schema::{MooSchema, MooSchemaRecord, MooSchemaRules},
+    stats::{MooAddressingForm, MooCorpusSummary, MooCoverageCluster, MooCoverageKey, MooCycleOutlier, MooGroupStats},
     test::moo_test::MooTest,
-    test_file::{stats::MooTestFileStats, MooTestFile},
-    types::{MooCpuFamily, MooCpuType, MooCycleState, MooFileMetadata, MooIvtOrder, MooTestGenMetadata},
+    test_file::{
+        dump::MooDumpOptions,
+        metadata_edit::{MooMetadataEdit, MooMetadataEditSummary},
+        names::MooNameFormatter,
+        normalize::{MooNormalizeProfile, MooNormalizeSummary},
+        read_options::{MooReadLimit, MooReadOptions},
+        redact::{MooRedaction, MooRedactionSummary},
+        search::{MooFileSearch, MooGlob},
+        seed::MooStateGenerator,
+        stats::{MooStatsAccumulator, MooTestFileStats},
+        transform::{transform_corpus, MooCorpusTransform, MooTransformFailure, MooTransformSummary},
+        verify::MooHashMismatch,
+        write_options::{MooCompressionKind, MooWriteOptions},
+        MooTestFile,
+    },
+    types::{
+        align::{align_transactions, MooAlignedDiff, DEFAULT_ALIGN_BAND},
+        anchor::{MooAnchor, MooAnchorKind},
+        arch_tag::MooArchTag,
+        cycle_cursor::MooCycleCursor,
+        cycle_iter::MooCycleIterExt,
+        exception::MooExceptionContext,
+        flags::{MooCpuFlag, MooCpuFlags, MooCpuFlagsDiff},
+        hash::{MooHashAlgorithm, MooTestDigest},
+        interpolate::MooInterpolatedState,
+        io_stimulus::{MooIoStimulus, MooPortWidth},
+        ivt::{MooIvt, MooIvtEntry, MooVectorMismatch},
+        license::MooLicense,
+        mutate::{MooMutation, MooMutator},
+        opcode::MooOpcodeId,
+        prefix::MooPrefix,
+        ram_diff::{ram_diff, MooRamDiffPrinter, MooRamRegion},
+        stack::{MooStackDirection, MooStackFrame, MooStackSlot, MooStackSlotKind},
+        timing::{MooCycleClass, MooTimingBreakdown},
+        trace_svg::MooSvgOptions,
+        trim::{MooTrimPolicy, MooTrimmedCycles},
+        MooBusHistogram,
+        MooBusState,
+        MooCaptureInfo,
+        MooCaptureSource,
+        MooCpuFamily,
+        MooCpuType,
+        MooCycleState,
+        MooFileMetadata,
+        MooIvtOrder,
+        MooQueueDiff,
+        MooTestGenMetadata,
+        MooTimingContext,
+    },
 };
@@ -0,0 +1,438 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Decoding of the GDT and IDT from a test's RAM.
+//!
+//! Protected-mode tests that load a segment register or take an interrupt seed the descriptor
+//! table entries they use into RAM. The `MOO` format does not record `GDTR` or `IDTR`, so the caller
+//! supplies the table location as a [MooTableRegister]. [MooDescriptorTable::from_ram] decodes
+//! the entries present in RAM, and [MooTestState::descriptor_cache_mismatches] checks a state's
+//! descriptor caches against them.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    registers::{MooDescriptors, MooSegmentRegister},
+    test::test_state::MooTestState,
+    types::{MooCpuFamily, MooRamEntry},
+};
+
+/// The size of a descriptor table entry in bytes.
+pub const DESCRIPTOR_SIZE: u32 = 8;
+
+/// The base address and limit of a descriptor table, as loaded into `GDTR` or `IDTR`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooTableRegister {
+    pub base:  u32,
+    /// The offset of the last valid byte of the table.
+    pub limit: u16,
+}
+
+impl MooTableRegister {
+    pub fn new(base: u32, limit: u16) -> Self {
+        Self { base, limit }
+    }
+
+    /// The number of entries that fit within the limit.
+    pub fn entry_ct(&self) -> u32 {
+        (self.limit as u32 + 1) / DESCRIPTOR_SIZE
+    }
+}
+
+/// The type of a system descriptor, from the low four bits of its access byte.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooSystemType {
+    Tss16,
+    Ldt,
+    Tss16Busy,
+    CallGate16,
+    TaskGate,
+    InterruptGate16,
+    TrapGate16,
+    Tss32,
+    Tss32Busy,
+    CallGate32,
+    InterruptGate32,
+    TrapGate32,
+    Reserved(u8),
+}
+
+impl MooSystemType {
+    pub fn from_access(access: u8) -> Self {
+        match access & 0x0F {
+            1 => MooSystemType::Tss16,
+            2 => MooSystemType::Ldt,
+            3 => MooSystemType::Tss16Busy,
+            4 => MooSystemType::CallGate16,
+            5 => MooSystemType::TaskGate,
+            6 => MooSystemType::InterruptGate16,
+            7 => MooSystemType::TrapGate16,
+            9 => MooSystemType::Tss32,
+            11 => MooSystemType::Tss32Busy,
+            12 => MooSystemType::CallGate32,
+            14 => MooSystemType::InterruptGate32,
+            15 => MooSystemType::TrapGate32,
+            other => MooSystemType::Reserved(other),
+        }
+    }
+
+    /// Returns true for call, task, interrupt and trap gates.
+    pub fn is_gate(&self) -> bool {
+        matches!(
+            self,
+            MooSystemType::CallGate16
+                | MooSystemType::TaskGate
+                | MooSystemType::InterruptGate16
+                | MooSystemType::TrapGate16
+                | MooSystemType::CallGate32
+                | MooSystemType::InterruptGate32
+                | MooSystemType::TrapGate32
+        )
+    }
+}
+
+/// A segment descriptor, describing a code, data or system segment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooSegmentDescriptor {
+    pub base:   u32,
+    /// The segment limit in bytes, scaled by the granularity bit.
+    pub limit:  u32,
+    /// The access rights byte.
+    pub access: u8,
+    /// The `G`, `D/B` and `AVL` bits, in the high nibble as encoded. Always zero on the 286.
+    pub flags:  u8,
+}
+
+impl MooSegmentDescriptor {
+    pub const ACCESS_PRESENT: u8 = 0x80;
+    pub const ACCESS_SEGMENT: u8 = 0x10;
+    pub const ACCESS_CODE: u8 = 0x08;
+    pub const ACCESS_ACCESSED: u8 = 0x01;
+    pub const FLAG_GRANULARITY: u8 = 0x80;
+    pub const FLAG_DEFAULT_SIZE: u8 = 0x40;
+
+    pub fn is_present(&self) -> bool {
+        self.access & Self::ACCESS_PRESENT != 0
+    }
+
+    /// The descriptor privilege level.
+    pub fn dpl(&self) -> u8 {
+        (self.access >> 5) & 0x03
+    }
+
+    /// Returns true for a code segment.
+    pub fn is_code(&self) -> bool {
+        self.access & (Self::ACCESS_SEGMENT | Self::ACCESS_CODE) == Self::ACCESS_SEGMENT | Self::ACCESS_CODE
+    }
+
+    /// Returns the system descriptor type, or `None` for a code or data segment.
+    pub fn system_type(&self) -> Option<MooSystemType> {
+        (self.access & Self::ACCESS_SEGMENT == 0).then(|| MooSystemType::from_access(self.access))
+    }
+
+    /// Returns true if the `D/B` bit is set.
+    pub fn is_32bit(&self) -> bool {
+        self.flags & Self::FLAG_DEFAULT_SIZE != 0
+    }
+
+    /// Return the access rights as held in a descriptor cache, in the `LOADALL` layout used by
+    /// [MooDescriptor32](crate::registers::descriptors_32::MooDescriptor32).
+    pub fn cache_access(&self) -> u32 {
+        ((self.access as u32) << 8)
+            | if self.is_32bit() { 1 << 22 } else { 0 }
+            | if self.flags & Self::FLAG_GRANULARITY != 0 {
+                1 << 23
+            }
+            else {
+                0
+            }
+    }
+}
+
+/// A call, task, interrupt or trap gate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooGateDescriptor {
+    pub selector: u16,
+    /// The entry point offset. Task gates have none.
+    pub offset:   u32,
+    /// The number of stack parameters copied by a call gate.
+    pub param_ct: u8,
+    pub access:   u8,
+}
+
+impl MooGateDescriptor {
+    pub fn gate_type(&self) -> MooSystemType {
+        MooSystemType::from_access(self.access)
+    }
+
+    pub fn is_present(&self) -> bool {
+        self.access & MooSegmentDescriptor::ACCESS_PRESENT != 0
+    }
+
+    /// The descriptor privilege level.
+    pub fn dpl(&self) -> u8 {
+        (self.access >> 5) & 0x03
+    }
+}
+
+/// A decoded descriptor table entry.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooDescriptor {
+    Segment(MooSegmentDescriptor),
+    Gate(MooGateDescriptor),
+}
+
+impl MooDescriptor {
+    /// Decode an 8-byte descriptor. On the 286, the last word is reserved and the base, limit and
+    /// offset fields are limited to 24, 16 and 16 bits.
+    pub fn decode(bytes: &[u8; 8], family: MooCpuFamily) -> Self {
+        let wide = matches!(family, MooCpuFamily::Intel80386);
+        let word = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]) as u32;
+        let access = bytes[5];
+
+        if access & MooSegmentDescriptor::ACCESS_SEGMENT == 0 && MooSystemType::from_access(access).is_gate() {
+            return MooDescriptor::Gate(MooGateDescriptor {
+                selector: word(2) as u16,
+                offset: word(0) | if wide { word(6) << 16 } else { 0 },
+                param_ct: bytes[4] & 0x1F,
+                access,
+            });
+        }
+
+        let mut base = word(2) | (bytes[4] as u32) << 16;
+        let mut limit = word(0);
+        let mut flags = 0;
+        if wide {
+            base |= (bytes[7] as u32) << 24;
+            limit |= ((bytes[6] & 0x0F) as u32) << 16;
+            flags = bytes[6] & 0xF0;
+            if flags & MooSegmentDescriptor::FLAG_GRANULARITY != 0 {
+                limit = (limit << 12) | 0xFFF;
+            }
+        }
+        MooDescriptor::Segment(MooSegmentDescriptor {
+            base,
+            limit,
+            access,
+            flags,
+        })
+    }
+}
+
+impl Display for MooDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooDescriptor::Segment(d) => write!(
+                f,
+                "Access:{:02X} Flags:{:X} Base:{:08X} Limit:{:08X}",
+                d.access,
+                d.flags >> 4,
+                d.base,
+                d.limit
+            ),
+            MooDescriptor::Gate(g) => write!(
+                f,
+                "{:?} Access:{:02X} Target:{:04X}:{:08X}",
+                g.gate_type(),
+                g.access,
+                g.selector,
+                g.offset
+            ),
+        }
+    }
+}
+
+/// An entry of a [MooDescriptorTable].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooTableEntry {
+    /// The index of the entry in the table. For a GDT, the selector is `index << 3`.
+    pub index: u16,
+    /// The linear address of the entry.
+    pub address: u32,
+    pub descriptor: MooDescriptor,
+}
+
+/// The entries of a GDT or IDT that are present in a set of RAM entries, in index order. Entries
+/// with any of their eight bytes missing are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooDescriptorTable {
+    entries: Vec<MooTableEntry>,
+}
+
+impl MooDescriptorTable {
+    /// Decode the entries of the table at `table` that are present in `ram`, using the descriptor
+    /// format of the given [MooCpuFamily].
+    pub fn from_ram(ram: &[MooRamEntry], table: MooTableRegister, family: MooCpuFamily) -> Self {
+        let table_end = table.base as u64 + table.entry_ct() as u64 * DESCRIPTOR_SIZE as u64;
+        let bytes: BTreeMap<u32, u8> = ram
+            .iter()
+            .filter(|e| e.address >= table.base && (e.address as u64) < table_end)
+            .map(|e| (e.address, e.value))
+            .collect();
+
+        let mut indices: Vec<u32> = bytes
+            .keys()
+            .map(|address| (address - table.base) / DESCRIPTOR_SIZE)
+            .collect();
+        indices.dedup();
+        let entries = indices
+            .into_iter()
+            .filter_map(|index| {
+                let address = table.base + index * DESCRIPTOR_SIZE;
+                let mut raw = [0u8; 8];
+                for (i, byte) in raw.iter_mut().enumerate() {
+                    *byte = *bytes.get(&(address + i as u32))?;
+                }
+                Some(MooTableEntry {
+                    index: index as u16,
+                    address,
+                    descriptor: MooDescriptor::decode(&raw, family),
+                })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Return the entry at `index`, if present.
+    pub fn get(&self, index: u16) -> Option<&MooTableEntry> {
+        self.entries
+            .binary_search_by_key(&index, |e| e.index)
+            .ok()
+            .map(|pos| &self.entries[pos])
+    }
+
+    /// Return the entry referenced by a selector, ignoring its RPL and table indicator bits.
+    pub fn get_selector(&self, selector: u16) -> Option<&MooTableEntry> {
+        self.get(selector >> 3)
+    }
+
+    pub fn entries(&self) -> &[MooTableEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A descriptor cache field checked by [MooTestState::descriptor_cache_mismatches].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooDescriptorField {
+    Base,
+    Limit,
+    Access,
+}
+
+/// A descriptor cache that disagrees with the GDT entry its selector refers to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooDescriptorMismatch {
+    pub segment:  MooSegmentRegister,
+    pub selector: u16,
+    pub field:    MooDescriptorField,
+    /// The value held in the descriptor cache.
+    pub cached:   u32,
+    /// The value decoded from the table.
+    pub table:    u32,
+}
+
+impl Display for MooDescriptorMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} (selector {:04X}) {:?}: cache {:08X}, table {:08X}",
+            self.segment, self.selector, self.field, self.cached, self.table
+        )
+    }
+}
+
+impl MooTestState {
+    /// Decode the entries of the descriptor table at `table` present in this state's RAM.
+    pub fn descriptor_table(&self, table: MooTableRegister, family: MooCpuFamily) -> MooDescriptorTable {
+        MooDescriptorTable::from_ram(&self.ram, table, family)
+    }
+
+    /// Compare this state's descriptor caches with the GDT entries selected by its segment
+    /// registers. Null selectors, LDT selectors and selectors whose entry is not in `gdt` are
+    /// skipped, as are states without descriptor caches.
+    ///
+    /// The accessed bit is ignored, since the CPU sets it in memory when loading a descriptor.
+    /// 286 caches are assumed to use the same `LOADALL` access layout as the 386.
+    pub fn descriptor_cache_mismatches(&self, gdt: &MooDescriptorTable) -> Vec<MooDescriptorMismatch> {
+        const ACCESS_MASK: u32 = 0x00C0_FE00;
+        let mut mismatches = Vec::new();
+        let Some(descriptors) = &self.descriptors
+        else {
+            return mismatches;
+        };
+
+        use MooSegmentRegister::*;
+        for segment in [CS, SS, DS, ES, FS, GS] {
+            let cache = match descriptors {
+                MooDescriptors::Sixteen(d) => d.get(segment).map(|c| (c.access, c.base, c.limit)),
+                MooDescriptors::ThirtyTwo(d) => {
+                    let c = d.get(segment);
+                    Some((c.access, c.base, c.limit))
+                }
+            };
+            let (Some((access, base, limit)), Some(selector)) = (cache, self.regs.segment(segment))
+            else {
+                continue;
+            };
+            // Skip null and LDT selectors.
+            if selector & !0x03 == 0 || selector & 0x04 != 0 {
+                continue;
+            }
+            let Some(MooTableEntry {
+                descriptor: MooDescriptor::Segment(descriptor),
+                ..
+            }) = gdt.get_selector(selector)
+            else {
+                continue;
+            };
+
+            let mut check = |field, cached: u32, table: u32| {
+                if cached != table {
+                    mismatches.push(MooDescriptorMismatch {
+                        segment,
+                        selector,
+                        field,
+                        cached,
+                        table,
+                    });
+                }
+            };
+            check(MooDescriptorField::Base, base, descriptor.base);
+            check(MooDescriptorField::Limit, limit, descriptor.limit);
+            check(
+                MooDescriptorField::Access,
+                access & ACCESS_MASK,
+                descriptor.cache_access() & ACCESS_MASK,
+            );
+        }
+        mismatches
+    }
+}
@@ -24,7 +24,11 @@ use std::fmt::Display;
 
 use binrw::binrw;
 
-#[derive(Clone, Debug, PartialEq)]
+use crate::registers::MooSegmentRegister;
+
+/// A [MooDescriptor16] is the hidden descriptor cache of a 286 segment register. 286 segments
+/// are always 16-bit, so there is no default size bit.
+#[derive(Clone, Debug, Default, PartialEq)]
 #[binrw]
 #[brw(little)]
 pub struct MooDescriptor16 {
@@ -43,7 +47,27 @@ impl Display for MooDescriptor16 {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// The descriptor caches of the four 286 segment registers.
+#[derive(Clone, Debug, Default, PartialEq)]
 #[binrw]
 #[brw(little)]
-pub struct MooDescriptors16 {}
+pub struct MooDescriptors16 {
+    pub es: MooDescriptor16,
+    pub cs: MooDescriptor16,
+    pub ss: MooDescriptor16,
+    pub ds: MooDescriptor16,
+}
+
+impl MooDescriptors16 {
+    /// Returns the descriptor cache of the given segment register, or None for `FS` and `GS`,
+    /// which the 286 does not have.
+    pub fn get(&self, segment: MooSegmentRegister) -> Option<&MooDescriptor16> {
+        match segment {
+            MooSegmentRegister::ES => Some(&self.es),
+            MooSegmentRegister::CS => Some(&self.cs),
+            MooSegmentRegister::SS => Some(&self.ss),
+            MooSegmentRegister::DS => Some(&self.ds),
+            MooSegmentRegister::FS | MooSegmentRegister::GS => None,
+        }
+    }
+}
@@ -24,7 +24,12 @@ use std::fmt::Display;
 
 use binrw::binrw;
 
-#[derive(Clone, Debug, PartialEq)]
+use crate::registers::MooSegmentRegister;
+
+/// A [MooDescriptor32] is the hidden descriptor cache of a 386 segment register. The `access`
+/// field uses the `LOADALL` layout: the access rights byte in bits 8-15, the `D/B` bit in bit 22
+/// and the granularity bit in bit 23.
+#[derive(Clone, Debug, Default, PartialEq)]
 #[binrw]
 #[brw(little)]
 pub struct MooDescriptor32 {
@@ -33,6 +38,17 @@ pub struct MooDescriptor32 {
     pub limit:  u32,
 }
 
+impl MooDescriptor32 {
+    /// The default operand size (`D/B`) bit of the `access` field.
+    pub const D_BIT: u32 = 1 << 22;
+
+    /// Returns true if the `D/B` bit is set. For a code segment this selects 32-bit default
+    /// operand and address sizes.
+    pub fn is_32bit(&self) -> bool {
+        self.access & Self::D_BIT != 0
+    }
+}
+
 impl Display for MooDescriptor32 {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -43,7 +59,41 @@ impl Display for MooDescriptor32 {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// The descriptor caches of all six 386 segment registers, as stored in a `DC32` chunk.
+#[derive(Clone, Debug, Default, PartialEq)]
 #[binrw]
 #[brw(little)]
-pub struct MooDescriptors32 {}
+pub struct MooDescriptors32 {
+    pub cs: MooDescriptor32,
+    pub ss: MooDescriptor32,
+    pub ds: MooDescriptor32,
+    pub es: MooDescriptor32,
+    pub fs: MooDescriptor32,
+    pub gs: MooDescriptor32,
+}
+
+impl MooDescriptors32 {
+    /// Returns the descriptor cache of the given segment register.
+    pub fn get(&self, segment: MooSegmentRegister) -> &MooDescriptor32 {
+        match segment {
+            MooSegmentRegister::CS => &self.cs,
+            MooSegmentRegister::SS => &self.ss,
+            MooSegmentRegister::DS => &self.ds,
+            MooSegmentRegister::ES => &self.es,
+            MooSegmentRegister::FS => &self.fs,
+            MooSegmentRegister::GS => &self.gs,
+        }
+    }
+
+    /// Returns a mutable reference to the descriptor cache of the given segment register.
+    pub fn get_mut(&mut self, segment: MooSegmentRegister) -> &mut MooDescriptor32 {
+        match segment {
+            MooSegmentRegister::CS => &mut self.cs,
+            MooSegmentRegister::SS => &mut self.ss,
+            MooSegmentRegister::DS => &mut self.ds,
+            MooSegmentRegister::ES => &mut self.es,
+            MooSegmentRegister::FS => &mut self.fs,
+            MooSegmentRegister::GS => &mut self.gs,
+        }
+    }
+}
@@ -24,16 +24,17 @@
 //! # Registers
 //! This module provides types that represent CPU registers and segment descriptors.
 
+pub mod descriptor_table;
 pub mod descriptors_16;
 pub mod descriptors_32;
 pub mod registers_16;
 pub mod registers_32;
 
-use std::fmt::Display;
+use std::{fmt::Display, io::Cursor};
 
-use crate::types::{chunks::MooChunkType, MooCpuType};
+use crate::types::{chunks::MooChunkType, errors::MooError, flags::MooCpuFlags, MooCpuType, MooSegmentSize};
 
-use binrw::binrw;
+use binrw::{binrw, BinRead, BinWrite};
 
 use crate::registers::{descriptors_16::MooDescriptors16, descriptors_32::MooDescriptors32};
 pub use registers_16::{MooRegisters16, MooRegisters16Init, MooRegisters16Printer};
@@ -100,6 +101,16 @@ pub enum MooSegmentRegister {
 }
 
 impl MooRegister {
+    /// Look up a register by its name as used in [MooRegisters::NAMES16] or
+    /// [MooRegisters::NAMES32], ignoring case.
+    pub fn from_name(name: &str) -> Option<MooRegister> {
+        let find = |names: &[&str], regs: &[MooRegister]| {
+            names.iter().position(|n| n.eq_ignore_ascii_case(name)).map(|i| regs[i])
+        };
+        find(&MooRegisters::NAMES16, &MooRegisters::REGISTERS16)
+            .or_else(|| find(&MooRegisters::NAMES32, &MooRegisters::REGISTERS32))
+    }
+
     pub fn is_32bit(&self) -> bool {
         matches!(
             self,
@@ -142,6 +153,36 @@ pub enum MooDescriptors {
     ThirtyTwo(MooDescriptors32),
 }
 
+impl MooDescriptors {
+    /// Returns the default size of the code segment, taken from the `D` bit of the CS descriptor
+    /// cache on the 386. 286 code segments are always 16-bit.
+    pub fn code_segment_size(&self) -> MooSegmentSize {
+        match self {
+            MooDescriptors::Sixteen(_) => MooSegmentSize::Sixteen,
+            MooDescriptors::ThirtyTwo(descriptors) if descriptors.cs.is_32bit() => MooSegmentSize::ThirtyTwo,
+            MooDescriptors::ThirtyTwo(_) => MooSegmentSize::Sixteen,
+        }
+    }
+
+    /// Returns the cached base address of the given segment register, or None if the CPU does
+    /// not have that segment register.
+    pub fn base(&self, segment: MooSegmentRegister) -> Option<u32> {
+        match self {
+            MooDescriptors::Sixteen(descriptors) => descriptors.get(segment).map(|d| d.base),
+            MooDescriptors::ThirtyTwo(descriptors) => Some(descriptors.get(segment).base),
+        }
+    }
+
+    /// Returns true if offsets into the given segment are 32 bits wide. For the stack segment this
+    /// is the `B` bit, selecting `ESP` over `SP`.
+    pub fn is_32bit(&self, segment: MooSegmentRegister) -> bool {
+        match self {
+            MooDescriptors::Sixteen(_) => false,
+            MooDescriptors::ThirtyTwo(descriptors) => descriptors.get(segment).is_32bit(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 #[binrw]
 #[brw(little)]
@@ -201,6 +242,225 @@ impl Default for MooRegisters {
 }
 
 impl MooRegisters {
+    /// Lowercase register names of a [MooRegisters16], in field and mask-bit order.
+    pub const NAMES16: [&'static str; 14] = [
+        "ax", "bx", "cx", "dx", "cs", "ss", "ds", "es", "sp", "bp", "si", "di", "ip", "flags",
+    ];
+    /// Lowercase register names of a [MooRegisters32], in field and mask-bit order.
+    pub const NAMES32: [&'static str; 20] = [
+        "cr0", "cr3", "eax", "ebx", "ecx", "edx", "esi", "edi", "ebp", "esp", "cs", "ds", "es", "fs", "gs", "ss",
+        "eip", "eflags", "dr6", "dr7",
+    ];
+    /// The [MooRegister] named by each entry of [Self::NAMES16].
+    pub const REGISTERS16: [MooRegister; 14] = [
+        MooRegister::AX,
+        MooRegister::BX,
+        MooRegister::CX,
+        MooRegister::DX,
+        MooRegister::CS,
+        MooRegister::SS,
+        MooRegister::DS,
+        MooRegister::ES,
+        MooRegister::SP,
+        MooRegister::BP,
+        MooRegister::SI,
+        MooRegister::DI,
+        MooRegister::IP,
+        MooRegister::FLAGS,
+    ];
+    /// The [MooRegister] named by each entry of [Self::NAMES32].
+    pub const REGISTERS32: [MooRegister; 20] = [
+        MooRegister::CR0,
+        MooRegister::CR3,
+        MooRegister::EAX,
+        MooRegister::EBX,
+        MooRegister::ECX,
+        MooRegister::EDX,
+        MooRegister::ESI,
+        MooRegister::EDI,
+        MooRegister::EBP,
+        MooRegister::ESP,
+        MooRegister::CS,
+        MooRegister::DS,
+        MooRegister::ES,
+        MooRegister::FS,
+        MooRegister::GS,
+        MooRegister::SS,
+        MooRegister::EIP,
+        MooRegister::EFLAGS,
+        MooRegister::DR6,
+        MooRegister::DR7,
+    ];
+
+    /// Expand a final register delta into a full register set, taking registers absent from the
+    /// delta's mask from `initial`. Registers of different widths are returned unchanged.
+    pub fn rehydrate(&self, initial: &MooRegisters) -> MooRegisters {
+        match (self, initial) {
+            (MooRegisters::Sixteen(f), MooRegisters::Sixteen(i)) => MooRegisters::Sixteen(f.rehydrate(i)),
+            (MooRegisters::ThirtyTwo(f), MooRegisters::ThirtyTwo(i)) => MooRegisters::ThirtyTwo(f.rehydrate(i)),
+            _ => self.clone(),
+        }
+    }
+
+    /// Return the register mask, and the register and raw value of each field, in field and
+    /// mask-bit order.
+    fn fields(&self) -> (u32, &'static [MooRegister], Vec<u32>) {
+        match self {
+            MooRegisters::Sixteen(r) => (
+                r.reg_mask as u32,
+                &Self::REGISTERS16,
+                [
+                    r.ax, r.bx, r.cx, r.dx, r.cs, r.ss, r.ds, r.es, r.sp, r.bp, r.si, r.di, r.ip, r.flags,
+                ]
+                .map(u32::from)
+                .to_vec(),
+            ),
+            MooRegisters::ThirtyTwo(r) => (
+                r.reg_mask,
+                &Self::REGISTERS32,
+                vec![
+                    r.cr0, r.cr3, r.eax, r.ebx, r.ecx, r.edx, r.esi, r.edi, r.ebp, r.esp, r.cs, r.ds, r.es, r.fs, r.gs,
+                    r.ss, r.eip, r.eflags, r.dr6, r.dr7,
+                ],
+            ),
+        }
+    }
+
+    /// Call `f` with every register and its value, in field order, whether or not the register is
+    /// present in the register mask.
+    pub fn visit(&self, mut f: impl FnMut(MooRegister, u32)) {
+        let (_, registers, values) = self.fields();
+        for (reg, value) in registers.iter().zip(values) {
+            f(*reg, value);
+        }
+    }
+
+    /// Call `f` with each register present in the register mask and its value, in field order.
+    pub fn visit_set(&self, mut f: impl FnMut(MooRegister, u32)) {
+        let (mask, registers, values) = self.fields();
+        for (i, (reg, value)) in registers.iter().zip(values).enumerate() {
+            if mask & (1 << i) != 0 {
+                f(*reg, value);
+            }
+        }
+    }
+
+    /// Return the value of `reg`, or `None` if this register set does not include it. Registers
+    /// absent from the register mask are still returned.
+    pub fn get(&self, reg: MooRegister) -> Option<u32> {
+        let (_, registers, values) = self.fields();
+        registers.iter().position(|r| *r == reg).map(|i| values[i])
+    }
+
+    /// Return the registers present in the register mask, in field order.
+    /// For a final state, these are the registers changed by the test.
+    pub fn present_registers(&self) -> Vec<MooRegister> {
+        let mut registers = Vec::new();
+        self.visit_set(|reg, _| registers.push(reg));
+        registers
+    }
+
+    /// Returns true if `reg` is present in the register mask.
+    pub fn is_set(&self, reg: MooRegister) -> bool {
+        let (mask, registers, _) = self.fields();
+        registers
+            .iter()
+            .position(|r| *r == reg)
+            .is_some_and(|i| mask & (1 << i) != 0)
+    }
+
+    /// Return each register that differs between `self` and `other`, with both values, in field
+    /// order. The same registers are compared as by [PartialEq]. Returns `None` if the two sets of
+    /// registers are of different widths.
+    pub fn mismatches(&self, other: &MooRegisters) -> Option<Vec<(MooRegister, u32, u32)>> {
+        if matches!(self, MooRegisters::ThirtyTwo(_)) != matches!(other, MooRegisters::ThirtyTwo(_)) {
+            return None;
+        }
+        let mut mismatches = Vec::new();
+        let (_, _, other_values) = other.fields();
+        let mut other_values = other_values.into_iter();
+        self.visit(|reg, value| {
+            let other_value = other_values.next().unwrap_or_default();
+            // Debug registers are not compared.
+            if value != other_value && !matches!(reg, MooRegister::DR6 | MooRegister::DR7) {
+                mismatches.push((reg, value, other_value));
+            }
+        });
+        Some(mismatches)
+    }
+
+    /// Return the register mask, and the name and value of each register present in the mask,
+    /// in field order. Names are taken from [Self::NAMES16] or [Self::NAMES32].
+    pub fn named_values(&self) -> (u32, Vec<(&'static str, u32)>) {
+        let mut buf = Cursor::new(Vec::new());
+        let (names, width): (&[&'static str], usize) = match self {
+            MooRegisters::Sixteen(r) => {
+                _ = r.write_le(&mut buf);
+                (&Self::NAMES16, 2)
+            }
+            MooRegisters::ThirtyTwo(r) => {
+                _ = r.write_le(&mut buf);
+                (&Self::NAMES32, 4)
+            }
+        };
+        // The binary encoding is the source of truth for which registers are present.
+        let bytes = buf.into_inner();
+        let read_le = |offset: usize| {
+            bytes[offset..offset + width]
+                .iter()
+                .rev()
+                .fold(0u32, |acc, b| (acc << 8) | *b as u32)
+        };
+
+        let mask = read_le(0);
+        let mut values = Vec::new();
+        let mut offset = width;
+        for (i, name) in names.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                values.push((*name, read_le(offset)));
+                offset += width;
+            }
+        }
+        (mask, values)
+    }
+
+    /// Build a [MooRegisters] from (name, value) pairs, as produced by [Self::named_values].
+    /// Registers that are not named are left out of the register mask. If `wide` is true a
+    /// [MooRegisters32] is built, otherwise a [MooRegisters16].
+    pub fn from_named_values<'a>(
+        wide: bool,
+        values: impl IntoIterator<Item = (&'a str, u32)>,
+    ) -> Result<MooRegisters, MooError> {
+        let (names, width): (&[&str], usize) = if wide { (&Self::NAMES32, 4) } else { (&Self::NAMES16, 2) };
+
+        let mut mask = 0u32;
+        let mut regs = vec![0u32; names.len()];
+        for (name, value) in values {
+            let i = names
+                .iter()
+                .position(|n| n.eq_ignore_ascii_case(name))
+                .ok_or_else(|| MooError::ParseError(format!("unknown register '{}'", name)))?;
+            mask |= 1 << i;
+            regs[i] = value;
+        }
+
+        let mut buf = mask.to_le_bytes()[..width].to_vec();
+        for (i, value) in regs.iter().enumerate() {
+            if mask & (1 << i) != 0 {
+                buf.extend_from_slice(&value.to_le_bytes()[..width]);
+            }
+        }
+
+        let mut cursor = Cursor::new(buf);
+        let regs = if wide {
+            MooRegisters32::read_le(&mut cursor).map(MooRegisters::ThirtyTwo)
+        }
+        else {
+            MooRegisters16::read_le(&mut cursor).map(MooRegisters::Sixteen)
+        };
+        regs.map_err(|e| MooError::ParseError(e.to_string()))
+    }
+
     pub fn default_opt(cpu_type: MooCpuType) -> Self {
         match cpu_type {
             MooCpuType::Intel80386Ex => MooRegisters::ThirtyTwo(MooRegisters32::default()),
@@ -222,6 +482,11 @@ impl MooRegisters {
         }
     }
 
+    /// Return the FLAGS or EFLAGS register as a typed [MooCpuFlags] set.
+    pub fn cpu_flags(&self) -> MooCpuFlags {
+        MooCpuFlags::from_bits(self.flags())
+    }
+
     pub fn delta(&self, other: &MooRegisters) -> MooRegisters {
         match (self, other) {
             (MooRegisters::Sixteen(regs1), MooRegisters::Sixteen(regs2)) => MooRegisters::Sixteen(regs1.delta(regs2)),
@@ -245,35 +510,153 @@ impl MooRegisters {
             MooRegisters::ThirtyTwo(regs) => regs.csip_linear_real(),
         }
     }
+
+    /// Returns the selector value of the given segment register, if present. `FS` and `GS` are
+    /// never present in a 16-bit register set.
+    pub fn segment(&self, segment: MooSegmentRegister) -> Option<u16> {
+        match self {
+            MooRegisters::Sixteen(regs) => match segment {
+                MooSegmentRegister::CS => regs.cs(),
+                MooSegmentRegister::SS => regs.ss(),
+                MooSegmentRegister::DS => regs.ds(),
+                MooSegmentRegister::ES => regs.es(),
+                MooSegmentRegister::FS | MooSegmentRegister::GS => None,
+            },
+            MooRegisters::ThirtyTwo(regs) => match segment {
+                MooSegmentRegister::CS => regs.cs(),
+                MooSegmentRegister::SS => regs.ss(),
+                MooSegmentRegister::DS => regs.ds(),
+                MooSegmentRegister::ES => regs.es(),
+                MooSegmentRegister::FS => regs.fs(),
+                MooSegmentRegister::GS => regs.gs(),
+            },
+        }
+    }
+
+    /// Returns the base address of the given segment. With `descriptors`, this is the base held
+    /// in the segment's descriptor cache; without, it is the real-mode base of `selector << 4`.
+    pub fn segment_base(&self, segment: MooSegmentRegister, descriptors: Option<&MooDescriptors>) -> Option<u32> {
+        match descriptors {
+            Some(descriptors) => descriptors.base(segment),
+            None => self.segment(segment).map(|selector| (selector as u32) << 4),
+        }
+    }
+
+    /// Compute the linear address of `segment:offset`, taking the segment base from `descriptors`
+    /// when provided and from the selector value otherwise. Returns `None` if the segment base
+    /// cannot be determined.
+    pub fn linear_address(
+        &self,
+        segment: MooSegmentRegister,
+        offset: u32,
+        descriptors: Option<&MooDescriptors>,
+    ) -> Option<u32> {
+        self.segment_base(segment, descriptors)
+            .map(|base| base.wrapping_add(offset))
+    }
+
+    /// Return the linear address of the top of the stack, if the stack pointer and segment base
+    /// are present. With 16-bit stack segment descriptors, only `SP` is used as the offset.
+    pub fn sp_linear(&self, descriptors: Option<&MooDescriptors>) -> Option<u32> {
+        let Some(descriptors) = descriptors
+        else {
+            return self.sp_linear_real();
+        };
+        let offset = match self {
+            MooRegisters::Sixteen(regs) => regs.sp()? as u32,
+            MooRegisters::ThirtyTwo(regs) if descriptors.is_32bit(MooSegmentRegister::SS) => regs.esp()?,
+            MooRegisters::ThirtyTwo(regs) => regs.esp()? & 0xFFFF,
+        };
+        self.linear_address(MooSegmentRegister::SS, offset, Some(descriptors))
+    }
+
+    /// Return the linear address of `CS:IP`, if the instruction pointer and segment base are
+    /// present. With a 16-bit code segment descriptor, only `IP` is used as the offset.
+    pub fn csip_linear(&self, descriptors: Option<&MooDescriptors>) -> Option<u32> {
+        let Some(descriptors) = descriptors
+        else {
+            return self.csip_linear_real();
+        };
+        let offset = match self {
+            MooRegisters::Sixteen(regs) => regs.ip()? as u32,
+            MooRegisters::ThirtyTwo(regs) if descriptors.is_32bit(MooSegmentRegister::CS) => regs.eip()?,
+            MooRegisters::ThirtyTwo(regs) => regs.eip()? & 0xFFFF,
+        };
+        self.linear_address(MooSegmentRegister::CS, offset, Some(descriptors))
+    }
 }
 
+/// Prints either register width with the options of [MooRegisters16Printer] and
+/// [MooRegisters32Printer]. A `diff` of a different width than `regs` is a formatting error.
 pub struct MooRegistersPrinter<'a> {
     pub regs: &'a MooRegisters,
     pub cpu_type: MooCpuType,
     pub diff: Option<&'a MooRegisters>,
     pub indent: u32,
+    pub expand_flags: bool,
+    pub mask_unset: bool,
+}
+
+impl<'a> MooRegistersPrinter<'a> {
+    pub fn new(regs: &'a MooRegisters, cpu_type: MooCpuType) -> Self {
+        Self {
+            regs,
+            cpu_type,
+            diff: None,
+            indent: 0,
+            expand_flags: true,
+            mask_unset: false,
+        }
+    }
+
+    pub fn with_diff(mut self, diff: &'a MooRegisters) -> Self {
+        self.diff = Some(diff);
+        self
+    }
+
+    pub fn with_indent(mut self, indent: u32) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn with_flag_expansion(mut self, state: bool) -> Self {
+        self.expand_flags = state;
+        self
+    }
+
+    pub fn with_unset_masked(mut self, state: bool) -> Self {
+        self.mask_unset = state;
+        self
+    }
 }
 
 impl Display for MooRegistersPrinter<'_> {
-    #[rustfmt::skip]
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-
-        match (self.regs, self.diff) {
-            (MooRegisters::Sixteen(regs), None) => {
-                write!(fmt, "{}", MooRegisters16Printer { regs, cpu_type: self.cpu_type, diff: None, indent: self.indent })
-            }
-            (MooRegisters::Sixteen(regs), Some(MooRegisters::Sixteen(diff_regs))) => {
-                let rehydrated = regs.rehydrate(diff_regs);
-                write!(fmt, "{}", MooRegisters16Printer { regs: &rehydrated, cpu_type: self.cpu_type, diff: Some(diff_regs), indent: self.indent })
-            }
-            (MooRegisters::ThirtyTwo(regs), None) => {
-                write!(fmt, "{}", MooRegisters32Printer { regs, cpu_type: self.cpu_type, diff: None, indent: self.indent })
+        match self.regs {
+            MooRegisters::Sixteen(regs) => {
+                let mut printer = MooRegisters16Printer::new(regs, self.cpu_type)
+                    .with_indent(self.indent)
+                    .with_flag_expansion(self.expand_flags)
+                    .with_unset_masked(self.mask_unset);
+                match self.diff {
+                    Some(MooRegisters::Sixteen(diff_regs)) => printer = printer.with_diff(diff_regs),
+                    Some(_) => return Err(std::fmt::Error),
+                    None => {}
+                }
+                write!(fmt, "{}", printer)
             }
-            (MooRegisters::ThirtyTwo(regs), Some(MooRegisters::ThirtyTwo(diff_regs))) => {
-                let rehydrated = regs.rehydrate(diff_regs);
-                write!(fmt, "{}", MooRegisters32Printer { regs: &rehydrated, cpu_type: self.cpu_type, diff: Some(diff_regs), indent: self.indent })
+            MooRegisters::ThirtyTwo(regs) => {
+                let mut printer = MooRegisters32Printer::new(regs, self.cpu_type)
+                    .with_indent(self.indent)
+                    .with_flag_expansion(self.expand_flags)
+                    .with_unset_masked(self.mask_unset);
+                match self.diff {
+                    Some(MooRegisters::ThirtyTwo(diff_regs)) => printer = printer.with_diff(diff_regs),
+                    Some(_) => return Err(std::fmt::Error),
+                    None => {}
+                }
+                write!(fmt, "{}", printer)
             }
-            _ => Err(std::fmt::Error),
         }
     }
 }
@@ -45,36 +45,37 @@ pub struct MooRegisters16Init {
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[binrw]
 #[brw(little)]
 pub struct MooRegisters16 {
-    reg_mask:  u16,
+    pub(crate) reg_mask: u16,
     #[brw(if(reg_mask & 0x0001 != 0))]
-    pub ax:    u16,
+    pub ax: u16,
     #[brw(if(reg_mask & 0x0002 != 0))]
-    pub bx:    u16,
+    pub bx: u16,
     #[brw(if(reg_mask & 0x0004 != 0))]
-    pub cx:    u16,
+    pub cx: u16,
     #[brw(if(reg_mask & 0x0008 != 0))]
-    pub dx:    u16,
+    pub dx: u16,
     #[brw(if(reg_mask & 0x0010 != 0))]
-    pub cs:    u16,
+    pub cs: u16,
     #[brw(if(reg_mask & 0x0020 != 0))]
-    pub ss:    u16,
+    pub ss: u16,
     #[brw(if(reg_mask & 0x0040 != 0))]
-    pub ds:    u16,
+    pub ds: u16,
     #[brw(if(reg_mask & 0x0080 != 0))]
-    pub es:    u16,
+    pub es: u16,
     #[brw(if(reg_mask & 0x0100 != 0))]
-    pub sp:    u16,
+    pub sp: u16,
     #[brw(if(reg_mask & 0x0200 != 0))]
-    pub bp:    u16,
+    pub bp: u16,
     #[brw(if(reg_mask & 0x0400 != 0))]
-    pub si:    u16,
+    pub si: u16,
     #[brw(if(reg_mask & 0x0800 != 0))]
-    pub di:    u16,
+    pub di: u16,
     #[brw(if(reg_mask & 0x1000 != 0))]
-    pub ip:    u16,
+    pub ip: u16,
     #[brw(if(reg_mask & 0x2000 != 0))]
     pub flags: u16,
 }
@@ -539,17 +540,58 @@ impl MooRegisters16 {
     }
 }
 
+/// Prints a [MooRegisters16] as a register dump.
+///
+/// If `diff` is provided, `regs` is rehydrated against it first, so a final register delta can be
+/// printed directly, and changed registers are marked with `*`.
 pub struct MooRegisters16Printer<'a> {
     pub regs: &'a MooRegisters16,
     pub cpu_type: MooCpuType,
     pub diff: Option<&'a MooRegisters16>,
     pub indent: u32,
+    /// Print a decoded flag string after the flags register.
+    pub expand_flags: bool,
+    /// Print registers absent from the register mask as dashes instead of their stored value.
+    pub mask_unset: bool,
+}
+
+impl<'a> MooRegisters16Printer<'a> {
+    pub fn new(regs: &'a MooRegisters16, cpu_type: MooCpuType) -> Self {
+        Self {
+            regs,
+            cpu_type,
+            diff: None,
+            indent: 0,
+            expand_flags: true,
+            mask_unset: false,
+        }
+    }
+
+    pub fn with_diff(mut self, diff: &'a MooRegisters16) -> Self {
+        self.diff = Some(diff);
+        self
+    }
+
+    pub fn with_indent(mut self, indent: u32) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn with_flag_expansion(mut self, state: bool) -> Self {
+        self.expand_flags = state;
+        self
+    }
+
+    pub fn with_unset_masked(mut self, state: bool) -> Self {
+        self.mask_unset = state;
+        self
+    }
 }
 
 macro_rules! diff_chr {
-    ($self:expr, $reg:ident) => {
+    ($self:expr, $regs:expr, $reg:ident) => {
         if let Some(d) = $self.diff {
-            if $self.regs.$reg != d.$reg {
+            if $regs.$reg != d.$reg {
                 '*'
             }
             else {
@@ -562,43 +604,59 @@ macro_rules! diff_chr {
     };
 }
 
+macro_rules! reg_val {
+    ($self:expr, $regs:expr, $reg:ident) => {
+        if $self.mask_unset && $regs.$reg().is_none() {
+            String::from("----")
+        }
+        else {
+            format!("{:04X}", $regs.$reg)
+        }
+    };
+}
+
 impl Display for MooRegisters16Printer<'_> {
     #[rustfmt::skip]
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let regs = match self.diff {
+            Some(diff) => self.regs.rehydrate(diff),
+            None => *self.regs,
+        };
+
         let reg_str = format!(
-            "{:indent$}AX:{}{:04X} BX:{}{:04X} CX:{}{:04X} DX:{}{:04X}\n\
-             {:indent$}SI:{}{:04X} DI:{}{:04X} BP:{}{:04X} SP:{}{:04X}\n\
-             {:indent$}CS:{}{:04X} DS:{}{:04X} ES:{}{:04X} SS:{}{:04X}\n\
-             {:indent$}IP:{}{:04X}\n",
+            "{:indent$}AX:{}{} BX:{}{} CX:{}{} DX:{}{}\n\
+             {:indent$}SI:{}{} DI:{}{} BP:{}{} SP:{}{}\n\
+             {:indent$}CS:{}{} DS:{}{} ES:{}{} SS:{}{}\n\
+             {:indent$}IP:{}{}\n",
             "",
-            diff_chr!(self, ax), self.regs.ax,
-            diff_chr!(self, bx), self.regs.bx,
-            diff_chr!(self, cx), self.regs.cx,
-            diff_chr!(self, dx), self.regs.dx,
+            diff_chr!(self, regs, ax), reg_val!(self, regs, ax),
+            diff_chr!(self, regs, bx), reg_val!(self, regs, bx),
+            diff_chr!(self, regs, cx), reg_val!(self, regs, cx),
+            diff_chr!(self, regs, dx), reg_val!(self, regs, dx),
             "",
-            diff_chr!(self, si), self.regs.si,
-            diff_chr!(self, di), self.regs.di,
-            diff_chr!(self, bp), self.regs.bp,
-            diff_chr!(self, sp), self.regs.sp,
+            diff_chr!(self, regs, si), reg_val!(self, regs, si),
+            diff_chr!(self, regs, di), reg_val!(self, regs, di),
+            diff_chr!(self, regs, bp), reg_val!(self, regs, bp),
+            diff_chr!(self, regs, sp), reg_val!(self, regs, sp),
             "",
-            diff_chr!(self, cs), self.regs.cs,
-            diff_chr!(self, ds), self.regs.ds,
-            diff_chr!(self, es), self.regs.es,
-            diff_chr!(self, ss), self.regs.ss,
+            diff_chr!(self, regs, cs), reg_val!(self, regs, cs),
+            diff_chr!(self, regs, ds), reg_val!(self, regs, ds),
+            diff_chr!(self, regs, es), reg_val!(self, regs, es),
+            diff_chr!(self, regs, ss), reg_val!(self, regs, ss),
             "",
-            diff_chr!(self, ip), self.regs.ip,
+            diff_chr!(self, regs, ip), reg_val!(self, regs, ip),
             indent = self.indent as usize,
         );
 
-        let flag_diff_chr = diff_chr!(self, flags);
-        let flag_str = format!("{:indent$}FLAGS:{}{:04X}",
+        let flag_diff_chr = diff_chr!(self, regs, flags);
+        let flag_str = format!("{:indent$}FLAGS:{}{}",
             "",
-            flag_diff_chr, self.regs.flags,
+            flag_diff_chr, reg_val!(self, regs, flags),
             indent = self.indent as usize,
         );
 
         // Expand flag info
-        let f = self.regs.flags;
+        let f = regs.flags;
         let c_chr = if MooRegisters16::FLAG_CARRY & f != 0 { 'C' } else { 'c' };
         let p_chr = if MooRegisters16::FLAG_PARITY & f != 0 { 'P' } else { 'p' };
         let a_chr = if MooRegisters16::FLAG_AUX_CARRY & f != 0 {
@@ -659,6 +717,9 @@ impl Display for MooRegisters16Printer<'_> {
         let iopl1_chr = if f & MooRegisters16::FLAG_IOPL1 != 0 { '1' } else { '0' };
 
         write!(fmt, "{}{}", reg_str, flag_str)?;
+        if !self.expand_flags || (self.mask_unset && regs.flags().is_none()) {
+            return Ok(());
+        }
 
         let result = write!(
             fmt,
@@ -51,10 +51,11 @@ pub struct MooRegisters32Init {
 }
 
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[binrw]
 #[brw(little)]
 pub struct MooRegisters32 {
-    reg_mask: u32,
+    pub(crate) reg_mask: u32,
     #[brw(if(reg_mask & MooRegisters32::CR0_MASK != 0))]
     pub cr0: u32,
     #[brw(if(reg_mask & MooRegisters32::CR3_MASK != 0))]
@@ -380,6 +381,9 @@ impl MooRegisters32 {
     pub const FLAG_NT: u32          = 0b0100_0000_0000_0000; // Nested Task
     pub const FLAG_IOPL0: u32       = 0b0001_0000_0000_0000; // IO Privilege Level
     pub const FLAG_IOPL1: u32       = 0b0010_0000_0000_0000; // IO Privilege Level
+    pub const FLAG_RF: u32          = 0b0000_0000_0000_0001_0000_0000_0000_0000; // Resume Flag
+    pub const FLAG_VM: u32          = 0b0000_0000_0000_0010_0000_0000_0000_0000; // Virtual-8086 Mode
+    pub const FLAG_AC: u32          = 0b0000_0000_0000_0100_0000_0000_0000_0000; // Alignment Check (486+)
 
     /// Create a [MooRegisters32] from a flag mask. This is used to generate a `RM32` chunk.
     pub fn from_flag_mask(mask: u32) -> Self {
@@ -809,17 +813,58 @@ impl MooRegisters32 {
     }
 }
 
+/// Prints a [MooRegisters32] as a register dump.
+///
+/// Behaves like [MooRegisters16Printer](crate::registers::MooRegisters16Printer): `regs` is
+/// rehydrated against `diff` when one is provided, and changed registers are marked with `*`.
 pub struct MooRegisters32Printer<'a> {
     pub regs: &'a MooRegisters32,
     pub cpu_type: MooCpuType,
     pub diff: Option<&'a MooRegisters32>,
     pub indent: u32,
+    /// Print a decoded flag string after the flags register.
+    pub expand_flags: bool,
+    /// Print registers absent from the register mask as dashes instead of their stored value.
+    pub mask_unset: bool,
+}
+
+impl<'a> MooRegisters32Printer<'a> {
+    pub fn new(regs: &'a MooRegisters32, cpu_type: MooCpuType) -> Self {
+        Self {
+            regs,
+            cpu_type,
+            diff: None,
+            indent: 0,
+            expand_flags: true,
+            mask_unset: false,
+        }
+    }
+
+    pub fn with_diff(mut self, diff: &'a MooRegisters32) -> Self {
+        self.diff = Some(diff);
+        self
+    }
+
+    pub fn with_indent(mut self, indent: u32) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    pub fn with_flag_expansion(mut self, state: bool) -> Self {
+        self.expand_flags = state;
+        self
+    }
+
+    pub fn with_unset_masked(mut self, state: bool) -> Self {
+        self.mask_unset = state;
+        self
+    }
 }
 
 macro_rules! diff_chr {
-    ($self:expr, $reg:ident) => {
+    ($self:expr, $regs:expr, $reg:ident) => {
         if let Some(d) = $self.diff {
-            if $self.regs.$reg != d.$reg {
+            if $regs.$reg != d.$reg {
                 '*'
             }
             else {
@@ -832,51 +877,67 @@ macro_rules! diff_chr {
     };
 }
 
+macro_rules! reg_val {
+    ($self:expr, $regs:expr, $reg:ident, $width:literal) => {
+        if $self.mask_unset && $regs.$reg().is_none() {
+            "-".repeat($width)
+        }
+        else {
+            format!("{:0width$X}", $regs.$reg, width = $width)
+        }
+    };
+}
+
 impl Display for MooRegisters32Printer<'_> {
     #[rustfmt::skip]
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let regs = match self.diff {
+            Some(diff) => self.regs.rehydrate(diff),
+            None => *self.regs,
+        };
+
         let reg_str = format!(
-            "{:indent$}CR0:{}{:08X}\n\
-             {:indent$}EAX:{}{:08X} EBX:{}{:08X} ECX:{}{:08X} EDX:{}{:08X}\n\
-             {:indent$}ESI:{}{:08X} EDI:{}{:08X} EBP:{}{:08X} ESP:{}{:08X} \n\
-             {:indent$}CS:{}{:04X} DS:{}{:04X} ES:{}{:04X} FS:{}{:04X} GS:{}{:04X} SS:{}{:04X}\n\
-             {:indent$}EIP:{}{:08X}\n",
+            "{:indent$}CR0:{}{}\n\
+             {:indent$}EAX:{}{} EBX:{}{} ECX:{}{} EDX:{}{}\n\
+             {:indent$}ESI:{}{} EDI:{}{} EBP:{}{} ESP:{}{} \n\
+             {:indent$}CS:{}{} DS:{}{} ES:{}{} FS:{}{} GS:{}{} SS:{}{}\n\
+             {:indent$}EIP:{}{}\n",
             "",
-            diff_chr!(self, cr0), self.regs.cr0,
+            diff_chr!(self, regs, cr0), reg_val!(self, regs, cr0, 8),
             "",
-            diff_chr!(self, eax), self.regs.eax,
-            diff_chr!(self, ebx), self.regs.ebx,
-            diff_chr!(self, ecx), self.regs.ecx,
-            diff_chr!(self, edx), self.regs.edx,
+            diff_chr!(self, regs, eax), reg_val!(self, regs, eax, 8),
+            diff_chr!(self, regs, ebx), reg_val!(self, regs, ebx, 8),
+            diff_chr!(self, regs, ecx), reg_val!(self, regs, ecx, 8),
+            diff_chr!(self, regs, edx), reg_val!(self, regs, edx, 8),
             "",
-            diff_chr!(self, esi), self.regs.esi,
-            diff_chr!(self, edi), self.regs.edi,
-            diff_chr!(self, ebp), self.regs.ebp,
-            diff_chr!(self, esp), self.regs.esp,
+            diff_chr!(self, regs, esi), reg_val!(self, regs, esi, 8),
+            diff_chr!(self, regs, edi), reg_val!(self, regs, edi, 8),
+            diff_chr!(self, regs, ebp), reg_val!(self, regs, ebp, 8),
+            diff_chr!(self, regs, esp), reg_val!(self, regs, esp, 8),
 
             "",
-            diff_chr!(self, cs), self.regs.cs,
-            diff_chr!(self, ds), self.regs.ds,
-            diff_chr!(self, es), self.regs.es,
-            diff_chr!(self, fs), self.regs.fs,
-            diff_chr!(self, gs), self.regs.gs,
-            diff_chr!(self, ss), self.regs.ss,
+            diff_chr!(self, regs, cs), reg_val!(self, regs, cs, 4),
+            diff_chr!(self, regs, ds), reg_val!(self, regs, ds, 4),
+            diff_chr!(self, regs, es), reg_val!(self, regs, es, 4),
+            diff_chr!(self, regs, fs), reg_val!(self, regs, fs, 4),
+            diff_chr!(self, regs, gs), reg_val!(self, regs, gs, 4),
+            diff_chr!(self, regs, ss), reg_val!(self, regs, ss, 4),
             "",
-            diff_chr!(self, eip), self.regs.eip,
+            diff_chr!(self, regs, eip), reg_val!(self, regs, eip, 8),
 
             indent = self.indent as usize,
 
         );
 
-        let flag_diff_chr = diff_chr!(self, eflags);
-        let flag_str = format!("{:indent$}EFLAGS:{}{:08X}",
+        let flag_diff_chr = diff_chr!(self, regs, eflags);
+        let flag_str = format!("{:indent$}EFLAGS:{}{}",
             "",
-            flag_diff_chr, self.regs.eflags,
+            flag_diff_chr, reg_val!(self, regs, eflags, 8),
             indent = self.indent as usize,
         );
 
         // Expand flag info
-        let f = self.regs.eflags;
+        let f = regs.eflags;
         let c_chr = if MooRegisters32::FLAG_CARRY & f != 0 { 'C' } else { 'c' };
         let p_chr = if MooRegisters32::FLAG_PARITY & f != 0 { 'P' } else { 'p' };
         let a_chr = if MooRegisters32::FLAG_AUX_CARRY & f != 0 {
@@ -915,7 +976,6 @@ impl Display for MooRegisters32Printer<'_> {
 
         let mut tag_string = String::with_capacity(16);
         if let Some(diff) = self.diff {
-            //tag_string.clear();
             for bit_i in (0..16).rev() {
                 if (f & (1 << bit_i)) != (diff.eflags & (1 << bit_i)) {
                     tag_string.push('^');
@@ -927,6 +987,9 @@ impl Display for MooRegisters32Printer<'_> {
         }
 
         write!(fmt, "{}{}", reg_str, flag_str)?;
+        if !self.expand_flags || (self.mask_unset && regs.eflags().is_none()) {
+            return Ok(());
+        }
 
         let result = write!(
             fmt,
@@ -0,0 +1,171 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Expected-failure (XFAIL) lists.
+//!
+//! An expected-failure list names tests that are known to fail against a particular emulator,
+//! so that those failures do not fail a CI run. Entries that now pass are reported as stale, so
+//! that the list can be pruned as the emulator improves.
+//!
+//! The list is plain text, one entry per line: a 40-digit hexadecimal test hash, optionally
+//! followed by whitespace and a free-form reason. Blank lines and lines beginning with `#` are
+//! ignored.
+//!
+//! ```text
+//! # Known DIV timing issue
+//! 3c1e0b6f4a2d9e8b7c6a5f4e3d2c1b0a99887766 DIV r/m16 is two cycles short
+//! ```
+
+use std::{collections::BTreeMap, io::BufRead};
+
+use crate::{
+    prelude::MooTest,
+    results::{MooResultFile, MooTestResultStatus},
+    types::{errors::MooError, hash::hex_string},
+};
+
+/// A list of tests expected to fail, keyed by test hash, each with an optional reason.
+#[derive(Clone, Debug, Default)]
+pub struct MooExpectedFailures {
+    entries: BTreeMap<[u8; 20], String>,
+}
+
+/// The outcome of checking a [MooResultFile] against a [MooExpectedFailures] list.
+#[derive(Clone, Debug, Default)]
+pub struct MooBaselineReport {
+    /// Failing tests that are not in the list. These should fail a CI run.
+    pub unexpected_failures: Vec<[u8; 20]>,
+    /// Failing tests that are in the list.
+    pub expected_failures: Vec<[u8; 20]>,
+    /// Tests in the list that now pass. These entries can be removed.
+    pub stale: Vec<[u8; 20]>,
+    /// Tests in the list that have no result, e.g. because they were not run.
+    pub missing: Vec<[u8; 20]>,
+}
+
+impl MooBaselineReport {
+    /// Returns true if there are no unexpected failures.
+    pub fn is_ok(&self) -> bool {
+        self.unexpected_failures.is_empty()
+    }
+}
+
+impl MooExpectedFailures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse an expected-failure list from its text form.
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, MooError> {
+        let mut list = Self::new();
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| MooError::ParseError(e.to_string()))?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (hash_str, reason) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let hash = parse_hash(hash_str).ok_or_else(|| {
+                MooError::ParseError(format!("line {}: invalid test hash '{}'", line_no + 1, hash_str))
+            })?;
+            list.insert(hash, reason.trim());
+        }
+        Ok(list)
+    }
+
+    /// Add a test to the list, replacing the reason of any existing entry.
+    pub fn insert(&mut self, hash: [u8; 20], reason: impl Into<String>) {
+        self.entries.insert(hash, reason.into());
+    }
+
+    /// Returns true if the test with the given hash is expected to fail.
+    pub fn contains(&self, hash: &[u8; 20]) -> bool {
+        self.entries.contains_key(hash)
+    }
+
+    /// Returns true if `test` is expected to fail. Tests without a hash are never listed.
+    pub fn contains_test(&self, test: &MooTest) -> bool {
+        test.hash().is_some_and(|hash| self.contains(hash))
+    }
+
+    /// Return the recorded reason for an expected failure, if the test is in the list.
+    pub fn reason(&self, hash: &[u8; 20]) -> Option<&str> {
+        self.entries.get(hash).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Check a set of results against this list.
+    pub fn check(&self, results: &MooResultFile) -> MooBaselineReport {
+        let mut report = MooBaselineReport::default();
+        for result in results.results() {
+            let listed = self.contains(&result.hash);
+            match result.status {
+                MooTestResultStatus::Fail if listed => report.expected_failures.push(result.hash),
+                MooTestResultStatus::Fail => report.unexpected_failures.push(result.hash),
+                MooTestResultStatus::Pass if listed => report.stale.push(result.hash),
+                _ => {}
+            }
+        }
+        report.missing = self
+            .entries
+            .keys()
+            .filter(|hash| results.get(hash).is_none())
+            .copied()
+            .collect();
+        report
+    }
+
+    /// Render the list in its text form, with one entry per line ordered by hash.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (hash, reason) in &self.entries {
+            let hash = hex_string(hash);
+            if reason.is_empty() {
+                out.push_str(&format!("{}\n", hash));
+            }
+            else {
+                out.push_str(&format!("{} {}\n", hash, reason));
+            }
+        }
+        out
+    }
+}
+
+/// Parse a 40-digit hexadecimal test hash, in either case.
+pub fn parse_hash(s: &str) -> Option<[u8; 20]> {
+    if s.len() != 40 || !s.is_ascii() {
+        return None;
+    }
+    let mut hash = [0u8; 20];
+    for (i, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(hash)
+}
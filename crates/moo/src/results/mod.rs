@@ -0,0 +1,420 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! # MOO-R result files
+//!
+//! A **MOO-R** file is a companion to a set of **MOO** test files that records the outcome of
+//! running each test against an emulator: pass or fail, the category of the first mismatch, and
+//! the emulator name and version. Committing result files alongside an emulator release makes it
+//! possible to track conformance across releases and to see exactly which tests were fixed or
+//! regressed.
+//!
+//! A MOO-R file uses the same chunk layout as a MOO file: a four-byte ASCII chunk ID followed by
+//! a `uint32` payload length. A reader skips chunks it does not recognize.
+//!
+//! - `MOOR` header chunk: `uint8` major version, `uint8` minor version, `uint16` reserved,
+//!   `uint32` result count.
+//! - `EMUL` chunk: `uint32` length and UTF-8 bytes of the emulator name, then the same for the
+//!   emulator version.
+//! - One `RSLT` chunk per test: the 20-byte SHA-1 test hash, `uint8` [MooTestResultStatus],
+//!   `uint8` [MooMismatchCategory], `uint16` reserved, then a `uint32` length and UTF-8 bytes of
+//!   a free-form detail string.
+//!
+//! Known failures can be listed in a [MooExpectedFailures](expected::MooExpectedFailures) file
+//! and checked against a result file, so that they do not fail a CI run.
+
+pub mod expected;
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{Cursor, Read, Seek, SeekFrom, Write},
+};
+
+use binrw::{binrw, BinRead, BinResult, BinWrite};
+
+use crate::types::{hash::hex_string, MooComparison};
+
+/// The major version of the MOO-R format written by this crate.
+pub const MOOR_MAJOR_VERSION: u8 = 1;
+/// The minor version of the MOO-R format written by this crate.
+pub const MOOR_MINOR_VERSION: u8 = 0;
+
+const MOOR_HEADER_ID: &[u8; 4] = b"MOOR";
+const MOOR_EMULATOR_ID: &[u8; 4] = b"EMUL";
+const MOOR_RESULT_ID: &[u8; 4] = b"RSLT";
+
+/// The outcome of running a single test.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[binrw]
+#[br(repr(u8))]
+#[bw(repr(u8))]
+pub enum MooTestResultStatus {
+    #[default]
+    Pass,
+    Fail,
+    /// The test was not run, e.g. because the emulator does not support it.
+    Skipped,
+}
+
+/// The category of the first mismatch found in a failing test.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[binrw]
+#[br(repr(u8))]
+#[bw(repr(u8))]
+pub enum MooMismatchCategory {
+    /// No mismatch, or the test passed.
+    #[default]
+    None,
+    Registers,
+    Memory,
+    CycleCount,
+    /// A cycle differed in ALE, address or bus state.
+    CycleBus,
+    Exception,
+    Other,
+}
+
+impl From<&MooComparison> for MooMismatchCategory {
+    fn from(comparison: &MooComparison) -> Self {
+        match comparison {
+            MooComparison::Equal => MooMismatchCategory::None,
+            MooComparison::RegisterMismatch(..) | MooComparison::RegisterWidthMismatch => {
+                MooMismatchCategory::Registers
+            }
+            MooComparison::CycleCountMismatch(..) => MooMismatchCategory::CycleCount,
+            MooComparison::CycleAddressMismatch(..)
+            | MooComparison::CycleBusMismatch(..)
+            | MooComparison::ALEMismatch(..) => MooMismatchCategory::CycleBus,
+            MooComparison::MemoryAddressMismatch(..) | MooComparison::MemoryValueMismatch(..) => {
+                MooMismatchCategory::Memory
+            }
+        }
+    }
+}
+
+#[binrw]
+#[brw(little)]
+struct MooResultHeader {
+    major_version: u8,
+    minor_version: u8,
+    reserved: u16,
+    result_count: u32,
+}
+
+#[binrw]
+#[brw(little)]
+struct MooResultString {
+    #[bw(calc = bytes.len() as u32)]
+    len:   u32,
+    #[br(count = len)]
+    bytes: Vec<u8>,
+}
+
+impl From<&str> for MooResultString {
+    fn from(s: &str) -> Self {
+        Self {
+            bytes: s.as_bytes().to_vec(),
+        }
+    }
+}
+
+impl From<MooResultString> for String {
+    fn from(s: MooResultString) -> Self {
+        String::from_utf8_lossy(&s.bytes).into_owned()
+    }
+}
+
+#[binrw]
+#[brw(little)]
+struct MooEmulatorInfo {
+    name:    MooResultString,
+    version: MooResultString,
+}
+
+/// The recorded result of a single test, identified by its SHA-1 hash.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[binrw]
+#[brw(little)]
+pub struct MooTestResult {
+    pub hash: [u8; 20],
+    pub status: MooTestResultStatus,
+    pub category: MooMismatchCategory,
+    #[brw(pad_before = 2)]
+    #[br(map = |s: MooResultString| s.into())]
+    #[bw(map = |s: &String| MooResultString::from(s.as_str()))]
+    pub detail: String,
+}
+
+impl MooTestResult {
+    /// Create a passing result for the test with the given hash.
+    pub fn pass(hash: [u8; 20]) -> Self {
+        Self {
+            hash,
+            ..Default::default()
+        }
+    }
+
+    /// Create a failing result for the test with the given hash.
+    pub fn fail(hash: [u8; 20], category: MooMismatchCategory) -> Self {
+        Self {
+            hash,
+            status: MooTestResultStatus::Fail,
+            category,
+            detail: String::new(),
+        }
+    }
+
+    /// Attach a free-form detail string, such as a description of the mismatch.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    /// Return the test hash as a lowercase hexadecimal string, in the same form as
+    /// [MooTest::hash_string](crate::prelude::MooTest::hash_string).
+    pub fn hash_string(&self) -> String {
+        hex_string(&self.hash)
+    }
+}
+
+/// Counts of results by status and mismatch category.
+#[derive(Clone, Debug, Default)]
+pub struct MooResultSummary {
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// The number of failing tests in each [MooMismatchCategory].
+    pub categories: BTreeMap<MooMismatchCategory, usize>,
+}
+
+/// The differences between two [MooResultFile]s, as hash lists.
+#[derive(Clone, Debug, Default)]
+pub struct MooResultDiff {
+    /// Tests that failed in the old results and pass in the new.
+    pub fixed: Vec<[u8; 20]>,
+    /// Tests that passed in the old results and fail in the new.
+    pub regressed: Vec<[u8; 20]>,
+    /// Tests that fail in both, but with a different mismatch category.
+    pub changed: Vec<[u8; 20]>,
+    /// Tests present only in the new results.
+    pub added: Vec<[u8; 20]>,
+    /// Tests present only in the old results.
+    pub removed: Vec<[u8; 20]>,
+}
+
+/// A [MooResultFile] holds the results of running a set of tests against one emulator build.
+#[derive(Clone, Debug, Default)]
+pub struct MooResultFile {
+    emulator: String,
+    emulator_version: String,
+    results: BTreeMap<[u8; 20], MooTestResult>,
+}
+
+impl MooResultFile {
+    /// Create an empty [MooResultFile] for the named emulator and version.
+    pub fn new(emulator: impl Into<String>, emulator_version: impl Into<String>) -> Self {
+        Self {
+            emulator: emulator.into(),
+            emulator_version: emulator_version.into(),
+            results: BTreeMap::new(),
+        }
+    }
+
+    pub fn emulator(&self) -> &str {
+        &self.emulator
+    }
+
+    pub fn emulator_version(&self) -> &str {
+        &self.emulator_version
+    }
+
+    /// Record a result, replacing any previous result for the same test hash.
+    pub fn insert(&mut self, result: MooTestResult) {
+        self.results.insert(result.hash, result);
+    }
+
+    /// Retrieve the result for the test with the given hash.
+    pub fn get(&self, hash: &[u8; 20]) -> Option<&MooTestResult> {
+        self.results.get(hash)
+    }
+
+    /// Iterate over all results, ordered by test hash.
+    pub fn results(&self) -> impl Iterator<Item = &MooTestResult> {
+        self.results.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Count results by status and mismatch category.
+    pub fn summary(&self) -> MooResultSummary {
+        let mut summary = MooResultSummary {
+            total: self.results.len(),
+            ..Default::default()
+        };
+        for result in self.results.values() {
+            match result.status {
+                MooTestResultStatus::Pass => summary.passed += 1,
+                MooTestResultStatus::Fail => {
+                    summary.failed += 1;
+                    *summary.categories.entry(result.category).or_insert(0) += 1;
+                }
+                MooTestResultStatus::Skipped => summary.skipped += 1,
+            }
+        }
+        summary
+    }
+
+    /// Compare these results, taken as the older set, against `newer`.
+    pub fn diff(&self, newer: &MooResultFile) -> MooResultDiff {
+        let mut diff = MooResultDiff::default();
+        let hashes: BTreeSet<&[u8; 20]> = self.results.keys().chain(newer.results.keys()).collect();
+
+        for hash in hashes {
+            match (self.results.get(hash), newer.results.get(hash)) {
+                (Some(old), Some(new)) => {
+                    use MooTestResultStatus::*;
+                    match (old.status, new.status) {
+                        (Fail, Pass) => diff.fixed.push(*hash),
+                        (Pass, Fail) => diff.regressed.push(*hash),
+                        (Fail, Fail) if old.category != new.category => diff.changed.push(*hash),
+                        _ => {}
+                    }
+                }
+                (None, Some(_)) => diff.added.push(*hash),
+                (Some(_), None) => diff.removed.push(*hash),
+                (None, None) => {}
+            }
+        }
+        diff
+    }
+
+    /// Read a [MooResultFile] from an implementor of [Read] + [Seek].
+    pub fn read<RS: Read + Seek>(reader: &mut RS) -> BinResult<MooResultFile> {
+        let (id, size) = read_chunk_header(reader)?;
+        if &id != MOOR_HEADER_ID {
+            return Err(binrw::Error::Custom {
+                pos: 0,
+                err: Box::new("MOOR header chunk not found"),
+            });
+        }
+        let header_pos = reader.stream_position()?;
+        let header = MooResultHeader::read(reader)?;
+        if header.major_version > MOOR_MAJOR_VERSION {
+            return Err(binrw::Error::Custom {
+                pos: header_pos,
+                err: Box::new(format!("Unsupported MOO-R major version {}", header.major_version)),
+            });
+        }
+        reader.seek(SeekFrom::Start(header_pos + size as u64))?;
+
+        let mut file = MooResultFile::default();
+        loop {
+            let (id, size) = match read_chunk_header(reader) {
+                Ok(chunk) => chunk,
+                Err(e) if e.is_eof() => break,
+                Err(e) => return Err(e),
+            };
+            let payload_pos = reader.stream_position()?;
+
+            match &id {
+                MOOR_EMULATOR_ID => {
+                    let info = MooEmulatorInfo::read(reader)?;
+                    file.emulator = info.name.into();
+                    file.emulator_version = info.version.into();
+                }
+                MOOR_RESULT_ID => {
+                    file.insert(MooTestResult::read(reader)?);
+                }
+                _ => {}
+            }
+            reader.seek(SeekFrom::Start(payload_pos + size as u64))?;
+        }
+
+        if file.results.len() != header.result_count as usize {
+            return Err(binrw::Error::Custom {
+                pos: header_pos,
+                err: Box::new(format!(
+                    "Expected {} results, found {}",
+                    header.result_count,
+                    file.results.len()
+                )),
+            });
+        }
+        Ok(file)
+    }
+
+    /// Write this [MooResultFile] to an implementor of [Write] + [Seek].
+    pub fn write<WS: Write + Seek>(&self, writer: &mut WS) -> BinResult<()> {
+        write_chunk(
+            writer,
+            MOOR_HEADER_ID,
+            &MooResultHeader {
+                major_version: MOOR_MAJOR_VERSION,
+                minor_version: MOOR_MINOR_VERSION,
+                reserved: 0,
+                result_count: self.results.len() as u32,
+            },
+        )?;
+        write_chunk(
+            writer,
+            MOOR_EMULATOR_ID,
+            &MooEmulatorInfo {
+                name:    self.emulator.as_str().into(),
+                version: self.emulator_version.as_str().into(),
+            },
+        )?;
+        for result in self.results.values() {
+            write_chunk(writer, MOOR_RESULT_ID, result)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_chunk_header<R: Read + Seek>(reader: &mut R) -> BinResult<([u8; 4], u32)> {
+    let id = <[u8; 4]>::read_le(reader)?;
+    let size = u32::read_le(reader)?;
+    Ok((id, size))
+}
+
+fn write_chunk<W, T>(writer: &mut W, id: &[u8; 4], payload: &T) -> BinResult<()>
+where
+    W: Write + Seek,
+    T: BinWrite + binrw::meta::WriteEndian,
+    for<'a> <T as BinWrite>::Args<'a>: Default,
+{
+    let mut payload_buf = Cursor::new(Vec::new());
+    payload.write_le(&mut payload_buf)?;
+    let payload = payload_buf.into_inner();
+
+    writer.write_all(id)?;
+    (payload.len() as u32).write_le(writer)?;
+    writer.write_all(&payload)?;
+    Ok(())
+}
@@ -0,0 +1,138 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Small MOO files built in memory, for trying out the API without binary fixtures.
+//!
+//! The samples are available to this crate's own tests and documentation, and to downstream
+//! crates that enable the `samples` feature.
+//!
+//! ```rust
+//! use moo::{prelude::*, samples};
+//! use std::io::Cursor;
+//!
+//! let test_file = samples::sample_test_file();
+//! assert_eq!(test_file.test_ct(), 1);
+//!
+//! // Round trip the sample through the MOO format.
+//! let mut buf = Cursor::new(Vec::new());
+//! test_file.write(&mut buf, true).unwrap();
+//! buf.set_position(0);
+//! let read_back = MooTestFile::read(&mut buf).unwrap();
+//! assert_eq!(read_back.tests()[0].name(), "inc ax");
+//! ```
+
+use crate::{
+    prelude::*,
+    registers::{MooRegisters16Init, MooRegistersInit},
+    types::{MooBusState, MooRamEntry, MooStateType, MooTestState},
+};
+
+/// The CPU type of the sample tests.
+pub const SAMPLE_CPU_TYPE: MooCpuType = MooCpuType::Intel8088;
+
+fn sample_regs(ax: u16, ip: u16, flags: u16) -> MooRegistersInit {
+    MooRegistersInit::Sixteen(MooRegisters16Init {
+        ax,
+        bx: 0x0000,
+        cx: 0x0000,
+        dx: 0x0000,
+        cs: 0x1000,
+        ss: 0x2000,
+        ds: 0x3000,
+        es: 0x4000,
+        sp: 0x0100,
+        bp: 0x0000,
+        si: 0x0000,
+        di: 0x0000,
+        ip,
+        flags,
+    })
+}
+
+fn sample_cycle(pins0: u8, memory_status: u8, data_bus: u16, bus_state: MooBusState, t_state: u8) -> MooCycleState {
+    MooCycleState {
+        pins0,
+        address_bus: 0x10001,
+        memory_status,
+        data_bus,
+        bus_state: bus_state as u8,
+        t_state,
+        ..Default::default()
+    }
+}
+
+/// Build an 8088 test of `inc ax`, with AX going from 0001 to 0002, and a single prefetch bus
+/// cycle of the following byte.
+pub fn sample_test() -> MooTest {
+    let initial_regs = sample_regs(0x0001, 0x0000, 0xF002);
+    let final_regs = sample_regs(0x0002, 0x0001, 0xF002);
+    let initial = MooTestState::new(
+        MooStateType::Initial,
+        &initial_regs,
+        None,
+        None,
+        Vec::new(),
+        vec![
+            MooRamEntry {
+                address: 0x10000,
+                value:   0x40,
+            },
+            MooRamEntry {
+                address: 0x10001,
+                value:   0x90,
+            },
+        ],
+    );
+    let fin = MooTestState::new(
+        MooStateType::Final,
+        &initial_regs,
+        Some(&final_regs),
+        None,
+        vec![0x90],
+        Vec::new(),
+    );
+    let cycles = [
+        sample_cycle(MooCycleState::PIN_ALE, 0, 0, MooBusState::CODE, 1),
+        sample_cycle(0, MooCycleState::MRDC_BIT, 0x0090, MooBusState::CODE, 2),
+        sample_cycle(0, MooCycleState::MRDC_BIT, 0x0090, MooBusState::PASV, 3),
+        sample_cycle(0, 0, 0x0090, MooBusState::PASV, 4),
+    ];
+    MooTest::new(
+        "inc ax".to_string(),
+        Some(MooTestGenMetadata::new(0x5A5A_5A5A, 1)),
+        &[0x40],
+        initial,
+        fin,
+        &cycles,
+        None,
+        None,
+    )
+}
+
+/// Build a [MooTestFile] holding [sample_test], with file metadata for opcode `40`.
+pub fn sample_test_file() -> MooTestFile {
+    let mut test_file = MooTestFile::new(1, 0, SAMPLE_CPU_TYPE, 1);
+    test_file.set_metadata(MooFileMetadata::new(1, 0, SAMPLE_CPU_TYPE, 0x40, None).with_test_count(1));
+    test_file.add_test(sample_test());
+    test_file
+}
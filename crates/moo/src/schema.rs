@@ -0,0 +1,410 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Test-generation schemas.
+//!
+//! A schema describes, per opcode, how a test suite should be shaped: how many tests a file should
+//! keep and which flags are undefined and should be masked out of comparisons. Schemas are stored
+//! as CSV with a header row naming the columns:
+//!
+//! | Column    | Contents                                                        |
+//! |-----------|-----------------------------------------------------------------|
+//! | `op`      | Opcode in hex (`0F A0` is written `0FA0`), or `*` for defaults   |
+//! | `ex`      | Group extension (`0`-`7`) in decimal, or empty                  |
+//! | `g`       | Group number in decimal, or empty                               |
+//! | `ct`      | Number of tests to keep, or empty                               |
+//! | `f_umask` | Mask of defined flags in hex, or empty                          |
+//!
+//! Other columns are ignored. Lines starting with `#` are comments, except that the first line may
+//! be a `# moo-schema N` version line. The `*` record supplies defaults for every field that a
+//! per-opcode record leaves empty.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use crate::{
+    registers::{MooRegisters, MooRegisters16, MooRegisters32},
+    test_file::MooTestFile,
+    types::{errors::MooError, MooCpuFamily, MooCpuType, MooFileMetadata},
+};
+
+const VERSION_PREFIX: &str = "# moo-schema";
+
+/// The rules a schema applies to the tests of one opcode.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooSchemaRules {
+    /// The opcode group number, if the opcode belongs to a group.
+    pub group: Option<u8>,
+    /// The number of tests a file should be trimmed to.
+    pub count: Option<u32>,
+    /// The mask of defined flags. Flags with a clear bit are undefined for the opcode.
+    pub flag_mask: Option<u32>,
+}
+
+impl MooSchemaRules {
+    /// Return true if no rule is set.
+    pub fn is_empty(&self) -> bool {
+        self.group.is_none() && self.count.is_none() && self.flag_mask.is_none()
+    }
+
+    /// Return these rules with any unset rule taken from `base`.
+    pub fn or(&self, base: &MooSchemaRules) -> MooSchemaRules {
+        MooSchemaRules {
+            group: self.group.or(base.group),
+            count: self.count.or(base.count),
+            flag_mask: self.flag_mask.or(base.flag_mask),
+        }
+    }
+
+    /// Build the register mask for the flag mask, sized for `cpu_type`.
+    ///
+    /// On 32-bit CPUs a mask with an empty upper half leaves the upper 16 flag bits defined, so a
+    /// schema written for 16-bit flags can be shared across CPU families.
+    pub fn register_mask(&self, cpu_type: MooCpuType) -> Option<MooRegisters> {
+        let mask = self.flag_mask?;
+        Some(match MooCpuFamily::from(cpu_type) {
+            MooCpuFamily::Intel80386 => {
+                let mask = if mask & 0xFFFF_0000 == 0 {
+                    mask | 0xFFFF_0000
+                }
+                else {
+                    mask
+                };
+                MooRegisters::ThirtyTwo(MooRegisters32::from_flag_mask(mask))
+            }
+            _ => MooRegisters::Sixteen(MooRegisters16::from_flag_mask(mask as u16)),
+        })
+    }
+}
+
+/// A schema record: the rules for one opcode, optionally narrowed to one group extension.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooSchemaRecord {
+    pub opcode: u16,
+    /// The group extension. A record without one applies to every extension of the opcode that
+    /// has no record of its own.
+    pub extension: Option<u8>,
+    pub rules: MooSchemaRules,
+}
+
+impl MooSchemaRecord {
+    pub fn new(opcode: u16, extension: Option<u8>, rules: MooSchemaRules) -> Self {
+        Self {
+            opcode,
+            extension,
+            rules,
+        }
+    }
+}
+
+/// A validated, versioned set of [MooSchemaRecord]s.
+#[derive(Clone, Debug, Default)]
+pub struct MooSchema {
+    defaults: MooSchemaRules,
+    records:  Vec<MooSchemaRecord>,
+    index:    HashMap<(u16, Option<u8>), usize>,
+}
+
+impl MooSchema {
+    /// The newest schema format version this library reads.
+    pub const VERSION: u32 = 1;
+
+    /// Create an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the rules that apply where a record leaves a rule unset, or where no record exists.
+    pub fn with_defaults(mut self, defaults: MooSchemaRules) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    /// Load a schema from a CSV file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, MooError> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| MooError::ParseError(format!("{}: {}", path.as_ref().display(), e)))?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Load a schema from CSV text. Errors name the offending line.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, MooError> {
+        let mut schema = MooSchema::new();
+        let mut columns: Option<Columns> = None;
+
+        for (i, line) in reader.lines().enumerate() {
+            let line_no = i + 1;
+            let line = line.map_err(|e| parse_err(line_no, e))?;
+            let text = line.trim();
+
+            if let Some(version) = text.strip_prefix(VERSION_PREFIX) {
+                if line_no != 1 {
+                    return Err(parse_err(line_no, "version line must be the first line"));
+                }
+                let version: u32 = version
+                    .trim()
+                    .parse()
+                    .map_err(|_| parse_err(line_no, format!("invalid schema version '{}'", version.trim())))?;
+                if version == 0 || version > Self::VERSION {
+                    return Err(parse_err(line_no, format!("unsupported schema version {}", version)));
+                }
+                continue;
+            }
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+
+            let fields = split_fields(text);
+            let Some(columns) = &columns
+            else {
+                columns = Some(Columns::from_header(line_no, &fields)?);
+                continue;
+            };
+
+            let op = columns.field(&fields, columns.op).unwrap_or_default();
+            let extension = columns
+                .field(&fields, columns.extension)
+                .map(|t| dec(line_no, t))
+                .transpose()?;
+            let rules = MooSchemaRules {
+                group: columns
+                    .field(&fields, columns.group)
+                    .map(|t| dec(line_no, t))
+                    .transpose()?,
+                count: columns
+                    .field(&fields, columns.count)
+                    .map(|t| dec(line_no, t))
+                    .transpose()?,
+                flag_mask: columns
+                    .field(&fields, columns.flag_mask)
+                    .map(|t| hex(line_no, t))
+                    .transpose()?,
+            };
+
+            if op == "*" {
+                if extension.is_some() {
+                    return Err(parse_err(line_no, "the default record cannot have an extension"));
+                }
+                validate_rules(&rules).map_err(|e| parse_err(line_no, e))?;
+                schema.defaults = rules;
+            }
+            else {
+                let record = MooSchemaRecord::new(hex(line_no, op)?, extension, rules);
+                schema.insert(record).map_err(|e| parse_err(line_no, e))?;
+            }
+        }
+
+        if columns.is_none() {
+            return Err(MooError::ParseError("schema has no header row".to_string()));
+        }
+        Ok(schema)
+    }
+
+    /// Add a record, rejecting it if it duplicates an existing record or has an invalid rule.
+    pub fn add_record(&mut self, record: MooSchemaRecord) -> Result<(), MooError> {
+        self.insert(record).map_err(MooError::ParseError)
+    }
+
+    fn insert(&mut self, record: MooSchemaRecord) -> Result<(), String> {
+        if let Some(ext) = record.extension {
+            if ext > 7 {
+                return Err(format!("invalid group extension {}", ext));
+            }
+        }
+        validate_rules(&record.rules)?;
+
+        let key = (record.opcode, record.extension);
+        if self.index.contains_key(&key) {
+            return Err(format!("duplicate record for opcode {}", record_name(&record)));
+        }
+        self.index.insert(key, self.records.len());
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// The schema's default rules.
+    pub fn defaults(&self) -> &MooSchemaRules {
+        &self.defaults
+    }
+
+    /// The schema's per-opcode records, in file order.
+    pub fn records(&self) -> &[MooSchemaRecord] {
+        &self.records
+    }
+
+    /// Return the record for `opcode` and `extension`, falling back to the opcode's record without
+    /// an extension.
+    pub fn record(&self, opcode: u16, extension: Option<u8>) -> Option<&MooSchemaRecord> {
+        self.index
+            .get(&(opcode, extension))
+            .or_else(|| extension.and_then(|_| self.index.get(&(opcode, None))))
+            .map(|&i| &self.records[i])
+    }
+
+    /// Resolve the rules for `opcode` and `extension`, with unset rules taken from the defaults.
+    /// Returns `None` if no record matches and the schema has no defaults.
+    pub fn rules(&self, opcode: u16, extension: Option<u8>) -> Option<MooSchemaRules> {
+        match self.record(opcode, extension) {
+            Some(record) => Some(record.rules.or(&self.defaults)),
+            None if !self.defaults.is_empty() => Some(self.defaults),
+            None => None,
+        }
+    }
+
+    /// Resolve the rules for the opcode a test file covers.
+    pub fn rules_for(&self, metadata: &MooFileMetadata) -> Option<MooSchemaRules> {
+        let opcode = u16::try_from(metadata.opcode).ok()?;
+        self.rules(opcode, metadata.group_extension())
+    }
+
+    /// Set the file's register mask from the schema's flag mask.
+    /// Returns true if the file was modified.
+    pub fn apply_flag_mask(&self, file: &mut MooTestFile) -> bool {
+        let Some(metadata) = file.metadata()
+        else {
+            return false;
+        };
+        let cpu_type = metadata.cpu_type;
+        match self.rules_for(metadata).and_then(|r| r.register_mask(cpu_type)) {
+            Some(mask) => {
+                file.set_register_mask(mask);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Trim the file to the schema's test count. Files that already have no more tests than the
+    /// count are left alone. Excess tests are removed through the edit journal, last first, so
+    /// the trim can be undone. Returns true if the file was modified.
+    pub fn apply_trim(&self, file: &mut MooTestFile) -> bool {
+        let Some(count) = file.metadata().and_then(|md| self.rules_for(md)).and_then(|r| r.count)
+        else {
+            return false;
+        };
+        if (count as usize) < file.test_ct() {
+            while file.test_ct() > count as usize {
+                file.remove_test(file.test_ct() - 1);
+            }
+            true
+        }
+        else {
+            false
+        }
+    }
+}
+
+/// Column positions in a schema's header row.
+struct Columns {
+    op: Option<usize>,
+    extension: Option<usize>,
+    group: Option<usize>,
+    count: Option<usize>,
+    flag_mask: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(line: usize, fields: &[String]) -> Result<Self, MooError> {
+        let find = |name: &str| fields.iter().position(|f| f.eq_ignore_ascii_case(name));
+        let columns = Columns {
+            op: find("op"),
+            extension: find("ex"),
+            group: find("g"),
+            count: find("ct"),
+            flag_mask: find("f_umask"),
+        };
+        if columns.op.is_none() {
+            return Err(parse_err(line, "header row has no 'op' column"));
+        }
+        Ok(columns)
+    }
+
+    /// Return the non-empty field in `column`, if the column exists.
+    fn field<'a>(&self, fields: &'a [String], column: Option<usize>) -> Option<&'a str> {
+        column
+            .and_then(|c| fields.get(c))
+            .map(String::as_str)
+            .filter(|t| !t.is_empty())
+    }
+}
+
+fn validate_rules(rules: &MooSchemaRules) -> Result<(), String> {
+    if rules.count == Some(0) {
+        return Err("test count must be greater than zero".to_string());
+    }
+    Ok(())
+}
+
+fn record_name(record: &MooSchemaRecord) -> String {
+    match record.extension {
+        Some(ext) => format!("{:02X}.{}", record.opcode, ext),
+        None => format!("{:02X}", record.opcode),
+    }
+}
+
+/// Split a CSV line into trimmed fields, honoring double-quoted fields.
+fn split_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+fn hex<T: TryFrom<u64>>(line: usize, text: &str) -> Result<T, MooError> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text)
+        .replace('_', "");
+    u64::from_str_radix(&digits, 16)
+        .ok()
+        .and_then(|v| T::try_from(v).ok())
+        .ok_or_else(|| parse_err(line, format!("invalid hex value '{}'", text)))
+}
+
+fn dec<T: std::str::FromStr>(line: usize, text: &str) -> Result<T, MooError> {
+    text.parse()
+        .map_err(|_| parse_err(line, format!("invalid decimal value '{}'", text)))
+}
+
+fn parse_err(line: usize, msg: impl std::fmt::Display) -> MooError {
+    MooError::ParseError(format!("line {}: {}", line, msg))
+}
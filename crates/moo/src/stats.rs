@@ -0,0 +1,381 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Statistics over tests, test files and whole corpora of test files.
+//!
+//! Statistics for a single file are gathered with [MooStatsAccumulator] or
+//! [MooTestFile::calc_stats]. [corpus_summary] aggregates many files, grouped by mnemonic and by
+//! opcode, for reports and dashboards that cover a complete test suite. [MooTestFile::cycle_outliers]
+//! flags tests whose cycle counts are far from those of comparable tests, which usually points to a
+//! capture glitch or an unintended exception. [MooTestFile::coverage_clusters] groups tests that
+//! exercise the same behavior, to find redundant tests in oversized files.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    ops::Range,
+};
+
+pub use crate::test_file::stats::{BusOpStats, MooStatsAccumulator, MooTestFileStats};
+use crate::{
+    test::moo_test::MooTest,
+    test_file::MooTestFile,
+    types::{opcode::MooOpcodeId, prefix::MooPrefix, timing::MooTimingBreakdown, MooCpuFamily, MooCpuFlags},
+};
+
+/// The default deviation, in standard deviations, beyond which a cycle count is an outlier.
+pub const DEFAULT_OUTLIER_SIGMA: f64 = 5.0;
+/// Groups with fewer tests than this are too small to judge and are not checked for outliers.
+const MIN_OUTLIER_GROUP: usize = 4;
+/// The default width of the cycle count ranges used to cluster tests by coverage.
+pub const DEFAULT_CYCLE_BUCKET: usize = 4;
+
+/// A [MooGroupStats] aggregates the statistics of a group of test files.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MooGroupStats {
+    /// The number of files in the group.
+    pub file_count: usize,
+    /// The number of tests in all files of the group.
+    pub test_count: usize,
+    /// The total number of cycles over all tests.
+    pub total_cycles: usize,
+    /// The number of tests that raised an exception.
+    pub exception_count: usize,
+    /// The number of tests raising each exception, by exception number.
+    pub exceptions: BTreeMap<u8, usize>,
+    /// The cycles of all tests, by what the CPU was doing in each.
+    pub timing: MooTimingBreakdown,
+}
+
+impl MooGroupStats {
+    /// Add the statistics of one file to the group.
+    pub fn add(&mut self, stats: &MooTestFileStats) {
+        self.file_count += 1;
+        self.test_count += stats.test_count;
+        self.total_cycles += stats.total_cycles;
+        self.timing += stats.timing;
+        self.exception_count += stats.exceptions_seen.len();
+        for &exception in &stats.exceptions_seen {
+            *self.exceptions.entry(exception).or_insert(0) += 1;
+        }
+    }
+
+    /// Returns the average number of cycles per test, or 0 if the group has no tests.
+    pub fn avg_cycles(&self) -> f64 {
+        if self.test_count > 0 {
+            self.total_cycles as f64 / self.test_count as f64
+        }
+        else {
+            0.0
+        }
+    }
+
+    /// Returns the fraction of tests that raised an exception, from 0.0 to 1.0.
+    pub fn exception_rate(&self) -> f64 {
+        if self.test_count > 0 {
+            self.exception_count as f64 / self.test_count as f64
+        }
+        else {
+            0.0
+        }
+    }
+}
+
+/// A [MooCorpusSummary] aggregates the statistics of many test files, overall and grouped by
+/// mnemonic and by opcode.
+#[derive(Clone, Debug, Default)]
+pub struct MooCorpusSummary {
+    /// Statistics over every file added.
+    pub total: MooGroupStats,
+    /// Statistics grouped by instruction mnemonic.
+    pub by_mnemonic: BTreeMap<String, MooGroupStats>,
+    /// Statistics grouped by [MooOpcodeId], in opcode map order.
+    pub by_opcode: BTreeMap<MooOpcodeId, MooGroupStats>,
+}
+
+impl MooCorpusSummary {
+    /// Create an empty [MooCorpusSummary].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the statistics of one file under the given mnemonic and opcode. A file without a
+    /// mnemonic or opcode is counted in the total but not in the corresponding grouping.
+    pub fn add_stats(&mut self, mnemonic: Option<&str>, opcode: Option<&MooOpcodeId>, stats: &MooTestFileStats) {
+        self.total.add(stats);
+        if let Some(mnemonic) = mnemonic.filter(|m| !m.is_empty()) {
+            self.by_mnemonic.entry(mnemonic.to_string()).or_default().add(stats);
+        }
+        if let Some(opcode) = opcode {
+            self.by_opcode.entry(opcode.clone()).or_default().add(stats);
+        }
+    }
+
+    /// Add a file, taking its mnemonic and opcode from the file metadata.
+    pub fn add_file(&mut self, file: &MooTestFile) {
+        let metadata = file.metadata();
+        self.add_stats(
+            metadata.map(|m| m.mnemonic()).as_deref(),
+            metadata.and_then(|m| m.opcode_id()).as_ref(),
+            &file.calc_stats(0),
+        );
+    }
+}
+
+/// Summarize a corpus of test files. Each file's mnemonic and opcode are taken from its metadata.
+pub fn corpus_summary<'a>(files: impl IntoIterator<Item = &'a MooTestFile>) -> MooCorpusSummary {
+    let mut summary = MooCorpusSummary::new();
+    for file in files {
+        summary.add_file(file);
+    }
+    summary
+}
+
+/// A [MooAddressingForm] describes the form of a test instruction that most affects its timing:
+/// whether it has a memory operand, and which prefixes it carries. Within a file, tests of the
+/// same form are expected to have comparable cycle counts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MooAddressingForm {
+    /// True if the instruction has a memory operand, as indicated by an effective address in the
+    /// initial state.
+    pub memory_operand: bool,
+    /// The distinct prefixes of the instruction, ordered by prefix byte.
+    pub prefixes: Vec<MooPrefix>,
+}
+
+impl MooAddressingForm {
+    /// Determine the [MooAddressingForm] of a test, decoding prefixes for the given [MooCpuFamily].
+    pub fn of(test: &MooTest, cpu_family: impl Into<MooCpuFamily>) -> Self {
+        let mut prefixes = test.prefixes(cpu_family);
+        prefixes.sort_unstable_by_key(|p| p.byte());
+        prefixes.dedup();
+        Self {
+            memory_operand: test.initial_state.ea.is_some(),
+            prefixes,
+        }
+    }
+}
+
+impl Display for MooAddressingForm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", if self.memory_operand { "mem" } else { "reg" })?;
+        for prefix in &self.prefixes {
+            write!(f, " {}", prefix)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [MooCycleOutlier] is a test whose cycle count deviates strongly from the other tests of the
+/// same [MooAddressingForm] in its file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MooCycleOutlier {
+    /// The index of the test within the file.
+    pub index: usize,
+    /// The cycle count of the test.
+    pub cycles: usize,
+    /// The addressing form the test was grouped by.
+    pub form: MooAddressingForm,
+    /// The number of tests in the group, including this one.
+    pub group_size: usize,
+    /// The mean cycle count of the other tests in the group.
+    pub mean: f64,
+    /// The standard deviation of the cycle counts of the other tests in the group.
+    pub std_dev: f64,
+    /// The signed deviation of this test from `mean`, in standard deviations. Infinite if every
+    /// other test in the group has the same cycle count.
+    pub sigma: f64,
+}
+
+/// Outlier detection
+impl MooTestFile {
+    /// Find tests whose cycle counts deviate from the other tests of the same
+    /// [MooAddressingForm] by more than `threshold` standard deviations. Each test is compared
+    /// against the rest of its group, so that a single extreme value cannot mask itself.
+    ///
+    /// Returns the outliers in test order.
+    pub fn cycle_outliers(&self, threshold: f64) -> Vec<MooCycleOutlier> {
+        let cpu_family = MooCpuFamily::from(self.cpu_type());
+        let mut groups: HashMap<MooAddressingForm, Vec<usize>> = HashMap::new();
+        for (index, test) in self.tests().iter().enumerate() {
+            groups
+                .entry(MooAddressingForm::of(test, cpu_family))
+                .or_default()
+                .push(index);
+        }
+
+        let mut outliers = Vec::new();
+        for (form, indices) in groups {
+            let n = indices.len();
+            if n < MIN_OUTLIER_GROUP {
+                continue;
+            }
+            let counts: Vec<f64> = indices.iter().map(|&i| self.tests()[i].cycles.len() as f64).collect();
+            let sum: f64 = counts.iter().sum();
+            let sum_sq: f64 = counts.iter().map(|c| c * c).sum();
+            let others = (n - 1) as f64;
+
+            for (&index, &cycles) in indices.iter().zip(&counts) {
+                let mean = (sum - cycles) / others;
+                let variance = ((sum_sq - cycles * cycles) / others - mean * mean).max(0.0);
+                let std_dev = variance.sqrt();
+                let deviation = cycles - mean;
+                let sigma = if std_dev > 0.0 {
+                    deviation / std_dev
+                }
+                else if deviation != 0.0 {
+                    deviation.signum() * f64::INFINITY
+                }
+                else {
+                    0.0
+                };
+
+                if sigma.abs() > threshold {
+                    outliers.push(MooCycleOutlier {
+                        index,
+                        cycles: cycles as usize,
+                        form: form.clone(),
+                        group_size: n,
+                        mean,
+                        std_dev,
+                        sigma,
+                    });
+                }
+            }
+        }
+        outliers.sort_by_key(|o| o.index);
+        outliers
+    }
+}
+
+/// Returns the flags register at the end of a test. Final states only record registers that
+/// changed, so unmodified flags are taken from the initial state.
+fn final_flags(test: &MooTest) -> MooCpuFlags {
+    let diff = test.diff_flags();
+    let flags = test.initial_state.regs.cpu_flags();
+    (flags | diff.set).difference(diff.cleared)
+}
+
+/// A [MooCoverageKey] summarizes the behavior a test exercises: its addressing form, its exception
+/// outcome, the resulting flags, and the range its cycle count falls in.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MooCoverageKey {
+    /// The addressing form of the instruction.
+    pub form: MooAddressingForm,
+    /// The exception raised by the test, if any.
+    pub exception: Option<u8>,
+    /// The flags the instruction may affect.
+    pub flags_affected: MooCpuFlags,
+    /// Which of the affected flags were set at the end of the test.
+    pub flags: MooCpuFlags,
+    /// The range of cycle counts the test falls in.
+    pub cycles: Range<usize>,
+}
+
+impl MooCoverageKey {
+    /// Determine the [MooCoverageKey] of a test of the given [MooCpuFamily]. Only the final values
+    /// of `flags_affected` are considered, since other flags keep their random initial values.
+    /// Cycle counts are grouped into ranges `cycle_bucket` cycles wide.
+    pub fn of(
+        test: &MooTest,
+        cpu_family: impl Into<MooCpuFamily>,
+        flags_affected: MooCpuFlags,
+        cycle_bucket: usize,
+    ) -> Self {
+        let cycle_bucket = cycle_bucket.max(1);
+        let start = test.cycles.len() / cycle_bucket * cycle_bucket;
+        Self {
+            form: MooAddressingForm::of(test, cpu_family),
+            exception: test.exception.as_ref().map(|e| e.exception_num),
+            flags_affected,
+            flags: final_flags(test) & flags_affected,
+            cycles: start..start + cycle_bucket,
+        }
+    }
+}
+
+impl Display for MooCoverageKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, ", self.form)?;
+        match self.exception {
+            Some(exception) => write!(f, "INT {}", exception)?,
+            None => write!(f, "no exception")?,
+        }
+        if !self.flags_affected.is_empty() {
+            write!(f, ", flags set: {}", self.flags.to_long_string())?;
+        }
+        write!(f, ", {}-{} cycles", self.cycles.start, self.cycles.end - 1)
+    }
+}
+
+/// A [MooCoverageCluster] is a group of tests in a file that share a [MooCoverageKey].
+#[derive(Clone, Debug)]
+pub struct MooCoverageCluster {
+    /// The behavior shared by the tests in the cluster.
+    pub key:   MooCoverageKey,
+    /// The indices of the tests in the cluster, in ascending order.
+    pub tests: Vec<usize>,
+}
+
+impl MooCoverageCluster {
+    /// Returns the indices of the tests beyond the first `keep`, which could be removed without
+    /// losing coverage of the cluster's behavior.
+    pub fn redundant(&self, keep: usize) -> &[usize] {
+        &self.tests[keep.min(self.tests.len())..]
+    }
+}
+
+/// Coverage analysis
+impl MooTestFile {
+    /// Cluster the tests of this file by [MooCoverageKey], with cycle counts grouped into ranges
+    /// `cycle_bucket` cycles wide. The affected flags are those modified by any test in the file.
+    /// Tests in the same cluster exercise the same behavior, so large clusters indicate redundant
+    /// tests.
+    ///
+    /// Returns the clusters ordered by their first test.
+    pub fn coverage_clusters(&self, cycle_bucket: usize) -> Vec<MooCoverageCluster> {
+        let cpu_family = MooCpuFamily::from(self.cpu_type());
+        let flags_affected = self
+            .tests()
+            .iter()
+            .fold(MooCpuFlags::default(), |acc, test| {
+                acc | (test.initial_state.regs.cpu_flags() ^ final_flags(test))
+            })
+            .without_reserved();
+
+        let mut clusters: HashMap<MooCoverageKey, Vec<usize>> = HashMap::new();
+        for (index, test) in self.tests().iter().enumerate() {
+            clusters
+                .entry(MooCoverageKey::of(test, cpu_family, flags_affected, cycle_bucket))
+                .or_default()
+                .push(index);
+        }
+
+        let mut clusters: Vec<MooCoverageCluster> = clusters
+            .into_iter()
+            .map(|(key, tests)| MooCoverageCluster { key, tests })
+            .collect();
+        clusters.sort_by_key(|c| c.tests[0]);
+        clusters
+    }
+}
@@ -0,0 +1,101 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! [Arbitrary] implementations for [MooTest] and [MooTestState], for fuzzing and property tests.
+//!
+//! Generated tests are consistent with what the reader accepts: both states use the same register
+//! width, tags are deduplicated and anchors are unique per kind. The register width is chosen
+//! per test, so a file built from generated tests should use a [MooCpuType](crate::types::MooCpuType)
+//! matching [MooTestState::regs].
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    registers::{MooRegisters, MooRegisters16, MooRegisters32},
+    test::{moo_test::MooTest, test_state::MooTestState},
+    types::{anchor::MooAnchorKind, MooStateType},
+};
+
+/// Generate a [MooTestState] with either 16 or 32-bit registers. Effective addresses and
+/// descriptors are left unset.
+fn arbitrary_state(u: &mut Unstructured<'_>, s_type: MooStateType, wide: bool) -> Result<MooTestState> {
+    let regs = if wide {
+        MooRegisters::ThirtyTwo(MooRegisters32::arbitrary(u)?)
+    }
+    else {
+        MooRegisters::Sixteen(MooRegisters16::arbitrary(u)?)
+    };
+    Ok(MooTestState {
+        s_type,
+        regs,
+        descriptors: None,
+        ea: None,
+        queue: Vec::arbitrary(u)?,
+        ram: Vec::arbitrary(u)?,
+        vendor_chunks: Vec::new(),
+    })
+}
+
+impl<'a> Arbitrary<'a> for MooTestState {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let s_type = if bool::arbitrary(u)? {
+            MooStateType::Final
+        }
+        else {
+            MooStateType::Initial
+        };
+        let wide = bool::arbitrary(u)?;
+        arbitrary_state(u, s_type, wide)
+    }
+}
+
+impl<'a> Arbitrary<'a> for MooTest {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let wide = bool::arbitrary(u)?;
+        let name = String::arbitrary(u)?;
+        let gen_metadata = Option::arbitrary(u)?;
+        let bytes: Vec<u8> = Vec::arbitrary(u)?;
+        let initial_state = arbitrary_state(u, MooStateType::Initial, wide)?;
+        let final_state = arbitrary_state(u, MooStateType::Final, wide)?;
+        let cycles: Vec<_> = Vec::arbitrary(u)?;
+        let exception = Option::arbitrary(u)?;
+
+        let mut test = MooTest::new(
+            name,
+            gen_metadata,
+            &bytes,
+            initial_state,
+            final_state,
+            &cycles,
+            exception,
+            None,
+        );
+        for tag in Vec::<String>::arbitrary(u)? {
+            test.add_tag(tag);
+        }
+        for _ in 0..u.int_in_range(0..=3)? {
+            test.set_anchor(MooAnchorKind::arbitrary(u)?, u.int_in_range(0..=cycles.len())?);
+        }
+        Ok(test)
+    }
+}
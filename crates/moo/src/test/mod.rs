@@ -21,5 +21,7 @@
     DEALINGS IN THE SOFTWARE.
 */
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 pub mod moo_test;
 pub mod test_state;
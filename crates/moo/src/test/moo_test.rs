@@ -22,23 +22,46 @@
 */
 use crate::{
     prelude::MooCycleState,
-    registers::{MooRegister, MooRegisterDiff, MooRegisters},
+    registers::{MooRegister, MooRegisterDiff, MooRegisters, MooRegisters32},
     test::test_state::MooTestState,
     types::{
-        chunks::{MooBytesChunk, MooChunkType, MooNameChunk, MooTestChunk},
-        comparison::MooComparison,
-        flags::{MooCpuFlag, MooCpuFlagsDiff},
+        align::{align_transactions, MooAlignedDiff},
+        anchor::{MooAnchor, MooAnchorKind, MooAnchorsChunk},
+        chunks::{MooBytesChunk, MooChunkType, MooDigestChunk, MooNameChunk, MooTagsChunk, MooTestChunk},
+        comparison::{MooCompareOptions, MooComparison},
+        cycle_iter::MooCycleIterExt,
+        errors::MooError,
+        flags::{MooCpuFlags, MooCpuFlagsDiff},
+        glitch::{detect_glitches, MooGlitch},
+        hash::{hex_string, MooHashAlgorithm, MooTestDigest},
+        io_stimulus::{MooIoStimulus, MooIoStimulusChunk, MooPortWidth},
+        opcode::MooOpcodeId,
+        prefix::MooPrefix,
+        ram_diff::MooRamDiffPrinter,
+        timing::MooTimingBreakdown,
+        trim::{MooTrimPolicy, MooTrimmedCycles},
+        vendor::MooVendorChunk,
+        MooAddressWrap,
+        MooBusHistogram,
+        MooBusState,
         MooCpuFamily,
         MooCpuMode,
+        MooCpuType,
         MooException,
         MooOperandSize,
+        MooQueueDiff,
+        MooRamEntry,
         MooSegmentSize,
         MooTestGenMetadata,
     },
 };
 use binrw::{BinResult, BinWrite};
 use sha1::Digest;
-use std::io::{Cursor, Seek, Write};
+use std::{
+    io::{Cursor, Seek, Write},
+    iter::Copied,
+    slice::Iter,
+};
 
 macro_rules! push_or_return {
     ($vec:expr, $item:expr, $ret:expr) => {{
@@ -58,6 +81,13 @@ pub struct MooTest {
     pub(crate) cycles: Vec<MooCycleState>,
     pub(crate) exception: Option<MooException>,
     pub(crate) hash: Option<[u8; 20]>,
+    pub(crate) hash_dirty: bool,
+    pub(crate) digest: Option<MooTestDigest>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) anchors: Vec<MooAnchor>,
+    pub(crate) io_stimulus: Vec<MooIoStimulus>,
+    pub(crate) vendor_chunks: Vec<MooVendorChunk>,
+    pub(crate) synthetic: bool,
 }
 
 /// An individual test case for a particular CPU.
@@ -70,6 +100,9 @@ pub struct MooTest {
 ///    during execution of the instruction(s)
 ///  - An optional [MooException] if an exception was raised during execution
 ///  - A SHA-1 hash of the test used to uniquely identify it
+///  - An optional alternate digest of the test, such as SHA-256
+///  - An optional set of string tags used to categorize the test
+///  - Optional expected values for the port reads performed by the test
 impl MooTest {
     /// Create a new [MooTest].
     /// # Arguments
@@ -103,6 +136,13 @@ impl MooTest {
             cycles: cycles.to_vec(),
             exception,
             hash,
+            hash_dirty: false,
+            digest: None,
+            tags: Vec::new(),
+            anchors: Vec::new(),
+            io_stimulus: Vec::new(),
+            vendor_chunks: Vec::new(),
+            synthetic: false,
         }
     }
 
@@ -112,32 +152,55 @@ impl MooTest {
     }
 
     /// Retrieve a mutable reference to the human-readable name of the test (typically the disassembly of the instruction(s) being tested).
+    /// The name is part of the test hash, so this marks the stored hash as stale.
     pub fn name_mut(&mut self) -> &mut String {
+        self.hash_dirty = true;
         &mut self.name
     }
 
+    /// Rename the test, marking the stored hash as stale.
+    pub fn rename(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+        self.hash_dirty = true;
+    }
+
     /// Retrieve the optional test generation metadata for the test.
     pub fn gen_metadata(&self) -> Option<&MooTestGenMetadata> {
         self.gen_metadata.as_ref()
     }
 
+    /// Retrieve the per-test seed the test was generated from, if recorded.
+    pub fn seed(&self) -> Option<u64> {
+        self.gen_metadata.as_ref().map(|g| g.seed)
+    }
+
     /// Retrieve a reference to a slice of the raw bytes that comprise the instruction(s) being tested.
     pub fn bytes(&self) -> &[u8] {
         &self.bytes
     }
 
     /// Retrieve a mutable reference to the vector of raw bytes that comprise the instruction(s) being tested.
+    /// Marks the stored hash as stale.
     pub fn bytes_mut(&mut self) -> &mut Vec<u8> {
+        self.hash_dirty = true;
         &mut self.bytes
     }
 
+    /// Replace the instruction bytes of the test, marking the stored hash as stale.
+    pub fn set_bytes(&mut self, bytes: &[u8]) {
+        self.bytes = bytes.to_vec();
+        self.hash_dirty = true;
+    }
+
     /// Retrieve a reference to the [MooTestState] representing the initial CPU state.
     pub fn initial_state(&self) -> &MooTestState {
         &self.initial_state
     }
 
     /// Retrieve a mutable reference to the [MooTestState] representing the initial CPU state.
+    /// Marks the stored hash as stale.
     pub fn initial_state_mut(&mut self) -> &mut MooTestState {
+        self.hash_dirty = true;
         &mut self.initial_state
     }
 
@@ -146,8 +209,19 @@ impl MooTest {
         &self.final_state
     }
 
+    /// Return a copy of the final CPU state with a complete register set. Registers absent from
+    /// the final state's sparse register mask are taken from the initial state. RAM and other
+    /// state are copied unchanged.
+    pub fn full_final_state(&self) -> MooTestState {
+        let mut state = self.final_state.clone();
+        state.regs = self.final_state.regs.rehydrate(&self.initial_state.regs);
+        state
+    }
+
     /// Retrieve a mutable reference to the [MooTestState] representing the final CPU state.
+    /// Marks the stored hash as stale.
     pub fn final_state_mut(&mut self) -> &mut MooTestState {
+        self.hash_dirty = true;
         &mut self.final_state
     }
 
@@ -157,17 +231,81 @@ impl MooTest {
         &self.cycles
     }
 
+    /// Retrieve an iterator over this test's cycles, by value.
+    /// Combine with the adapters in [MooCycleIterExt](crate::types::cycle_iter::MooCycleIterExt)
+    /// to filter cycles semantically, e.g. `test.cycles_iter().ale_cycles().mem_reads().count()`.
+    pub fn cycles_iter(&self) -> Copied<Iter<'_, MooCycleState>> {
+        self.cycles.iter().copied()
+    }
+
+    /// Returns true if this test was derived from another test, such as by
+    /// [MooTest::slice_cycles]. A synthetic test has no hash and cannot be written.
+    pub fn is_synthetic(&self) -> bool {
+        self.synthetic
+    }
+
+    /// Retrieve the SHA-1 hash of the test, if available.
+    pub fn hash(&self) -> Option<&[u8; 20]> {
+        self.hash.as_ref()
+    }
+
     /// Retrieve the SHA-1 hash of the test as a hexadecimal ASCII string.
     /// If the hash is not available, returns the literal string "##NOHASH##".
     pub fn hash_string(&self) -> String {
         if let Some(hash) = &self.hash {
-            hash.iter().map(|b| format!("{:02x}", b)).collect()
+            hex_string(hash)
         }
         else {
             "##NOHASH##".to_string()
         }
     }
 
+    /// Returns true if the test has been modified in a way that may invalidate its stored hash.
+    /// A dirty hash is never preserved when the test is written.
+    pub fn is_hash_dirty(&self) -> bool {
+        self.hash_dirty
+    }
+
+    /// Recalculate the SHA-1 hash of the test from its current contents, as written at position
+    /// `index` in a file, and clear the dirty flag. An alternate digest, if present, is
+    /// recalculated with the same algorithm.
+    pub fn recompute_hash(&mut self, index: usize) -> BinResult<&[u8; 20]> {
+        let data = self.hash_data(index)?;
+        self.hash = Some(sha1::Sha1::digest(&data).into());
+        if let Some(digest) = &mut self.digest {
+            *digest = MooTestDigest::compute(digest.algorithm, &data);
+        }
+        self.hash_dirty = false;
+        Ok(self.hash.as_ref().unwrap())
+    }
+
+    /// Retrieve the alternate digest of the test, if present.
+    pub fn digest(&self) -> Option<&MooTestDigest> {
+        self.digest.as_ref()
+    }
+
+    /// Compute a digest of the test with the specified algorithm, as written at position `index`
+    /// in a file. The digest covers the same data as the canonical SHA-1 hash.
+    pub fn compute_digest(&self, index: usize, algorithm: MooHashAlgorithm) -> BinResult<MooTestDigest> {
+        Ok(MooTestDigest::compute(algorithm, &self.hash_data(index)?))
+    }
+
+    /// Returns true if the stored SHA-1 hash and alternate digest, where present, match the
+    /// contents of the test as written at position `index` in a file. Hashes that were
+    /// preserved across edits or reordering will not verify.
+    pub fn verify_hashes(&self, index: usize) -> BinResult<bool> {
+        let data = self.hash_data(index)?;
+
+        let hash_ok = self
+            .hash
+            .is_none_or(|hash| sha1::Sha1::digest(&data).as_slice() == hash.as_slice());
+        let digest_ok = self
+            .digest
+            .as_ref()
+            .is_none_or(|digest| digest.algorithm.digest(&data) == digest.digest);
+        Ok(hash_ok && digest_ok)
+    }
+
     /// Retrieve an optional reference to any [MooException].
     /// A [MooException] will be present if an exception was raised during test execution.
     pub fn exception(&self) -> Option<&MooException> {
@@ -176,10 +314,237 @@ impl MooTest {
 
     /// Retrieve an optional mutable reference to any [MooException].
     /// A [MooException] will be present if an exception was raised during test execution.
+    /// Marks the stored hash as stale.
     pub fn exception_mut(&mut self) -> Option<&mut MooException> {
+        self.hash_dirty = true;
         self.exception.as_mut()
     }
 
+    /// Retrieve the tags attached to this test, in sorted order.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns true if this test has the specified tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.binary_search_by(|t| t.as_str().cmp(tag)).is_ok()
+    }
+
+    /// Attach a tag to this test. Tags are not included in the test hash, so tagging a test does
+    /// not change its identity.
+    /// Returns false if the test already had the tag.
+    pub fn add_tag(&mut self, tag: impl Into<String>) -> bool {
+        let tag = tag.into();
+        match self.tags.binary_search(&tag) {
+            Ok(_) => false,
+            Err(pos) => {
+                self.tags.insert(pos, tag);
+                true
+            }
+        }
+    }
+
+    /// Retrieve the cycle anchors of this test, in [MooAnchorKind] order.
+    pub fn anchors(&self) -> &[MooAnchor] {
+        &self.anchors
+    }
+
+    /// Returns the cycle index of the anchor of the given kind, if set.
+    pub fn anchor(&self, kind: MooAnchorKind) -> Option<usize> {
+        self.anchors.iter().find(|a| a.kind == kind).map(|a| a.cycle)
+    }
+
+    /// Set the anchor of the given kind to a cycle index, replacing any existing anchor of that
+    /// kind. Like tags, anchors are not included in the test hash.
+    pub fn set_anchor(&mut self, kind: MooAnchorKind, cycle: usize) {
+        match self.anchors.binary_search_by_key(&kind, |a| a.kind) {
+            Ok(pos) => self.anchors[pos].cycle = cycle,
+            Err(pos) => self.anchors.insert(pos, MooAnchor { kind, cycle }),
+        }
+    }
+
+    /// Remove the anchor of the given kind. Returns false if the test had no such anchor.
+    pub fn remove_anchor(&mut self, kind: MooAnchorKind) -> bool {
+        match self.anchors.binary_search_by_key(&kind, |a| a.kind) {
+            Ok(pos) => {
+                self.anchors.remove(pos);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Retrieve the expected port read values of this test, ordered by port and width.
+    pub fn io_stimulus(&self) -> &[MooIoStimulus] {
+        &self.io_stimulus
+    }
+
+    /// Returns the values to return to reads of `port` at the given width, in read order, if the
+    /// test has any.
+    pub fn port_values(&self, port: u16, width: MooPortWidth) -> Option<&[u32]> {
+        self.io_stimulus
+            .iter()
+            .find(|s| s.port == port && s.width == width)
+            .map(|s| s.values.as_slice())
+    }
+
+    /// Set the values returned by reads of `port` at the given width, replacing any existing
+    /// values. An empty `values` removes the entry. Values are masked to the access width. Like
+    /// anchors, the IO stimulus is not included in the test hash.
+    pub fn set_port_values(&mut self, port: u16, width: MooPortWidth, mut values: Vec<u32>) {
+        values.iter_mut().for_each(|v| *v &= width.mask());
+        match self
+            .io_stimulus
+            .binary_search_by_key(&(port, width), |s| (s.port, s.width))
+        {
+            Ok(pos) if values.is_empty() => {
+                self.io_stimulus.remove(pos);
+            }
+            Ok(pos) => self.io_stimulus[pos].values = values,
+            Err(_) if values.is_empty() => {}
+            Err(pos) => self.io_stimulus.insert(pos, MooIoStimulus::new(port, width, values)),
+        }
+    }
+
+    /// Return the cycles from the [MooAnchorKind::FirstExecution] anchor through the
+    /// [MooAnchorKind::LastExecution] anchor, inclusive. A missing anchor extends the range to the
+    /// start or end of the trace, and anchors beyond the trace are clamped to it.
+    pub fn execution_cycles(&self) -> &[MooCycleState] {
+        let cycles = self.cycles();
+        let start = self
+            .anchor(MooAnchorKind::FirstExecution)
+            .unwrap_or(0)
+            .min(cycles.len());
+        let end = self
+            .anchor(MooAnchorKind::LastExecution)
+            .map_or(cycles.len(), |last| last.saturating_add(1).min(cycles.len()));
+        &cycles[start..end.max(start)]
+    }
+
+    /// Remove cycles from the start and end of the cycle trace as selected by `policy`, returning
+    /// the removed cycles. Anchors are shifted to match the trimmed trace.
+    ///
+    /// The trim is refused, leaving the test unchanged, if it would remove an anchored cycle, or
+    /// any cycle from the address latch of a bus transaction through its last data transfer.
+    /// Idle cycles that trail a transaction may be removed. For a test that raises an exception,
+    /// the writes of the exception frame are checked first, so that a trim that would cut the
+    /// frame is reported as such.
+    pub fn trim(&mut self, policy: MooTrimPolicy) -> Result<MooTrimmedCycles, MooError> {
+        let cycles = self.cycles();
+        let len = cycles.len();
+
+        // The cycle span of each transaction that must be kept whole, with its address and
+        // whether it was a write.
+        let spans: Vec<(usize, usize, u32, bool)> = self
+            .cycles_iter()
+            .transactions()
+            .map(|t| {
+                let end = (t.start..t.start + t.len)
+                    .rev()
+                    .find(|&i| cycles[i].is_reading() || cycles[i].is_writing())
+                    .unwrap_or(t.start);
+                (t.start, end, t.address, t.is_mem_write())
+            })
+            .collect();
+
+        let (leading, trailing) = match policy {
+            MooTrimPolicy::Idle => {
+                let anchored = self.anchors.iter().map(|a| a.cycle).filter(|&c| c < len);
+                let first = spans.iter().map(|s| s.0).chain(anchored.clone()).min();
+                let last = spans.iter().map(|s| s.1).chain(anchored).max();
+                match (first, last) {
+                    (Some(first), Some(last)) => (first, len - 1 - last),
+                    _ => (0, 0),
+                }
+            }
+            MooTrimPolicy::Anchors => {
+                let first = self.anchor(MooAnchorKind::FirstExecution).unwrap_or(0);
+                let last = self
+                    .anchor(MooAnchorKind::LastExecution)
+                    .unwrap_or(len.saturating_sub(1));
+                if first > last || last >= len {
+                    return Err(MooError::TrimError(format!(
+                        "execution anchors {}..={} are outside the {} cycle trace",
+                        first, last, len
+                    )));
+                }
+                (first, len - 1 - last)
+            }
+            MooTrimPolicy::Count { leading, trailing } => {
+                if leading.saturating_add(trailing) > len {
+                    return Err(MooError::TrimError(format!(
+                        "cannot remove {} cycles from a {} cycle trace",
+                        leading.saturating_add(trailing),
+                        len
+                    )));
+                }
+                (leading, trailing)
+            }
+        };
+
+        let keep = leading..len - trailing;
+        let removed = |start: usize, end: usize| start < keep.start || end >= keep.end;
+
+        if let Some(exception) = &self.exception {
+            let frame = (exception.flag_address.saturating_sub(4) & !3)..exception.flag_address.saturating_add(2);
+            if let Some(span) = spans.iter().find(|s| s.3 && frame.contains(&s.2) && removed(s.0, s.1)) {
+                return Err(MooError::TrimError(format!(
+                    "trim would cut the exception frame write at cycle {}",
+                    span.0
+                )));
+            }
+        }
+        if let Some(anchor) = self.anchors.iter().find(|a| a.cycle < len && removed(a.cycle, a.cycle)) {
+            return Err(MooError::TrimError(format!(
+                "trim would remove the {} anchor at cycle {}",
+                anchor.kind, anchor.cycle
+            )));
+        }
+        if let Some(span) = spans.iter().find(|s| removed(s.0, s.1)) {
+            return Err(MooError::TrimError(format!(
+                "trim would cut the bus transaction at cycle {}",
+                span.0
+            )));
+        }
+
+        if leading == 0 && trailing == 0 {
+            return Ok(MooTrimmedCycles::default());
+        }
+
+        let mut kept = std::mem::take(&mut self.cycles);
+        let trimmed = MooTrimmedCycles {
+            trailing: kept.split_off(keep.end),
+            leading:  kept.drain(..keep.start).collect(),
+        };
+        self.cycles = kept;
+        for anchor in &mut self.anchors {
+            anchor.cycle = anchor.cycle.saturating_sub(leading);
+        }
+        self.hash_dirty = true;
+        Ok(trimmed)
+    }
+
+    /// Retrieve the vendor chunks attached to this test.
+    pub fn vendor_chunks(&self) -> &[MooVendorChunk] {
+        &self.vendor_chunks
+    }
+
+    /// Retrieve a mutable reference to the vendor chunks attached to this test.
+    pub fn vendor_chunks_mut(&mut self) -> &mut Vec<MooVendorChunk> {
+        &mut self.vendor_chunks
+    }
+
+    /// Remove a tag from this test. Returns false if the test did not have the tag.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        match self.tags.binary_search_by(|t| t.as_str().cmp(tag)) {
+            Ok(pos) => {
+                self.tags.remove(pos);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
     /// Compare two MooTests and return a vector of differences as [MooComparison] entries.
     /// Arguments:
     /// * `other` - The other [MooTest] to compare against.
@@ -191,42 +556,20 @@ impl MooTest {
     pub fn compare(&self, other: &MooTest, return_first: bool) -> Vec<MooComparison> {
         let mut differences = Vec::new();
 
-        if self.final_state.regs != other.final_state.regs {
-            push_or_return!(differences, MooComparison::RegisterMismatch, return_first);
-        }
-        if self.cycles.len() != other.cycles.len() {
-            push_or_return!(
-                differences,
-                MooComparison::CycleCountMismatch(self.cycles.len(), other.cycles.len()),
-                return_first
-            );
-        }
-        for ((i, this_cycle), other_cycle) in self.cycles.iter().enumerate().zip(other.cycles.iter()) {
-            // The address bus is inconsistent except at ALE, so only compare if ALE bit is set.
-            if this_cycle.pins0 & MooCycleState::PIN_ALE != 0 {
-                if other_cycle.pins0 & MooCycleState::PIN_ALE == 0 {
-                    push_or_return!(differences, MooComparison::ALEMismatch(i, true, false), return_first);
-                }
-
-                if this_cycle.address_bus != other_cycle.address_bus {
-                    push_or_return!(
-                        differences,
-                        MooComparison::CycleAddressMismatch(this_cycle.address_bus, other_cycle.address_bus),
-                        return_first
-                    );
-                }
-
-                if this_cycle.bus_state != other_cycle.bus_state {
+        match self.final_state.regs.mismatches(&other.final_state.regs) {
+            Some(mismatches) => {
+                for (reg, this_value, other_value) in mismatches {
                     push_or_return!(
                         differences,
-                        MooComparison::CycleBusMismatch(this_cycle.bus_state, other_cycle.bus_state),
+                        MooComparison::RegisterMismatch(reg, this_value, other_value),
                         return_first
                     );
                 }
             }
-            else if other_cycle.pins0 & MooCycleState::PIN_ALE != 0 {
-                push_or_return!(differences, MooComparison::ALEMismatch(i, false, true), return_first);
-            }
+            None => push_or_return!(differences, MooComparison::RegisterWidthMismatch, return_first),
+        }
+        for difference in MooTest::cycle_differences(self.cycles(), other.cycles()) {
+            push_or_return!(differences, difference, return_first);
         }
 
         for (this_ram_entry, other_ram_entry) in self
@@ -254,318 +597,224 @@ impl MooTest {
         differences
     }
 
-    /// Determine the differences in CPU flags between the initial and final states.
-    /// Returns a [MooCpuFlagsDiff] struct containing the flags that were set, cleared,
-    /// and those that remained unmodified.
-    pub fn diff_flags(&self) -> MooCpuFlagsDiff {
-        let mut set_flags: Vec<MooCpuFlag> = Vec::new();
-        let mut cleared_flags: Vec<MooCpuFlag> = Vec::new();
-        let mut unmodified_set_flags: Vec<MooCpuFlag> = Vec::new();
-        let mut unmodified_cleared_flags: Vec<MooCpuFlag> = Vec::new();
-
-        let flags_changed = match (&self.initial_state.regs, &self.final_state.regs) {
-            (MooRegisters::Sixteen(regs16_0), MooRegisters::Sixteen(regs16_1)) => {
-                if let Some(flags) = regs16_1.flags() {
-                    (regs16_0.flags as u32) ^ (flags as u32)
-                }
-                else {
-                    return MooCpuFlagsDiff::default();
+    /// Compare the final state of this test against a state captured by the caller, such as an
+    /// emulator's registers and memory after executing the test, without building a [MooTest].
+    ///
+    /// `regs` should hold the full register set: the test's final register delta is expanded
+    /// against its initial state before comparing. Each final RAM entry of the test is looked up
+    /// by address in `ram`; an address missing from `ram` is reported as a
+    /// [MooComparison::MemoryValueMismatch] against a default entry. If `cycles` is provided, it
+    /// is compared as by [MooTest::compare].
+    ///
+    /// In each [MooComparison], the first value is the test's and the second the caller's.
+    pub fn compare_final_state(
+        &self,
+        regs: &MooRegisters,
+        ram: &[MooRamEntry],
+        cycles: Option<&[MooCycleState]>,
+        options: &MooCompareOptions,
+    ) -> Vec<MooComparison> {
+        let return_first = options.return_first;
+        let mut differences = Vec::new();
+
+        let expected_regs = self.final_state.regs.rehydrate(&self.initial_state.regs);
+        match expected_regs.mismatches(regs) {
+            Some(mismatches) => {
+                for (reg, expected, actual) in mismatches {
+                    let ignored = match reg {
+                        MooRegister::FLAGS | MooRegister::EFLAGS => options.ignore_flags,
+                        _ => 0,
+                    };
+                    if (expected ^ actual) & !ignored != 0 {
+                        push_or_return!(
+                            differences,
+                            MooComparison::RegisterMismatch(reg, expected, actual),
+                            return_first
+                        );
+                    }
                 }
             }
-            (MooRegisters::ThirtyTwo(regs32_0), MooRegisters::ThirtyTwo(regs32_1)) => {
-                if let Some(flags) = regs32_1.eflags() {
-                    regs32_0.eflags ^ flags
+            None => push_or_return!(differences, MooComparison::RegisterWidthMismatch, return_first),
+        }
+
+        for entry in self.final_state.ram() {
+            let actual = ram.iter().find(|e| e.address == entry.address);
+            match actual {
+                Some(actual) if actual.value == entry.value => {}
+                Some(actual) => {
+                    push_or_return!(
+                        differences,
+                        MooComparison::MemoryValueMismatch(*entry, *actual),
+                        return_first
+                    );
                 }
-                else {
-                    return MooCpuFlagsDiff::default();
+                None => {
+                    push_or_return!(
+                        differences,
+                        MooComparison::MemoryValueMismatch(*entry, MooRamEntry::default()),
+                        return_first
+                    );
                 }
             }
-            _ => 0,
-        };
+        }
 
-        if flags_changed == 0 {
-            return MooCpuFlagsDiff::default();
+        if let Some(cycles) = cycles {
+            for difference in MooTest::cycle_differences(self.cycles(), cycles) {
+                push_or_return!(differences, difference, return_first);
+            }
         }
 
-        //log::debug!("Flags changed: {:08X}", flags_changed);
+        differences
+    }
 
-        for i in 0..32 {
-            let flag_mask = 1 << i;
+    /// Compare two cycle traces. The address and bus state are only compared on cycles where
+    /// ALE is asserted, as the address bus is not meaningful otherwise.
+    fn cycle_differences(this: &[MooCycleState], other: &[MooCycleState]) -> Vec<MooComparison> {
+        let mut differences = Vec::new();
+        if this.len() != other.len() {
+            differences.push(MooComparison::CycleCountMismatch(this.len(), other.len()));
+        }
+        for ((i, this_cycle), other_cycle) in this.iter().enumerate().zip(other.iter()) {
+            if this_cycle.pins0 & MooCycleState::PIN_ALE != 0 {
+                if other_cycle.pins0 & MooCycleState::PIN_ALE == 0 {
+                    differences.push(MooComparison::ALEMismatch(i, true, false));
+                }
+                if this_cycle.address_bus != other_cycle.address_bus {
+                    differences.push(MooComparison::CycleAddressMismatch(
+                        i,
+                        this_cycle.address_bus,
+                        other_cycle.address_bus,
+                    ));
+                }
+                if this_cycle.bus_state != other_cycle.bus_state {
+                    differences.push(MooComparison::CycleBusMismatch(
+                        i,
+                        this_cycle.bus_state,
+                        other_cycle.bus_state,
+                    ));
+                }
+            }
+            else if other_cycle.pins0 & MooCycleState::PIN_ALE != 0 {
+                differences.push(MooComparison::ALEMismatch(i, false, true));
+            }
+        }
+        differences
+    }
 
-            // Check if flag is set
-            let is_set = match &self.final_state.regs {
-                MooRegisters::Sixteen(regs16_1) => (regs16_1.flags as u32) & flag_mask != 0,
-                MooRegisters::ThirtyTwo(regs32_1) => regs32_1.eflags & flag_mask != 0,
-            };
+    /// Compare the bus transactions of this test's cycles against those of `other`, aligning the
+    /// two sequences so that a difference in timing, such as an extra idle cycle, does not cause
+    /// every later transaction to mismatch. See [align_transactions] for the meaning of `band`.
+    pub fn compare_aligned(&self, other: &MooTest, band: usize) -> Vec<MooAlignedDiff> {
+        let this: Vec<_> = self.cycles().iter().copied().transactions().collect();
+        let other: Vec<_> = other.cycles().iter().copied().transactions().collect();
+        align_transactions(&this, &other, band)
+    }
 
-            // Check if flag is cleared
-            let is_cleared = match &self.final_state.regs {
-                MooRegisters::Sixteen(regs16_1) => (regs16_1.flags as u32) & flag_mask == 0,
-                MooRegisters::ThirtyTwo(regs32_1) => regs32_1.eflags & flag_mask == 0,
-            };
+    /// Count this test's bus cycles by the [MooBusState] decoded at the start of each. A bus cycle
+    /// begins at each cycle with ALE (or ADS) active, so pipelined bus cycles are each counted.
+    pub fn bus_state_histogram(&self, cpu_type: MooCpuType) -> MooBusHistogram {
+        let mut histogram = MooBusHistogram::default();
+        for transaction in self.cycles_iter().transactions() {
+            histogram.add(transaction.bus_state(cpu_type));
+        }
+        histogram
+    }
 
-            if let Some(flag) = MooCpuFlag::from_bit(i as u8) {
-                // Check if flags are unmodified
-                if flags_changed & flag_mask == 0 {
-                    if is_set {
-                        unmodified_set_flags.push(flag);
-                    }
-                    else if is_cleared {
-                        unmodified_cleared_flags.push(flag);
-                    }
-                    continue;
-                }
+    /// Count this test's cycles by what the CPU was doing: fetching code, executing, accessing
+    /// memory or IO, or waiting. See [MooTimingBreakdown].
+    pub fn timing_breakdown(&self, cpu_type: MooCpuType) -> MooTimingBreakdown {
+        MooTimingBreakdown::from_cycles(self.cycles(), cpu_type)
+    }
 
-                if is_set {
-                    set_flags.push(flag);
-                }
+    /// Return a [MooRamDiffPrinter] for the RAM changes of this test, with the regions holding
+    /// the initial `CS:IP` and the top of the stack labelled `code` and `stack`.
+    pub fn ram_diff_printer(&self) -> MooRamDiffPrinter<'_> {
+        let mut printer = MooRamDiffPrinter::new(self.initial_state.ram(), self.final_state.ram());
+        if let Some(csip) = self.initial_state.csip_linear() {
+            printer = printer.with_label(csip, "code");
+        }
+        if let Some(sp) = self.initial_state.sp_linear() {
+            // Pushes write below SP and pops read from SP upwards.
+            printer = printer.with_label(sp.wrapping_sub(1), "stack").with_label(sp, "stack");
+        }
+        printer
+    }
 
-                if is_cleared {
-                    cleared_flags.push(flag);
-                }
-            }
+    /// Determine the differences in CPU flags between the initial and final states.
+    /// Returns a [MooCpuFlagsDiff] struct containing the flags that were set, cleared,
+    /// and those that remained unmodified.
+    pub fn diff_flags(&self) -> MooCpuFlagsDiff {
+        let (initial_flags, final_flags) = match (&self.initial_state.regs, &self.final_state.regs) {
+            (MooRegisters::Sixteen(regs16_0), MooRegisters::Sixteen(regs16_1)) => match regs16_1.flags() {
+                Some(flags) => (MooCpuFlags::from(regs16_0.flags), MooCpuFlags::from(flags)),
+                None => return MooCpuFlagsDiff::default(),
+            },
+            (MooRegisters::ThirtyTwo(regs32_0), MooRegisters::ThirtyTwo(regs32_1)) => match regs32_1.eflags() {
+                Some(flags) => (MooCpuFlags::from(regs32_0.eflags), MooCpuFlags::from(flags)),
+                None => return MooCpuFlagsDiff::default(),
+            },
+            _ => return MooCpuFlagsDiff::default(),
+        };
+
+        let flags_changed = initial_flags ^ final_flags;
+        if flags_changed.is_empty() {
+            return MooCpuFlagsDiff::default();
         }
 
         MooCpuFlagsDiff {
-            set: set_flags,
-            cleared: cleared_flags,
-            unmodified_set: unmodified_set_flags,
-            unmodified_cleared: unmodified_cleared_flags,
+            set: final_flags & flags_changed,
+            cleared: !final_flags & flags_changed,
+            unmodified_set: final_flags.difference(flags_changed),
+            unmodified_cleared: (!final_flags).difference(flags_changed),
+        }
+    }
+
+    /// Determine how the instruction queue changed between the initial and final states.
+    /// The fewest consumed bytes that explain the final queue are reported, so a queue that was
+    /// flushed and refilled may appear as a partial consumption if the bytes happen to match.
+    /// Returns `None` if neither state has queue contents.
+    pub fn diff_queue(&self) -> Option<MooQueueDiff> {
+        let initial = self.initial_state.queue();
+        let r#final = self.final_state.queue();
+        if initial.is_empty() && r#final.is_empty() {
+            return None;
         }
+        let consumed = (0..=initial.len())
+            .find(|&i| r#final.starts_with(&initial[i..]))
+            .unwrap_or(initial.len());
+        Some(MooQueueDiff {
+            consumed,
+            fetched: r#final[initial.len() - consumed..].to_vec(),
+        })
     }
 
     /// Determine the differences in CPU registers between the initial and final states.
     /// Returns a vector of [MooRegisterDiff] entries representing the registers that changed.
+    ///
+    /// Only registers present in the final state's register mask are considered, and of those,
+    /// only registers whose value differs from the initial state are reported. A register missing
+    /// from the initial state's mask is always reported.
     pub fn diff_regs(&self) -> Vec<MooRegisterDiff> {
+        let initial_regs = &self.initial_state.regs;
         let mut diff_regs = Vec::new();
-
-        match (&self.initial_state.regs, &self.final_state.regs) {
-            (MooRegisters::Sixteen(regs16_0), MooRegisters::Sixteen(regs16_1)) => {
-                if regs16_0.ax != regs16_1.ax {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EAX,
-                        initial:  regs16_0.ax as u32,
-                        r#final:  regs16_1.ax as u32,
-                    });
-                }
-                if regs16_0.bx != regs16_1.bx {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EBX,
-                        initial:  regs16_0.bx as u32,
-                        r#final:  regs16_1.bx as u32,
-                    });
-                }
-                if regs16_0.cx != regs16_1.cx {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ECX,
-                        initial:  regs16_0.cx as u32,
-                        r#final:  regs16_1.cx as u32,
-                    });
-                }
-                if regs16_0.dx != regs16_1.dx {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EDX,
-                        initial:  regs16_0.dx as u32,
-                        r#final:  regs16_1.dx as u32,
-                    });
-                }
-                if regs16_0.si != regs16_1.si {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ESI,
-                        initial:  regs16_0.si as u32,
-                        r#final:  regs16_1.si as u32,
-                    });
-                }
-                if regs16_0.di != regs16_1.di {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EDI,
-                        initial:  regs16_0.di as u32,
-                        r#final:  regs16_1.di as u32,
-                    });
-                }
-                if regs16_0.bp != regs16_1.bp {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EBP,
-                        initial:  regs16_0.bp as u32,
-                        r#final:  regs16_1.bp as u32,
-                    });
-                }
-                if regs16_0.sp != regs16_1.sp {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ESP,
-                        initial:  regs16_0.sp as u32,
-                        r#final:  regs16_1.sp as u32,
-                    });
-                }
-                if regs16_0.ip != regs16_1.ip {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EIP,
-                        initial:  regs16_0.ip as u32,
-                        r#final:  regs16_1.ip as u32,
-                    });
-                }
-                if regs16_0.flags != regs16_1.flags {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EFLAGS,
-                        initial:  regs16_0.flags as u32,
-                        r#final:  regs16_1.flags as u32,
-                    });
-                }
-                if regs16_0.cs != regs16_1.cs {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::CS,
-                        initial:  regs16_0.cs as u32,
-                        r#final:  regs16_1.cs as u32,
-                    });
-                }
-                if regs16_0.ds != regs16_1.ds {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::DS,
-                        initial:  regs16_0.ds as u32,
-                        r#final:  regs16_1.ds as u32,
-                    });
-                }
-                if regs16_0.es != regs16_1.es {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ES,
-                        initial:  regs16_0.es as u32,
-                        r#final:  regs16_1.es as u32,
-                    });
-                }
-            }
-            (MooRegisters::ThirtyTwo(regs32_0), MooRegisters::ThirtyTwo(regs32_1)) => {
-                if let Some(cr0) = regs32_1.cr0() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::CR0,
-                        initial:  regs32_0.cr0,
-                        r#final:  cr0,
-                    });
-                }
-                if let Some(cr3) = regs32_1.cr3() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::CR3,
-                        initial:  regs32_0.cr3,
-                        r#final:  cr3,
-                    });
-                }
-                if let Some(eax) = regs32_1.eax() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EAX,
-                        initial:  regs32_0.eax,
-                        r#final:  eax,
-                    });
-                }
-                if let Some(ebx) = regs32_1.ebx() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EBX,
-                        initial:  regs32_0.ebx,
-                        r#final:  ebx,
-                    });
-                }
-                if let Some(ecx) = regs32_1.ecx() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ECX,
-                        initial:  regs32_0.ecx,
-                        r#final:  ecx,
-                    });
-                }
-                if let Some(edx) = regs32_1.edx() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EDX,
-                        initial:  regs32_0.edx,
-                        r#final:  edx,
-                    });
-                }
-                if let Some(esi) = regs32_1.esi() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ESI,
-                        initial:  regs32_0.esi,
-                        r#final:  esi,
-                    });
-                }
-                if let Some(edi) = regs32_1.edi() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EDI,
-                        initial:  regs32_0.edi,
-                        r#final:  edi,
-                    });
-                }
-                if let Some(ebp) = regs32_1.ebp() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EBP,
-                        initial:  regs32_0.ebp,
-                        r#final:  ebp,
-                    });
-                }
-                if let Some(esp) = regs32_1.esp() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ESP,
-                        initial:  regs32_0.esp,
-                        r#final:  esp,
-                    });
-                }
-                if let Some(eip) = regs32_1.eip() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EIP,
-                        initial:  regs32_0.eip,
-                        r#final:  eip,
-                    });
-                }
-                if let Some(eflags) = regs32_1.eflags() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::EFLAGS,
-                        initial:  regs32_0.eflags,
-                        r#final:  eflags,
-                    });
-                }
-                if let Some(cs) = regs32_1.cs() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::CS,
-                        initial:  regs32_0.cs,
-                        r#final:  cs as u32,
-                    });
-                }
-                if let Some(ds) = regs32_1.ds() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::DS,
-                        initial:  regs32_0.ds,
-                        r#final:  ds as u32,
-                    });
-                }
-                if let Some(es) = regs32_1.es() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::ES,
-                        initial:  regs32_0.es,
-                        r#final:  es as u32,
-                    });
-                }
-                if let Some(fs) = regs32_1.fs() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::FS,
-                        initial:  regs32_0.fs,
-                        r#final:  fs as u32,
-                    });
-                }
-                if let Some(gs) = regs32_1.gs() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::GS,
-                        initial:  regs32_0.gs,
-                        r#final:  gs as u32,
-                    });
-                }
-                if let Some(ss) = regs32_1.ss() {
-                    diff_regs.push(MooRegisterDiff {
-                        register: MooRegister::SS,
-                        initial:  regs32_0.ss,
-                        r#final:  ss as u32,
-                    });
-                }
-            }
-            _ => {
-                // Different types, cannot compare
-            }
+        if matches!(initial_regs, MooRegisters::ThirtyTwo(_))
+            != matches!(self.final_state.regs, MooRegisters::ThirtyTwo(_))
+        {
+            // Different types, cannot compare
+            return diff_regs;
         }
 
+        // The final state only holds the registers that may have changed.
+        self.final_state.regs.visit_set(|register, r#final| {
+            let initial = initial_regs.get(register).unwrap_or_default();
+            if initial != r#final || !initial_regs.is_set(register) {
+                diff_regs.push(MooRegisterDiff {
+                    register,
+                    initial,
+                    r#final,
+                });
+            }
+        });
         diff_regs
     }
 
@@ -577,11 +826,134 @@ impl MooTest {
         if self.initial_state.descriptors.is_none() {
             return MooCpuMode::RealMode;
         }
-        else {
-            // For 286, we need to look at the MSW register mode bit.
-            // For 386, we need to look at the CR0 bits and flag bits.
+        match &self.initial_state.regs {
+            MooRegisters::ThirtyTwo(regs) => {
+                if regs.cr0 & CR0_PE == 0 {
+                    MooCpuMode::RealMode
+                }
+                else if regs.eflags & MooRegisters32::FLAG_VM != 0 {
+                    MooCpuMode::Virtual8086Mode
+                }
+                else {
+                    MooCpuMode::ProtectedMode
+                }
+            }
+            // For 286, we need to look at the MSW register mode bit, which is not captured.
+            MooRegisters::Sixteen(_) => MooCpuMode::RealMode,
         }
-        MooCpuMode::RealMode
+    }
+
+    /// Decode the [MooOpcodeId] of the test instruction for the given [MooCpuFamily].
+    /// Returns None if the instruction bytes are truncated.
+    pub fn opcode_id(&self, cpu_family: impl Into<MooCpuFamily>) -> Option<MooOpcodeId> {
+        MooOpcodeId::from_instruction_bytes(&self.bytes, cpu_family.into())
+    }
+
+    /// Return the prefix and opcode bytes of the test instruction for the given [MooCpuFamily],
+    /// in encoded order. Returns None if the instruction bytes are truncated.
+    pub fn opcode_bytes(&self, cpu_family: impl Into<MooCpuFamily>) -> Option<Vec<u8>> {
+        MooOpcodeId::opcode_bytes(&self.bytes, cpu_family.into())
+    }
+
+    /// Decode the prefixes at the start of the instruction bytes for the given [MooCpuFamily],
+    /// in the order they appear.
+    pub fn prefixes(&self, cpu_family: impl Into<MooCpuFamily>) -> Vec<MooPrefix> {
+        MooPrefix::decode(&self.bytes, cpu_family.into())
+    }
+
+    /// Returns true if the instruction carries the given [MooPrefix] for the given [MooCpuFamily].
+    pub fn has_prefix(&self, prefix: MooPrefix, cpu_family: impl Into<MooCpuFamily>) -> bool {
+        self.prefixes(cpu_family).contains(&prefix)
+    }
+
+    /// Returns true if the instruction bytes begin with a LOCK (`F0`) prefix, possibly among other
+    /// prefixes valid for the given [MooCpuFamily].
+    pub fn has_lock_prefix(&self, cpu_family: impl Into<MooCpuFamily>) -> bool {
+        self.has_prefix(MooPrefix::Lock, cpu_family)
+    }
+
+    /// Returns true if the CPU should lock the bus while executing the test instruction, either
+    /// because of a LOCK prefix or because the instruction is `XCHG` with a memory operand, which
+    /// is locked implicitly.
+    pub fn is_locked(&self, cpu_family: impl Into<MooCpuFamily>) -> bool {
+        let cpu_family = cpu_family.into();
+        let mut bytes = self.bytes.iter().skip_while(|&&b| is_prefix(b, cpu_family));
+        let implicit = match (bytes.next(), bytes.next()) {
+            (Some(0x86 | 0x87), Some(modrm)) => modrm >> 6 != 0b11,
+            _ => false,
+        };
+        implicit || self.has_lock_prefix(cpu_family)
+    }
+
+    /// Collect evidence that this real-mode test crosses the 1MB address boundary, decoding bus
+    /// states for the given [MooCpuType]. Returns an empty vector for tests in other CPU modes.
+    ///
+    /// Whether such accesses wrap to low memory or reach the high memory area depends on the
+    /// file's [MooA20Policy](crate::types::MooA20Policy).
+    pub fn address_wraps(&self, cpu_type: MooCpuType) -> Vec<MooAddressWrap> {
+        const ONE_MB: u32 = 0x10_0000;
+        let mut wraps = Vec::new();
+
+        if !matches!(self.cpu_mode(cpu_type), MooCpuMode::RealMode) {
+            return wraps;
+        }
+
+        if let Some(csip) = self.initial_state.regs.csip_linear_real() {
+            let last_byte = csip.wrapping_add(self.bytes.len().saturating_sub(1) as u32);
+            if last_byte >= ONE_MB {
+                wraps.push(MooAddressWrap::InstructionFetch { linear_address: csip });
+            }
+        }
+
+        if let Some(ea) = &self.initial_state.ea {
+            if ea.linear_address >= ONE_MB {
+                wraps.push(MooAddressWrap::EffectiveAddress {
+                    linear_address: ea.linear_address,
+                });
+            }
+        }
+
+        for transaction in self.cycles_iter().transactions() {
+            if transaction.bus_state(cpu_type).is_memory() && transaction.address >= ONE_MB {
+                wraps.push(MooAddressWrap::HighMemoryAccess {
+                    cycle:   transaction.start,
+                    address: transaction.address,
+                });
+            }
+        }
+        wraps
+    }
+
+    /// Check the initial state's effective address against the memory bus transactions of this
+    /// test, decoding bus states for the given [MooCpuType].
+    ///
+    /// Returns `None` if the test has no effective address or performs no memory reads or writes,
+    /// otherwise returns true if any memory read or write addressed the effective address's
+    /// physical address.
+    pub fn ea_matches_bus(&self, cpu_type: MooCpuType) -> Option<bool> {
+        let ea = self.initial_state.ea.as_ref()?;
+        let mut seen_data = false;
+        for transaction in self.cycles_iter().transactions() {
+            if matches!(transaction.bus_state(cpu_type), MooBusState::MEMR | MooBusState::MEMW) {
+                if ea.matches_bus_address(cpu_type, transaction.address) {
+                    return Some(true);
+                }
+                seen_data = true;
+            }
+        }
+        seen_data.then_some(false)
+    }
+
+    /// Scan the cycles of this test for physically impossible sequences that indicate a capture
+    /// glitch, decoding bus states for the given [MooCpuType]. See [detect_glitches].
+    pub fn detect_glitches(&self, cpu_type: MooCpuType) -> Vec<MooGlitch> {
+        detect_glitches(cpu_type, self.cycles_iter())
+    }
+
+    /// Returns true if this real-mode test crosses the 1MB address boundary.
+    /// See [MooTest::address_wraps].
+    pub fn exercises_wraparound(&self, cpu_type: MooCpuType) -> bool {
+        !self.address_wraps(cpu_type).is_empty()
     }
 
     /// Determine the native segment size of the test instruction.
@@ -592,13 +964,11 @@ impl MooTest {
         match self.cpu_mode(cpu_family) {
             MooCpuMode::RealMode => MooSegmentSize::Sixteen,
             MooCpuMode::ProtectedMode => {
-                // In protected mode, segment size is determined by the descriptor.
-                if let Some(_descriptors) = &self.initial_state.descriptors {
-                    // if let Some(cs_descriptor) = descriptors.get(&MooRegister::CS) {
-                    //     return cs_descriptor.size;
-                    // }
+                // In protected mode, segment size is determined by the CS descriptor.
+                match &self.initial_state.descriptors {
+                    Some(descriptors) => descriptors.code_segment_size(),
+                    None => MooSegmentSize::Sixteen, // Default to 16 if unknown
                 }
-                MooSegmentSize::Sixteen // Default to 16 if unknown
             }
             _ => MooSegmentSize::Sixteen, // Default to 16 for other modes
         }
@@ -625,16 +995,8 @@ impl MooTest {
     /// * `cpu_family` - The CPU family to consider when determining operand size. Only the 386
     ///     family supports operand size overrides.
     pub fn has_operand_size_override(&self, cpu_family: impl Into<MooCpuFamily>) -> bool {
-        let cpu_family = cpu_family.into();
-        match cpu_family {
-            MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186 | MooCpuFamily::Intel80286 => {
-                false
-            }
-            MooCpuFamily::Intel80386 => {
-                // In 386 mode, check if the operand size override prefix (0x66) is present in the instruction bytes.
-                self.bytes.contains(&0x66)
-            }
-        }
+        // Only the 386 decodes 0x66 as a prefix.
+        self.has_prefix(MooPrefix::OperandSize, cpu_family)
     }
 
     /// Determine if the test instruction has an address size override prefix.
@@ -642,16 +1004,8 @@ impl MooTest {
     /// * `cpu_family` - The CPU family to consider when determining address size. Only the 386
     ///    family supports address size overrides.
     pub fn has_address_size_override(&self, cpu_family: impl Into<MooCpuFamily>) -> bool {
-        let cpu_family = cpu_family.into();
-        match cpu_family {
-            MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186 | MooCpuFamily::Intel80286 => {
-                false
-            }
-            MooCpuFamily::Intel80386 => {
-                // In 386 mode, check if the address size override prefix (0x67) is present in the instruction bytes.
-                self.bytes.contains(&0x67)
-            }
-        }
+        // Only the 386 decodes 0x67 as a prefix.
+        self.has_prefix(MooPrefix::AddressSize, cpu_family)
     }
 
     /// Write a [MooTest] to an implementor of [Write] + [Seek].
@@ -659,17 +1013,120 @@ impl MooTest {
     /// * `index` - The index of the test.
     /// * `writer` - The writer to write the MOO file to.
     /// * `preserve_hash` - If true, preserves the existing test hash, if present. If false, the
-    ///      test hash will be recalculated from the test data. The test hash will be recalculated if
-    ///      missing, regardless of this flag.
+    ///   test hash will be recalculated from the test data. The test hash will be recalculated if
+    ///   missing or dirty (see [MooTest::is_hash_dirty]), regardless of this flag.
+    ///
+    /// An alternate digest present on the test is recalculated and written with the same
+    /// algorithm.
     pub fn write<WS: Write + Seek>(&self, index: usize, writer: &mut WS, preserve_hash: bool) -> BinResult<()> {
+        self.write_with_digest(index, writer, preserve_hash, None)
+    }
+
+    /// Write a [MooTest] as [MooTest::write] does, additionally writing a `DGST` chunk with the
+    /// specified alternate digest algorithm. If `algorithm` is `None`, the algorithm of any
+    /// existing alternate digest is used.
+    pub fn write_with_digest<WS: Write + Seek>(
+        &self,
+        index: usize,
+        writer: &mut WS,
+        preserve_hash: bool,
+        algorithm: Option<MooHashAlgorithm>,
+    ) -> BinResult<()> {
+        if self.synthetic {
+            return Err(binrw::Error::Custom {
+                pos: writer.stream_position()?,
+                err: Box::new(MooError::WriteError(format!(
+                    "test {} is synthetic and cannot be written",
+                    self.name
+                ))),
+            });
+        }
+
         let mut test_buffer = Cursor::new(Vec::new());
+        self.write_hashed(index, &mut test_buffer, true)?;
+
+        // Vendor chunks nested in the states are written above but not hashed, so if there are
+        // any, hash the test as written without them.
+        let has_state_vendor_chunks =
+            !self.initial_state.vendor_chunks.is_empty() || !self.final_state.vendor_chunks.is_empty();
+        let vendor_free_data = match has_state_vendor_chunks {
+            true => Some(self.hash_data(index)?),
+            false => None,
+        };
+        let hash_data = vendor_free_data.as_deref().unwrap_or(test_buffer.get_ref());
+
+        // The alternate digest covers the same data as the SHA-1 hash, so compute it first.
+        let digest = algorithm
+            .or(self.digest.as_ref().map(|d| d.algorithm))
+            .map(|algorithm| MooTestDigest::compute(algorithm, hash_data));
 
+        let hash = match &self.hash {
+            // Keep the existing hash.
+            Some(hash) if preserve_hash && !self.hash_dirty => hash.to_vec(),
+            // Create the SHA1 hash from the hashed chunks.
+            _ => sha1::Sha1::digest(hash_data).to_vec(),
+        };
+        MooChunkType::Hash.write(&mut test_buffer, &hash)?;
+
+        if let Some(digest) = &digest {
+            MooChunkType::Digest.write(&mut test_buffer, &MooDigestChunk::from(digest))?;
+        }
+
+        // Write the tags chunk, if any tags are present. This is written after the hash so that
+        // tags do not contribute to the test's identity.
+        if !self.tags.is_empty() {
+            let tags_chunk = MooTagsChunk {
+                tag_ct: self.tags.len() as u32,
+                tags:   self
+                    .tags
+                    .iter()
+                    .map(|tag| MooNameChunk {
+                        len:  tag.len() as u32,
+                        name: tag.clone(),
+                    })
+                    .collect(),
+            };
+            MooChunkType::Tags.write(&mut test_buffer, &tags_chunk)?;
+        }
+
+        // Write the anchors chunk, if any anchors are set. Like tags, anchors are not hashed.
+        if !self.anchors.is_empty() {
+            MooChunkType::Anchors.write(&mut test_buffer, &MooAnchorsChunk::from(self.anchors.as_slice()))?;
+        }
+
+        // Write the IO stimulus chunk, if any port values are set. This is not hashed either.
+        if !self.io_stimulus.is_empty() {
+            MooChunkType::IoStimulus.write(&mut test_buffer, &MooIoStimulusChunk::from(self.io_stimulus.as_slice()))?;
+        }
+
+        // Write any vendor chunks. Like tags, these are not included in the test hash.
+        for chunk in &self.vendor_chunks {
+            MooChunkType::Vendor(chunk.id).write(&mut test_buffer, &chunk.data)?;
+        }
+
+        // Write the test chunk.
+        MooChunkType::TestHeader.write(writer, &test_buffer.into_inner())?;
+
+        Ok(())
+    }
+
+    /// Return the data covered by the test hash: the chunks written by [MooTest::write_hashed],
+    /// without any vendor chunks nested in the initial and final states.
+    fn hash_data(&self, index: usize) -> BinResult<Vec<u8>> {
+        let mut test_buffer = Cursor::new(Vec::new());
+        self.write_hashed(index, &mut test_buffer, false)?;
+        Ok(test_buffer.into_inner())
+    }
+
+    /// Write the chunks of the test that precede its hash. With `vendor_chunks` false, vendor
+    /// chunks nested in the initial and final states are left out.
+    fn write_hashed<WS: Write + Seek>(&self, index: usize, test_buffer: &mut WS, vendor_chunks: bool) -> BinResult<()> {
         // Write the test chunk body.
-        MooTestChunk { index: index as u32 }.write(&mut test_buffer)?;
+        MooTestChunk { index: index as u32 }.write(test_buffer)?;
 
         // Write the generator metadata chunk if present.
         if let Some(gen_metadata) = &self.gen_metadata {
-            MooChunkType::GeneratorMetadata.write(&mut test_buffer, gen_metadata)?;
+            MooChunkType::GeneratorMetadata.write(test_buffer, gen_metadata)?;
         }
 
         // Write the name chunk.
@@ -677,50 +1134,45 @@ impl MooTest {
             len:  self.name.len() as u32,
             name: self.name.clone(),
         };
-        MooChunkType::Name.write(&mut test_buffer, &name_chunk)?;
+        MooChunkType::Name.write(test_buffer, &name_chunk)?;
 
         // Write the bytes chunk.
         let bytes_chunk = MooBytesChunk {
             len:   self.bytes.len() as u32,
             bytes: self.bytes.clone(),
         };
-        MooChunkType::Bytes.write(&mut test_buffer, &bytes_chunk)?;
+        MooChunkType::Bytes.write(test_buffer, &bytes_chunk)?;
 
         // Write the initial state chunk.
-        self.initial_state.write(&mut test_buffer)?;
+        self.initial_state.write_chunk(test_buffer, vendor_chunks)?;
 
         // Write the final state chunk.
-        self.final_state.write(&mut test_buffer)?;
+        self.final_state.write_chunk(test_buffer, vendor_chunks)?;
 
         let mut cycle_buffer = Cursor::new(Vec::new());
         // Write the count of cycles to the cycle buffer.
         (self.cycles.len() as u32).write_le(&mut cycle_buffer)?;
         // Write all the cycles to the cycle buffer.
-        for cycle in &self.cycles {
+        for cycle in self.cycles.iter() {
             cycle.write(&mut cycle_buffer)?;
         }
 
         // Write the cycles chunk.
-        MooChunkType::CycleStates.write(&mut test_buffer, &cycle_buffer.into_inner())?;
+        MooChunkType::CycleStates.write(test_buffer, &cycle_buffer.into_inner())?;
 
         // If an exception is present, write the exception chunk.
         if let Some(exception) = &self.exception {
-            MooChunkType::Exception.write(&mut test_buffer, exception)?;
-        }
-
-        if preserve_hash && self.hash.is_some() {
-            // Write the existing hash chunk.
-            MooChunkType::Hash.write(&mut test_buffer, self.hash.as_ref().unwrap())?;
-        }
-        else {
-            // Create the SHA1 hash from the current state of the test buffer.
-            let hash = sha1::Sha1::digest(&test_buffer.get_ref()).to_vec();
-            MooChunkType::Hash.write(&mut test_buffer, &hash)?;
+            MooChunkType::Exception.write(test_buffer, exception)?;
         }
 
-        // Write the test chunk.
-        MooChunkType::TestHeader.write(writer, &test_buffer.into_inner())?;
-
         Ok(())
     }
 }
+
+/// The protection enable bit of CR0.
+const CR0_PE: u32 = 0x0000_0001;
+
+/// Returns true if `byte` is an instruction prefix for the given [MooCpuFamily].
+pub(crate) fn is_prefix(byte: u8, cpu_family: MooCpuFamily) -> bool {
+    MooPrefix::from_byte(byte, cpu_family).is_some()
+}
@@ -25,7 +25,15 @@ use std::io::{Cursor, Seek, Write};
 
 use crate::{
     registers::*,
-    types::{chunks::MooChunkType, effective_address::MooEffectiveAddress, MooRamEntries, MooRamEntry, MooStateType},
+    types::{
+        chunks::{MooBytesChunk, MooChunkType},
+        effective_address::MooEffectiveAddress,
+        ivt::MooIvt,
+        vendor::MooVendorChunk,
+        MooRamEntries,
+        MooRamEntry,
+        MooStateType,
+    },
 };
 
 use binrw::BinResult;
@@ -33,6 +41,12 @@ use binrw::BinResult;
 /// A [MooTestState] represents a CPU state snapshot, either the initial state of the CPU before
 /// test execution, or the final state of the CPU after test execution. The `s_type` field indicates
 /// whether the state is initial or final, via the [MooStateType] enum.
+///
+/// The registers of a final state are normally sparse: only registers changed by the test are
+/// present in the register mask, and the remaining fields read as zero. Use
+/// [MooRegisters::present_registers] to see which registers are present, or
+/// [MooTest::full_final_state](crate::prelude::MooTest::full_final_state) to obtain a final state
+/// with a complete register set.
 #[derive(Clone, Default)]
 pub struct MooTestState {
     /// The type of state (initial or final).
@@ -47,6 +61,8 @@ pub struct MooTestState {
     pub queue: Vec<u8>,
     /// The RAM contents for this state.
     pub ram: Vec<MooRamEntry>,
+    /// Any vendor chunks present in this state, preserved verbatim.
+    pub vendor_chunks: Vec<MooVendorChunk>,
 }
 
 impl MooTestState {
@@ -74,6 +90,7 @@ impl MooTestState {
             ea,
             queue,
             ram,
+            vendor_chunks: Vec::new(),
         }
     }
 
@@ -82,6 +99,16 @@ impl MooTestState {
         &self.regs
     }
 
+    /// Returns true if the register mask of this state omits any registers, as is usual for a final
+    /// state. Registers outside the mask do not hold real values.
+    pub fn is_sparse(&self) -> bool {
+        let count = match self.regs {
+            MooRegisters::Sixteen(_) => MooRegisters::REGISTERS16.len(),
+            MooRegisters::ThirtyTwo(_) => MooRegisters::REGISTERS32.len(),
+        };
+        self.regs.present_registers().len() < count
+    }
+
     /// Return a mutable reference to the [MooRegisters] for this state.
     pub fn regs_mut(&mut self) -> &mut MooRegisters {
         &mut self.regs
@@ -102,9 +129,36 @@ impl MooTestState {
         self.ea.as_ref()
     }
 
+    /// Return the linear address of `segment:offset` in this state, using the state's segment
+    /// descriptors if present. See [MooRegisters::linear_address].
+    pub fn linear_address(&self, segment: MooSegmentRegister, offset: u32) -> Option<u32> {
+        self.regs.linear_address(segment, offset, self.descriptors.as_ref())
+    }
+
+    /// Return the linear address of `CS:IP` in this state, in real or protected mode.
+    pub fn csip_linear(&self) -> Option<u32> {
+        self.regs.csip_linear(self.descriptors.as_ref())
+    }
+
+    /// Return the linear address of `SS:SP` in this state, in real or protected mode.
+    pub fn sp_linear(&self) -> Option<u32> {
+        self.regs.sp_linear(self.descriptors.as_ref())
+    }
+
+    /// Return the real-mode interrupt vector table entries present in this state's RAM.
+    pub fn ivt(&self) -> MooIvt {
+        MooIvt::from_ram(&self.ram)
+    }
+
     /// Write this [MooTestState] to the given implementor of [Write] + [Seek] as a `MOO` `INIT` or
     /// `FINA` chunk, depending on the state's [MooStateType].
     pub fn write<WS: Write + Seek>(&self, writer: &mut WS) -> BinResult<()> {
+        self.write_chunk(writer, true)
+    }
+
+    /// Write this [MooTestState] as an `INIT` or `FINA` chunk, leaving out its vendor chunks
+    /// unless `vendor_chunks` is set. Vendor chunks are not covered by the test hash.
+    pub(crate) fn write_chunk<WS: Write + Seek>(&self, writer: &mut WS, vendor_chunks: bool) -> BinResult<()> {
         // Create a buffer to write our state data into, so we can write it to the final
         // chunk in one go.
         let mut state_buffer = Cursor::new(Vec::new());
@@ -113,9 +167,13 @@ impl MooTestState {
         let chunk_type = MooChunkType::from(&self.regs);
         chunk_type.write(&mut state_buffer, &self.regs)?;
 
-        // Write the initial queue, if not empty.
+        // Write the queue, if not empty.
         if !self.queue.is_empty() {
-            MooChunkType::QueueState.write(&mut state_buffer, &self.queue)?;
+            let queue_chunk = MooBytesChunk {
+                len:   self.queue.len() as u32,
+                bytes: self.queue.clone(),
+            };
+            MooChunkType::QueueState.write(&mut state_buffer, &queue_chunk)?;
         }
 
         // Write the EA chunk, if present.
@@ -123,6 +181,11 @@ impl MooTestState {
             MooChunkType::EffectiveAddress32.write(&mut state_buffer, ea)?;
         }
 
+        // Write the descriptor chunk, if present. Only 386 descriptor caches have a chunk type.
+        if let Some(MooDescriptors::ThirtyTwo(descriptors)) = &self.descriptors {
+            MooChunkType::Descriptors32.write(&mut state_buffer, descriptors)?;
+        }
+
         // Write the RAM chunk.
         MooChunkType::Ram.write(
             &mut state_buffer,
@@ -132,6 +195,13 @@ impl MooTestState {
             },
         )?;
 
+        // Write any vendor chunks.
+        if vendor_chunks {
+            for chunk in &self.vendor_chunks {
+                MooChunkType::Vendor(chunk.id).write(&mut state_buffer, &chunk.data)?;
+            }
+        }
+
         match self.s_type {
             MooStateType::Initial => {
                 // Write the initial state chunk.
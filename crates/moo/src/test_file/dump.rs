@@ -0,0 +1,872 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A deterministic, versioned plain-text rendering of a [MooTestFile] and its parser.
+//!
+//! A text dump lists every field of the file in a fixed order, one item per line, so that dumps
+//! of a corpus can be committed to version control as golden files and reviewed as ordinary
+//! diffs. [MooTestFile::parse_text] reads a dump back into a [MooTestFile]. A dump made with the
+//! default [MooDumpOptions] round-trips losslessly.
+//!
+//! Integer fields are written in uppercase hexadecimal without a prefix, except for counts and
+//! test indices, which are decimal. Strings are double-quoted, with `"`, `\` and any byte outside
+//! printable ASCII written as escapes (`\"`, `\\`, `\xNN`). Blank lines and lines beginning with
+//! `#` are ignored by the parser.
+
+use std::{
+    fmt::Debug,
+    io::{self, BufRead, Cursor, Write},
+};
+
+use binrw::BinRead;
+
+use crate::{
+    registers::{
+        descriptors_32::{MooDescriptor32, MooDescriptors32},
+        MooDescriptors,
+        MooRegisters,
+        MooSegmentRegister,
+    },
+    test::{moo_test::MooTest, test_state::MooTestState},
+    test_file::MooTestFile,
+    types::{
+        anchor::MooAnchorKind,
+        arch_tag::MooArchTag,
+        effective_address::MooEffectiveAddress,
+        errors::MooError,
+        hash::{MooHashAlgorithm, MooTestDigest},
+        io_stimulus::MooPortWidth,
+        license::MooLicense,
+        vendor::MooVendorChunk,
+        MooByteString,
+        MooCaptureInfo,
+        MooCaptureSource,
+        MooCycleState,
+        MooException,
+        MooFileMetadata,
+        MooRamEntry,
+        MooStateType,
+        MooTestGenMetadata,
+        MooTimingContext,
+    },
+    MOO_MAJOR_VERSION,
+    MOO_MINOR_VERSION,
+};
+
+/// The version of the text dump format produced by [MooTestFile::dump_text].
+pub const MOO_TEXT_VERSION: u32 = 1;
+const MOO_TEXT_MAGIC: &str = "MOO-TEXT";
+
+/// [MooDumpOptions] controls what [MooTestFile::dump_text] includes in a text dump.
+#[derive(Clone, Debug)]
+pub struct MooDumpOptions {
+    /// Whether to include each test's cycles. Omitting cycles keeps dumps small, at the cost of a
+    /// lossy round trip.
+    pub cycles: bool,
+    /// Whether to include each test's hash. Without hashes, tests parsed from the dump will be
+    /// re-hashed when written.
+    pub hashes: bool,
+}
+
+impl Default for MooDumpOptions {
+    fn default() -> Self {
+        Self {
+            cycles: true,
+            hashes: true,
+        }
+    }
+}
+
+impl MooDumpOptions {
+    /// Create a new [MooDumpOptions] with default settings, which include all fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to set whether test cycles are included.
+    pub fn with_cycles(mut self, cycles: bool) -> Self {
+        self.cycles = cycles;
+        self
+    }
+
+    /// Builder-style method to set whether test hashes are included.
+    pub fn with_hashes(mut self, hashes: bool) -> Self {
+        self.hashes = hashes;
+        self
+    }
+}
+
+/// Text dump methods
+impl MooTestFile {
+    /// Write a deterministic plain-text rendering of this file to `writer`.
+    /// See the [module documentation](self) for a description of the format.
+    pub fn dump_text<W: Write>(&self, writer: &mut W, options: &MooDumpOptions) -> io::Result<()> {
+        writeln!(writer, "{} {}", MOO_TEXT_MAGIC, MOO_TEXT_VERSION)?;
+        writeln!(writer, "version {} {}", self.major_version, self.minor_version)?;
+        writeln!(writer, "arch {}", quote(self.arch.as_bytes()))?;
+        writeln!(writer, "chunk_crc {}", self.chunk_crc as u8)?;
+        writeln!(writer, "end_chunk {}", self.end_chunk as u8)?;
+
+        if let Some(metadata) = &self.metadata {
+            dump_metadata(writer, metadata)?;
+        }
+        if let Some(register_mask) = &self.register_mask {
+            writeln!(writer, "register_mask {}", regs_to_text(register_mask))?;
+        }
+        if let Some(license) = &self.license {
+            writeln!(writer, "license {}", quote(license.identifier.as_bytes()))?;
+            for contributor in &license.contributors {
+                writeln!(writer, "contributor {}", quote(contributor.as_bytes()))?;
+            }
+        }
+        for chunk in &self.vendor_chunks {
+            writeln!(writer, "vendor {}", vendor_to_text(chunk))?;
+        }
+
+        for (i, test) in self.tests.iter().enumerate() {
+            writeln!(writer)?;
+            writeln!(writer, "test {}", i)?;
+            dump_test(writer, test, options)?;
+            writeln!(writer, "end")?;
+        }
+        Ok(())
+    }
+
+    /// Parse a text dump produced by [MooTestFile::dump_text] back into a [MooTestFile].
+    pub fn parse_text<R: BufRead>(reader: &mut R) -> Result<MooTestFile, MooError> {
+        let mut parser = TextParser {
+            lines:   reader.lines(),
+            line_no: 0,
+        };
+
+        let (line, tokens) = parser.expect_line()?;
+        match tokens.as_slice() {
+            [magic, version] if magic == MOO_TEXT_MAGIC => {
+                let version: u32 = dec(line, version)?;
+                if version > MOO_TEXT_VERSION {
+                    return Err(parse_err(line, format!("unsupported text dump version {}", version)));
+                }
+            }
+            _ => return Err(parse_err(line, "missing MOO-TEXT header")),
+        }
+
+        let (line, tokens) = parser.expect_keyword("version")?;
+        let major_version: u8 = dec(line, arg(line, &tokens, 1)?)?;
+        let minor_version: u8 = dec(line, arg(line, &tokens, 2)?)?;
+        if (major_version, minor_version) > (MOO_MAJOR_VERSION, MOO_MINOR_VERSION) {
+            return Err(parse_err(
+                line,
+                format!("unsupported MOO version {}.{}", major_version, minor_version),
+            ));
+        }
+
+        let (line, tokens) = parser.expect_keyword("arch")?;
+        let arch = utf8(line, unquote(line, arg(line, &tokens, 1)?)?)?;
+        let arch = MooArchTag::new(&arch).map_err(|e| parse_err(line, e))?;
+        let cpu_type = arch.cpu_type().map_err(|e| parse_err(line, e))?;
+
+        let mut file = MooTestFile::new(major_version, minor_version, cpu_type, 0);
+        file.arch = arch;
+        // Dumps that predate the `end_chunk` line describe files without an END chunk.
+        file.end_chunk = false;
+
+        while let Some((line, tokens)) = parser.next_line()? {
+            match tokens[0].as_str() {
+                "chunk_crc" => file.chunk_crc = dec::<u8>(line, arg(line, &tokens, 1)?)? != 0,
+                "end_chunk" => file.end_chunk = dec::<u8>(line, arg(line, &tokens, 1)?)? != 0,
+                "metadata" => file.metadata = Some(parse_metadata(&mut parser)?),
+                "register_mask" => file.register_mask = Some(regs_from_text(line, &tokens[1..])?),
+                "license" => {
+                    let identifier = utf8(line, unquote(line, arg(line, &tokens, 1)?)?)?;
+                    file.license = Some(MooLicense::new(&identifier));
+                }
+                "contributor" => {
+                    let contributor = utf8(line, unquote(line, arg(line, &tokens, 1)?)?)?;
+                    match file.license.as_mut() {
+                        Some(license) => license.contributors.push(contributor),
+                        None => return Err(parse_err(line, "contributor without a preceding license")),
+                    }
+                }
+                "vendor" => file.vendor_chunks.push(vendor_from_text(line, &tokens[1..])?),
+                "test" => {
+                    let test = parse_test(&mut parser)?;
+                    if let Some(hash) = &test.hash {
+                        let hash_str = crate::types::hash::hex_string(hash);
+                        file.hashes.entry(hash_str).or_insert(file.tests.len());
+                    }
+                    file.tests.push(test);
+                }
+                other => return Err(parse_err(line, format!("unexpected '{}'", other))),
+            }
+        }
+        Ok(file)
+    }
+}
+
+fn dump_metadata<W: Write>(writer: &mut W, metadata: &MooFileMetadata) -> io::Result<()> {
+    writeln!(writer, "metadata")?;
+    writeln!(
+        writer,
+        "  set_version {} {}",
+        metadata.set_version_major, metadata.set_version_minor
+    )?;
+    writeln!(writer, "  cpu_type {:?}", metadata.cpu_type)?;
+    writeln!(writer, "  opcode {:08X}", metadata.opcode)?;
+    writeln!(writer, "  mnemonic {}", quote(&metadata.mnemonic))?;
+    writeln!(writer, "  test_ct {}", metadata.test_ct)?;
+    writeln!(writer, "  file_seed {:016X}", metadata.file_seed)?;
+    writeln!(writer, "  cpu_mode {:?}", metadata.cpu_mode)?;
+    writeln!(writer, "  extension {:02X}", metadata.extension)?;
+    writeln!(writer, "  reserved {}", hex_bytes(&metadata.reserved))?;
+    if let Some(timing) = &metadata.timing {
+        writeln!(
+            writer,
+            "  timing {} {} {}",
+            timing.clock_hz,
+            timing.refresh_period,
+            quote(&timing.board_id)
+        )?;
+    }
+    if let Some(capture) = &metadata.capture {
+        // Sources unknown to this version are written as their raw value.
+        match capture.source() {
+            Some(source) => writeln!(writer, "  capture {} {}", source, quote(&capture.tool))?,
+            None => writeln!(writer, "  capture {} {}", capture.source, quote(&capture.tool))?,
+        }
+    }
+    if let Some(ext) = &metadata.mnemonic_ext {
+        writeln!(writer, "  mnemonic_ext {}", quote(&ext.bytes))?;
+    }
+    if let Some(opcode_bytes) = metadata.opcode_bytes() {
+        writeln!(writer, "  opcode_bytes {}", hex_bytes(opcode_bytes))?;
+    }
+    writeln!(writer, "end")
+}
+
+fn dump_test<W: Write>(writer: &mut W, test: &MooTest, options: &MooDumpOptions) -> io::Result<()> {
+    writeln!(writer, "  name {}", quote(test.name.as_bytes()))?;
+    if let Some(gen_metadata) = &test.gen_metadata {
+        writeln!(
+            writer,
+            "  gen seed={:016X} gen_ct={}",
+            gen_metadata.seed, gen_metadata.gen_ct
+        )?;
+    }
+    writeln!(writer, "  bytes {}", hex_bytes(&test.bytes))?;
+    dump_state(writer, "initial", &test.initial_state)?;
+    dump_state(writer, "final", &test.final_state)?;
+
+    if options.cycles {
+        writeln!(writer, "  cycles {}", test.cycles.len())?;
+        writeln!(writer, "    # pins0 address  seg mem io pins1 data bus t q qbyte")?;
+        for c in test.cycles.iter() {
+            writeln!(
+                writer,
+                "    {:02X} {:08X} {:02X} {:02X} {:02X} {:02X} {:04X} {:02X} {:02X} {:02X} {:02X}",
+                c.pins0,
+                c.address_bus,
+                c.segment,
+                c.memory_status,
+                c.io_status,
+                c.pins1,
+                c.data_bus,
+                c.bus_state,
+                c.t_state,
+                c.queue_op,
+                c.queue_byte
+            )?;
+        }
+    }
+
+    if let Some(exception) = &test.exception {
+        writeln!(
+            writer,
+            "  exception num={:02X} flag_address={:08X}",
+            exception.exception_num, exception.flag_address
+        )?;
+    }
+    if options.hashes && test.hash.is_some() {
+        writeln!(writer, "  hash {}", test.hash_string())?;
+    }
+    if let Some(digest) = test.digest.as_ref().filter(|_| options.hashes) {
+        writeln!(writer, "  digest {} {}", digest.algorithm, digest.digest_string())?;
+    }
+    for tag in &test.tags {
+        writeln!(writer, "  tag {}", quote(tag.as_bytes()))?;
+    }
+    for anchor in &test.anchors {
+        writeln!(writer, "  anchor {} {}", anchor.kind, anchor.cycle)?;
+    }
+    for stimulus in &test.io_stimulus {
+        write!(writer, "  io port={:04X} width={}", stimulus.port, stimulus.width)?;
+        for value in &stimulus.values {
+            write!(writer, " {:X}", value)?;
+        }
+        writeln!(writer)?;
+    }
+    for chunk in &test.vendor_chunks {
+        writeln!(writer, "  vendor {}", vendor_to_text(chunk))?;
+    }
+    Ok(())
+}
+
+fn dump_state<W: Write>(writer: &mut W, keyword: &str, state: &MooTestState) -> io::Result<()> {
+    writeln!(writer, "  {}", keyword)?;
+    writeln!(writer, "    {}", regs_to_text(&state.regs))?;
+    if let Some(ea) = &state.ea {
+        writeln!(
+            writer,
+            "    ea seg={:?} selector={:04X} base={:08X} limit={:08X} offset={:08X} linear={:08X} physical={:08X}",
+            ea.base_segment,
+            ea.base_selector,
+            ea.base_address,
+            ea.base_limit,
+            ea.offset,
+            ea.linear_address,
+            ea.physical_address
+        )?;
+    }
+    if let Some(MooDescriptors::ThirtyTwo(descriptors)) = &state.descriptors {
+        use MooSegmentRegister::*;
+        for segment in [CS, SS, DS, ES, FS, GS] {
+            let d = descriptors.get(segment);
+            writeln!(
+                writer,
+                "    descriptor {:?} access={:08X} base={:08X} limit={:08X}",
+                segment, d.access, d.base, d.limit
+            )?;
+        }
+    }
+    if !state.queue.is_empty() {
+        writeln!(writer, "    queue {}", hex_bytes(&state.queue))?;
+    }
+    for entry in &state.ram {
+        writeln!(writer, "    ram {:08X} {:02X}", entry.address, entry.value)?;
+    }
+    for chunk in &state.vendor_chunks {
+        writeln!(writer, "    vendor {}", vendor_to_text(chunk))?;
+    }
+    writeln!(writer, "  end")
+}
+
+fn parse_metadata<R: BufRead>(parser: &mut TextParser<R>) -> Result<MooFileMetadata, MooError> {
+    let mut metadata = MooFileMetadata::default();
+    loop {
+        let (line, tokens) = parser.expect_line()?;
+        match tokens[0].as_str() {
+            "set_version" => {
+                metadata.set_version_major = dec(line, arg(line, &tokens, 1)?)?;
+                metadata.set_version_minor = dec(line, arg(line, &tokens, 2)?)?;
+            }
+            "cpu_type" => metadata.cpu_type = enum_from_name(line, arg(line, &tokens, 1)?)?,
+            "opcode" => metadata.opcode = hex(line, arg(line, &tokens, 1)?)?,
+            "mnemonic" => {
+                let mnemonic = unquote(line, arg(line, &tokens, 1)?)?;
+                metadata.mnemonic = mnemonic
+                    .try_into()
+                    .map_err(|_| parse_err(line, "mnemonic must be exactly 8 bytes"))?;
+            }
+            "test_ct" => metadata.test_ct = dec(line, arg(line, &tokens, 1)?)?,
+            "file_seed" => metadata.file_seed = hex(line, arg(line, &tokens, 1)?)?,
+            "cpu_mode" => metadata.cpu_mode = enum_from_name(line, arg(line, &tokens, 1)?)?,
+            "extension" => metadata.extension = hex(line, arg(line, &tokens, 1)?)?,
+            "reserved" => {
+                metadata.reserved = hex_byte_tokens(line, &tokens[1..])?
+                    .try_into()
+                    .map_err(|_| parse_err(line, "reserved must be exactly 2 bytes"))?;
+            }
+            "timing" => {
+                let board_id = unquote(line, arg(line, &tokens, 3)?)?;
+                metadata.timing = Some(MooTimingContext {
+                    clock_hz: dec(line, arg(line, &tokens, 1)?)?,
+                    refresh_period: dec(line, arg(line, &tokens, 2)?)?,
+                    board_id: board_id
+                        .try_into()
+                        .map_err(|_| parse_err(line, "board_id must be exactly 8 bytes"))?,
+                });
+            }
+            "capture" => {
+                let tool = unquote(line, arg(line, &tokens, 2)?)?;
+                let source = arg(line, &tokens, 1)?;
+                metadata.capture = Some(MooCaptureInfo {
+                    source: match source.parse::<MooCaptureSource>() {
+                        Ok(source) => source.to_u8(),
+                        Err(e) => source.parse().map_err(|_| parse_err(line, e))?,
+                    },
+                    tool:   tool
+                        .try_into()
+                        .map_err(|_| parse_err(line, "capture tool must be exactly 16 bytes"))?,
+                });
+            }
+            "mnemonic_ext" => {
+                let bytes = unquote(line, arg(line, &tokens, 1)?)?;
+                if bytes.len() > u8::MAX as usize {
+                    return Err(parse_err(line, "mnemonic_ext must not exceed 255 bytes"));
+                }
+                metadata.mnemonic_ext = Some(MooByteString { bytes });
+            }
+            "opcode_bytes" => {
+                let bytes = hex_byte_tokens(line, &tokens[1..])?;
+                if bytes.len() > u8::MAX as usize {
+                    return Err(parse_err(line, "opcode_bytes must not exceed 255 bytes"));
+                }
+                metadata.set_opcode_bytes(Some(&bytes));
+            }
+            "end" => return Ok(metadata),
+            other => return Err(parse_err(line, format!("unexpected '{}' in metadata", other))),
+        }
+    }
+}
+
+fn parse_test<R: BufRead>(parser: &mut TextParser<R>) -> Result<MooTest, MooError> {
+    let mut name = None;
+    let mut gen_metadata = None;
+    let mut bytes = Vec::new();
+    let mut initial_state = None;
+    let mut final_state = None;
+    let mut cycles = Vec::new();
+    let mut exception = None;
+    let mut hash = None;
+    let mut digest = None;
+    let mut tags = Vec::new();
+    let mut anchors = Vec::new();
+    let mut io_stimulus = Vec::new();
+    let mut vendor_chunks = Vec::new();
+
+    let end_line = loop {
+        let (line, tokens) = parser.expect_line()?;
+        match tokens[0].as_str() {
+            "name" => name = Some(utf8(line, unquote(line, arg(line, &tokens, 1)?)?)?),
+            "gen" => {
+                gen_metadata = Some(MooTestGenMetadata {
+                    seed:   hex(line, kv(line, &tokens, 1, "seed")?)?,
+                    gen_ct: dec(line, kv(line, &tokens, 2, "gen_ct")?)?,
+                })
+            }
+            "bytes" => bytes = hex_byte_tokens(line, &tokens[1..])?,
+            "initial" => initial_state = Some(parse_state(parser, MooStateType::Initial)?),
+            "final" => final_state = Some(parse_state(parser, MooStateType::Final)?),
+            "cycles" => {
+                let cycle_ct: usize = dec(line, arg(line, &tokens, 1)?)?;
+                for _ in 0..cycle_ct {
+                    let (line, tokens) = parser.expect_line()?;
+                    cycles.push(cycle_from_text(line, &tokens)?);
+                }
+            }
+            "exception" => {
+                exception = Some(MooException {
+                    exception_num: hex(line, kv(line, &tokens, 1, "num")?)?,
+                    flag_address:  hex(line, kv(line, &tokens, 2, "flag_address")?)?,
+                })
+            }
+            "hash" => {
+                let hash_bytes = hex_string(line, arg(line, &tokens, 1)?)?;
+                hash = Some(
+                    hash_bytes
+                        .try_into()
+                        .map_err(|_| parse_err(line, "hash must be exactly 20 bytes"))?,
+                );
+            }
+            "digest" => {
+                let algorithm: MooHashAlgorithm = arg(line, &tokens, 1)?
+                    .parse()
+                    .map_err(|e: MooError| parse_err(line, e.to_string()))?;
+                let digest_bytes = hex_string(line, arg(line, &tokens, 2)?)?;
+                if digest_bytes.len() != algorithm.digest_len() {
+                    return Err(parse_err(
+                        line,
+                        format!("{} digest must be exactly {} bytes", algorithm, algorithm.digest_len()),
+                    ));
+                }
+                digest = Some(MooTestDigest {
+                    algorithm,
+                    digest: digest_bytes,
+                });
+            }
+            "tag" => tags.push(utf8(line, unquote(line, arg(line, &tokens, 1)?)?)?),
+            "anchor" => {
+                let kind: MooAnchorKind = arg(line, &tokens, 1)?
+                    .parse()
+                    .map_err(|e: MooError| parse_err(line, e.to_string()))?;
+                anchors.push((kind, dec(line, arg(line, &tokens, 2)?)?));
+            }
+            "io" => {
+                let port: u16 = hex(line, kv(line, &tokens, 1, "port")?)?;
+                let width: MooPortWidth = kv(line, &tokens, 2, "width")?
+                    .parse()
+                    .map_err(|e: MooError| parse_err(line, e.to_string()))?;
+                let values = tokens[3..]
+                    .iter()
+                    .map(|t| hex(line, t))
+                    .collect::<Result<Vec<u32>, _>>()?;
+                io_stimulus.push((port, width, values));
+            }
+            "vendor" => vendor_chunks.push(vendor_from_text(line, &tokens[1..])?),
+            "end" => break line,
+            other => return Err(parse_err(line, format!("unexpected '{}' in test", other))),
+        }
+    };
+
+    let missing = |what: &str| parse_err(end_line, format!("test is missing {}", what));
+    let mut test = MooTest {
+        name: name.ok_or_else(|| missing("a name"))?,
+        gen_metadata,
+        bytes,
+        initial_state: initial_state.ok_or_else(|| missing("an initial state"))?,
+        final_state: final_state.ok_or_else(|| missing("a final state"))?,
+        cycles,
+        exception,
+        hash,
+        hash_dirty: false,
+        digest,
+        tags: Vec::new(),
+        anchors: Vec::new(),
+        io_stimulus: Vec::new(),
+        vendor_chunks,
+        synthetic: false,
+    };
+    for tag in tags {
+        test.add_tag(tag);
+    }
+    for (kind, cycle) in anchors {
+        test.set_anchor(kind, cycle);
+    }
+    for (port, width, values) in io_stimulus {
+        test.set_port_values(port, width, values);
+    }
+    Ok(test)
+}
+
+fn parse_state<R: BufRead>(parser: &mut TextParser<R>, s_type: MooStateType) -> Result<MooTestState, MooError> {
+    let mut state = MooTestState {
+        s_type,
+        ..Default::default()
+    };
+    loop {
+        let (line, tokens) = parser.expect_line()?;
+        match tokens[0].as_str() {
+            "regs16" | "regs32" => state.regs = regs_from_text(line, &tokens)?,
+            "ea" => {
+                state.ea = Some(MooEffectiveAddress {
+                    base_segment: enum_from_name(line, kv(line, &tokens, 1, "seg")?)?,
+                    base_selector: hex(line, kv(line, &tokens, 2, "selector")?)?,
+                    base_address: hex(line, kv(line, &tokens, 3, "base")?)?,
+                    base_limit: hex(line, kv(line, &tokens, 4, "limit")?)?,
+                    offset: hex(line, kv(line, &tokens, 5, "offset")?)?,
+                    linear_address: hex(line, kv(line, &tokens, 6, "linear")?)?,
+                    physical_address: hex(line, kv(line, &tokens, 7, "physical")?)?,
+                })
+            }
+            "descriptor" => {
+                let segment: MooSegmentRegister = enum_from_name(line, arg(line, &tokens, 1)?)?;
+                let descriptor = MooDescriptor32 {
+                    access: hex(line, kv(line, &tokens, 2, "access")?)?,
+                    base:   hex(line, kv(line, &tokens, 3, "base")?)?,
+                    limit:  hex(line, kv(line, &tokens, 4, "limit")?)?,
+                };
+                match &mut state.descriptors {
+                    Some(MooDescriptors::ThirtyTwo(descriptors)) => *descriptors.get_mut(segment) = descriptor,
+                    _ => {
+                        let mut descriptors = MooDescriptors32::default();
+                        *descriptors.get_mut(segment) = descriptor;
+                        state.descriptors = Some(MooDescriptors::ThirtyTwo(descriptors));
+                    }
+                }
+            }
+            "queue" => state.queue = hex_byte_tokens(line, &tokens[1..])?,
+            "ram" => state.ram.push(MooRamEntry {
+                address: hex(line, arg(line, &tokens, 1)?)?,
+                value:   hex(line, arg(line, &tokens, 2)?)?,
+            }),
+            "vendor" => state.vendor_chunks.push(vendor_from_text(line, &tokens[1..])?),
+            "end" => return Ok(state),
+            other => return Err(parse_err(line, format!("unexpected '{}' in state", other))),
+        }
+    }
+}
+
+fn cycle_from_text(line: usize, tokens: &[String]) -> Result<MooCycleState, MooError> {
+    if tokens.len() != 11 {
+        return Err(parse_err(
+            line,
+            format!("expected 11 cycle fields, found {}", tokens.len()),
+        ));
+    }
+    Ok(MooCycleState {
+        pins0: hex(line, &tokens[0])?,
+        address_bus: hex(line, &tokens[1])?,
+        segment: hex(line, &tokens[2])?,
+        memory_status: hex(line, &tokens[3])?,
+        io_status: hex(line, &tokens[4])?,
+        pins1: hex(line, &tokens[5])?,
+        data_bus: hex(line, &tokens[6])?,
+        bus_state: hex(line, &tokens[7])?,
+        t_state: hex(line, &tokens[8])?,
+        queue_op: hex(line, &tokens[9])?,
+        queue_byte: hex(line, &tokens[10])?,
+    })
+}
+
+/// Render registers as `regs16`/`regs32`, the register mask, then each present register in
+/// field order.
+fn regs_to_text(regs: &MooRegisters) -> String {
+    let (keyword, width) = match regs {
+        MooRegisters::Sixteen(_) => ("regs16", 2),
+        MooRegisters::ThirtyTwo(_) => ("regs32", 4),
+    };
+    let (mask, values) = regs.named_values();
+    let mut out = format!("{} mask={:0w$X}", keyword, mask, w = width * 2);
+    for (name, value) in values {
+        out.push_str(&format!(" {}={:0w$X}", name, value, w = width * 2));
+    }
+    out
+}
+
+fn regs_from_text(line: usize, tokens: &[String]) -> Result<MooRegisters, MooError> {
+    let (names, wide): (&[&str], bool) = match arg(line, tokens, 0)? {
+        "regs16" => (&MooRegisters::NAMES16, false),
+        "regs32" => (&MooRegisters::NAMES32, true),
+        other => return Err(parse_err(line, format!("expected regs16 or regs32, found '{}'", other))),
+    };
+    let mask: u32 = hex(line, kv(line, tokens, 1, "mask")?)?;
+
+    let mut values = Vec::new();
+    let mut token_idx = 2;
+    for (i, name) in names.iter().enumerate() {
+        if mask & (1 << i) != 0 {
+            let value: u32 = hex(line, kv(line, tokens, token_idx, name)?)?;
+            values.push((*name, value));
+            token_idx += 1;
+        }
+    }
+    if token_idx != tokens.len() {
+        return Err(parse_err(line, "register values do not match register mask"));
+    }
+
+    MooRegisters::from_named_values(wide, values).map_err(|e| parse_err(line, e))
+}
+
+fn vendor_to_text(chunk: &MooVendorChunk) -> String {
+    let data = chunk.data.iter().map(|b| format!("{:02X}", b)).collect::<String>();
+    if data.is_empty() {
+        quote(&chunk.id)
+    }
+    else {
+        format!("{} {}", quote(&chunk.id), data)
+    }
+}
+
+fn vendor_from_text(line: usize, tokens: &[String]) -> Result<MooVendorChunk, MooError> {
+    let id = unquote(line, arg(line, tokens, 0)?)?
+        .try_into()
+        .map_err(|_| parse_err(line, "vendor chunk id must be exactly 4 bytes"))?;
+    let data = match tokens.get(1) {
+        Some(data) => hex_string(line, data)?,
+        None => Vec::new(),
+    };
+    MooVendorChunk::new(id, data).map_err(|e| parse_err(line, e))
+}
+
+/// A line-oriented reader over a text dump that skips blank lines and comments.
+struct TextParser<R: BufRead> {
+    lines:   io::Lines<R>,
+    line_no: usize,
+}
+
+impl<R: BufRead> TextParser<R> {
+    fn next_line(&mut self) -> Result<Option<(usize, Vec<String>)>, MooError> {
+        for line in self.lines.by_ref() {
+            self.line_no += 1;
+            let line = line.map_err(|e| parse_err(self.line_no, e))?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            return Ok(Some((self.line_no, tokenize(self.line_no, trimmed)?)));
+        }
+        Ok(None)
+    }
+
+    fn expect_line(&mut self) -> Result<(usize, Vec<String>), MooError> {
+        self.next_line()?
+            .ok_or_else(|| parse_err(self.line_no, "unexpected end of input"))
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(usize, Vec<String>), MooError> {
+        let (line, tokens) = self.expect_line()?;
+        if tokens[0] != keyword {
+            return Err(parse_err(
+                line,
+                format!("expected '{}', found '{}'", keyword, tokens[0]),
+            ));
+        }
+        Ok((line, tokens))
+    }
+}
+
+/// Split a line on whitespace, keeping quoted strings (with their quotes) as single tokens.
+fn tokenize(line: usize, text: &str) -> Result<Vec<String>, MooError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        if c == '"' {
+            token.push(c);
+            chars.next();
+            let mut closed = false;
+            while let Some(c) = chars.next() {
+                token.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        token.push(escaped);
+                    }
+                }
+                else if c == '"' {
+                    closed = true;
+                    break;
+                }
+            }
+            if !closed {
+                return Err(parse_err(line, "unterminated string"));
+            }
+        }
+        else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn quote(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 2);
+    out.push('"');
+    for &b in bytes {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7E => out.push(b as char),
+            _ => out.push_str(&format!("\\x{:02X}", b)),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn unquote(line: usize, token: &str) -> Result<Vec<u8>, MooError> {
+    let inner = token
+        .strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .ok_or_else(|| parse_err(line, format!("expected a quoted string, found '{}'", token)))?;
+
+    let mut out = Vec::with_capacity(inner.len());
+    let mut bytes = inner.bytes();
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            out.push(b);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'"') => out.push(b'"'),
+            Some(b'\\') => out.push(b'\\'),
+            Some(b'x') => {
+                let digits = [bytes.next().unwrap_or(0), bytes.next().unwrap_or(0)];
+                let digits = std::str::from_utf8(&digits).unwrap_or("");
+                out.push(hex(line, digits)?);
+            }
+            _ => return Err(parse_err(line, "invalid escape sequence")),
+        }
+    }
+    Ok(out)
+}
+
+fn utf8(line: usize, bytes: Vec<u8>) -> Result<String, MooError> {
+    String::from_utf8(bytes).map_err(|e| parse_err(line, e))
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
+}
+
+fn hex_byte_tokens(line: usize, tokens: &[String]) -> Result<Vec<u8>, MooError> {
+    tokens.iter().map(|t| hex(line, t)).collect()
+}
+
+fn hex_string(line: usize, text: &str) -> Result<Vec<u8>, MooError> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return Err(parse_err(line, format!("invalid hex string '{}'", text)));
+    }
+    (0..text.len()).step_by(2).map(|i| hex(line, &text[i..i + 2])).collect()
+}
+
+fn hex<T: TryFrom<u64>>(line: usize, text: &str) -> Result<T, MooError> {
+    u64::from_str_radix(text, 16)
+        .ok()
+        .and_then(|v| T::try_from(v).ok())
+        .ok_or_else(|| parse_err(line, format!("invalid hex value '{}'", text)))
+}
+
+fn dec<T: std::str::FromStr>(line: usize, text: &str) -> Result<T, MooError> {
+    text.parse()
+        .map_err(|_| parse_err(line, format!("invalid decimal value '{}'", text)))
+}
+
+fn arg(line: usize, tokens: &[String], index: usize) -> Result<&str, MooError> {
+    tokens
+        .get(index)
+        .map(String::as_str)
+        .ok_or_else(|| parse_err(line, format!("missing argument {}", index)))
+}
+
+/// Return the value of a `key=value` token at `index`, checking that the key matches.
+fn kv<'a>(line: usize, tokens: &'a [String], index: usize, key: &str) -> Result<&'a str, MooError> {
+    arg(line, tokens, index)?
+        .strip_prefix(key)
+        .and_then(|t| t.strip_prefix('='))
+        .ok_or_else(|| parse_err(line, format!("expected '{}=' at argument {}", key, index)))
+}
+
+/// Find the variant of a `u8`-represented enum whose [Debug] name is `name`.
+fn enum_from_name<T>(line: usize, name: &str) -> Result<T, MooError>
+where
+    T: Debug + for<'a> BinRead<Args<'a> = ()>,
+{
+    (0..=u8::MAX)
+        .filter_map(|b| T::read_le(&mut Cursor::new([b])).ok())
+        .find(|v| format!("{:?}", v) == name)
+        .ok_or_else(|| parse_err(line, format!("unknown value '{}'", name)))
+}
+
+fn parse_err(line: usize, msg: impl std::fmt::Display) -> MooError {
+    MooError::ParseError(format!("line {}: {}", line, msg))
+}
@@ -0,0 +1,447 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! An edit journal for [MooTestFile], providing undo and redo of test mutations.
+//!
+//! Each mutation made through the journaled methods of [MooTestFile] records the inverse
+//! operation needed to revert it. The journal also tracks which tests have been modified since
+//! the journal was last cleared, so that only those tests need to be re-hashed when the file is
+//! written with `preserve_hash` set. A [MooJournalSummary] of the journal tells how many tests a
+//! series of edits touched, which is how a caller can report what a save would change.
+
+use std::{collections::BTreeSet, fmt::Display};
+
+use crate::{
+    test::{moo_test::MooTest, test_state::MooTestState},
+    test_file::MooTestFile,
+    types::{
+        anchor::MooAnchor,
+        hash::MooTestDigest,
+        io_stimulus::MooIoStimulus,
+        vendor::MooVendorChunk,
+        MooCycleState,
+        MooException,
+        MooTestGenMetadata,
+    },
+};
+
+/// A [MooTestEdit] describes a single mutation of one field of a [MooTest].
+/// Applying an edit to a test produces another [MooTestEdit] that reverts it.
+pub enum MooTestEdit {
+    /// Replace the test name.
+    SetName(String),
+    /// Replace the instruction bytes.
+    SetBytes(Vec<u8>),
+    /// Replace the initial [MooTestState].
+    SetInitialState(MooTestState),
+    /// Replace the final [MooTestState].
+    SetFinalState(MooTestState),
+    /// Replace the vector of [MooCycleState]s.
+    SetCycles(Vec<MooCycleState>),
+    /// Replace the optional [MooException].
+    SetException(Option<MooException>),
+    /// Replace the set of tags. Tags are expected to be sorted and unique.
+    SetTags(Vec<String>),
+    /// Replace the optional [MooTestGenMetadata].
+    SetGenMetadata(Option<MooTestGenMetadata>),
+    /// Replace the optional alternate [MooTestDigest].
+    SetDigest(Option<MooTestDigest>),
+    /// Replace the vector of [MooAnchor]s.
+    SetAnchors(Vec<MooAnchor>),
+    /// Replace the vector of [MooIoStimulus] entries. Entries are expected to be sorted by port and
+    /// width, and unique.
+    SetIoStimulus(Vec<MooIoStimulus>),
+    /// Replace the test-level [MooVendorChunk]s. Vendor chunks of the test states are edited
+    /// through [MooTestEdit::SetInitialState] and [MooTestEdit::SetFinalState].
+    SetVendorChunks(Vec<MooVendorChunk>),
+}
+
+impl MooTestEdit {
+    /// Returns true if the edited field is covered by the test hash. Tags, digests, anchors, IO
+    /// stimulus and test-level vendor chunks are not hashed; every other field is.
+    pub fn is_hashed(&self) -> bool {
+        !matches!(
+            self,
+            MooTestEdit::SetTags(_)
+                | MooTestEdit::SetDigest(_)
+                | MooTestEdit::SetAnchors(_)
+                | MooTestEdit::SetIoStimulus(_)
+                | MooTestEdit::SetVendorChunks(_)
+        )
+    }
+
+    /// Apply this edit to `test`, returning the edit that reverts it.
+    fn apply(self, test: &mut MooTest) -> MooTestEdit {
+        use std::mem::replace;
+        if self.is_hashed() {
+            test.hash_dirty = true;
+        }
+        match self {
+            MooTestEdit::SetName(name) => MooTestEdit::SetName(replace(&mut test.name, name)),
+            MooTestEdit::SetBytes(bytes) => MooTestEdit::SetBytes(replace(&mut test.bytes, bytes)),
+            MooTestEdit::SetInitialState(state) => {
+                MooTestEdit::SetInitialState(replace(&mut test.initial_state, state))
+            }
+            MooTestEdit::SetFinalState(state) => MooTestEdit::SetFinalState(replace(&mut test.final_state, state)),
+            MooTestEdit::SetCycles(cycles) => MooTestEdit::SetCycles(replace(&mut test.cycles, cycles)),
+            MooTestEdit::SetException(exception) => MooTestEdit::SetException(replace(&mut test.exception, exception)),
+            MooTestEdit::SetTags(tags) => MooTestEdit::SetTags(replace(&mut test.tags, tags)),
+            MooTestEdit::SetGenMetadata(gen_metadata) => {
+                MooTestEdit::SetGenMetadata(replace(&mut test.gen_metadata, gen_metadata))
+            }
+            MooTestEdit::SetDigest(digest) => MooTestEdit::SetDigest(replace(&mut test.digest, digest)),
+            MooTestEdit::SetAnchors(anchors) => MooTestEdit::SetAnchors(replace(&mut test.anchors, anchors)),
+            MooTestEdit::SetIoStimulus(stimulus) => {
+                MooTestEdit::SetIoStimulus(replace(&mut test.io_stimulus, stimulus))
+            }
+            MooTestEdit::SetVendorChunks(chunks) => {
+                MooTestEdit::SetVendorChunks(replace(&mut test.vendor_chunks, chunks))
+            }
+        }
+    }
+}
+
+/// A single journaled operation on the test vector of a [MooTestFile].
+enum MooJournalOp {
+    Edit { index: usize, edit: Box<MooTestEdit> },
+    Insert { index: usize, test: Box<MooTest> },
+    Remove { index: usize },
+    Move { from: usize, to: usize },
+}
+
+/// A [MooJournalSummary] counts the changes recorded in a [MooEditJournal], as returned by
+/// [MooTestFile::journal_summary]. Undone operations are not counted. Its [Display] form is
+/// suitable for an edit log.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooJournalSummary {
+    /// The number of edits applied to each test, indexed by its current position in the file.
+    /// Edits made to tests that were later removed are not included.
+    pub edit_counts:    Vec<usize>,
+    /// The number of tests inserted.
+    pub tests_inserted: usize,
+    /// The number of tests removed.
+    pub tests_removed:  usize,
+    /// The number of times a test was moved to another position.
+    pub tests_moved:    usize,
+}
+
+impl MooJournalSummary {
+    /// Returns the total number of edits applied to tests still in the file.
+    pub fn edits(&self) -> usize {
+        self.edit_counts.iter().sum()
+    }
+
+    /// Returns the number of tests with at least one edit.
+    pub fn tests_edited(&self) -> usize {
+        self.edit_counts.iter().filter(|&&ct| ct > 0).count()
+    }
+
+    /// Returns an iterator over the index and edit count of each edited test, in ascending order.
+    pub fn edited_tests(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edit_counts.iter().copied().enumerate().filter(|&(_, ct)| ct > 0)
+    }
+
+    /// Returns true if any test was edited, inserted or removed.
+    pub fn changed(&self) -> bool {
+        self.tests_inserted > 0
+            || self.tests_removed > 0
+            || self.tests_moved > 0
+            || self.edit_counts.iter().any(|&ct| ct > 0)
+    }
+}
+
+impl Display for MooJournalSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} tests edited ({} edits), {} inserted, {} removed",
+            self.tests_edited(),
+            self.edits(),
+            self.tests_inserted,
+            self.tests_removed
+        )?;
+        if self.tests_moved > 0 {
+            write!(f, ", {} moved", self.tests_moved)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [MooEditJournal] holds the undo and redo stacks for a [MooTestFile], along with the set of
+/// test indices whose hashed content was modified since the journal was last cleared.
+#[derive(Default)]
+pub struct MooEditJournal {
+    undo_stack: Vec<MooJournalOp>,
+    redo_stack: Vec<MooJournalOp>,
+    dirty: BTreeSet<usize>,
+}
+
+impl MooEditJournal {
+    /// Returns true if there is an operation that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Returns true if there is an operation that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Returns true if the hashed content of the test at `index` has been modified since the
+    /// journal was last cleared, or if the test was inserted since then.
+    pub fn is_dirty(&self, index: usize) -> bool {
+        self.dirty.contains(&index)
+    }
+
+    /// Returns an iterator over the indices of modified tests, in ascending order.
+    pub fn dirty_tests(&self) -> impl Iterator<Item = usize> + '_ {
+        self.dirty.iter().copied()
+    }
+
+    /// Record a test inserted at `index`, shifting the indices of following dirty tests up.
+    fn shift_dirty_insert(&mut self, index: usize) {
+        self.dirty = self
+            .dirty
+            .iter()
+            .map(|&i| {
+                if i >= index {
+                    i + 1
+                }
+                else {
+                    i
+                }
+            })
+            .collect();
+        self.dirty.insert(index);
+    }
+
+    /// Record a test moved from `from` to `to`. The tests in between shift by one place, and as a
+    /// test's hash covers its index, every test in that range is marked dirty.
+    fn shift_dirty_move(&mut self, from: usize, to: usize) {
+        self.shift_dirty_remove(from);
+        self.shift_dirty_insert(to);
+        self.dirty.extend(from.min(to)..=from.max(to));
+    }
+
+    /// Record a test removed from `index`, shifting the indices of following dirty tests down.
+    fn shift_dirty_remove(&mut self, index: usize) {
+        self.dirty = self
+            .dirty
+            .iter()
+            .filter(|&&i| i != index)
+            .map(|&i| {
+                if i > index {
+                    i - 1
+                }
+                else {
+                    i
+                }
+            })
+            .collect();
+    }
+}
+
+/// Journaled editing methods
+impl MooTestFile {
+    /// Returns a reference to the [MooEditJournal] for this file.
+    pub fn journal(&self) -> &MooEditJournal {
+        &self.journal
+    }
+
+    /// Summarize the operations in the journal's undo history. Per-test edit counts follow tests
+    /// through later insertions and removals, so they index the file's current tests. Changes
+    /// made without the journal, such as through [MooTestFile::tests_mut], are not counted.
+    pub fn journal_summary(&self) -> MooJournalSummary {
+        let ops = &self.journal.undo_stack;
+        // The undo stack holds inverses: a Remove undoes an insertion and an Insert a removal.
+        let inserted = ops
+            .iter()
+            .filter(|op| matches!(op, MooJournalOp::Remove { .. }))
+            .count();
+        let removed = ops
+            .iter()
+            .filter(|op| matches!(op, MooJournalOp::Insert { .. }))
+            .count();
+        let moved = ops.iter().filter(|op| matches!(op, MooJournalOp::Move { .. })).count();
+
+        // Replay the history against the test count before the first recorded operation. Tests
+        // added or dropped outside the journal, such as by trim_tests, can leave indices out of
+        // range; these are clamped rather than trusted.
+        let mut edit_counts = vec![0; (self.tests.len() + removed).saturating_sub(inserted)];
+        for op in ops {
+            match *op {
+                MooJournalOp::Edit { index, .. } => {
+                    if let Some(ct) = edit_counts.get_mut(index) {
+                        *ct += 1;
+                    }
+                }
+                MooJournalOp::Remove { index } => edit_counts.insert(index.min(edit_counts.len()), 0),
+                MooJournalOp::Insert { index, .. } => {
+                    if index < edit_counts.len() {
+                        edit_counts.remove(index);
+                    }
+                }
+                // The inverse of a move from `to` to `from`.
+                MooJournalOp::Move { from, to } => {
+                    if to < edit_counts.len() {
+                        let ct = edit_counts.remove(to);
+                        edit_counts.insert(from.min(edit_counts.len()), ct);
+                    }
+                }
+            }
+        }
+        edit_counts.resize(self.tests.len(), 0);
+
+        MooJournalSummary {
+            edit_counts,
+            tests_inserted: inserted,
+            tests_removed: removed,
+            tests_moved: moved,
+        }
+    }
+
+    /// Apply a [MooTestEdit] to the test at `index`, recording its inverse in the journal.
+    /// Any pending redo operations are discarded.
+    ///
+    /// Returns false if `index` is out of range.
+    pub fn edit_test(&mut self, index: usize, edit: MooTestEdit) -> bool {
+        if index >= self.tests.len() {
+            return false;
+        }
+        self.record(MooJournalOp::Edit {
+            index,
+            edit: Box::new(edit),
+        });
+        true
+    }
+
+    /// Insert a [MooTest] at `index`, recording the insertion in the journal.
+    ///
+    /// Returns false if `index` is greater than the number of tests.
+    pub fn insert_test(&mut self, index: usize, test: MooTest) -> bool {
+        if index > self.tests.len() {
+            return false;
+        }
+        self.record(MooJournalOp::Insert {
+            index,
+            test: Box::new(test),
+        });
+        true
+    }
+
+    /// Remove the test at `index`, recording the removal in the journal so that it may be undone.
+    ///
+    /// Returns false if `index` is out of range.
+    pub fn remove_test(&mut self, index: usize) -> bool {
+        if index >= self.tests.len() {
+            return false;
+        }
+        self.record(MooJournalOp::Remove { index });
+        true
+    }
+
+    /// Move the test at `from` so that it ends up at index `to`, recording the move in the journal.
+    /// The tests in between shift by one place and are re-hashed when the file is written.
+    ///
+    /// Returns false if either index is out of range.
+    pub fn move_test(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.tests.len() || to >= self.tests.len() {
+            return false;
+        }
+        self.record(MooJournalOp::Move { from, to });
+        true
+    }
+
+    /// Revert the most recent journaled operation. Returns false if there was nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.journal.undo_stack.pop() {
+            Some(op) => {
+                let inverse = self.apply_op(op);
+                self.journal.redo_stack.push(inverse);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Re-apply the most recently undone operation. Returns false if there was nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.journal.redo_stack.pop() {
+            Some(op) => {
+                let inverse = self.apply_op(op);
+                self.journal.undo_stack.push(inverse);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Discard the undo and redo history and mark all tests clean.
+    /// This is typically called after the file has been saved.
+    pub fn clear_journal(&mut self) {
+        self.journal = MooEditJournal::default();
+    }
+
+    fn record(&mut self, op: MooJournalOp) {
+        let inverse = self.apply_op(op);
+        self.journal.undo_stack.push(inverse);
+        self.journal.redo_stack.clear();
+    }
+
+    /// Apply a journal operation to the test vector and return its inverse.
+    fn apply_op(&mut self, op: MooJournalOp) -> MooJournalOp {
+        match op {
+            MooJournalOp::Edit { index, edit } => {
+                if edit.is_hashed() {
+                    self.journal.dirty.insert(index);
+                }
+                let inverse = edit.apply(&mut self.tests[index]);
+                MooJournalOp::Edit {
+                    index,
+                    edit: Box::new(inverse),
+                }
+            }
+            MooJournalOp::Insert { index, test } => {
+                self.tests.insert(index, *test);
+                self.sync_test_count();
+                self.journal.shift_dirty_insert(index);
+                MooJournalOp::Remove { index }
+            }
+            MooJournalOp::Remove { index } => {
+                let test = self.tests.remove(index);
+                self.sync_test_count();
+                self.journal.shift_dirty_remove(index);
+                MooJournalOp::Insert {
+                    index,
+                    test: Box::new(test),
+                }
+            }
+            MooJournalOp::Move { from, to } => {
+                let test = self.tests.remove(from);
+                self.tests.insert(to, test);
+                self.journal.shift_dirty_move(from, to);
+                MooJournalOp::Move { from: to, to: from }
+            }
+        }
+    }
+}
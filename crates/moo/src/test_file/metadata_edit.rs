@@ -0,0 +1,206 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Validated edits to the metadata of a [MooTestFile].
+//!
+//! The fields of [MooFileMetadata](crate::types::MooFileMetadata) can be set directly, but nothing
+//! then stops a file from claiming an opcode its tests don't execute. [MooTestFile::edit_metadata]
+//! checks a [MooMetadataEdit] against the tests before applying any of it, so a file is either
+//! edited consistently or left as it was.
+
+use std::fmt::Display;
+
+use crate::{
+    test_file::MooTestFile,
+    types::{errors::MooError, opcode::MooOpcodeId, MooCpuFamily},
+};
+
+/// A set of changes to a file's metadata. Fields left as `None` are not changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooMetadataEdit {
+    pub mnemonic: Option<String>,
+    pub opcode: Option<MooOpcodeId>,
+    pub file_seed: Option<u64>,
+    /// The major and minor version of the test collection.
+    pub set_version: Option<(u8, u8)>,
+}
+
+impl MooMetadataEdit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mnemonic(mut self, mnemonic: impl Into<String>) -> Self {
+        self.mnemonic = Some(mnemonic.into());
+        self
+    }
+
+    pub fn with_opcode(mut self, opcode: MooOpcodeId) -> Self {
+        self.opcode = Some(opcode);
+        self
+    }
+
+    pub fn with_file_seed(mut self, seed: u64) -> Self {
+        self.file_seed = Some(seed);
+        self
+    }
+
+    pub fn with_set_version(mut self, major: u8, minor: u8) -> Self {
+        self.set_version = Some((major, minor));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The fields changed by [MooTestFile::edit_metadata], described for display.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooMetadataEditSummary {
+    pub changes: Vec<String>,
+}
+
+impl MooMetadataEditSummary {
+    /// Returns true if any field was changed. Setting a field to its current value is not a change.
+    pub fn changed(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
+
+impl Display for MooMetadataEditSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.changes.is_empty() {
+            write!(f, "metadata unchanged")
+        }
+        else {
+            write!(f, "{}", self.changes.join(", "))
+        }
+    }
+}
+
+/// Returns true if an instruction decoded as `test_id` is an instance of `id`: the opcodes match,
+/// the extensions match if `id` names one, and every prefix of `id` is present.
+fn opcode_matches(test_id: &MooOpcodeId, id: &MooOpcodeId) -> bool {
+    test_id.opcode() == id.opcode()
+        && id.extension().is_none_or(|ext| test_id.extension() == Some(ext))
+        && id.prefixes().iter().all(|p| test_id.prefixes().contains(p))
+}
+
+impl MooTestFile {
+    /// Return the indices of the tests whose instruction is not an instance of `id`, including
+    /// tests whose bytes are too short to decode.
+    pub fn opcode_mismatches(&self, id: &MooOpcodeId) -> Vec<usize> {
+        let cpu_family = MooCpuFamily::from(self.cpu_type());
+        self.tests()
+            .iter()
+            .enumerate()
+            .filter(|(_, test)| {
+                !test
+                    .opcode_id(cpu_family)
+                    .is_some_and(|test_id| opcode_matches(&test_id, id))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Apply `edit` to the file's metadata. The mnemonic must be non-empty printable ASCII, and the
+    /// opcode must fit in four bytes and be the instruction executed by every test in the file.
+    ///
+    /// # Returns
+    /// A [MooMetadataEditSummary] of the fields changed, or a [MooError::MetadataError] if the file
+    /// has no metadata or a check fails, in which case nothing is changed.
+    pub fn edit_metadata(&mut self, edit: &MooMetadataEdit) -> Result<MooMetadataEditSummary, MooError> {
+        if let Some(mnemonic) = &edit.mnemonic {
+            if mnemonic.trim().is_empty() || !mnemonic.bytes().all(|b| b.is_ascii_graphic() || b == b' ') {
+                return Err(MooError::MetadataError(format!(
+                    "mnemonic '{}' must be non-empty printable ASCII",
+                    mnemonic
+                )));
+            }
+        }
+        if let Some(id) = &edit.opcode {
+            if id.to_u32().is_none() {
+                return Err(MooError::MetadataError(format!(
+                    "opcode {} is longer than four bytes",
+                    id
+                )));
+            }
+            let mismatches = self.opcode_mismatches(id);
+            if let Some(&first) = mismatches.first() {
+                let cpu_family = MooCpuFamily::from(self.cpu_type());
+                let found = self.tests()[first]
+                    .opcode_id(cpu_family)
+                    .map_or("undecodable bytes".to_string(), |found| found.to_string());
+                return Err(MooError::MetadataError(format!(
+                    "opcode {} does not match {} of {} tests, e.g. test {} ({})",
+                    id,
+                    mismatches.len(),
+                    self.test_ct(),
+                    first,
+                    found
+                )));
+            }
+        }
+
+        let metadata = self
+            .metadata_mut()
+            .ok_or_else(|| MooError::MetadataError("file has no metadata chunk".to_string()))?;
+        let mut summary = MooMetadataEditSummary::default();
+
+        if let Some(mnemonic) = &edit.mnemonic {
+            if metadata.mnemonic() != mnemonic.trim() {
+                summary
+                    .changes
+                    .push(format!("mnemonic {} -> {}", metadata.mnemonic(), mnemonic.trim()));
+                metadata.set_mnemonic(mnemonic.trim());
+            }
+        }
+        if let Some(id) = &edit.opcode {
+            if metadata.opcode_id().as_ref() != Some(id) {
+                let old = metadata.opcode_id().map_or("-".to_string(), |old| old.to_string());
+                metadata.set_opcode_id(id)?;
+                summary.changes.push(format!("opcode {} -> {}", old, id));
+            }
+        }
+        if let Some(seed) = edit.file_seed {
+            if metadata.file_seed != seed {
+                summary
+                    .changes
+                    .push(format!("file seed {:016X} -> {:016X}", metadata.file_seed, seed));
+                metadata.file_seed = seed;
+            }
+        }
+        if let Some((major, minor)) = edit.set_version {
+            let old = (metadata.set_version_major, metadata.set_version_minor);
+            if old != (major, minor) {
+                summary
+                    .changes
+                    .push(format!("set version {}.{} -> {}.{}", old.0, old.1, major, minor));
+                metadata.set_version_major = major;
+                metadata.set_version_minor = minor;
+            }
+        }
+        Ok(summary)
+    }
+}
@@ -21,27 +21,51 @@
     DEALINGS IN THE SOFTWARE.
 */
 
+pub mod dump;
+pub mod journal;
+pub mod metadata_edit;
+pub mod names;
+pub mod normalize;
+pub mod read_options;
+pub mod redact;
+pub mod search;
+pub mod seed;
 pub mod stats;
+pub mod transform;
+pub mod verify;
+pub mod write_options;
 
 use std::{
     collections::HashMap,
-    io::{self, Cursor, Read, Seek, SeekFrom, Write},
+    fs,
+    io::{self, BufWriter, Cursor, Read, Seek, SeekFrom, Write},
+    path::Path,
 };
 
 use crate::{
     test::moo_test::MooTest,
     types::{
+        anchor::{MooAnchor, MooAnchorsChunk},
+        arch_tag::MooArchTag,
         chunks::{
             MooBytesChunk,
             MooChunkHeader,
             MooChunkType,
+            MooDigestChunk,
+            MooEndChunk,
             MooFileHeader,
             MooHashChunk,
+            MooLicenseChunk,
             MooNameChunk,
+            MooTagsChunk,
             MooTestChunk,
         },
         effective_address::MooEffectiveAddress,
         errors::MooError,
+        hash::{hex_string, MooTestDigest},
+        io_stimulus::{MooIoStimulus, MooIoStimulusChunk},
+        license::MooLicense,
+        vendor::MooVendorChunk,
         MooCpuType,
         MooCycleState,
         MooException,
@@ -57,11 +81,16 @@ use crate::{
 use binrw::{BinRead, BinResult};
 
 use crate::{
-    registers::{MooRegisters, MooRegisters16, MooRegisters32},
+    registers::{descriptors_32::MooDescriptors32, MooDescriptors, MooRegisters, MooRegisters16, MooRegisters32},
     test::test_state::MooTestState,
+    test_file::{
+        journal::MooEditJournal,
+        read_options::{MooReadLimit, MooReadOptions},
+        write_options::{MooCompressionKind, MooWriteOptions},
+    },
 };
 #[cfg(feature = "gzip")]
-use flate2::read::GzDecoder;
+use flate2::read::MultiGzDecoder;
 
 /// A representation of a **MOO** test file.
 ///
@@ -96,9 +125,10 @@ pub struct MooTestFile {
     major_version: u8,
     /// The minor version of the **MOO** file format.
     minor_version: u8,
-    /// The encoded architecture tag as a String.
-    arch: String,
-    /// The decoded architecture tag as a [MooCpuType] enum.
+    /// The encoded architecture tag.
+    arch: MooArchTag,
+    /// The CPU type of the file when there is no metadata. Initially decoded from the
+    /// architecture tag.
     cpu_type: MooCpuType,
     /// A vector of all tests contained in the file as [MooTest] structs.
     tests: Vec<MooTest>,
@@ -108,12 +138,26 @@ pub struct MooTestFile {
     metadata: Option<MooFileMetadata>,
     /// Optional register mask to use for all tests in this file.
     register_mask: Option<MooRegisters>,
-    /// Whether the file was read as gzip-compressed.
-    compressed: bool,
+    /// The compression the file was read with.
+    compression: MooCompressionKind,
+    /// Whether top-level chunks carry a trailing CRC32.
+    chunk_crc: bool,
+    /// Whether the file is terminated by an `END ` chunk.
+    end_chunk: bool,
+    /// The undo/redo journal and modified-test set for journaled edits.
+    journal: MooEditJournal,
+    /// Top-level vendor chunks, preserved verbatim.
+    vendor_chunks: Vec<MooVendorChunk>,
+    /// Optional license and attribution information.
+    license: Option<MooLicense>,
 }
 
 /// Main implementation block
 impl MooTestFile {
+    /// The size of the `CRC ` subchunk that ends each top-level chunk when per-chunk checksums
+    /// are enabled: a chunk header followed by a `uint32` CRC32.
+    const CHUNK_CRC_SIZE: u32 = MooChunkHeader::SIZE as u32 + 4;
+
     /// Create a new empty `MooTestFile`.
     ///
     /// It is unlikely any users of this crate will need to call this directly. It is normally
@@ -135,13 +179,18 @@ impl MooTestFile {
         Self {
             major_version,
             minor_version,
-            arch: cpu_type.to_str().to_string(),
+            arch: MooArchTag::from(cpu_type),
             cpu_type,
             tests: Vec::with_capacity(capacity),
             hashes: HashMap::with_capacity(capacity),
             metadata: None,
             register_mask: None,
-            compressed: false,
+            compression: MooCompressionKind::Uncompressed,
+            chunk_crc: false,
+            end_chunk: true,
+            journal: MooEditJournal::default(),
+            vendor_chunks: Vec::new(),
+            license: None,
         }
     }
 
@@ -151,13 +200,19 @@ impl MooTestFile {
     }
 
     /// Returns a mutable reference to the optional [MooFileMetadata] struct, if present.
+    ///
+    /// Changing the metadata's `cpu_type` through this reference changes the CPU type of the file,
+    /// but leaves the architecture tag of the header as it was. Use [MooTestFile::set_cpu_type]
+    /// to change both, or call [MooTestFile::repair_arch] afterwards.
     pub fn metadata_mut(&mut self) -> Option<&mut MooFileMetadata> {
         self.metadata.as_mut()
     }
 
-    /// Set the optional [MooFileMetadata] struct
+    /// Set the optional [MooFileMetadata] struct. The CPU type and architecture tag of the file
+    /// are taken from the metadata.
     pub fn set_metadata(&mut self, metadata: MooFileMetadata) {
         self.cpu_type = metadata.cpu_type;
+        self.arch = MooArchTag::from(metadata.cpu_type);
         self.metadata = Some(metadata);
     }
 
@@ -171,26 +226,107 @@ impl MooTestFile {
         self.register_mask = Some(register_mask);
     }
 
-    /// Returns whether the file was read as gzip-compressed.
-    /// This flag persists when writing the file back out, unless changed via [MooTestFile::set_compressed].
+    /// Returns whether the file was read compressed, with either gzip or zstd.
+    /// The compression persists when writing the file back out, unless changed via
+    /// [MooTestFile::set_compressed] or [MooTestFile::set_compression].
     pub fn compressed(&self) -> bool {
-        self.compressed
+        self.compression != MooCompressionKind::Uncompressed
     }
 
     /// Set whether the file should be written as gzip-compressed.
     pub fn set_compressed(&mut self, compressed: bool) {
-        self.compressed = compressed;
+        self.compression = if compressed {
+            MooCompressionKind::Gzip
+        }
+        else {
+            MooCompressionKind::Uncompressed
+        };
+    }
+
+    /// Returns the compression the file was read with, or was last set to.
+    pub fn compression(&self) -> MooCompressionKind {
+        self.compression
     }
 
-    /// Appends a [MooTest] to the test file's test vector.
+    /// Set the compression the file should be written with.
+    pub fn set_compression(&mut self, compression: MooCompressionKind) {
+        self.compression = compression;
+    }
+
+    /// Returns whether top-level chunks carry a CRC32 checksum.
+    /// This flag is set when reading a file with per-chunk checksums, and persists when writing the
+    /// file back out, unless changed via [MooTestFile::set_chunk_crc].
+    pub fn chunk_crc(&self) -> bool {
+        self.chunk_crc
+    }
+
+    /// Set whether a CRC32 checksum should be emitted after each top-level chunk when writing.
+    /// Checksums let a reader pinpoint a corrupted test rather than rejecting the whole file.
+    pub fn set_chunk_crc(&mut self, chunk_crc: bool) {
+        self.chunk_crc = chunk_crc;
+    }
+
+    /// Returns whether the file is terminated by an `END ` chunk.
+    /// New files are written with one; files read without one are written back out the same way,
+    /// unless changed via [MooTestFile::set_end_chunk].
+    pub fn end_chunk(&self) -> bool {
+        self.end_chunk
+    }
+
+    /// Set whether an `END ` chunk should be written after the last test. A reader can then detect
+    /// a file truncated at a test boundary.
+    pub fn set_end_chunk(&mut self, end_chunk: bool) {
+        self.end_chunk = end_chunk;
+    }
+
+    /// Returns a reference to a slice of the top-level [MooVendorChunk]s in the file.
+    pub fn vendor_chunks(&self) -> &[MooVendorChunk] {
+        &self.vendor_chunks
+    }
+
+    /// Returns a mutable reference to the vector of top-level [MooVendorChunk]s in the file.
+    /// Top-level vendor chunks are written after the file metadata and before the first test.
+    pub fn vendor_chunks_mut(&mut self) -> &mut Vec<MooVendorChunk> {
+        &mut self.vendor_chunks
+    }
+
+    /// Returns a reference to the optional [MooLicense] of the file, if present.
+    pub fn license(&self) -> Option<&MooLicense> {
+        self.license.as_ref()
+    }
+
+    /// Set or clear the [MooLicense] of the file.
+    pub fn set_license(&mut self, license: Option<MooLicense>) {
+        self.license = license;
+    }
+
+    /// Appends a [MooTest] to the test file's test vector, updating the metadata test count.
     pub fn add_test(&mut self, test: MooTest) {
         self.tests.push(test);
+        self.sync_test_count();
     }
 
-    /// Truncates the test vector to the specified new count.
+    /// Truncates the test vector to the specified new count, updating the metadata test count.
+    /// Has no effect on the tests if `new_ct` is not less than the current number of tests.
     pub fn trim_tests(&mut self, new_ct: usize) {
         self.tests.truncate(new_ct);
+        self.sync_test_count();
+    }
 
+    /// Set the number of tests in the file by truncating the test vector, keeping the metadata
+    /// test count consistent.
+    ///
+    /// Returns false, leaving the file unchanged, if `test_ct` exceeds the number of tests.
+    pub fn set_test_count(&mut self, test_ct: usize) -> bool {
+        if test_ct > self.tests.len() {
+            return false;
+        }
+        self.trim_tests(test_ct);
+        true
+    }
+
+    /// Update the metadata test count, if metadata is present, to the length of the test vector.
+    fn sync_test_count(&mut self) {
         if let Some(metadata) = self.metadata.as_mut() {
             metadata.test_ct = self.tests.len() as u32;
         }
@@ -201,6 +337,11 @@ impl MooTestFile {
         (self.major_version, self.minor_version)
     }
 
+    /// Set the major and/or minor `MOO` file format version. A version given as `None` is left
+    /// unchanged.
+    ///
+    /// # Panics
+    /// Panics if the major or minor version exceeds [MOO_MAJOR_VERSION] or [MOO_MINOR_VERSION].
     pub fn set_version(&mut self, major_opt: Option<u8>, minor_opt: Option<u8>) {
         if let Some(major) = major_opt {
             if major > MOO_MAJOR_VERSION {
@@ -221,12 +362,48 @@ impl MooTestFile {
     /// This is derived from the architecture string in the [MooTestFile] header if a [MooFileMetadata]
     /// chunk is not present, otherwise it is taken from the metadata's `cpu_type` field.
     pub fn cpu_type(&self) -> MooCpuType {
-        self.cpu_type
+        self.metadata
+            .as_ref()
+            .map_or(self.cpu_type, |metadata| metadata.cpu_type)
+    }
+
+    /// Set the CPU type of the file. The architecture string of the header and the `cpu_type` of
+    /// the metadata, if present, are updated to match.
+    pub fn set_cpu_type(&mut self, cpu_type: MooCpuType) {
+        self.cpu_type = cpu_type;
+        self.arch = MooArchTag::from(cpu_type);
+        if let Some(metadata) = self.metadata.as_mut() {
+            metadata.cpu_type = cpu_type;
+        }
     }
 
     /// Returns a reference to the architecture string from the [MooTestFile] header.
     pub fn arch(&self) -> &str {
-        &self.arch
+        self.arch.as_str()
+    }
+
+    /// Returns the [MooArchTag] from the [MooTestFile] header.
+    pub fn arch_tag(&self) -> MooArchTag {
+        self.arch
+    }
+
+    /// Returns true if the architecture tag of the header names the CPU type of the file.
+    /// Files are read with their header tag as written, so a file whose `META` chunk disagrees
+    /// with its header reads back with a mismatch until [MooTestFile::repair_arch] is called.
+    pub fn arch_matches_cpu_type(&self) -> bool {
+        self.arch
+            .cpu_type()
+            .is_ok_and(|arch_type| arch_type.to_str() == self.cpu_type().to_str())
+    }
+
+    /// Set the architecture tag of the header from the CPU type of the file, if they disagree.
+    /// Returns true if the tag was changed.
+    pub fn repair_arch(&mut self) -> bool {
+        if self.arch_matches_cpu_type() {
+            return false;
+        }
+        self.arch = MooArchTag::from(self.cpu_type());
+        true
     }
 
     /// Returns a reference to a slice containing the individual [MooTest]s in the test file.
@@ -239,81 +416,128 @@ impl MooTestFile {
         &mut self.tests
     }
 
+    /// Returns an iterator over the final state of each test in the file with a complete register
+    /// set. See [MooTest::full_final_state].
+    pub fn full_final_states(&self) -> impl Iterator<Item = MooTestState> + '_ {
+        self.tests.iter().map(MooTest::full_final_state)
+    }
+
+    /// Returns an iterator over the tests in the file that have the specified tag.
+    pub fn tests_with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a MooTest> + 'a {
+        self.tests.iter().filter(move |test| test.has_tag(tag))
+    }
+
+    /// Returns an iterator over the real-mode tests in the file that cross the 1MB address
+    /// boundary. See [MooTest::address_wraps].
+    pub fn tests_exercising_wraparound(&self) -> impl Iterator<Item = &MooTest> + '_ {
+        self.tests.iter().filter(|t| t.exercises_wraparound(self.cpu_type()))
+    }
+
     /// Returns the number of tests in the file.
     pub fn test_ct(&self) -> usize {
         self.tests.len()
     }
 
     /// Read a [MooTestFile] from an implementor of [Read] + [Seek].
-    /// Automatically detects gzip compression if the `gzip` feature is enabled.
+    /// Automatically detects gzip and zstd compression if the `gzip` or `zstd` feature is enabled.
     ///
     /// # Arguments:
     /// * `reader` - The reader to read the MOO file from.
     /// # Returns:
     /// * A [MooTestFile] struct representing the parsed file, or an error if parsing fails.
     pub fn read<RS: Read + Seek>(reader: &mut RS) -> BinResult<MooTestFile> {
+        MooTestFile::read_with_options(reader, &MooReadOptions::default())
+    }
+
+    /// Read a [MooTestFile] from an implementor of [Read] + [Seek], using the specified
+    /// [MooReadOptions].
+    /// Automatically detects gzip and zstd compression if the `gzip` or `zstd` feature is enabled.
+    ///
+    /// # Arguments:
+    /// * `reader` - The reader to read the MOO file from.
+    /// * `options` - The [MooReadOptions] controlling how the file is read.
+    /// # Returns:
+    /// * A [MooTestFile] struct representing the parsed file, or an error if parsing fails.
+    pub fn read_with_options<RS: Read + Seek>(reader: &mut RS, options: &MooReadOptions) -> BinResult<MooTestFile> {
         // Seek to the start of the reader.
         reader.seek(SeekFrom::Start(0))?;
 
-        let is_gz = MooTestFile::is_gzip_stream(reader)?; // This seeks back to 0.
-
-        // If it's gz, decompress to a Vec and parse from a Cursor so we still have Read+Seek.
-        #[cfg(feature = "gzip")]
-        if is_gz {
-            let mut compressed = Vec::new();
-            reader.read_to_end(&mut compressed)?;
-            let mut gz = GzDecoder::new(&compressed[..]);
-
-            let mut decompressed = Vec::new();
-            gz.read_to_end(&mut decompressed)?;
-
-            let mut cursor = Cursor::new(decompressed);
-            let mut test_file = MooTestFile::read_impl(&mut cursor)?;
-
-            test_file.compressed = true;
-            return Ok(test_file);
+        let compression = MooTestFile::stream_compression(reader)?; // This seeks back to 0.
+        if compression == MooCompressionKind::Uncompressed {
+            // Plain path: parse directly.
+            return MooTestFile::read_impl(reader, options);
         }
 
-        // If gzip is disabled but stream looks like gzip, return a helpful error.
-        #[cfg(not(feature = "gzip"))]
-        if is_gz {
-            return Err(binrw::Error::Custom {
-                pos: 0,
-                err: Box::new(MooError::ParseError(
-                    "Input appears to be gzip-compressed; rebuild with the `gzip` feature enabled.".to_string(),
-                )),
-            });
-        }
+        // Decompress to a Vec and parse from a Cursor so we still have Read+Seek.
+        let mut decompressed = Vec::new();
+        MooTestFile::decoder(compression, reader)?.read_to_end(&mut decompressed)?;
+
+        let mut cursor = Cursor::new(decompressed);
+        let mut test_file = MooTestFile::read_impl(&mut cursor, options)?;
 
-        // Plain (non-gz) path: parse directly.
-        MooTestFile::read_impl(reader)
+        test_file.compression = compression;
+        Ok(test_file)
     }
 
-    /// Peek the first two bytes to detect gzip magic (0x1F, 0x8B). Seeks back to start.
-    fn is_gzip_stream<R: Read + Seek>(reader: &mut R) -> io::Result<bool> {
-        let mut magic = [0u8; 2];
+    /// Peek the first four bytes to detect gzip (0x1F, 0x8B) or zstd (0x28, 0xB5, 0x2F, 0xFD)
+    /// magic. Seeks back to start.
+    fn stream_compression<R: Read + Seek>(reader: &mut R) -> io::Result<MooCompressionKind> {
+        let mut magic = Vec::with_capacity(4);
         let start = reader.stream_position().unwrap_or(0);
-        reader.read_exact(&mut magic).or_else(|e| {
-            // If we can't even read 2 bytes, treat as not-gzip (rewind anyway).
-            if e.kind() == io::ErrorKind::UnexpectedEof {
-                Ok(())
-            }
-            else {
-                Err(e)
-            }
-        })?;
+        // A stream shorter than the magic is treated as uncompressed.
+        reader.by_ref().take(4).read_to_end(&mut magic)?;
         reader.seek(SeekFrom::Start(start))?;
-        Ok(magic == [0x1F, 0x8B])
+        Ok(match magic[..] {
+            [0x1F, 0x8B, ..] => MooCompressionKind::Gzip,
+            [0x28, 0xB5, 0x2F, 0xFD] => MooCompressionKind::Zstd,
+            _ => MooCompressionKind::Uncompressed,
+        })
     }
 
-    fn read_impl<R: Read + Seek>(reader: &mut R) -> BinResult<MooTestFile> {
-        // Seek to the start of the reader.
+    /// Return a reader decompressing `reader` with the given compression. Concatenated gzip
+    /// members and zstd frames are decoded as a single stream. If support for the compression is
+    /// not enabled, a helpful error is returned.
+    fn decoder<'a, R: Read + 'a>(compression: MooCompressionKind, reader: R) -> BinResult<Box<dyn Read + 'a>> {
+        #[cfg(not(all(feature = "gzip", feature = "zstd")))]
+        let missing = |feature: &str| binrw::Error::Custom {
+            pos: 0,
+            err: Box::new(MooError::ParseError(format!(
+                "Input appears to be {}-compressed; rebuild with the `{}` feature enabled.",
+                feature, feature
+            ))),
+        };
+        match compression {
+            MooCompressionKind::Uncompressed => Ok(Box::new(reader)),
+            #[cfg(feature = "gzip")]
+            MooCompressionKind::Gzip => Ok(Box::new(MultiGzDecoder::new(reader))),
+            #[cfg(feature = "zstd")]
+            MooCompressionKind::Zstd => Ok(Box::new(zstd::Decoder::new(reader)?)),
+            #[cfg(not(feature = "gzip"))]
+            MooCompressionKind::Gzip => Err(missing("gzip")),
+            #[cfg(not(feature = "zstd"))]
+            MooCompressionKind::Zstd => Err(missing("zstd")),
+        }
+    }
+
+    /// Read only the [MooFileHeader] of a MOO file, such as to find its test count without
+    /// reading any tests. A compressed file is decompressed only as far as the header.
+    pub fn read_header<RS: Read + Seek>(reader: &mut RS) -> BinResult<MooFileHeader> {
         reader.seek(SeekFrom::Start(0))?;
 
-        // Get reader len.
-        let reader_len = MooTestFile::get_reader_len(reader)?;
+        let compression = MooTestFile::stream_compression(reader)?;
+        if compression == MooCompressionKind::Uncompressed {
+            return MooTestFile::read_file_header(reader);
+        }
+
+        let mut header_bytes = Vec::new();
+        MooTestFile::decoder(compression, reader)?
+            .take(64)
+            .read_to_end(&mut header_bytes)?;
+        MooTestFile::read_file_header(&mut Cursor::new(header_bytes))
+    }
 
-        // Read the file header chunk.
+    /// Read the file header chunk at the current position of `reader`.
+    fn read_file_header<R: Read + Seek>(reader: &mut R) -> BinResult<MooFileHeader> {
         let header_chunk = MooChunkHeader::read(reader)?;
         if !matches!(header_chunk.chunk_type, MooChunkType::FileHeader) {
             return Err(binrw::Error::Custom {
@@ -323,24 +547,52 @@ impl MooTestFile {
                 )),
             });
         }
+        MooFileHeader::read(reader)
+    }
+
+    fn read_impl<R: Read + Seek>(reader: &mut R, options: &MooReadOptions) -> BinResult<MooTestFile> {
+        // Seek to the start of the reader.
+        reader.seek(SeekFrom::Start(0))?;
+
+        // Get reader len.
+        let reader_len = MooTestFile::get_reader_len(reader)?;
+
         // Read the file header.
-        let header: MooFileHeader = MooFileHeader::read(reader)?;
+        let header = MooTestFile::read_file_header(reader)?;
 
-        let cpu_string = String::from_utf8_lossy(&header.cpu_id).to_string();
-        let cpu_type = MooCpuType::from_str(&cpu_string).map_err(|e| binrw::Error::Custom {
+        let cpu_type = header.cpu_id.cpu_type().map_err(|e| binrw::Error::Custom {
             pos: reader.stream_position().unwrap_or(0),
             err: Box::new(MooError::ParseError(format!(
                 "Invalid CPU type '{}': {}",
-                cpu_string, e
+                header.cpu_id, e
             ))),
         })?;
 
-        let mut new_file = MooTestFile::new(
-            header.major_version,
-            header.minor_version,
-            cpu_type,
-            header.test_count as usize,
-        );
+        if header.major_version > MOO_MAJOR_VERSION {
+            return Err(binrw::Error::Custom {
+                pos: reader.stream_position().unwrap_or(0),
+                err: Box::new(MooError::ParseError(format!(
+                    "Unsupported MOO major version {} (expected <= {}).",
+                    header.major_version, MOO_MAJOR_VERSION
+                ))),
+            });
+        }
+
+        MooReadOptions::check(
+            MooReadLimit::TestCount,
+            header.test_count as u64,
+            options.max_tests as u64,
+            reader.stream_position().unwrap_or(0),
+        )?;
+
+        // Don't trust the header's test count for the allocation: every test takes at least a
+        // chunk header, so a corrupt count can't exceed what the file could hold.
+        let capacity = (header.test_count as usize).min(reader_len as usize / MooChunkHeader::SIZE);
+        let mut new_file = MooTestFile::new(header.major_version, 0, cpu_type, capacity);
+        // A later minor version only adds optional fields, so keep the version as read.
+        new_file.minor_version = header.minor_version;
+        new_file.chunk_crc = header.has_chunk_crc();
+        new_file.end_chunk = header.has_end_chunk();
 
         log::debug!(
             "Reading MooTestFile: version {}.{}, arch: {} test_ct: {}",
@@ -354,25 +606,69 @@ impl MooTestFile {
         let mut test_num = 0;
         let mut have_initial_state = false;
         let mut have_final_state = false;
-        let cpu_type = MooCpuType::from_str(&new_file.arch).map_err(|e| binrw::Error::Custom {
-            pos: reader.stream_position().unwrap_or(0),
-            err: Box::new(MooError::ParseError(format!(
-                "Invalid CPU type '{}': {}",
-                new_file.arch, e
-            ))),
-        })?;
+        let cpu_type = new_file.cpu_type;
+        let test_range = options.test_range.clone().unwrap_or(0..usize::MAX);
 
-        // Read chunks until exhausted.
+        // Read chunks until the END chunk or, in a file without one, the last test. Top-level
+        // chunks may follow the last test only in a file with an END chunk.
         loop {
-            if test_num == header.test_count as usize {
-                // We have read all tests, exit the loop.
-                log::trace!("Reached expected test count: {}", test_num);
-                log::trace!("{} bytes remaining in reader.", reader_len - reader.stream_position()?);
+            if test_num >= test_range.end {
+                // Nothing further was asked for.
                 break;
             }
-
             let top_level_chunk_offset = reader.stream_position()?;
-            let chunk = MooChunkHeader::read(reader)?;
+            let bytes_remaining = reader_len.saturating_sub(top_level_chunk_offset);
+            if test_num < header.test_count as usize && bytes_remaining == 0 {
+                return Err(binrw::Error::Custom {
+                    pos: top_level_chunk_offset,
+                    err: Box::new(MooError::ParseError(format!(
+                        "File ended after {} of {} tests.",
+                        test_num, header.test_count
+                    ))),
+                });
+            }
+            if test_num == header.test_count as usize && !new_file.end_chunk {
+                // Without an END chunk, the file ends with its last test. Anything after it is
+                // padding or garbage from an older writer.
+                if bytes_remaining > 0 {
+                    log::warn!("Ignoring {} trailing bytes after the last test", bytes_remaining);
+                }
+                break;
+            }
+            if test_num == header.test_count as usize && bytes_remaining < MooChunkHeader::SIZE as u64 {
+                return Err(binrw::Error::Custom {
+                    pos: top_level_chunk_offset,
+                    err: Box::new(MooError::ParseError(
+                        "File is missing its END chunk and may be truncated.".to_string(),
+                    )),
+                });
+            }
+
+            let mut chunk = MooChunkHeader::read(reader)?;
+
+            // Check the chunk size before it is used to size any buffers.
+            MooReadOptions::check(
+                MooReadLimit::ChunkSize,
+                chunk.size as u64,
+                options.max_chunk_size as u64,
+                top_level_chunk_offset,
+            )?;
+            let chunk_remaining = reader_len.saturating_sub(reader.stream_position()?);
+            if chunk.size as u64 > chunk_remaining {
+                return Err(binrw::Error::Custom {
+                    pos: top_level_chunk_offset,
+                    err: Box::new(MooError::ParseError(format!(
+                        "Chunk size ({}) exceeds the remaining file data ({} bytes).",
+                        chunk.size, chunk_remaining
+                    ))),
+                });
+            }
+
+            if new_file.chunk_crc {
+                MooTestFile::verify_chunk_crc(reader, top_level_chunk_offset, &chunk, test_num)?;
+                // Parse the chunk without its trailing CRC subchunk.
+                chunk.size -= MooTestFile::CHUNK_CRC_SIZE;
+            }
 
             // log::trace!(
             //     "Read chunk: {:?} pos: {:06X} size: {}",
@@ -385,10 +681,24 @@ impl MooTestFile {
                     log::warn!("Unexpected FileHeader chunk!.");
                 }
                 MooChunkType::FileMetadata => {
-                    // Read the file metadata chunk.
-                    let metadata: MooFileMetadata = BinRead::read(reader)?;
+                    // Read the file metadata chunk. The chunk may end before optional trailing
+                    // fields, so parse it from a buffer bounded by the chunk size.
+                    let mut metadata_buf = vec![0; chunk.size as usize];
+                    reader.read_exact(&mut metadata_buf)?;
+                    let metadata = MooFileMetadata::from_payload(&metadata_buf).map_err(|e| binrw::Error::Custom {
+                        pos: top_level_chunk_offset,
+                        err: Box::new(e),
+                    })?;
                     log::debug!("Reading FileMetadata chunk: {:?}", metadata.mnemonic());
-                    new_file.set_metadata(metadata);
+                    // Keep the header tag as written, so that a mismatch can be reported.
+                    new_file.metadata = Some(metadata);
+                    if !new_file.arch_matches_cpu_type() {
+                        log::warn!(
+                            "Architecture tag '{}' does not match metadata CPU type {:?}",
+                            new_file.arch,
+                            new_file.cpu_type()
+                        );
+                    }
                 }
                 MooChunkType::RegisterMask16 => {
                     // Read a top-level `RMSK` chunk.
@@ -400,6 +710,22 @@ impl MooTestFile {
                     let regs = MooRegisters32::read(reader)?;
                     new_file.set_register_mask(MooRegisters::ThirtyTwo(regs));
                 }
+                MooChunkType::TestHeader if test_num == header.test_count as usize => {
+                    log::warn!(
+                        "Skipping TEST chunk beyond the header test count of {}",
+                        header.test_count
+                    );
+                    reader.seek(SeekFrom::Start(
+                        top_level_chunk_offset + MooChunkHeader::SIZE as u64 + chunk.size as u64,
+                    ))?;
+                }
+                MooChunkType::TestHeader if !test_range.contains(&test_num) => {
+                    // Skip tests outside the requested range without decoding them.
+                    test_num += 1;
+                    reader.seek(SeekFrom::Start(
+                        top_level_chunk_offset + MooChunkHeader::SIZE as u64 + chunk.size as u64,
+                    ))?;
+                }
                 MooChunkType::TestHeader => {
                     // Do a sanity check - did the previous test have both required states?
                     if in_test && (!have_initial_state || !have_final_state) {
@@ -430,7 +756,16 @@ impl MooTestFile {
                     test_num += 1;
 
                     // Read the test chunk length into a Cursor.
-                    let mut test_buffer = vec![0; chunk.size as usize - size_of::<MooTestChunk>()];
+                    let body_len = (chunk.size as usize)
+                        .checked_sub(size_of::<MooTestChunk>())
+                        .ok_or_else(|| binrw::Error::Custom {
+                            pos: top_level_chunk_offset,
+                            err: Box::new(MooError::ParseError(format!(
+                                "TEST chunk size ({}) is too small.",
+                                chunk.size
+                            ))),
+                        })?;
+                    let mut test_buffer = vec![0; body_len];
                     // Read the test chunk body into the buffer.
                     reader.read_exact(&mut test_buffer)?;
                     let mut test_reader = Cursor::new(test_buffer);
@@ -439,30 +774,32 @@ impl MooTestFile {
                     let mut final_state = MooTestState::default();
 
                     let mut hash: Option<[u8; 20]> = None;
-                    let mut cycle_vec = Vec::new();
+                    let mut digest: Option<MooTestDigest> = None;
+                    let mut cycle_vec: Vec<MooCycleState> = Vec::new();
 
                     let mut exception = None;
                     let mut gen_metadata: Option<MooTestGenMetadata> = None;
+                    let mut tags: Vec<String> = Vec::new();
+                    let mut anchors: Vec<MooAnchor> = Vec::new();
+                    let mut io_stimulus: Vec<MooIoStimulus> = Vec::new();
+                    let mut vendor_chunks: Vec<MooVendorChunk> = Vec::new();
 
                     loop {
                         // Read the next chunk type.
-                        let bytes_remaining = test_reader.get_ref().len() - test_reader.position() as usize;
+                        let bytes_remaining =
+                            (test_reader.get_ref().len() as u64).saturating_sub(test_reader.position()) as usize;
                         if bytes_remaining == 0 {
-                            if hash.is_none() {
+                            let Some(hash_bytes) = &hash
+                            else {
                                 return Err(binrw::Error::Custom {
                                     pos: top_level_chunk_offset + test_reader.position(),
                                     err: Box::new(MooError::ParseError(
                                         "Test is missing required HASH chunk.".to_string(),
                                     )),
                                 });
-                            }
+                            };
 
-                            let hash_str = hash
-                                .as_ref()
-                                .unwrap()
-                                .iter()
-                                .map(|b| format!("{:02X}", b))
-                                .collect::<String>();
+                            let hash_str = hex_string(hash_bytes);
                             if new_file.hashes.contains_key(&hash_str) {
                                 log::warn!("Duplicate test hash detected: {} in test '{}'", hash_str, test_name);
                             }
@@ -471,15 +808,24 @@ impl MooTestFile {
                             }
 
                             // Push the test to the file.
+                            // All test fields are moved into the new test; nothing is referenced
+                            // again after we break out of the subchunk loop.
                             new_file.add_test(MooTest {
-                                name: test_name.clone(),
-                                gen_metadata: gen_metadata.clone(),
-                                bytes: test_bytes.clone(),
-                                initial_state: initial_state.clone(),
-                                final_state: final_state.clone(),
-                                cycles: cycle_vec.clone(),
-                                exception: exception.clone(),
-                                hash: hash.clone(),
+                                name: test_name,
+                                gen_metadata,
+                                bytes: test_bytes,
+                                initial_state,
+                                final_state,
+                                cycles: cycle_vec,
+                                exception,
+                                hash,
+                                hash_dirty: false,
+                                digest,
+                                tags,
+                                anchors,
+                                io_stimulus,
+                                vendor_chunks,
+                                synthetic: false,
                             });
                             break;
                         }
@@ -499,8 +845,8 @@ impl MooTestFile {
                             MooChunkType::Name => {
                                 // Read the name chunk.
                                 let name_chunk: MooNameChunk = BinRead::read(&mut test_reader)?;
-                                test_name = name_chunk.name.clone();
                                 log::trace!("Reading NAME chunk: name: {} len: {}", name_chunk.name, name_chunk.len);
+                                test_name = name_chunk.name;
                             }
                             MooChunkType::Bytes => {
                                 // Read the bytes chunk.
@@ -527,13 +873,19 @@ impl MooTestFile {
                             }
                             MooChunkType::CycleStates => {
                                 // Read the cycle states chunk.
-                                cycle_vec.clear();
                                 let cycle_count: u32 = BinRead::read_le(&mut test_reader)?;
+                                MooReadOptions::check(
+                                    MooReadLimit::CycleCount,
+                                    cycle_count as u64,
+                                    options.max_cycles as u64,
+                                    top_level_chunk_offset + test_reader.position(),
+                                )?;
                                 //log::debug!("Reading {} cycles", cycle_count);
-                                for _ in 0..cycle_count {
-                                    let cycle_state = MooCycleState::read(&mut test_reader)?;
-                                    cycle_vec.push(cycle_state);
-                                }
+                                cycle_vec = MooTestFile::read_cycles(
+                                    &mut test_reader,
+                                    cycle_count as usize,
+                                    top_level_chunk_offset,
+                                )?;
                             }
                             MooChunkType::Hash => {
                                 // Read the hash chunk.
@@ -545,15 +897,66 @@ impl MooTestFile {
                                 // );
                                 hash = Some(hash_chunk.hash);
                             }
+                            MooChunkType::Digest => {
+                                let digest_chunk = MooDigestChunk::read(&mut test_reader)?;
+                                match MooTestDigest::try_from(digest_chunk) {
+                                    Ok(test_digest) => digest = Some(test_digest),
+                                    Err(e) => log::warn!("Skipping DGST chunk: {}", e),
+                                }
+                            }
                             MooChunkType::Exception => {
                                 // Read the exception chunk.
                                 let exception_chunk = MooException::read(&mut test_reader)?;
                                 exception = Some(exception_chunk);
                             }
                             MooChunkType::GeneratorMetadata => {
+                                // The GMET layout may grow; read the known fields and skip the rest.
+                                let size = next_chunk.size as usize;
+                                if size < MooTestGenMetadata::SIZE {
+                                    return Err(binrw::Error::Custom {
+                                        pos: top_level_chunk_offset + test_reader.position(),
+                                        err: Box::new(MooError::ParseError(format!(
+                                            "GMET chunk length {} is shorter than {} bytes",
+                                            size,
+                                            MooTestGenMetadata::SIZE
+                                        ))),
+                                    });
+                                }
                                 let gen_metadata_chunk = MooTestGenMetadata::read(&mut test_reader)?;
+                                if size > MooTestGenMetadata::SIZE {
+                                    log::warn!(
+                                        "Skipping {} unknown bytes in GMET chunk",
+                                        size - MooTestGenMetadata::SIZE
+                                    );
+                                    test_reader.seek(SeekFrom::Current((size - MooTestGenMetadata::SIZE) as i64))?;
+                                }
                                 gen_metadata = Some(gen_metadata_chunk);
                             }
+                            MooChunkType::Tags => {
+                                let tags_chunk = MooTagsChunk::read(&mut test_reader)?;
+                                tags = tags_chunk.tags.into_iter().map(|tag| tag.name).collect();
+                                tags.sort_unstable();
+                                tags.dedup();
+                            }
+                            MooChunkType::Anchors => {
+                                let anchors_chunk = MooAnchorsChunk::read(&mut test_reader)?;
+                                anchors = anchors_chunk.to_anchors();
+                                anchors.sort_by_key(|a| a.kind);
+                                anchors.dedup_by_key(|a| a.kind);
+                            }
+                            MooChunkType::IoStimulus => {
+                                let stimulus_chunk = MooIoStimulusChunk::read(&mut test_reader)?;
+                                io_stimulus = stimulus_chunk.to_stimulus();
+                                io_stimulus.sort_by_key(|s| (s.port, s.width));
+                                io_stimulus.dedup_by_key(|s| (s.port, s.width));
+                            }
+                            MooChunkType::Vendor(id) => {
+                                vendor_chunks.push(MooTestFile::read_vendor_chunk(
+                                    &mut test_reader,
+                                    id,
+                                    next_chunk.size,
+                                )?);
+                            }
                             _ => {
                                 log::warn!(
                                     "Unexpected chunk type in test: {:?}, skipping next {} bytes",
@@ -566,13 +969,140 @@ impl MooTestFile {
                         }
                     }
                 }
-                _ => break, // End of file or unknown chunk type
+                MooChunkType::License => {
+                    // Parse the license from a buffer bounded by the chunk size, so that a chunk
+                    // extended by a later writer leaves the reader at the next chunk.
+                    let mut license_buf = vec![0; chunk.size as usize];
+                    reader.read_exact(&mut license_buf)?;
+                    let license_chunk = MooLicenseChunk::read(&mut Cursor::new(&license_buf))?;
+                    new_file.license = Some(MooLicense::from(license_chunk));
+                }
+                MooChunkType::Vendor(id) => {
+                    let vendor_chunk = MooTestFile::read_vendor_chunk(reader, id, chunk.size)?;
+                    new_file.vendor_chunks.push(vendor_chunk);
+                }
+                MooChunkType::EndOfFile => {
+                    let end_chunk = MooEndChunk::read(reader)?;
+                    if test_num < header.test_count as usize || end_chunk.test_count != header.test_count {
+                        return Err(binrw::Error::Custom {
+                            pos: top_level_chunk_offset,
+                            err: Box::new(MooError::ParseError(format!(
+                                "END chunk reports {} tests after reading {} of {}.",
+                                end_chunk.test_count, test_num, header.test_count
+                            ))),
+                        });
+                    }
+                    new_file.end_chunk = true;
+                    log::trace!(
+                        "Reached END chunk, {} bytes remaining in reader.",
+                        reader_len.saturating_sub(reader.stream_position()?)
+                    );
+                    break;
+                }
+                other => {
+                    match other.raw_id() {
+                        Some(id) => log::warn!(
+                            "Skipping unknown top-level chunk '{}' ({} bytes)",
+                            String::from_utf8_lossy(&id),
+                            chunk.size
+                        ),
+                        None => log::warn!("Skipping unexpected top-level chunk {:?} ({} bytes)", other, chunk.size),
+                    }
+                    reader.seek(SeekFrom::Start(
+                        top_level_chunk_offset + MooChunkHeader::SIZE as u64 + chunk.size as u64,
+                    ))?;
+                }
+            }
+
+            if new_file.chunk_crc {
+                // Skip past the chunk and its trailing CRC, regardless of how much of the chunk we parsed.
+                reader.seek(SeekFrom::Start(
+                    top_level_chunk_offset
+                        + MooChunkHeader::SIZE as u64
+                        + chunk.size as u64
+                        + MooTestFile::CHUNK_CRC_SIZE as u64,
+                ))?;
             }
         }
 
         Ok(new_file)
     }
 
+    /// Verify the `CRC ` subchunk that ends the top-level chunk starting at `chunk_offset`. The
+    /// reader is expected to be positioned just after the chunk header, and is returned there on
+    /// success.
+    fn verify_chunk_crc<RS: Read + Seek>(
+        reader: &mut RS,
+        chunk_offset: u64,
+        chunk: &MooChunkHeader,
+        test_num: usize,
+    ) -> BinResult<()> {
+        let payload_offset = reader.stream_position()?;
+        let mut chunk_buffer = vec![0; MooChunkHeader::SIZE + chunk.size as usize];
+        reader.seek(SeekFrom::Start(chunk_offset))?;
+        reader.read_exact(&mut chunk_buffer)?;
+
+        let chunk_desc = match chunk.chunk_type {
+            MooChunkType::TestHeader => format!("TEST chunk for test {}", test_num),
+            other => format!("{:?} chunk", other),
+        };
+        let checksum_error = |msg: String| binrw::Error::Custom {
+            pos: chunk_offset,
+            err: Box::new(MooError::ChecksumError(format!(
+                "{} at offset {:08X}: {}",
+                chunk_desc, chunk_offset, msg
+            ))),
+        };
+
+        // The CRC subchunk is counted in the chunk size, so it is the last thing in the buffer.
+        let crc_offset = (chunk_buffer.len() as u64)
+            .checked_sub(MooTestFile::CHUNK_CRC_SIZE as u64)
+            .filter(|offset| *offset >= MooChunkHeader::SIZE as u64)
+            .ok_or_else(|| checksum_error("chunk is too small to hold a CRC".to_string()))?;
+        let mut crc_reader = Cursor::new(&chunk_buffer[crc_offset as usize..]);
+        MooChunkHeader::read(&mut crc_reader)
+            .ok()
+            .filter(|header| matches!(header.chunk_type, MooChunkType::ChunkCrc) && header.size == 4)
+            .ok_or_else(|| checksum_error("CRC subchunk is missing".to_string()))?;
+        let stored_crc: u32 = BinRead::read_le(&mut crc_reader)?;
+        let computed_crc = crc32fast::hash(&chunk_buffer[..crc_offset as usize]);
+
+        if stored_crc != computed_crc {
+            return Err(checksum_error(format!(
+                "stored CRC {:08X}, computed {:08X}",
+                stored_crc, computed_crc
+            )));
+        }
+
+        reader.seek(SeekFrom::Start(payload_offset))?;
+        Ok(())
+    }
+
+    /// Read the payload of a vendor chunk with the given `id` and `size`.
+    fn read_vendor_chunk<RS: Read + Seek>(reader: &mut RS, id: [u8; 4], size: u32) -> BinResult<MooVendorChunk> {
+        let pos = reader.stream_position()?;
+        let remaining = MooTestFile::get_reader_len(reader)?.saturating_sub(pos);
+        if size as u64 > remaining {
+            return Err(binrw::Error::Custom {
+                pos,
+                err: Box::new(MooError::ParseError(format!(
+                    "Vendor chunk '{}' size ({}) exceeds the remaining data ({} bytes).",
+                    String::from_utf8_lossy(&id),
+                    size,
+                    remaining
+                ))),
+            });
+        }
+        let mut data = vec![0; size as usize];
+        reader.read_exact(&mut data)?;
+        log::trace!(
+            "Preserving vendor chunk '{}' ({} bytes)",
+            String::from_utf8_lossy(&id),
+            size
+        );
+        Ok(MooVendorChunk { id, data })
+    }
+
     fn get_reader_len<RS: Read + Seek>(reader: &mut RS) -> BinResult<u64> {
         // Get the current position in the stream.
         let saved_pos = reader.stream_position()?;
@@ -585,6 +1115,40 @@ impl MooTestFile {
         Ok(len)
     }
 
+    /// Decode `cycle_count` cycle records from the current position of `test_reader` in one pass.
+    /// The cycle records are sliced directly out of the test buffer rather than read field by
+    /// field, and the returned vector is allocated once at its final size.
+    fn read_cycles(
+        test_reader: &mut Cursor<Vec<u8>>,
+        cycle_count: usize,
+        chunk_offset: u64,
+    ) -> BinResult<Vec<MooCycleState>> {
+        let start = test_reader.position() as usize;
+        let cycle_bytes_len = cycle_count * MooCycleState::RECORD_SIZE;
+        let buffer = test_reader.get_ref();
+
+        if cycle_bytes_len > buffer.len().saturating_sub(start) {
+            return Err(binrw::Error::Custom {
+                pos: chunk_offset + start as u64,
+                err: Box::new(MooError::ParseError(format!(
+                    "Cycle count ({}) exceeds the remaining test data ({} bytes).",
+                    cycle_count,
+                    buffer.len().saturating_sub(start)
+                ))),
+            });
+        }
+
+        let mut cycles = Vec::with_capacity(cycle_count);
+        cycles.extend(
+            buffer[start..start + cycle_bytes_len]
+                .chunks_exact(MooCycleState::RECORD_SIZE)
+                .map(|record| MooCycleState::from_le_bytes(record.try_into().unwrap())),
+        );
+
+        test_reader.set_position((start + cycle_bytes_len) as u64);
+        Ok(cycles)
+    }
+
     fn read_state<RS: Read + Seek>(
         s_type: MooStateType,
         reader: &mut RS,
@@ -602,6 +1166,7 @@ impl MooTestFile {
             queue: Vec::new(),
             ea: None,
             ram: Vec::new(),
+            vendor_chunks: Vec::new(),
         };
 
         // Get stream length.
@@ -662,6 +1227,16 @@ impl MooTestFile {
                 MooChunkType::QueueState => {
                     // Read the queue chunk.
                     let queue = MooBytesChunk::read(reader)?;
+                    if next_chunk.size as usize != 4 + queue.bytes.len() {
+                        return Err(binrw::Error::Custom {
+                            pos: reader.stream_position().unwrap_or(0),
+                            err: Box::new(MooError::ParseError(format!(
+                                "QUEU chunk length {} does not match declared queue length {}",
+                                next_chunk.size,
+                                queue.bytes.len()
+                            ))),
+                        });
+                    }
                     new_state.queue = queue.bytes;
                     have_queue = true;
                 }
@@ -669,6 +1244,14 @@ impl MooTestFile {
                     let ea = MooEffectiveAddress::read(reader)?;
                     new_state.ea = Some(ea);
                 }
+                MooChunkType::Descriptors32 => {
+                    let descriptors = MooDescriptors32::read(reader)?;
+                    new_state.descriptors = Some(MooDescriptors::ThirtyTwo(descriptors));
+                }
+                MooChunkType::Vendor(id) => {
+                    let vendor_chunk = MooTestFile::read_vendor_chunk(reader, id, next_chunk.size)?;
+                    new_state.vendor_chunks.push(vendor_chunk);
+                }
                 _ => {
                     log::warn!("Unexpected chunk type in test state: {:?}", next_chunk.chunk_type);
                     // Skip the chunk by advancing reader.
@@ -682,46 +1265,105 @@ impl MooTestFile {
     /// # Arguments:
     /// * `writer` - The writer to write the `MOO` file to.
     /// * `preserve_hash` - If true, preserves the existing test hashes, if present. If false, test
-    ///      hashes will be recalculated from the test data. Test hashes will be recalculated if
-    ///      missing, regardless of this flag.
+    ///   hashes will be recalculated from the test data. Test hashes will be recalculated if
+    ///   missing, or if the test was modified through the edit journal or a method that marks its
+    ///   hash dirty, regardless of this flag.
     pub fn write<WS: Write + Seek>(&self, writer: &mut WS, preserve_hash: bool) -> BinResult<()> {
-        #[cfg(feature = "gzip")]
-        let mut file_writer = if self.compressed {
-            // Wrap the writer in a GzEncoder
-            use flate2::{write::GzEncoder, Compression};
-            let encoder = GzEncoder::new(writer, Compression::new(9));
-            Box::new(encoder) as Box<dyn Write>
+        self.write_with_options(writer, &MooWriteOptions::new().with_preserve_hash(preserve_hash))
+    }
+
+    /// Write a [MooTestFile] to an implementor of [Write] + [Seek] with the specified
+    /// [MooWriteOptions]. See [MooTestFile::write_stream].
+    pub fn write_with_options<WS: Write + Seek>(&self, writer: &mut WS, options: &MooWriteOptions) -> BinResult<()> {
+        self.write_stream(writer, options)?;
+        Ok(())
+    }
+
+    /// Write a [MooTestFile] to any implementor of [Write], such as a pipe or socket, and return
+    /// the writer once all output has been flushed to it.
+    ///
+    /// Output is buffered with a buffer of `options.buffer_size` bytes. The file is compressed
+    /// as selected by `options.compression`, or if that is unset, with the compression the file
+    /// was read with or set to by [MooTestFile::set_compression]. The compressed stream is
+    /// finished explicitly, so errors writing the trailer are returned rather than lost on drop.
+    pub fn write_stream<W: Write>(&self, writer: W, options: &MooWriteOptions) -> BinResult<W> {
+        let compression = options.compression.unwrap_or(self.compression);
+        let mut buffered = BufWriter::with_capacity(options.buffer_size.max(1), writer);
+
+        match compression {
+            MooCompressionKind::Uncompressed => self.write_body(&mut buffered, options)?,
+            #[cfg(feature = "gzip")]
+            MooCompressionKind::Gzip => {
+                use flate2::{write::GzEncoder, Compression};
+                let level = Compression::new(options.compression_level.min(9));
+                let mut encoder = GzEncoder::new(buffered, level);
+                self.write_body(&mut encoder, options)?;
+                buffered = encoder.finish()?;
+            }
+            #[cfg(feature = "zstd")]
+            MooCompressionKind::Zstd => {
+                let level = options.compression_level.min(22) as i32;
+                let mut encoder = zstd::Encoder::new(buffered, level)?;
+                self.write_body(&mut encoder, options)?;
+                buffered = encoder.finish()?;
+            }
+            #[cfg(not(feature = "gzip"))]
+            MooCompressionKind::Gzip => {
+                return Err(binrw::Error::Io(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "gzip support is not enabled",
+                )))
+            }
+            #[cfg(not(feature = "zstd"))]
+            MooCompressionKind::Zstd => {
+                return Err(binrw::Error::Io(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "zstd support is not enabled",
+                )))
+            }
         }
-        else {
-            Box::new(writer) as Box<dyn Write>
-        };
 
-        #[cfg(not(feature = "gzip"))]
-        let mut file_writer = writer;
+        let mut writer = buffered.into_inner().map_err(|e| e.into_error())?;
+        writer.flush()?;
+        Ok(writer)
+    }
 
+    /// Write the file header, top-level chunks and tests to `file_writer`.
+    fn write_body<W: Write>(&self, file_writer: &mut W, options: &MooWriteOptions) -> BinResult<()> {
+        let preserve_hash = options.preserve_hash;
         let mut cursor = Cursor::new(Vec::<u8>::new());
 
+        let mut flags = 0;
+        if self.chunk_crc {
+            flags |= MooFileHeader::FLAG_CHUNK_CRC32;
+        }
+        if self.end_chunk {
+            flags |= MooFileHeader::FLAG_END_CHUNK;
+        }
+
         // Write the file header chunk.
         MooChunkType::FileHeader.write(
             &mut cursor,
             &MooFileHeader {
                 major_version: self.major_version,
                 minor_version: self.minor_version,
-                reserved: [0; 2],
+                flags,
+                reserved: 0,
                 test_count: self.tests.len() as u32,
-                cpu_id: self.arch.clone().into_bytes()[0..4]
-                    .try_into()
-                    .expect("CPU Name must be <=4 chars"),
+                cpu_id: self.arch,
             },
         )?;
 
         // Write the file metadata chunk, if present
         if let Some(metadata) = &self.metadata {
-            MooChunkType::FileMetadata.write(&mut cursor, metadata)?;
+            let chunk_start = cursor.position() as usize;
+            MooChunkType::FileMetadata.write(&mut cursor, &metadata.to_payload()?)?;
+            self.write_chunk_crc(&mut cursor, chunk_start)?;
         }
 
         // Write the register mask chunk, if present
         if let Some(register_mask) = &self.register_mask {
+            let chunk_start = cursor.position() as usize;
             match register_mask {
                 MooRegisters::Sixteen(regs) => {
                     MooChunkType::RegisterMask16.write(&mut cursor, regs)?;
@@ -730,18 +1372,139 @@ impl MooTestFile {
                     MooChunkType::RegisterMask32.write(&mut cursor, regs)?;
                 }
             }
+            self.write_chunk_crc(&mut cursor, chunk_start)?;
+        }
+
+        // Write the license chunk, if present
+        if let Some(license) = &self.license {
+            let chunk_start = cursor.position() as usize;
+            MooChunkType::License.write(&mut cursor, &MooLicenseChunk::from(license))?;
+            self.write_chunk_crc(&mut cursor, chunk_start)?;
+        }
+
+        // Write any top-level vendor chunks.
+        for vendor_chunk in &self.vendor_chunks {
+            let chunk_start = cursor.position() as usize;
+            MooChunkType::Vendor(vendor_chunk.id).write(&mut cursor, &vendor_chunk.data)?;
+            self.write_chunk_crc(&mut cursor, chunk_start)?;
         }
 
         // Write the file header + metadata to the file writer.
         file_writer.write_all(&cursor.into_inner())?;
 
+        let stale_ct = self.tests.iter().filter(|test| test.is_hash_dirty()).count();
+        if preserve_hash && stale_ct > 0 {
+            log::warn!(
+                "Not preserving stale hashes of {} modified test(s); recalculating",
+                stale_ct
+            );
+        }
+
         // Write all the tests.
         for (ti, test) in self.tests.iter().enumerate() {
             let mut cursor = Cursor::new(Vec::<u8>::new());
-            test.write(ti, &mut cursor, preserve_hash)?;
+            test.write_with_digest(
+                ti,
+                &mut cursor,
+                preserve_hash && !self.journal.is_dirty(ti),
+                options.digest,
+            )?;
+            self.write_chunk_crc(&mut cursor, 0)?;
+            file_writer.write_all(&cursor.into_inner())?;
+        }
+
+        // Terminate the file with an END chunk, if enabled.
+        if self.end_chunk {
+            let mut cursor = Cursor::new(Vec::<u8>::new());
+            MooChunkType::EndOfFile.write(
+                &mut cursor,
+                &MooEndChunk {
+                    test_count: self.tests.len() as u32,
+                },
+            )?;
+            self.write_chunk_crc(&mut cursor, 0)?;
             file_writer.write_all(&cursor.into_inner())?;
         }
 
         Ok(())
     }
+
+    /// Write a [MooTestFile] to the file at `path`.
+    ///
+    /// The compression is set from the file extension: `.gz` selects gzip, `.zst` or `.zstd`
+    /// selects zstd, and any other extension writes an uncompressed file, unless
+    /// `options.compression` is set. Missing parent directories are created. The file is first
+    /// written and synced alongside `path` and then renamed over it, so that an existing file is
+    /// never left partially written.
+    /// # Arguments:
+    /// * `path` - The path of the `MOO` file to write.
+    /// * `options` - The [MooWriteOptions] to write with.
+    pub fn write_to_path(&mut self, path: impl AsRef<Path>, options: &MooWriteOptions) -> Result<(), MooError> {
+        let path = path.as_ref();
+        self.compression = Self::compression_for_path(path)?;
+
+        let io_err = |e: io::Error| MooError::WriteError(format!("{}: {}", path.display(), e));
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(io_err)?;
+        }
+
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| MooError::WriteError(format!("{}: missing file name", path.display())))?;
+        let mut tmp_name = std::ffi::OsString::from(".");
+        tmp_name.push(file_name);
+        tmp_name.push(format!(".{}.tmp", std::process::id()));
+        let tmp_path = path.with_file_name(tmp_name);
+
+        let result = fs::File::create(&tmp_path).map_err(io_err).and_then(|mut file| {
+            self.write_with_options(&mut file, options)
+                .map_err(|e| MooError::WriteError(format!("{}: {}", path.display(), e)))?;
+            // Make sure the contents are on disk before the rename makes them visible.
+            file.sync_all().map_err(io_err)
+        });
+        match result.and_then(|_| fs::rename(&tmp_path, path).map_err(io_err)) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                _ = fs::remove_file(&tmp_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// Determine the compression of a file written to `path`, from its extension.
+    fn compression_for_path(path: &Path) -> Result<MooCompressionKind, MooError> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("gz") if cfg!(feature = "gzip") => Ok(MooCompressionKind::Gzip),
+            Some("gz") => Err(MooError::WriteError(format!(
+                "{}: gzip support is not enabled",
+                path.display()
+            ))),
+            Some("zst") | Some("zstd") if cfg!(feature = "zstd") => Ok(MooCompressionKind::Zstd),
+            Some("zst") | Some("zstd") => Err(MooError::WriteError(format!(
+                "{}: zstd support is not enabled",
+                path.display()
+            ))),
+            _ => Ok(MooCompressionKind::Uncompressed),
+        }
+    }
+
+    /// Append a `CRC ` subchunk to the chunk written to `cursor` from `chunk_start` onwards, if
+    /// per-chunk checksums are enabled. The chunk's size is grown to include the subchunk, so
+    /// readers that don't check the CRC skip it with the rest of the chunk.
+    fn write_chunk_crc(&self, cursor: &mut Cursor<Vec<u8>>, chunk_start: usize) -> BinResult<()> {
+        if self.chunk_crc {
+            let size_range = chunk_start + 4..chunk_start + MooChunkHeader::SIZE;
+            let buffer = cursor.get_mut();
+            let size = u32::from_le_bytes(buffer[size_range.clone()].try_into().unwrap()) + Self::CHUNK_CRC_SIZE;
+            buffer[size_range].copy_from_slice(&size.to_le_bytes());
+
+            let crc = crc32fast::hash(&cursor.get_ref()[chunk_start..]);
+            MooChunkType::ChunkCrc.write(cursor, &crc.to_le_bytes().to_vec())?;
+        }
+        Ok(())
+    }
 }
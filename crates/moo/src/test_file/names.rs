@@ -0,0 +1,106 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Regeneration of test names from the disassembly of their instructions.
+//!
+//! Test names are normally the disassembly of the test instruction, but generators have used
+//! different disassemblers and syntaxes over time. [MooTestFile::regenerate_names] rebuilds every
+//! name in a file with one [MooNameFormatter], so that a corpus can be brought to a single style.
+
+use std::fmt::Display;
+
+use crate::{
+    test::moo_test::MooTest,
+    test_file::{journal::MooTestEdit, MooTestFile},
+    types::MooCpuType,
+};
+
+/// A [MooNameFormatter] produces the name of a test, typically by disassembling its instruction
+/// bytes in a particular syntax.
+pub trait MooNameFormatter {
+    /// Returns a short identifier for the naming style, such as `nasm`.
+    fn style(&self) -> &str;
+
+    /// Format the name of `test`, a test for the given [MooCpuType]. Returns None if the
+    /// instruction could not be formatted.
+    fn format_name(&mut self, cpu_type: MooCpuType, test: &MooTest) -> Option<String>;
+}
+
+/// A [MooRenameSummary] records the outcome of [MooTestFile::regenerate_names]. Its [Display]
+/// form is suitable for an edit log.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooRenameSummary {
+    /// The naming style applied, from [MooNameFormatter::style].
+    pub style: String,
+    /// The number of tests whose names changed.
+    pub renamed: usize,
+    /// The number of tests that already had the formatted name.
+    pub unchanged: usize,
+    /// The indices of tests the formatter could not name. Their names are left as they were.
+    pub failed: Vec<usize>,
+}
+
+impl Display for MooRenameSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "regenerated names in {} style: {} renamed, {} unchanged, {} failed",
+            self.style,
+            self.renamed,
+            self.unchanged,
+            self.failed.len()
+        )
+    }
+}
+
+impl MooRenameSummary {
+    /// Returns true if any test was renamed.
+    pub fn changed(&self) -> bool {
+        self.renamed > 0
+    }
+}
+
+/// Name regeneration
+impl MooTestFile {
+    /// Rebuild the name of every test with `formatter`. Each changed name is applied as a
+    /// journaled [MooTestEdit::SetName], so the renames can be undone, and renamed tests are
+    /// re-hashed when the file is written.
+    pub fn regenerate_names(&mut self, formatter: &mut impl MooNameFormatter) -> MooRenameSummary {
+        let mut summary = MooRenameSummary {
+            style: formatter.style().to_string(),
+            ..Default::default()
+        };
+
+        for index in 0..self.tests.len() {
+            match formatter.format_name(self.cpu_type(), &self.tests[index]) {
+                Some(name) if name == self.tests[index].name() => summary.unchanged += 1,
+                Some(name) => {
+                    self.edit_test(index, MooTestEdit::SetName(name));
+                    summary.renamed += 1;
+                }
+                None => summary.failed.push(index),
+            }
+        }
+        summary
+    }
+}
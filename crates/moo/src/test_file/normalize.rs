@@ -0,0 +1,163 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Normalization of the nondeterministic parts of a [MooTestFile], so that two releases of a
+//! corpus built from the same tests compare byte for byte.
+//!
+//! Generator seeds, capture details and the order in which a generator happened to emit tests vary
+//! between runs without changing what the tests check. A [MooNormalizeProfile] selects which of
+//! these [MooTestFile::normalize] canonicalizes. Seeds and capture fields are cleared with the
+//! same selectors as [MooTestFile::redact], and tests are put in canonical order by their content
+//! hash.
+
+use std::{fmt::Display, str::FromStr};
+
+use binrw::BinResult;
+
+use crate::{
+    test_file::{
+        redact::{MooRedaction, MooRedactionSummary},
+        MooTestFile,
+    },
+    types::{errors::MooError, hash::MooHashAlgorithm},
+};
+
+/// A [MooNormalizeProfile] selects the fields [MooTestFile::normalize] canonicalizes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooNormalizeProfile {
+    /// Zero the generator seed of each test and the file seed.
+    Seeds,
+    /// Zero the seeds, blank the capture tool and board ID, and sort the tests into canonical
+    /// order.
+    Release,
+}
+
+impl MooNormalizeProfile {
+    /// The redactions the profile applies.
+    pub fn redactions(&self) -> Vec<MooRedaction> {
+        let mut redactions = vec![MooRedaction::GeneratorSeed, MooRedaction::FileSeed];
+        if matches!(self, MooNormalizeProfile::Release) {
+            redactions.extend([MooRedaction::CaptureTool, MooRedaction::BoardId]);
+        }
+        redactions
+    }
+
+    /// Returns true if the profile sorts tests into canonical order.
+    pub fn sorts_tests(&self) -> bool {
+        matches!(self, MooNormalizeProfile::Release)
+    }
+}
+
+impl FromStr for MooNormalizeProfile {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "seeds" => Ok(MooNormalizeProfile::Seeds),
+            "release" => Ok(MooNormalizeProfile::Release),
+            _ => Err(MooError::ParseError(format!("unknown normalization profile '{}'", s))),
+        }
+    }
+}
+
+impl Display for MooNormalizeProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooNormalizeProfile::Seeds => write!(f, "seeds"),
+            MooNormalizeProfile::Release => write!(f, "release"),
+        }
+    }
+}
+
+/// A [MooNormalizeSummary] records the outcome of [MooTestFile::normalize]. Its [Display] form
+/// names the profile and is meant as the provenance entry in an edit log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooNormalizeSummary {
+    pub profile: MooNormalizeProfile,
+    /// The outcome of the profile's redactions.
+    pub redaction: MooRedactionSummary,
+    /// The number of tests that changed position when sorted.
+    pub tests_moved: usize,
+}
+
+impl MooNormalizeSummary {
+    /// Returns true if anything was changed.
+    pub fn changed(&self) -> bool {
+        self.redaction.changed() || self.tests_moved > 0
+    }
+}
+
+impl Display for MooNormalizeSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "normalized with {} profile: {}", self.profile, self.redaction)?;
+        if self.profile.sorts_tests() {
+            write!(f, ", {} tests reordered", self.tests_moved)?;
+        }
+        Ok(())
+    }
+}
+
+/// Normalization
+impl MooTestFile {
+    /// Canonicalize the fields selected by `profile` across the file and its tests.
+    ///
+    /// Redactions are applied first, so that the canonical order doesn't depend on the seeds being
+    /// removed. All changes go through the edit journal and can be undone.
+    pub fn normalize(&mut self, profile: MooNormalizeProfile) -> BinResult<MooNormalizeSummary> {
+        let redaction = self.redact(&profile.redactions());
+        let tests_moved = if profile.sorts_tests() {
+            self.sort_tests_canonical()?
+        }
+        else {
+            0
+        };
+        Ok(MooNormalizeSummary {
+            profile,
+            redaction,
+            tests_moved,
+        })
+    }
+
+    /// Sort the tests by the SHA-1 digest of their content, leaving out their position in the file,
+    /// so that the same set of tests always ends up in the same order. Tests are moved with
+    /// [MooTestFile::move_test]. Returns the number of tests whose position changed.
+    pub fn sort_tests_canonical(&mut self) -> BinResult<usize> {
+        let mut keys = Vec::with_capacity(self.tests.len());
+        for (index, test) in self.tests.iter().enumerate() {
+            keys.push((test.compute_digest(0, MooHashAlgorithm::Sha1)?.digest, index));
+        }
+        keys.sort();
+
+        // `current` holds the original index of the test at each position as moves are made.
+        let mut current: Vec<usize> = (0..self.tests.len()).collect();
+        for (to, &(_, original)) in keys.iter().enumerate() {
+            let from = to + current[to..].iter().position(|&i| i == original).unwrap_or(0);
+            if from != to {
+                self.move_test(from, to);
+                let moved = current.remove(from);
+                current.insert(to, moved);
+            }
+        }
+        Ok(current.iter().enumerate().filter(|&(pos, &i)| pos != i).count())
+    }
+}
@@ -0,0 +1,129 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fmt::Display, ops::Range};
+
+/// A size limit enforced while reading a [MooTestFile](crate::prelude::MooTestFile). Reported by
+/// [MooError::LimitExceeded](crate::types::errors::MooError::LimitExceeded).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooReadLimit {
+    /// The number of tests declared in the file header.
+    TestCount,
+    /// The size of a top-level chunk.
+    ChunkSize,
+    /// The number of cycles in a single test.
+    CycleCount,
+}
+
+impl Display for MooReadLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooReadLimit::TestCount => write!(f, "test count"),
+            MooReadLimit::ChunkSize => write!(f, "chunk size"),
+            MooReadLimit::CycleCount => write!(f, "cycle count"),
+        }
+    }
+}
+
+/// [MooReadOptions] controls how a [MooTestFile](crate::prelude::MooTestFile) is read.
+/// Pass to [MooTestFile::read_with_options](crate::prelude::MooTestFile::read_with_options).
+///
+/// The size limits guard against corrupt or hostile files that declare huge counts or sizes.
+/// The defaults are well above anything a real test set contains.
+#[derive(Clone, Debug)]
+pub struct MooReadOptions {
+    /// The maximum number of tests a file may declare.
+    pub max_tests: usize,
+    /// The maximum size of a top-level chunk, in bytes.
+    pub max_chunk_size: u32,
+    /// The maximum number of cycles in a single test.
+    pub max_cycles: usize,
+    /// If set, only tests with an index in this range are decoded.
+    pub test_range: Option<Range<usize>>,
+}
+
+impl Default for MooReadOptions {
+    fn default() -> Self {
+        Self {
+            max_tests: Self::DEFAULT_MAX_TESTS,
+            max_chunk_size: Self::DEFAULT_MAX_CHUNK_SIZE,
+            max_cycles: Self::DEFAULT_MAX_CYCLES,
+            test_range: None,
+        }
+    }
+}
+
+impl MooReadOptions {
+    /// The default limit on the number of tests in a file.
+    pub const DEFAULT_MAX_TESTS: usize = 1 << 20;
+    /// The default limit on the size of a top-level chunk (64 MiB).
+    pub const DEFAULT_MAX_CHUNK_SIZE: u32 = 64 << 20;
+    /// The default limit on the number of cycles in a test.
+    pub const DEFAULT_MAX_CYCLES: usize = 1 << 16;
+
+    /// Create a new [MooReadOptions] with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to set the maximum number of tests a file may declare.
+    pub fn with_max_tests(mut self, max_tests: usize) -> Self {
+        self.max_tests = max_tests;
+        self
+    }
+
+    /// Builder-style method to set the maximum size of a top-level chunk, in bytes.
+    pub fn with_max_chunk_size(mut self, max_chunk_size: u32) -> Self {
+        self.max_chunk_size = max_chunk_size;
+        self
+    }
+
+    /// Builder-style method to set the maximum number of cycles in a single test.
+    pub fn with_max_cycles(mut self, max_cycles: usize) -> Self {
+        self.max_cycles = max_cycles;
+        self
+    }
+
+    /// Builder-style method to decode only the tests with an index in `test_range`.
+    ///
+    /// Tests before the range are skipped without being decoded, and reading stops after the
+    /// last test in the range, so any top-level chunks following it (such as the `END ` and
+    /// `LICN` chunks) are not read. Use [MooTestFile::read_header](crate::prelude::MooTestFile::read_header)
+    /// to find the number of tests in a file.
+    pub fn with_test_range(mut self, test_range: Range<usize>) -> Self {
+        self.test_range = Some(test_range);
+        self
+    }
+
+    /// Return a [MooError::LimitExceeded](crate::types::errors::MooError::LimitExceeded) error
+    /// at `pos` if `value` exceeds `max`.
+    pub(crate) fn check(limit: MooReadLimit, value: u64, max: u64, pos: u64) -> binrw::BinResult<()> {
+        if value > max {
+            return Err(binrw::Error::Custom {
+                pos,
+                err: Box::new(crate::types::errors::MooError::LimitExceeded { limit, value, max }),
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Removal of capture details from a [MooTestFile] before publishing.
+//!
+//! A [MooRedaction] selects a chunk to drop or a field to clear. Selectors are written as a chunk
+//! ID, such as `GMET`, or a chunk ID and field name, such as `META.board_id`. See
+//! [MooTestFile::redact].
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    test::moo_test::MooTest,
+    test_file::{journal::MooTestEdit, MooTestFile},
+    types::{chunks::MooChunkType, errors::MooError, vendor::MooVendorChunk},
+};
+
+/// The IDs of the optional chunks a [MooRedaction::Chunk] may remove, besides vendor chunks.
+const REMOVABLE_CHUNKS: [&[u8; 4]; 6] = [b"GMET", b"DGST", b"TAGS", b"ANCH", b"IOST", b"LICN"];
+const LICN: [u8; 4] = *b"LICN";
+const GMET: [u8; 4] = *b"GMET";
+
+/// A [MooRedaction] selects a chunk or field for [MooTestFile::redact] to remove.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooRedaction {
+    /// Remove a chunk by ID. Only optional chunks can be removed: `GMET`, `DGST`, `TAGS`, `ANCH`,
+    /// `IOST`, `LICN`, and vendor chunks at the file, test or state level. Use
+    /// [MooRedaction::chunk] to check the ID.
+    Chunk([u8; 4]),
+    /// Zero the seed of each test's `GMET` chunk, keeping the generation count.
+    GeneratorSeed,
+    /// Zero the file seed in the `META` chunk.
+    FileSeed,
+    /// Blank the capture board ID in the `META` chunk.
+    BoardId,
+    /// Blank the capture tool name in the `META` chunk.
+    CaptureTool,
+}
+
+impl MooRedaction {
+    /// Create a [MooRedaction::Chunk], checking that the chunk is optional.
+    pub fn chunk(id: [u8; 4]) -> Result<Self, MooError> {
+        if REMOVABLE_CHUNKS.contains(&&id) || MooChunkType::is_vendor_id(&id) {
+            Ok(MooRedaction::Chunk(id))
+        }
+        else {
+            Err(MooError::ParseError(format!(
+                "chunk '{}' is not optional and cannot be redacted",
+                String::from_utf8_lossy(&id)
+            )))
+        }
+    }
+}
+
+impl FromStr for MooRedaction {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('.') {
+            Some(("GMET", "seed")) => Ok(MooRedaction::GeneratorSeed),
+            Some(("META", "file_seed")) => Ok(MooRedaction::FileSeed),
+            Some(("META", "board_id")) => Ok(MooRedaction::BoardId),
+            Some(("META", "capture_tool")) => Ok(MooRedaction::CaptureTool),
+            Some(_) => Err(MooError::ParseError(format!("unknown redaction field '{}'", s))),
+            None => {
+                let id: [u8; 4] = s
+                    .as_bytes()
+                    .try_into()
+                    .map_err(|_| MooError::ParseError(format!("invalid chunk ID '{}'", s)))?;
+                MooRedaction::chunk(id)
+            }
+        }
+    }
+}
+
+impl Display for MooRedaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooRedaction::Chunk(id) => write!(f, "{}", String::from_utf8_lossy(id)),
+            MooRedaction::GeneratorSeed => write!(f, "GMET.seed"),
+            MooRedaction::FileSeed => write!(f, "META.file_seed"),
+            MooRedaction::BoardId => write!(f, "META.board_id"),
+            MooRedaction::CaptureTool => write!(f, "META.capture_tool"),
+        }
+    }
+}
+
+/// A [MooRedactionSummary] records the outcome of [MooTestFile::redact]. Its [Display] form is
+/// suitable for an edit log.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooRedactionSummary {
+    /// The number of tests that had something removed.
+    pub tests_redacted: usize,
+    /// The number of redacted tests whose hashed content changed. These are re-hashed on write.
+    pub tests_rehashed: usize,
+    /// True if file-level chunks or fields were removed.
+    pub file_redacted:  bool,
+}
+
+impl MooRedactionSummary {
+    /// Returns true if anything was removed.
+    pub fn changed(&self) -> bool {
+        self.file_redacted || self.tests_redacted > 0
+    }
+}
+
+impl Display for MooRedactionSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "redacted {} tests ({} re-hashed){}",
+            self.tests_redacted,
+            self.tests_rehashed,
+            if self.file_redacted { " and file metadata" } else { "" }
+        )
+    }
+}
+
+/// Redaction
+impl MooTestFile {
+    /// Apply each of `redactions` to the file and all of its tests.
+    ///
+    /// Changes to tests are applied as journaled [MooTestEdit]s, so they can be undone and appear
+    /// in [MooTestFile::journal_summary]. Only tests whose hashed content changed, such as by
+    /// removing their `GMET` chunk, are marked for re-hashing; written with [MooWriteOptions::with_preserve_hash](crate::prelude::MooWriteOptions::with_preserve_hash),
+    /// every other test keeps its hash. A [MooRedaction::Chunk] naming a chunk that isn't
+    /// optional has no effect.
+    pub fn redact(&mut self, redactions: &[MooRedaction]) -> MooRedactionSummary {
+        let mut summary = MooRedactionSummary::default();
+
+        for redaction in redactions {
+            summary.file_redacted |= self.redact_file(redaction);
+        }
+
+        for index in 0..self.tests.len() {
+            let edits = redact_test(&self.tests[index], redactions);
+            if edits.is_empty() {
+                continue;
+            }
+            summary.tests_redacted += 1;
+            if edits.iter().any(MooTestEdit::is_hashed) {
+                summary.tests_rehashed += 1;
+            }
+            for edit in edits {
+                self.edit_test(index, edit);
+            }
+        }
+        summary
+    }
+
+    /// Apply a redaction to the file-level chunks. Returns true if anything was removed.
+    fn redact_file(&mut self, redaction: &MooRedaction) -> bool {
+        match redaction {
+            MooRedaction::Chunk(LICN) => self.license.take().is_some(),
+            MooRedaction::Chunk(id) if MooChunkType::is_vendor_id(id) => {
+                remove_vendor_chunks(&mut self.vendor_chunks, &[*id])
+            }
+            MooRedaction::FileSeed => match &mut self.metadata {
+                Some(metadata) if metadata.file_seed != 0 => {
+                    metadata.file_seed = 0;
+                    true
+                }
+                _ => false,
+            },
+            MooRedaction::BoardId => match self.metadata.as_mut().and_then(|m| m.timing.as_mut()) {
+                Some(timing) if timing.board_id != [b' '; 8] => {
+                    timing.board_id = [b' '; 8];
+                    true
+                }
+                _ => false,
+            },
+            MooRedaction::CaptureTool => match self.metadata.as_mut().and_then(|m| m.capture.as_mut()) {
+                Some(capture) if capture.tool != [b' '; 16] => {
+                    capture.tool = [b' '; 16];
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Build the journal edits that apply `redactions` to `test`, with at most one edit per field.
+fn redact_test(test: &MooTest, redactions: &[MooRedaction]) -> Vec<MooTestEdit> {
+    let mut edits = Vec::new();
+    let selects = |id: &[u8; 4]| redactions.contains(&MooRedaction::Chunk(*id));
+
+    let mut gen_metadata = test.gen_metadata.clone();
+    for redaction in redactions {
+        match redaction {
+            MooRedaction::Chunk(GMET) => gen_metadata = None,
+            MooRedaction::GeneratorSeed => {
+                if let Some(gen_metadata) = gen_metadata.as_mut() {
+                    gen_metadata.seed = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+    let gen_metadata_changed = match (&test.gen_metadata, &gen_metadata) {
+        (Some(before), Some(after)) => before.seed != after.seed,
+        (before, after) => before.is_some() != after.is_some(),
+    };
+    if gen_metadata_changed {
+        edits.push(MooTestEdit::SetGenMetadata(gen_metadata));
+    }
+
+    if selects(b"DGST") && test.digest.is_some() {
+        edits.push(MooTestEdit::SetDigest(None));
+    }
+    if selects(b"TAGS") && !test.tags.is_empty() {
+        edits.push(MooTestEdit::SetTags(Vec::new()));
+    }
+    if selects(b"ANCH") && !test.anchors.is_empty() {
+        edits.push(MooTestEdit::SetAnchors(Vec::new()));
+    }
+    if selects(b"IOST") && !test.io_stimulus.is_empty() {
+        edits.push(MooTestEdit::SetIoStimulus(Vec::new()));
+    }
+
+    // Vendor chunks in the test states are hashed; those of the test are not.
+    let vendor_ids: Vec<[u8; 4]> = redactions
+        .iter()
+        .filter_map(|redaction| match redaction {
+            MooRedaction::Chunk(id) if MooChunkType::is_vendor_id(id) => Some(*id),
+            _ => None,
+        })
+        .collect();
+    if !vendor_ids.is_empty() {
+        let mut test_chunks = test.vendor_chunks.clone();
+        if remove_vendor_chunks(&mut test_chunks, &vendor_ids) {
+            edits.push(MooTestEdit::SetVendorChunks(test_chunks));
+        }
+        let mut initial_state = test.initial_state.clone();
+        if remove_vendor_chunks(&mut initial_state.vendor_chunks, &vendor_ids) {
+            edits.push(MooTestEdit::SetInitialState(initial_state));
+        }
+        let mut final_state = test.final_state.clone();
+        if remove_vendor_chunks(&mut final_state.vendor_chunks, &vendor_ids) {
+            edits.push(MooTestEdit::SetFinalState(final_state));
+        }
+    }
+    edits
+}
+
+/// Remove the vendor chunks with any of the given IDs. Returns true if any were removed.
+fn remove_vendor_chunks(chunks: &mut Vec<MooVendorChunk>, ids: &[[u8; 4]]) -> bool {
+    let before = chunks.len();
+    chunks.retain(|c| !ids.contains(&c.id));
+    chunks.len() != before
+}
@@ -0,0 +1,231 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Locating MOO files on disk.
+//!
+//! A [MooFileSearch] walks a directory tree, optionally to a limited depth, and collects the files
+//! matching a set of [MooGlob] patterns while skipping any that match an exclusion pattern.
+
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// A glob pattern matched case-insensitively against a path relative to the search root.
+///
+/// `*` matches any run of characters within a path component, `?` matches a single character, and
+/// a `**` component matches zero or more whole components, e.g. `**/8088/*.moo.gz`. A pattern
+/// without a `/` is matched against the file name alone.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooGlob {
+    components: Vec<String>,
+    anchored:   bool,
+}
+
+impl MooGlob {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = pattern.replace('\\', "/").to_ascii_lowercase();
+        let anchored = pattern.contains('/');
+        let components = pattern
+            .split('/')
+            .filter(|c| !c.is_empty() && *c != ".")
+            .map(String::from)
+            .collect();
+        Self { components, anchored }
+    }
+
+    /// Returns true if `relative`, a path relative to the search root, matches this pattern.
+    pub fn matches(&self, relative: &Path) -> bool {
+        let path: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_ascii_lowercase())
+            .collect();
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        let patterns: Vec<&str> = self.components.iter().map(String::as_str).collect();
+
+        if self.anchored {
+            match_components(&patterns, &path)
+        }
+        else {
+            path.last().is_some_and(|name| match_components(&patterns, &[name]))
+        }
+    }
+}
+
+impl From<&str> for MooGlob {
+    fn from(pattern: &str) -> Self {
+        MooGlob::new(pattern)
+    }
+}
+
+fn match_components(patterns: &[&str], path: &[&str]) -> bool {
+    match patterns.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_components(rest, &path[skip..])),
+        Some((pattern, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                match_wildcards(pattern.as_bytes(), name.as_bytes()) && match_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a single path component against a pattern containing `*` and `?` wildcards.
+fn match_wildcards(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` seen, and the name position it is currently matched up to.
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        }
+        else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, n));
+            p += 1;
+        }
+        else if let Some((star_p, star_n)) = star {
+            // Let the last `*` absorb one more character and retry.
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        }
+        else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// A [MooFileSearch] collects MOO files under a root path.
+///
+/// By default only the root directory itself is searched, and files are included if their names
+/// end in `.moo` or `.moo.gz`, ignoring case. If the root is a file, it is returned as-is.
+#[derive(Clone, Debug)]
+pub struct MooFileSearch {
+    root: PathBuf,
+    max_depth: Option<usize>,
+    include: Vec<MooGlob>,
+    exclude: Vec<MooGlob>,
+}
+
+impl MooFileSearch {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_depth: Some(1),
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Set how many directory levels to descend. A depth of 1 searches only the root directory,
+    /// and `None` searches the whole tree.
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Search the whole tree below the root.
+    pub fn with_recursion(self) -> Self {
+        self.with_max_depth(None)
+    }
+
+    /// Only include files matching one of the given patterns, instead of all MOO files.
+    pub fn with_include(mut self, include: impl IntoIterator<Item = MooGlob>) -> Self {
+        self.include.extend(include);
+        self
+    }
+
+    /// Skip files, and whole directories, matching any of the given patterns.
+    pub fn with_exclude(mut self, exclude: impl IntoIterator<Item = MooGlob>) -> Self {
+        self.exclude.extend(exclude);
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Walk the tree and return the matching files, sorted by path.
+    /// Unreadable subdirectories are skipped; an unreadable root is an error.
+    pub fn collect(&self) -> io::Result<Vec<PathBuf>> {
+        if self.root.is_file() {
+            return Ok(vec![self.root.clone()]);
+        }
+
+        let mut files = Vec::new();
+        self.walk(&self.root, Path::new(""), 1, &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+
+    fn walk(&self, dir: &Path, relative: &Path, depth: usize, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let Ok(entry) = entry
+            else {
+                continue;
+            };
+            let Ok(file_type) = entry.file_type()
+            else {
+                continue;
+            };
+            let relative = relative.join(entry.file_name());
+            if self.exclude.iter().any(|glob| glob.matches(&relative)) {
+                continue;
+            }
+
+            let path = entry.path();
+            if file_type.is_dir() || (file_type.is_symlink() && path.is_dir()) {
+                if self.max_depth.is_none_or(|max| depth < max) {
+                    if let Err(e) = self.walk(&path, &relative, depth + 1, files) {
+                        log::warn!("Skipping unreadable directory {}: {}", path.display(), e);
+                    }
+                }
+            }
+            else if self.is_included(&relative) {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    fn is_included(&self, relative: &Path) -> bool {
+        if self.include.is_empty() {
+            is_moo_file_name(relative)
+        }
+        else {
+            self.include.iter().any(|glob| glob.matches(relative))
+        }
+    }
+}
+
+/// Returns true if the file name of `path` ends in `.moo` or `.moo.gz`, ignoring case.
+pub fn is_moo_file_name(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|name| name.ends_with(".moo") || name.ends_with(".moo.gz"))
+}
@@ -0,0 +1,127 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Per-test seeds for generated test files.
+//!
+//! A generator derives the seed of each test from the file seed, the opcode under test and the
+//! index of the test, so that a single test can be reproduced without regenerating its file.
+//! A generator backend implements [MooStateGenerator] to let
+//! [MooTestFile::regenerate_initial_state] re-derive a test's initial state for debugging.
+
+use crate::{
+    test::test_state::MooTestState,
+    test_file::MooTestFile,
+    types::{errors::MooError, opcode::MooOpcodeId, MooCpuType},
+};
+
+/// A SplitMix64 pseudo-random number generator. It is defined here rather than taken from a
+/// crate so that a seed reproduces the same tests regardless of dependency versions.
+#[derive(Clone, Debug)]
+pub struct MooSplitMix64(u64);
+
+impl MooSplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    pub fn next_u16(&mut self) -> u16 {
+        (self.next_u64() >> 48) as u16
+    }
+
+    pub fn next_u8(&mut self) -> u8 {
+        (self.next_u64() >> 56) as u8
+    }
+}
+
+/// Derive the seed of test `index` of the file generated for `opcode` from the file seed.
+pub fn derive_test_seed(file_seed: u64, opcode: &MooOpcodeId, index: usize) -> u64 {
+    let mut rng = MooSplitMix64::new(file_seed ^ opcode.to_u32().unwrap_or(u32::MAX) as u64);
+    let base = rng.next_u64();
+    MooSplitMix64::new(base.wrapping_add(index as u64)).next_u64()
+}
+
+/// A [MooStateGenerator] produces the initial state of a test of `opcode` from a per-test seed.
+/// Given the same seed, it must produce the same state as when the test was first generated.
+pub trait MooStateGenerator {
+    type Error: From<MooError>;
+
+    fn initial_state(
+        &mut self,
+        cpu_type: MooCpuType,
+        opcode: &MooOpcodeId,
+        seed: u64,
+    ) -> Result<MooTestState, Self::Error>;
+}
+
+/// Seed reproduction methods
+impl MooTestFile {
+    /// Returns the seed of the test at `index`. The seed recorded with the test is used if
+    /// present; otherwise it is derived from the file seed and opcode in the file metadata.
+    ///
+    /// Returns None if `index` is out of range, or if the test has no recorded seed and the file
+    /// has no metadata for a single opcode.
+    pub fn test_seed(&self, index: usize) -> Option<u64> {
+        let test = self.tests.get(index)?;
+        test.seed().or_else(|| {
+            let metadata = self.metadata.as_ref()?;
+            Some(derive_test_seed(metadata.file_seed, &metadata.opcode_id()?, index))
+        })
+    }
+
+    /// Re-derive the initial state of the test at `index` with `generator`, from the test's seed.
+    /// Comparing the result with the stored initial state shows whether the test is reproducible.
+    ///
+    /// # Returns
+    /// A [MooError::ParseError] if `index` is out of range or the file does not record the seed
+    /// and opcode needed to reproduce the test.
+    pub fn regenerate_initial_state<G: MooStateGenerator>(
+        &self,
+        index: usize,
+        generator: &mut G,
+    ) -> Result<MooTestState, G::Error> {
+        if index >= self.tests.len() {
+            return Err(MooError::ParseError(format!("test index {} is out of range", index)).into());
+        }
+        let opcode = self
+            .metadata
+            .as_ref()
+            .and_then(|m| m.opcode_id())
+            .ok_or_else(|| MooError::ParseError("file metadata does not specify a single opcode".to_string()))?;
+        let seed = self
+            .test_seed(index)
+            .ok_or_else(|| MooError::ParseError(format!("no seed is available for test {}", index)))?;
+        generator.initial_state(self.cpu_type(), &opcode, seed)
+    }
+}
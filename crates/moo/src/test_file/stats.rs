@@ -21,17 +21,42 @@
     DEALINGS IN THE SOFTWARE.
 */
 use super::MooTestFile;
-use crate::{
-    prelude::*,
-    types::{flags::MooCpuFlag, MooBusState},
-};
-use std::collections::HashSet;
+use crate::{prelude::*, types::MooBusState};
+use std::collections::{BTreeMap, HashSet};
 
 #[derive(Clone, Default)]
 pub struct BusOpStats {
     pub total: usize,
     pub min:   usize,
     pub max:   usize,
+    /// The sum of the recorded counts.
+    pub sum:   usize,
+}
+
+impl BusOpStats {
+    /// Record the bus operation count of one test.
+    fn add(&mut self, n: usize) {
+        if self.total == 0 {
+            self.min = n;
+            self.max = n;
+        }
+        else {
+            self.min = self.min.min(n);
+            self.max = self.max.max(n);
+        }
+        self.total += 1;
+        self.sum += n;
+    }
+
+    /// Returns the mean of the recorded counts, or 0 if nothing was recorded.
+    pub fn avg(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        }
+        else {
+            self.sum as f64 / self.total as f64
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -47,14 +72,31 @@ pub struct MooTestFileStats {
     pub io_reads: BusOpStats,
     pub io_writes: BusOpStats,
     pub wait_states: usize,
+    /// Instruction queue length, in bytes, of the initial state of each test.
+    pub initial_queue_len: BusOpStats,
+    /// Instruction queue length, in bytes, of the final state of each test.
+    pub final_queue_len: BusOpStats,
+    /// The cycles of all tests, by what the CPU was doing in each.
+    pub timing: MooTimingBreakdown,
+    /// Bytes of RAM seeded by the initial state of each test.
+    pub ram_seeded: BusOpStats,
+    /// Bytes of RAM in the final state of each test, i.e. the bytes a test is checked to have
+    /// written.
+    pub ram_touched: BusOpStats,
+    /// Distinct addresses across the initial and final RAM of each test: the memory a harness
+    /// must provide to run it.
+    pub ram_footprint: BusOpStats,
 
     pub exceptions_seen: Vec<u8>,
     pub registers_modified: Vec<MooRegister>,
-    pub flags_set: Vec<MooCpuFlag>,
-    pub flags_cleared: Vec<MooCpuFlag>,
-    pub flags_modified: Vec<MooCpuFlag>,
-    pub flags_always_set: Vec<MooCpuFlag>,
-    pub flags_always_cleared: Vec<MooCpuFlag>,
+    pub flags_set: MooCpuFlags,
+    pub flags_cleared: MooCpuFlags,
+    pub flags_modified: MooCpuFlags,
+    pub flags_always_set: MooCpuFlags,
+    pub flags_always_cleared: MooCpuFlags,
+
+    /// The number of tests carrying each tag, sorted by tag name.
+    pub tag_counts: Vec<(String, usize)>,
 }
 
 fn into_sorted_vec<T: Ord>(set: HashSet<T>) -> Vec<T> {
@@ -63,245 +105,153 @@ fn into_sorted_vec<T: Ord>(set: HashSet<T>) -> Vec<T> {
     v
 }
 
-macro_rules! collect_bus_stats {
-    ($self:ident, $new_stats:ident, $field:ident, $iter:expr) => {{
-        let iter = $iter;
-
-        $new_stats.$field.total = iter.clone().count();
-
-        let min_max: Option<(usize, usize)> = iter.fold(None, |acc, n| {
-            Some(match acc {
-                None => (n, n),
-                Some((mn, mx)) => (mn.min(n), mx.max(n)),
-            })
-        });
-
-        if let Some((min, max)) = min_max {
-            $new_stats.$field.min = min;
-            $new_stats.$field.max = max;
-        }
-    }};
+/// A [MooStatsAccumulator] computes [MooTestFileStats] incrementally. Tests are fed in one at a
+/// time with [MooStatsAccumulator::add], so statistics can be gathered over tests as they are
+/// read, or over tests from several files of the same CPU type, without holding them all in
+/// memory.
+pub struct MooStatsAccumulator {
+    cpu_type: MooCpuType,
+    cycle_subtract: usize,
+    stats: MooTestFileStats,
+    min_cycles: Option<usize>,
+    max_cycles: Option<usize>,
+    registers_modified: HashSet<MooRegister>,
+    flags_unmodified_set: MooCpuFlags,
+    flags_unmodified_cleared: MooCpuFlags,
+    tag_counts: BTreeMap<String, usize>,
 }
 
-/// Implementation block for statistics generation
-impl MooTestFile {
-    pub fn calc_stats(&mut self, cycle_subtract: usize) -> MooTestFileStats {
-        let test_ct = self.tests.len();
+impl MooStatsAccumulator {
+    /// Create a new [MooStatsAccumulator] for tests of the given [MooCpuType].
+    /// `cycle_subtract` is subtracted from the reported minimum and maximum cycle counts.
+    pub fn new(cpu_type: MooCpuType, cycle_subtract: usize) -> Self {
+        Self {
+            cpu_type,
+            cycle_subtract,
+            stats: MooTestFileStats::default(),
+            min_cycles: None,
+            max_cycles: None,
+            registers_modified: HashSet::new(),
+            flags_unmodified_set: MooCpuFlags::default(),
+            flags_unmodified_cleared: MooCpuFlags::default(),
+            tag_counts: BTreeMap::new(),
+        }
+    }
 
-        let mut new_stats = MooTestFileStats::default();
-        let filter_exception = |t: &&MooTest| t.exception.is_none();
+    /// Returns the number of tests added so far.
+    pub fn test_count(&self) -> usize {
+        self.stats.test_count
+    }
 
-        new_stats.total_cycles = self.tests.iter().map(|t| t.cycles.len()).sum();
-        new_stats.min_cycles = self
-            .tests
-            .iter()
-            .filter(filter_exception)
-            .map(|t| t.cycles.len())
-            .min()
-            .unwrap_or(0);
-        new_stats.max_cycles = self
-            .tests
-            .iter()
-            .filter(filter_exception)
-            .map(|t| t.cycles.len())
-            .max()
-            .unwrap_or(0);
-        new_stats.avg_cycles = if test_ct > 0 {
-            new_stats.total_cycles as f64 / test_ct as f64
+    /// Add a single test to the statistics.
+    pub fn add(&mut self, test: &MooTest) {
+        let cpu_type = self.cpu_type;
+        let stats = &mut self.stats;
+
+        stats.test_count += 1;
+        stats.total_cycles += test.cycles.len();
+        stats.initial_queue_len.add(test.initial_state.queue.len());
+        stats.final_queue_len.add(test.final_state.queue.len());
+        stats.timing += test.timing_breakdown(cpu_type);
+
+        let initial_ram = test.initial_state.ram();
+        let final_ram = test.final_state.ram();
+        let footprint: HashSet<u32> = initial_ram.iter().chain(final_ram).map(|e| e.address).collect();
+        stats.ram_seeded.add(initial_ram.len());
+        stats.ram_touched.add(final_ram.len());
+        stats.ram_footprint.add(footprint.len());
+
+        if let Some(exception) = &test.exception {
+            stats.exceptions_seen.push(exception.exception_num);
         }
         else {
-            0.0
-        };
-
-        new_stats.min_cycles = new_stats.min_cycles.saturating_sub(cycle_subtract);
-        new_stats.max_cycles = new_stats.max_cycles.saturating_sub(cycle_subtract);
-
-        let registers_modified: HashSet<MooRegister> = self
-            .tests
-            .iter()
-            .filter(|t| t.exception.is_none())
-            .flat_map(|t| t.diff_regs().iter().map(|diff| diff.register()).collect::<Vec<_>>())
-            .collect();
-
-        log::debug!("Calculated registers modified: {:?}", registers_modified);
-
-        if self.arch.contains("386") {
-            // Only count read signal on ALE.
-            let mem_reads_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| {
-                        c.ale()
-                            && c.bus_state(self.cpu_type) == MooBusState::MEMR
-                            && (c.memory_status & MooCycleState::MRDC_BIT != 0)
-                    })
-                    .count()
-            });
-
-            collect_bus_stats!(self, new_stats, mem_reads, mem_reads_iter);
-
-            let mem_writes_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| c.ale() && (c.memory_status & MooCycleState::MWTC_BIT != 0))
-                    .count()
-            });
-
-            collect_bus_stats!(self, new_stats, mem_writes, mem_writes_iter);
-
-            let code_fetches_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| {
-                        c.ale() && c.is_code_fetch(self.cpu_type) && (c.memory_status & MooCycleState::MRDC_BIT != 0)
-                    })
-                    .count()
-            });
+            let cycle_ct = test.cycles.len();
+            self.min_cycles = Some(self.min_cycles.map_or(cycle_ct, |m| m.min(cycle_ct)));
+            self.max_cycles = Some(self.max_cycles.map_or(cycle_ct, |m| m.max(cycle_ct)));
+
+            self.registers_modified
+                .extend(test.diff_regs().iter().map(|diff| diff.register()));
+
+            if let MooCpuFamily::Intel80386 = MooCpuFamily::from(cpu_type) {
+                // The 386 bus may be pipelined, with ADS for the next bus cycle asserted before the
+                // current one completes, and the command strobes are not valid on the ADS cycle.
+                // Count one bus cycle per ADS, by the status decoded on it.
+                let histogram = test.bus_state_histogram(cpu_type);
+                stats.mem_reads.add(histogram.count(MooBusState::MEMR));
+                stats.mem_writes.add(histogram.count(MooBusState::MEMW));
+                stats.code_fetches.add(histogram.count(MooBusState::CODE));
+                stats.io_reads.add(histogram.count(MooBusState::IOR));
+                stats.io_writes.add(histogram.count(MooBusState::IOW));
+            }
+            else {
+                // Other CPUs can wait for PASV bus to signal completed read/write.
+                let cycles = || test.cycles_iter().in_bus_state(cpu_type, MooBusState::PASV);
+                stats.mem_reads.add(cycles().mem_reads().count());
+                stats.mem_writes.add(cycles().mem_writes().count());
+                stats.code_fetches.add(cycles().code_fetches(cpu_type).count());
+                stats.io_reads.add(cycles().io_reads().count());
+                stats.io_writes.add(cycles().io_writes().count());
+            }
+        }
 
-            collect_bus_stats!(self, new_stats, code_fetches, code_fetches_iter);
+        let fd = test.diff_flags();
+        stats.flags_set |= fd.set;
+        stats.flags_cleared |= fd.cleared;
+        self.flags_unmodified_set |= fd.unmodified_set;
+        self.flags_unmodified_cleared |= fd.unmodified_cleared;
 
-            let io_reads_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| c.ale() && (c.io_status & MooCycleState::IORC_BIT != 0))
-                    .count()
-            });
-            collect_bus_stats!(self, new_stats, io_reads, io_reads_iter);
+        for tag in &test.tags {
+            *self.tag_counts.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
 
-            let io_writes_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| c.ale() && (c.io_status & MooCycleState::IOWC_BIT != 0))
-                    .count()
-            });
+    /// Consume the accumulator and produce the final [MooTestFileStats].
+    pub fn finish(self) -> MooTestFileStats {
+        let mut stats = self.stats;
 
-            collect_bus_stats!(self, new_stats, io_writes, io_writes_iter);
+        stats.min_cycles = self.min_cycles.unwrap_or(0).saturating_sub(self.cycle_subtract);
+        stats.max_cycles = self.max_cycles.unwrap_or(0).saturating_sub(self.cycle_subtract);
+        stats.avg_cycles = if stats.test_count > 0 {
+            stats.total_cycles as f64 / stats.test_count as f64
         }
         else {
-            // Other CPUs can wait for PASV bus to signal completed read/write.
-            let mem_reads_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| {
-                        c.bus_state(self.cpu_type) == MooBusState::PASV
-                            && (c.memory_status & MooCycleState::MRDC_BIT != 0)
-                    })
-                    .count()
-            });
-
-            collect_bus_stats!(self, new_stats, mem_reads, mem_reads_iter);
-
-            let mem_writes_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| {
-                        c.bus_state(self.cpu_type) == MooBusState::PASV
-                            && (c.memory_status & MooCycleState::MWTC_BIT != 0)
-                    })
-                    .count()
-            });
-
-            collect_bus_stats!(self, new_stats, mem_writes, mem_writes_iter);
-
-            let code_fetches_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| {
-                        c.bus_state(self.cpu_type) == MooBusState::PASV
-                            && (c.memory_status & MooCycleState::MRDC_BIT != 0)
-                            && c.is_code_fetch(self.cpu_type)
-                    })
-                    .count()
-            });
-
-            collect_bus_stats!(self, new_stats, code_fetches, code_fetches_iter);
-
-            let io_reads_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| {
-                        c.bus_state(self.cpu_type) == MooBusState::PASV && (c.io_status & MooCycleState::IORC_BIT != 0)
-                    })
-                    .count()
-            });
-
-            collect_bus_stats!(self, new_stats, io_reads, io_reads_iter);
-
-            let io_writes_iter = self.tests.iter().filter(filter_exception).map(|t| {
-                t.cycles
-                    .iter()
-                    .filter(|c| {
-                        c.bus_state(self.cpu_type) == MooBusState::PASV && (c.io_status & MooCycleState::IOWC_BIT != 0)
-                    })
-                    .count()
-            });
-
-            collect_bus_stats!(self, new_stats, io_writes, io_writes_iter);
+            0.0
         };
 
-        let exceptions_seen = self
-            .tests
-            .iter()
-            .filter_map(|t| {
-                if let Some(exception) = &t.exception {
-                    Some(exception.exception_num)
-                }
-                else {
-                    None
-                }
-            })
-            .collect();
-
-        let (flags_set, flags_cleared, flags_unmodified_set, flags_unmodified_cleared): (
-            HashSet<_>,
-            HashSet<_>,
-            HashSet<_>,
-            HashSet<_>,
-        ) = self.tests.iter().fold(
-            (
-                HashSet::default(),
-                HashSet::default(),
-                HashSet::default(),
-                HashSet::default(),
-            ),
-            |(mut set_acc, mut clr_acc, mut uset_acc, mut uclr_acc), t| {
-                let fd = t.diff_flags();
-                set_acc.extend(fd.set.iter().cloned());
-                clr_acc.extend(fd.cleared.iter().cloned());
-                uset_acc.extend(fd.unmodified_set.iter().cloned());
-                uclr_acc.extend(fd.unmodified_cleared.iter().cloned());
-                (set_acc, clr_acc, uset_acc, uclr_acc)
-            },
-        );
+        log::debug!("Calculated registers modified: {:?}", self.registers_modified);
+        stats.registers_modified = into_sorted_vec(self.registers_modified);
 
         // Flags that were always modified and set but never cleared; and always modified and cleared but never set.
-        let flags_always_set: HashSet<_> = flags_set
-            .difference(&flags_cleared)
-            .cloned()
-            .collect::<HashSet<MooCpuFlag>>()
-            .difference(&flags_unmodified_cleared)
-            .cloned()
-            .collect();
-
-        let flags_always_cleared: HashSet<_> = flags_cleared
-            .difference(&flags_set)
-            .cloned()
-            .collect::<HashSet<MooCpuFlag>>()
-            .difference(&flags_unmodified_set)
-            .cloned()
-            .collect();
-
-        let flags_modified: HashSet<_> = flags_set.union(&flags_cleared).cloned().collect();
+        stats.flags_always_set = stats
+            .flags_set
+            .difference(stats.flags_cleared)
+            .difference(self.flags_unmodified_cleared);
+        stats.flags_always_cleared = stats
+            .flags_cleared
+            .difference(stats.flags_set)
+            .difference(self.flags_unmodified_set);
+        stats.flags_modified = stats.flags_set | stats.flags_cleared;
+
+        stats.tag_counts = self.tag_counts.into_iter().collect();
+        stats
+    }
+}
 
-        new_stats.test_count = test_ct;
-        new_stats.exceptions_seen = exceptions_seen;
-        new_stats.registers_modified = into_sorted_vec(registers_modified);
-        new_stats.flags_set = into_sorted_vec(flags_set);
-        new_stats.flags_cleared = into_sorted_vec(flags_cleared);
-        new_stats.flags_modified = into_sorted_vec(flags_modified);
-        new_stats.flags_always_set = into_sorted_vec(flags_always_set);
-        new_stats.flags_always_cleared = into_sorted_vec(flags_always_cleared);
+impl<'a> Extend<&'a MooTest> for MooStatsAccumulator {
+    fn extend<I: IntoIterator<Item = &'a MooTest>>(&mut self, iter: I) {
+        for test in iter {
+            self.add(test);
+        }
+    }
+}
 
-        new_stats
+/// Implementation block for statistics generation
+impl MooTestFile {
+    /// Calculate [MooTestFileStats] over all tests in the file.
+    /// See [MooStatsAccumulator] to gather statistics incrementally.
+    pub fn calc_stats(&self, cycle_subtract: usize) -> MooTestFileStats {
+        let mut accumulator = MooStatsAccumulator::new(self.cpu_type(), cycle_subtract);
+        accumulator.extend(self.tests.iter());
+        accumulator.finish()
     }
 }
@@ -0,0 +1,226 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Rewriting a corpus of MOO files.
+//!
+//! A [MooCorpusTransform] reads every file found by a [MooFileSearch] in parallel, passes each to a
+//! user-supplied function, and writes the files that function reports as modified to an output
+//! directory, mirroring the layout of the input tree. [transform_corpus] covers the common case of
+//! a single input directory with default options.
+
+use std::{
+    fmt::{self, Display},
+    fs,
+    io::{self, Cursor},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    test_file::{
+        search::MooFileSearch,
+        write_options::{MooCompressionKind, MooWriteOptions},
+        MooTestFile,
+    },
+    types::errors::MooError,
+};
+
+/// A file that could not be read, transformed or written.
+#[derive(Clone, Debug)]
+pub struct MooTransformFailure {
+    pub path:  PathBuf,
+    pub error: String,
+}
+
+impl Display for MooTransformFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+/// Totals from a [MooCorpusTransform] run.
+#[derive(Clone, Debug, Default)]
+pub struct MooTransformSummary {
+    /// Files read successfully.
+    pub files_read: usize,
+    /// Files reported as modified and written to the output directory.
+    pub files_written: usize,
+    /// Files that failed, sorted by path.
+    pub failures: Vec<MooTransformFailure>,
+}
+
+/// A [MooCorpusTransform] applies a function to every file of a corpus and writes the results.
+///
+/// Output files are written atomically with [MooTestFile::write_to_path]. Each keeps its path
+/// relative to the search root. If the [MooWriteOptions] select a compression, every output file
+/// uses it and its `.gz` suffix is added or removed to match; otherwise each file keeps the
+/// compression it was read with.
+#[derive(Clone, Debug)]
+pub struct MooCorpusTransform {
+    search: MooFileSearch,
+    out_dir: PathBuf,
+    write_options: MooWriteOptions,
+    threads: Option<usize>,
+}
+
+impl MooCorpusTransform {
+    pub fn new(search: MooFileSearch, out_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            search,
+            out_dir: out_dir.into(),
+            write_options: MooWriteOptions::new().with_preserve_hash(true),
+            threads: None,
+        }
+    }
+
+    /// Set the options used to write modified files. Hashes are preserved by default.
+    pub fn with_write_options(mut self, options: MooWriteOptions) -> Self {
+        self.write_options = options;
+        self
+    }
+
+    /// Set the number of worker threads. Defaults to the available parallelism.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads.max(1));
+        self
+    }
+
+    /// Return the path the output for `input`, read with the given compression, is written to.
+    pub fn output_path(&self, input: &Path, compression: MooCompressionKind) -> PathBuf {
+        let relative = input.strip_prefix(self.search.root()).unwrap_or(input);
+        let relative = if relative.as_os_str().is_empty() {
+            // The search root was a single file.
+            Path::new(input.file_name().unwrap_or_default())
+        }
+        else {
+            relative
+        };
+
+        let compression = self.write_options.compression.unwrap_or(compression);
+        let name = relative.file_name().unwrap_or_default().to_string_lossy();
+        let base = [".gz", ".zst", ".zstd"]
+            .iter()
+            .find_map(|ext| {
+                let i = name.len().checked_sub(ext.len())?;
+                name.get(i..)
+                    .is_some_and(|e| e.eq_ignore_ascii_case(ext))
+                    .then(|| &name[..i])
+            })
+            .unwrap_or(&name[..]);
+        let name = match compression.extension() {
+            Some(extension) => format!("{}.{}", base, extension),
+            None => base.to_string(),
+        };
+        self.out_dir.join(relative.with_file_name(name))
+    }
+
+    /// Run `transform` over every file of the corpus. The function returns `Ok(true)` if it
+    /// modified the file and it should be written, or `Ok(false)` to leave it untouched.
+    ///
+    /// Per-file errors are collected in the returned [MooTransformSummary]; only a failure to
+    /// search the input directory is returned as an error.
+    pub fn run<F, E>(&self, transform: F) -> io::Result<MooTransformSummary>
+    where
+        F: Fn(&Path, &mut MooTestFile) -> Result<bool, E> + Sync,
+        E: Display,
+    {
+        let files = self.search.collect()?;
+        let threads = self
+            .threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+            .min(files.len().max(1));
+
+        let next = AtomicUsize::new(0);
+        let summary = Mutex::new(MooTransformSummary::default());
+        thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    while let Some(path) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        let result = self.transform_file(path, &transform);
+                        let mut summary = summary.lock().unwrap();
+                        match result {
+                            Ok(written) => {
+                                summary.files_read += 1;
+                                summary.files_written += written as usize;
+                            }
+                            Err((read, error)) => {
+                                summary.files_read += read as usize;
+                                summary.failures.push(MooTransformFailure {
+                                    path: path.clone(),
+                                    error,
+                                });
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut summary = summary.into_inner().unwrap();
+        summary.failures.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(summary)
+    }
+
+    /// Read, transform and write a single file. Returns whether the file was written, or an error
+    /// message along with whether the file had been read.
+    fn transform_file<F, E>(&self, path: &Path, transform: &F) -> Result<bool, (bool, String)>
+    where
+        F: Fn(&Path, &mut MooTestFile) -> Result<bool, E>,
+        E: Display,
+    {
+        let data = fs::read(path).map_err(|e| (false, e.to_string()))?;
+        let mut moo = MooTestFile::read(&mut Cursor::new(data)).map_err(|e| (false, e.to_string()))?;
+
+        if !transform(path, &mut moo).map_err(|e| (true, e.to_string()))? {
+            return Ok(false);
+        }
+
+        // The output extension selects the compression.
+        let out_path = self.output_path(path, moo.compression());
+        let options = MooWriteOptions {
+            compression: None,
+            ..self.write_options.clone()
+        };
+        moo.write_to_path(&out_path, &options)
+            .map_err(|e: MooError| (true, e.to_string()))?;
+        Ok(true)
+    }
+}
+
+/// Apply `transform` to every MOO file in `in_dir` and write the files it modifies to `out_dir`,
+/// preserving their hashes and compression. See [MooCorpusTransform] for more control.
+pub fn transform_corpus<F, E>(
+    in_dir: impl Into<PathBuf>,
+    out_dir: impl Into<PathBuf>,
+    transform: F,
+) -> io::Result<MooTransformSummary>
+where
+    F: Fn(&Path, &mut MooTestFile) -> Result<bool, E> + Sync,
+    E: Display,
+{
+    MooCorpusTransform::new(MooFileSearch::new(in_dir), out_dir).run(transform)
+}
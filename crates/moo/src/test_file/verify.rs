@@ -0,0 +1,98 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Verification of stored test hashes.
+//!
+//! A test's SHA-1 hash is computed from its serialized payload when it is written. If a file is
+//! edited by a tool that preserves hashes without recalculating them, the stored hashes go
+//! stale. [MooTestFile::verify_hashes] recalculates every hash and reports those that differ.
+
+use std::fmt::Display;
+
+use binrw::BinResult;
+
+use crate::{
+    test_file::MooTestFile,
+    types::hash::{hex_string, MooHashAlgorithm},
+};
+
+/// A [MooHashMismatch] records a test whose stored hash or alternate digest does not match its
+/// contents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooHashMismatch {
+    /// The index of the test within the file.
+    pub index: usize,
+    /// The algorithm of the mismatched hash. [MooHashAlgorithm::Sha1] refers to the canonical
+    /// `HASH` chunk.
+    pub algorithm: MooHashAlgorithm,
+    /// The hash stored in the file.
+    pub stored: Vec<u8>,
+    /// The hash recalculated from the test's contents.
+    pub computed: Vec<u8>,
+}
+
+impl Display for MooHashMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "test {}: stored {} {} does not match computed {}",
+            self.index,
+            self.algorithm,
+            hex_string(&self.stored),
+            hex_string(&self.computed)
+        )
+    }
+}
+
+impl MooTestFile {
+    /// Recalculate the hash, and the alternate digest if present, of every test from its
+    /// serialized payload, and return a [MooHashMismatch] for each that differs from the stored
+    /// value. Tests without a stored hash are skipped.
+    ///
+    /// The hash covers the test's index, so tests that were reordered or removed without
+    /// rehashing are reported as well.
+    pub fn verify_hashes(&self) -> BinResult<Vec<MooHashMismatch>> {
+        let mut mismatches = Vec::new();
+
+        for (index, test) in self.tests.iter().enumerate() {
+            let stored_digests = test
+                .hash()
+                .map(|hash| (MooHashAlgorithm::Sha1, hash.to_vec()))
+                .into_iter()
+                .chain(test.digest().map(|d| (d.algorithm, d.digest.clone())));
+
+            for (algorithm, stored) in stored_digests {
+                let computed = test.compute_digest(index, algorithm)?.digest;
+                if computed != stored {
+                    mismatches.push(MooHashMismatch {
+                        index,
+                        algorithm,
+                        stored,
+                        computed,
+                    });
+                }
+            }
+        }
+        Ok(mismatches)
+    }
+}
@@ -0,0 +1,120 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use crate::types::hash::MooHashAlgorithm;
+
+/// The default compression level. For gzip, levels run from 0 (fastest) to 9 (smallest).
+pub const DEFAULT_COMPRESSION_LEVEL: u32 = 9;
+/// The default size of the output buffer used when writing, in bytes.
+pub const DEFAULT_WRITE_BUFFER_SIZE: usize = 256 * 1024;
+
+/// A [MooCompressionKind] selects the compression applied to a written file.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooCompressionKind {
+    /// Write a plain `MOO` file.
+    Uncompressed,
+    /// Write a gzip-compressed `MOO` file. Requires the `gzip` feature.
+    Gzip,
+    /// Write a zstd-compressed `MOO` file. Requires the `zstd` feature.
+    Zstd,
+}
+
+impl MooCompressionKind {
+    /// Returns the file extension appended to the name of a file with this compression, if any.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            MooCompressionKind::Uncompressed => None,
+            MooCompressionKind::Gzip => Some("gz"),
+            MooCompressionKind::Zstd => Some("zst"),
+        }
+    }
+}
+
+/// [MooWriteOptions] controls how a [MooTestFile](crate::prelude::MooTestFile) is written to disk.
+/// Pass to [MooTestFile::write_to_path](crate::prelude::MooTestFile::write_to_path) or
+/// [MooTestFile::write_with_options](crate::prelude::MooTestFile::write_with_options).
+#[derive(Clone, Debug)]
+pub struct MooWriteOptions {
+    /// Preserve existing test hashes instead of recalculating them.
+    /// See [MooTestFile::write](crate::prelude::MooTestFile::write).
+    pub preserve_hash: bool,
+    /// Write an alternate digest of each test with this algorithm, in addition to the SHA-1 hash.
+    /// Tests that already carry an alternate digest keep their algorithm if this is `None`.
+    pub digest: Option<MooHashAlgorithm>,
+    /// The compression to apply. If `None`, the file's own compression flag is used.
+    pub compression: Option<MooCompressionKind>,
+    /// The compression level. Gzip levels run from 0 to 9 and zstd levels from 1 to 22; higher
+    /// values are clamped to the maximum.
+    pub compression_level: u32,
+    /// The size of the output buffer, in bytes.
+    pub buffer_size: usize,
+}
+
+impl Default for MooWriteOptions {
+    fn default() -> Self {
+        Self {
+            preserve_hash: false,
+            digest: None,
+            compression: None,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+            buffer_size: DEFAULT_WRITE_BUFFER_SIZE,
+        }
+    }
+}
+
+impl MooWriteOptions {
+    /// Create a new [MooWriteOptions] with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to set whether existing test hashes are preserved.
+    pub fn with_preserve_hash(mut self, preserve_hash: bool) -> Self {
+        self.preserve_hash = preserve_hash;
+        self
+    }
+
+    /// Builder-style method to set the alternate digest algorithm.
+    pub fn with_digest(mut self, digest: Option<MooHashAlgorithm>) -> Self {
+        self.digest = digest;
+        self
+    }
+
+    /// Builder-style method to force the compression of the written file.
+    pub fn with_compression(mut self, compression: MooCompressionKind) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// Builder-style method to set the compression level.
+    pub fn with_compression_level(mut self, level: u32) -> Self {
+        self.compression_level = level;
+        self
+    }
+
+    /// Builder-style method to set the output buffer size.
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+}
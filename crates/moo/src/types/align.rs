@@ -0,0 +1,139 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Alignment of bus transaction sequences.
+//!
+//! Comparing two cycle traces cycle-by-cycle reports a mismatch on every cycle after the first
+//! point of divergence, so a trace that is off by a single idle cycle produces a flood of
+//! differences. Aligning the traces' bus transactions instead, with a banded edit distance,
+//! yields the smallest set of inserted, removed and substituted transactions.
+
+use std::fmt::{self, Display};
+
+use crate::types::cycle_iter::MooBusTransaction;
+
+#[allow(unused_imports)]
+use crate::prelude::MooTest;
+
+/// The default band width for [align_transactions], in transactions.
+pub const DEFAULT_ALIGN_BAND: usize = 8;
+
+/// One difference in an alignment of two bus transaction sequences.
+///
+/// As with [MooComparison](crate::types::comparison::MooComparison), the first sequence is from
+/// the test `compare_aligned` was called on and the second from the test it was compared against.
+#[derive(Copy, Clone, Debug)]
+pub enum MooAlignedDiff {
+    /// A transaction present only in the first sequence.
+    Removed(MooBusTransaction),
+    /// A transaction present only in the second sequence.
+    Inserted(MooBusTransaction),
+    /// Aligned transactions that perform different operations.
+    Substituted(MooBusTransaction, MooBusTransaction),
+}
+
+fn fmt_transaction(f: &mut fmt::Formatter<'_>, t: &MooBusTransaction) -> fmt::Result {
+    write!(f, "[cycle {}] addr {:08X} bus {:X}", t.start, t.address, t.bus_state)?;
+    if let Some(data) = t.data_bus {
+        write!(f, " data {:04X}", data)?;
+    }
+    Ok(())
+}
+
+impl Display for MooAlignedDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MooAlignedDiff::Removed(t) => {
+                write!(f, "- ")?;
+                fmt_transaction(f, t)
+            }
+            MooAlignedDiff::Inserted(t) => {
+                write!(f, "+ ")?;
+                fmt_transaction(f, t)
+            }
+            MooAlignedDiff::Substituted(a, b) => {
+                write!(f, "~ ")?;
+                fmt_transaction(f, a)?;
+                write!(f, " -> ")?;
+                fmt_transaction(f, b)
+            }
+        }
+    }
+}
+
+/// Align two sequences of bus transactions and return a minimal list of differences, in sequence
+/// order. Transactions match if they perform the same operation, as by
+/// [MooBusTransaction::same_operation]; their timing is not compared.
+///
+/// Only alignments that stay within `band` transactions of the diagonal are considered. The band
+/// is widened to the difference in sequence lengths if that is larger.
+pub fn align_transactions(a: &[MooBusTransaction], b: &[MooBusTransaction], band: usize) -> Vec<MooAlignedDiff> {
+    const INF: usize = usize::MAX / 2;
+    let (n, m) = (a.len(), b.len());
+    let band = band.max(n.abs_diff(m));
+    let width = m + 1;
+
+    // Edit distance between a[..i] and b[..j], at dist[i * width + j].
+    let mut dist = vec![INF; (n + 1) * width];
+    for i in 0..=n {
+        for j in i.saturating_sub(band)..=(i + band).min(m) {
+            dist[i * width + j] = if i == 0 || j == 0 {
+                i + j
+            }
+            else {
+                let cost = usize::from(!a[i - 1].same_operation(&b[j - 1]));
+                (dist[(i - 1) * width + j - 1] + cost)
+                    .min(dist[(i - 1) * width + j] + 1)
+                    .min(dist[i * width + j - 1] + 1)
+            };
+        }
+    }
+
+    // Trace back from the end, preferring matches and substitutions.
+    let mut diffs = Vec::new();
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        let here = dist[i * width + j];
+        if i > 0 && j > 0 {
+            let same = a[i - 1].same_operation(&b[j - 1]);
+            if here == dist[(i - 1) * width + j - 1] + usize::from(!same) {
+                if !same {
+                    diffs.push(MooAlignedDiff::Substituted(a[i - 1], b[j - 1]));
+                }
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && here == dist[(i - 1) * width + j] + 1 {
+            diffs.push(MooAlignedDiff::Removed(a[i - 1]));
+            i -= 1;
+        }
+        else {
+            diffs.push(MooAlignedDiff::Inserted(b[j - 1]));
+            j -= 1;
+        }
+    }
+    diffs.reverse();
+    diffs
+}
@@ -0,0 +1,149 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::types::errors::MooError;
+use binrw::binrw;
+
+/// A [MooAnchorKind] identifies a key event in a test's cycle trace. Anchors let comparison tools
+/// align traces that differ only in their leading or trailing fetch cycles.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MooAnchorKind {
+    /// The first cycle in which the instruction under test executes.
+    FirstExecution,
+    /// The last cycle in which the instruction under test executes.
+    LastExecution,
+    /// The first cycle of exception dispatch, for tests that raise an exception.
+    ExceptionDispatch,
+}
+
+impl MooAnchorKind {
+    /// Convert the kind to its encoding in an `ANCH` chunk.
+    pub fn to_u8(self) -> u8 {
+        match self {
+            MooAnchorKind::FirstExecution => 0,
+            MooAnchorKind::LastExecution => 1,
+            MooAnchorKind::ExceptionDispatch => 2,
+        }
+    }
+
+    /// Decode a kind from its encoding in an `ANCH` chunk, or `None` if the value is unknown.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MooAnchorKind::FirstExecution),
+            1 => Some(MooAnchorKind::LastExecution),
+            2 => Some(MooAnchorKind::ExceptionDispatch),
+            _ => None,
+        }
+    }
+
+    /// The name of the kind as used in text dumps.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MooAnchorKind::FirstExecution => "first_exec",
+            MooAnchorKind::LastExecution => "last_exec",
+            MooAnchorKind::ExceptionDispatch => "exception",
+        }
+    }
+}
+
+impl FromStr for MooAnchorKind {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first_exec" => Ok(MooAnchorKind::FirstExecution),
+            "last_exec" => Ok(MooAnchorKind::LastExecution),
+            "exception" => Ok(MooAnchorKind::ExceptionDispatch),
+            _ => Err(MooError::ParseError(format!("Unknown anchor kind: {}", s))),
+        }
+    }
+}
+
+impl Display for MooAnchorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A [MooAnchor] marks the cycle, as an index into the test's cycle trace, at which a
+/// [MooAnchorKind] event occurs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooAnchor {
+    pub kind:  MooAnchorKind,
+    pub cycle: usize,
+}
+
+#[derive(Debug)]
+#[binrw]
+#[brw(little)]
+pub(crate) struct MooAnchorEntry {
+    pub kind:  u8,
+    pub cycle: u32,
+}
+
+/// A [MooAnchorsChunk] holds the cycle anchors of a test.
+#[derive(Debug)]
+#[binrw]
+#[brw(little)]
+pub(crate) struct MooAnchorsChunk {
+    pub anchor_ct: u32,
+    #[br(count = anchor_ct)]
+    pub anchors:   Vec<MooAnchorEntry>,
+}
+
+impl MooAnchorsChunk {
+    /// Decode the anchors in the chunk, skipping any of an unknown kind.
+    pub fn to_anchors(&self) -> Vec<MooAnchor> {
+        self.anchors
+            .iter()
+            .filter_map(|entry| match MooAnchorKind::from_u8(entry.kind) {
+                Some(kind) => Some(MooAnchor {
+                    kind,
+                    cycle: entry.cycle as usize,
+                }),
+                None => {
+                    log::warn!("Skipping anchor of unknown kind {}", entry.kind);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl From<&[MooAnchor]> for MooAnchorsChunk {
+    fn from(anchors: &[MooAnchor]) -> Self {
+        Self {
+            anchor_ct: anchors.len() as u32,
+            anchors:   anchors
+                .iter()
+                .map(|anchor| MooAnchorEntry {
+                    kind:  anchor.kind.to_u8(),
+                    cycle: anchor.cycle as u32,
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::types::{errors::MooError, MooCpuType};
+use binrw::binrw;
+
+/// A [MooArchTag] is the four-character CPU id stored in the header of a MOO file, such as `8088`
+/// or `286 `. Tags are printable ASCII and padded with spaces to four characters, so a tag can
+/// always be written without truncation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[binrw]
+#[br(try_map = |bytes: [u8; 4]| MooArchTag::from_bytes(bytes))]
+#[bw(map = |tag: &MooArchTag| tag.0)]
+pub struct MooArchTag([u8; 4]);
+
+impl MooArchTag {
+    /// The length of an architecture tag in bytes.
+    pub const LEN: usize = 4;
+
+    /// Create a [MooArchTag] from a string of one to four printable ASCII characters, padding it
+    /// with spaces. Returns an error for empty, overlong or non-ASCII strings.
+    pub fn new(tag: &str) -> Result<Self, MooError> {
+        if tag.len() > Self::LEN {
+            return Err(MooError::ParseError(format!(
+                "Architecture tag {:?} is longer than {} characters",
+                tag,
+                Self::LEN
+            )));
+        }
+        let mut bytes = [b' '; Self::LEN];
+        bytes[..tag.len()].copy_from_slice(tag.as_bytes());
+        Self::from_bytes(bytes)
+    }
+
+    /// Create a [MooArchTag] from the four bytes of a file header. Returns an error unless every
+    /// byte is printable ASCII and at least one is not a space.
+    pub fn from_bytes(bytes: [u8; 4]) -> Result<Self, MooError> {
+        if !bytes.iter().all(|b| (0x20..0x7F).contains(b)) || bytes.iter().all(|&b| b == b' ') {
+            return Err(MooError::ParseError(format!(
+                "Invalid architecture tag {:02X?}: expected printable ASCII",
+                bytes
+            )));
+        }
+        Ok(MooArchTag(bytes))
+    }
+
+    /// Returns the raw bytes of the tag.
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+
+    /// Returns the tag as a string, including any padding.
+    pub fn as_str(&self) -> &str {
+        // Validated as ASCII on construction.
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+
+    /// Decode the [MooCpuType] named by this tag.
+    pub fn cpu_type(&self) -> Result<MooCpuType, MooError> {
+        MooCpuType::from_str(self.as_str()).map_err(MooError::ParseError)
+    }
+}
+
+impl From<MooCpuType> for MooArchTag {
+    fn from(cpu_type: MooCpuType) -> Self {
+        let mut bytes = [b' '; Self::LEN];
+        let name = cpu_type.to_str().as_bytes();
+        let len = name.len().min(Self::LEN);
+        bytes[..len].copy_from_slice(&name[..len]);
+        MooArchTag(bytes)
+    }
+}
+
+impl FromStr for MooArchTag {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MooArchTag::new(s)
+    }
+}
+
+impl Display for MooArchTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -21,6 +21,7 @@
     DEALINGS IN THE SOFTWARE.
 */
 
+use crate::types::arch_tag::MooArchTag;
 use binrw::{binrw, BinResult, BinWrite};
 use std::io::{Cursor, Seek, Write};
 
@@ -62,15 +63,50 @@ pub enum MooChunkType {
     CycleStates,
     #[brw(magic = b"HASH")]
     Hash,
+    #[brw(magic = b"DGST")]
+    Digest,
     #[brw(magic = b"META")]
     FileMetadata,
     #[brw(magic = b"GMET")]
     GeneratorMetadata,
     #[brw(magic = b"EXCP")]
     Exception,
+    #[brw(magic = b"TAGS")]
+    Tags,
+    #[brw(magic = b"LICN")]
+    License,
+    #[brw(magic = b"END ")]
+    EndOfFile,
+    #[brw(magic = b"CRC ")]
+    ChunkCrc,
+    #[brw(magic = b"ANCH")]
+    Anchors,
+    #[brw(magic = b"IOST")]
+    IoStimulus,
+    /// A vendor or experimental chunk. See [MooChunkType::is_vendor_id] for the reserved ID range.
+    Vendor(#[br(assert(MooChunkType::is_vendor_id(&self_0)))] [u8; 4]),
+    /// A chunk ID this version of the crate does not recognize, such as a chunk type added by a
+    /// later revision of the format. Readers skip these chunks by their size.
+    Unknown([u8; 4]),
 }
 
 impl MooChunkType {
+    /// Returns true if `id` falls within the chunk ID range reserved for vendor or experimental
+    /// chunks. Vendor chunk IDs begin with a lowercase ASCII letter, followed by three printable
+    /// ASCII characters, e.g. `lacp`. Official chunk IDs will never begin with a lowercase letter.
+    pub fn is_vendor_id(id: &[u8; 4]) -> bool {
+        id[0].is_ascii_lowercase() && id[1..].iter().all(|b| b.is_ascii_graphic() || *b == b' ')
+    }
+
+    /// Returns the four-byte chunk ID for vendor and unknown chunks, or `None` for chunk types
+    /// with a fixed ID.
+    pub fn raw_id(&self) -> Option<[u8; 4]> {
+        match self {
+            MooChunkType::Vendor(id) | MooChunkType::Unknown(id) => Some(*id),
+            _ => None,
+        }
+    }
+
     pub fn write<WS, T>(&self, writer: &mut WS, payload: &T) -> BinResult<()>
     where
         WS: Write + Seek,
@@ -103,15 +139,44 @@ pub struct MooChunkHeader {
     pub size: u32,
 }
 
+impl MooChunkHeader {
+    /// The serialized size of a chunk header in bytes: a four-byte chunk ID followed by a `uint32`
+    /// payload size.
+    pub const SIZE: usize = 8;
+}
+
 #[derive(Debug)]
 #[binrw]
 #[brw(little)]
 pub struct MooFileHeader {
     pub major_version: u8,
     pub minor_version: u8,
-    pub reserved: [u8; 2],
+    /// File-level option flags. See the `FLAG_*` constants for bit definitions.
+    pub flags: u8,
+    pub reserved: u8,
     pub test_count: u32,
-    pub cpu_id: [u8; 4],
+    /// The architecture tag, validated on read.
+    pub cpu_id: MooArchTag,
+}
+
+impl MooFileHeader {
+    /// If set, every top-level chunk following the file header ends with a `CRC ` subchunk
+    /// holding a `uint32` CRC32 of the chunk's type, length and payload bytes up to the subchunk.
+    /// The subchunk is counted in the chunk's length.
+    pub const FLAG_CHUNK_CRC32: u8 = 0b0000_0001;
+    /// If set, the file ends with an `END ` chunk. A file with this flag but no `END ` chunk has
+    /// been truncated.
+    pub const FLAG_END_CHUNK: u8 = 0b0000_0010;
+
+    /// Returns true if the file contains per-chunk CRC32 values.
+    pub fn has_chunk_crc(&self) -> bool {
+        self.flags & Self::FLAG_CHUNK_CRC32 != 0
+    }
+
+    /// Returns true if the file is terminated by an `END ` chunk.
+    pub fn has_end_chunk(&self) -> bool {
+        self.flags & Self::FLAG_END_CHUNK != 0
+    }
 }
 
 #[derive(Debug)]
@@ -140,9 +205,48 @@ pub struct MooBytesChunk {
     pub bytes: Vec<u8>,
 }
 
+/// A [MooEndChunk] marks the end of a file, repeating the number of tests it contains.
+#[derive(Debug)]
+#[binrw]
+#[brw(little)]
+pub struct MooEndChunk {
+    pub test_count: u32,
+}
+
+/// A [MooTagsChunk] holds the set of tags attached to a test, each encoded as a [MooNameChunk].
+#[binrw]
+#[brw(little)]
+pub struct MooTagsChunk {
+    pub tag_ct: u32,
+    #[br(count = tag_ct)]
+    pub tags:   Vec<MooNameChunk>,
+}
+
+/// A [MooLicenseChunk] holds a license identifier followed by a list of contributor credits,
+/// each encoded as a [MooNameChunk].
+#[binrw]
+#[brw(little)]
+pub struct MooLicenseChunk {
+    pub identifier: MooNameChunk,
+    pub contributor_ct: u32,
+    #[br(count = contributor_ct)]
+    pub contributors: Vec<MooNameChunk>,
+}
+
 #[derive(Debug)]
 #[binrw]
 #[brw(little)]
 pub struct MooHashChunk {
     pub hash: [u8; 20],
 }
+
+/// A [MooDigestChunk] holds an alternate digest of a test, identified by algorithm ID.
+#[derive(Debug)]
+#[binrw]
+#[brw(little)]
+pub struct MooDigestChunk {
+    pub algorithm: u8,
+    pub len: u8,
+    #[br(count = len)]
+    pub digest: Vec<u8>,
+}
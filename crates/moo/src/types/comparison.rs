@@ -20,24 +20,32 @@
     FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
     DEALINGS IN THE SOFTWARE.
 */
-use crate::types::MooRamEntry;
+use std::fmt::{self, Display};
+
+use crate::{registers::MooRegister, types::MooRamEntry};
 
 #[allow(unused_imports)]
 use crate::prelude::MooTest;
 
 /// An enumeration of possible results when comparing two [MooTest]s.
+///
+/// Where two values are given, the first is from the test `compare` was called on and the second
+/// from the test it was compared against.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum MooComparison {
     /// The two [MooTest]s are equal.
     Equal,
-    /// The two [MooTest]s differ in register values.
-    RegisterMismatch,
+    /// The two [MooTest]s differ in the final value of a register, with the register and both
+    /// values provided.
+    RegisterMismatch(MooRegister, u32, u32),
+    /// The two [MooTest]s have final register states of different widths.
+    RegisterWidthMismatch,
     /// The two [MooTest]s differ in cycle count, with the differing values provided.
     CycleCountMismatch(usize, usize),
-    /// The two [MooTest]s differ in cycle address, with the differing values provided.
-    CycleAddressMismatch(u32, u32),
-    /// The two [MooTest]s differ in bus state, with the differing values provided.
-    CycleBusMismatch(u8, u8),
+    /// The two [MooTest]s differ in cycle address, with the cycle index and differing values provided.
+    CycleAddressMismatch(usize, u32, u32),
+    /// The two [MooTest]s differ in bus state, with the cycle index and differing values provided.
+    CycleBusMismatch(usize, u8, u8),
     /// The two [MooTest]s differ in memory address, with the differing entries provided.
     MemoryAddressMismatch(MooRamEntry, MooRamEntry),
     /// The two [MooTest]s differ in memory values, with the differing entries provided.
@@ -45,3 +53,52 @@ pub enum MooComparison {
     /// The two [MooTest]s differ in ALE signal state, with the cycle number and differing values provided.
     ALEMismatch(usize, bool, bool),
 }
+
+/// Options for [MooTest::compare_final_state].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MooCompareOptions {
+    /// Return after finding the first difference.
+    pub return_first: bool,
+    /// Bits of the flags register to ignore, such as flags left undefined by the instruction.
+    pub ignore_flags: u32,
+}
+
+impl MooCompareOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to set whether to return after the first difference.
+    pub fn with_return_first(mut self, return_first: bool) -> Self {
+        self.return_first = return_first;
+        self
+    }
+
+    /// Builder-style method to set the flag bits to ignore.
+    pub fn with_ignore_flags(mut self, ignore_flags: u32) -> Self {
+        self.ignore_flags = ignore_flags;
+        self
+    }
+}
+
+impl Display for MooComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MooComparison::Equal => write!(f, "equal"),
+            MooComparison::RegisterMismatch(reg, a, b) => write!(f, "register {:?}: {:X} != {:X}", reg, a, b),
+            MooComparison::RegisterWidthMismatch => write!(f, "register width mismatch"),
+            MooComparison::CycleCountMismatch(a, b) => write!(f, "cycle count: {} != {}", a, b),
+            MooComparison::CycleAddressMismatch(i, a, b) => {
+                write!(f, "cycle {} address: {:06X} != {:06X}", i, a, b)
+            }
+            MooComparison::CycleBusMismatch(i, a, b) => write!(f, "cycle {} bus state: {} != {}", i, a, b),
+            MooComparison::MemoryAddressMismatch(a, b) => {
+                write!(f, "memory address: {:06X} != {:06X}", a.address, b.address)
+            }
+            MooComparison::MemoryValueMismatch(a, b) => {
+                write!(f, "memory {:06X}: {:02X} != {:02X}", a.address, a.value, b.value)
+            }
+            MooComparison::ALEMismatch(i, a, b) => write!(f, "cycle {} ALE: {} != {}", i, a, b),
+        }
+    }
+}
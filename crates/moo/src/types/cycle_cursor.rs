@@ -0,0 +1,178 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A seekable cursor over a test's cycles, for debuggers that step through a test alongside an
+//! emulator.
+//!
+//! [MooCycleCursor] keeps a [MooInterpolatedState] in step with its position and knows the bus
+//! transaction each cycle belongs to. Moving backwards replays from the nearest checkpoint
+//! rather than from the start of the trace, so stepping back and forth through a long test stays
+//! cheap.
+
+use std::collections::VecDeque;
+
+use crate::{
+    test::moo_test::MooTest,
+    types::{
+        cycle_iter::{MooBusTransaction, MooCycleIterExt},
+        interpolate::MooInterpolatedState,
+        MooCpuType,
+        MooCycleState,
+    },
+};
+
+/// The number of cycles between saved states.
+const CHECKPOINT_INTERVAL: usize = 64;
+
+/// A cursor over the cycles of a [MooTest]. The position counts the cycles applied, from 0 (the
+/// initial state) to the number of cycles in the test (the end of the trace).
+pub struct MooCycleCursor<'a> {
+    test: &'a MooTest,
+    transactions: Vec<MooBusTransaction>,
+    /// The state at every multiple of [CHECKPOINT_INTERVAL] reached so far.
+    checkpoints: Vec<MooInterpolatedState>,
+    state: MooInterpolatedState,
+}
+
+impl<'a> MooCycleCursor<'a> {
+    /// Create a cursor positioned before the first cycle of `test`.
+    pub fn new(test: &'a MooTest, cpu_type: MooCpuType) -> Self {
+        let state = MooInterpolatedState::new(test, cpu_type);
+        Self {
+            test,
+            transactions: test.cycles_iter().transactions().collect(),
+            checkpoints: vec![state.clone()],
+            state,
+        }
+    }
+
+    /// Return the number of cycles applied.
+    pub fn position(&self) -> usize {
+        self.state.cycle()
+    }
+
+    /// Return the number of cycles in the test.
+    pub fn len(&self) -> usize {
+        self.test.cycles().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if every cycle has been applied.
+    pub fn is_at_end(&self) -> bool {
+        self.position() >= self.len()
+    }
+
+    /// Move to `position`, clamped to the end of the trace, and return the new position.
+    pub fn seek(&mut self, position: usize) -> usize {
+        let target = position.min(self.len());
+        if target < self.position() {
+            let checkpoint = (target / CHECKPOINT_INTERVAL).min(self.checkpoints.len() - 1);
+            self.state = self.checkpoints[checkpoint].clone();
+        }
+        while self.position() < target {
+            self.apply_next();
+        }
+        target
+    }
+
+    /// Apply the next cycle and return it, or `None` at the end of the trace.
+    pub fn step(&mut self) -> Option<MooCycleState> {
+        if self.is_at_end() {
+            return None;
+        }
+        self.apply_next();
+        self.current()
+    }
+
+    /// Undo the last applied cycle and return the cycle now current, or `None` if the cursor is
+    /// back at the initial state. Returns `None` without moving if it was already there.
+    pub fn prev(&mut self) -> Option<MooCycleState> {
+        let position = self.position().checked_sub(1)?;
+        self.seek(position);
+        self.current()
+    }
+
+    /// Move to the end of the next bus transaction's ALE cycle, and return that transaction.
+    pub fn step_transaction(&mut self) -> Option<&MooBusTransaction> {
+        let index = self.transactions.iter().position(|t| t.start >= self.position())?;
+        self.seek(self.transactions[index].start + 1);
+        self.transactions.get(index)
+    }
+
+    /// Return the last applied cycle, or `None` at the initial state.
+    pub fn current(&self) -> Option<MooCycleState> {
+        let index = self.position().checked_sub(1)?;
+        self.test.cycles().get(index).copied()
+    }
+
+    /// Return the bus transaction the last applied cycle belongs to. A transaction runs from its
+    /// ALE cycle up to the next one, so idle cycles belong to the transaction before them.
+    pub fn transaction(&self) -> Option<&MooBusTransaction> {
+        let index = self.position().checked_sub(1)?;
+        self.transactions
+            .iter()
+            .take_while(|t| t.start <= index)
+            .last()
+            .filter(|t| index < t.start + t.len)
+    }
+
+    /// Return the address latched by the current transaction.
+    pub fn address_latch(&self) -> Option<u32> {
+        self.transaction().map(|t| t.address)
+    }
+
+    /// Return the approximate contents of the prefetch queue.
+    pub fn queue(&self) -> &VecDeque<u8> {
+        self.state.queue()
+    }
+
+    /// Return the reconstructed state at the cursor: memory, writes so far and the approximate
+    /// instruction pointer.
+    pub fn state(&self) -> &MooInterpolatedState {
+        &self.state
+    }
+
+    /// Return all bus transactions of the test.
+    pub fn transactions(&self) -> &[MooBusTransaction] {
+        &self.transactions
+    }
+
+    fn apply_next(&mut self) {
+        let cycle = self.test.cycles()[self.position()];
+        self.state.step(&cycle);
+        let position = self.position();
+        if position.is_multiple_of(CHECKPOINT_INTERVAL) && position / CHECKPOINT_INTERVAL == self.checkpoints.len() {
+            self.checkpoints.push(self.state.clone());
+        }
+    }
+}
+
+impl MooTest {
+    /// Create a [MooCycleCursor] over this test's cycles.
+    pub fn cycle_cursor(&self, cpu_type: MooCpuType) -> MooCycleCursor<'_> {
+        MooCycleCursor::new(self, cpu_type)
+    }
+}
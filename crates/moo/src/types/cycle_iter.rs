@@ -0,0 +1,224 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Iterator adapters for filtering and grouping sequences of [MooCycleState]s.
+//!
+//! Import [MooCycleIterExt] (also available from the prelude) to use these adapters on any
+//! iterator of [MooCycleState]s, such as the one returned by
+//! [MooTest::cycles_iter](crate::prelude::MooTest::cycles_iter).
+
+use std::iter::Peekable;
+
+use crate::types::{MooBusState, MooCpuDataBusWidth, MooCpuType, MooCycleState, MooDataWidth};
+
+/// Semantic filters and grouping adapters over an iterator of [MooCycleState]s.
+pub trait MooCycleIterExt: Iterator<Item = MooCycleState> + Sized {
+    /// Keep only cycles where ALE (or ADS, translated to ALE) is active.
+    fn ale_cycles(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.ale())
+    }
+
+    /// Keep only cycles where a memory or I/O read strobe is active.
+    fn reads(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.is_reading())
+    }
+
+    /// Keep only cycles where a memory or I/O write strobe is active.
+    fn writes(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.is_writing())
+    }
+
+    /// Keep only cycles where the memory read strobe is active.
+    fn mem_reads(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.is_reading_mem())
+    }
+
+    /// Keep only cycles where the memory write strobe is active.
+    fn mem_writes(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.is_writing_mem())
+    }
+
+    /// Keep only cycles where the I/O read strobe is active.
+    fn io_reads(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.is_reading_io())
+    }
+
+    /// Keep only cycles where the I/O write strobe is active.
+    fn io_writes(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.is_writing_io())
+    }
+
+    /// Keep only cycles where an I/O read or write strobe is active.
+    fn io_ops(self) -> impl Iterator<Item = MooCycleState> {
+        self.filter(|c| c.is_reading_io() || c.is_writing_io())
+    }
+
+    /// Keep only cycles performing a code fetch, as decoded for the given [MooCpuType].
+    fn code_fetches(self, cpu_type: MooCpuType) -> impl Iterator<Item = MooCycleState> {
+        self.filter(move |c| c.is_code_fetch(cpu_type))
+    }
+
+    /// Keep only cycles whose decoded bus state, for the given [MooCpuType], is `bus_state`.
+    fn in_bus_state(self, cpu_type: MooCpuType, bus_state: MooBusState) -> impl Iterator<Item = MooCycleState> {
+        self.filter(move |c| c.bus_state(cpu_type) == bus_state)
+    }
+
+    /// Group cycles into [MooBusTransaction]s. A transaction begins at a cycle with ALE active and
+    /// extends up to the next such cycle. Cycles preceding the first ALE are skipped.
+    fn transactions(self) -> MooBusTransactions<Self> {
+        MooBusTransactions {
+            inner: self.enumerate().peekable(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = MooCycleState>> MooCycleIterExt for I {}
+
+/// A [MooBusTransaction] summarizes a single bus cycle, from the cycle where the address was
+/// latched to the cycle preceding the next address latch.
+#[derive(Copy, Clone, Debug)]
+pub struct MooBusTransaction {
+    /// The index of the ALE cycle that began this transaction.
+    pub start: usize,
+    /// The number of cycles in this transaction, including the ALE cycle.
+    pub len: usize,
+    /// The address latched at the start of the transaction.
+    pub address: u32,
+    /// The raw bus state at the start of the transaction.
+    pub bus_state: u8,
+    /// The memory status bits of all cycles in the transaction, ORed together.
+    pub memory_status: u8,
+    /// The I/O status bits of all cycles in the transaction, ORed together.
+    pub io_status: u8,
+    /// The contents of the data bus on the last cycle with a read or write strobe active, if any.
+    pub data_bus: Option<u16>,
+    /// Whether LOCK was asserted on any cycle of the transaction. See [MooCycleState::lock].
+    pub locked: bool,
+    /// The raw `pins0` bitfield of the ALE cycle, where BHE is valid alongside the address.
+    pub pins0: u8,
+    /// The raw `pins1` bitfield of the ALE cycle.
+    pub pins1: u8,
+}
+
+impl MooBusTransaction {
+    /// Returns true if `other` performs the same bus operation: the same address, bus state,
+    /// status and data. The timing of the two transactions is not compared.
+    pub fn same_operation(&self, other: &MooBusTransaction) -> bool {
+        self.address == other.address
+            && self.bus_state == other.bus_state
+            && self.memory_status == other.memory_status
+            && self.io_status == other.io_status
+            && self.data_bus == other.data_bus
+    }
+    /// Returns true if the memory read strobe was active during this transaction.
+    pub fn is_mem_read(&self) -> bool {
+        self.memory_status & MooCycleState::MRDC_BIT != 0
+    }
+    /// Returns true if the memory write strobe was active during this transaction.
+    pub fn is_mem_write(&self) -> bool {
+        self.memory_status & MooCycleState::MWTC_BIT != 0
+    }
+    /// Returns true if the I/O read strobe was active during this transaction.
+    pub fn is_io_read(&self) -> bool {
+        self.io_status & MooCycleState::IORC_BIT != 0
+    }
+    /// Returns true if the I/O write strobe was active during this transaction.
+    pub fn is_io_write(&self) -> bool {
+        self.io_status & MooCycleState::IOWC_BIT != 0
+    }
+    /// Returns the data bus lanes enabled by A0 and BHE for this transaction, for the given
+    /// [MooCpuType]. Returns [MooDataWidth::Invalid] for an odd address without BHE, which
+    /// enables no lanes on a 16-bit bus.
+    pub fn data_width(&self, cpu_type: MooCpuType) -> MooDataWidth {
+        let ale_cycle = MooCycleState {
+            pins0: self.pins0,
+            pins1: self.pins1,
+            ..Default::default()
+        };
+        match MooCpuDataBusWidth::from(cpu_type) {
+            MooCpuDataBusWidth::Eight => MooDataWidth::EightLow,
+            MooCpuDataBusWidth::Sixteen => match (self.address & 1 != 0, ale_cycle.bhe_active(cpu_type)) {
+                (false, true) => MooDataWidth::Sixteen,
+                (false, false) => MooDataWidth::EightLow,
+                (true, true) => MooDataWidth::EightHigh,
+                (true, false) => MooDataWidth::Invalid,
+            },
+        }
+    }
+    /// Returns the decoded [MooBusState] at the start of the transaction, for the given [MooCpuType].
+    pub fn bus_state(&self, cpu_type: MooCpuType) -> MooBusState {
+        cpu_type.decode_status(self.bus_state)
+    }
+}
+
+/// An iterator grouping [MooCycleState]s into [MooBusTransaction]s.
+/// Created by [MooCycleIterExt::transactions].
+pub struct MooBusTransactions<I: Iterator<Item = MooCycleState>> {
+    inner: Peekable<std::iter::Enumerate<I>>,
+}
+
+impl<I: Iterator<Item = MooCycleState>> Iterator for MooBusTransactions<I> {
+    type Item = MooBusTransaction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip to the next ALE cycle.
+        let (start, ale_cycle) = loop {
+            let (i, cycle) = self.inner.next()?;
+            if cycle.ale() {
+                break (i, cycle);
+            }
+        };
+
+        let mut transaction = MooBusTransaction {
+            start,
+            len: 1,
+            address: ale_cycle.address_bus,
+            bus_state: ale_cycle.bus_state,
+            memory_status: ale_cycle.memory_status,
+            io_status: ale_cycle.io_status,
+            data_bus: None,
+            locked: ale_cycle.lock(),
+            pins0: ale_cycle.pins0,
+            pins1: ale_cycle.pins1,
+        };
+
+        let mut cycle = ale_cycle;
+        loop {
+            if cycle.is_reading() || cycle.is_writing() {
+                transaction.data_bus = Some(cycle.data_bus);
+            }
+            match self.inner.next_if(|(_, c)| !c.ale()) {
+                Some((_, next)) => {
+                    transaction.len += 1;
+                    transaction.memory_status |= next.memory_status;
+                    transaction.io_status |= next.io_status;
+                    transaction.locked |= next.lock();
+                    cycle = next;
+                }
+                None => break,
+            }
+        }
+
+        Some(transaction)
+    }
+}
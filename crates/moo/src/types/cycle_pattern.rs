@@ -0,0 +1,227 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A small pattern language for matching sequences of bus transactions in a cycle trace.
+//!
+//! A pattern is a sequence of steps separated by `then`. Each step names a bus state, optionally
+//! followed by `@` and an address or inclusive address range. A step may be constrained to begin
+//! within a number of cycles of the previous step with a trailing `within N`.
+//!
+//! ```text
+//! MEMR@0xB8000 then IOW@0x3D4 within 10
+//! CODE@0xFFFF0-0xFFFFF then MEMW
+//! ```
+//!
+//! Bus states are `INTA`, `IOR`, `IOW`, `HALT`, `CODE`, `MEMR` and `MEMW`, matched
+//! case-insensitively. Addresses may be given in hexadecimal with a `0x` prefix, or in decimal.
+//! I/O addresses are compared against the low 16 bits of the address bus.
+
+use std::{fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use crate::{
+    test::moo_test::MooTest,
+    types::{
+        cycle_iter::{MooBusTransaction, MooCycleIterExt},
+        errors::MooError,
+        MooBusState,
+        MooCpuType,
+    },
+};
+
+/// A single step of a [MooCyclePattern], matching one bus transaction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MooCyclePatternStep {
+    /// The bus state the transaction must have.
+    pub bus_state: MooBusState,
+    /// The address, or range of addresses, the transaction must target, if any.
+    pub address:   Option<RangeInclusive<u32>>,
+    /// The maximum number of cycles between the start of the previous step's transaction and the
+    /// start of this one. Ignored for the first step.
+    pub within:    Option<usize>,
+}
+
+impl MooCyclePatternStep {
+    fn matches(&self, transaction: &MooBusTransaction, cpu_type: MooCpuType) -> bool {
+        let bus_state = transaction.bus_state(cpu_type);
+        if bus_state != self.bus_state {
+            return false;
+        }
+        match &self.address {
+            Some(range) => {
+                let address = if bus_state.is_io() {
+                    transaction.address & 0xFFFF
+                }
+                else {
+                    transaction.address
+                };
+                range.contains(&address)
+            }
+            None => true,
+        }
+    }
+}
+
+/// A [MooCyclePattern] matches an ordered sequence of bus transactions within a test's cycles.
+/// Parse one from a string with [str::parse]; see the [module documentation](self) for syntax.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MooCyclePattern {
+    steps: Vec<MooCyclePatternStep>,
+}
+
+impl MooCyclePattern {
+    /// Returns the steps of this pattern.
+    pub fn steps(&self) -> &[MooCyclePatternStep] {
+        &self.steps
+    }
+
+    /// Search the cycles of `test` for this pattern, decoding bus states for `cpu_type`.
+    /// Returns the starting cycle index of the transaction matched by each step, for the earliest
+    /// match, or `None` if the pattern does not occur.
+    pub fn find(&self, test: &MooTest, cpu_type: MooCpuType) -> Option<Vec<usize>> {
+        let transactions: Vec<MooBusTransaction> = test.cycles_iter().transactions().collect();
+        let mut matched = Vec::with_capacity(self.steps.len());
+        self.find_from(&transactions, cpu_type, 0, &mut matched)
+            .then_some(matched)
+    }
+
+    /// Returns true if this pattern occurs in the cycles of `test`.
+    pub fn is_match(&self, test: &MooTest, cpu_type: MooCpuType) -> bool {
+        self.find(test, cpu_type).is_some()
+    }
+
+    /// Try to match the remaining steps starting at transaction `start`, backtracking as needed
+    /// so that a `within` constraint on a later step can be satisfied by a later candidate.
+    fn find_from(
+        &self,
+        transactions: &[MooBusTransaction],
+        cpu_type: MooCpuType,
+        start: usize,
+        matched: &mut Vec<usize>,
+    ) -> bool {
+        let Some(step) = self.steps.get(matched.len())
+        else {
+            return true;
+        };
+        let prev_cycle = matched.last().copied();
+
+        for (i, transaction) in transactions.iter().enumerate().skip(start) {
+            if let (Some(prev), Some(within)) = (prev_cycle, step.within) {
+                if transaction.start - prev > within {
+                    break;
+                }
+            }
+            if step.matches(transaction, cpu_type) {
+                matched.push(transaction.start);
+                if self.find_from(transactions, cpu_type, i + 1, matched) {
+                    return true;
+                }
+                matched.pop();
+            }
+        }
+        false
+    }
+}
+
+impl FromStr for MooCyclePattern {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        let mut tokens = s.split_whitespace().peekable();
+
+        loop {
+            let step_str = tokens.next().ok_or_else(|| pattern_err(s, "expected a bus state"))?;
+            let mut step = parse_step(s, step_str)?;
+
+            if tokens.peek().is_some_and(|t| t.eq_ignore_ascii_case("within")) {
+                tokens.next();
+                if steps.is_empty() {
+                    return Err(pattern_err(s, "'within' cannot apply to the first step"));
+                }
+                let cycles = tokens
+                    .next()
+                    .and_then(|t| t.parse::<usize>().ok())
+                    .ok_or_else(|| pattern_err(s, "expected a cycle count after 'within'"))?;
+                step.within = Some(cycles);
+            }
+            steps.push(step);
+
+            match tokens.next() {
+                Some(t) if t.eq_ignore_ascii_case("then") => continue,
+                Some(t) => return Err(pattern_err(s, format!("expected 'then', found '{}'", t))),
+                None => break,
+            }
+        }
+        Ok(Self { steps })
+    }
+}
+
+fn parse_step(pattern: &str, step: &str) -> Result<MooCyclePatternStep, MooError> {
+    let (state_str, address_str) = match step.split_once('@') {
+        Some((state, address)) => (state, Some(address)),
+        None => (step, None),
+    };
+
+    let bus_state = match state_str.to_ascii_uppercase().as_str() {
+        "INTA" => MooBusState::INTA,
+        "IOR" => MooBusState::IOR,
+        "IOW" => MooBusState::IOW,
+        "HALT" => MooBusState::HALT,
+        "CODE" => MooBusState::CODE,
+        "MEMR" => MooBusState::MEMR,
+        "MEMW" => MooBusState::MEMW,
+        _ => return Err(pattern_err(pattern, format!("unknown bus state '{}'", state_str))),
+    };
+
+    let address = match address_str {
+        Some(address_str) => {
+            let (lo, hi) = match address_str.split_once('-') {
+                Some((lo, hi)) => (parse_address(pattern, lo)?, parse_address(pattern, hi)?),
+                None => {
+                    let address = parse_address(pattern, address_str)?;
+                    (address, address)
+                }
+            };
+            Some(lo..=hi)
+        }
+        None => None,
+    };
+
+    Ok(MooCyclePatternStep {
+        bus_state,
+        address,
+        within: None,
+    })
+}
+
+fn parse_address(pattern: &str, address: &str) -> Result<u32, MooError> {
+    let parsed = match address.strip_prefix("0x").or_else(|| address.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => address.parse::<u32>(),
+    };
+    parsed.map_err(|_| pattern_err(pattern, format!("invalid address '{}'", address)))
+}
+
+fn pattern_err(pattern: &str, msg: impl Display) -> MooError {
+    MooError::ParseError(format!("invalid cycle pattern \"{}\": {}", pattern, msg))
+}
@@ -21,7 +21,7 @@
     DEALINGS IN THE SOFTWARE.
 */
 
-use crate::types::{MooBusState, MooCpuDataBusWidth, MooCpuType, MooDataWidth, MooTState};
+use crate::types::{MooBusState, MooCpuDataBusWidth, MooCpuFamily, MooCpuType, MooDataWidth, MooTState};
 use binrw::binrw;
 use std::fmt::Display;
 
@@ -30,6 +30,7 @@ use std::fmt::Display;
 ///
 /// This struct corresponds to the payload of a `CYCL` chunk in a `MOO` test file.
 #[derive(Copy, Clone, Debug, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[binrw]
 #[brw(little)]
 pub struct MooCycleState {
@@ -72,6 +73,8 @@ impl MooCycleState {
     pub const PIN_READY: u8 = 0b0000_0100;
     /// A constant mask for the LOCK pin in the pins0 field.
     pub const PIN_LOCK: u8 = 0b0000_1000;
+    /// A constant mask for the BHE pin in the pins1 field, used by the 8086 and V30.
+    pub const PIN1_BHE: u8 = 0b0000_0001;
 
     /// A constant mask for the MRDC (Memory Read) bit in the memory_status field.
     pub const MRDC_BIT: u8 = 0b0000_0100;
@@ -87,11 +90,49 @@ impl MooCycleState {
     /// A constant mask for the IOWC (I/O Write) bit in the io_status field.
     pub const IOWC_BIT: u8 = 0b0000_0001;
 
+    /// The size in bytes of a single serialized [MooCycleState] record within a `CYCL` chunk.
+    pub const RECORD_SIZE: usize = 15;
+
+    /// Decode a [MooCycleState] from a single serialized little-endian cycle record.
+    /// This is equivalent to reading the record via [binrw], but avoids the per-field overhead of
+    /// a generic reader when decoding large numbers of cycles at once.
+    #[inline]
+    pub fn from_le_bytes(bytes: &[u8; Self::RECORD_SIZE]) -> Self {
+        Self {
+            pins0: bytes[0],
+            address_bus: u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]),
+            segment: bytes[5],
+            memory_status: bytes[6],
+            io_status: bytes[7],
+            pins1: bytes[8],
+            data_bus: u16::from_le_bytes([bytes[9], bytes[10]]),
+            bus_state: bytes[11],
+            t_state: bytes[12],
+            queue_op: bytes[13],
+            queue_byte: bytes[14],
+        }
+    }
+
     /// Returns true if the BHE (Bus High Enable) pin is active (low).
     #[inline]
     pub fn bhe(&self) -> bool {
         self.pins0 & MooCycleState::PIN_BHE == 0
     }
+    /// Returns true if the BHE (Bus High Enable) pin is active (low), reading it from the pin
+    /// bitfield where the given [MooCpuType] records it.
+    #[inline]
+    pub fn bhe_active(&self, cpu_type: MooCpuType) -> bool {
+        match MooCpuFamily::from(cpu_type) {
+            MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 => self.pins1 & MooCycleState::PIN1_BHE == 0,
+            _ => self.bhe(),
+        }
+    }
+    /// Returns true if the LOCK pin is active (low).
+    /// Only the 80286 and 80386 record LOCK; for other CPUs this value is meaningless.
+    #[inline]
+    pub fn lock(&self) -> bool {
+        self.pins0 & MooCycleState::PIN_LOCK == 0
+    }
     /// Returns true if the ALE (Address Latch Enable) pin is active (high).
     /// On architectures that use an active-low ADS signal, ADS is translated to ALE for consistency.
     #[inline]
@@ -21,7 +21,10 @@
     DEALINGS IN THE SOFTWARE.
 */
 
-use crate::registers::MooSegmentRegister;
+use crate::{
+    registers::MooSegmentRegister,
+    types::{MooCpuDataBusWidth, MooCpuType},
+};
 
 use binrw::binrw;
 
@@ -79,4 +82,55 @@ impl MooEffectiveAddress {
             physical_address: linear_address,
         }
     }
+
+    /// Set the physical address, for tests where paging translates the linear address.
+    pub fn with_physical_address(mut self, physical_address: u32) -> Self {
+        self.physical_address = physical_address;
+        self
+    }
+
+    /// Return the segment register used as the base for the effective address.
+    pub fn segment(&self) -> MooSegmentRegister {
+        self.base_segment
+    }
+
+    /// Return the logical address as a `(selector, offset)` pair.
+    pub fn logical(&self) -> (u16, u32) {
+        (self.base_selector, self.offset)
+    }
+
+    /// Return the linear address.
+    pub fn linear(&self) -> u32 {
+        self.linear_address
+    }
+
+    /// Return the physical address.
+    pub fn physical(&self) -> u32 {
+        self.physical_address
+    }
+
+    /// Returns true if the physical address differs from the linear address, indicating that
+    /// paging was in effect.
+    pub fn is_paged(&self) -> bool {
+        self.physical_address != self.linear_address
+    }
+
+    /// Returns true if the stored linear address equals the segment base plus the offset.
+    pub fn is_consistent(&self) -> bool {
+        self.linear_address == self.base_address.wrapping_add(self.offset)
+    }
+
+    /// Returns true if the offset lies beyond the segment limit.
+    pub fn exceeds_limit(&self) -> bool {
+        self.offset > self.base_limit
+    }
+
+    /// Returns true if `address`, as latched on the bus of the specified [MooCpuType], refers to
+    /// the physical address. On a 16-bit bus, addresses are compared by word.
+    pub fn matches_bus_address(&self, cpu_type: MooCpuType, address: u32) -> bool {
+        match MooCpuDataBusWidth::from(cpu_type) {
+            MooCpuDataBusWidth::Eight => address == self.physical_address,
+            MooCpuDataBusWidth::Sixteen => address & !1 == self.physical_address & !1,
+        }
+    }
 }
@@ -23,12 +23,26 @@
 
 use thiserror::Error;
 
+use crate::test_file::read_options::MooReadLimit;
+
 #[derive(Error, Debug)]
 pub enum MooError {
     #[error("Error parsing MOO file: {0}")]
     ParseError(String),
+    #[error("Checksum mismatch in MOO file: {0}")]
+    ChecksumError(String),
     #[error("Error writing MOO file: {0}")]
     WriteError(String),
+    #[error("MOO file {limit} ({value}) exceeds the read limit of {max}")]
+    LimitExceeded { limit: MooReadLimit, value: u64, max: u64 },
+    #[error("Error trimming test cycles: {0}")]
+    TrimError(String),
+    #[error("Error merging RAM entries: {0}")]
+    MergeError(String),
+    #[error("Error decoding instruction: {0}")]
+    DecodeError(String),
+    #[error("Invalid metadata: {0}")]
+    MetadataError(String),
     #[error("A compliant MOO file was not detected")]
     FileDetectionError,
     #[error("An unknown error occurred")]
@@ -0,0 +1,122 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Names for exception vectors, and [Display] implementations for [MooException].
+//!
+//! A [MooException] only records the vector number and the address the flags were pushed to.
+//! [MooTest::exception_context] adds the handler the vector resolves to, when the test runs in real
+//! mode and its initial RAM holds the IVT entry.
+
+use std::fmt::Display;
+
+use crate::{
+    test::moo_test::MooTest,
+    types::{ivt::MooIvtEntry, MooCpuMode, MooCpuType, MooException},
+};
+
+/// Mnemonics and descriptions of the architecturally defined exception vectors.
+const EXCEPTION_NAMES: [(Option<&str>, &str); 18] = [
+    (Some("#DE"), "divide error"),
+    (Some("#DB"), "debug"),
+    (None, "non-maskable interrupt"),
+    (Some("#BP"), "breakpoint"),
+    (Some("#OF"), "overflow"),
+    (Some("#BR"), "bound range exceeded"),
+    (Some("#UD"), "invalid opcode"),
+    (Some("#NM"), "device not available"),
+    (Some("#DF"), "double fault"),
+    (None, "coprocessor segment overrun"),
+    (Some("#TS"), "invalid TSS"),
+    (Some("#NP"), "segment not present"),
+    (Some("#SS"), "stack fault"),
+    (Some("#GP"), "general protection"),
+    (Some("#PF"), "page fault"),
+    (None, "reserved"),
+    (Some("#MF"), "floating-point error"),
+    (Some("#AC"), "alignment check"),
+];
+
+/// Return the mnemonic of an exception vector, such as `#GP`, if it has one.
+pub fn vector_mnemonic(vector: u8) -> Option<&'static str> {
+    EXCEPTION_NAMES.get(vector as usize).and_then(|(mnemonic, _)| *mnemonic)
+}
+
+/// Return a short description of an exception vector, if it is architecturally defined.
+pub fn vector_name(vector: u8) -> Option<&'static str> {
+    EXCEPTION_NAMES.get(vector as usize).map(|(_, name)| *name)
+}
+
+impl MooException {
+    pub fn mnemonic(&self) -> Option<&'static str> {
+        vector_mnemonic(self.exception_num)
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        vector_name(self.exception_num)
+    }
+}
+
+impl Display for MooException {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02X}", self.exception_num)?;
+        if let Some(mnemonic) = self.mnemonic() {
+            write!(f, " {}", mnemonic)?;
+        }
+        if let Some(name) = self.name() {
+            write!(f, " ({})", name)?;
+        }
+        write!(f, ", flags at {:06X}", self.flag_address)
+    }
+}
+
+/// A [MooException] together with the handler its vector points to.
+#[derive(Clone, Debug)]
+pub struct MooExceptionContext {
+    pub exception: MooException,
+    /// The IVT entry for the exception, if the test runs in real mode and its initial RAM holds
+    /// the entry.
+    pub handler:   Option<MooIvtEntry>,
+}
+
+impl Display for MooExceptionContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.exception)?;
+        if let Some(handler) = &self.handler {
+            write!(f, ", handler {:04X}:{:04X}", handler.segment, handler.offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl MooTest {
+    /// Return the exception raised by the test along with its resolved handler, or `None` if the
+    /// test raised no exception.
+    pub fn exception_context(&self, cpu_type: MooCpuType) -> Option<MooExceptionContext> {
+        let exception = self.exception()?.clone();
+        let handler = match self.cpu_mode(cpu_type) {
+            MooCpuMode::RealMode => self.initial_state().ivt().get(exception.exception_num).copied(),
+            _ => None,
+        };
+        Some(MooExceptionContext { exception, handler })
+    }
+}
@@ -21,6 +21,13 @@
     DEALINGS IN THE SOFTWARE.
 */
 
+use std::{
+    fmt::Display,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not},
+};
+
+use crate::types::{MooCpuFamily, MooCpuType};
+
 /// [MooCpuFlag] represents the individual bits contained within an x86 CPU's FLAGS or EFLAGS
 /// register.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
@@ -31,11 +38,11 @@ pub enum MooCpuFlag {
     Reserved0 = 1,
     /// Parity Flag
     PF = 2,
-    // Reserved bit, always 0
+    /// Reserved bit, always 0
     Reserved1 = 3,
     /// Auxiliary Carry Flag
     AF = 4,
-    /// Reserved bit, always 1
+    /// Reserved bit, always 0
     Reserved2 = 5,
     /// Zero Flag
     ZF = 6,
@@ -61,6 +68,8 @@ pub enum MooCpuFlag {
     RF = 16,
     /// Virtual-8086 Mode flag
     VM = 17,
+    /// Alignment Check flag. Introduced with the 80486; reserved and always 0 on the 80386.
+    AC = 18,
 }
 
 impl MooCpuFlag {
@@ -85,20 +94,324 @@ impl MooCpuFlag {
             15 => Some(MooCpuFlag::Reserved3),
             16 => Some(MooCpuFlag::RF),
             17 => Some(MooCpuFlag::VM),
+            18 => Some(MooCpuFlag::AC),
             _ => None,
         }
     }
+
+    /// Return the bit mask of this flag within the FLAGS or EFLAGS register.
+    #[inline]
+    pub fn mask(self) -> u32 {
+        1 << (self as u32)
+    }
+
+    /// Returns true if this is one of the reserved flag bits.
+    pub fn is_reserved(self) -> bool {
+        matches!(
+            self,
+            MooCpuFlag::Reserved0 | MooCpuFlag::Reserved1 | MooCpuFlag::Reserved2 | MooCpuFlag::Reserved3
+        )
+    }
+
+    /// Return the conventional mnemonic name of this flag, e.g. `"OF"`.
+    /// Reserved bits are named `"R"` followed by their bit index.
+    pub fn name(self) -> &'static str {
+        match self {
+            MooCpuFlag::CF => "CF",
+            MooCpuFlag::Reserved0 => "R1",
+            MooCpuFlag::PF => "PF",
+            MooCpuFlag::Reserved1 => "R3",
+            MooCpuFlag::AF => "AF",
+            MooCpuFlag::Reserved2 => "R5",
+            MooCpuFlag::ZF => "ZF",
+            MooCpuFlag::SF => "SF",
+            MooCpuFlag::TF => "TF",
+            MooCpuFlag::IF => "IF",
+            MooCpuFlag::DF => "DF",
+            MooCpuFlag::OF => "OF",
+            MooCpuFlag::IOPL0 => "IOPL0",
+            MooCpuFlag::IOPL1 => "IOPL1",
+            MooCpuFlag::NT => "NT",
+            MooCpuFlag::Reserved3 => "R15",
+            MooCpuFlag::RF => "RF",
+            MooCpuFlag::VM => "VM",
+            MooCpuFlag::AC => "AC",
+        }
+    }
+}
+
+impl Display for MooCpuFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Join the names of `flags` with commas, in the order given, or return `-` if there are none.
+fn flag_list(flags: impl IntoIterator<Item = MooCpuFlag>) -> String {
+    let names: Vec<&str> = flags.into_iter().map(MooCpuFlag::name).collect();
+    if names.is_empty() {
+        "-".to_string()
+    }
+    else {
+        names.join(", ")
+    }
+}
+
+/// A typed set of [MooCpuFlag]s, wrapping the raw value of a FLAGS or EFLAGS register.
+///
+/// [MooCpuFlags] implements [Display] using the `odiszapc` convention, where each of the
+/// eight arithmetic and control flags is shown as its lowercase letter if set, or `.` if clear.
+/// Any of the EFLAGS-only RF, VM and AC flags that are set are appended by name, e.g.
+/// `o..s...c vm`.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct MooCpuFlags(u32);
+
+impl MooCpuFlags {
+    /// The flags shown by the [Display] implementation, in display order.
+    pub const DISPLAY_ORDER: [(MooCpuFlag, char); 8] = [
+        (MooCpuFlag::OF, 'o'),
+        (MooCpuFlag::DF, 'd'),
+        (MooCpuFlag::IF, 'i'),
+        (MooCpuFlag::SF, 's'),
+        (MooCpuFlag::ZF, 'z'),
+        (MooCpuFlag::AF, 'a'),
+        (MooCpuFlag::PF, 'p'),
+        (MooCpuFlag::CF, 'c'),
+    ];
+
+    /// The EFLAGS-only flags appended by the [Display] implementation when set.
+    pub const EXTENDED_DISPLAY_ORDER: [(MooCpuFlag, &'static str); 3] =
+        [(MooCpuFlag::RF, "rf"), (MooCpuFlag::VM, "vm"), (MooCpuFlag::AC, "ac")];
+
+    /// Create a [MooCpuFlags] from a raw register value.
+    pub const fn from_bits(bits: u32) -> Self {
+        MooCpuFlags(bits)
+    }
+
+    /// Return the raw register value.
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns true if no bits are set.
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if `flag` is set.
+    pub fn contains(&self, flag: MooCpuFlag) -> bool {
+        self.0 & flag.mask() != 0
+    }
+
+    /// Set `flag`.
+    pub fn set(&mut self, flag: MooCpuFlag) {
+        self.0 |= flag.mask();
+    }
+
+    /// Clear `flag`.
+    pub fn clear(&mut self, flag: MooCpuFlag) {
+        self.0 &= !flag.mask();
+    }
+
+    /// Return the flags set in `self` but not in `other`.
+    pub fn difference(self, other: MooCpuFlags) -> MooCpuFlags {
+        MooCpuFlags(self.0 & !other.0)
+    }
+
+    /// Iterate over the [MooCpuFlag]s that are set, in ascending bit order.
+    /// Bits with no corresponding [MooCpuFlag] are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = MooCpuFlag> + '_ {
+        (0..32u8)
+            .filter_map(MooCpuFlag::from_bit)
+            .filter(|flag| self.contains(*flag))
+    }
+
+    /// Return a copy with all reserved and undefined bits cleared.
+    pub fn without_reserved(self) -> MooCpuFlags {
+        self.iter().filter(|flag| !flag.is_reserved()).collect()
+    }
+
+    /// Return the names of the set flags as a comma-separated list, e.g. `"ZF, SF, OF"`,
+    /// ignoring reserved bits. Returns `-` if no flags are set.
+    pub fn to_long_string(&self) -> String {
+        flag_list(self.without_reserved().iter())
+    }
+
+    /// Return the mask of reserved bits that always read as 1 on the specified CPU.
+    pub fn reserved_set_mask(cpu_type: MooCpuType) -> u32 {
+        match MooCpuFamily::from(cpu_type) {
+            // Bits 12-15 are unused on the 8086 and 80186 and read as 1.
+            // On the V30, bit 15 is the MD flag, which always reads as 1 in native mode.
+            MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186 => 0xF002,
+            MooCpuFamily::Intel80286 | MooCpuFamily::Intel80386 => 0x0002,
+        }
+    }
+
+    /// Return the mask of reserved bits that always read as 0 on the specified CPU.
+    pub fn reserved_clear_mask(cpu_type: MooCpuType) -> u32 {
+        match MooCpuFamily::from(cpu_type) {
+            MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186 => 0xFFFF_0028,
+            MooCpuFamily::Intel80286 => 0xFFFF_8028,
+            MooCpuFamily::Intel80386 => 0xFFFC_8028,
+        }
+    }
+
+    /// Return a copy with reserved bits forced to their fixed values for the specified CPU.
+    pub fn normalize(self, cpu_type: MooCpuType) -> MooCpuFlags {
+        MooCpuFlags((self.0 | Self::reserved_set_mask(cpu_type)) & !Self::reserved_clear_mask(cpu_type))
+    }
+
+    /// Returns true if all reserved bits hold their fixed values for the specified CPU.
+    pub fn reserved_bits_valid(&self, cpu_type: MooCpuType) -> bool {
+        self.normalize(cpu_type) == *self
+    }
+}
+
+impl From<u32> for MooCpuFlags {
+    fn from(bits: u32) -> Self {
+        MooCpuFlags(bits)
+    }
+}
+
+impl From<u16> for MooCpuFlags {
+    fn from(bits: u16) -> Self {
+        MooCpuFlags(bits as u32)
+    }
+}
+
+impl From<MooCpuFlags> for u32 {
+    fn from(flags: MooCpuFlags) -> Self {
+        flags.0
+    }
+}
+
+impl FromIterator<MooCpuFlag> for MooCpuFlags {
+    fn from_iter<I: IntoIterator<Item = MooCpuFlag>>(iter: I) -> Self {
+        let mut flags = MooCpuFlags::default();
+        flags.extend(iter);
+        flags
+    }
+}
+
+impl Extend<MooCpuFlag> for MooCpuFlags {
+    fn extend<I: IntoIterator<Item = MooCpuFlag>>(&mut self, iter: I) {
+        for flag in iter {
+            self.set(flag);
+        }
+    }
+}
+
+impl BitOr for MooCpuFlags {
+    type Output = MooCpuFlags;
+    fn bitor(self, rhs: Self) -> Self::Output {
+        MooCpuFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MooCpuFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for MooCpuFlags {
+    type Output = MooCpuFlags;
+    fn bitand(self, rhs: Self) -> Self::Output {
+        MooCpuFlags(self.0 & rhs.0)
+    }
+}
+
+impl BitAndAssign for MooCpuFlags {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitXor for MooCpuFlags {
+    type Output = MooCpuFlags;
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        MooCpuFlags(self.0 ^ rhs.0)
+    }
+}
+
+impl Not for MooCpuFlags {
+    type Output = MooCpuFlags;
+    fn not(self) -> Self::Output {
+        MooCpuFlags(!self.0)
+    }
+}
+
+impl Display for MooCpuFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (flag, chr) in Self::DISPLAY_ORDER {
+            write!(f, "{}", if self.contains(flag) { chr } else { '.' })?;
+        }
+        for (flag, name) in Self::EXTENDED_DISPLAY_ORDER {
+            if self.contains(flag) {
+                write!(f, " {}", name)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// A representation of the difference between two flag registers.
-#[derive(Clone, Default, Debug)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
 pub struct MooCpuFlagsDiff {
     /// Flags that were modified and set in the final flag state.
-    pub set: Vec<MooCpuFlag>,
+    pub set: MooCpuFlags,
     /// Flags that were modified and cleared in the final flag state.
-    pub cleared: Vec<MooCpuFlag>,
+    pub cleared: MooCpuFlags,
     /// Flags that were unmodified and remain set in the final flag state.
-    pub unmodified_set: Vec<MooCpuFlag>,
+    pub unmodified_set: MooCpuFlags,
     /// Flags that were unmodified and remain cleared in the final flag state.
-    pub unmodified_cleared: Vec<MooCpuFlag>,
+    pub unmodified_cleared: MooCpuFlags,
+}
+
+impl MooCpuFlagsDiff {
+    /// Returns true if no flags were modified.
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.cleared.is_empty()
+    }
+
+    /// Return the modified flags in long form, e.g. `"set: ZF, OF; cleared: CF"`.
+    /// Reserved bits are ignored. Returns `-` if no flags were modified.
+    pub fn to_long_string(&self) -> String {
+        let set = self.set.without_reserved();
+        let cleared = self.cleared.without_reserved();
+        match (set.is_empty(), cleared.is_empty()) {
+            (true, true) => "-".to_string(),
+            (false, true) => format!("set: {}", flag_list(set.iter())),
+            (true, false) => format!("cleared: {}", flag_list(cleared.iter())),
+            (false, false) => format!("set: {}; cleared: {}", flag_list(set.iter()), flag_list(cleared.iter())),
+        }
+    }
+}
+
+/// [MooCpuFlagsDiff] is displayed in the same `odiszapc` layout as [MooCpuFlags], with each flag
+/// shown as its uppercase letter if it was set, its lowercase letter if it was cleared, or `.` if
+/// it was not modified. Modified EFLAGS-only flags are appended by name in the same case.
+impl Display for MooCpuFlagsDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (flag, chr) in MooCpuFlags::DISPLAY_ORDER {
+            if self.set.contains(flag) {
+                write!(f, "{}", chr.to_ascii_uppercase())?;
+            }
+            else if self.cleared.contains(flag) {
+                write!(f, "{}", chr)?;
+            }
+            else {
+                write!(f, ".")?;
+            }
+        }
+        for (flag, name) in MooCpuFlags::EXTENDED_DISPLAY_ORDER {
+            if self.set.contains(flag) {
+                write!(f, " {}", name.to_ascii_uppercase())?;
+            }
+            else if self.cleared.contains(flag) {
+                write!(f, " {}", name)?;
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,169 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Heuristics for detecting capture glitches in a cycle trace.
+//!
+//! Hardware captures occasionally record cycle sequences that a real bus cannot produce, for
+//! example when the capture board samples a pin mid-transition. [detect_glitches] flags the most
+//! common of these so that the affected tests can be recaptured.
+
+use std::fmt::Display;
+
+use crate::types::{MooBusState, MooCpuFamily, MooCpuType, MooCycleState, MooTState};
+
+/// The kind of a [MooGlitch].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooGlitchKind {
+    /// ALE was asserted on a cycle outside of T1.
+    AleOutsideT1(MooTState),
+    /// ALE was asserted while the bus status was passive.
+    AleWithoutBusCycle,
+    /// The address bus changed during a bus cycle without ALE being asserted. Only checked on the
+    /// 80386, whose address bus is not multiplexed with data.
+    AddressChangedWithoutAle { previous: u32, current: u32 },
+    /// A read or write strobe was active during T1, while the multiplexed bus still carries the
+    /// address. Only checked on CPUs with a multiplexed address and data bus.
+    StrobeDuringT1,
+    /// The data bus changed while a write strobe remained active.
+    WriteDataChanged { previous: u16, current: u16 },
+    /// A wait state followed a cycle on which READY was asserted.
+    WaitAfterReady,
+    /// The bus status changed from one active state to another without returning to passive.
+    /// Only checked on CPUs with a multiplexed address and data bus.
+    IllegalStatusTransition { from: MooBusState, to: MooBusState },
+}
+
+/// A [MooGlitch] is a physically impossible cycle sequence, located at the cycle that completes it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooGlitch {
+    /// The index of the offending cycle.
+    pub cycle: usize,
+    /// What was wrong with it.
+    pub kind:  MooGlitchKind,
+}
+
+impl Display for MooGlitch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle {}: ", self.cycle)?;
+        match self.kind {
+            MooGlitchKind::AleOutsideT1(t_state) => write!(f, "ALE asserted in {:?}", t_state),
+            MooGlitchKind::AleWithoutBusCycle => write!(f, "ALE asserted with passive bus status"),
+            MooGlitchKind::AddressChangedWithoutAle { previous, current } => {
+                write!(
+                    f,
+                    "address changed from {:06X} to {:06X} without ALE",
+                    previous, current
+                )
+            }
+            MooGlitchKind::StrobeDuringT1 => write!(f, "read or write strobe active in T1"),
+            MooGlitchKind::WriteDataChanged { previous, current } => {
+                write!(f, "write data changed from {:04X} to {:04X}", previous, current)
+            }
+            MooGlitchKind::WaitAfterReady => write!(f, "wait state inserted after READY"),
+            MooGlitchKind::IllegalStatusTransition { from, to } => {
+                write!(f, "bus status changed from {} to {} without passive state", from, to)
+            }
+        }
+    }
+}
+
+/// Scan a cycle trace for [MooGlitch]es, decoding bus states for the given [MooCpuType].
+pub fn detect_glitches(cpu_type: MooCpuType, cycles: impl IntoIterator<Item = MooCycleState>) -> Vec<MooGlitch> {
+    let multiplexed = matches!(
+        MooCpuFamily::from(cpu_type),
+        MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186
+    );
+    let latched_address = matches!(MooCpuFamily::from(cpu_type), MooCpuFamily::Intel80386);
+
+    let mut glitches = Vec::new();
+    let mut push = |cycle: usize, kind: MooGlitchKind| glitches.push(MooGlitch { cycle, kind });
+    let mut previous: Option<MooCycleState> = None;
+
+    for (i, cycle) in cycles.into_iter().enumerate() {
+        let t_state = cycle.t_state();
+        let bus_state = cycle.bus_state(cpu_type);
+
+        if cycle.ale() {
+            if t_state != MooTState::T1 {
+                push(i, MooGlitchKind::AleOutsideT1(t_state));
+            }
+            if bus_state == MooBusState::PASV {
+                push(i, MooGlitchKind::AleWithoutBusCycle);
+            }
+        }
+
+        if multiplexed && t_state == MooTState::T1 && (cycle.is_reading() || cycle.is_writing()) {
+            push(i, MooGlitchKind::StrobeDuringT1);
+        }
+
+        if let Some(prev) = previous {
+            if latched_address
+                && !cycle.ale()
+                && matches!(t_state, MooTState::T2 | MooTState::Tw)
+                && cycle.address_bus != prev.address_bus
+            {
+                push(
+                    i,
+                    MooGlitchKind::AddressChangedWithoutAle {
+                        previous: prev.address_bus,
+                        current:  cycle.address_bus,
+                    },
+                );
+            }
+
+            if !cycle.ale() && prev.is_writing() && cycle.is_writing() && cycle.data_bus != prev.data_bus {
+                push(
+                    i,
+                    MooGlitchKind::WriteDataChanged {
+                        previous: prev.data_bus,
+                        current:  cycle.data_bus,
+                    },
+                );
+            }
+
+            if t_state == MooTState::Tw
+                && matches!(prev.t_state(), MooTState::T3 | MooTState::Tw)
+                && prev.pins0 & MooCycleState::PIN_READY != 0
+            {
+                push(i, MooGlitchKind::WaitAfterReady);
+            }
+
+            let prev_bus_state = prev.bus_state(cpu_type);
+            if multiplexed
+                && prev_bus_state != MooBusState::PASV
+                && bus_state != MooBusState::PASV
+                && prev_bus_state != bus_state
+            {
+                push(
+                    i,
+                    MooGlitchKind::IllegalStatusTransition {
+                        from: prev_bus_state,
+                        to:   bus_state,
+                    },
+                );
+            }
+        }
+        previous = Some(cycle);
+    }
+    glitches
+}
@@ -0,0 +1,162 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Alternate test digests, stored in an optional `DGST` chunk alongside the canonical SHA-1
+//! `HASH` chunk.
+
+use std::{fmt::Display, str::FromStr};
+
+use sha1::Digest;
+
+use crate::types::{chunks::MooDigestChunk, errors::MooError};
+
+/// Format a hash or digest as a lowercase hexadecimal string, the form used to identify tests in
+/// text files and messages.
+pub fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A [MooHashAlgorithm] identifies the algorithm used to produce a test digest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MooHashAlgorithm {
+    /// SHA-1, the algorithm of the canonical `HASH` chunk.
+    Sha1,
+    /// SHA-256.
+    Sha256,
+}
+
+impl MooHashAlgorithm {
+    /// Returns the algorithm ID stored in a `DGST` chunk.
+    pub fn id(&self) -> u8 {
+        match self {
+            MooHashAlgorithm::Sha1 => 1,
+            MooHashAlgorithm::Sha256 => 2,
+        }
+    }
+
+    /// Returns the [MooHashAlgorithm] with the given `DGST` algorithm ID, if known.
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(MooHashAlgorithm::Sha1),
+            2 => Some(MooHashAlgorithm::Sha256),
+            _ => None,
+        }
+    }
+
+    /// Returns the length of a digest produced by this algorithm, in bytes.
+    pub fn digest_len(&self) -> usize {
+        match self {
+            MooHashAlgorithm::Sha1 => 20,
+            MooHashAlgorithm::Sha256 => 32,
+        }
+    }
+
+    /// Compute the digest of `data`.
+    pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            MooHashAlgorithm::Sha1 => sha1::Sha1::digest(data).to_vec(),
+            MooHashAlgorithm::Sha256 => sha2::Sha256::digest(data).to_vec(),
+        }
+    }
+}
+
+impl Display for MooHashAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooHashAlgorithm::Sha1 => write!(f, "sha1"),
+            MooHashAlgorithm::Sha256 => write!(f, "sha256"),
+        }
+    }
+}
+
+impl FromStr for MooHashAlgorithm {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('-', "").as_str() {
+            "sha1" => Ok(MooHashAlgorithm::Sha1),
+            "sha256" => Ok(MooHashAlgorithm::Sha256),
+            _ => Err(MooError::ParseError(format!("Unknown hash algorithm: {}", s))),
+        }
+    }
+}
+
+/// A [MooTestDigest] is an alternate digest of a test, computed over the same bytes as the
+/// canonical SHA-1 hash. The SHA-1 hash remains the test's identity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooTestDigest {
+    pub algorithm: MooHashAlgorithm,
+    pub digest:    Vec<u8>,
+}
+
+impl MooTestDigest {
+    /// Compute a [MooTestDigest] of `data` with the specified algorithm.
+    pub fn compute(algorithm: MooHashAlgorithm, data: &[u8]) -> Self {
+        Self {
+            algorithm,
+            digest: algorithm.digest(data),
+        }
+    }
+
+    /// Return the digest as a lowercase hexadecimal string.
+    pub fn digest_string(&self) -> String {
+        hex_string(&self.digest)
+    }
+}
+
+impl Display for MooTestDigest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.digest_string())
+    }
+}
+
+impl TryFrom<MooDigestChunk> for MooTestDigest {
+    type Error = MooError;
+
+    fn try_from(chunk: MooDigestChunk) -> Result<Self, Self::Error> {
+        let algorithm = MooHashAlgorithm::from_id(chunk.algorithm)
+            .ok_or_else(|| MooError::ParseError(format!("Unknown DGST algorithm ID: {}", chunk.algorithm)))?;
+        if chunk.digest.len() != algorithm.digest_len() {
+            return Err(MooError::ParseError(format!(
+                "DGST digest length {} does not match {} ({} bytes)",
+                chunk.digest.len(),
+                algorithm,
+                algorithm.digest_len()
+            )));
+        }
+        Ok(Self {
+            algorithm,
+            digest: chunk.digest,
+        })
+    }
+}
+
+impl From<&MooTestDigest> for MooDigestChunk {
+    fn from(digest: &MooTestDigest) -> Self {
+        Self {
+            algorithm: digest.algorithm.id(),
+            len: digest.digest.len() as u8,
+            digest: digest.digest.clone(),
+        }
+    }
+}
@@ -0,0 +1,298 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Approximate reconstruction of intermediate architectural state from a test's cycle trace.
+//!
+//! A MOO test only records the CPU state before and after an instruction. [MooInterpolatedState]
+//! walks the cycle trace from the initial state and tracks memory writes, prefetch queue activity
+//! and instruction bytes consumed, so that the state of memory part-way through an instruction
+//! can be inspected. This is useful when an emulator diverges mid-instruction.
+//!
+//! The reconstruction is approximate. Registers other than IP are not tracked, and IP can only be
+//! followed on CPUs that expose queue status lines, and only until the queue is flushed.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::Range,
+};
+
+use crate::{
+    registers::MooRegisters,
+    test::moo_test::MooTest,
+    types::{anchor::MooAnchor, MooBusState, MooCpuDataBusWidth, MooCpuType, MooCycleState, MooRamEntry},
+};
+
+/// A single byte written to memory, as observed on the bus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooMemoryWrite {
+    /// The index of the first cycle at which the write strobe was active.
+    pub cycle:   usize,
+    /// The physical address written.
+    pub address: u32,
+    /// The byte value written.
+    pub value:   u8,
+}
+
+/// The reconstructed state of a test after some number of cycles.
+#[derive(Clone, Debug)]
+pub struct MooInterpolatedState {
+    cpu_type: MooCpuType,
+    cycle: usize,
+    initial_ip: Option<u32>,
+    ip_wrap_mask: u32,
+    memory: BTreeMap<u32, u8>,
+    writes: Vec<MooMemoryWrite>,
+    queue: VecDeque<u8>,
+    bytes_consumed: usize,
+    flushed: bool,
+    // Bus state latched at the last ALE.
+    latch_address: u32,
+    latch_bus_state: MooBusState,
+    latch_bhe: bool,
+    // Index of the first entry in `writes` belonging to the current bus transaction.
+    pending_write: Option<usize>,
+    // Data read by the current code fetch, committed to the queue when the read strobe ends.
+    pending_fetch: Option<Vec<u8>>,
+}
+
+impl MooInterpolatedState {
+    /// Create a [MooInterpolatedState] from the initial state of `test`, before any cycles have
+    /// been applied.
+    pub fn new(test: &MooTest, cpu_type: MooCpuType) -> Self {
+        let initial = test.initial_state();
+        let (initial_ip, ip_wrap_mask) = match initial.regs() {
+            MooRegisters::Sixteen(regs) => (regs.ip().map(|ip| ip as u32), 0xFFFF),
+            MooRegisters::ThirtyTwo(regs) => (regs.eip(), 0xFFFF_FFFF),
+        };
+
+        Self {
+            cpu_type,
+            cycle: 0,
+            initial_ip,
+            ip_wrap_mask,
+            memory: initial.ram().iter().map(|e| (e.address, e.value)).collect(),
+            writes: Vec::new(),
+            queue: initial.queue().iter().copied().collect(),
+            bytes_consumed: 0,
+            flushed: false,
+            latch_address: 0,
+            latch_bus_state: MooBusState::PASV,
+            latch_bhe: false,
+            pending_write: None,
+            pending_fetch: None,
+        }
+    }
+
+    /// Apply a single [MooCycleState] to the reconstructed state.
+    pub fn step(&mut self, cycle: &MooCycleState) {
+        if cycle.ale() {
+            self.latch_address = cycle.address_bus;
+            self.latch_bus_state = cycle.bus_state(self.cpu_type);
+            self.latch_bhe = cycle.bhe_active(self.cpu_type);
+            self.pending_write = None;
+            self.commit_fetch();
+        }
+
+        if cycle.is_writing_mem() && self.latch_bus_state == MooBusState::MEMW {
+            let bytes = self.bus_bytes(cycle.data_bus);
+            match self.pending_write {
+                // Data may not be valid on the first cycle of the strobe, so keep the latest value.
+                Some(index) => {
+                    for (i, (address, value)) in bytes.into_iter().enumerate() {
+                        self.memory.insert(address, value);
+                        self.writes[index + i].value = value;
+                    }
+                }
+                None => {
+                    self.pending_write = Some(self.writes.len());
+                    for (address, value) in bytes {
+                        self.memory.insert(address, value);
+                        self.writes.push(MooMemoryWrite {
+                            cycle: self.cycle,
+                            address,
+                            value,
+                        });
+                    }
+                }
+            }
+        }
+
+        if cycle.is_reading_mem() && self.latch_bus_state == MooBusState::CODE {
+            self.pending_fetch = Some(self.bus_bytes(cycle.data_bus).into_iter().map(|(_, v)| v).collect());
+        }
+        else {
+            self.commit_fetch();
+        }
+
+        match cycle.queue_op {
+            // First or subsequent byte read from the queue.
+            1 | 3 => {
+                self.queue.pop_front();
+                self.bytes_consumed += 1;
+            }
+            // Queue flushed.
+            2 => {
+                self.queue.clear();
+                self.flushed = true;
+            }
+            _ => {}
+        }
+
+        self.cycle += 1;
+    }
+
+    /// Return the number of cycles applied so far.
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    /// Return the reconstructed memory contents, keyed by physical address. This includes the
+    /// test's initial RAM plus any bytes written so far.
+    pub fn memory(&self) -> &BTreeMap<u32, u8> {
+        &self.memory
+    }
+
+    /// Return the reconstructed byte at `address`, if known.
+    pub fn read_u8(&self, address: u32) -> Option<u8> {
+        self.memory.get(&address).copied()
+    }
+
+    /// Return the memory writes observed so far, in bus order.
+    pub fn writes(&self) -> &[MooMemoryWrite] {
+        &self.writes
+    }
+
+    /// Return the approximate contents of the prefetch queue.
+    pub fn queue(&self) -> &VecDeque<u8> {
+        &self.queue
+    }
+
+    /// Return the number of instruction bytes read from the queue so far, as reported by the
+    /// queue status lines.
+    pub fn bytes_consumed(&self) -> usize {
+        self.bytes_consumed
+    }
+
+    /// Return the approximate instruction pointer, derived from the initial IP and the number of
+    /// bytes read from the queue. Returns `None` once the queue has been flushed, since the
+    /// destination of a jump can't be determined from the bus alone.
+    pub fn ip(&self) -> Option<u32> {
+        if self.flushed {
+            return None;
+        }
+        self.initial_ip
+            .map(|ip| ip.wrapping_add(self.bytes_consumed as u32) & self.ip_wrap_mask)
+    }
+
+    /// Returns true if a queue flush has been observed.
+    pub fn is_flushed(&self) -> bool {
+        self.flushed
+    }
+
+    fn commit_fetch(&mut self) {
+        if let Some(bytes) = self.pending_fetch.take() {
+            self.queue.extend(bytes);
+        }
+    }
+
+    /// Return the (address, value) pairs driven on the data bus for the current transaction.
+    fn bus_bytes(&self, data_bus: u16) -> Vec<(u32, u8)> {
+        let address = self.latch_address;
+        let [lo, hi] = data_bus.to_le_bytes();
+        match MooCpuDataBusWidth::from(self.cpu_type) {
+            MooCpuDataBusWidth::Eight => vec![(address, lo)],
+            MooCpuDataBusWidth::Sixteen => match (address & 1 != 0, self.latch_bhe) {
+                (false, true) => vec![(address, lo), (address.wrapping_add(1), hi)],
+                (false, false) => vec![(address, lo)],
+                (true, _) => vec![(address, hi)],
+            },
+        }
+    }
+}
+
+impl MooTest {
+    /// Reconstruct the approximate state of this test after the first `cycle` cycles have
+    /// been applied. A `cycle` beyond the end of the trace applies every cycle.
+    pub fn state_at_cycle(&self, cpu_type: MooCpuType, cycle: usize) -> MooInterpolatedState {
+        let mut state = MooInterpolatedState::new(self, cpu_type);
+        for c in self.cycles_iter().take(cycle) {
+            state.step(&c);
+        }
+        state
+    }
+
+    /// Derive a synthetic test covering only the cycles in `range`, for focused debugging and
+    /// display. The range is clamped to the trace.
+    ///
+    /// The initial state is advanced to the start of the range using [MooTest::state_at_cycle]:
+    /// RAM includes the bytes written so far, the queue holds the reconstructed queue contents,
+    /// and the instruction pointer is advanced unless the queue was flushed. Other registers
+    /// can't be recovered from the bus and keep their initial values; the final state is kept
+    /// as is. Anchors are shifted into the slice, and anchors outside it are dropped.
+    ///
+    /// The returned test has no hash and is marked [synthetic](MooTest::is_synthetic), so it
+    /// can't be mistaken for a captured test or written to a file.
+    pub fn slice_cycles(&self, cpu_type: MooCpuType, range: Range<usize>) -> MooTest {
+        let len = self.cycles().len();
+        let end = range.end.min(len);
+        let start = range.start.min(end);
+        let state = self.state_at_cycle(cpu_type, start);
+
+        let mut initial_state = self.initial_state().clone();
+        initial_state.ram = state
+            .memory()
+            .iter()
+            .map(|(&address, &value)| MooRamEntry { address, value })
+            .collect();
+        initial_state.queue = state.queue().iter().copied().collect();
+        if let Some(ip) = state.ip() {
+            match &mut initial_state.regs {
+                MooRegisters::Sixteen(regs) => regs.set_ip(ip as u16),
+                MooRegisters::ThirtyTwo(regs) => regs.set_eip(ip),
+            }
+        }
+
+        let mut slice = MooTest::new(
+            format!("{} [cycles {}..{}]", self.name(), start, end),
+            self.gen_metadata().cloned(),
+            self.bytes(),
+            initial_state,
+            self.final_state().clone(),
+            &self.cycles()[start..end],
+            self.exception().cloned(),
+            None,
+        );
+        slice.tags = self.tags.clone();
+        slice.anchors = self
+            .anchors()
+            .iter()
+            .filter(|a| (start..end).contains(&a.cycle))
+            .map(|a| MooAnchor {
+                kind:  a.kind,
+                cycle: a.cycle - start,
+            })
+            .collect();
+        slice.synthetic = true;
+        slice
+    }
+}
@@ -0,0 +1,210 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Expected values for the port reads performed by a test.
+//!
+//! The data returned by an `IN` instruction was supplied by the test bench, not the CPU, so an
+//! emulator running a test has to know what to feed each port read. Rather than scraping the
+//! cycle trace for I/O read cycles, it can look the values up in the test's `IOST` chunk: one
+//! [MooIoStimulus] per port and access width, listing the values in the order they were read.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    test::moo_test::MooTest,
+    types::{cycle_iter::MooCycleIterExt, errors::MooError, MooBusState, MooCpuType, MooDataWidth},
+};
+use binrw::binrw;
+
+/// The width of a port access.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MooPortWidth {
+    Byte,
+    Word,
+    Dword,
+}
+
+impl MooPortWidth {
+    /// The access width in bytes, as encoded in an `IOST` chunk.
+    pub fn size(self) -> u8 {
+        match self {
+            MooPortWidth::Byte => 1,
+            MooPortWidth::Word => 2,
+            MooPortWidth::Dword => 4,
+        }
+    }
+
+    /// Decode a width from its size in bytes, or `None` if the size is not 1, 2 or 4.
+    pub fn from_size(size: u8) -> Option<Self> {
+        match size {
+            1 => Some(MooPortWidth::Byte),
+            2 => Some(MooPortWidth::Word),
+            4 => Some(MooPortWidth::Dword),
+            _ => None,
+        }
+    }
+
+    /// Returns the largest value a port access of this width can return.
+    pub fn mask(self) -> u32 {
+        match self {
+            MooPortWidth::Byte => 0xFF,
+            MooPortWidth::Word => 0xFFFF,
+            MooPortWidth::Dword => 0xFFFF_FFFF,
+        }
+    }
+}
+
+impl FromStr for MooPortWidth {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<u8>()
+            .ok()
+            .and_then(MooPortWidth::from_size)
+            .ok_or_else(|| MooError::ParseError(format!("Invalid port width: {}", s)))
+    }
+}
+
+impl Display for MooPortWidth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.size())
+    }
+}
+
+/// The sequence of values returned by reads of one port at one width.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooIoStimulus {
+    pub port:   u16,
+    pub width:  MooPortWidth,
+    /// The values returned to each read, in order.
+    pub values: Vec<u32>,
+}
+
+impl MooIoStimulus {
+    pub fn new(port: u16, width: MooPortWidth, values: Vec<u32>) -> Self {
+        Self { port, width, values }
+    }
+}
+
+impl Display for MooIoStimulus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.width.size() as usize * 2;
+        write!(f, "port {:04X} ({}-byte):", self.port, self.width.size())?;
+        for value in &self.values {
+            write!(f, " {:0digits$X}", value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+#[binrw]
+#[brw(little)]
+pub(crate) struct MooIoStimulusEntry {
+    pub port: u16,
+    pub width: u8,
+    pub value_ct: u32,
+    #[br(count = value_ct)]
+    pub values: Vec<u32>,
+}
+
+/// A [MooIoStimulusChunk] holds the expected port read values of a test.
+#[derive(Debug)]
+#[binrw]
+#[brw(little)]
+pub(crate) struct MooIoStimulusChunk {
+    pub entry_ct: u32,
+    #[br(count = entry_ct)]
+    pub entries:  Vec<MooIoStimulusEntry>,
+}
+
+impl MooIoStimulusChunk {
+    /// Decode the entries in the chunk, skipping any with an invalid width.
+    pub fn to_stimulus(&self) -> Vec<MooIoStimulus> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match MooPortWidth::from_size(entry.width) {
+                Some(width) => Some(MooIoStimulus::new(entry.port, width, entry.values.clone())),
+                None => {
+                    log::warn!(
+                        "Skipping IO stimulus for port {:04X} with width {}",
+                        entry.port,
+                        entry.width
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl From<&[MooIoStimulus]> for MooIoStimulusChunk {
+    fn from(stimulus: &[MooIoStimulus]) -> Self {
+        Self {
+            entry_ct: stimulus.len() as u32,
+            entries:  stimulus
+                .iter()
+                .map(|s| MooIoStimulusEntry {
+                    port: s.port,
+                    width: s.width.size(),
+                    value_ct: s.values.len() as u32,
+                    values: s.values.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl MooTest {
+    /// Build the IO stimulus for this test from the I/O read cycles in its cycle trace, decoding
+    /// bus states for the given [MooCpuType]. This is how a generator fills in the `IOST` chunk;
+    /// consumers should use [MooTest::io_stimulus] instead.
+    ///
+    /// Each bus transaction becomes one read, so a word read split into two byte transactions by
+    /// an 8-bit bus is recorded as two byte reads of consecutive ports.
+    pub fn derive_io_stimulus(&self, cpu_type: MooCpuType) -> Vec<MooIoStimulus> {
+        let mut stimulus: Vec<MooIoStimulus> = Vec::new();
+        for transaction in self.cycles_iter().transactions() {
+            if transaction.bus_state(cpu_type) != MooBusState::IOR {
+                continue;
+            }
+            let Some(data) = transaction.data_bus
+            else {
+                continue;
+            };
+            let port = transaction.address as u16;
+            let (width, value) = match transaction.data_width(cpu_type) {
+                MooDataWidth::Sixteen => (MooPortWidth::Word, data as u32),
+                MooDataWidth::EightLow => (MooPortWidth::Byte, data as u32 & 0xFF),
+                MooDataWidth::EightHigh => (MooPortWidth::Byte, (data >> 8) as u32),
+                MooDataWidth::Invalid => continue,
+            };
+            match stimulus.iter_mut().find(|s| s.port == port && s.width == width) {
+                Some(entry) => entry.values.push(value),
+                None => stimulus.push(MooIoStimulus::new(port, width, vec![value])),
+            }
+        }
+        stimulus.sort_by_key(|s| (s.port, s.width));
+        stimulus
+    }
+}
@@ -0,0 +1,175 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A view of the real-mode interrupt vector table held in a test's RAM.
+//!
+//! Tests that raise an exception or execute `INT` read their vector from the IVT at linear address
+//! 0, so the initial RAM of such a test holds the entries it used. [MooIvt] decodes the complete
+//! entries found in a [MooTestState](crate::test::test_state::MooTestState), and
+//! [MooTest::exception_vector_mismatch] checks that the test ended up at the handler its vector
+//! points to.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    registers::{MooRegisters, MooSegmentRegister},
+    test::moo_test::MooTest,
+    types::{MooCpuMode, MooCpuType, MooRamEntry},
+};
+
+/// The size of an IVT entry in bytes: a 16-bit offset followed by a 16-bit segment.
+pub const IVT_ENTRY_SIZE: u32 = 4;
+
+const HLT_OPCODE: u8 = 0xF4;
+
+/// A single interrupt vector, pointing to the handler at `segment:offset`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooIvtEntry {
+    pub vector:  u8,
+    pub offset:  u16,
+    pub segment: u16,
+}
+
+impl MooIvtEntry {
+    /// The linear address of the entry within the IVT.
+    pub fn address(&self) -> u32 {
+        self.vector as u32 * IVT_ENTRY_SIZE
+    }
+
+    /// The real-mode linear address of the handler.
+    pub fn target_linear(&self) -> u32 {
+        ((self.segment as u32) << 4) + self.offset as u32
+    }
+}
+
+impl Display for MooIvtEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02X}: {:04X}:{:04X}", self.vector, self.segment, self.offset)
+    }
+}
+
+/// The IVT entries present in a set of RAM entries, in vector order. Entries with any of their
+/// four bytes missing are left out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooIvt {
+    entries: Vec<MooIvtEntry>,
+}
+
+impl MooIvt {
+    /// Decode the IVT entries present in `ram`.
+    pub fn from_ram(ram: &[MooRamEntry]) -> Self {
+        let table_end = 256 * IVT_ENTRY_SIZE;
+        let bytes: BTreeMap<u32, u8> = ram
+            .iter()
+            .filter(|e| e.address < table_end)
+            .map(|e| (e.address, e.value))
+            .collect();
+
+        let mut vectors: Vec<u32> = bytes.keys().map(|address| address / IVT_ENTRY_SIZE).collect();
+        vectors.dedup();
+        let entries = vectors
+            .into_iter()
+            .filter_map(|vector| {
+                let base = vector * IVT_ENTRY_SIZE;
+                let byte = |i: u32| bytes.get(&(base + i)).copied();
+                let word = |i: u32| Some(u16::from_le_bytes([byte(i)?, byte(i + 1)?]));
+                Some(MooIvtEntry {
+                    vector:  vector as u8,
+                    offset:  word(0)?,
+                    segment: word(2)?,
+                })
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Return the entry for `vector`, if present.
+    pub fn get(&self, vector: u8) -> Option<&MooIvtEntry> {
+        self.entries
+            .binary_search_by_key(&vector, |e| e.vector)
+            .ok()
+            .map(|pos| &self.entries[pos])
+    }
+
+    pub fn entries(&self) -> &[MooIvtEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A real-mode exception whose handler address in the IVT does not match the final `CS:IP`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooVectorMismatch {
+    /// The IVT entry of the exception taken.
+    pub expected: MooIvtEntry,
+    /// The final code segment.
+    pub cs: u16,
+    /// The final instruction pointer.
+    pub ip: u32,
+}
+
+impl Display for MooVectorMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "vector {:02X} points to {:04X}:{:04X}, but the test ends at {:04X}:{:04X}",
+            self.expected.vector, self.expected.segment, self.expected.offset, self.cs, self.ip
+        )
+    }
+}
+
+impl MooTest {
+    /// Check that a real-mode test raising an exception ends at the handler given by its vector
+    /// in the initial IVT. Returns `None` if the handler matches, or if there is nothing to check:
+    /// the test raised no exception, runs in protected mode, or its vector is not in the initial
+    /// RAM. Generated tests place a `HLT` at the handler, so a test ending just past a `HLT` at the
+    /// vector address is also a match.
+    pub fn exception_vector_mismatch(&self, cpu_type: MooCpuType) -> Option<MooVectorMismatch> {
+        let exception = self.exception()?;
+        if !matches!(self.cpu_mode(cpu_type), MooCpuMode::RealMode) {
+            return None;
+        }
+        let expected = *self.initial_state().ivt().get(exception.exception_num)?;
+
+        let final_state = self.full_final_state();
+        let cs = final_state.regs().segment(MooSegmentRegister::CS)?;
+        let ip = match final_state.regs() {
+            MooRegisters::Sixteen(regs) => regs.ip()? as u32,
+            MooRegisters::ThirtyTwo(regs) => regs.eip()?,
+        };
+        let halted = self
+            .initial_state()
+            .ram
+            .iter()
+            .any(|e| e.address == expected.target_linear() && e.value == HLT_OPCODE);
+        let at_handler = ip == expected.offset as u32 || (halted && ip == expected.offset as u32 + 1);
+        (cs != expected.segment || !at_handler).then_some(MooVectorMismatch { expected, cs, ip })
+    }
+}
@@ -0,0 +1,87 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! License and attribution information for published test sets, stored in an optional top-level
+//! `LICN` chunk.
+
+use std::fmt::Display;
+
+use crate::types::chunks::{MooLicenseChunk, MooNameChunk};
+
+/// A [MooLicense] holds the license identifier and contributor credits of a test file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooLicense {
+    /// The license identifier, preferably an SPDX expression such as `MIT` or `CC0-1.0`.
+    pub identifier:   String,
+    /// The contributors credited for the tests in this file, in order.
+    pub contributors: Vec<String>,
+}
+
+impl MooLicense {
+    /// Create a new [MooLicense] with the given license identifier and no contributors.
+    pub fn new(identifier: &str) -> Self {
+        Self {
+            identifier:   identifier.to_string(),
+            contributors: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to credit a contributor.
+    pub fn with_contributor(mut self, contributor: &str) -> Self {
+        self.contributors.push(contributor.to_string());
+        self
+    }
+}
+
+impl Display for MooLicense {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.identifier)?;
+        if !self.contributors.is_empty() {
+            write!(f, " ({})", self.contributors.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<MooLicenseChunk> for MooLicense {
+    fn from(chunk: MooLicenseChunk) -> Self {
+        Self {
+            identifier:   chunk.identifier.name,
+            contributors: chunk.contributors.into_iter().map(|c| c.name).collect(),
+        }
+    }
+}
+
+impl From<&MooLicense> for MooLicenseChunk {
+    fn from(license: &MooLicense) -> Self {
+        let name_chunk = |s: &String| MooNameChunk {
+            len:  s.len() as u32,
+            name: s.clone(),
+        };
+        Self {
+            identifier: name_chunk(&license.identifier),
+            contributor_ct: license.contributors.len() as u32,
+            contributors: license.contributors.iter().map(name_chunk).collect(),
+        }
+    }
+}
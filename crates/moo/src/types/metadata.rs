@@ -21,10 +21,180 @@
     DEALINGS IN THE SOFTWARE.
 */
 
-use crate::types::{MooCpuMode, MooCpuType};
-use binrw::binrw;
+use std::{fmt::Display, io::Cursor, str::FromStr};
+
+use crate::types::{errors::MooError, opcode::MooOpcodeId, MooCpuMode, MooCpuType};
+use binrw::{binrw, BinRead, BinResult, BinWrite};
+
+/// The [MooA20Policy] enum describes how the A20 address line was handled by the system that
+/// captured a test file. This determines whether real-mode accesses past 1MB (such as at
+/// `FFFF:FFFF`) wrap around to low memory or appear on the bus above 1MB.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum MooA20Policy {
+    /// The A20 policy was not recorded. No A20 validation is possible.
+    #[default]
+    Unspecified,
+    /// A20 was gated off, or the CPU has only 20 address lines. Accesses wrap at 1MB.
+    Masked,
+    /// A20 was enabled. Accesses past 1MB reach the high memory area.
+    Enabled,
+}
+
+impl MooA20Policy {
+    /// Convert the policy to its encoding in the `META` chunk.
+    pub fn to_u8(self) -> u8 {
+        match self {
+            MooA20Policy::Unspecified => 0,
+            MooA20Policy::Masked => 1,
+            MooA20Policy::Enabled => 2,
+        }
+    }
+
+    /// Decode a policy from its encoding in the `META` chunk, or `None` if the value is unknown.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MooA20Policy::Unspecified),
+            1 => Some(MooA20Policy::Masked),
+            2 => Some(MooA20Policy::Enabled),
+            _ => None,
+        }
+    }
+}
+
+/// The [MooCaptureSource] enum describes how the tests in a file were produced. Consumers may
+/// wish to weigh traces captured from real hardware differently from those produced by an
+/// emulator or synthesized by hand.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub enum MooCaptureSource {
+    /// The capture source was not recorded.
+    #[default]
+    Unspecified,
+    /// The tests were captured from a physical CPU.
+    Hardware,
+    /// The tests were generated by running an emulator.
+    Emulator,
+    /// The tests were constructed or edited by hand or by a tool, and may not reflect any real
+    /// execution.
+    Synthesized,
+}
+
+impl MooCaptureSource {
+    /// Convert the capture source to its encoding in the `META` chunk.
+    pub fn to_u8(self) -> u8 {
+        match self {
+            MooCaptureSource::Unspecified => 0,
+            MooCaptureSource::Hardware => 1,
+            MooCaptureSource::Emulator => 2,
+            MooCaptureSource::Synthesized => 3,
+        }
+    }
+
+    /// Decode a capture source from its encoding in the `META` chunk, or `None` if the value is
+    /// unknown.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MooCaptureSource::Unspecified),
+            1 => Some(MooCaptureSource::Hardware),
+            2 => Some(MooCaptureSource::Emulator),
+            3 => Some(MooCaptureSource::Synthesized),
+            _ => None,
+        }
+    }
+}
+
+impl Display for MooCaptureSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            MooCaptureSource::Unspecified => "unspecified",
+            MooCaptureSource::Hardware => "hardware",
+            MooCaptureSource::Emulator => "emulator",
+            MooCaptureSource::Synthesized => "synthesized",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for MooCaptureSource {
+    type Err = MooError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "unspecified" => Ok(MooCaptureSource::Unspecified),
+            "hardware" | "hw" => Ok(MooCaptureSource::Hardware),
+            "emulator" | "emu" => Ok(MooCaptureSource::Emulator),
+            "synthesized" | "synth" => Ok(MooCaptureSource::Synthesized),
+            _ => Err(MooError::ParseError(format!("unknown capture source '{}'", s))),
+        }
+    }
+}
+
+/// A [MooCaptureInfo] records the [MooCaptureSource] of a test file along with the name of the
+/// tool that produced it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[binrw]
+#[brw(little)]
+pub struct MooCaptureInfo {
+    /// The [MooCaptureSource], kept raw so that files from newer writers still parse; use
+    /// [MooCaptureInfo::source] to decode it.
+    pub source: u8,
+    /// The ASCII name of the capture tool or emulator, padded with spaces.
+    pub tool:   [u8; 16],
+}
+
+impl MooCaptureInfo {
+    /// The serialized size of a [MooCaptureInfo] in bytes.
+    pub const SIZE: usize = 17;
+
+    /// Create a new [MooCaptureInfo] with the given source and tool name. Tool names longer than
+    /// sixteen bytes are truncated.
+    pub fn new(source: MooCaptureSource, tool: &str) -> Self {
+        let mut info = Self {
+            source: source.to_u8(),
+            tool:   [b' '; 16],
+        };
+        let len = std::cmp::min(tool.len(), 16);
+        info.tool[..len].copy_from_slice(&tool.as_bytes()[..len]);
+        info
+    }
+
+    /// Get the [MooCaptureSource], or `None` if the stored value is unknown to this version of
+    /// the library.
+    pub fn source(&self) -> Option<MooCaptureSource> {
+        MooCaptureSource::from_u8(self.source)
+    }
+
+    /// Set the [MooCaptureSource].
+    pub fn set_source(&mut self, source: MooCaptureSource) {
+        self.source = source.to_u8();
+    }
+
+    /// Get the tool name, if set.
+    pub fn tool(&self) -> Option<String> {
+        let tool = String::from_utf8_lossy(&self.tool).trim().to_string();
+        (!tool.is_empty()).then_some(tool)
+    }
+}
+
+impl Display for MooCaptureInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.source() {
+            Some(source) => write!(f, "{}", source)?,
+            None => write!(f, "unknown ({})", self.source)?,
+        }
+        match self.tool() {
+            Some(tool) => write!(f, " ({})", tool),
+            None => Ok(()),
+        }
+    }
+}
 
 /// A [MooFileMetadata] struct represents the metadata header for a `MOO` test file.
+///
+/// The binrw implementations cover the fixed fields only. The optional trailing fields are read
+/// and written with the rest of the `META` chunk by [MooFileMetadata::from_payload] and
+/// [MooFileMetadata::to_payload].
 #[derive(Clone, Debug, Default)]
 #[binrw]
 #[brw(little)]
@@ -41,7 +211,8 @@ pub struct MooFileMetadata {
     /// 16 bits.
     pub opcode: u32,
     /// The ASCII-encoded mnemonic string of the instruction being tested in this file, padded
-    /// with spaces.
+    /// with spaces. Mnemonics longer than eight bytes are truncated here and stored in full in a
+    /// trailing field, so prefer [MooFileMetadata::mnemonic] and [MooFileMetadata::set_mnemonic].
     pub mnemonic: [u8; 8],
     /// The number of tests contained in this file.
     pub test_ct: u32,
@@ -51,10 +222,217 @@ pub struct MooFileMetadata {
     pub cpu_mode: MooCpuMode,
     /// The group extension of the instruction being tested in this file, if applicable.
     pub extension: u8,
+    /// Reserved bytes. The first holds the A20 gating policy, kept raw so that files from newer
+    /// writers still parse; use [MooFileMetadata::a20_policy] to decode it.
     pub reserved: [u8; 2],
+    /// The clock and board configuration the tests were captured on, if recorded. This is an
+    /// optional trailing field, absent in files written before it was introduced.
+    /// An all-zero timing block reads as `None`; one is written in its place when a later
+    /// trailing field is present so that the trailing fields stay aligned.
+    #[brw(ignore)]
+    pub timing: Option<MooTimingContext>,
+    /// How the tests in this file were produced, if recorded. Like `timing`, this is an optional
+    /// trailing field. A default capture block is written in its place when a later trailing
+    /// field is present.
+    #[brw(ignore)]
+    pub capture: Option<MooCaptureInfo>,
+    /// The full mnemonic, present only if it does not fit in the fixed `mnemonic` field.
+    /// This optional trailing field was introduced in MOO 1.2. An empty string is written in its
+    /// place when `opcode_bytes` is present.
+    #[brw(ignore)]
+    pub(crate) mnemonic_ext: Option<MooByteString>,
+    /// The prefix and opcode bytes of the instruction tested in this file, in encoded order.
+    /// Unlike `opcode`, this is unambiguous for prefixed and multibyte opcodes. This optional
+    /// trailing field was introduced in MOO 1.2.
+    #[brw(ignore)]
+    pub(crate) opcode_bytes: Option<MooByteString>,
+}
+
+/// Reads the optional trailing fields of a `META` chunk payload, in order.
+struct MooMetadataTail<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> MooMetadataTail<'a> {
+    /// Take the `len` bytes of the field `name`, or `None` if the payload ended before it.
+    fn take(&mut self, name: &str, len: usize) -> Result<Option<&'a [u8]>, MooError> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        self.split(name, len).map(Some)
+    }
+
+    /// Take the length-prefixed byte string `name`, or `None` if the payload ended before it.
+    fn take_string(&mut self, name: &str) -> Result<Option<&'a [u8]>, MooError> {
+        match self.take(name, 1)? {
+            Some(len) => self.split(name, len[0] as usize).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn split(&mut self, name: &str, len: usize) -> Result<&'a [u8], MooError> {
+        if self.bytes.len() < len {
+            return Err(MooError::ParseError(format!(
+                "META chunk ends inside its {} field ({} of {} bytes)",
+                name,
+                self.bytes.len(),
+                len
+            )));
+        }
+        let (field, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(field)
+    }
+}
+
+/// A variable-length byte string of up to 255 bytes, stored as a length byte followed by the
+/// bytes themselves.
+#[binrw]
+#[brw(little)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct MooByteString {
+    #[bw(calc = bytes.len() as u8)]
+    len: u8,
+    #[br(count = len)]
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// A [MooTimingContext] describes the clock rate and board configuration of the system that
+/// captured a test file. Wait states and DRAM refresh cycles depend on these, so consumers need
+/// them to interpret bus timing patterns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[binrw]
+#[brw(little)]
+pub struct MooTimingContext {
+    /// The CPU clock frequency in Hz, or 0 if unknown.
+    pub clock_hz: u32,
+    /// The DRAM refresh period in CPU clock cycles, or 0 if refresh was disabled or unknown.
+    pub refresh_period: u32,
+    /// The ASCII identifier of the capture board, padded with spaces.
+    pub board_id: [u8; 8],
+}
+
+impl MooTimingContext {
+    /// The serialized size of a [MooTimingContext] in bytes.
+    pub const SIZE: usize = 16;
+
+    /// Create a new [MooTimingContext] for the given clock frequency in Hz.
+    pub fn new(clock_hz: u32) -> Self {
+        Self {
+            clock_hz,
+            refresh_period: 0,
+            board_id: [b' '; 8],
+        }
+    }
+
+    /// Builder-style method to set the DRAM refresh period, in CPU clock cycles.
+    pub fn with_refresh_period(mut self, refresh_period: u32) -> Self {
+        self.refresh_period = refresh_period;
+        self
+    }
+
+    /// Builder-style method to set the board identifier. Identifiers longer than eight bytes are
+    /// truncated.
+    pub fn with_board_id(mut self, board_id: &str) -> Self {
+        self.board_id = [b' '; 8];
+        let len = std::cmp::min(board_id.len(), 8);
+        self.board_id[..len].copy_from_slice(&board_id.as_bytes()[..len]);
+        self
+    }
+
+    /// Get the clock frequency in Hz, if known.
+    pub fn clock_hz(&self) -> Option<u32> {
+        (self.clock_hz != 0).then_some(self.clock_hz)
+    }
+
+    /// Get the DRAM refresh period in CPU clock cycles, if known.
+    pub fn refresh_period(&self) -> Option<u32> {
+        (self.refresh_period != 0).then_some(self.refresh_period)
+    }
+
+    /// Get the board identifier, if set.
+    pub fn board_id(&self) -> Option<String> {
+        let id = String::from_utf8_lossy(&self.board_id).trim().to_string();
+        (!id.is_empty()).then_some(id)
+    }
+}
+
+impl Display for MooTimingContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.clock_hz() {
+            Some(hz) => write!(f, "{:.3} MHz", hz as f64 / 1_000_000.0)?,
+            None => write!(f, "unknown clock")?,
+        }
+        if let Some(period) = self.refresh_period() {
+            write!(f, ", refresh every {} cycles", period)?;
+        }
+        if let Some(board_id) = self.board_id() {
+            write!(f, ", board {}", board_id)?;
+        }
+        Ok(())
+    }
 }
 
 impl MooFileMetadata {
+    /// Parse a `META` chunk payload. Each optional trailing field is read only if the payload
+    /// extends into it, and must then be complete. Bytes after the last known field are ignored,
+    /// so that chunks with fields added by later revisions still parse.
+    ///
+    /// # Returns
+    /// A [MooError::ParseError] if the payload is shorter than the fixed fields or ends inside a
+    /// trailing field.
+    pub fn from_payload(payload: &[u8]) -> Result<Self, MooError> {
+        let malformed = |e: binrw::Error| MooError::ParseError(format!("malformed META chunk: {}", e));
+        let mut cursor = Cursor::new(payload);
+        let mut metadata = Self::read(&mut cursor).map_err(malformed)?;
+        let mut tail = MooMetadataTail {
+            bytes: &payload[cursor.position() as usize..],
+        };
+
+        if let Some(bytes) = tail.take("timing", MooTimingContext::SIZE)? {
+            let timing = MooTimingContext::read(&mut Cursor::new(bytes)).map_err(malformed)?;
+            metadata.timing = Some(timing).filter(|t| *t != MooTimingContext::default());
+        }
+        if let Some(bytes) = tail.take("capture", MooCaptureInfo::SIZE)? {
+            let capture = MooCaptureInfo::read(&mut Cursor::new(bytes)).map_err(malformed)?;
+            metadata.capture = Some(capture).filter(|c| *c != MooCaptureInfo::default());
+        }
+        if let Some(bytes) = tail.take_string("mnemonic_ext")? {
+            metadata.mnemonic_ext = (!bytes.is_empty()).then(|| MooByteString { bytes: bytes.to_vec() });
+        }
+        if let Some(bytes) = tail.take_string("opcode_bytes")? {
+            metadata.opcode_bytes = (!bytes.is_empty()).then(|| MooByteString { bytes: bytes.to_vec() });
+        }
+        if !tail.bytes.is_empty() {
+            log::debug!("Ignoring {} unknown trailing bytes in META chunk", tail.bytes.len());
+        }
+        Ok(metadata)
+    }
+
+    /// Serialize the [MooFileMetadata] as a `META` chunk payload. Trailing fields are written up
+    /// to the last one present, with defaults in place of any absent fields before it.
+    pub fn to_payload(&self) -> BinResult<Vec<u8>> {
+        let has_mnemonic_ext = self.mnemonic_ext.is_some() || self.opcode_bytes.is_some();
+        let has_capture = self.capture.is_some() || has_mnemonic_ext;
+        let has_timing = self.timing.is_some() || has_capture;
+
+        let mut cursor = Cursor::new(Vec::new());
+        self.write(&mut cursor)?;
+        if has_timing {
+            self.timing.clone().unwrap_or_default().write(&mut cursor)?;
+        }
+        if has_capture {
+            self.capture.clone().unwrap_or_default().write(&mut cursor)?;
+        }
+        if has_mnemonic_ext {
+            self.mnemonic_ext.clone().unwrap_or_default().write(&mut cursor)?;
+        }
+        if let Some(opcode_bytes) = &self.opcode_bytes {
+            opcode_bytes.write(&mut cursor)?;
+        }
+        Ok(cursor.into_inner())
+    }
+
     /// Create a new [MooFileMetadata] with the specified parameters.
     /// # Arguments
     /// * `set_version_major` - The major version of the MOO test file collection this file belongs to.
@@ -104,22 +482,64 @@ impl MooFileMetadata {
         self.cpu_mode = cpu_mode;
         self
     }
+    /// Builder-style method to set the A20 policy of the [MooFileMetadata].
+    /// # Arguments
+    /// * `a20_policy` - The A20 gating policy in effect when the tests were captured.
+    pub fn with_a20_policy(mut self, a20_policy: MooA20Policy) -> Self {
+        self.set_a20_policy(a20_policy);
+        self
+    }
+    /// Builder-style method to set the [MooTimingContext] of the [MooFileMetadata].
+    /// # Arguments
+    /// * `timing` - The clock and board configuration the tests were captured on.
+    pub fn with_timing(mut self, timing: MooTimingContext) -> Self {
+        self.timing = Some(timing);
+        self
+    }
+    /// Builder-style method to set the [MooCaptureInfo] of the [MooFileMetadata].
+    /// # Arguments
+    /// * `capture` - The source and tool that produced the tests in this file.
+    pub fn with_capture(mut self, capture: MooCaptureInfo) -> Self {
+        self.capture = Some(capture);
+        self
+    }
     /// Builder-style method to set the mnemonic string of the [MooFileMetadata].
     /// # Arguments
     /// * `mnemonic` - The ASCII-encoded mnemonic string of the instruction being tested in this file.
     pub fn with_mnemonic(mut self, mnemonic: String) -> Self {
-        for c in self.mnemonic.iter_mut() {
-            *c = ' ' as u8;
-        }
-        let mnemonic = mnemonic.into_bytes();
-        let mnemonic_len = std::cmp::min(mnemonic.len(), 8);
-        self.mnemonic[0..mnemonic_len].copy_from_slice(&mnemonic.as_slice()[0..mnemonic_len]);
+        self.set_mnemonic(&mnemonic);
         self
     }
 
-    /// Get the mnemonic string of the [MooFileMetadata].
+    /// Get the A20 gating policy in effect when the tests in this file were captured, or `None`
+    /// if the stored value is unknown to this version of the library.
+    pub fn a20_policy(&self) -> Option<MooA20Policy> {
+        MooA20Policy::from_u8(self.reserved[0])
+    }
+
+    /// Set the A20 gating policy of the [MooFileMetadata].
+    pub fn set_a20_policy(&mut self, a20_policy: MooA20Policy) {
+        self.reserved[0] = a20_policy.to_u8();
+    }
+
+    /// Get the mnemonic string of the [MooFileMetadata], with padding removed.
     pub fn mnemonic(&self) -> String {
-        String::from_utf8_lossy(&self.mnemonic).trim().to_string()
+        let bytes = match &self.mnemonic_ext {
+            Some(ext) => ext.bytes.as_slice(),
+            None => self.mnemonic.as_slice(),
+        };
+        String::from_utf8_lossy(bytes).trim().to_string()
+    }
+
+    /// Set the mnemonic string of the [MooFileMetadata]. The first eight bytes are stored in the
+    /// fixed mnemonic field for older readers; longer mnemonics are also stored in full, up to
+    /// 255 bytes.
+    pub fn set_mnemonic(&mut self, mnemonic: &str) {
+        let bytes = &mnemonic.as_bytes()[..mnemonic.len().min(u8::MAX as usize)];
+        let fixed_len = bytes.len().min(8);
+        self.mnemonic = [b' '; 8];
+        self.mnemonic[..fixed_len].copy_from_slice(&bytes[..fixed_len]);
+        self.mnemonic_ext = (bytes.len() > 8).then(|| MooByteString { bytes: bytes.to_vec() });
     }
 
     pub fn group_extension(&self) -> Option<u8> {
@@ -134,17 +554,121 @@ impl MooFileMetadata {
     pub fn set_group_extension(&mut self, extension: Option<u8>) {
         self.extension = extension.unwrap_or(0xFF);
     }
+
+    /// Get the [MooOpcodeId] of the instruction tested in this file, combining the opcode bytes
+    /// and `extension` field. The opcode byte sequence is used if present, otherwise the id is
+    /// decoded from the `opcode` field. Returns None if the file contains multiple opcodes.
+    pub fn opcode_id(&self) -> Option<MooOpcodeId> {
+        if self.opcode == 0xFFFF_FFFF {
+            return None;
+        }
+        Some(match self.opcode_bytes() {
+            Some(bytes) => MooOpcodeId::from_bytes(bytes, self.group_extension(), self.cpu_type.into()),
+            None => MooOpcodeId::from_u32(self.opcode, self.group_extension(), self.cpu_type.into()),
+        })
+    }
+
+    /// Set the `opcode`, `extension` and opcode byte sequence fields from a [MooOpcodeId].
+    ///
+    /// # Returns
+    /// A [MooError::WriteError] if the prefix and opcode bytes do not fit in the `opcode` field.
+    pub fn set_opcode_id(&mut self, id: &MooOpcodeId) -> Result<(), MooError> {
+        self.opcode = id
+            .to_u32()
+            .ok_or_else(|| MooError::WriteError(format!("opcode id {} is longer than four bytes", id)))?;
+        self.set_group_extension(id.extension());
+        self.set_opcode_bytes(Some(&id.to_bytes()));
+        Ok(())
+    }
+
+    /// Get the prefix and opcode bytes of the instruction tested in this file, in encoded order,
+    /// if recorded. Files written before MOO 1.2 only have the `opcode` field.
+    pub fn opcode_bytes(&self) -> Option<&[u8]> {
+        self.opcode_bytes.as_ref().map(|o| o.bytes.as_slice())
+    }
+
+    /// Set or clear the opcode byte sequence. Sequences longer than 255 bytes are truncated.
+    /// See [MooOpcodeId::opcode_bytes] to derive a sequence from instruction bytes.
+    pub fn set_opcode_bytes(&mut self, bytes: Option<&[u8]>) {
+        self.opcode_bytes = bytes.filter(|b| !b.is_empty()).map(|b| MooByteString {
+            bytes: b[..b.len().min(u8::MAX as usize)].to_vec(),
+        });
+    }
+
+    /// Get the capture clock frequency in Hz, if recorded.
+    pub fn clock_hz(&self) -> Option<u32> {
+        self.timing.as_ref().and_then(|t| t.clock_hz())
+    }
+
+    /// Get the DRAM refresh period in CPU clock cycles, if recorded.
+    pub fn refresh_period(&self) -> Option<u32> {
+        self.timing.as_ref().and_then(|t| t.refresh_period())
+    }
+
+    /// Get the capture board identifier, if recorded.
+    pub fn board_id(&self) -> Option<String> {
+        self.timing.as_ref().and_then(|t| t.board_id())
+    }
+
+    /// Get the [MooCaptureSource] of the tests in this file. Returns
+    /// [MooCaptureSource::Unspecified] if not recorded, or `None` if the stored value is unknown
+    /// to this version of the library.
+    pub fn capture_source(&self) -> Option<MooCaptureSource> {
+        self.capture
+            .as_ref()
+            .map_or(Some(MooCaptureSource::Unspecified), |c| c.source())
+    }
+
+    /// Get the name of the tool that produced the tests in this file, if recorded.
+    pub fn capture_tool(&self) -> Option<String> {
+        self.capture.as_ref().and_then(|c| c.tool())
+    }
 }
 
 /// A [MooTestGenMetadata] struct represents the test generation metadata for a `MOO` test file.
 /// This chunk and struct are considered for internal use only by a `MOO` test generator / validator.
 /// It is subject to change at any time.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[binrw]
 #[brw(little)]
 pub struct MooTestGenMetadata {
-    /// The per-test seed value used for generating this test.
+    /// The per-test seed value used for generating this test. Given the same generator, this
+    /// seed alone reproduces the test's initial state.
     pub seed:   u64,
-    /// The number of generations (attempts) it took to create this test.
+    /// The number of generations (attempts) it took to create this test. Its exact meaning
+    /// depends on the generator; some count retries and record 0 for a first-attempt success.
     pub gen_ct: u16,
 }
+
+impl MooTestGenMetadata {
+    /// The size in bytes of a `GMET` chunk payload.
+    pub const SIZE: usize = 10;
+
+    /// Create a new [MooTestGenMetadata] from a per-test seed and generation count.
+    pub fn new(seed: u64, gen_ct: u16) -> Self {
+        Self { seed, gen_ct }
+    }
+
+    /// Get the per-test seed.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get the number of generations it took to create the test.
+    pub fn gen_ct(&self) -> u16 {
+        self.gen_ct
+    }
+}
+
+impl Display for MooTestGenMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "seed {:016X}, {} generation{}",
+            self.seed,
+            self.gen_ct,
+            if self.gen_ct == 1 { "" } else { "s" }
+        )
+    }
+}
@@ -21,14 +21,36 @@
     DEALINGS IN THE SOFTWARE.
 */
 
+pub mod align;
+pub mod anchor;
+pub mod arch_tag;
 pub mod chunks;
 pub mod comparison;
+pub mod cycle_cursor;
+pub mod cycle_iter;
+pub mod cycle_pattern;
 pub mod cycles;
 pub mod effective_address;
 pub mod errors;
+pub mod exception;
 pub mod flags;
+pub mod glitch;
+pub mod hash;
+pub mod interpolate;
+pub mod io_stimulus;
+pub mod ivt;
+pub mod license;
 pub mod metadata;
+pub mod mutate;
+pub mod opcode;
+pub mod prefix;
 pub mod ram;
+pub mod ram_diff;
+pub mod stack;
+pub mod timing;
+pub mod trace_svg;
+pub mod trim;
+pub mod vendor;
 
 use std::fmt::Display;
 
@@ -37,6 +59,10 @@ pub use cycles::*;
 pub use metadata::*;
 pub use ram::*;
 
+// Compatibility re-exports. The canonical definitions live in `test`, `registers` and
+// `types::flags`; these paths are kept so older code importing everything from `types` still builds.
+pub use crate::registers::{MooRegister, MooRegisterDiff, MooRegisters};
+pub use flags::{MooCpuFlag, MooCpuFlags, MooCpuFlagsDiff};
 pub use test::{moo_test::MooTest, test_state::MooTestState};
 
 use crate::test;
@@ -108,6 +134,17 @@ pub enum MooStateType {
     Final,
 }
 
+/// A [MooQueueDiff] describes how the instruction queue changed between the initial and final
+/// states of a test, as a number of bytes consumed from the front of the initial queue followed
+/// by the bytes appended to it by prefetching.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooQueueDiff {
+    /// The number of bytes removed from the front of the initial queue.
+    pub consumed: usize,
+    /// The bytes appended to the queue after the remaining initial bytes.
+    pub fetched:  Vec<u8>,
+}
+
 /// [MooCpuDataBusWidth] represents the native bus size of a CPU.
 #[derive(Copy, Clone, Debug, Default)]
 pub enum MooCpuDataBusWidth {
@@ -130,7 +167,7 @@ impl From<MooCpuType> for MooCpuDataBusWidth {
 
 /// [MooDataWidth] represents the active width of a data bus.
 /// On 16-bit buses, this can be the full 16-bits, or either 8-bit halves (high or low).
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum MooDataWidth {
     #[default]
     Invalid,
@@ -142,7 +179,7 @@ pub enum MooDataWidth {
     EightHigh,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MooBusState {
     /// Interrupt Acknowledge
     INTA = 0,
@@ -162,6 +199,71 @@ pub enum MooBusState {
     PASV = 7,
 }
 
+impl MooBusState {
+    /// All bus states, in the order of their discriminants.
+    pub const ALL: [MooBusState; 8] = [
+        MooBusState::INTA,
+        MooBusState::IOR,
+        MooBusState::IOW,
+        MooBusState::HALT,
+        MooBusState::CODE,
+        MooBusState::MEMR,
+        MooBusState::MEMW,
+        MooBusState::PASV,
+    ];
+
+    /// Returns true for bus cycles that read from memory or IO, including code fetches.
+    pub fn is_read(&self) -> bool {
+        matches!(self, MooBusState::CODE | MooBusState::MEMR | MooBusState::IOR)
+    }
+
+    /// Returns true for bus cycles that write to memory or IO.
+    pub fn is_write(&self) -> bool {
+        matches!(self, MooBusState::MEMW | MooBusState::IOW)
+    }
+
+    /// Returns true for bus cycles that access memory, including code fetches.
+    pub fn is_memory(&self) -> bool {
+        matches!(self, MooBusState::CODE | MooBusState::MEMR | MooBusState::MEMW)
+    }
+
+    /// Returns true for bus cycles that access IO.
+    pub fn is_io(&self) -> bool {
+        matches!(self, MooBusState::IOR | MooBusState::IOW)
+    }
+}
+
+/// A [MooBusHistogram] counts bus cycles by [MooBusState].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooBusHistogram([usize; 8]);
+
+impl MooBusHistogram {
+    /// Add one bus cycle of the given state.
+    pub fn add(&mut self, state: MooBusState) {
+        self.0[state as usize] += 1;
+    }
+
+    /// Returns the number of bus cycles of the given state.
+    pub fn count(&self, state: MooBusState) -> usize {
+        self.0[state as usize]
+    }
+
+    /// Returns the number of bus cycles whose state satisfies `f`, e.g. [MooBusState::is_read].
+    pub fn count_where(&self, f: impl Fn(&MooBusState) -> bool) -> usize {
+        self.iter().filter(|(state, _)| f(state)).map(|(_, n)| n).sum()
+    }
+
+    /// Returns the total number of bus cycles counted.
+    pub fn total(&self) -> usize {
+        self.0.iter().sum()
+    }
+
+    /// Iterate over each bus state and its count, in the order of [MooBusState::ALL].
+    pub fn iter(&self) -> impl Iterator<Item = (MooBusState, usize)> + '_ {
+        MooBusState::ALL.iter().map(|&state| (state, self.count(state)))
+    }
+}
+
 /// Display implementation for MooBusState.
 impl Display for MooBusState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -200,7 +302,7 @@ impl From<MooCpuType> for MooIvtOrder {
 }
 
 /// [MooTState] represents the T-state of the CPU.
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum MooTState {
     /// Idle T-state, when a bus cycle is not in progress.
     Ti,
@@ -448,6 +550,7 @@ impl MooCpuType {
 /// A [MooException] represents the `EXCP` chunk in a MOO file and contains information about the
 /// exception that a test execution may have triggered.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[binrw]
 #[brw(little)]
 pub struct MooException {
@@ -457,6 +560,19 @@ pub struct MooException {
     pub flag_address:  u32,
 }
 
+/// A [MooAddressWrap] is evidence that a real-mode test crosses the 1MB address boundary, where
+/// the result depends on the number of address lines and the state of the A20 gate.
+/// See [MooTest::address_wraps](crate::prelude::MooTest::address_wraps).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooAddressWrap {
+    /// The instruction bytes at `CS:IP` extend to or past 1MB. `linear_address` is `CS:IP`.
+    InstructionFetch { linear_address: u32 },
+    /// The memory operand's segment base plus offset lies at or past 1MB.
+    EffectiveAddress { linear_address: u32 },
+    /// A memory bus transaction beginning at `cycle` addressed memory at or past 1MB.
+    HighMemoryAccess { cycle: usize, address: u32 },
+}
+
 /// A [MooSegmentSize] represents the native size of a segment.
 /// This is only relevant for the 80386 family, as earlier CPUs only support 16-bit segments.
 #[derive(Clone, Debug)]
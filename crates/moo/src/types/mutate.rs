@@ -0,0 +1,206 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Mutated variants of captured tests, for fuzzing the error paths of emulators and test runners.
+//!
+//! A [MooMutation] changes one aspect of a test's initial state while keeping its recorded final
+//! state and cycles, so the result no longer describes a real execution. Mutated tests have no
+//! hash or generator metadata, and carry the [SYNTHETIC_TAG] tag along with a tag naming the
+//! mutation. They remain writable, so that files of them can be handed to an emulator; such files
+//! should be marked with [MooCaptureSource::Synthesized](crate::types::MooCaptureSource::Synthesized).
+//! [MooMutator] picks mutations at random from a seed.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    registers::MooRegisters,
+    test::moo_test::MooTest,
+    test_file::seed::MooSplitMix64,
+    types::{flags::MooCpuFlag, MooCpuFamily, MooCpuType, MooRamEntry},
+};
+
+/// The tag applied to every mutated test.
+pub const SYNTHETIC_TAG: &str = "synthetic";
+
+/// The flags [MooMutation::FlipFlag] may toggle. The trap and interrupt flags are left alone so
+/// that a mutated test still runs a single instruction.
+const MUTABLE_FLAGS: [MooCpuFlag; 7] = [
+    MooCpuFlag::CF,
+    MooCpuFlag::PF,
+    MooCpuFlag::AF,
+    MooCpuFlag::ZF,
+    MooCpuFlag::SF,
+    MooCpuFlag::DF,
+    MooCpuFlag::OF,
+];
+
+/// A single change applied to a test by [MooTest::mutated].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooMutation {
+    /// Toggle a flag in the initial flags register.
+    FlipFlag(MooCpuFlag),
+    /// XOR the last instruction byte, in both the test bytes and initial RAM, with a non-zero
+    /// mask. Immediate operands are encoded last, so this perturbs the immediate of instructions
+    /// that have one, and the displacement or ModR/M byte of those that don't.
+    PerturbImmediate(u8),
+    /// Move the initial RAM outside the instruction bytes by a number of bytes, so that memory
+    /// operands read different values than the test expects.
+    ShiftMemory(i32),
+}
+
+impl MooMutation {
+    /// The tag identifying the kind of mutation.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            MooMutation::FlipFlag(_) => "mutated-flags",
+            MooMutation::PerturbImmediate(_) => "mutated-immediate",
+            MooMutation::ShiftMemory(_) => "mutated-memory",
+        }
+    }
+}
+
+impl Display for MooMutation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooMutation::FlipFlag(flag) => write!(f, "flip {}", flag.name()),
+            MooMutation::PerturbImmediate(mask) => write!(f, "xor last instruction byte with {:02X}", mask),
+            MooMutation::ShiftMemory(delta) => write!(f, "shift memory by {:+}", delta),
+        }
+    }
+}
+
+/// The mask of address lines present on a CPU family.
+fn address_mask(family: MooCpuFamily) -> u32 {
+    match family {
+        MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186 => 0x000F_FFFF,
+        MooCpuFamily::Intel80286 => 0x00FF_FFFF,
+        MooCpuFamily::Intel80386 => 0xFFFF_FFFF,
+    }
+}
+
+impl MooTest {
+    /// Derive a variant of this test with `mutation` applied to its initial state. The final state
+    /// is stored in full, so that the expected registers don't follow the mutated initial ones.
+    ///
+    /// Returns `None` if the mutation doesn't apply: the test has no flags register, no
+    /// instruction bytes past the opcode, or no memory outside the instruction.
+    pub fn mutated(&self, cpu_type: MooCpuType, mutation: MooMutation) -> Option<MooTest> {
+        let family = MooCpuFamily::from(cpu_type);
+        let mut bytes = self.bytes.clone();
+        let mut initial_state = self.initial_state().clone();
+        let csip = initial_state.regs.csip_linear(initial_state.descriptors.as_ref())?;
+        let mask = address_mask(family);
+        let code = |address: u32| (0..bytes.len() as u32).any(|i| csip.wrapping_add(i) & mask == address);
+
+        match mutation {
+            MooMutation::FlipFlag(flag) => {
+                let flags = initial_state.regs.flags() ^ flag.mask();
+                match &mut initial_state.regs {
+                    MooRegisters::Sixteen(regs) => regs.set_flags(flags as u16),
+                    MooRegisters::ThirtyTwo(regs) => regs.set_eflags(flags),
+                }
+            }
+            MooMutation::PerturbImmediate(xor) => {
+                let opcode_len = self.opcode_bytes(family)?.len();
+                if xor == 0 || bytes.len() <= opcode_len {
+                    return None;
+                }
+                let last = bytes.len() - 1;
+                bytes[last] ^= xor;
+                let address = csip.wrapping_add(last as u32) & mask;
+                for entry in initial_state.ram.iter_mut().filter(|e| e.address == address) {
+                    entry.value = bytes[last];
+                }
+            }
+            MooMutation::ShiftMemory(delta) => {
+                if delta == 0 || initial_state.ram.iter().all(|e| code(e.address)) {
+                    return None;
+                }
+                // Instruction bytes stay where they are and win over shifted data.
+                let mut ram: BTreeMap<u32, u8> = BTreeMap::new();
+                for entry in initial_state.ram.iter().filter(|e| !code(e.address)) {
+                    ram.insert(entry.address.wrapping_add_signed(delta) & mask, entry.value);
+                }
+                for entry in initial_state.ram.iter().filter(|e| code(e.address)) {
+                    ram.insert(entry.address, entry.value);
+                }
+                initial_state.ram = ram
+                    .into_iter()
+                    .map(|(address, value)| MooRamEntry { address, value })
+                    .collect();
+            }
+        }
+
+        let mut test = MooTest::new(
+            self.name().to_string(),
+            None,
+            &bytes,
+            initial_state,
+            self.full_final_state(),
+            self.cycles(),
+            self.exception().cloned(),
+            None,
+        );
+        test.tags = self.tags.clone();
+        test.anchors = self.anchors.clone();
+        test.io_stimulus = self.io_stimulus.clone();
+        test.add_tag(SYNTHETIC_TAG);
+        test.add_tag(mutation.tag());
+        Some(test)
+    }
+}
+
+/// A [MooMutator] derives mutated tests with randomly chosen [MooMutation]s. The same seed and
+/// sequence of tests always produces the same mutations.
+pub struct MooMutator {
+    rng: MooSplitMix64,
+}
+
+impl MooMutator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: MooSplitMix64::new(seed),
+        }
+    }
+
+    /// Pick a random mutation of any kind.
+    pub fn next_mutation(&mut self) -> MooMutation {
+        match self.rng.next_u8() % 3 {
+            0 => MooMutation::FlipFlag(MUTABLE_FLAGS[self.rng.next_u8() as usize % MUTABLE_FLAGS.len()]),
+            1 => MooMutation::PerturbImmediate(self.rng.next_u8().max(1)),
+            _ => {
+                let delta = (self.rng.next_u8() % 16) as i32 + 1;
+                MooMutation::ShiftMemory(if self.rng.next_u8() & 1 == 0 { delta } else { -delta })
+            }
+        }
+    }
+
+    /// Derive a mutated variant of `test`. A few mutations are tried in turn, since not every kind
+    /// applies to every test. Returns `None` if none applied.
+    pub fn mutate(&mut self, cpu_type: MooCpuType, test: &MooTest) -> Option<(MooMutation, MooTest)> {
+        (0..4).find_map(|_| {
+            let mutation = self.next_mutation();
+            test.mutated(cpu_type, mutation).map(|mutated| (mutation, mutated))
+        })
+    }
+}
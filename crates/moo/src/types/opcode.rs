@@ -0,0 +1,244 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A [MooOpcodeId] identifies the instruction a test or test file exercises.
+//!
+//! Test files are named after the opcode they test, with an optional group extension for
+//! instructions that select an operation with the `reg` field of their ModR/M byte, such as
+//! `D2.1.MOO` for `ROR r/m8, CL` or `0F01.4.MOO` for `SMSW`. Any prefixes that are part of the
+//! tested form come first, e.g. `F3A4.MOO` for `REP MOVSB`.
+
+use std::{fmt::Display, path::Path};
+
+use crate::{
+    test::moo_test::is_prefix,
+    types::{errors::MooError, MooCpuFamily},
+};
+
+/// A [MooOpcodeId] holds the primary opcode bytes of an instruction, its group extension if it
+/// has one, and the set of prefixes that are part of its identity.
+///
+/// Ids are ordered by opcode, then extension, then prefixes, so that a sorted list of ids follows
+/// opcode map order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MooOpcodeId {
+    opcode:    Vec<u8>,
+    extension: Option<u8>,
+    prefixes:  Vec<u8>,
+}
+
+impl MooOpcodeId {
+    /// Create a new [MooOpcodeId] from primary opcode bytes and an optional group extension.
+    pub fn new(opcode: &[u8], extension: Option<u8>) -> Self {
+        Self {
+            opcode: opcode.to_vec(),
+            extension,
+            prefixes: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to add a prefix byte. Prefixes are kept sorted and unique.
+    pub fn with_prefix(mut self, prefix: u8) -> Self {
+        if let Err(pos) = self.prefixes.binary_search(&prefix) {
+            self.prefixes.insert(pos, prefix);
+        }
+        self
+    }
+
+    /// Returns the primary opcode bytes.
+    pub fn opcode(&self) -> &[u8] {
+        &self.opcode
+    }
+
+    /// Returns the group extension, if any.
+    pub fn extension(&self) -> Option<u8> {
+        self.extension
+    }
+
+    /// Returns the sorted set of prefix bytes.
+    pub fn prefixes(&self) -> &[u8] {
+        &self.prefixes
+    }
+
+    /// Returns true if `opcode` selects its operation with the `reg` field of the ModR/M byte on
+    /// the given [MooCpuFamily].
+    pub fn is_group_opcode(opcode: &[u8], cpu_family: MooCpuFamily) -> bool {
+        use MooCpuFamily::*;
+        match opcode {
+            [0x80..=0x83 | 0xD0..=0xD3 | 0xF6 | 0xF7 | 0xFE | 0xFF] => true,
+            [0xC0 | 0xC1] => !matches!(cpu_family, Intel8086 | NecV30),
+            [0x0F, 0x00 | 0x01] => matches!(cpu_family, Intel80286 | Intel80386),
+            [0x0F, 0xBA] => matches!(cpu_family, Intel80386),
+            _ => false,
+        }
+    }
+
+    /// Returns true if `0x0F` begins a two-byte opcode on the given [MooCpuFamily]. On the 8086
+    /// and 80186 it is a single-byte opcode.
+    fn has_two_byte_opcodes(cpu_family: MooCpuFamily) -> bool {
+        !matches!(cpu_family, MooCpuFamily::Intel8086 | MooCpuFamily::Intel80186)
+    }
+
+    /// Split instruction bytes into the number of leading prefix bytes and the length of the
+    /// opcode that follows them. Returns None if `bytes` ends before the opcode.
+    fn split_instruction(bytes: &[u8], cpu_family: MooCpuFamily) -> Option<(usize, usize)> {
+        let prefix_ct = bytes.iter().take_while(|&&b| is_prefix(b, cpu_family)).count();
+        let opcode_len = match bytes.get(prefix_ct)? {
+            0x0F if Self::has_two_byte_opcodes(cpu_family) => 2,
+            _ => 1,
+        };
+        (bytes.len() >= prefix_ct + opcode_len).then_some((prefix_ct, opcode_len))
+    }
+
+    /// Return the prefix and opcode bytes at the start of an instruction, in encoded order. The
+    /// ModR/M byte and any operands are excluded.
+    ///
+    /// Returns None if `bytes` ends before the opcode.
+    pub fn opcode_bytes(bytes: &[u8], cpu_family: MooCpuFamily) -> Option<Vec<u8>> {
+        let (prefix_ct, opcode_len) = Self::split_instruction(bytes, cpu_family)?;
+        Some(bytes[..prefix_ct + opcode_len].to_vec())
+    }
+
+    /// Decode the [MooOpcodeId] of an instruction from its bytes. Leading prefixes are collected
+    /// into the prefix set, and the group extension is taken from the ModR/M byte of a group
+    /// opcode.
+    ///
+    /// Returns None if `bytes` ends before the opcode or a required ModR/M byte.
+    pub fn from_instruction_bytes(bytes: &[u8], cpu_family: MooCpuFamily) -> Option<Self> {
+        let (prefix_ct, opcode_len) = Self::split_instruction(bytes, cpu_family)?;
+        let rest = &bytes[prefix_ct..];
+        let opcode = &rest[..opcode_len];
+        let extension = if Self::is_group_opcode(opcode, cpu_family) {
+            Some((rest.get(opcode_len)? >> 3) & 0x07)
+        }
+        else {
+            None
+        };
+
+        let mut id = Self::new(opcode, extension);
+        for &prefix in &bytes[..prefix_ct] {
+            id = id.with_prefix(prefix);
+        }
+        Some(id)
+    }
+
+    /// Parse a [MooOpcodeId] from its string form, e.g. `D2.1`, `0F01.4` or `F3A4`: hexadecimal
+    /// prefix and opcode bytes, optionally followed by `.` and a single-digit group extension.
+    pub fn parse(s: &str, cpu_family: MooCpuFamily) -> Result<Self, MooError> {
+        let err = |msg: &str| MooError::ParseError(format!("invalid opcode id '{}': {}", s, msg));
+
+        let (hex, extension) = match s.split_once('.') {
+            Some((hex, ext)) => match ext.as_bytes() {
+                [d @ b'0'..=b'7'] => (hex, Some(d - b'0')),
+                _ => return Err(err("extension must be a digit from 0 to 7")),
+            },
+            None => (s, None),
+        };
+        if hex.is_empty() || hex.len() % 2 != 0 {
+            return Err(err("expected an even number of hex digits"));
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| err("expected hex digits"))?;
+
+        let prefix_ct = bytes.iter().take_while(|&&b| is_prefix(b, cpu_family)).count();
+        if prefix_ct == bytes.len() {
+            return Err(err("missing opcode"));
+        }
+        let mut id = Self::new(&bytes[prefix_ct..], extension);
+        for &prefix in &bytes[..prefix_ct] {
+            id = id.with_prefix(prefix);
+        }
+        Ok(id)
+    }
+
+    /// Parse a [MooOpcodeId] from a test file name such as `D2.1.MOO` or `0F01.4.MOO.gz`.
+    /// Only the portion of the file name before the `MOO` extension is considered.
+    pub fn from_file_name(path: impl AsRef<Path>, cpu_family: MooCpuFamily) -> Result<Self, MooError> {
+        let name = path
+            .as_ref()
+            .file_name()
+            .and_then(|os| os.to_str())
+            .ok_or_else(|| MooError::ParseError("missing file name".to_string()))?;
+        let stem = name
+            .to_ascii_uppercase()
+            .find(".MOO")
+            .map(|pos| &name[..pos])
+            .unwrap_or(name);
+        Self::parse(stem, cpu_family)
+    }
+
+    /// Returns the prefix bytes followed by the opcode bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.prefixes.iter().chain(&self.opcode).copied().collect()
+    }
+
+    /// Encode the prefix and opcode bytes as the big-endian `u32` used by the `opcode` field of
+    /// [MooFileMetadata](crate::types::MooFileMetadata). Returns None if there are more than
+    /// four bytes.
+    pub fn to_u32(&self) -> Option<u32> {
+        let bytes = self.to_bytes();
+        (bytes.len() <= 4).then(|| bytes.iter().fold(0u32, |acc, &b| (acc << 8) | b as u32))
+    }
+
+    /// Decode a [MooOpcodeId] from a big-endian `u32` opcode value and optional group extension,
+    /// as stored in [MooFileMetadata](crate::types::MooFileMetadata). The extension is ignored
+    /// for opcodes that are not group opcodes, as some files store 0 rather than 0xFF there.
+    pub fn from_u32(opcode: u32, extension: Option<u8>, cpu_family: MooCpuFamily) -> Self {
+        let be = opcode.to_be_bytes();
+        let start = be.iter().position(|&b| b != 0).unwrap_or(3);
+        Self::from_bytes(&be[start..], extension, cpu_family)
+    }
+
+    /// Decode a [MooOpcodeId] from a sequence of prefix and opcode bytes and an optional group
+    /// extension. The final byte is always taken as part of the opcode. As with
+    /// [MooOpcodeId::from_u32], the extension is ignored for opcodes that are not group opcodes.
+    pub fn from_bytes(bytes: &[u8], extension: Option<u8>, cpu_family: MooCpuFamily) -> Self {
+        let prefix_ct = bytes
+            .iter()
+            .take(bytes.len().saturating_sub(1))
+            .take_while(|&&b| is_prefix(b, cpu_family))
+            .count();
+        let opcode = &bytes[prefix_ct..];
+        let extension = extension.filter(|_| Self::is_group_opcode(opcode, cpu_family));
+        let mut id = Self::new(opcode, extension);
+        for &prefix in &bytes[..prefix_ct] {
+            id = id.with_prefix(prefix);
+        }
+        id
+    }
+}
+
+impl Display for MooOpcodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for b in self.prefixes.iter().chain(&self.opcode) {
+            write!(f, "{:02X}", b)?;
+        }
+        if let Some(extension) = self.extension {
+            write!(f, ".{}", extension)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fmt::Display;
+
+use crate::{registers::MooSegmentRegister, types::MooCpuFamily};
+
+/// A [MooPrefix] is a decoded instruction prefix byte.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MooPrefix {
+    /// A segment override prefix (`26`, `2E`, `36`, `3E`, and `64`, `65` on the 386).
+    Segment(MooSegmentRegister),
+    /// The `LOCK` prefix (`F0`).
+    Lock,
+    /// The `REPNE`/`REPNZ` prefix (`F2`).
+    Repne,
+    /// The `REP`/`REPE`/`REPZ` prefix (`F3`).
+    Rep,
+    /// The operand size override prefix (`66`). 386 only.
+    OperandSize,
+    /// The address size override prefix (`67`). 386 only.
+    AddressSize,
+}
+
+impl MooPrefix {
+    /// Decode `byte` as a prefix for the given [MooCpuFamily]. Returns None if `byte` is not a
+    /// prefix on that CPU.
+    pub fn from_byte(byte: u8, cpu_family: MooCpuFamily) -> Option<Self> {
+        let is_386 = matches!(cpu_family, MooCpuFamily::Intel80386);
+        match byte {
+            0x26 => Some(MooPrefix::Segment(MooSegmentRegister::ES)),
+            0x2E => Some(MooPrefix::Segment(MooSegmentRegister::CS)),
+            0x36 => Some(MooPrefix::Segment(MooSegmentRegister::SS)),
+            0x3E => Some(MooPrefix::Segment(MooSegmentRegister::DS)),
+            0x64 if is_386 => Some(MooPrefix::Segment(MooSegmentRegister::FS)),
+            0x65 if is_386 => Some(MooPrefix::Segment(MooSegmentRegister::GS)),
+            0x66 if is_386 => Some(MooPrefix::OperandSize),
+            0x67 if is_386 => Some(MooPrefix::AddressSize),
+            0xF0 => Some(MooPrefix::Lock),
+            0xF2 => Some(MooPrefix::Repne),
+            0xF3 => Some(MooPrefix::Rep),
+            _ => None,
+        }
+    }
+
+    /// Returns the encoded prefix byte.
+    pub fn byte(&self) -> u8 {
+        match self {
+            MooPrefix::Segment(MooSegmentRegister::ES) => 0x26,
+            MooPrefix::Segment(MooSegmentRegister::CS) => 0x2E,
+            MooPrefix::Segment(MooSegmentRegister::SS) => 0x36,
+            MooPrefix::Segment(MooSegmentRegister::DS) => 0x3E,
+            MooPrefix::Segment(MooSegmentRegister::FS) => 0x64,
+            MooPrefix::Segment(MooSegmentRegister::GS) => 0x65,
+            MooPrefix::OperandSize => 0x66,
+            MooPrefix::AddressSize => 0x67,
+            MooPrefix::Lock => 0xF0,
+            MooPrefix::Repne => 0xF2,
+            MooPrefix::Rep => 0xF3,
+        }
+    }
+
+    /// Decode the run of prefixes at the start of `bytes`, in order. Decoding stops at the first
+    /// byte that is not a prefix, so opcode, ModR/M and immediate bytes are never mistaken for
+    /// prefixes.
+    pub fn decode(bytes: &[u8], cpu_family: MooCpuFamily) -> Vec<MooPrefix> {
+        bytes
+            .iter()
+            .map_while(|&b| MooPrefix::from_byte(b, cpu_family))
+            .collect()
+    }
+}
+
+impl Display for MooPrefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooPrefix::Segment(seg) => write!(f, "{:?}:", seg),
+            MooPrefix::Lock => write!(f, "lock"),
+            MooPrefix::Repne => write!(f, "repne"),
+            MooPrefix::Rep => write!(f, "rep"),
+            MooPrefix::OperandSize => write!(f, "o32"),
+            MooPrefix::AddressSize => write!(f, "a32"),
+        }
+    }
+}
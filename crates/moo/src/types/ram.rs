@@ -21,8 +21,28 @@
     DEALINGS IN THE SOFTWARE.
 */
 
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeBounds,
+};
+
 use binrw::binrw;
 
+use crate::types::errors::MooError;
+
+/// How [MooRamEntries::merge] resolves an address present in both collections with different
+/// values.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MooRamMergePolicy {
+    /// Keep the existing value.
+    #[default]
+    Keep,
+    /// Take the value from the merged collection.
+    Replace,
+    /// Fail the merge, leaving the collection unchanged.
+    Error,
+}
+
 /// [MooRamEntries] is a collection of [MooRamEntry] items representing memory addresses and their
 /// corresponding byte values. It maps to a `MOO` `RAM ` chunk.
 #[derive(Clone, Debug, Default)]
@@ -43,12 +63,116 @@ impl From<&[MooRamEntry]> for MooRamEntries {
     }
 }
 
+impl From<&BTreeMap<u32, u8>> for MooRamEntries {
+    fn from(map: &BTreeMap<u32, u8>) -> Self {
+        let entries: Vec<MooRamEntry> = map
+            .iter()
+            .map(|(&address, &value)| MooRamEntry { address, value })
+            .collect();
+        Self {
+            entry_count: entries.len() as u32,
+            entries,
+        }
+    }
+}
+
+impl From<BTreeMap<u32, u8>> for MooRamEntries {
+    fn from(map: BTreeMap<u32, u8>) -> Self {
+        MooRamEntries::from(&map)
+    }
+}
+
+impl From<&MooRamEntries> for BTreeMap<u32, u8> {
+    fn from(entries: &MooRamEntries) -> Self {
+        entries.to_btree_map()
+    }
+}
+
 impl MooRamEntries {
     /// Returns the number of entries in the [MooRamEntries] chunk.
     pub fn len(&self) -> usize {
         self.entries.len()
     }
 
+    /// Returns true if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns true if any entry has the given `address`.
+    pub fn contains_address(&self, address: u32) -> bool {
+        self.entries.iter().any(|entry| entry.address == address)
+    }
+
+    /// Returns an iterator over the entries whose address falls within `range`, in entry order.
+    pub fn range<R: RangeBounds<u32>>(&self, range: R) -> impl Iterator<Item = &MooRamEntry> {
+        self.entries.iter().filter(move |entry| range.contains(&entry.address))
+    }
+
+    /// Sort the entries by address. The sort is stable, so duplicate addresses keep their
+    /// relative order.
+    pub fn sort_by_address(&mut self) {
+        self.entries.sort_by_key(|entry| entry.address);
+    }
+
+    /// Return the entries as a map of address to value. If an address appears more than once,
+    /// the last entry wins.
+    pub fn to_btree_map(&self) -> BTreeMap<u32, u8> {
+        self.entries.iter().map(|entry| (entry.address, entry.value)).collect()
+    }
+
+    /// Merge the entries of `other` into this collection. Addresses not yet present are appended
+    /// in the order they appear in `other`; an address present in both with a different value is
+    /// resolved by `policy`. If an address appears in this collection more than once, its last
+    /// entry is the one compared and replaced.
+    ///
+    /// Returns the number of entries added or replaced. With [MooRamMergePolicy::Error], a
+    /// conflict returns [MooError::MergeError] and leaves this collection unchanged.
+    pub fn merge(&mut self, other: &MooRamEntries, policy: MooRamMergePolicy) -> Result<usize, MooError> {
+        let mut index: HashMap<u32, usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.address, i))
+            .collect();
+
+        if policy == MooRamMergePolicy::Error {
+            let mut incoming: HashMap<u32, u8> = HashMap::new();
+            for entry in &other.entries {
+                let existing = index
+                    .get(&entry.address)
+                    .map(|&i| self.entries[i].value)
+                    .or_else(|| incoming.get(&entry.address).copied());
+                if let Some(value) = existing.filter(|&value| value != entry.value) {
+                    return Err(MooError::MergeError(format!(
+                        "RAM address {:08X} holds {:02X}, merged entry holds {:02X}",
+                        entry.address, value, entry.value
+                    )));
+                }
+                incoming.insert(entry.address, entry.value);
+            }
+        }
+
+        let mut changed = 0;
+        for entry in &other.entries {
+            match index.get(&entry.address) {
+                Some(&i) => {
+                    if self.entries[i].value != entry.value && policy == MooRamMergePolicy::Replace {
+                        self.entries[i].value = entry.value;
+                        changed += 1;
+                    }
+                }
+                None => {
+                    index.insert(entry.address, self.entries.len());
+                    self.entries.push(*entry);
+                    changed += 1;
+                }
+            }
+        }
+        self.entry_count = self.entries.len() as u32;
+        Ok(changed)
+    }
+
     /// Returns a slice of all [MooRamEntry] items in the [MooRamEntries] chunk.
     pub fn entries(&self) -> &[MooRamEntry] {
         &self.entries
@@ -106,6 +230,7 @@ impl MooRamEntries {
 
 /// A [MooRamEntry] represents a single memory address and its corresponding byte value.
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[binrw]
 #[brw(little)]
 pub struct MooRamEntry {
@@ -0,0 +1,238 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A hexdump-style view of how memory changed between the initial and final states of a test.
+//!
+//! [ram_diff] merges the initial and final RAM entries of a test into [MooRamRegion]s: runs of
+//! consecutive 16-byte rows holding at least one entry. [MooRamDiffPrinter] prints each region
+//! as rows of bytes before and after the test, with changed bytes marked by a `*`.
+
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::types::MooRamEntry;
+
+/// The number of bytes shown on each row of a [MooRamDiffPrinter].
+pub const RAM_DIFF_ROW_LEN: u32 = 16;
+
+/// The value of one address before and after a test. An address may be missing from either
+/// state: initial RAM holds the bytes the test may read, final RAM the bytes it wrote.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooRamDiffByte {
+    pub address: u32,
+    pub before:  Option<u8>,
+    pub after:   Option<u8>,
+}
+
+impl MooRamDiffByte {
+    /// Returns true if the test wrote a value different from the initial one, or wrote to an
+    /// address with no initial value.
+    pub fn changed(&self) -> bool {
+        self.after.is_some_and(|after| Some(after) != self.before)
+    }
+
+    /// Returns the value of the address after the test: the final value if one was written,
+    /// otherwise the initial value.
+    pub fn value_after(&self) -> Option<u8> {
+        self.after.or(self.before)
+    }
+}
+
+/// A [MooRamRegion] is a run of consecutive rows of memory touched by a test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooRamRegion {
+    /// The address of the first row of the region.
+    pub start: u32,
+    /// The address just past the last row of the region.
+    pub end:   u64,
+    /// The bytes of the region present in either state, in address order.
+    pub bytes: Vec<MooRamDiffByte>,
+}
+
+impl MooRamRegion {
+    /// Returns true if `address` lies within the region's rows.
+    pub fn contains(&self, address: u32) -> bool {
+        address >= self.start && (address as u64) < self.end
+    }
+
+    /// Returns the number of changed bytes in the region.
+    pub fn changed_ct(&self) -> usize {
+        self.bytes.iter().filter(|b| b.changed()).count()
+    }
+}
+
+/// Merge `initial` and `final_ram` into [MooRamRegion]s, in address order. Where an address
+/// appears more than once in a state, the last entry wins.
+pub fn ram_diff(initial: &[MooRamEntry], final_ram: &[MooRamEntry]) -> Vec<MooRamRegion> {
+    let mut merged: BTreeMap<u32, MooRamDiffByte> = BTreeMap::new();
+    let blank = |address| MooRamDiffByte {
+        address,
+        before: None,
+        after: None,
+    };
+    for e in initial {
+        merged.entry(e.address).or_insert_with(|| blank(e.address)).before = Some(e.value);
+    }
+    for e in final_ram {
+        merged.entry(e.address).or_insert_with(|| blank(e.address)).after = Some(e.value);
+    }
+
+    let mut regions: Vec<MooRamRegion> = Vec::new();
+    for byte in merged.into_values() {
+        let row = byte.address - byte.address % RAM_DIFF_ROW_LEN;
+        match regions.last_mut() {
+            // Extend the current region over this row if it's the same row or the next one.
+            Some(region) if (row as u64) <= region.end => {
+                region.end = row as u64 + RAM_DIFF_ROW_LEN as u64;
+                region.bytes.push(byte);
+            }
+            _ => regions.push(MooRamRegion {
+                start: row,
+                end:   row as u64 + RAM_DIFF_ROW_LEN as u64,
+                bytes: vec![byte],
+            }),
+        }
+    }
+    regions
+}
+
+/// A helper struct for implementing [Display] for the RAM changes of a test.
+///
+/// Each region is introduced by a header giving its address range, its label if one was added
+/// with [MooRamDiffPrinter::with_label], and the number of changed bytes. Rows the test didn't
+/// write are printed once; rows it wrote are printed as a `before` row and an `after` row, in
+/// which changed bytes are preceded by `*`. Addresses absent from both states are shown as `..`.
+pub struct MooRamDiffPrinter<'a> {
+    pub initial: &'a [MooRamEntry],
+    pub final_ram: &'a [MooRamEntry],
+    pub labels: Vec<(u32, String)>,
+    pub indent: u32,
+    pub changed_only: bool,
+}
+
+impl<'a> MooRamDiffPrinter<'a> {
+    pub fn new(initial: &'a [MooRamEntry], final_ram: &'a [MooRamEntry]) -> Self {
+        Self {
+            initial,
+            final_ram,
+            labels: Vec::new(),
+            indent: 0,
+            changed_only: false,
+        }
+    }
+
+    /// Label the region containing `address`, such as `"stack"`. The first matching label is used.
+    pub fn with_label(mut self, address: u32, label: impl Into<String>) -> Self {
+        self.labels.push((address, label.into()));
+        self
+    }
+
+    pub fn with_indent(mut self, indent: u32) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Only print regions containing a changed byte.
+    pub fn with_changed_only(mut self, state: bool) -> Self {
+        self.changed_only = state;
+        self
+    }
+
+    fn label(&self, region: &MooRamRegion) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(address, _)| region.contains(*address))
+            .map(|(_, label)| label.as_str())
+    }
+}
+
+impl Display for MooRamDiffPrinter<'_> {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let regions = ram_diff(self.initial, self.final_ram);
+        let indent = self.indent as usize;
+        let wide = regions.last().is_some_and(|r| r.end > 0x100_0000);
+        let addr = |address: u64| {
+            if wide {
+                format!("{:08X}", address)
+            }
+            else {
+                format!("{:06X}", address)
+            }
+        };
+
+        for region in regions.iter().filter(|r| !self.changed_only || r.changed_ct() > 0) {
+            let changed_ct = region.changed_ct();
+            writeln!(
+                fmt,
+                "{:indent$}{}{}-{}: {} byte{}, {} changed",
+                "",
+                self.label(region).map(|l| format!("{} ", l)).unwrap_or_default(),
+                addr(region.start as u64),
+                addr(region.end - 1),
+                region.bytes.len(),
+                if region.bytes.len() == 1 { "" } else { "s" },
+                changed_ct,
+            )?;
+
+            for row in region
+                .bytes
+                .chunk_by(|a, b| a.address / RAM_DIFF_ROW_LEN == b.address / RAM_DIFF_ROW_LEN)
+            {
+                let row_start = row[0].address - row[0].address % RAM_DIFF_ROW_LEN;
+                let cells = |value: fn(&MooRamDiffByte) -> Option<u8>, mark: bool| {
+                    let mut line = String::new();
+                    for offset in 0..RAM_DIFF_ROW_LEN {
+                        let byte = row.iter().find(|b| b.address == row_start + offset);
+                        let marker = if mark && byte.is_some_and(|b| b.changed()) {
+                            '*'
+                        }
+                        else {
+                            ' '
+                        };
+                        match byte.and_then(value) {
+                            Some(v) => line.push_str(&format!("{}{:02X}", marker, v)),
+                            None => line.push_str(" .."),
+                        }
+                    }
+                    line
+                };
+
+                let row_addr = addr(row_start as u64);
+                if row.iter().any(|b| b.after.is_some()) {
+                    writeln!(fmt, "{:indent$}  {} before{}", "", row_addr, cells(|b| b.before, false))?;
+                    writeln!(
+                        fmt,
+                        "{:indent$}  {:width$} after {}",
+                        "",
+                        "",
+                        cells(MooRamDiffByte::value_after, true),
+                        width = row_addr.len()
+                    )?;
+                }
+                else {
+                    writeln!(fmt, "{:indent$}  {}       {}", "", row_addr, cells(|b| b.before, false))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Decoding of the words a test pushes onto or pops off the stack.
+//!
+//! [MooTest::stack_frame] compares `SS:SP` before and after the test to find the stack region the
+//! instruction touched, then reads each word from memory. Pushed words are taken from the final
+//! RAM state, falling back to the bus writes in the cycle trace; popped words are taken from the
+//! initial RAM state. Words are labelled from the instruction opcode and any exception raised, so
+//! an interrupt frame shows up as flags, return CS and return IP rather than three anonymous words.
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    registers::{MooRegisters, MooSegmentRegister},
+    test::{moo_test::MooTest, test_state::MooTestState},
+    types::{MooCpuFamily, MooCpuMode, MooCpuType, MooOperandSize},
+};
+
+/// Exceptions that push an error code in protected mode.
+const ERROR_CODE_EXCEPTIONS: [u8; 7] = [8, 10, 11, 12, 13, 14, 17];
+/// The largest stack movement, in bytes, decoded into slots. No single instruction moves the
+/// stack further than this except `ENTER`, `LEAVE` and loads of `SP`, whose frames aren't worth
+/// listing anyway.
+const MAX_FRAME_BYTES: u64 = 64;
+
+/// What a [MooStackSlot] holds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooStackSlotKind {
+    /// The offset to return to, pushed by a call or interrupt.
+    ReturnIp,
+    /// The code segment to return to, pushed by a far call or interrupt.
+    ReturnCs,
+    /// The flags register.
+    Flags,
+    /// The error code pushed by some protected-mode exceptions.
+    ErrorCode,
+    /// Anything else.
+    Data,
+}
+
+impl Display for MooStackSlotKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MooStackSlotKind::ReturnIp => write!(f, "return IP"),
+            MooStackSlotKind::ReturnCs => write!(f, "return CS"),
+            MooStackSlotKind::Flags => write!(f, "flags"),
+            MooStackSlotKind::ErrorCode => write!(f, "error code"),
+            MooStackSlotKind::Data => write!(f, "data"),
+        }
+    }
+}
+
+/// Whether a [MooStackFrame] grew or shrank the stack.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooStackDirection {
+    Pushed,
+    Popped,
+}
+
+/// A single word pushed or popped by a test.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MooStackSlot {
+    /// The linear address of the slot.
+    pub address: u32,
+    /// The `SP` value that points at the slot.
+    pub offset:  u32,
+    /// The word stored in the slot, or `None` if any of its bytes are missing from the test.
+    pub value:   Option<u32>,
+    /// The slot width in bytes, either 2 or 4.
+    pub width:   u8,
+    /// The decoded meaning of the slot.
+    pub kind:    MooStackSlotKind,
+}
+
+impl Display for MooStackSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.width as usize * 2;
+        write!(f, "{:06X} [SP={:04X}]: ", self.address, self.offset)?;
+        match self.value {
+            Some(value) => write!(f, "{:0digits$X}", value)?,
+            None => write!(f, "{:?<digits$}", "")?,
+        }
+        write!(f, " {}", self.kind)
+    }
+}
+
+/// The words a test moved onto or off the stack, in the order they were pushed or popped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MooStackFrame {
+    /// The stack pointer offset before the test.
+    pub initial_sp: u32,
+    /// The stack pointer offset after the test.
+    pub final_sp: u32,
+    /// Whether the stack grew or shrank.
+    pub direction: MooStackDirection,
+    /// The slots between the two stack pointers. Pushed slots are listed from the highest address
+    /// down, popped slots from the lowest address up.
+    pub slots: Vec<MooStackSlot>,
+}
+
+impl MooStackFrame {
+    /// Return the first slot of the given kind, if any.
+    pub fn find(&self, kind: MooStackSlotKind) -> Option<&MooStackSlot> {
+        self.slots.iter().find(|slot| slot.kind == kind)
+    }
+}
+
+impl Display for MooStackFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verb = match self.direction {
+            MooStackDirection::Pushed => "pushed",
+            MooStackDirection::Popped => "popped",
+        };
+        write!(
+            f,
+            "SP {:04X} -> {:04X}, {} slot(s) {}",
+            self.initial_sp,
+            self.final_sp,
+            self.slots.len(),
+            verb
+        )
+    }
+}
+
+/// Return the stack pointer offset of `state` and whether the stack segment is 32-bit.
+fn stack_pointer(state: &MooTestState) -> Option<(u32, bool)> {
+    let wide = state
+        .descriptors
+        .as_ref()
+        .is_some_and(|d| d.is_32bit(MooSegmentRegister::SS));
+    match state.regs() {
+        MooRegisters::Sixteen(regs) => regs.sp().map(|sp| (sp as u32, false)),
+        MooRegisters::ThirtyTwo(regs) if wide => regs.esp().map(|esp| (esp, true)),
+        MooRegisters::ThirtyTwo(regs) => regs.esp().map(|esp| (esp & 0xFFFF, false)),
+    }
+}
+
+/// Return the slot labels implied by the test's opcode or exception, in push or pop order.
+fn slot_labels(test: &MooTest, cpu_type: MooCpuType, direction: MooStackDirection) -> Vec<MooStackSlotKind> {
+    use MooStackSlotKind::*;
+    let family = MooCpuFamily::from(cpu_type);
+    let opcode = test.opcode_id(family);
+    let (op, ext) = match &opcode {
+        Some(id) => (id.opcode().first().copied(), id.extension()),
+        None => (None, None),
+    };
+
+    match direction {
+        MooStackDirection::Pushed => {
+            if let Some(exception) = test.exception() {
+                let mut labels = vec![Flags, ReturnCs, ReturnIp];
+                let protected = !matches!(test.cpu_mode(family), MooCpuMode::RealMode);
+                if protected && ERROR_CODE_EXCEPTIONS.contains(&exception.exception_num) {
+                    labels.push(ErrorCode);
+                }
+                return labels;
+            }
+            match (op, ext) {
+                (Some(0xCC | 0xCD | 0xCE | 0xF1), _) => vec![Flags, ReturnCs, ReturnIp],
+                (Some(0xE8), _) | (Some(0xFF), Some(2)) => vec![ReturnIp],
+                (Some(0x9A), _) | (Some(0xFF), Some(3)) => vec![ReturnCs, ReturnIp],
+                (Some(0x9C), _) => vec![Flags],
+                _ => Vec::new(),
+            }
+        }
+        MooStackDirection::Popped => match op {
+            Some(0xC2 | 0xC3) => vec![ReturnIp],
+            Some(0xCA | 0xCB) => vec![ReturnIp, ReturnCs],
+            Some(0xCF) => vec![ReturnIp, ReturnCs, Flags],
+            Some(0x9D) => vec![Flags],
+            _ => Vec::new(),
+        },
+    }
+}
+
+impl MooTest {
+    /// Decode the words this test pushed onto or popped off the stack, decoding bus states for
+    /// the given [MooCpuType]. Returns `None` if the stack pointer or stack segment base is
+    /// missing, or if the stack pointer did not move.
+    ///
+    /// Slots are two bytes wide, or four for 32-bit operands. Interrupt and exception frames are
+    /// always decoded as two-byte slots in real mode. If the stack pointer moved more than 64
+    /// bytes the frame is returned without slots.
+    pub fn stack_frame(&self, cpu_type: MooCpuType) -> Option<MooStackFrame> {
+        let family = MooCpuFamily::from(cpu_type);
+        let initial = self.initial_state();
+        let (initial_sp, wide) = stack_pointer(initial)?;
+        let (final_sp, _) = stack_pointer(&self.full_final_state())?;
+
+        // Measure the distance moved in the stack's own address size, so that a push from SP=0
+        // wraps to the top of the segment instead of appearing as a large pop.
+        let delta = match wide {
+            true => final_sp.wrapping_sub(initial_sp) as i32 as i64,
+            false => (final_sp as u16).wrapping_sub(initial_sp as u16) as i16 as i64,
+        };
+        if delta == 0 {
+            return None;
+        }
+        let direction = match delta < 0 {
+            true => MooStackDirection::Pushed,
+            false => MooStackDirection::Popped,
+        };
+        if delta.unsigned_abs() > MAX_FRAME_BYTES {
+            return Some(MooStackFrame {
+                initial_sp,
+                final_sp,
+                direction,
+                slots: Vec::new(),
+            });
+        }
+
+        let interrupt = self.exception().is_some()
+            || matches!(
+                self.opcode_id(family).and_then(|id| id.opcode().first().copied()),
+                Some(0xCC | 0xCD | 0xCE | 0xF1 | 0xCF)
+            );
+        let real_mode = matches!(self.cpu_mode(family), MooCpuMode::RealMode);
+        let width: u8 = match self.operand_size(family) {
+            MooOperandSize::ThirtyTwo if !(interrupt && real_mode) => 4,
+            _ => 2,
+        };
+
+        let offset_mask: u32 = if wide { 0xFFFF_FFFF } else { 0xFFFF };
+        let initial_ram: HashMap<u32, u8> = initial.ram().iter().map(|e| (e.address, e.value)).collect();
+        let final_ram: HashMap<u32, u8> = self.final_state().ram().iter().map(|e| (e.address, e.value)).collect();
+        let written = match direction {
+            MooStackDirection::Pushed => Some(self.state_at_cycle(cpu_type, usize::MAX)),
+            MooStackDirection::Popped => None,
+        };
+        let read_byte = |address: u32| -> Option<u8> {
+            match &written {
+                Some(written) => final_ram.get(&address).copied().or_else(|| written.read_u8(address)),
+                None => initial_ram.get(&address).copied(),
+            }
+        };
+
+        let labels = slot_labels(self, cpu_type, direction);
+        let slot_ct = (delta.unsigned_abs() / width as u64) as usize;
+        let mut slots = Vec::with_capacity(slot_ct);
+        for i in 0..slot_ct {
+            let step = width as u32 * i as u32;
+            let offset = match direction {
+                MooStackDirection::Pushed => initial_sp.wrapping_sub(step + width as u32),
+                MooStackDirection::Popped => initial_sp.wrapping_add(step),
+            } & offset_mask;
+            let address = initial.linear_address(MooSegmentRegister::SS, offset)?;
+            let value = (0..width as u32).rev().try_fold(0u32, |acc, b| {
+                let byte_offset = offset.wrapping_add(b) & offset_mask;
+                let byte_address = initial.linear_address(MooSegmentRegister::SS, byte_offset)?;
+                read_byte(byte_address).map(|byte| (acc << 8) | byte as u32)
+            });
+            slots.push(MooStackSlot {
+                address,
+                offset,
+                value,
+                width,
+                kind: labels.get(i).copied().unwrap_or(MooStackSlotKind::Data),
+            });
+        }
+
+        Some(MooStackFrame {
+            initial_sp,
+            final_sp,
+            direction,
+            slots,
+        })
+    }
+}
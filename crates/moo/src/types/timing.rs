@@ -0,0 +1,160 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::ops::AddAssign;
+
+use crate::types::{MooBusState, MooCpuType, MooCycleState, MooTState};
+
+/// A [MooCycleClass] describes what the CPU was doing during a single cycle, for a
+/// [MooTimingBreakdown].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MooCycleClass {
+    /// A cycle of a code fetch bus cycle.
+    Fetch,
+    /// A cycle with no bus cycle in progress, or of a bus cycle that transfers no data, such as
+    /// a halt or interrupt acknowledge.
+    Execute,
+    /// A cycle of a memory read or write bus cycle.
+    Memory,
+    /// A cycle of an IO read or write bus cycle.
+    Io,
+    /// A wait state inserted into any bus cycle.
+    Wait,
+}
+
+impl MooCycleClass {
+    /// All cycle classes, in the order they are reported.
+    pub const ALL: [MooCycleClass; 5] = [
+        MooCycleClass::Fetch,
+        MooCycleClass::Execute,
+        MooCycleClass::Memory,
+        MooCycleClass::Io,
+        MooCycleClass::Wait,
+    ];
+
+    /// A short name for the class, for display.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MooCycleClass::Fetch => "fetch",
+            MooCycleClass::Execute => "execute",
+            MooCycleClass::Memory => "memory",
+            MooCycleClass::Io => "io",
+            MooCycleClass::Wait => "wait",
+        }
+    }
+}
+
+/// Classify each cycle of a trace. A bus cycle begins at a cycle with ALE active and takes its
+/// class from the bus status latched on that cycle. It lasts until its T4 or an idle cycle, or
+/// until the next ALE on CPUs without a T4 state. Wait states are classed as [MooCycleClass::Wait]
+/// whatever the bus cycle.
+pub fn classify_cycles(cycles: &[MooCycleState], cpu_type: MooCpuType) -> Vec<MooCycleClass> {
+    let mut bus_cycle: Option<MooBusState> = None;
+    cycles
+        .iter()
+        .map(|cycle| {
+            if cycle.ale() {
+                bus_cycle = Some(cycle.bus_state(cpu_type));
+            }
+            let t_state = cycle.t_state();
+            if t_state == MooTState::Ti {
+                bus_cycle = None;
+            }
+            let class = match (t_state, bus_cycle) {
+                (MooTState::Tw, _) => MooCycleClass::Wait,
+                (_, Some(MooBusState::CODE)) => MooCycleClass::Fetch,
+                (_, Some(state)) if state.is_memory() => MooCycleClass::Memory,
+                (_, Some(state)) if state.is_io() => MooCycleClass::Io,
+                _ => MooCycleClass::Execute,
+            };
+            if t_state == MooTState::T4 {
+                bus_cycle = None;
+            }
+            class
+        })
+        .collect()
+}
+
+/// A [MooTimingBreakdown] counts the cycles of one or more tests by [MooCycleClass].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MooTimingBreakdown {
+    pub fetch: usize,
+    pub execute: usize,
+    pub memory: usize,
+    pub io: usize,
+    pub wait: usize,
+}
+
+impl MooTimingBreakdown {
+    /// Count the cycles of a trace by class. See [classify_cycles].
+    pub fn from_cycles(cycles: &[MooCycleState], cpu_type: MooCpuType) -> Self {
+        let mut breakdown = Self::default();
+        for class in classify_cycles(cycles, cpu_type) {
+            breakdown.add(class, 1);
+        }
+        breakdown
+    }
+
+    /// Add `n` cycles of the given class.
+    pub fn add(&mut self, class: MooCycleClass, n: usize) {
+        match class {
+            MooCycleClass::Fetch => self.fetch += n,
+            MooCycleClass::Execute => self.execute += n,
+            MooCycleClass::Memory => self.memory += n,
+            MooCycleClass::Io => self.io += n,
+            MooCycleClass::Wait => self.wait += n,
+        }
+    }
+
+    /// Returns the number of cycles of the given class.
+    pub fn count(&self, class: MooCycleClass) -> usize {
+        match class {
+            MooCycleClass::Fetch => self.fetch,
+            MooCycleClass::Execute => self.execute,
+            MooCycleClass::Memory => self.memory,
+            MooCycleClass::Io => self.io,
+            MooCycleClass::Wait => self.wait,
+        }
+    }
+
+    /// Returns the total number of cycles counted.
+    pub fn total(&self) -> usize {
+        self.fetch + self.execute + self.memory + self.io + self.wait
+    }
+
+    /// Returns the percentage of cycles of the given class, or 0.0 if no cycles were counted.
+    pub fn percent(&self, class: MooCycleClass) -> f64 {
+        match self.total() {
+            0 => 0.0,
+            total => self.count(class) as f64 * 100.0 / total as f64,
+        }
+    }
+}
+
+impl AddAssign for MooTimingBreakdown {
+    fn add_assign(&mut self, other: Self) {
+        for class in MooCycleClass::ALL {
+            self.add(class, other.count(class));
+        }
+    }
+}
@@ -0,0 +1,296 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Rendering of a test's cycles as an SVG timing diagram.
+//!
+//! [MooTest::render_svg] draws one column per cycle, with a lane each for ALE, the latched
+//! address, the bus status, the T-state and the read and write strobes. The output is a
+//! self-contained `<svg>` element that can be inlined in an HTML report or attached to a bug
+//! report as an `.svg` file.
+
+use std::fmt::Write;
+
+use crate::{
+    test::moo_test::MooTest,
+    types::{MooBusState, MooCpuType, MooCycleState},
+};
+
+/// The width of the lane label column, in pixels.
+const LABEL_WIDTH: u32 = 40;
+/// The height of the cycle number ruler above the lanes, in pixels.
+const RULER_HEIGHT: u32 = 12;
+/// A cycle number is printed above every this many cycles.
+const RULER_INTERVAL: usize = 5;
+const LANES: [&str; 5] = ["ALE", "ADDR", "BUS", "T", "R/W"];
+
+/// Options for [MooTest::render_svg].
+#[derive(Clone, Debug)]
+pub struct MooSvgOptions {
+    pub cpu_type: MooCpuType,
+    /// The width of each cycle column, in pixels.
+    pub cycle_width: u32,
+    /// The height of each lane, in pixels.
+    pub lane_height: u32,
+    /// The maximum number of cycles to draw. Longer traces are cut off and marked as truncated.
+    pub max_cycles: Option<usize>,
+    /// A title drawn above the diagram.
+    pub title: Option<String>,
+}
+
+impl MooSvgOptions {
+    pub fn new(cpu_type: MooCpuType) -> Self {
+        Self {
+            cpu_type,
+            cycle_width: 14,
+            lane_height: 14,
+            max_cycles: None,
+            title: None,
+        }
+    }
+
+    pub fn with_cycle_width(mut self, width: u32) -> Self {
+        self.cycle_width = width.max(1);
+        self
+    }
+
+    pub fn with_lane_height(mut self, height: u32) -> Self {
+        self.lane_height = height.max(1);
+        self
+    }
+
+    pub fn with_max_cycles(mut self, max_cycles: usize) -> Self {
+        self.max_cycles = Some(max_cycles);
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// The fill color of a bus status, or `None` for a passive bus.
+fn bus_state_color(state: MooBusState) -> Option<&'static str> {
+    match state {
+        MooBusState::INTA => Some("#b07cd8"),
+        MooBusState::IOR => Some("#4fb3b3"),
+        MooBusState::IOW => Some("#e09a3e"),
+        MooBusState::HALT => Some("#9a9a9a"),
+        MooBusState::CODE => Some("#5b8fd9"),
+        MooBusState::MEMR => Some("#5fb55f"),
+        MooBusState::MEMW => Some("#d95b5b"),
+        MooBusState::PASV => None,
+    }
+}
+
+/// The label and fill color of the strobe active during a cycle, if any.
+fn strobe(cycle: &MooCycleState) -> Option<(&'static str, &'static str)> {
+    if cycle.is_reading_mem() {
+        Some(("R", "#5fb55f"))
+    }
+    else if cycle.is_writing_mem() {
+        Some(("W", "#d95b5b"))
+    }
+    else if cycle.is_reading_io() {
+        Some(("r", "#4fb3b3"))
+    }
+    else if cycle.is_writing_io() {
+        Some(("w", "#e09a3e"))
+    }
+    else {
+        None
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl MooTest {
+    /// Render the test's cycles as an SVG timing diagram. Runs of cycles with the same bus status
+    /// are drawn as one labelled block, and read and write strobes are marked `R` and `W` for
+    /// memory and `r` and `w` for I/O.
+    pub fn render_svg(&self, options: &MooSvgOptions) -> String {
+        let cycles = self.cycles();
+        let shown = options.max_cycles.map_or(cycles.len(), |max| max.min(cycles.len()));
+        let truncated = shown < cycles.len();
+
+        let cw = options.cycle_width;
+        let lh = options.lane_height;
+        let title_height = if options.title.is_some() { lh } else { 0 };
+        let top = title_height + RULER_HEIGHT;
+        let width = LABEL_WIDTH + cw * shown as u32 + if truncated { cw } else { 0 };
+        let height = top + lh * LANES.len() as u32;
+        let x = |cycle: usize| LABEL_WIDTH + cw * cycle as u32;
+        let lane_y = |lane: u32| top + lh * lane;
+        // Text is vertically centered in a lane by offsetting the baseline.
+        let text_y = |lane: u32| lane_y(lane) + lh / 2 + 3;
+
+        let mut svg = String::new();
+        _ = writeln!(
+            svg,
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace" font-size="9">"##
+        );
+        _ = writeln!(svg, r##"<rect width="{width}" height="{height}" fill="#ffffff"/>"##);
+        if let Some(title) = &options.title {
+            _ = writeln!(
+                svg,
+                r##"<text x="2" y="{}" font-size="10">{}</text>"##,
+                lh - 3,
+                escape_xml(title)
+            );
+        }
+
+        // Lane labels and separators.
+        for (lane, label) in LANES.iter().enumerate() {
+            let lane = lane as u32;
+            _ = writeln!(svg, r##"<text x="2" y="{}">{}</text>"##, text_y(lane), label);
+            _ = writeln!(
+                svg,
+                r##"<line x1="0" y1="{y}" x2="{width}" y2="{y}" stroke="#dddddd"/>"##,
+                y = lane_y(lane)
+            );
+        }
+
+        // The cycle number ruler.
+        for cycle in (0..shown).step_by(RULER_INTERVAL) {
+            _ = writeln!(
+                svg,
+                r##"<text x="{}" y="{}" fill="#888888">{}</text>"##,
+                x(cycle) + 1,
+                top - 3,
+                cycle
+            );
+            _ = writeln!(
+                svg,
+                r##"<line x1="{x}" y1="{}" x2="{x}" y2="{height}" stroke="#eeeeee"/>"##,
+                top,
+                x = x(cycle)
+            );
+        }
+
+        let mut bus_run: Option<(usize, MooBusState)> = None;
+        let close_run = |svg: &mut String, run: Option<(usize, MooBusState)>, end: usize| {
+            let Some((start, state)) = run
+            else {
+                return;
+            };
+            let Some(color) = bus_state_color(state)
+            else {
+                return;
+            };
+            let run_width = cw * (end - start) as u32;
+            _ = writeln!(
+                svg,
+                r##"<rect x="{}" y="{}" width="{}" height="{}" fill="{}" stroke="#ffffff"/>"##,
+                x(start),
+                lane_y(2) + 1,
+                run_width,
+                lh - 2,
+                color
+            );
+            // Only label blocks wide enough to hold the text.
+            if run_width >= 24 {
+                _ = writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}" fill="#ffffff">{}</text>"##,
+                    x(start) + 2,
+                    text_y(2),
+                    state.to_string().trim_end()
+                );
+            }
+        };
+
+        for (i, cycle) in cycles[..shown].iter().enumerate() {
+            if cycle.ale() {
+                _ = writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#333333"/>"##,
+                    x(i) + 1,
+                    lane_y(0) + 2,
+                    cw.saturating_sub(2).max(1),
+                    lh - 4
+                );
+                _ = writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}">{:05X}</text>"##,
+                    x(i) + 1,
+                    text_y(1),
+                    cycle.address_bus
+                );
+            }
+
+            let state = cycle.bus_state(options.cpu_type);
+            match bus_run {
+                Some((_, run_state)) if run_state == state => {}
+                _ => {
+                    close_run(&mut svg, bus_run, i);
+                    bus_run = Some((i, state));
+                }
+            }
+
+            let t_str = options.cpu_type.tstate_to_string(cycle.t_state());
+            _ = writeln!(
+                svg,
+                r##"<text x="{}" y="{}" fill="#555555">{}</text>"##,
+                x(i) + 1,
+                text_y(3),
+                t_str.trim_start_matches('T')
+            );
+
+            if let Some((label, color)) = strobe(cycle) {
+                _ = writeln!(
+                    svg,
+                    r##"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"##,
+                    x(i) + 1,
+                    lane_y(4) + 2,
+                    cw.saturating_sub(2).max(1),
+                    lh - 4,
+                    color
+                );
+                _ = writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}" fill="#ffffff">{}</text>"##,
+                    x(i) + 2,
+                    text_y(4),
+                    label
+                );
+            }
+        }
+        close_run(&mut svg, bus_run, shown);
+
+        if truncated {
+            _ = writeln!(
+                svg,
+                r##"<text x="{}" y="{}" fill="#888888">…</text>"##,
+                x(shown) + 2,
+                text_y(2)
+            );
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
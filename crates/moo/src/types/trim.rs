@@ -0,0 +1,73 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fmt::Display;
+
+use crate::prelude::MooCycleState;
+
+/// A [MooTrimPolicy] selects the cycles [MooTest::trim](crate::prelude::MooTest::trim) removes from
+/// the ends of a test's cycle trace.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MooTrimPolicy {
+    /// Remove the cycles before the first bus transaction begins and after the last one finishes
+    /// transferring data. Anchored cycles are kept.
+    Idle,
+    /// Remove the cycles outside the [MooAnchorKind::FirstExecution](crate::prelude::MooAnchorKind)
+    /// and [MooAnchorKind::LastExecution](crate::prelude::MooAnchorKind) anchors.
+    Anchors,
+    /// Remove a fixed number of cycles from each end of the trace.
+    Count { leading: usize, trailing: usize },
+}
+
+/// The cycles removed by [MooTest::trim](crate::prelude::MooTest::trim), for recording alongside
+/// the edited file.
+#[derive(Clone, Debug, Default)]
+pub struct MooTrimmedCycles {
+    /// The cycles removed from the start of the trace, in order.
+    pub leading:  Vec<MooCycleState>,
+    /// The cycles removed from the end of the trace, in order.
+    pub trailing: Vec<MooCycleState>,
+}
+
+impl MooTrimmedCycles {
+    /// Returns the total number of cycles removed.
+    pub fn len(&self) -> usize {
+        self.leading.len() + self.trailing.len()
+    }
+
+    /// Returns true if no cycles were removed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Display for MooTrimmedCycles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "trimmed {} leading and {} trailing cycles",
+            self.leading.len(),
+            self.trailing.len()
+        )
+    }
+}
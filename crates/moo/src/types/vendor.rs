@@ -0,0 +1,111 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Support for vendor and experimental chunks.
+//!
+//! Third parties may embed their own data in a `MOO` file using chunk IDs from the vendor range
+//! (see [MooChunkType::is_vendor_id]). Vendor chunks are always preserved verbatim when a file is
+//! read and written back, whether or not anything knows how to decode them. A
+//! [MooVendorChunkRegistry] maps vendor chunk IDs to user-supplied decode callbacks.
+
+use std::{any::Any, collections::HashMap, fmt::Debug};
+
+use crate::types::{chunks::MooChunkType, errors::MooError};
+
+/// A [MooVendorChunk] holds the raw ID and payload of a vendor or experimental chunk.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MooVendorChunk {
+    /// The four-byte chunk ID.
+    pub id:   [u8; 4],
+    /// The raw chunk payload.
+    pub data: Vec<u8>,
+}
+
+impl MooVendorChunk {
+    /// Create a new [MooVendorChunk].
+    ///
+    /// # Returns
+    /// A [MooError::WriteError] if `id` is not in the vendor chunk ID range.
+    pub fn new(id: [u8; 4], data: Vec<u8>) -> Result<Self, MooError> {
+        if !MooChunkType::is_vendor_id(&id) {
+            return Err(MooError::WriteError(format!(
+                "'{}' is not a valid vendor chunk ID",
+                String::from_utf8_lossy(&id)
+            )));
+        }
+        Ok(Self { id, data })
+    }
+
+    /// Returns the chunk ID as a string.
+    pub fn id_str(&self) -> String {
+        String::from_utf8_lossy(&self.id).to_string()
+    }
+}
+
+/// A decode callback for a vendor chunk. Receives the raw chunk payload and returns the decoded
+/// value, boxed as [Any] so that it may be downcast by the caller.
+pub type MooVendorDecodeFn = Box<dyn Fn(&[u8]) -> Result<Box<dyn Any + Send + Sync>, MooError> + Send + Sync>;
+
+/// A [MooVendorChunkRegistry] maps vendor chunk IDs to decode callbacks.
+#[derive(Default)]
+pub struct MooVendorChunkRegistry {
+    decoders: HashMap<[u8; 4], MooVendorDecodeFn>,
+}
+
+impl MooVendorChunkRegistry {
+    /// Create a new, empty [MooVendorChunkRegistry].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a decode callback for the vendor chunk `id`, replacing any existing callback.
+    ///
+    /// # Returns
+    /// A [MooError::ParseError] if `id` is not in the vendor chunk ID range.
+    pub fn register<F>(&mut self, id: [u8; 4], decode: F) -> Result<(), MooError>
+    where
+        F: Fn(&[u8]) -> Result<Box<dyn Any + Send + Sync>, MooError> + Send + Sync + 'static,
+    {
+        if !MooChunkType::is_vendor_id(&id) {
+            return Err(MooError::ParseError(format!(
+                "'{}' is not a valid vendor chunk ID",
+                String::from_utf8_lossy(&id)
+            )));
+        }
+        self.decoders.insert(id, Box::new(decode));
+        Ok(())
+    }
+
+    /// Returns true if a decode callback is registered for `id`.
+    pub fn is_registered(&self, id: &[u8; 4]) -> bool {
+        self.decoders.contains_key(id)
+    }
+
+    /// Decode a [MooVendorChunk] with its registered callback.
+    ///
+    /// # Returns
+    /// `None` if no callback is registered for the chunk's ID, otherwise the result of the callback.
+    pub fn decode(&self, chunk: &MooVendorChunk) -> Option<Result<Box<dyn Any + Send + Sync>, MooError>> {
+        self.decoders.get(&chunk.id).map(|decode| decode(&chunk.data))
+    }
+}
@@ -0,0 +1,106 @@
+use moo::{prelude::*, types::MooCompareOptions};
+use std::{collections::HashMap, io::Cursor, path::Path};
+
+#[derive(Default)]
+struct Cpu {
+    cr0: u32,
+    cr3: u32,
+    eax: u32,
+    ebx: u32,
+    ecx: u32,
+    edx: u32,
+    esi: u32,
+    edi: u32,
+    ebp: u32,
+    esp: u32,
+    cs: u16,
+    ds: u16,
+    es: u16,
+    fs: u16,
+    gs: u16,
+    ss: u16,
+    eip: u32,
+    eflags: u32,
+    memory: HashMap<u32, u8>,
+}
+
+moo::impl_moo_registers!(Cpu {
+    CR0 => cr0,
+    CR3 => cr3,
+    EAX => eax,
+    EBX => ebx,
+    ECX => ecx,
+    EDX => edx,
+    ESI => esi,
+    EDI => edi,
+    EBP => ebp,
+    ESP => esp,
+    CS => cs,
+    DS => ds,
+    ES => es,
+    FS => fs,
+    GS => gs,
+    SS => ss,
+    EIP => eip,
+    EFLAGS => eflags,
+});
+
+impl FromMooState for Cpu {
+    fn write_memory(&mut self, address: u32, value: u8) {
+        self.memory.insert(address, value);
+    }
+}
+
+impl IntoMooState for Cpu {
+    fn read_memory(&self, address: u32) -> Option<u8> {
+        self.memory.get(&address).copied()
+    }
+}
+
+#[test]
+pub fn test_state_adapter() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let test = &test_file.tests()[0];
+
+    let mut cpu = Cpu::default();
+    cpu.apply_moo_state(test.initial_state());
+    assert_eq!(&cpu.to_moo_registers(true), test.initial_state().regs());
+
+    // "Execute" the test by applying the final register delta and memory.
+    cpu.apply_moo_state(test.final_state());
+    let options = MooCompareOptions::new();
+    assert!(cpu.compare_with_test(test, &options).is_empty());
+
+    cpu.eax = cpu.eax.wrapping_add(1);
+    assert_eq!(cpu.compare_with_test(test, &options).len(), 1);
+}
+
+#[test]
+pub fn test_visit_registers() {
+    let regs = MooRegisters::from_named_values(false, [("ax", 0x1234), ("ip", 0x100)]).unwrap();
+
+    let mut all = Vec::new();
+    regs.visit(|reg, value| all.push((reg, value)));
+    assert_eq!(all.len(), MooRegisters::REGISTERS16.len());
+    assert_eq!(all[0], (MooRegister::AX, 0x1234));
+
+    let mut set = Vec::new();
+    regs.visit_set(|reg, value| set.push((reg, value)));
+    assert_eq!(set, vec![(MooRegister::AX, 0x1234), (MooRegister::IP, 0x100)]);
+    assert_eq!(regs.get(MooRegister::IP), Some(0x100));
+    assert_eq!(regs.get(MooRegister::EAX), None);
+
+    // Every register in a test's diff is present in its final state.
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let test = &test_file.tests()[0];
+    let mut final_set = Vec::new();
+    test.final_state().regs().visit_set(|reg, _| final_set.push(reg));
+    for diff in test.diff_regs() {
+        assert!(final_set.contains(&diff.register));
+        assert_ne!(diff.initial, diff.r#final);
+    }
+}
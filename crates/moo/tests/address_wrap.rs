@@ -0,0 +1,75 @@
+use moo::{
+    prelude::*,
+    types::{MooA20Policy, MooAddressWrap, MooTestState},
+};
+use std::io::Cursor;
+
+fn high_memory_test() -> MooTest {
+    // A single 286 memory read bus cycle addressing the high memory area.
+    let cycles = [
+        MooCycleState {
+            pins0: MooCycleState::PIN_ALE,
+            address_bus: 0x10_0010,
+            bus_state: 0b0101,
+            ..Default::default()
+        },
+        MooCycleState {
+            memory_status: MooCycleState::MRDC_BIT,
+            ..Default::default()
+        },
+    ];
+    MooTest::new(
+        "mov al,[0010h]".to_string(),
+        None,
+        &[0xA0, 0x10, 0x00],
+        MooTestState::default(),
+        MooTestState::default(),
+        &cycles,
+        None,
+        None,
+    )
+}
+
+#[test]
+pub fn test_address_wraps_high_memory() {
+    let test = high_memory_test();
+    assert_eq!(
+        test.address_wraps(MooCpuType::Intel80286),
+        vec![MooAddressWrap::HighMemoryAccess {
+            cycle:   0,
+            address: 0x10_0010,
+        }]
+    );
+
+    let mut test_file = MooTestFile::new(1, 1, MooCpuType::Intel80286, 1);
+    test_file.add_test(high_memory_test());
+    assert_eq!(test_file.tests_exercising_wraparound().count(), 1);
+}
+
+#[test]
+pub fn test_a20_policy_round_trip() {
+    let mut test_file = MooTestFile::new(1, 1, MooCpuType::Intel80286, 1);
+    test_file.set_metadata(
+        MooFileMetadata::new(1, 0, MooCpuType::Intel80286, 0xA0, None)
+            .with_test_count(1)
+            .with_a20_policy(MooA20Policy::Enabled),
+    );
+    test_file.add_test(high_memory_test());
+
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, false).expect("Failed to write test file");
+    out.set_position(0);
+    let read_back = MooTestFile::read(&mut out).expect("Failed to read test file");
+    assert_eq!(read_back.metadata().unwrap().a20_policy(), Some(MooA20Policy::Enabled));
+
+    // A value this library doesn't know still parses, and reads back as no policy.
+    let mut metadata = read_back.metadata().unwrap().clone();
+    metadata.reserved[0] = 0x7F;
+    test_file.set_metadata(metadata);
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, false).expect("Failed to write test file");
+    out.set_position(0);
+    let read_back = MooTestFile::read(&mut out).expect("Failed to read test file");
+    assert_eq!(read_back.metadata().unwrap().reserved[0], 0x7F);
+    assert_eq!(read_back.metadata().unwrap().a20_policy(), None);
+}
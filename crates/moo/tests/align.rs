@@ -0,0 +1,48 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn with_cycles(test: &MooTest, cycles: &[MooCycleState]) -> MooTest {
+    MooTest::new(
+        test.name().to_string(),
+        None,
+        test.bytes(),
+        test.initial_state().clone(),
+        test.final_state().clone(),
+        cycles,
+        None,
+        None,
+    )
+}
+
+#[test]
+pub fn test_compare_aligned() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let test = &test_file.tests()[0];
+    let cycles = test.cycles().to_vec();
+    let transactions: Vec<_> = cycles.iter().copied().transactions().collect();
+    assert!(transactions.len() > 2);
+
+    // Repeat a non-ALE cycle near the start, delaying every later transaction by a cycle.
+    let idle = (1..cycles.len()).find(|&i| !cycles[i].ale()).unwrap();
+    let mut delayed = cycles.clone();
+    delayed.insert(idle, cycles[idle]);
+    let delayed = with_cycles(test, &delayed);
+    assert!(test.compare(&delayed, false).len() > 1);
+    assert!(test.compare_aligned(&delayed, DEFAULT_ALIGN_BAND).is_empty());
+
+    // Change the address of the second transaction.
+    let mut changed = cycles.clone();
+    changed[transactions[1].start].address_bus ^= 0x10;
+    let diffs = test.compare_aligned(&with_cycles(test, &changed), DEFAULT_ALIGN_BAND);
+    assert_eq!(diffs.len(), 1);
+    assert!(matches!(diffs[0], MooAlignedDiff::Substituted(a, b) if a.address ^ b.address == 0x10));
+
+    // Drop the last transaction.
+    let last = transactions.last().unwrap();
+    let truncated = with_cycles(test, &cycles[..last.start]);
+    let diffs = test.compare_aligned(&truncated, DEFAULT_ALIGN_BAND);
+    assert_eq!(diffs.len(), 1);
+    assert!(matches!(diffs[0], MooAlignedDiff::Removed(t) if t.start == last.start));
+}
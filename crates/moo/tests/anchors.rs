@@ -0,0 +1,42 @@
+use moo::prelude::*;
+use std::{
+    io::{BufReader, Cursor},
+    path::Path,
+};
+
+#[test]
+pub fn test_anchors_round_trip() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let test = &mut test_file.tests_mut()[0];
+    let hash = test.hash_string();
+    let cycle_ct = test.cycles().len();
+    assert!(cycle_ct > 4);
+    test.set_anchor(MooAnchorKind::LastExecution, cycle_ct - 2);
+    test.set_anchor(MooAnchorKind::FirstExecution, 1);
+    test.set_anchor(MooAnchorKind::FirstExecution, 2);
+    assert_eq!(test.anchor(MooAnchorKind::FirstExecution), Some(2));
+    assert_eq!(test.anchors()[0].kind, MooAnchorKind::FirstExecution);
+    assert_eq!(test.execution_cycles().len(), cycle_ct - 3);
+
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, false).expect("Failed to write test file");
+    buf.set_position(0);
+    let read_back = MooTestFile::read(&mut buf).expect("Failed to read test file");
+
+    // Anchors are not part of the test hash.
+    let test = &read_back.tests()[0];
+    assert_eq!(test.hash_string(), hash);
+    assert_eq!(test.anchors(), test_file.tests()[0].anchors());
+    assert_eq!(test.anchor(MooAnchorKind::ExceptionDispatch), None);
+    assert!(read_back.tests()[1].anchors().is_empty());
+
+    let mut text = Vec::new();
+    read_back
+        .dump_text(&mut text, &MooDumpOptions::default())
+        .expect("Failed to dump test file");
+    let parsed = MooTestFile::parse_text(&mut BufReader::new(text.as_slice())).expect("Failed to parse dump");
+    assert_eq!(parsed.tests()[0].anchors(), test.anchors());
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use moo::{prelude::*, types::MooCpuType};
+use std::io::Cursor;
+
+fn write(test_file: &MooTestFile) -> Vec<u8> {
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).expect("Failed to write test file");
+    buf.into_inner()
+}
+
+#[test]
+pub fn test_arbitrary_round_trip() {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut data = vec![0u8; 4096];
+
+    for i in 0..500 {
+        for byte in data.iter_mut() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *byte = state as u8;
+        }
+        let mut u = Unstructured::new(&data);
+        let Ok(test) = MooTest::arbitrary(&mut u)
+        else {
+            continue;
+        };
+
+        let cpu_type = match test.initial_state().regs() {
+            MooRegisters::Sixteen(_) => MooCpuType::Intel80286,
+            MooRegisters::ThirtyTwo(_) => MooCpuType::Intel80386Ex,
+        };
+        let mut test_file = MooTestFile::new(1, 0, cpu_type, 1);
+        test_file.add_test(test);
+
+        let bytes = write(&test_file);
+        let read_back = MooTestFile::read(&mut Cursor::new(bytes.clone()))
+            .unwrap_or_else(|e| panic!("iteration {}: failed to read test file: {}", i, e));
+        assert_eq!(write(&read_back), bytes, "iteration {}", i);
+    }
+}
@@ -0,0 +1,72 @@
+use moo::{test_file::MooTestFile, types::errors::MooError};
+use std::{io::Cursor, path::Path};
+
+fn read_test_file() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+fn write_with_crc(test_file: &mut MooTestFile) -> Vec<u8> {
+    test_file.set_chunk_crc(true);
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, true).expect("Failed to write output file");
+    out.into_inner()
+}
+
+#[test]
+pub fn test_chunk_crc_round_trip() {
+    let mut test_file = read_test_file();
+    let bytes = write_with_crc(&mut test_file);
+
+    let crc_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse CRC file");
+    assert!(crc_file.chunk_crc());
+    assert_eq!(crc_file.test_ct(), test_file.test_ct());
+    for (a, b) in crc_file.tests().iter().zip(test_file.tests()) {
+        assert_eq!(a.hash_string(), b.hash_string());
+    }
+}
+
+#[test]
+pub fn test_chunk_crc_mismatch_reports_test() {
+    let mut test_file = read_test_file();
+    let mut bytes = write_with_crc(&mut test_file);
+
+    // Flip a bit inside the last test's HASH payload, just before its CRC subchunk.
+    let flip_pos = bytes.len() - 22;
+    bytes[flip_pos] ^= 0x01;
+
+    let err = match MooTestFile::read(&mut Cursor::new(bytes)) {
+        Ok(_) => panic!("Corrupted file should fail to parse"),
+        Err(e) => e,
+    };
+
+    match err {
+        binrw::Error::Custom { err, .. } => {
+            let moo_err = err.downcast_ref::<MooError>().expect("Expected a MooError");
+            assert!(matches!(moo_err, MooError::ChecksumError(_)));
+            let expected = format!("test {}", test_file.test_ct() - 1);
+            assert!(moo_err.to_string().contains(&expected), "{}", moo_err);
+        }
+        other => panic!("Unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+pub fn test_chunk_crc_skipped_by_unaware_reader() {
+    let mut test_file = read_test_file();
+    // Fill in META's last trailing field, so that its CRC subchunk reads as unknown later data.
+    test_file.metadata_mut().unwrap().set_opcode_bytes(Some(&[0x00]));
+    let mut bytes = write_with_crc(&mut test_file);
+
+    // Clear the CRC flag, as a reader that predates it would ignore it. The CRC subchunks are
+    // counted in their chunks' sizes, so the file still parses.
+    bytes[10] &= !0x01;
+    let plain_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse CRC file");
+    assert!(!plain_file.chunk_crc());
+    assert_eq!(plain_file.test_ct(), test_file.test_ct());
+    assert_eq!(
+        plain_file.metadata().unwrap().mnemonic(),
+        test_file.metadata().unwrap().mnemonic()
+    );
+}
@@ -0,0 +1,104 @@
+use moo::{
+    prelude::*,
+    types::{MooCompareOptions, MooComparison, MooCycleState, MooRamEntry},
+};
+use std::{io::Cursor, path::Path};
+
+fn read_test_file() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+#[test]
+pub fn test_compare_details() {
+    let expected = read_test_file();
+    let mut actual = read_test_file();
+    let test = &expected.tests()[0];
+    assert!(test.compare(&actual.tests()[0], false).is_empty());
+
+    let MooRegisters::ThirtyTwo(regs) = &mut actual.tests_mut()[0].final_state_mut().regs
+    else {
+        panic!("Expected 32-bit registers");
+    };
+    let old_eax = regs.eax;
+    regs.eax = old_eax.wrapping_add(1);
+
+    let differences = test.compare(&actual.tests()[0], false);
+    assert_eq!(
+        differences,
+        vec![MooComparison::RegisterMismatch(
+            MooRegister::EAX,
+            old_eax,
+            old_eax.wrapping_add(1)
+        )]
+    );
+    assert!(differences[0].to_string().starts_with("register EAX:"));
+
+    // Cycle mismatches report the index of the cycle that differed.
+    let mut cycles = test.cycles().to_vec();
+    let cycle = cycles
+        .iter()
+        .position(|c| c.pins0 & MooCycleState::PIN_ALE != 0)
+        .expect("Expected a cycle with ALE asserted");
+    let address = cycles[cycle].address_bus;
+    cycles[cycle].address_bus ^= 1;
+    let other = MooTest::new(
+        test.name().to_string(),
+        None,
+        test.bytes(),
+        test.initial_state().clone(),
+        test.final_state().clone(),
+        &cycles,
+        None,
+        None,
+    );
+    assert_eq!(
+        test.compare(&other, false),
+        vec![MooComparison::CycleAddressMismatch(cycle, address, address ^ 1)]
+    );
+
+    assert_eq!(MooRegister::from_name("eflags"), Some(MooRegister::EFLAGS));
+    assert_eq!(MooRegister::from_name("IP"), Some(MooRegister::IP));
+    assert_eq!(MooRegister::from_name("xyz"), None);
+}
+
+#[test]
+pub fn test_compare_final_state() {
+    let test_file = read_test_file();
+    let test = &test_file.tests()[0];
+    let options = MooCompareOptions::new();
+
+    // A full register set and the expected memory compare equal.
+    let mut regs = test.final_state().regs().rehydrate(test.initial_state().regs());
+    let ram = test.final_state().ram().to_vec();
+    assert!(test
+        .compare_final_state(&regs, &ram, Some(test.cycles()), &options)
+        .is_empty());
+
+    let MooRegisters::ThirtyTwo(regs32) = &mut regs
+    else {
+        panic!("Expected 32-bit registers");
+    };
+    let eflags = regs32.eflags;
+    regs32.eflags ^= MooRegisters32::FLAG_AUX_CARRY;
+    let differences = test.compare_final_state(&regs, &ram, None, &options);
+    assert_eq!(
+        differences,
+        vec![MooComparison::RegisterMismatch(
+            MooRegister::EFLAGS,
+            eflags,
+            eflags ^ MooRegisters32::FLAG_AUX_CARRY
+        )]
+    );
+    let options = options.with_ignore_flags(MooRegisters32::FLAG_AUX_CARRY);
+    assert!(test.compare_final_state(&regs, &ram, None, &options).is_empty());
+
+    if let Some(entry) = ram.first() {
+        let differences = test.compare_final_state(&regs, &ram[1..], None, &options);
+        assert_eq!(
+            differences,
+            vec![MooComparison::MemoryValueMismatch(*entry, MooRamEntry::default())]
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use moo::{prelude::*, types::errors::MooError};
+use std::{io::Cursor, path::Path};
+
+fn load_bytes() -> Vec<u8> {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    std::fs::read(input_file).expect("Failed to read input file")
+}
+
+fn find(bytes: &[u8], id: &[u8; 4]) -> usize {
+    bytes.windows(4).position(|w| w == id).expect("chunk not found")
+}
+
+#[test]
+pub fn test_corrupt_input_is_an_error() {
+    let bytes = load_bytes();
+    let test_chunk = find(&bytes, b"TEST");
+    let meta_chunk = find(&bytes, b"META");
+
+    let corruptions: [(&str, usize, &[u8]); 5] = [
+        ("major version", 8, &[0xFF]),
+        ("header test count", 12, &[0xFF; 4]),
+        ("TEST chunk size", test_chunk + 4, &[0x02, 0, 0, 0]),
+        ("TEST chunk size past end", test_chunk + 4, &[0xFF; 4]),
+        ("META chunk size past end", meta_chunk + 4, &[0xFF; 4]),
+    ];
+    for (what, offset, patch) in corruptions {
+        let mut data = bytes.clone();
+        data[offset..offset + patch.len()].copy_from_slice(patch);
+        assert!(
+            MooTestFile::read(&mut Cursor::new(data)).is_err(),
+            "corrupt {} was accepted",
+            what
+        );
+    }
+}
+
+fn limit_exceeded(bytes: &[u8], options: &MooReadOptions) -> MooReadLimit {
+    match MooTestFile::read_with_options(&mut Cursor::new(bytes), options) {
+        Err(binrw::Error::Custom { err, .. }) => match err.downcast_ref::<MooError>() {
+            Some(MooError::LimitExceeded { limit, .. }) => *limit,
+            other => panic!("Unexpected error: {:?}", other),
+        },
+        Err(other) => panic!("Unexpected error: {:?}", other),
+        Ok(_) => panic!("Read limit was not enforced"),
+    }
+}
+
+#[test]
+pub fn test_read_limits() {
+    let bytes = load_bytes();
+    assert!(MooTestFile::read_with_options(&mut Cursor::new(&bytes), &MooReadOptions::new()).is_ok());
+
+    // A huge header test count is rejected before anything is allocated for it.
+    let mut data = bytes.clone();
+    data[12..16].copy_from_slice(&[0xFF; 4]);
+    assert_eq!(limit_exceeded(&data, &MooReadOptions::new()), MooReadLimit::TestCount);
+
+    let options = MooReadOptions::new().with_max_tests(1);
+    assert_eq!(limit_exceeded(&bytes, &options), MooReadLimit::TestCount);
+    let options = MooReadOptions::new().with_max_chunk_size(16);
+    assert_eq!(limit_exceeded(&bytes, &options), MooReadLimit::ChunkSize);
+    let options = MooReadOptions::new().with_max_cycles(10);
+    assert_eq!(limit_exceeded(&bytes, &options), MooReadLimit::CycleCount);
+}
@@ -0,0 +1,67 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn read_file(name: &str) -> MooTestFile {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data").join(name);
+    MooTestFile::read(&mut Cursor::new(std::fs::read(path).unwrap())).unwrap()
+}
+
+#[test]
+pub fn test_cycle_cursor() {
+    let test_file = read_file("golden/8086.MOO");
+    let cpu_type = test_file.cpu_type();
+    let test = &test_file.tests()[0];
+    let mut cursor = test.cycle_cursor(cpu_type);
+
+    assert_eq!(cursor.position(), 0);
+    assert!(cursor.current().is_none() && cursor.transaction().is_none());
+    assert!(cursor.prev().is_none());
+
+    // The first cycle latches the first code fetch.
+    let first = cursor.step().unwrap();
+    assert!(first.ale());
+    assert_eq!(cursor.address_latch(), Some(first.address_bus));
+    assert_eq!(cursor.transaction().unwrap().start, 0);
+
+    let end = cursor.seek(usize::MAX);
+    assert_eq!(end, test.cycles().len());
+    assert!(cursor.is_at_end() && cursor.step().is_none());
+    assert_eq!(cursor.state().writes(), test.state_at_cycle(cpu_type, end).writes());
+
+    // Stepping back past the write undoes it.
+    let write_cycle = cursor.state().writes()[0].cycle;
+    cursor.seek(write_cycle);
+    assert!(cursor.state().writes().is_empty());
+    assert!(cursor.step().is_some());
+    assert!(!cursor.state().writes().is_empty());
+    assert_eq!(
+        cursor.prev().unwrap().address_bus,
+        test.cycles()[write_cycle - 1].address_bus
+    );
+    assert!(cursor.state().writes().is_empty());
+
+    // Transactions step from ALE to ALE.
+    cursor.seek(0);
+    let mut starts = Vec::new();
+    while let Some(transaction) = cursor.step_transaction() {
+        starts.push(transaction.start);
+    }
+    let expected: Vec<usize> = cursor.transactions().iter().map(|t| t.start).collect();
+    assert_eq!(starts, expected);
+}
+
+#[test]
+pub fn test_cycle_cursor_seek_matches_replay() {
+    let test_file = read_file("00.MOO");
+    let cpu_type = test_file.cpu_type();
+    let test = test_file.tests().iter().max_by_key(|t| t.cycles().len()).unwrap();
+    assert!(test.cycles().len() > 64);
+
+    let mut cursor = test.cycle_cursor(cpu_type);
+    let len = cursor.len();
+    for position in (0..=len).rev().chain([len / 2, 1, len]) {
+        cursor.seek(position);
+        let expected = test.state_at_cycle(cpu_type, position);
+        assert_eq!(format!("{:?}", cursor.state()), format!("{:?}", expected));
+    }
+}
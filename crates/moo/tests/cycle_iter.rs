@@ -0,0 +1,75 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_transactions_match_ale_cycles() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    for test in test_file.tests() {
+        let ale_ct = test.cycles_iter().ale_cycles().count();
+        let transactions: Vec<_> = test.cycles_iter().transactions().collect();
+        assert_eq!(transactions.len(), ale_ct);
+
+        // Transactions are contiguous from the first ALE cycle to the end of the test.
+        if let Some(first) = transactions.first() {
+            let covered: usize = transactions.iter().map(|t| t.len).sum();
+            assert_eq!(first.start + covered, test.cycles().len());
+        }
+
+        let mem_read_ct = test.cycles_iter().mem_reads().count();
+        let io_read_ct = test.cycles_iter().io_reads().count();
+        assert_eq!(test.cycles_iter().reads().count(), mem_read_ct + io_read_ct);
+    }
+}
+
+#[test]
+pub fn test_transaction_data_width() {
+    use moo::types::MooDataWidth;
+
+    let transaction = |address: u32, bhe: bool| {
+        let pins0 = MooCycleState::PIN_ALE | if bhe { 0 } else { MooCycleState::PIN_BHE };
+        let cycle = MooCycleState {
+            pins0,
+            address_bus: address,
+            ..Default::default()
+        };
+        std::iter::once(cycle).transactions().next().unwrap()
+    };
+
+    let cpu = MooCpuType::Intel80286;
+    assert_eq!(transaction(0x1000, true).data_width(cpu), MooDataWidth::Sixteen);
+    assert_eq!(transaction(0x1000, false).data_width(cpu), MooDataWidth::EightLow);
+    assert_eq!(transaction(0x1001, true).data_width(cpu), MooDataWidth::EightHigh);
+    assert_eq!(transaction(0x1001, false).data_width(cpu), MooDataWidth::Invalid);
+    assert_eq!(
+        transaction(0x1001, false).data_width(MooCpuType::Intel8088),
+        MooDataWidth::EightLow
+    );
+}
+
+#[test]
+pub fn test_bus_state_histogram() {
+    assert!(MooBusState::CODE.is_read() && MooBusState::CODE.is_memory());
+    assert!(MooBusState::IOW.is_write() && MooBusState::IOW.is_io());
+    assert!(!MooBusState::INTA.is_read() && !MooBusState::PASV.is_memory());
+
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let cpu_type = test_file.cpu_type();
+
+    for test in test_file.tests() {
+        let histogram = test.bus_state_histogram(cpu_type);
+        assert_eq!(histogram.total(), test.cycles_iter().transactions().count());
+        assert_eq!(histogram.iter().map(|(_, n)| n).sum::<usize>(), histogram.total());
+        assert_eq!(
+            histogram.count_where(MooBusState::is_memory),
+            histogram.count(MooBusState::CODE)
+                + histogram.count(MooBusState::MEMR)
+                + histogram.count(MooBusState::MEMW)
+        );
+        assert!(histogram.count(MooBusState::CODE) > 0);
+    }
+}
@@ -0,0 +1,44 @@
+use moo::{
+    prelude::*,
+    types::{cycle_pattern::MooCyclePattern, MooBusState},
+};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_cycle_pattern_parse() {
+    let pattern: MooCyclePattern = "MEMR@0xB8000 then iow@980-0x3DF within 10".parse().unwrap();
+    let steps = pattern.steps();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[0].bus_state, MooBusState::MEMR);
+    assert_eq!(steps[0].address, Some(0xB8000..=0xB8000));
+    assert_eq!(steps[0].within, None);
+    assert_eq!(steps[1].bus_state, MooBusState::IOW);
+    assert_eq!(steps[1].address, Some(0x3D4..=0x3DF));
+    assert_eq!(steps[1].within, Some(10));
+
+    assert!("".parse::<MooCyclePattern>().is_err());
+    assert!("MEMR within 5".parse::<MooCyclePattern>().is_err());
+    assert!("MEMR then".parse::<MooCyclePattern>().is_err());
+    assert!("FOO@0x10".parse::<MooCyclePattern>().is_err());
+}
+
+#[test]
+pub fn test_cycle_pattern_find() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let cpu_type = test_file.cpu_type();
+
+    let code: MooCyclePattern = "CODE".parse().unwrap();
+    let code_then_code: MooCyclePattern = "CODE then CODE within 0".parse().unwrap();
+    for test in test_file.tests() {
+        let first_fetch = test
+            .cycles_iter()
+            .transactions()
+            .find(|t| t.bus_state(cpu_type) == MooBusState::CODE)
+            .map(|t| vec![t.start]);
+        assert_eq!(code.find(test, cpu_type), first_fetch);
+        // Two transactions can never start on the same cycle.
+        assert!(!code_then_code.is_match(test, cpu_type));
+    }
+}
@@ -0,0 +1,210 @@
+use moo::{
+    prelude::*,
+    registers::{
+        descriptor_table::{MooDescriptor, MooDescriptorField, MooSystemType, MooTableRegister},
+        descriptors_32::{MooDescriptor32, MooDescriptors32},
+        MooDescriptors,
+        MooRegisters32,
+        MooSegmentRegister,
+    },
+    types::{MooCpuFamily, MooCpuMode, MooOperandSize, MooRamEntry, MooSegmentSize, MooTestState},
+};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_protected_mode_segment_size() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let cpu_type = test_file.cpu_type();
+
+    let test = &test_file.tests()[0];
+    assert!(matches!(test.cpu_mode(cpu_type), MooCpuMode::RealMode));
+    assert!(matches!(test.segment_size(cpu_type), MooSegmentSize::Sixteen));
+
+    // Enter protected mode with a 32-bit code segment.
+    let descriptors = MooDescriptors32 {
+        cs: MooDescriptor32 {
+            access: 0x00C0_9B00,
+            base:   0,
+            limit:  0xFFFF_FFFF,
+        },
+        ..Default::default()
+    };
+    let state = test_file.tests_mut()[0].initial_state_mut();
+    if let MooRegisters::ThirtyTwo(regs) = state.regs_mut() {
+        regs.cr0 |= 1;
+    }
+    state.descriptors = Some(MooDescriptors::ThirtyTwo(descriptors.clone()));
+
+    let test = &test_file.tests()[0];
+    assert!(matches!(test.cpu_mode(cpu_type), MooCpuMode::ProtectedMode));
+    assert!(matches!(test.segment_size(cpu_type), MooSegmentSize::ThirtyTwo));
+    assert!(matches!(test.operand_size(cpu_type), MooOperandSize::ThirtyTwo));
+
+    // Descriptors survive a binary and a text round trip.
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, true).expect("Failed to write output file");
+    let read_back = MooTestFile::read(&mut Cursor::new(out.into_inner())).expect("Failed to parse output file");
+    assert_eq!(
+        read_back.tests()[0].initial_state().descriptors,
+        Some(MooDescriptors::ThirtyTwo(descriptors.clone()))
+    );
+
+    let mut text = Vec::new();
+    read_back
+        .dump_text(&mut text, &MooDumpOptions::default())
+        .expect("Failed to dump test file");
+    let parsed = MooTestFile::parse_text(&mut text.as_slice()).expect("Failed to parse text dump");
+    assert_eq!(
+        parsed.tests()[0].initial_state().descriptors,
+        Some(MooDescriptors::ThirtyTwo(descriptors))
+    );
+}
+
+#[test]
+pub fn test_linear_address() {
+    let mut regs = MooRegisters32::default();
+    regs.set_cs(0x1000);
+    regs.set_eip(0x0001_0010);
+    regs.set_ss(0x2000);
+    regs.set_esp(0x0001_0020);
+    let regs = MooRegisters::ThirtyTwo(regs);
+
+    // Real mode bases come from the selector.
+    assert_eq!(regs.linear_address(MooSegmentRegister::CS, 0x10, None), Some(0x10010));
+    assert_eq!(regs.linear_address(MooSegmentRegister::DS, 0x10, None), None);
+    assert_eq!(regs.csip_linear(None), regs.csip_linear_real());
+
+    // Protected mode bases come from the descriptor cache. Only the low word of EIP and ESP is
+    // used with 16-bit segments.
+    let mut descriptors = MooDescriptors32::default();
+    descriptors.cs.base = 0x0040_0000;
+    descriptors.ss.base = 0x0080_0000;
+    descriptors.ss.access = MooDescriptor32::D_BIT;
+    let descriptors = MooDescriptors::ThirtyTwo(descriptors);
+    assert_eq!(
+        regs.linear_address(MooSegmentRegister::DS, 0x10, Some(&descriptors)),
+        Some(0x10)
+    );
+    assert_eq!(regs.csip_linear(Some(&descriptors)), Some(0x0040_0010));
+    assert_eq!(regs.sp_linear(Some(&descriptors)), Some(0x0081_0020));
+}
+
+#[test]
+pub fn test_descriptor_tables() {
+    let gdt_bytes: [[u8; 8]; 3] = [
+        [0; 8],
+        // 32-bit code, base 10000, limit FFFF.
+        [0xFF, 0xFF, 0x00, 0x00, 0x01, 0x9B, 0x40, 0x00],
+        // Page-granular data, base 60000, limit 0F -> FFFF.
+        [0x0F, 0x00, 0x00, 0x00, 0x06, 0x93, 0x80, 0x00],
+    ];
+    // A 32-bit interrupt gate to 0008:12345678, at IDT base 2000.
+    let idt_gate: [u8; 8] = [0x78, 0x56, 0x08, 0x00, 0x00, 0x8E, 0x34, 0x12];
+
+    let mut ram: Vec<MooRamEntry> = gdt_bytes
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, &value)| MooRamEntry {
+            address: 0x1000 + i as u32,
+            value,
+        })
+        .collect();
+    // A partial fourth GDT entry, which is left out.
+    ram.push(MooRamEntry {
+        address: 0x1018,
+        value:   0xFF,
+    });
+    ram.extend(idt_gate.iter().enumerate().map(|(i, &value)| MooRamEntry {
+        address: 0x2000 + i as u32,
+        value,
+    }));
+
+    let mut regs = MooRegisters32::default();
+    regs.cr0 = 1;
+    regs.set_cs(0x0008);
+    regs.set_ss(0x0010);
+    regs.set_ds(0x0013);
+    regs.set_es(0x0000);
+    regs.set_fs(0x0014);
+    regs.set_gs(0x0018);
+    let data = MooDescriptor32 {
+        access: 0x0080_9300,
+        base:   0x60000,
+        limit:  0xFFFF,
+    };
+    let mut state = MooTestState {
+        regs: MooRegisters::ThirtyTwo(regs),
+        ram,
+        descriptors: Some(MooDescriptors::ThirtyTwo(MooDescriptors32 {
+            // The CPU sets the accessed bit on load; that is not a mismatch.
+            cs: MooDescriptor32 {
+                access: 0x0040_9B00,
+                base:   0x10000,
+                limit:  0xFFFF,
+            },
+            ss: data.clone(),
+            ds: MooDescriptor32 {
+                base: 0x70000,
+                ..data.clone()
+            },
+            es: MooDescriptor32::default(),
+            fs: MooDescriptor32::default(),
+            gs: MooDescriptor32::default(),
+        })),
+        ..Default::default()
+    };
+
+    let gdt = state.descriptor_table(MooTableRegister::new(0x1000, 0x1F), MooCpuFamily::Intel80386);
+    assert_eq!(gdt.len(), 3);
+    let Some(MooDescriptor::Segment(code)) = gdt.get_selector(0x000B).map(|e| e.descriptor)
+    else {
+        panic!("expected a code segment");
+    };
+    assert!(code.is_code() && code.is_32bit() && code.is_present());
+    assert_eq!(
+        (code.base, code.limit, code.cache_access()),
+        (0x10000, 0xFFFF, 0x0040_9B00)
+    );
+    let Some(MooDescriptor::Segment(data_desc)) = gdt.get(2).map(|e| e.descriptor)
+    else {
+        panic!("expected a data segment");
+    };
+    assert_eq!((data_desc.base, data_desc.limit), (0x60000, 0xFFFF));
+
+    // The same bytes on a 286 ignore the last word.
+    let gdt_286 = state.descriptor_table(MooTableRegister::new(0x1000, 0x1F), MooCpuFamily::Intel80286);
+    let Some(MooDescriptor::Segment(data_286)) = gdt_286.get(2).map(|e| e.descriptor)
+    else {
+        panic!("expected a data segment");
+    };
+    assert_eq!((data_286.limit, data_286.flags), (0x000F, 0));
+
+    let idt = state.descriptor_table(MooTableRegister::new(0x2000, 0x7FF), MooCpuFamily::Intel80386);
+    let Some(MooDescriptor::Gate(gate)) = idt.get(0).map(|e| e.descriptor)
+    else {
+        panic!("expected a gate");
+    };
+    assert_eq!(gate.gate_type(), MooSystemType::InterruptGate32);
+    assert_eq!((gate.selector, gate.offset), (0x0008, 0x1234_5678));
+
+    // DS has the wrong base. FS selects the LDT and GS an entry that isn't in RAM.
+    let mismatches = state.descriptor_cache_mismatches(&gdt);
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].segment, MooSegmentRegister::DS);
+    assert_eq!(mismatches[0].field, MooDescriptorField::Base);
+    assert_eq!((mismatches[0].cached, mismatches[0].table), (0x70000, 0x60000));
+
+    if let Some(MooDescriptors::ThirtyTwo(caches)) = &mut state.descriptors {
+        caches.ds.base = 0x60000;
+        caches.ss.access |= MooDescriptor32::D_BIT;
+    }
+    let mismatches = state.descriptor_cache_mismatches(&gdt);
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(
+        (mismatches[0].segment, mismatches[0].field),
+        (MooSegmentRegister::SS, MooDescriptorField::Access)
+    );
+}
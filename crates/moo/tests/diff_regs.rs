@@ -0,0 +1,86 @@
+use moo::{prelude::*, registers::MooRegisterDiff};
+use std::{io::Cursor, path::Path};
+
+fn read_test_file() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+fn registers(diffs: &[MooRegisterDiff]) -> Vec<MooRegister> {
+    diffs.iter().map(|d| d.register).collect()
+}
+
+#[test]
+pub fn test_diff_regs_sparse_32() {
+    let mut test_file = read_test_file();
+    let test = &mut test_file.tests_mut()[0];
+    let initial = test.initial_state().regs().clone();
+    let value = |reg| initial.get(reg).unwrap();
+
+    // CR0 and EAX are present in the final mask but unchanged; only EBX changed.
+    test.final_state_mut().regs = MooRegisters::from_named_values(
+        true,
+        [
+            ("cr0", value(MooRegister::CR0)),
+            ("eax", value(MooRegister::EAX)),
+            ("ebx", value(MooRegister::EBX) ^ 1),
+        ],
+    )
+    .unwrap();
+
+    let diffs = test.diff_regs();
+    assert_eq!(registers(&diffs), vec![MooRegister::EBX]);
+    assert_eq!(diffs[0].initial, value(MooRegister::EBX));
+    assert_eq!(diffs[0].r#final, value(MooRegister::EBX) ^ 1);
+}
+
+#[test]
+pub fn test_diff_regs_sparse_16() {
+    let test_file = read_test_file();
+    let source = &test_file.tests()[0];
+    let mut initial_state = source.initial_state().clone();
+    let mut final_state = source.final_state().clone();
+
+    let names = MooRegisters::NAMES16;
+    initial_state.regs =
+        MooRegisters::from_named_values(false, names.iter().enumerate().map(|(i, n)| (*n, i as u32 + 1))).unwrap();
+    // Registers absent from the final mask read as zero, but are not changes.
+    final_state.regs = MooRegisters::from_named_values(false, [("ax", 1), ("cx", 0x55)]).unwrap();
+
+    let test = MooTest::new(
+        "sparse".to_string(),
+        None,
+        source.bytes(),
+        initial_state,
+        final_state,
+        source.cycles(),
+        None,
+        None,
+    );
+    let diffs = test.diff_regs();
+    assert_eq!(registers(&diffs), vec![MooRegister::CX]);
+    assert_eq!((diffs[0].initial, diffs[0].r#final), (3, 0x55));
+}
+
+#[test]
+pub fn test_full_final_state() {
+    let test_file = read_test_file();
+    let test = &test_file.tests()[0];
+    assert!(!test.initial_state().is_sparse());
+    assert!(test.final_state().is_sparse());
+
+    let present = test.final_state().regs().present_registers();
+    let full = test.full_final_state();
+    assert!(!full.is_sparse());
+    for reg in MooRegisters::REGISTERS32 {
+        let expected = if present.contains(&reg) {
+            test.final_state().regs().get(reg)
+        }
+        else {
+            test.initial_state().regs().get(reg)
+        };
+        assert_eq!(full.regs().get(reg), expected, "{:?}", reg);
+    }
+    assert_eq!(test_file.full_final_states().count(), test_file.tests().len());
+}
@@ -0,0 +1,38 @@
+use moo::{prelude::*, registers::MooSegmentRegister, types::effective_address::MooEffectiveAddress};
+use std::{io::Cursor, path::Path};
+
+#[test]
+fn test_effective_address_components() {
+    let ea = MooEffectiveAddress::new_real(MooSegmentRegister::DS, 0x1234, 0x12340, 0xFFFF, 0x0011);
+    assert_eq!(ea.segment(), MooSegmentRegister::DS);
+    assert_eq!(ea.logical(), (0x1234, 0x0011));
+    assert_eq!(ea.linear(), 0x12351);
+    assert_eq!(ea.physical(), 0x12351);
+    assert!(ea.is_consistent());
+    assert!(!ea.is_paged());
+    assert!(!ea.exceeds_limit());
+
+    assert!(ea.matches_bus_address(MooCpuType::Intel8088, 0x12351));
+    assert!(!ea.matches_bus_address(MooCpuType::Intel8088, 0x12350));
+    assert!(ea.matches_bus_address(MooCpuType::Intel80286, 0x12350));
+
+    let paged = ea.with_physical_address(0x0040_0351);
+    assert!(paged.is_paged());
+    assert_eq!(paged.physical(), 0x0040_0351);
+}
+
+#[test]
+fn test_effective_address_bus_match() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let cpu_type = test_file.metadata().unwrap().cpu_type;
+    let mut checked = 0;
+    for test in test_file.tests() {
+        if let Some(matched) = test.ea_matches_bus(cpu_type) {
+            assert!(test.initial_state().ea().unwrap().is_consistent());
+            checked += matched as usize;
+        }
+    }
+    assert!(checked > 0);
+}
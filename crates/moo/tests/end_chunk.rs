@@ -0,0 +1,48 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn read_test_file() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+#[test]
+pub fn test_end_chunk() {
+    assert!(MooTestFile::new(1, 2, MooCpuType::Intel8088, 0).end_chunk());
+
+    let mut test_file = read_test_file();
+    assert!(!test_file.end_chunk());
+
+    // Without an END chunk, anything after the last test is ignored, as older readers did.
+    let mut buffer = Cursor::new(Vec::new());
+    test_file.write(&mut buffer, true).expect("Failed to write file");
+    let mut legacy = buffer.into_inner();
+    legacy.extend([0xFF; 13]);
+    let read_back = MooTestFile::read(&mut Cursor::new(legacy)).expect("Failed to read file with trailing bytes");
+    assert_eq!(read_back.test_ct(), test_file.test_ct());
+    test_file.set_end_chunk(true);
+
+    let mut buffer = Cursor::new(Vec::new());
+    test_file.write(&mut buffer, true).expect("Failed to write file");
+    let data = buffer.into_inner();
+    let (body, end) = data.split_at(data.len() - 12);
+    assert_eq!(&end[0..4], b"END ");
+
+    let read_back = MooTestFile::read(&mut Cursor::new(&data)).expect("Failed to read file");
+    assert!(read_back.end_chunk());
+    assert_eq!(read_back.test_ct(), test_file.test_ct());
+
+    // Without its END chunk, the file is reported as truncated.
+    assert!(MooTestFile::read(&mut Cursor::new(body)).is_err());
+
+    // Top-level chunks following the last test are read rather than ignored.
+    let mut with_trailer = body.to_vec();
+    with_trailer.extend(b"zprv");
+    with_trailer.extend(3u32.to_le_bytes());
+    with_trailer.extend(b"abc");
+    with_trailer.extend(end);
+    let read_back = MooTestFile::read(&mut Cursor::new(with_trailer)).expect("Failed to read file");
+    assert_eq!(read_back.vendor_chunks().len(), 1);
+    assert_eq!(read_back.vendor_chunks()[0].data, b"abc");
+}
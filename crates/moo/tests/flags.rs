@@ -0,0 +1,99 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use moo::prelude::*;
+
+#[test]
+fn test_flags_set_clear_iter() {
+    let mut flags = MooCpuFlags::from_bits(0x0002);
+    assert!(!flags.contains(MooCpuFlag::CF));
+    flags.set(MooCpuFlag::CF);
+    flags.set(MooCpuFlag::ZF);
+    assert!(flags.contains(MooCpuFlag::CF));
+    assert_eq!(flags.bits(), 0x0043);
+    flags.clear(MooCpuFlag::CF);
+    assert_eq!(
+        flags.iter().collect::<Vec<_>>(),
+        vec![MooCpuFlag::Reserved0, MooCpuFlag::ZF]
+    );
+    assert_eq!(
+        flags.without_reserved().iter().collect::<Vec<_>>(),
+        vec![MooCpuFlag::ZF]
+    );
+}
+
+#[test]
+fn test_flags_display() {
+    let flags: MooCpuFlags = [MooCpuFlag::OF, MooCpuFlag::ZF, MooCpuFlag::CF].into_iter().collect();
+    assert_eq!(flags.to_string(), "o...z..c");
+    assert_eq!(MooCpuFlags::default().to_string(), "........");
+}
+
+#[test]
+fn test_flags_reserved_bits() {
+    let flags = MooCpuFlags::from_bits(0x0046);
+    assert!(!flags.reserved_bits_valid(MooCpuType::Intel8088));
+    assert_eq!(flags.normalize(MooCpuType::Intel8088).bits(), 0xF046);
+    assert!(flags.reserved_bits_valid(MooCpuType::Intel80286));
+
+    let flags = MooCpuFlags::from_bits(0x0000_802A);
+    assert_eq!(flags.normalize(MooCpuType::Intel80386Ex).bits(), 0x0000_0002);
+}
+
+#[test]
+fn test_flags_eflags_high_bits() {
+    assert_eq!(MooCpuFlag::from_bit(16), Some(MooCpuFlag::RF));
+    assert_eq!(MooCpuFlag::from_bit(17), Some(MooCpuFlag::VM));
+    assert_eq!(MooCpuFlag::from_bit(18), Some(MooCpuFlag::AC));
+    assert_eq!(MooCpuFlag::from_bit(19), None);
+
+    let flags = MooCpuFlags::from_bits(0x0003_0083);
+    assert_eq!(
+        flags.without_reserved().iter().collect::<Vec<_>>(),
+        vec![MooCpuFlag::CF, MooCpuFlag::SF, MooCpuFlag::RF, MooCpuFlag::VM]
+    );
+    assert_eq!(flags.to_string(), "...s...c rf vm");
+    assert_eq!(MooCpuFlags::from_bits(0x0002).to_string(), "........");
+}
+
+#[test]
+fn test_flags_long_form() {
+    let flags = MooCpuFlags::from_bits(0x0002_08C3);
+    assert_eq!(flags.to_long_string(), "CF, ZF, SF, OF, VM");
+    assert_eq!(MooCpuFlags::from_bits(0x0002).to_long_string(), "-");
+    assert_eq!(MooCpuFlag::IOPL1.to_string(), "IOPL1");
+}
+
+#[test]
+fn test_flags_diff_display() {
+    let diff = MooCpuFlagsDiff {
+        set: [MooCpuFlag::ZF, MooCpuFlag::OF].into_iter().collect(),
+        cleared: [MooCpuFlag::CF, MooCpuFlag::AC].into_iter().collect(),
+        unmodified_set: [MooCpuFlag::Reserved0, MooCpuFlag::SF].into_iter().collect(),
+        unmodified_cleared: MooCpuFlags::default(),
+    };
+    assert_eq!(diff.to_string(), "O...Z..c ac");
+    assert_eq!(diff.to_long_string(), "set: ZF, OF; cleared: CF, AC");
+    assert!(MooCpuFlagsDiff::default().is_empty());
+    assert_eq!(MooCpuFlagsDiff::default().to_long_string(), "-");
+}
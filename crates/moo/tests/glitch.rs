@@ -0,0 +1,65 @@
+use moo::{
+    prelude::*,
+    types::{
+        glitch::{detect_glitches, MooGlitchKind},
+        MooTState,
+    },
+};
+use std::{io::Cursor, path::Path};
+
+#[test]
+fn test_no_glitches_in_clean_capture() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    for test in test_file.tests() {
+        assert_eq!(
+            test.detect_glitches(test_file.cpu_type()),
+            Vec::new(),
+            "{}",
+            test.name()
+        );
+    }
+}
+
+#[test]
+fn test_detect_glitches() {
+    // T-state values: Ti = 0, T1 = 1, T2 = 2, T3 = 3, T4 = 4, Tw = 5.
+    // 8088 bus states: CODE = 4, MEMR = 5, PASV = 7.
+    let cycle = |pins0: u8, bus_state: u8, t_state: u8| MooCycleState {
+        pins0,
+        bus_state,
+        t_state,
+        ..Default::default()
+    };
+    let cycles = [
+        cycle(MooCycleState::PIN_ALE, 4, 1),
+        cycle(MooCycleState::PIN_READY, 4, 3),
+        cycle(0, 7, 5),
+        cycle(MooCycleState::PIN_ALE, 7, 2),
+        cycle(0, 5, 3),
+    ];
+    let kinds: Vec<_> = detect_glitches(MooCpuType::Intel8088, cycles)
+        .into_iter()
+        .map(|g| (g.cycle, g.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (2, MooGlitchKind::WaitAfterReady),
+            (3, MooGlitchKind::AleOutsideT1(MooTState::T2)),
+            (3, MooGlitchKind::AleWithoutBusCycle),
+        ]
+    );
+
+    let status_glitch = detect_glitches(
+        MooCpuType::Intel8088,
+        [cycle(MooCycleState::PIN_ALE, 4, 1), cycle(0, 5, 2)],
+    );
+    assert_eq!(status_glitch.len(), 1);
+    assert_eq!(
+        status_glitch[0].to_string(),
+        "cycle 1: bus status changed from CODE to MEMR without passive state"
+    );
+}
@@ -0,0 +1,320 @@
+//! Golden fixtures: one small file per CPU type under `tests/test_data/golden`, built from the
+//! API below. Set `MOO_BLESS=1` to regenerate the fixtures after an intentional format change.
+
+use moo::{
+    prelude::*,
+    registers::{
+        descriptors_32::{MooDescriptor32, MooDescriptors32},
+        MooDescriptors,
+        MooRegisters16Init,
+        MooRegisters32Init,
+        MooRegistersInit,
+        MooSegmentRegister,
+    },
+    types::{
+        effective_address::MooEffectiveAddress,
+        MooCpuType,
+        MooException,
+        MooRamEntry,
+        MooStateType,
+        MooTestState,
+    },
+};
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+const CPU_TYPES: [MooCpuType; 9] = [
+    MooCpuType::Intel8088,
+    MooCpuType::Intel8086,
+    MooCpuType::NecV20,
+    MooCpuType::NecV30,
+    MooCpuType::Intel80188,
+    MooCpuType::Intel80186,
+    MooCpuType::Harris80C286,
+    MooCpuType::Intel80286,
+    MooCpuType::Intel80386Ex,
+];
+
+fn fixture_path(cpu_type: MooCpuType) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/test_data/golden")
+        .join(format!("{}.MOO", cpu_type.to_str().trim()))
+}
+
+fn write(test_file: &MooTestFile) -> Vec<u8> {
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).expect("Failed to write test file");
+    buf.into_inner()
+}
+
+fn read(bytes: &[u8]) -> MooTestFile {
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to read test file")
+}
+
+fn regs(cpu_type: MooCpuType, ax: u16, bx: u16, sp: u16, ip: u16, flags: u16) -> MooRegistersInit {
+    match cpu_type {
+        MooCpuType::Intel80386Ex => MooRegistersInit::ThirtyTwo(MooRegisters32Init {
+            cr0: 0x7FFF_FFE0,
+            cr3: 0,
+            eax: 0x1234_0000 | ax as u32,
+            ebx: bx as u32,
+            ecx: 0x0000_0003,
+            edx: 0x0000_0004,
+            esi: 0x0000_0010,
+            edi: 0x0000_0020,
+            ebp: 0x0000_0100,
+            esp: sp as u32,
+            cs: 0x1000,
+            ds: 0x2000,
+            es: 0x3000,
+            fs: 0x4000,
+            gs: 0x5000,
+            ss: 0x6000,
+            eip: ip as u32,
+            dr6: 0xFFFF_0FF0,
+            dr7: 0x0000_0400,
+            eflags: 0xFFFC_0000 | flags as u32,
+        }),
+        _ => MooRegistersInit::Sixteen(MooRegisters16Init {
+            ax,
+            bx,
+            cx: 0x0003,
+            dx: 0x0004,
+            cs: 0x1000,
+            ss: 0x6000,
+            ds: 0x2000,
+            es: 0x3000,
+            sp,
+            bp: 0x0100,
+            si: 0x0010,
+            di: 0x0020,
+            ip,
+            flags,
+        }),
+    }
+}
+
+fn state(
+    s_type: MooStateType,
+    cpu_type: MooCpuType,
+    initial: &MooRegistersInit,
+    fin: Option<&MooRegistersInit>,
+    queue: Vec<u8>,
+    ram: &[(u32, u8)],
+) -> MooTestState {
+    let is_initial = matches!(s_type, MooStateType::Initial);
+    let ea = is_initial.then(|| MooEffectiveAddress::new_real(MooSegmentRegister::DS, 0x2000, 0x20000, 0xFFFF, 0x0010));
+    let ram = ram
+        .iter()
+        .map(|&(address, value)| MooRamEntry { address, value })
+        .collect();
+    let mut state = MooTestState::new(s_type, initial, fin, ea, queue, ram);
+    if matches!(cpu_type, MooCpuType::Intel80386Ex) && is_initial {
+        let descriptor = |base: u32| MooDescriptor32 {
+            access: 0x0000_9300,
+            base,
+            limit: 0xFFFF,
+        };
+        state.descriptors = Some(MooDescriptors::ThirtyTwo(MooDescriptors32 {
+            cs: MooDescriptor32 {
+                access: 0x0000_9B00,
+                ..descriptor(0x10000)
+            },
+            ss: descriptor(0x60000),
+            ds: descriptor(0x20000),
+            es: descriptor(0x30000),
+            fs: descriptor(0x40000),
+            gs: descriptor(0x50000),
+        }));
+    }
+    state
+}
+
+fn cycle(pins0: u8, address_bus: u32, memory_status: u8, data_bus: u16, bus_state: u8, t_state: u8) -> MooCycleState {
+    MooCycleState {
+        pins0,
+        address_bus,
+        memory_status,
+        data_bus,
+        bus_state,
+        t_state,
+        ..Default::default()
+    }
+}
+
+/// `add [bx+si],al`: a code fetch, a memory read and a memory write, with a queue and an EA.
+fn add_test(cpu_type: MooCpuType) -> MooTest {
+    let initial_regs = regs(cpu_type, 0x0042, 0x0000, 0x0100, 0x0000, 0x0002);
+    let final_regs = regs(cpu_type, 0x0042, 0x0000, 0x0100, 0x0002, 0x0006);
+    let initial = state(
+        MooStateType::Initial,
+        cpu_type,
+        &initial_regs,
+        None,
+        vec![0x00],
+        &[(0x10000, 0x00), (0x10001, 0x00), (0x20010, 0x11)],
+    );
+    let fin = state(
+        MooStateType::Final,
+        cpu_type,
+        &initial_regs,
+        Some(&final_regs),
+        vec![0x90, 0x90],
+        &[(0x20010, 0x53)],
+    );
+    let cycles = [
+        cycle(MooCycleState::PIN_ALE, 0x10002, 0, 0, 0b100, 1),
+        cycle(0, 0x10002, MooCycleState::MRDC_BIT, 0x9090, 0b100, 2),
+        cycle(MooCycleState::PIN_ALE, 0x20010, 0, 0, 0b101, 1),
+        cycle(0, 0x20010, MooCycleState::MRDC_BIT, 0x0011, 0b101, 2),
+        cycle(MooCycleState::PIN_ALE, 0x20010, 0, 0, 0b110, 1),
+        cycle(0, 0x20010, MooCycleState::MWTC_BIT, 0x0053, 0b110, 2),
+    ];
+    MooTest::new(
+        "add [bx+si],al".to_string(),
+        Some(MooTestGenMetadata::new(0x0123_4567_89AB_CDEF, 1)),
+        &[0x00, 0x00],
+        initial,
+        fin,
+        &cycles,
+        None,
+        None,
+    )
+}
+
+/// `int3`: pushes the flags, CS and IP and records the exception number and flag address.
+fn int3_test(cpu_type: MooCpuType) -> MooTest {
+    let initial_regs = regs(cpu_type, 0x0000, 0x0000, 0x0100, 0x0000, 0x0302);
+    let final_regs = regs(cpu_type, 0x0000, 0x0000, 0x00FA, 0x0400, 0x0002);
+    let initial = state(
+        MooStateType::Initial,
+        cpu_type,
+        &initial_regs,
+        None,
+        Vec::new(),
+        &[
+            (0x10000, 0xCC),
+            (0x0000C, 0x00),
+            (0x0000D, 0x04),
+            (0x0000E, 0x00),
+            (0x0000F, 0x00),
+        ],
+    );
+    let fin = state(
+        MooStateType::Final,
+        cpu_type,
+        &initial_regs,
+        Some(&final_regs),
+        Vec::new(),
+        &[
+            (0x600FE, 0x02),
+            (0x600FF, 0x03),
+            (0x600FC, 0x00),
+            (0x600FD, 0x10),
+            (0x600FA, 0x01),
+            (0x600FB, 0x00),
+        ],
+    );
+    let cycles = [
+        cycle(MooCycleState::PIN_ALE, 0x600FE, 0, 0, 0b110, 1),
+        cycle(0, 0x600FE, MooCycleState::MWTC_BIT, 0x0302, 0b110, 2),
+        cycle(MooCycleState::PIN_ALE, 0x600FC, 0, 0, 0b110, 1),
+        cycle(0, 0x600FC, MooCycleState::MWTC_BIT, 0x1000, 0b110, 2),
+        cycle(MooCycleState::PIN_ALE, 0x600FA, 0, 0, 0b110, 1),
+        cycle(0, 0x600FA, MooCycleState::MWTC_BIT, 0x0001, 0b110, 2),
+    ];
+    let mut test = MooTest::new(
+        "int3".to_string(),
+        Some(MooTestGenMetadata::new(0xFEDC_BA98_7654_3210, 2)),
+        &[0xCC],
+        initial,
+        fin,
+        &cycles,
+        Some(MooException {
+            exception_num: 3,
+            flag_address:  0x600FE,
+        }),
+        None,
+    );
+    test.add_tag("exception");
+    test
+}
+
+fn golden_file(cpu_type: MooCpuType) -> MooTestFile {
+    let mut test_file = MooTestFile::new(1, 0, cpu_type, 2);
+    test_file.set_metadata(
+        MooFileMetadata::new(1, 0, cpu_type, 0x00, None)
+            .with_test_count(2)
+            .with_file_seed(0x5EED),
+    );
+    test_file.add_test(add_test(cpu_type));
+    test_file.add_test(int3_test(cpu_type));
+    test_file
+}
+
+#[test]
+pub fn test_golden_fixtures() {
+    let bless = std::env::var_os("MOO_BLESS").is_some();
+    for cpu_type in CPU_TYPES {
+        let path = fixture_path(cpu_type);
+        let bytes = write(&golden_file(cpu_type));
+        if bless {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, &bytes).unwrap();
+        }
+        let golden = fs::read(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        assert!(golden == bytes, "{:?} output differs from {}", cpu_type, path.display());
+    }
+}
+
+#[test]
+pub fn test_golden_round_trip() {
+    for cpu_type in CPU_TYPES {
+        let golden = fs::read(fixture_path(cpu_type)).unwrap();
+        let test_file = read(&golden);
+        assert_eq!(test_file.cpu_type().to_str(), cpu_type.to_str());
+        assert_eq!(test_file.test_ct(), 2);
+
+        let wide = matches!(cpu_type, MooCpuType::Intel80386Ex);
+        for test in test_file.tests() {
+            for state in [test.initial_state(), test.final_state()] {
+                assert_eq!(matches!(state.regs(), MooRegisters::ThirtyTwo(_)), wide);
+            }
+            assert!(test.initial_state().ea().is_some());
+            assert_eq!(test.initial_state().descriptors.is_some(), wide);
+        }
+        assert!(!test_file.tests()[0].final_state().queue().is_empty());
+        assert_eq!(test_file.tests()[1].exception().unwrap().exception_num, 3);
+
+        assert!(write(&test_file) == golden, "{:?} round trip differs", cpu_type);
+    }
+}
+
+#[test]
+pub fn test_golden_gzip_round_trip() {
+    for cpu_type in CPU_TYPES {
+        let golden = fs::read(fixture_path(cpu_type)).unwrap();
+        let mut test_file = read(&golden);
+        test_file.set_compressed(true);
+
+        let compressed = write(&test_file);
+        assert_eq!(&compressed[..2], &[0x1F, 0x8B]);
+        let mut read_back = read(&compressed);
+        assert!(read_back.compressed());
+        assert!(
+            write(&read_back) == compressed,
+            "{:?} gzip round trip differs",
+            cpu_type
+        );
+
+        read_back.set_compressed(false);
+        assert!(
+            write(&read_back) == golden,
+            "{:?} decompressed output differs",
+            cpu_type
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use moo::{prelude::*, types::MooTestState};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_interpolated_writes_match_final_ram() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let cpu_type = test_file.metadata().unwrap().cpu_type;
+
+    for test in test_file.tests() {
+        let start = test.state_at_cycle(cpu_type, 0);
+        assert!(start.writes().is_empty());
+        assert_eq!(start.memory().len(), test.initial_state().ram().len());
+
+        let end = test.state_at_cycle(cpu_type, usize::MAX);
+        assert_eq!(end.cycle(), test.cycles().len());
+        assert!(end.writes().windows(2).all(|w| w[0].cycle <= w[1].cycle));
+
+        for entry in test.final_state().ram() {
+            if end.writes().iter().any(|w| w.address == entry.address) {
+                assert_eq!(end.read_u8(entry.address), Some(entry.value), "test {}", test.name());
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_interpolated_queue_and_ip() {
+    let initial = MooTestState {
+        regs: MooRegisters::Sixteen(
+            MooRegisters16Init {
+                ax:    0,
+                bx:    0,
+                cx:    0,
+                dx:    0,
+                cs:    0,
+                ss:    0,
+                ds:    0,
+                es:    0,
+                sp:    0,
+                bp:    0,
+                si:    0,
+                di:    0,
+                ip:    0xFFFF,
+                flags: 0x0002,
+            }
+            .into(),
+        ),
+        queue: vec![0x90, 0x90],
+        ..Default::default()
+    };
+    let test = MooTest::new(
+        String::new(),
+        None,
+        &[0x90],
+        initial,
+        MooTestState::default(),
+        &[],
+        None,
+        None,
+    );
+
+    let cycle = |queue_op: u8| MooCycleState {
+        queue_op,
+        ..Default::default()
+    };
+
+    let mut state = MooInterpolatedState::new(&test, MooCpuType::Intel8088);
+    assert_eq!(state.ip(), Some(0xFFFF));
+    state.step(&cycle(1));
+    state.step(&cycle(0));
+    assert_eq!(state.bytes_consumed(), 1);
+    assert_eq!(state.queue().len(), 1);
+    // IP wraps at 64K for 16-bit register files.
+    assert_eq!(state.ip(), Some(0));
+    state.step(&cycle(2));
+    assert!(state.queue().is_empty());
+    assert_eq!(state.ip(), None);
+    assert_eq!(state.cycle(), 3);
+}
+
+#[test]
+pub fn test_slice_cycles() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let cpu_type = test_file.metadata().unwrap().cpu_type;
+
+    // Test 0 writes its result in the transaction starting at cycle 17.
+    let test = &test_file.tests()[0];
+    let write = test.state_at_cycle(cpu_type, usize::MAX).writes()[0];
+    assert!(!test.is_synthetic());
+
+    let slice = test.slice_cycles(cpu_type, write.cycle + 1..usize::MAX);
+    assert!(slice.is_synthetic());
+    assert!(slice.hash().is_none());
+    assert_eq!(slice.cycles().len(), test.cycles().len() - write.cycle - 1);
+    assert!(slice
+        .name()
+        .ends_with(&format!("[cycles {}..{}]", write.cycle + 1, test.cycles().len())));
+    assert!(slice
+        .initial_state()
+        .ram()
+        .iter()
+        .any(|e| e.address == write.address && e.value == write.value));
+
+    let mut buf = Cursor::new(Vec::new());
+    assert!(slice.write(0, &mut buf, false).is_err());
+
+    // A reversed range yields an empty slice.
+    let (start, end) = (5, 2);
+    assert!(test.slice_cycles(cpu_type, start..end).cycles().is_empty());
+}
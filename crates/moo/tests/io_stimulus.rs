@@ -0,0 +1,101 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn golden_8086() -> MooTestFile {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/golden/8086.MOO");
+    let bytes = std::fs::read(path).unwrap();
+    MooTestFile::read(&mut Cursor::new(bytes)).unwrap()
+}
+
+/// An 8086 I/O read. BHE is active low on the 8086.
+fn io_read(port: u32, data: u16, bhe: bool) -> [MooCycleState; 2] {
+    let ale = MooCycleState {
+        pins0: MooCycleState::PIN_ALE,
+        pins1: if bhe { 0 } else { MooCycleState::PIN1_BHE },
+        address_bus: port,
+        bus_state: 0b001,
+        t_state: 1,
+        ..Default::default()
+    };
+    let data = MooCycleState {
+        address_bus: port,
+        io_status: MooCycleState::IORC_BIT,
+        data_bus: data,
+        bus_state: 0b001,
+        t_state: 2,
+        ..Default::default()
+    };
+    [ale, data]
+}
+
+fn write(test_file: &MooTestFile) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, true).unwrap();
+    out.into_inner()
+}
+
+#[test]
+pub fn test_io_stimulus() {
+    let mut test_file = golden_8086();
+    let cpu_type = test_file.cpu_type();
+    let base = &test_file.tests()[0];
+
+    // `in al,60h` twice, then `in ax,62h`.
+    let cycles: Vec<MooCycleState> = [io_read(0x60, 0x00A5, false), io_read(0x60, 0x005A, false)]
+        .into_iter()
+        .chain([io_read(0x62, 0xBEEF, true)])
+        .flatten()
+        .collect();
+    let mut test = MooTest::new(
+        "in".to_string(),
+        None,
+        &[0xE4, 0x60],
+        base.initial_state().clone(),
+        base.final_state().clone(),
+        &cycles,
+        None,
+        None,
+    );
+
+    let derived = test.derive_io_stimulus(cpu_type);
+    assert_eq!(
+        derived,
+        [
+            MooIoStimulus::new(0x60, MooPortWidth::Byte, vec![0xA5, 0x5A]),
+            MooIoStimulus::new(0x62, MooPortWidth::Word, vec![0xBEEF]),
+        ]
+    );
+    for stimulus in derived {
+        test.set_port_values(stimulus.port, stimulus.width, stimulus.values);
+    }
+    assert_eq!(test.port_values(0x60, MooPortWidth::Byte), Some(&[0xA5, 0x5A][..]));
+    assert_eq!(test.port_values(0x60, MooPortWidth::Word), None);
+
+    // Values are masked to the access width, and an empty list removes the entry.
+    test.set_port_values(0x70, MooPortWidth::Byte, vec![0x1FF]);
+    assert_eq!(test.port_values(0x70, MooPortWidth::Byte), Some(&[0xFF][..]));
+    test.set_port_values(0x70, MooPortWidth::Byte, Vec::new());
+    assert_eq!(test.io_stimulus().len(), 2);
+
+    test_file.add_test(test);
+    let bytes = write(&test_file);
+    let read_back = MooTestFile::read(&mut Cursor::new(bytes)).unwrap();
+    let test = &read_back.tests()[2];
+    assert_eq!(test.io_stimulus(), test_file.tests()[2].io_stimulus());
+
+    // The stimulus is not hashed, so removing it leaves the test hash unchanged.
+    let hash = test.hash_string();
+    let mut redacted = read_back;
+    let summary = redacted.redact(&[MooRedaction::chunk(*b"IOST").unwrap()]);
+    assert_eq!(summary.tests_redacted, 1);
+    let redacted = MooTestFile::read(&mut Cursor::new(write(&redacted))).unwrap();
+    assert!(redacted.tests()[2].io_stimulus().is_empty());
+    assert_eq!(redacted.tests()[2].hash_string(), hash);
+
+    // The text dump carries the stimulus.
+    let mut text = Vec::new();
+    test_file.dump_text(&mut text, &MooDumpOptions::default()).unwrap();
+    assert!(String::from_utf8_lossy(&text).contains("  io port=0060 width=1 A5 5A\n"));
+    let parsed = MooTestFile::parse_text(&mut text.as_slice()).unwrap();
+    assert_eq!(parsed.tests()[2].io_stimulus(), test_file.tests()[2].io_stimulus());
+}
@@ -0,0 +1,74 @@
+use moo::{prelude::*, types::MooRamEntry};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_ivt() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/golden/8086.MOO");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(std::fs::read(path).unwrap())).unwrap();
+    let cpu_type = test_file.cpu_type();
+    let test = &mut test_file.tests_mut()[1];
+
+    let ivt = test.initial_state().ivt();
+    assert_eq!(ivt.len(), 1);
+    let entry = *ivt.get(3).unwrap();
+    assert_eq!((entry.segment, entry.offset), (0x0000, 0x0400));
+    assert_eq!((entry.address(), entry.target_linear()), (0x0C, 0x0400));
+    assert_eq!(entry.to_string(), "03: 0000:0400");
+    assert!(ivt.get(4).is_none());
+    assert!(test.final_state().ivt().is_empty());
+
+    // A partial entry is left out.
+    let partial = MooIvt::from_ram(&[MooRamEntry {
+        address: 0x10,
+        value:   0,
+    }]);
+    assert!(partial.is_empty());
+
+    // The fixture keeps CS at 1000 after the interrupt, so it does not reach the handler.
+    let mismatch = test.exception_vector_mismatch(cpu_type).unwrap();
+    assert_eq!(mismatch.expected, entry);
+    assert_eq!((mismatch.cs, mismatch.ip), (0x1000, 0x0400));
+
+    // Point the vector at 1000:0400 and the test matches.
+    for ram_entry in &mut test.initial_state_mut().ram {
+        if ram_entry.address == 0x0F {
+            ram_entry.value = 0x10;
+        }
+    }
+    assert_eq!(test.initial_state().ivt().get(3).unwrap().segment, 0x1000);
+    assert!(test.exception_vector_mismatch(cpu_type).is_none());
+
+    // A test that stops on a HLT at the handler ends one byte past the vector.
+    if let MooRegisters::Sixteen(regs) = test.final_state_mut().regs_mut() {
+        regs.set_ip(0x0401);
+    }
+    assert!(test.exception_vector_mismatch(cpu_type).is_some());
+    test.initial_state_mut().ram.push(MooRamEntry {
+        address: 0x10400,
+        value:   0xF4,
+    });
+    assert!(test.exception_vector_mismatch(cpu_type).is_none());
+}
+
+#[test]
+pub fn test_exception_display() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/golden/8086.MOO");
+    let test_file = MooTestFile::read(&mut Cursor::new(std::fs::read(path).unwrap())).unwrap();
+    let cpu_type = test_file.cpu_type();
+    let test = &test_file.tests()[1];
+
+    let exception = test.exception().unwrap();
+    assert_eq!(
+        (exception.mnemonic(), exception.name()),
+        (Some("#BP"), Some("breakpoint"))
+    );
+    assert_eq!(exception.to_string(), "03 #BP (breakpoint), flags at 0600FE");
+
+    let context = test.exception_context(cpu_type).unwrap();
+    assert_eq!(context.handler, test.initial_state().ivt().get(3).copied());
+    assert_eq!(
+        context.to_string(),
+        "03 #BP (breakpoint), flags at 0600FE, handler 0000:0400"
+    );
+    assert!(test_file.tests()[0].exception_context(cpu_type).is_none());
+}
@@ -0,0 +1,68 @@
+use moo::test_file::{journal::MooTestEdit, MooTestFile};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_journal_undo_redo() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let test_ct = test_file.test_ct();
+    let original_name = test_file.tests()[1].name().to_string();
+
+    assert!(test_file.edit_test(1, MooTestEdit::SetName("renamed".to_string())));
+    assert!(test_file.remove_test(0));
+    // Removing test 0 shifts the edited test down to index 0.
+    assert_eq!(test_file.tests()[0].name(), "renamed");
+    assert_eq!(test_file.journal().dirty_tests().collect::<Vec<_>>(), vec![0]);
+
+    assert!(test_file.undo());
+    assert_eq!(test_file.test_ct(), test_ct);
+    assert!(test_file.undo());
+    assert_eq!(test_file.tests()[1].name(), original_name);
+    assert!(!test_file.undo());
+
+    assert!(test_file.redo());
+    assert_eq!(test_file.tests()[1].name(), "renamed");
+    assert!(test_file.journal().can_redo());
+
+    // A new edit discards the redo stack.
+    assert!(test_file.edit_test(2, MooTestEdit::SetBytes(vec![0x90])));
+    assert!(!test_file.journal().can_redo());
+    assert!(!test_file.edit_test(test_ct, MooTestEdit::SetBytes(vec![0x90])));
+}
+
+#[test]
+pub fn test_journal_summary() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let test_ct = test_file.test_ct();
+    assert!(!test_file.journal_summary().changed());
+
+    assert!(test_file.edit_test(3, MooTestEdit::SetName("renamed".to_string())));
+    assert!(test_file.edit_test(3, MooTestEdit::SetBytes(vec![0x90])));
+    // Tags are not hashed, so a tag edit is counted but doesn't mark the test for re-hashing.
+    assert!(test_file.edit_test(5, MooTestEdit::SetTags(vec!["tagged".to_string()])));
+    assert!(!test_file.journal().is_dirty(5));
+    assert!(test_file.remove_test(0));
+    assert!(test_file.remove_test(test_file.test_ct() - 1));
+
+    // Edit counts follow the edited tests down past the removal.
+    let summary = test_file.journal_summary();
+    assert_eq!(summary.edit_counts.len(), test_ct - 2);
+    assert_eq!(summary.edited_tests().collect::<Vec<_>>(), vec![(2, 2), (4, 1)]);
+    assert_eq!(
+        (summary.tests_edited(), summary.edits(), summary.tests_removed),
+        (2, 3, 2)
+    );
+    assert_eq!(summary.to_string(), "2 tests edited (3 edits), 0 inserted, 2 removed");
+
+    // Undone operations are not counted.
+    assert!(test_file.undo());
+    assert!(test_file.undo());
+    assert!(test_file.undo());
+    let summary = test_file.journal_summary();
+    assert_eq!(summary.edited_tests().collect::<Vec<_>>(), vec![(3, 2)]);
+    assert_eq!(summary.tests_removed, 0);
+}
@@ -0,0 +1,43 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_license_preserved() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    assert!(test_file.license().is_none());
+
+    let license = MooLicense::new("CC0-1.0")
+        .with_contributor("Daniel Balsom")
+        .with_contributor("Another \"Contributor\"");
+    test_file.set_license(Some(license.clone()));
+    assert_eq!(license.to_string(), "CC0-1.0 (Daniel Balsom, Another \"Contributor\")");
+
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, true).expect("Failed to write output file");
+    let read_back = MooTestFile::read(&mut Cursor::new(out.get_ref())).expect("Failed to parse output file");
+    assert_eq!(read_back.license(), Some(&license));
+    assert_eq!(read_back.test_ct(), test_file.test_ct());
+
+    // A LICN chunk extended by a later writer is read up to its end.
+    let mut bytes = out.into_inner();
+    let licn = bytes
+        .windows(4)
+        .position(|w| w == b"LICN")
+        .expect("License chunk not written");
+    let size = u32::from_le_bytes(bytes[licn + 4..licn + 8].try_into().unwrap());
+    bytes[licn + 4..licn + 8].copy_from_slice(&(size + 4).to_le_bytes());
+    let end = licn + 8 + size as usize;
+    bytes.splice(end..end, [0xAA; 4]);
+    let extended = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse extended license chunk");
+    assert_eq!(extended.license(), Some(&license));
+    assert_eq!(extended.test_ct(), test_file.test_ct());
+
+    let mut text = Vec::new();
+    read_back
+        .dump_text(&mut text, &MooDumpOptions::default())
+        .expect("Failed to dump test file");
+    let parsed = MooTestFile::parse_text(&mut text.as_slice()).expect("Failed to parse text dump");
+    assert_eq!(parsed.license(), Some(&license));
+}
@@ -0,0 +1,38 @@
+use moo::{
+    prelude::*,
+    types::{MooCpuFamily, MooTestState},
+};
+
+fn test_with_bytes(bytes: &[u8]) -> MooTest {
+    MooTest::new(
+        String::new(),
+        None,
+        bytes,
+        MooTestState::default(),
+        MooTestState::default(),
+        &[],
+        None,
+        None,
+    )
+}
+
+#[test]
+pub fn test_lock_prefix_detection() {
+    let family = MooCpuFamily::Intel80386;
+
+    // lock add [gs:bp+si],bh
+    let test = test_with_bytes(&[0xF0, 0x65, 0x00, 0x3A]);
+    assert!(test.has_lock_prefix(family));
+    assert!(test.is_locked(family));
+    // LOCK comes first, so it is found even where 0x65 is not a prefix.
+    assert!(test.has_lock_prefix(MooCpuFamily::Intel8086));
+
+    // An F0 byte that is not in prefix position is not a LOCK prefix.
+    let test = test_with_bytes(&[0xB0, 0xF0]);
+    assert!(!test.has_lock_prefix(family));
+    assert!(!test.is_locked(family));
+
+    // xchg [bx],al is implicitly locked; xchg bl,al is not.
+    assert!(test_with_bytes(&[0x86, 0x07]).is_locked(family));
+    assert!(!test_with_bytes(&[0x86, 0xC3]).is_locked(family));
+}
@@ -0,0 +1,69 @@
+use moo::{prelude::*, types::errors::MooError};
+use std::{io::Cursor, path::Path};
+
+fn read_file(name: &str) -> MooTestFile {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data").join(name);
+    MooTestFile::read(&mut Cursor::new(std::fs::read(path).unwrap())).unwrap()
+}
+
+#[test]
+pub fn test_edit_metadata() {
+    let mut test_file = read_file("00.MOO");
+    let family = MooCpuFamily::from(test_file.cpu_type());
+    let add = MooOpcodeId::parse("00", family).unwrap();
+    assert!(test_file.opcode_mismatches(&add).is_empty());
+
+    let edit = MooMetadataEdit::new()
+        .with_mnemonic("ADD")
+        .with_opcode(add.clone())
+        .with_file_seed(7)
+        .with_set_version(1, 2);
+    let summary = test_file.edit_metadata(&edit).unwrap();
+    assert!(summary.changed());
+    let metadata = test_file.metadata().unwrap();
+    assert_eq!(metadata.mnemonic(), "ADD");
+    assert_eq!(metadata.opcode_id(), Some(add));
+    assert_eq!(metadata.file_seed, 7);
+    assert_eq!((metadata.set_version_major, metadata.set_version_minor), (1, 2));
+
+    // Applying the same edit again changes nothing.
+    assert!(!test_file.edit_metadata(&edit).unwrap().changed());
+
+    // The edit survives a write and read.
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).unwrap();
+    let read_back = MooTestFile::read(&mut Cursor::new(buf.into_inner())).unwrap();
+    assert_eq!(read_back.metadata().unwrap().mnemonic(), "ADD");
+    assert_eq!(read_back.metadata().unwrap().file_seed, 7);
+}
+
+#[test]
+pub fn test_edit_metadata_validation() {
+    // The golden file holds an `add` and an `int3`, so no single opcode fits it.
+    let mut test_file = read_file("golden/8086.MOO");
+    let family = MooCpuFamily::from(test_file.cpu_type());
+    let add = MooOpcodeId::parse("00", family).unwrap();
+    assert_eq!(test_file.opcode_mismatches(&add), vec![1]);
+
+    let before = test_file.metadata().unwrap().clone();
+    let edit = MooMetadataEdit::new().with_file_seed(7).with_opcode(add);
+    assert!(matches!(
+        test_file.edit_metadata(&edit),
+        Err(MooError::MetadataError(_))
+    ));
+    let bad_mnemonic = MooMetadataEdit::new().with_mnemonic("  ");
+    assert!(test_file.edit_metadata(&bad_mnemonic).is_err());
+    let long_opcode = MooOpcodeId::new(&[0x0F, 0x38, 0x00], None)
+        .with_prefix(0x66)
+        .with_prefix(0xF3);
+    let edit = MooMetadataEdit::new().with_mnemonic("LONG").with_opcode(long_opcode);
+    assert!(matches!(
+        test_file.edit_metadata(&edit),
+        Err(MooError::MetadataError(_))
+    ));
+    assert_eq!(test_file.metadata().unwrap().mnemonic(), before.mnemonic());
+
+    // Nothing is changed by a failed edit.
+    assert_eq!(test_file.metadata().unwrap().file_seed, before.file_seed);
+    assert_eq!(test_file.metadata().unwrap().opcode, before.opcode);
+}
@@ -0,0 +1,70 @@
+use moo::{
+    prelude::*,
+    types::{flags::MooCpuFlag, mutate::SYNTHETIC_TAG},
+};
+use std::{io::Cursor, path::Path};
+
+fn read_golden() -> MooTestFile {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/golden/8086.MOO");
+    MooTestFile::read(&mut Cursor::new(std::fs::read(path).unwrap())).unwrap()
+}
+
+#[test]
+pub fn test_mutations() {
+    let test_file = read_golden();
+    let cpu_type = test_file.cpu_type();
+    let (add, int3) = (&test_file.tests()[0], &test_file.tests()[1]);
+
+    let flipped = add.mutated(cpu_type, MooMutation::FlipFlag(MooCpuFlag::CF)).unwrap();
+    assert_eq!(
+        flipped.initial_state().regs().flags() ^ add.initial_state().regs().flags(),
+        MooCpuFlag::CF.mask()
+    );
+    // The expected final registers are those of the original test.
+    assert_eq!(flipped.full_final_state().regs(), add.full_final_state().regs());
+    assert!(flipped.has_tag(SYNTHETIC_TAG) && flipped.has_tag("mutated-flags"));
+    assert!(flipped.hash().is_none() && flipped.gen_metadata().is_none());
+    assert!(!flipped.is_synthetic());
+
+    // `add [bx+si],al` has a ModR/M byte after the opcode; `int3` has nothing to perturb.
+    let perturbed = add.mutated(cpu_type, MooMutation::PerturbImmediate(0x40)).unwrap();
+    assert_eq!(perturbed.bytes(), &[0x00, 0x40]);
+    let ram = perturbed.initial_state().ram();
+    assert!(ram.iter().any(|e| e.address == 0x10001 && e.value == 0x40));
+    assert!(int3.mutated(cpu_type, MooMutation::PerturbImmediate(0x40)).is_none());
+
+    // Data moves; the instruction bytes stay put.
+    let shifted = add.mutated(cpu_type, MooMutation::ShiftMemory(4)).unwrap();
+    let addresses: Vec<u32> = shifted.initial_state().ram().iter().map(|e| e.address).collect();
+    assert_eq!(addresses, vec![0x10000, 0x10001, 0x20014]);
+    assert!(add.mutated(cpu_type, MooMutation::ShiftMemory(0)).is_none());
+}
+
+#[test]
+pub fn test_mutator() {
+    let test_file = read_golden();
+    let cpu_type = test_file.cpu_type();
+
+    let run = |seed| {
+        let mut mutator = MooMutator::new(seed);
+        let mut out = MooTestFile::new(1, 0, cpu_type, 8);
+        let mut mutations = Vec::new();
+        for _ in 0..4 {
+            for test in test_file.tests() {
+                let (mutation, mutated) = mutator.mutate(cpu_type, test).unwrap();
+                mutations.push(mutation);
+                out.add_test(mutated);
+            }
+        }
+        let mut buf = Cursor::new(Vec::new());
+        out.write(&mut buf, false).unwrap();
+        (mutations, buf.into_inner())
+    };
+
+    // The same seed gives the same mutations, and mutated tests can be written and read back.
+    let (mutations, bytes) = run(7);
+    assert_eq!(run(7), (mutations.clone(), bytes.clone()));
+    let read_back = MooTestFile::read(&mut Cursor::new(&bytes)).unwrap();
+    assert_eq!(read_back.test_ct(), mutations.len());
+    assert!(read_back.tests().iter().all(|t| t.has_tag(SYNTHETIC_TAG)));
+}
@@ -0,0 +1,244 @@
+use moo::{
+    prelude::*,
+    test_file::{journal::MooTestEdit, MooTestFile},
+};
+use std::{io::Cursor, path::Path};
+
+fn read_test_file() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+#[test]
+pub fn test_set_cpu_type() {
+    let mut test_file = read_test_file();
+    test_file.set_cpu_type(MooCpuType::Intel80286);
+    assert!(matches!(test_file.cpu_type(), MooCpuType::Intel80286));
+    assert_eq!(test_file.arch(), "286 ");
+    assert!(matches!(test_file.metadata().unwrap().cpu_type, MooCpuType::Intel80286));
+
+    let mut buffer = Cursor::new(Vec::new());
+    test_file.write(&mut buffer, true).expect("Failed to write file");
+    let read_back = MooTestFile::read(&mut Cursor::new(buffer.into_inner())).expect("Failed to read file");
+    assert!(matches!(read_back.cpu_type(), MooCpuType::Intel80286));
+    assert_eq!(read_back.arch(), "286 ");
+}
+
+#[test]
+pub fn test_arch_mismatch() {
+    let mut test_file = read_test_file();
+    assert!(test_file.arch_matches_cpu_type());
+    assert!(!test_file.repair_arch());
+
+    // Editing the metadata directly changes the CPU type, but not the header tag.
+    test_file.metadata_mut().unwrap().cpu_type = MooCpuType::Intel80286;
+    assert!(matches!(test_file.cpu_type(), MooCpuType::Intel80286));
+    assert_eq!(test_file.arch(), "386E");
+    assert!(!test_file.arch_matches_cpu_type());
+
+    // The mismatch survives a round trip, so that it can be reported.
+    let mut buffer = Cursor::new(Vec::new());
+    test_file.write(&mut buffer, true).expect("Failed to write file");
+    let mut read_back = MooTestFile::read(&mut Cursor::new(buffer.into_inner())).expect("Failed to read file");
+    assert_eq!(read_back.arch(), "386E");
+    assert!(matches!(read_back.cpu_type(), MooCpuType::Intel80286));
+    assert!(!read_back.arch_matches_cpu_type());
+
+    assert!(read_back.repair_arch());
+    assert_eq!(read_back.arch(), "286 ");
+    assert!(read_back.arch_matches_cpu_type());
+
+    // Setting the metadata keeps both in step.
+    let mut metadata = read_back.metadata().unwrap().clone();
+    metadata.cpu_type = MooCpuType::Intel8088;
+    read_back.set_metadata(metadata);
+    assert_eq!(read_back.arch(), "8088");
+    assert!(read_back.arch_matches_cpu_type());
+}
+
+#[test]
+pub fn test_set_test_count() {
+    let mut test_file = read_test_file();
+    let test_ct = test_file.test_ct();
+    assert!(!test_file.set_test_count(test_ct + 1));
+    assert_eq!(test_file.test_ct(), test_ct);
+
+    assert!(test_file.set_test_count(10));
+    assert_eq!(test_file.test_ct(), 10);
+    assert_eq!(test_file.metadata().unwrap().test_ct, 10);
+
+    // Journaled removals and their undo keep the metadata count in step.
+    assert!(test_file.remove_test(0));
+    assert_eq!(test_file.metadata().unwrap().test_ct, 9);
+    assert!(test_file.undo());
+    assert_eq!(test_file.metadata().unwrap().test_ct, 10);
+
+    assert!(test_file.edit_test(0, MooTestEdit::SetName("renamed".to_string())));
+    assert_eq!(test_file.metadata().unwrap().test_ct, 10);
+}
+
+#[test]
+pub fn test_arch_tag() {
+    assert_eq!(MooArchTag::new("V20").unwrap().as_str(), "V20 ");
+    assert_eq!(MooArchTag::from(MooCpuType::Intel80286).as_bytes(), b"286 ");
+    assert!(matches!(
+        MooArchTag::new("88").unwrap().cpu_type(),
+        Ok(MooCpuType::Intel8088)
+    ));
+    assert!(MooArchTag::new("").is_err());
+    assert!(MooArchTag::new("80386").is_err());
+    assert!(MooArchTag::new("8é").is_err());
+    assert!(MooArchTag::from_bytes([b'8', b'0', 0, b'6']).is_err());
+
+    // A header with an unprintable CPU id is rejected rather than decoded lossily.
+    let test_file = read_test_file();
+    let mut buffer = Cursor::new(Vec::new());
+    test_file.write(&mut buffer, true).expect("Failed to write file");
+    let mut bytes = buffer.into_inner();
+    let cpu_id = bytes
+        .windows(4)
+        .position(|w| w == test_file.arch_tag().as_bytes())
+        .expect("No CPU id");
+    bytes[cpu_id] = 0;
+    assert!(MooTestFile::read(&mut Cursor::new(bytes)).is_err());
+}
+
+#[test]
+pub fn test_hash_invalidation() {
+    let mut test_file = read_test_file();
+    let original = *test_file.tests()[2].hash().unwrap();
+    assert!(!test_file.tests()[2].is_hash_dirty());
+
+    // A clean hash is preserved as is, and recomputing it reproduces it.
+    assert_eq!(test_file.tests_mut()[2].recompute_hash(2).unwrap(), &original);
+    test_file.tests_mut()[2].add_tag("tagged");
+    assert!(!test_file.tests()[2].is_hash_dirty());
+
+    test_file.tests_mut()[2].rename("renamed");
+    assert!(test_file.tests()[2].is_hash_dirty());
+
+    let mut buffer = Cursor::new(Vec::new());
+    test_file.write(&mut buffer, true).expect("Failed to write file");
+    let read_back = MooTestFile::read(&mut Cursor::new(buffer.into_inner())).expect("Failed to read file");
+    let new_hash = *read_back.tests()[2].hash().unwrap();
+    assert_ne!(new_hash, original);
+    assert_eq!(test_file.tests_mut()[2].recompute_hash(2).unwrap(), &new_hash);
+    assert!(!test_file.tests()[2].is_hash_dirty());
+    assert_eq!(read_back.tests()[3].hash(), test_file.tests()[3].hash());
+}
+
+#[test]
+pub fn test_alternate_digest() {
+    let test_file = read_test_file();
+    assert!(test_file.tests()[0].digest().is_none());
+    assert!(test_file.tests()[0].verify_hashes(0).unwrap());
+
+    let options = MooWriteOptions::new()
+        .with_preserve_hash(true)
+        .with_digest(Some(MooHashAlgorithm::Sha256));
+    let mut buffer = Cursor::new(Vec::new());
+    test_file
+        .write_with_options(&mut buffer, &options)
+        .expect("Failed to write file");
+    let mut read_back = MooTestFile::read(&mut Cursor::new(buffer.into_inner())).expect("Failed to read file");
+
+    // The SHA-1 hash is unchanged and the digest verifies against the same data.
+    let test = &read_back.tests()[0];
+    assert_eq!(test.hash(), test_file.tests()[0].hash());
+    let digest = test.digest().expect("Missing digest");
+    assert_eq!(digest.algorithm, MooHashAlgorithm::Sha256);
+    assert_eq!(digest.digest.len(), 32);
+    assert_eq!(test.compute_digest(0, MooHashAlgorithm::Sha256).unwrap(), *digest);
+    assert!(test.verify_hashes(0).unwrap());
+    assert!(!test.verify_hashes(1).unwrap());
+
+    // Digests survive a plain rewrite and are recalculated along with the hash after an edit.
+    let old_digest = digest.clone();
+    read_back.tests_mut()[0].rename("renamed");
+    let mut buffer = Cursor::new(Vec::new());
+    read_back.write(&mut buffer, true).expect("Failed to write file");
+    let read_back = MooTestFile::read(&mut Cursor::new(buffer.into_inner())).expect("Failed to read file");
+    assert_ne!(read_back.tests()[0].digest(), Some(&old_digest));
+    assert!(read_back.tests()[0].verify_hashes(0).unwrap());
+    assert_eq!(
+        read_back.tests()[1].digest().map(|d| d.algorithm),
+        Some(MooHashAlgorithm::Sha256)
+    );
+}
+
+#[test]
+pub fn test_verify_hashes() {
+    let mut test_file = read_test_file();
+    assert!(test_file.verify_hashes().unwrap().is_empty());
+
+    // Renaming leaves the stored hash in place until the file is written.
+    test_file.tests_mut()[4].rename("renamed");
+    let mismatches = test_file.verify_hashes().unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!(mismatches[0].index, 4);
+    assert_eq!(mismatches[0].algorithm, MooHashAlgorithm::Sha1);
+    assert_eq!(mismatches[0].stored, test_file.tests()[4].hash().unwrap().to_vec());
+
+    let mut buffer = Cursor::new(Vec::new());
+    test_file.write(&mut buffer, true).expect("Failed to write file");
+    let read_back = MooTestFile::read(&mut Cursor::new(buffer.into_inner())).expect("Failed to read file");
+    assert!(read_back.verify_hashes().unwrap().is_empty());
+}
+
+#[test]
+pub fn test_write_compression_options() {
+    let test_file = read_test_file();
+
+    let mut plain = Cursor::new(Vec::new());
+    test_file
+        .write_with_options(&mut plain, &MooWriteOptions::new().with_buffer_size(16))
+        .expect("Failed to write file");
+    let plain = plain.into_inner();
+    assert_eq!(&plain[0..4], b"MOO ");
+
+    let options = MooWriteOptions::new()
+        .with_compression(MooCompressionKind::Gzip)
+        .with_compression_level(1);
+    let mut gzipped = Cursor::new(Vec::new());
+    test_file
+        .write_with_options(&mut gzipped, &options)
+        .expect("Failed to write file");
+    let gzipped = gzipped.into_inner();
+    assert_eq!(&gzipped[0..2], &[0x1F, 0x8B]);
+    assert!(gzipped.len() < plain.len());
+
+    let read_back = MooTestFile::read(&mut Cursor::new(gzipped)).expect("Failed to read file");
+    assert_eq!(read_back.test_ct(), test_file.test_ct());
+    let mut rewritten = Cursor::new(Vec::new());
+    read_back
+        .write_with_options(
+            &mut rewritten,
+            &MooWriteOptions::new().with_compression(MooCompressionKind::Uncompressed),
+        )
+        .expect("Failed to write file");
+    assert_eq!(rewritten.into_inner(), plain);
+}
+
+#[test]
+pub fn test_gzip_stream_round_trip() {
+    let mut test_file = read_test_file();
+    assert!(test_file.test_ct() > 1);
+    test_file.tests_mut()[1].rename("second");
+
+    // A plain Vec is not Seek; the gzip stream must still be complete when it is handed back.
+    let options = MooWriteOptions::new().with_compression(MooCompressionKind::Gzip);
+    let gzipped = test_file
+        .write_stream(Vec::new(), &options)
+        .expect("Failed to write file");
+    assert_eq!(&gzipped[0..2], &[0x1F, 0x8B]);
+
+    let read_back = MooTestFile::read(&mut Cursor::new(gzipped)).expect("Failed to read file");
+    assert_eq!(read_back.test_ct(), test_file.test_ct());
+    for (i, (a, b)) in read_back.tests().iter().zip(test_file.tests()).enumerate() {
+        assert_eq!(a.name(), b.name(), "test {} name mismatch", i);
+        assert_eq!(a.bytes(), b.bytes(), "test {} bytes mismatch", i);
+        assert!(a.verify_hashes(i).unwrap(), "test {} hash mismatch", i);
+    }
+    assert_eq!(read_back.tests()[1].name(), "second");
+}
@@ -0,0 +1,92 @@
+use moo::{
+    disasm::MooDisassemblyNamer,
+    prelude::*,
+    test_file::names::{MooNameFormatter, MooRenameSummary},
+    types::errors::MooError,
+};
+use std::{io::Cursor, path::Path};
+
+/// Names tests after their instruction bytes, failing on tests whose name ends in `!`.
+struct HexFormatter;
+
+impl MooNameFormatter for HexFormatter {
+    fn style(&self) -> &str {
+        "hex"
+    }
+
+    fn format_name(&mut self, _cpu_type: MooCpuType, test: &MooTest) -> Option<String> {
+        if test.name().ends_with('!') {
+            return None;
+        }
+        Some(test.bytes().iter().map(|b| format!("{:02X}", b)).collect())
+    }
+}
+
+#[test]
+pub fn test_regenerate_names() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    test_file.set_test_count(4);
+    let original: Vec<String> = test_file.tests().iter().map(|t| t.name().to_string()).collect();
+
+    *test_file.tests_mut()[1].name_mut() = "skip me!".to_string();
+    let summary = test_file.regenerate_names(&mut HexFormatter);
+    assert_eq!(
+        summary,
+        MooRenameSummary {
+            style: "hex".to_string(),
+            renamed: 3,
+            unchanged: 0,
+            failed: vec![1],
+        }
+    );
+    assert_eq!(
+        test_file.tests()[0].name(),
+        HexFormatter
+            .format_name(test_file.cpu_type(), &test_file.tests()[0])
+            .unwrap()
+    );
+    assert_eq!(test_file.tests()[1].name(), "skip me!");
+    assert!(test_file.journal().is_dirty(0) && !test_file.journal().is_dirty(1));
+
+    // Running again changes nothing, and the renames can be undone.
+    let again = test_file.regenerate_names(&mut HexFormatter);
+    assert_eq!((again.renamed, again.unchanged), (0, 3));
+    assert!(summary.changed() && !again.changed());
+    for _ in 0..3 {
+        assert!(test_file.undo());
+    }
+    assert_eq!(test_file.tests()[0].name(), original[0]);
+}
+
+/// Decodes every instruction as a one-byte `nop`, rejecting `0F`.
+struct NopDisassembler;
+
+impl MooDisassembler for NopDisassembler {
+    fn decode(&mut self, _cpu_type: MooCpuType, bytes: &[u8], _ip: u32) -> Result<MooDecodedInstruction, MooError> {
+        match bytes.first() {
+            Some(0x0F) | None => Err(MooError::DecodeError(format!("{:02X?}", bytes))),
+            Some(_) => Ok(MooDecodedInstruction::from_text(1, "lock rep NOP")),
+        }
+    }
+}
+
+#[test]
+pub fn test_disassembly_namer() {
+    let decoded = MooDecodedInstruction::from_text(3, "rep movsb");
+    assert_eq!(decoded.mnemonic, "movsb");
+    assert_eq!(MooDecodedInstruction::from_text(1, "").mnemonic, "");
+
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    test_file.set_test_count(2);
+    test_file.tests_mut()[1].bytes_mut()[0] = 0x0F;
+
+    let mut namer = MooDisassemblyNamer::new(NopDisassembler, "nop");
+    let summary = test_file.regenerate_names(&mut namer);
+    assert_eq!((summary.style.as_str(), summary.renamed), ("nop", 1));
+    assert_eq!(summary.failed, vec![1]);
+    assert_eq!(test_file.tests()[0].name(), "lock rep NOP");
+}
@@ -0,0 +1,77 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn read_input() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+fn write(test_file: &MooTestFile) -> Vec<u8> {
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).expect("Failed to write test file");
+    buf.into_inner()
+}
+
+#[test]
+pub fn test_normalize_release() {
+    let mut a = read_input();
+    let mut b = read_input();
+    let original = write(&a);
+
+    // Emit b's tests in a different order, as another generator run might.
+    let test_ct = b.test_ct();
+    for i in 0..test_ct {
+        assert!(b.move_test(test_ct - 1, i));
+    }
+    assert_eq!(b.journal_summary().tests_moved, test_ct);
+    assert_eq!(b.tests()[0].name(), a.tests()[test_ct - 1].name());
+    assert!(write(&a) != write(&b));
+
+    let summary = a.normalize(MooNormalizeProfile::Release).unwrap();
+    assert!(summary.changed());
+    assert!(summary.tests_moved > 0);
+    assert!(summary.to_string().starts_with("normalized with release profile: "));
+    b.normalize(MooNormalizeProfile::Release).unwrap();
+
+    assert!(a.tests().iter().all(|t| t.gen_metadata().is_none_or(|g| g.seed == 0)));
+    assert_eq!(a.metadata().unwrap().file_seed, 0);
+    let normalized = write(&a);
+    assert!(normalized == write(&b));
+    let read_back = MooTestFile::read(&mut Cursor::new(&normalized)).unwrap();
+    assert!(read_back.verify_hashes().unwrap().is_empty());
+
+    // Normalizing again changes nothing.
+    assert!(!a.normalize(MooNormalizeProfile::Release).unwrap().changed());
+
+    // Changes to tests are journaled; file-level redactions are not.
+    while a.undo() {}
+    let original = MooTestFile::read(&mut Cursor::new(&original)).unwrap();
+    for (test, original) in a.tests().iter().zip(original.tests()) {
+        assert_eq!(test.name(), original.name());
+        assert_eq!(
+            test.gen_metadata().map(|g| g.seed),
+            original.gen_metadata().map(|g| g.seed)
+        );
+    }
+}
+
+#[test]
+pub fn test_normalize_seeds() {
+    let mut test_file = read_input();
+    let names: Vec<String> = test_file.tests().iter().map(|t| t.name().to_string()).collect();
+    let summary = test_file.normalize(MooNormalizeProfile::Seeds).unwrap();
+    assert_eq!(summary.tests_moved, 0);
+    assert!(!summary.to_string().contains("reordered"));
+    assert!(test_file
+        .tests()
+        .iter()
+        .map(|t| t.name())
+        .eq(names.iter().map(String::as_str)));
+
+    assert_eq!(
+        "release".parse::<MooNormalizeProfile>().unwrap(),
+        MooNormalizeProfile::Release
+    );
+    assert!("shuffle".parse::<MooNormalizeProfile>().is_err());
+}
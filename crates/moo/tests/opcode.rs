@@ -0,0 +1,136 @@
+use moo::{prelude::*, types::MooCpuFamily};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_opcode_id_parse() {
+    let family = MooCpuFamily::Intel80386;
+    let id = MooOpcodeId::parse("D2.1", family).unwrap();
+    assert_eq!(id.opcode(), &[0xD2]);
+    assert_eq!(id.extension(), Some(1));
+    assert_eq!(id.to_string(), "D2.1");
+
+    let id = MooOpcodeId::from_file_name("/tests/386/0F01.4.MOO.gz", family).unwrap();
+    assert_eq!(id, MooOpcodeId::new(&[0x0F, 0x01], Some(4)));
+
+    let id = MooOpcodeId::parse("F366A5", family).unwrap();
+    assert_eq!(id.prefixes(), &[0x66, 0xF3]);
+    assert_eq!(id.opcode(), &[0xA5]);
+    assert_eq!(id.to_string(), "66F3A5");
+    assert_eq!(id.to_u32(), Some(0x66F3A5));
+    assert_eq!(MooOpcodeId::from_u32(0x66F3A5, None, family), id);
+
+    // 0x66 is only a prefix on the 386.
+    let id = MooOpcodeId::parse("66", MooCpuFamily::Intel8086).unwrap();
+    assert_eq!(id.opcode(), &[0x66]);
+    assert!(MooOpcodeId::parse("66", family).is_err());
+    assert!(MooOpcodeId::parse("D2.8", family).is_err());
+    assert!(MooOpcodeId::parse("D", family).is_err());
+
+    let mut ids: Vec<MooOpcodeId> = ["10", "0F00.1", "D2.1", "D2", "0F00.0"]
+        .iter()
+        .map(|s| MooOpcodeId::parse(s, family).unwrap())
+        .collect();
+    ids.sort();
+    let sorted: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    assert_eq!(sorted, ["0F00.0", "0F00.1", "10", "D2", "D2.1"]);
+}
+
+#[test]
+pub fn test_opcode_id_from_instruction_bytes() {
+    // ROR byte [bx], cl with a segment override.
+    let id = MooOpcodeId::from_instruction_bytes(&[0x26, 0xD2, 0x0F], MooCpuFamily::Intel8086).unwrap();
+    assert_eq!(id.to_string(), "26D2.1");
+    // SMSW ax on the 286; on the 8086, 0F is POP CS.
+    let id = MooOpcodeId::from_instruction_bytes(&[0x0F, 0x01, 0xE0], MooCpuFamily::Intel80286).unwrap();
+    assert_eq!(id.to_string(), "0F01.4");
+    let id = MooOpcodeId::from_instruction_bytes(&[0x0F, 0x01, 0xE0], MooCpuFamily::Intel8086).unwrap();
+    assert_eq!(id.to_string(), "0F");
+    // A group opcode without its ModR/M byte is truncated.
+    assert!(MooOpcodeId::from_instruction_bytes(&[0xD2], MooCpuFamily::Intel8086).is_none());
+
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let metadata = test_file.metadata().unwrap();
+    let file_id = metadata.opcode_id().unwrap();
+    assert_eq!(file_id.to_string(), "00");
+    assert!(test_file
+        .tests()
+        .iter()
+        .all(|t| t.opcode_id(test_file.cpu_type()).unwrap().opcode() == file_id.opcode()));
+
+    let mut metadata = metadata.clone();
+    metadata
+        .set_opcode_id(&MooOpcodeId::new(&[0x0F, 0xBA], Some(5)))
+        .unwrap();
+    assert_eq!(metadata.opcode, 0x0FBA);
+    assert_eq!(metadata.group_extension(), Some(5));
+}
+
+#[test]
+pub fn test_prefix_decode() {
+    let family = MooCpuFamily::Intel80386;
+    let prefixes = MooPrefix::decode(&[0x26, 0x66, 0xF3, 0xA5, 0x66], family);
+    assert_eq!(
+        prefixes,
+        [
+            MooPrefix::Segment(moo::registers::MooSegmentRegister::ES),
+            MooPrefix::OperandSize,
+            MooPrefix::Rep
+        ]
+    );
+    assert_eq!(
+        prefixes.iter().map(|p| p.byte()).collect::<Vec<_>>(),
+        [0x26, 0x66, 0xF3]
+    );
+    // 0x66 is not a prefix before the 386.
+    assert!(MooPrefix::decode(&[0x66, 0x01], MooCpuFamily::Intel80286).is_empty());
+
+    // A 0x66 ModR/M or displacement byte is not an operand size override.
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let cpu_type = test_file.cpu_type();
+    for test in test_file.tests() {
+        let expected = test.prefixes(cpu_type).contains(&MooPrefix::OperandSize);
+        assert_eq!(test.has_operand_size_override(cpu_type), expected);
+        if test.bytes()[1..].contains(&0x66) && test.bytes()[0] == 0x00 {
+            assert!(!test.has_operand_size_override(cpu_type));
+        }
+    }
+}
+
+#[test]
+pub fn test_opcode_bytes() {
+    let family = MooCpuFamily::Intel80386;
+    assert_eq!(
+        MooOpcodeId::opcode_bytes(&[0xF3, 0x66, 0x0F, 0xBA, 0xE8, 0x01], family),
+        Some(vec![0xF3, 0x66, 0x0F, 0xBA])
+    );
+    assert_eq!(
+        MooOpcodeId::opcode_bytes(&[0x0F, 0x01, 0xE0], MooCpuFamily::Intel8086),
+        Some(vec![0x0F])
+    );
+    assert!(MooOpcodeId::opcode_bytes(&[0x66, 0x0F], family).is_none());
+
+    // A file with an opcode byte sequence decodes its id from the sequence rather than the u32.
+    let mut metadata = MooFileMetadata::new(1, 0, MooCpuType::Intel80386Ex, 0, None);
+    metadata
+        .set_opcode_id(&MooOpcodeId::new(&[0x0F, 0xBA], Some(5)).with_prefix(0x66))
+        .unwrap();
+    assert_eq!(metadata.opcode_bytes(), Some(&[0x66, 0x0F, 0xBA][..]));
+    metadata.opcode = 0x0F;
+    assert_eq!(metadata.opcode_id().unwrap().to_string(), "660FBA.5");
+
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    test_file.set_metadata(metadata);
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, true).unwrap();
+    let parsed = MooTestFile::read(&mut Cursor::new(out.into_inner())).unwrap();
+    let metadata = parsed.metadata().unwrap();
+    assert_eq!(metadata.opcode_bytes(), Some(&[0x66, 0x0F, 0xBA][..]));
+    assert!(metadata.capture.is_none());
+    assert_eq!(metadata.mnemonic(), "");
+}
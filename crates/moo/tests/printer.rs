@@ -0,0 +1,50 @@
+use moo::{
+    prelude::*,
+    registers::{MooRegisters, MooRegisters16, MooRegisters32, MooRegistersPrinter},
+};
+
+#[test]
+pub fn test_register_printer_options() {
+    let mut initial = MooRegisters16::default();
+    initial.set_ax(0x1234);
+    initial.set_bx(0x5678);
+    initial.set_flags(0x0002);
+    let mut delta = MooRegisters16::default();
+    delta.set_ax(0xBEEF);
+
+    let initial = MooRegisters::Sixteen(initial);
+    let delta = MooRegisters::Sixteen(delta);
+
+    // Unset registers are dashed out only when masking is requested.
+    let text = MooRegistersPrinter::new(&initial, MooCpuType::Intel8088)
+        .with_indent(4)
+        .with_unset_masked(true)
+        .to_string();
+    assert!(text.starts_with("    AX: 1234 BX: 5678 CX: ----"));
+    assert!(text.contains("FLAGS: 0002 "));
+    let text = MooRegistersPrinter::new(&initial, MooCpuType::Intel8088).to_string();
+    assert!(text.contains("CX: 0000"));
+
+    // A delta is rehydrated against the diff before printing.
+    let text = MooRegistersPrinter::new(&delta, MooCpuType::Intel8088)
+        .with_diff(&initial)
+        .with_unset_masked(true)
+        .with_flag_expansion(false)
+        .to_string();
+    assert!(text.starts_with("AX:*BEEF BX: 5678"));
+    assert!(text.ends_with("FLAGS: 0002"));
+
+    let mut regs32 = MooRegisters32::default();
+    regs32.set_eax(0xDEADBEEF);
+    let regs32 = MooRegisters::ThirtyTwo(regs32);
+    let text = MooRegistersPrinter::new(&regs32, MooCpuType::Intel80386Ex)
+        .with_unset_masked(true)
+        .to_string();
+    assert!(text.contains("EAX: DEADBEEF EBX: --------"));
+    assert!(text.contains("CS: ----"));
+    assert!(text.ends_with("EFLAGS: --------"));
+
+    // Mismatched register widths cannot be diffed.
+    let printer = MooRegistersPrinter::new(&regs32, MooCpuType::Intel80386Ex).with_diff(&initial);
+    assert!(std::fmt::write(&mut String::new(), format_args!("{}", printer)).is_err());
+}
@@ -0,0 +1,34 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_final_queue_round_trip() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let test = &mut test_file.tests_mut()[0];
+    test.initial_state_mut().queue = vec![0x90, 0x01, 0x02];
+    test.final_state_mut().queue = vec![0x02, 0x03, 0x04, 0x05];
+
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).expect("Failed to write test file");
+    buf.set_position(0);
+    let read_back = MooTestFile::read(&mut buf).expect("Failed to read test file");
+
+    let test = &read_back.tests()[0];
+    assert_eq!(test.initial_state().queue(), &[0x90, 0x01, 0x02]);
+    assert_eq!(test.final_state().queue(), &[0x02, 0x03, 0x04, 0x05]);
+    assert_eq!(
+        test.diff_queue(),
+        Some(MooQueueDiff {
+            consumed: 2,
+            fetched:  vec![0x03, 0x04, 0x05],
+        })
+    );
+    assert_eq!(read_back.tests()[1].diff_queue(), None);
+
+    let stats = read_back.calc_stats(0);
+    assert_eq!((stats.initial_queue_len.min, stats.initial_queue_len.max), (0, 3));
+    assert_eq!((stats.final_queue_len.min, stats.final_queue_len.max), (0, 4));
+}
@@ -0,0 +1,102 @@
+use moo::types::{
+    errors::MooError,
+    ram_diff::{ram_diff, MooRamDiffPrinter},
+    MooRamEntries,
+    MooRamEntry,
+    MooRamMergePolicy,
+};
+use std::collections::BTreeMap;
+
+fn entries(pairs: &[(u32, u8)]) -> MooRamEntries {
+    let entries: Vec<MooRamEntry> = pairs
+        .iter()
+        .map(|&(address, value)| MooRamEntry { address, value })
+        .collect();
+    MooRamEntries::from(entries.as_slice())
+}
+
+fn pairs(entries: &MooRamEntries) -> Vec<(u32, u8)> {
+    entries.entries().iter().map(|e| (e.address, e.value)).collect()
+}
+
+#[test]
+pub fn test_ram_queries() {
+    let mut ram = entries(&[(0x102, 0xCC), (0x100, 0xAA), (0x200, 0xDD), (0x101, 0xBB)]);
+    assert!(ram.contains_address(0x200));
+    assert!(!ram.contains_address(0x103));
+
+    let in_range: Vec<u32> = ram.range(0x100..0x102).map(|e| e.address).collect();
+    assert_eq!(in_range, [0x100, 0x101]);
+    assert_eq!(ram.range(0x101..=0x200).count(), 3);
+
+    ram.sort_by_address();
+    assert_eq!(
+        pairs(&ram),
+        [(0x100, 0xAA), (0x101, 0xBB), (0x102, 0xCC), (0x200, 0xDD)]
+    );
+    assert_eq!(ram.get_consecutive_bytes(0), [0xAA, 0xBB, 0xCC]);
+
+    let map = ram.to_btree_map();
+    assert_eq!(map.get(&0x102), Some(&0xCC));
+    let from_map = MooRamEntries::from(&map);
+    assert_eq!(pairs(&from_map), pairs(&ram));
+    assert_eq!(from_map.entry_count, 4);
+    assert_eq!(BTreeMap::from(&from_map), map);
+}
+
+#[test]
+pub fn test_ram_merge() {
+    let base = entries(&[(0x10, 1), (0x11, 2)]);
+    let other = entries(&[(0x11, 9), (0x12, 3), (0x10, 1)]);
+
+    let mut kept = base.clone();
+    assert_eq!(kept.merge(&other, MooRamMergePolicy::Keep).unwrap(), 1);
+    assert_eq!(pairs(&kept), [(0x10, 1), (0x11, 2), (0x12, 3)]);
+    assert_eq!(kept.entry_count, 3);
+
+    let mut replaced = base.clone();
+    assert_eq!(replaced.merge(&other, MooRamMergePolicy::Replace).unwrap(), 2);
+    assert_eq!(pairs(&replaced), [(0x10, 1), (0x11, 9), (0x12, 3)]);
+
+    let mut strict = base.clone();
+    assert!(matches!(
+        strict.merge(&other, MooRamMergePolicy::Error),
+        Err(MooError::MergeError(_))
+    ));
+    assert_eq!(pairs(&strict), pairs(&base));
+
+    // Matching values are not conflicts.
+    let agreeing = entries(&[(0x10, 1), (0x13, 4)]);
+    assert_eq!(strict.merge(&agreeing, MooRamMergePolicy::Error).unwrap(), 1);
+    assert_eq!(pairs(&strict), [(0x10, 1), (0x11, 2), (0x13, 4)]);
+}
+
+#[test]
+pub fn test_ram_diff() {
+    // Code at 0x1000, and a two-byte push below SP = 0x2010 that rewrites one byte unchanged.
+    let initial = entries(&[(0x1000, 0x50), (0x200E, 0x11), (0x200F, 0x22), (0x2010, 0x33)]);
+    let fin = entries(&[(0x200E, 0x34), (0x200F, 0x22)]);
+
+    let regions = ram_diff(&initial.entries, &fin.entries);
+    assert_eq!(regions.len(), 2);
+    assert_eq!((regions[0].start, regions[0].end), (0x1000, 0x1010));
+    // The rows at 0x2000 and 0x2010 are consecutive, so they form one region.
+    assert_eq!((regions[1].start, regions[1].end), (0x2000, 0x2020));
+    assert_eq!(regions[1].bytes.len(), 3);
+    assert_eq!(regions[1].changed_ct(), 1);
+
+    let printer = MooRamDiffPrinter::new(&initial.entries, &fin.entries)
+        .with_label(0x1000, "code")
+        .with_label(0x200F, "stack");
+    let text = printer.to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "code 001000-00100F: 1 byte, 0 changed");
+    assert!(lines[1].starts_with("  001000        50 .."));
+    assert_eq!(lines[2], "stack 002000-00201F: 3 bytes, 1 changed");
+    assert!(lines[3].ends_with(" 11 22"));
+    assert!(lines[4].ends_with("*34 22"));
+    assert!(lines[5].starts_with("  002010        33 .."));
+
+    let changed_only = MooRamDiffPrinter::new(&initial.entries, &fin.entries).with_changed_only(true);
+    assert_eq!(changed_only.to_string().lines().count(), 4);
+}
@@ -0,0 +1,43 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_read_test_range() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let full = MooTestFile::read(&mut Cursor::new(&bytes)).unwrap();
+
+    let header = MooTestFile::read_header(&mut Cursor::new(&bytes)).unwrap();
+    assert_eq!(header.test_count as usize, full.test_ct());
+
+    let options = MooReadOptions::new().with_test_range(10..13);
+    let partial = MooTestFile::read_with_options(&mut Cursor::new(&bytes), &options).unwrap();
+    assert_eq!(partial.test_ct(), 3);
+    for (i, test) in partial.tests().iter().enumerate() {
+        assert_eq!(test.hash(), full.tests()[10 + i].hash());
+    }
+
+    // The last tests, from a compressed copy.
+    let mut gz = Cursor::new(Vec::new());
+    full.write_with_options(
+        &mut gz,
+        &MooWriteOptions::new().with_compression(MooCompressionKind::Gzip),
+    )
+    .unwrap();
+    let gz = gz.into_inner();
+    let count = MooTestFile::read_header(&mut Cursor::new(&gz)).unwrap().test_count as usize;
+    assert_eq!(count, full.test_ct());
+
+    let options = MooReadOptions::new().with_test_range(count - 2..count + 5);
+    let tail = MooTestFile::read_with_options(&mut Cursor::new(&gz), &options).unwrap();
+    assert_eq!(tail.test_ct(), 2);
+    assert_eq!(tail.tests()[1].hash(), full.tests().last().unwrap().hash());
+
+    let options = MooReadOptions::new().with_test_range(count..count + 1);
+    assert_eq!(
+        MooTestFile::read_with_options(&mut Cursor::new(&bytes), &options)
+            .unwrap()
+            .test_ct(),
+        0
+    );
+}
@@ -0,0 +1,71 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn load() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+fn round_trip(test_file: &MooTestFile) -> MooTestFile {
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).expect("Failed to write test file");
+    buf.set_position(0);
+    MooTestFile::read(&mut buf).expect("Failed to read test file")
+}
+
+#[test]
+pub fn test_redaction_selectors() {
+    for text in [
+        "GMET",
+        "DGST",
+        "LICN",
+        "xser",
+        "GMET.seed",
+        "META.file_seed",
+        "META.board_id",
+        "META.capture_tool",
+    ] {
+        let redaction: MooRedaction = text.parse().expect(text);
+        assert_eq!(redaction.to_string(), text);
+    }
+    for text in ["CYCL", "NAME", "META.opcode", "GMETX"] {
+        assert!(text.parse::<MooRedaction>().is_err(), "accepted {}", text);
+    }
+}
+
+#[test]
+pub fn test_redact_preserves_unchanged_hashes() {
+    let mut test_file = load();
+    test_file.tests_mut()[0].add_tag("private");
+    let hashes: Vec<_> = test_file.tests().iter().map(|t| *t.hash().unwrap()).collect();
+    let seeded: Vec<bool> = test_file
+        .tests()
+        .iter()
+        .map(|t| t.seed().is_some_and(|s| s != 0))
+        .collect();
+
+    assert!(seeded.iter().any(|s| *s));
+
+    // Tags are not hashed, so removing them keeps every hash.
+    let summary = test_file.redact(&[MooRedaction::Chunk(*b"TAGS")]);
+    assert_eq!(summary.tests_redacted, 1);
+    assert_eq!(summary.tests_rehashed, 0);
+    let read_back = round_trip(&test_file);
+    assert!(read_back.tests()[0].tags().is_empty());
+    assert!(read_back.tests().iter().zip(&hashes).all(|(t, h)| t.hash() == Some(h)));
+
+    // Clearing generator seeds re-hashes only the tests that had one.
+    let summary = test_file.redact(&[MooRedaction::GeneratorSeed, MooRedaction::FileSeed]);
+    assert_eq!(summary.tests_rehashed, seeded.iter().filter(|s| **s).count());
+    let read_back = round_trip(&test_file);
+    assert_eq!(read_back.metadata().unwrap().file_seed, 0);
+    for (i, test) in read_back.tests().iter().enumerate() {
+        assert_eq!(test.hash() != Some(&hashes[i]), seeded[i], "test {}", i);
+        assert!(test.verify_hashes(i).unwrap());
+        assert!(test.seed().is_none_or(|s| s == 0));
+    }
+
+    // A second pass finds nothing left to remove.
+    assert!(!test_file.redact(&[MooRedaction::GeneratorSeed]).changed());
+}
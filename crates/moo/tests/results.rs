@@ -0,0 +1,79 @@
+use moo::results::{MooMismatchCategory, MooResultFile, MooTestResult, MooTestResultStatus};
+use std::io::Cursor;
+
+#[test]
+pub fn test_result_file_round_trip() {
+    let mut results = MooResultFile::new("martypc", "0.4.0");
+    results.insert(MooTestResult::pass([1; 20]));
+    results.insert(MooTestResult::fail([2; 20], MooMismatchCategory::CycleCount).with_detail("24 != 25"));
+
+    let mut buf = Cursor::new(Vec::new());
+    results.write(&mut buf).unwrap();
+    buf.set_position(0);
+    let read = MooResultFile::read(&mut buf).unwrap();
+
+    assert_eq!(read.emulator(), "martypc");
+    assert_eq!(read.emulator_version(), "0.4.0");
+    assert_eq!(read.len(), 2);
+    let failed = read.get(&[2; 20]).unwrap();
+    assert_eq!(failed.status, MooTestResultStatus::Fail);
+    assert_eq!(failed.category, MooMismatchCategory::CycleCount);
+    assert_eq!(failed.detail, "24 != 25");
+
+    let summary = read.summary();
+    assert_eq!((summary.passed, summary.failed), (1, 1));
+    assert_eq!(summary.categories.get(&MooMismatchCategory::CycleCount), Some(&1));
+}
+
+#[test]
+pub fn test_result_file_diff() {
+    let mut old = MooResultFile::new("emu", "1");
+    old.insert(MooTestResult::fail([1; 20], MooMismatchCategory::Registers));
+    old.insert(MooTestResult::pass([2; 20]));
+    old.insert(MooTestResult::fail([3; 20], MooMismatchCategory::Memory));
+    old.insert(MooTestResult::pass([4; 20]));
+
+    let mut new = MooResultFile::new("emu", "2");
+    new.insert(MooTestResult::pass([1; 20]));
+    new.insert(MooTestResult::fail([2; 20], MooMismatchCategory::CycleBus));
+    new.insert(MooTestResult::fail([3; 20], MooMismatchCategory::Registers));
+    new.insert(MooTestResult::pass([5; 20]));
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.fixed, vec![[1; 20]]);
+    assert_eq!(diff.regressed, vec![[2; 20]]);
+    assert_eq!(diff.changed, vec![[3; 20]]);
+    assert_eq!(diff.removed, vec![[4; 20]]);
+    assert_eq!(diff.added, vec![[5; 20]]);
+}
+
+#[test]
+pub fn test_expected_failures() {
+    use moo::results::expected::MooExpectedFailures;
+
+    let text = "# known issues\n\
+                0101010101010101010101010101010101010101 DIV timing\n\
+                \n\
+                0303030303030303030303030303030303030303\n\
+                0404040404040404040404040404040404040404 not run\n";
+    let expected = MooExpectedFailures::parse(text.as_bytes()).unwrap();
+    assert_eq!(expected.len(), 3);
+    assert_eq!(expected.reason(&[1; 20]), Some("DIV timing"));
+    assert_eq!(expected.reason(&[3; 20]), Some(""));
+
+    let mut results = MooResultFile::new("emu", "1");
+    results.insert(MooTestResult::fail([1; 20], MooMismatchCategory::CycleCount));
+    results.insert(MooTestResult::fail([2; 20], MooMismatchCategory::Registers));
+    results.insert(MooTestResult::pass([3; 20]));
+
+    let report = expected.check(&results);
+    assert!(!report.is_ok());
+    assert_eq!(report.expected_failures, vec![[1; 20]]);
+    assert_eq!(report.unexpected_failures, vec![[2; 20]]);
+    assert_eq!(report.stale, vec![[3; 20]]);
+    assert_eq!(report.missing, vec![[4; 20]]);
+
+    assert!(MooExpectedFailures::parse("xyz reason".as_bytes()).is_err());
+    let reparsed = MooExpectedFailures::parse(expected.to_text().as_bytes()).unwrap();
+    assert_eq!(reparsed.len(), 3);
+}
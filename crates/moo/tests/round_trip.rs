@@ -1,4 +1,7 @@
-use moo::test_file::MooTestFile;
+use moo::{
+    prelude::{MooCompressionKind, MooWriteOptions},
+    test_file::MooTestFile,
+};
 use std::{
     fs::{self, File},
     io::{BufReader, BufWriter},
@@ -20,6 +23,90 @@ pub fn test_round_trip() {
 //     round_trip(input_file);
 // }
 
+#[test]
+pub fn test_write_to_path() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let original_content = fs::read(&input_file).expect("Failed to read original file");
+    let mut test_file = MooTestFile::read(&mut BufReader::new(File::open(&input_file).unwrap())).unwrap();
+
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let options = MooWriteOptions::new().with_preserve_hash(true);
+
+    // Parent directories are created, and the extension selects compression.
+    let gz_file = temp_dir.path().join("nested/dir/00.MOO.gz");
+    test_file
+        .write_to_path(&gz_file, &options)
+        .expect("Failed to write compressed file");
+    assert!(test_file.compressed());
+    let gz_content = fs::read(&gz_file).unwrap();
+    assert_eq!(&gz_content[..2], &[0x1F, 0x8B]);
+
+    let plain_file = temp_dir.path().join("nested/dir/00.MOO");
+    test_file
+        .write_to_path(&plain_file, &options)
+        .expect("Failed to write file");
+    assert!(!test_file.compressed());
+    assert_eq!(fs::read(&plain_file).unwrap(), original_content);
+
+    // Replacing an existing file leaves no temporary files behind.
+    test_file
+        .write_to_path(&plain_file, &options)
+        .expect("Failed to replace file");
+    assert_eq!(fs::read_dir(plain_file.parent().unwrap()).unwrap().count(), 2);
+
+    let zst_file = temp_dir.path().join("00.MOO.zst");
+    test_file
+        .write_to_path(&zst_file, &options)
+        .expect("Failed to write zstd file");
+    assert_eq!(test_file.compression(), MooCompressionKind::Zstd);
+    let zst_content = fs::read(&zst_file).unwrap();
+    assert_eq!(&zst_content[..4], &[0x28, 0xB5, 0x2F, 0xFD]);
+
+    let read_back = MooTestFile::read(&mut BufReader::new(File::open(&zst_file).unwrap())).unwrap();
+    assert_eq!(read_back.compression(), MooCompressionKind::Zstd);
+    assert_eq!(
+        MooTestFile::read_header(&mut BufReader::new(File::open(&zst_file).unwrap()))
+            .unwrap()
+            .test_count as usize,
+        read_back.test_ct()
+    );
+    let mut plain = Vec::new();
+    read_back
+        .write_stream(
+            &mut plain,
+            &options.clone().with_compression(MooCompressionKind::Uncompressed),
+        )
+        .unwrap();
+    assert_eq!(plain, original_content);
+}
+
+#[test]
+pub fn test_read_concatenated_gzip() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::{Cursor, Write};
+
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let original_content = fs::read(&input_file).expect("Failed to read original file");
+    let original = MooTestFile::read(&mut Cursor::new(&original_content)).unwrap();
+
+    // Compress each half of the file as a separate gzip member and concatenate them.
+    let mut concatenated = Vec::new();
+    let (first, second) = original_content.split_at(original_content.len() / 2);
+    for part in [first, second] {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(part).unwrap();
+        concatenated.extend(encoder.finish().unwrap());
+    }
+
+    let test_file = MooTestFile::read(&mut Cursor::new(concatenated)).expect("Failed to read concatenated gzip");
+    assert!(test_file.compressed());
+    assert_eq!(test_file.test_ct(), original.test_ct());
+    assert_eq!(
+        test_file.tests().last().unwrap().name(),
+        original.tests().last().unwrap().name()
+    );
+}
+
 pub fn round_trip(input_file: PathBuf) {
     println!("Input file: {}", input_file.to_string_lossy());
 
@@ -0,0 +1,26 @@
+use moo::{prelude::*, samples};
+use std::io::Cursor;
+
+#[test]
+pub fn test_sample_round_trip() {
+    let test_file = samples::sample_test_file();
+    assert_eq!(test_file.cpu_type().to_str(), samples::SAMPLE_CPU_TYPE.to_str());
+    assert!(test_file.arch_matches_cpu_type());
+
+    let mut buf = Cursor::new(Vec::new());
+    test_file.write(&mut buf, true).unwrap();
+    let bytes = buf.into_inner();
+    let read_back = MooTestFile::read(&mut Cursor::new(&bytes)).unwrap();
+    assert_eq!(read_back.test_ct(), 1);
+    assert_eq!(read_back.metadata().unwrap().test_ct, 1);
+
+    let test = &read_back.tests()[0];
+    assert_eq!(test.bytes(), &[0x40]);
+    assert_eq!(test.cycles().len(), 4);
+    assert!(test.cycles()[0].ale());
+    assert_eq!(test.final_state().queue(), &[0x90]);
+
+    let mut rewritten = Cursor::new(Vec::new());
+    read_back.write(&mut rewritten, true).unwrap();
+    assert!(rewritten.into_inner() == bytes);
+}
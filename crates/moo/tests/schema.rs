@@ -0,0 +1,87 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+const SCHEMA: &str = "\
+# moo-schema 1
+op,ex,g,ct,mnemonic,f_umask
+*,,,500,\"default, all\",
+00,,,,ADD,08D5
+80,,1,,GRP1,
+80,7,1,250,CMP,0x08D5
+";
+
+#[test]
+pub fn test_schema_rules() {
+    let schema = MooSchema::from_reader(Cursor::new(SCHEMA)).expect("Failed to parse schema");
+    assert_eq!(schema.records().len(), 3);
+
+    let rules = schema.rules(0x00, None).unwrap();
+    assert_eq!(rules.count, Some(500));
+    assert_eq!(rules.flag_mask, Some(0x08D5));
+
+    // Extension 7 has its own record; extension 0 falls back to the opcode record.
+    assert_eq!(schema.rules(0x80, Some(7)).unwrap().count, Some(250));
+    let rules = schema.rules(0x80, Some(0)).unwrap();
+    assert_eq!(rules.count, Some(500));
+    assert_eq!(rules.group, Some(1));
+    assert_eq!(rules.flag_mask, None);
+
+    // Unlisted opcodes get the defaults.
+    assert_eq!(schema.rules(0x90, None).unwrap().count, Some(500));
+}
+
+#[test]
+pub fn test_schema_validation() {
+    let bad = [
+        "op,ex,ct\n00,,10\n00,,20\n",
+        "op,ex,ct\n00,8,10\n",
+        "op,ct\n00,0\n",
+        "op,ct\n0G,1\n",
+        "# moo-schema 2\nop\n",
+        "ex,ct\n",
+    ];
+    for text in bad {
+        assert!(
+            MooSchema::from_reader(Cursor::new(text)).is_err(),
+            "accepted:\n{}",
+            text
+        );
+    }
+
+    let err = MooSchema::from_reader(Cursor::new("op,ct\n00,1\n01,x\n")).unwrap_err();
+    assert!(err.to_string().contains("line 3"), "{}", err);
+}
+
+#[test]
+pub fn test_schema_apply() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let test_ct = test_file.test_ct();
+
+    let schema = MooSchema::from_reader(Cursor::new(SCHEMA)).unwrap();
+    assert!(schema.apply_flag_mask(&mut test_file));
+    match test_file.register_mask() {
+        Some(MooRegisters::ThirtyTwo(mask)) => assert_eq!(mask.eflags, 0xFFFF_08D5),
+        other => panic!("unexpected register mask: {:?}", other),
+    }
+
+    // The file has fewer tests than the schema count, so it is not trimmed.
+    assert!(!schema.apply_trim(&mut test_file));
+    assert_eq!(test_file.test_ct(), test_ct);
+
+    let mut schema = MooSchema::new();
+    schema
+        .add_record(MooSchemaRecord::new(
+            0x00,
+            None,
+            MooSchemaRules {
+                count: Some(2),
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+    assert!(!schema.apply_flag_mask(&mut test_file));
+    assert!(schema.apply_trim(&mut test_file));
+    assert_eq!(test_file.test_ct(), 2);
+}
@@ -0,0 +1,78 @@
+use std::{fs, path::Path};
+
+use moo::prelude::{MooFileSearch, MooGlob};
+use tempfile::tempdir;
+
+fn touch(root: &Path, relative: &str) {
+    let path = root.join(relative);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, b"").unwrap();
+}
+
+fn relative_names(root: &Path, search: MooFileSearch) -> Vec<String> {
+    search
+        .collect()
+        .unwrap()
+        .iter()
+        .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+        .collect()
+}
+
+#[test]
+fn test_file_search() {
+    let temp_dir = tempdir().unwrap();
+    let root = temp_dir.path();
+    for file in [
+        "00.MOO",
+        "notes.txt",
+        "8088/01.MOO.gz",
+        "8088/v2/02.moo.gz",
+        "286/03.MOO",
+        "old/8088/04.MOO.gz",
+    ] {
+        touch(root, file);
+    }
+
+    assert_eq!(relative_names(root, MooFileSearch::new(root)), ["00.MOO"]);
+    assert_eq!(
+        relative_names(root, MooFileSearch::new(root).with_recursion()),
+        [
+            "00.MOO",
+            "286/03.MOO",
+            "8088/01.MOO.gz",
+            "8088/v2/02.moo.gz",
+            "old/8088/04.MOO.gz"
+        ]
+    );
+    assert_eq!(
+        relative_names(root, MooFileSearch::new(root).with_max_depth(Some(2))),
+        ["00.MOO", "286/03.MOO", "8088/01.MOO.gz"]
+    );
+    assert_eq!(
+        relative_names(
+            root,
+            MooFileSearch::new(root)
+                .with_recursion()
+                .with_include([MooGlob::new("**/8088/*.moo.gz")])
+                .with_exclude([MooGlob::new("old")])
+        ),
+        ["8088/01.MOO.gz"]
+    );
+
+    // A file root is returned as-is.
+    let file = root.join("notes.txt");
+    assert_eq!(MooFileSearch::new(&file).collect().unwrap(), [file]);
+}
+
+#[test]
+fn test_glob_matching() {
+    let glob = MooGlob::new("**/8088/*.moo.gz");
+    assert!(glob.matches(Path::new("8088/00.MOO.gz")));
+    assert!(glob.matches(Path::new("a/b/8088/D2.1.MOO.gz")));
+    assert!(!glob.matches(Path::new("8088/00.MOO")));
+    assert!(!glob.matches(Path::new("8088/sub/00.MOO.gz")));
+
+    let name_glob = MooGlob::new("D?.*.moo");
+    assert!(name_glob.matches(Path::new("any/dir/D2.1.MOO")));
+    assert!(!name_glob.matches(Path::new("D20.1.MOO")));
+}
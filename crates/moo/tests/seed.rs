@@ -0,0 +1,76 @@
+use moo::{
+    prelude::*,
+    test_file::seed::derive_test_seed,
+    types::{errors::MooError, MooTestState},
+};
+use std::{io::Cursor, path::Path};
+
+/// A generator that records the seed it was asked for and returns an empty state.
+struct RecordingGenerator(Option<u64>);
+
+impl MooStateGenerator for RecordingGenerator {
+    type Error = MooError;
+
+    fn initial_state(&mut self, _: MooCpuType, _: &MooOpcodeId, seed: u64) -> Result<MooTestState, MooError> {
+        self.0 = Some(seed);
+        Ok(MooTestState::default())
+    }
+}
+
+#[test]
+pub fn test_regenerate_from_seed() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let opcode = MooOpcodeId::new(&[0x00], None);
+    assert_eq!(derive_test_seed(1, &opcode, 3), derive_test_seed(1, &opcode, 3));
+    assert_ne!(derive_test_seed(1, &opcode, 3), derive_test_seed(1, &opcode, 4));
+
+    // A recorded seed takes precedence over one derived from the file seed.
+    let recorded = test_file.tests()[2].seed();
+    let file_seed = test_file.metadata().unwrap().file_seed;
+    let expected = recorded.unwrap_or_else(|| derive_test_seed(file_seed, &opcode, 2));
+    assert_eq!(test_file.test_seed(2), Some(expected));
+
+    let mut generator = RecordingGenerator(None);
+    test_file.regenerate_initial_state(2, &mut generator).unwrap();
+    assert_eq!(generator.0, Some(expected));
+
+    assert!(test_file.test_seed(test_file.test_ct()).is_none());
+    assert!(test_file
+        .regenerate_initial_state(test_file.test_ct(), &mut generator)
+        .is_err());
+
+    // Without metadata, only recorded seeds are available.
+    test_file.metadata_mut().unwrap().opcode = 0xFFFF_FFFF;
+    assert_eq!(test_file.test_seed(2), recorded);
+    assert!(test_file.regenerate_initial_state(2, &mut generator).is_err());
+}
+
+#[test]
+pub fn test_gen_metadata_display() {
+    let gen_metadata = MooTestGenMetadata::new(0x0123_4567_89AB_CDEF, 2);
+    assert_eq!(gen_metadata.to_string(), "seed 0123456789ABCDEF, 2 generations");
+    assert_eq!(
+        MooTestGenMetadata::new(1, 1).to_string(),
+        "seed 0000000000000001, 1 generation"
+    );
+}
+
+#[test]
+pub fn test_gen_metadata_chunk_too_short() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let mut bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let gmet = bytes
+        .windows(4)
+        .position(|w| w == b"GMET")
+        .expect("Test file should contain a GMET chunk");
+    bytes[gmet + 4..gmet + 8].copy_from_slice(&8u32.to_le_bytes());
+
+    let err = match MooTestFile::read(&mut Cursor::new(bytes)) {
+        Ok(_) => panic!("Short GMET chunk should fail to parse"),
+        Err(e) => e,
+    };
+    assert!(err.to_string().contains("GMET"), "{}", err);
+}
@@ -0,0 +1,61 @@
+use moo::{
+    prelude::*,
+    types::stack::{MooStackDirection, MooStackSlotKind},
+};
+use std::{fs::File, io::BufReader, path::Path};
+
+fn golden(name: &str) -> MooTestFile {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/test_data/golden")
+        .join(name);
+    let mut reader = BufReader::new(File::open(&path).unwrap());
+    MooTestFile::read(&mut reader).unwrap()
+}
+
+#[test]
+pub fn test_stack_frame_interrupt() {
+    for name in ["8086.MOO", "386E.MOO"] {
+        let test_file = golden(name);
+        let cpu_type = test_file.cpu_type();
+
+        // `add [bx+si],al` leaves SP alone.
+        assert!(test_file.tests()[0].stack_frame(cpu_type).is_none());
+
+        let frame = test_file.tests()[1].stack_frame(cpu_type).unwrap();
+        assert_eq!(frame.direction, MooStackDirection::Pushed);
+        assert_eq!((frame.initial_sp, frame.final_sp), (0x0100, 0x00FA));
+
+        let slots: Vec<_> = frame
+            .slots
+            .iter()
+            .map(|slot| (slot.address, slot.value, slot.kind))
+            .collect();
+        assert_eq!(
+            slots,
+            [
+                (0x600FE, Some(0x0302), MooStackSlotKind::Flags),
+                (0x600FC, Some(0x1000), MooStackSlotKind::ReturnCs),
+                (0x600FA, Some(0x0001), MooStackSlotKind::ReturnIp),
+            ]
+        );
+        assert!(frame.slots.iter().all(|slot| slot.width == 2));
+        assert_eq!(frame.find(MooStackSlotKind::ReturnIp).unwrap().offset, 0x00FA);
+        assert_eq!(frame.slots[0].to_string(), "0600FE [SP=00FE]: 0302 flags");
+    }
+}
+
+#[test]
+pub fn test_stack_frame_large_move() {
+    let mut test_file = golden("8086.MOO");
+    let cpu_type = test_file.cpu_type();
+    let test = &mut test_file.tests_mut()[1];
+    if let MooRegisters::Sixteen(regs) = test.final_state_mut().regs_mut() {
+        regs.set_sp(0x8000);
+    }
+
+    // A move this far is reported, but not decoded into slots.
+    let frame = test.stack_frame(cpu_type).unwrap();
+    assert_eq!(frame.direction, MooStackDirection::Popped);
+    assert_eq!((frame.initial_sp, frame.final_sp), (0x0100, 0x8000));
+    assert!(frame.slots.is_empty());
+}
@@ -0,0 +1,184 @@
+use moo::{
+    prelude::*,
+    stats::{DEFAULT_CYCLE_BUCKET, DEFAULT_OUTLIER_SIGMA},
+    test_file::journal::MooTestEdit,
+};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_stats_accumulator() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let stats = test_file.calc_stats(0);
+    assert_eq!(stats.test_count, test_file.test_ct());
+
+    // Feeding the same tests twice doubles the totals but leaves the ranges unchanged.
+    let mut accumulator = MooStatsAccumulator::new(test_file.cpu_type(), 0);
+    for test in test_file.tests().iter().chain(test_file.tests()) {
+        accumulator.add(test);
+    }
+    assert_eq!(accumulator.test_count(), stats.test_count * 2);
+    let doubled = accumulator.finish();
+    assert_eq!(doubled.total_cycles, stats.total_cycles * 2);
+    assert_eq!(doubled.min_cycles, stats.min_cycles);
+    assert_eq!(doubled.max_cycles, stats.max_cycles);
+    assert_eq!(doubled.avg_cycles, stats.avg_cycles);
+    assert_eq!(doubled.mem_reads.total, stats.mem_reads.total * 2);
+    assert_eq!(doubled.mem_reads.max, stats.mem_reads.max);
+    assert_eq!(doubled.registers_modified, stats.registers_modified);
+    assert_eq!(doubled.flags_modified, stats.flags_modified);
+    assert_eq!(doubled.exceptions_seen.len(), stats.exceptions_seen.len() * 2);
+}
+
+#[test]
+pub fn test_ram_footprint_stats() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let stats = test_file.calc_stats(0);
+
+    let seeded: usize = test_file.tests().iter().map(|t| t.initial_state().ram().len()).sum();
+    assert_eq!(stats.ram_seeded.total, test_file.test_ct());
+    assert_eq!(stats.ram_seeded.sum, seeded);
+    assert!((stats.ram_seeded.avg() - seeded as f64 / test_file.test_ct() as f64).abs() < 1e-9);
+
+    // The footprint covers both states, so it is never smaller than either.
+    assert!(stats.ram_footprint.max >= stats.ram_seeded.max);
+    assert!(stats.ram_footprint.max >= stats.ram_touched.max);
+    assert!(stats.ram_footprint.sum <= stats.ram_seeded.sum + stats.ram_touched.sum);
+    assert!(stats.ram_footprint.min > 0);
+}
+
+#[test]
+pub fn test_corpus_summary() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let stats = test_file.calc_stats(0);
+
+    let summary = moo::stats::corpus_summary([&test_file, &test_file]);
+    assert_eq!(summary.total.file_count, 2);
+    assert_eq!(summary.total.test_count, stats.test_count * 2);
+    assert_eq!(summary.total.exception_count, stats.exceptions_seen.len() * 2);
+    assert!((summary.total.avg_cycles() - stats.total_cycles as f64 / stats.test_count as f64).abs() < 1e-9);
+
+    let mnemonic = test_file.metadata().expect("No metadata").mnemonic();
+    assert_eq!(summary.by_mnemonic.len(), 1);
+    assert_eq!(summary.by_mnemonic[&mnemonic], summary.total);
+    if let Some(opcode) = test_file.metadata().and_then(|m| m.opcode_id()) {
+        assert_eq!(summary.by_opcode[&opcode], summary.total);
+    }
+
+    // A file without a mnemonic or opcode is only counted in the total.
+    let mut partial = MooCorpusSummary::new();
+    partial.add_stats(None, None, &stats);
+    assert_eq!(partial.total.test_count, stats.test_count);
+    assert!(partial.by_mnemonic.is_empty() && partial.by_opcode.is_empty());
+}
+
+#[test]
+pub fn test_cycle_outliers() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    // Pad one test's cycles far beyond the rest of its group.
+    let outliers_before = test_file.cycle_outliers(DEFAULT_OUTLIER_SIGMA);
+    let index = (0..test_file.test_ct())
+        .find(|i| !outliers_before.iter().any(|o| o.index == *i))
+        .expect("No regular test");
+    let mut cycles: Vec<MooCycleState> = test_file.tests()[index].cycles().to_vec();
+    let last = *cycles.last().expect("No cycles");
+    cycles.extend(std::iter::repeat_n(last, 1000));
+    assert!(test_file.edit_test(index, MooTestEdit::SetCycles(cycles)));
+
+    let outliers = test_file.cycle_outliers(DEFAULT_OUTLIER_SIGMA);
+    let outlier = outliers
+        .iter()
+        .find(|o| o.index == index)
+        .expect("Padded test not flagged");
+    assert!(outlier.sigma > DEFAULT_OUTLIER_SIGMA);
+    assert!(outlier.cycles as f64 > outlier.mean);
+    let form = MooAddressingForm::of(&test_file.tests()[index], test_file.cpu_type());
+    assert_eq!(outlier.form, form);
+}
+
+#[test]
+pub fn test_coverage_clusters() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let clusters = test_file.coverage_clusters(DEFAULT_CYCLE_BUCKET);
+    assert_eq!(
+        clusters.iter().map(|c| c.tests.len()).sum::<usize>(),
+        test_file.test_ct()
+    );
+    assert!(clusters.windows(2).all(|w| w[0].tests[0] < w[1].tests[0]));
+
+    for cluster in &clusters {
+        assert!(cluster.tests.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(cluster.redundant(1).len(), cluster.tests.len() - 1);
+        assert!(cluster.redundant(cluster.tests.len() + 1).is_empty());
+        for &index in &cluster.tests {
+            let cycles = test_file.tests()[index].cycles().len();
+            assert!(cluster.key.cycles.contains(&cycles));
+        }
+    }
+
+    // A single cycle count range splits clusters no further than the default.
+    let coarse = test_file.coverage_clusters(usize::MAX / 2);
+    assert!(coarse.len() <= clusters.len());
+}
+
+#[test]
+pub fn test_pipelined_bus_counts() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let template = test_file
+        .tests()
+        .iter()
+        .find(|t| t.exception().is_none())
+        .expect("No test without an exception");
+
+    let cycle = |ads: bool, bus_state: u8, memory_status: u8, io_status: u8| MooCycleState {
+        pins0: if ads { MooCycleState::PIN_ALE } else { 0 },
+        bus_state,
+        memory_status,
+        io_status,
+        ..Default::default()
+    };
+    // A code fetch, memory read, memory write and IO read, each ADS asserted while the previous
+    // bus cycle's strobe is still active.
+    let cycles = [
+        cycle(true, 4, 0, 0),
+        cycle(true, 6, MooCycleState::MRDC_BIT, 0),
+        cycle(false, 6, MooCycleState::MRDC_BIT, 0),
+        cycle(true, 7, MooCycleState::MRDC_BIT, 0),
+        cycle(false, 7, MooCycleState::MWTC_BIT, 0),
+        cycle(true, 2, MooCycleState::MWTC_BIT, 0),
+        cycle(false, 2, 0, MooCycleState::IORC_BIT),
+    ];
+    let test = MooTest::new(
+        template.name().to_string(),
+        None,
+        template.bytes(),
+        template.initial_state().clone(),
+        template.final_state().clone(),
+        &cycles,
+        None,
+        None,
+    );
+
+    let mut accumulator = MooStatsAccumulator::new(MooCpuType::Intel80386Ex, 0);
+    accumulator.add(&test);
+    let stats = accumulator.finish();
+    assert_eq!(stats.code_fetches.max, 1);
+    assert_eq!(stats.mem_reads.max, 1);
+    assert_eq!(stats.mem_writes.max, 1);
+    assert_eq!(stats.io_reads.max, 1);
+    assert_eq!(stats.io_writes.max, 0);
+}
@@ -0,0 +1,189 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn dump(test_file: &MooTestFile, options: &MooDumpOptions) -> String {
+    let mut out = Vec::new();
+    test_file
+        .dump_text(&mut out, options)
+        .expect("Failed to dump test file");
+    String::from_utf8(out).expect("Dump should be valid UTF-8")
+}
+
+fn write(test_file: &MooTestFile) -> Vec<u8> {
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, true).expect("Failed to write test file");
+    out.into_inner()
+}
+
+#[test]
+pub fn test_text_dump_round_trip() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let text = dump(&test_file, &MooDumpOptions::default());
+    assert!(text.starts_with("MOO-TEXT 1\n"));
+
+    let parsed = MooTestFile::parse_text(&mut text.as_bytes()).expect("Failed to parse text dump");
+    assert_eq!(parsed.test_ct(), test_file.test_ct());
+    assert_eq!(dump(&parsed, &MooDumpOptions::default()), text);
+    assert_eq!(write(&parsed), write(&test_file));
+
+    // Without cycles, the dump still parses, but tests have no cycles.
+    let text = dump(&test_file, &MooDumpOptions::new().with_cycles(false));
+    let parsed = MooTestFile::parse_text(&mut text.as_bytes()).expect("Failed to parse text dump");
+    assert!(parsed.tests().iter().all(|t| t.cycles().is_empty()));
+}
+
+#[test]
+pub fn test_text_dump_reports_line() {
+    let text = "MOO-TEXT 1\nversion 1 1\narch \"8088\"\n\ntest 0\n  bogus\nend\n";
+    let err = match MooTestFile::parse_text(&mut text.as_bytes()) {
+        Ok(_) => panic!("Invalid dump should fail to parse"),
+        Err(e) => e,
+    };
+    assert!(err.to_string().contains("line 6"), "{}", err);
+}
+
+#[test]
+pub fn test_text_dump_rejects_bad_counts() {
+    // A cycle count far beyond the lines present fails cleanly instead of allocating for it.
+    let text = "MOO-TEXT 1\nversion 1 1\narch \"8088\"\n\ntest 0\n  cycles 18446744073709551615\nend\n";
+    assert!(MooTestFile::parse_text(&mut text.as_bytes()).is_err());
+
+    let text = "MOO-TEXT 1\nversion 1 9\narch \"8088\"\n";
+    let err = MooTestFile::parse_text(&mut text.as_bytes()).err().unwrap();
+    assert!(err.to_string().contains("unsupported MOO version 1.9"), "{}", err);
+}
+
+#[test]
+pub fn test_timing_context_round_trip() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    // Files written before timing was introduced read without it.
+    assert!(test_file.metadata().unwrap().timing.is_none());
+
+    let timing = MooTimingContext::new(4_772_727)
+        .with_refresh_period(72)
+        .with_board_id("ARDUINO");
+    test_file.metadata_mut().unwrap().timing = Some(timing.clone());
+
+    let parsed = MooTestFile::read(&mut Cursor::new(write(&test_file))).expect("Failed to re-read test file");
+    let metadata = parsed.metadata().unwrap();
+    assert_eq!(metadata.timing.as_ref(), Some(&timing));
+    assert_eq!(metadata.clock_hz(), Some(4_772_727));
+    assert_eq!(metadata.refresh_period(), Some(72));
+    assert_eq!(metadata.board_id().as_deref(), Some("ARDUINO"));
+    assert_eq!(parsed.test_ct(), test_file.test_ct());
+
+    let text = dump(&test_file, &MooDumpOptions::default());
+    let parsed = MooTestFile::parse_text(&mut text.as_bytes()).expect("Failed to parse text dump");
+    assert_eq!(parsed.metadata().unwrap().timing.as_ref(), Some(&timing));
+}
+
+#[test]
+pub fn test_capture_info_round_trip() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    assert_eq!(
+        test_file.metadata().unwrap().capture_source(),
+        Some(MooCaptureSource::Unspecified)
+    );
+
+    // Capture info without timing still reads back, with timing absent.
+    let capture = MooCaptureInfo::new(MooCaptureSource::Hardware, "ArduinoX86");
+    test_file.metadata_mut().unwrap().capture = Some(capture.clone());
+
+    let parsed = MooTestFile::read(&mut Cursor::new(write(&test_file))).expect("Failed to re-read test file");
+    let metadata = parsed.metadata().unwrap();
+    assert!(metadata.timing.is_none());
+    assert_eq!(metadata.capture.as_ref(), Some(&capture));
+    assert_eq!(metadata.capture_tool().as_deref(), Some("ArduinoX86"));
+
+    let text = dump(&test_file, &MooDumpOptions::default());
+    let parsed = MooTestFile::parse_text(&mut text.as_bytes()).expect("Failed to parse text dump");
+    assert_eq!(
+        parsed.metadata().unwrap().capture_source(),
+        Some(MooCaptureSource::Hardware)
+    );
+
+    // A source from a newer writer still reads, and is kept as written.
+    test_file.metadata_mut().unwrap().capture.as_mut().unwrap().source = 0x7F;
+    let parsed = MooTestFile::read(&mut Cursor::new(write(&test_file))).expect("Failed to re-read test file");
+    let metadata = parsed.metadata().unwrap();
+    assert_eq!(metadata.capture_source(), None);
+    assert_eq!(metadata.capture.as_ref().unwrap().source, 0x7F);
+    assert_eq!(
+        metadata.capture.as_ref().unwrap().to_string(),
+        "unknown (127) (ArduinoX86)"
+    );
+
+    let text = dump(&test_file, &MooDumpOptions::default());
+    let parsed = MooTestFile::parse_text(&mut text.as_bytes()).expect("Failed to parse text dump");
+    assert_eq!(parsed.metadata().unwrap().capture.as_ref().unwrap().source, 0x7F);
+}
+
+#[test]
+pub fn test_long_mnemonic_round_trip() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let metadata = test_file.metadata_mut().unwrap();
+    metadata.set_mnemonic("CMPXCHG8B");
+    assert_eq!(metadata.mnemonic(), "CMPXCHG8B");
+    assert!(metadata.capture.is_none());
+
+    let parsed = MooTestFile::read(&mut Cursor::new(write(&test_file))).expect("Failed to re-read test file");
+    let metadata = parsed.metadata().unwrap();
+    assert_eq!(metadata.mnemonic(), "CMPXCHG8B");
+    assert!(metadata.timing.is_none());
+    assert!(metadata.capture.is_none());
+
+    let text = dump(&parsed, &MooDumpOptions::default());
+    let parsed = MooTestFile::parse_text(&mut text.as_bytes()).expect("Failed to parse text dump");
+    assert_eq!(parsed.metadata().unwrap().mnemonic(), "CMPXCHG8B");
+
+    // Short mnemonics fit in the fixed field alone.
+    test_file.metadata_mut().unwrap().set_mnemonic("ADD");
+    let parsed = MooTestFile::read(&mut Cursor::new(write(&test_file))).expect("Failed to re-read test file");
+    assert_eq!(parsed.metadata().unwrap().mnemonic(), "ADD");
+}
+
+#[test]
+pub fn test_metadata_payload_tail() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    let mut metadata = test_file.metadata().unwrap().clone();
+    metadata.timing = Some(MooTimingContext::new(4_772_727));
+    metadata.set_mnemonic("CMPXCHG8B");
+    let payload = metadata.to_payload().unwrap();
+
+    let parsed = MooFileMetadata::from_payload(&payload).unwrap();
+    assert_eq!(parsed.timing, metadata.timing);
+    assert!(parsed.capture.is_none());
+    assert_eq!(parsed.mnemonic(), "CMPXCHG8B");
+
+    // Fields added by a later revision are skipped.
+    let mut extended = payload.clone();
+    extended.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(
+        MooFileMetadata::from_payload(&extended).unwrap().mnemonic(),
+        "CMPXCHG8B"
+    );
+
+    // A tail that ends inside a field is an error rather than a silently dropped field.
+    let err = MooFileMetadata::from_payload(&payload[..payload.len() - 1]).unwrap_err();
+    assert!(err.to_string().contains("mnemonic_ext"), "{}", err);
+    let fixed_len = payload.len() - MooTimingContext::SIZE - MooCaptureInfo::SIZE - 10;
+    assert!(MooFileMetadata::from_payload(&payload[..fixed_len + 4]).is_err());
+    assert!(MooFileMetadata::from_payload(&payload[..fixed_len])
+        .unwrap()
+        .timing
+        .is_none());
+}
@@ -0,0 +1,47 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn cycle(ale: bool, bus_state: u8, t_state: u8) -> MooCycleState {
+    MooCycleState {
+        pins0: if ale { MooCycleState::PIN_ALE } else { 0 },
+        bus_state,
+        t_state,
+        ..Default::default()
+    }
+}
+
+#[test]
+pub fn test_timing_breakdown() {
+    // A code fetch with one wait state, an idle cycle, then a memory read.
+    let cycles = [
+        cycle(true, 4, 1),
+        cycle(false, 4, 2),
+        cycle(false, 4, 3),
+        cycle(false, 4, 5),
+        cycle(false, 7, 4),
+        cycle(false, 7, 0),
+        cycle(true, 5, 1),
+        cycle(false, 5, 2),
+        cycle(false, 5, 3),
+        cycle(false, 7, 4),
+    ];
+    let breakdown = MooTimingBreakdown::from_cycles(&cycles, MooCpuType::Intel8088);
+    assert_eq!(
+        breakdown,
+        MooTimingBreakdown {
+            fetch: 4,
+            execute: 1,
+            memory: 4,
+            io: 0,
+            wait: 1,
+        }
+    );
+    assert_eq!(breakdown.percent(MooCycleClass::Memory), 40.0);
+
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let stats = test_file.calc_stats(0);
+    assert_eq!(stats.timing.total(), stats.total_cycles);
+    assert!(stats.timing.fetch > 0);
+}
@@ -0,0 +1,26 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_render_svg() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/golden/8086.MOO");
+    let test_file = MooTestFile::read(&mut Cursor::new(std::fs::read(path).unwrap())).unwrap();
+    let test = &test_file.tests()[0];
+    let options = MooSvgOptions::new(test_file.cpu_type()).with_title("add [bx+si], al <&>");
+
+    let svg = test.render_svg(&options);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains("add [bx+si], al &lt;&amp;&gt;"));
+
+    // One address label per bus transaction, and the write to memory is marked.
+    let transactions = test.cycles_iter().transactions().count();
+    assert_eq!(svg.matches("fill=\"#333333\"").count(), transactions);
+    assert!(svg.contains(">MEMW</text>"));
+    assert!(svg.contains(">W</text>"));
+
+    // A truncated trace is narrower and marked as cut off.
+    let short = test.render_svg(&options.clone().with_max_cycles(4));
+    assert!(short.len() < svg.len());
+    assert!(short.contains('…') && !svg.contains('…'));
+}
@@ -0,0 +1,59 @@
+use moo::{prelude::*, types::errors::MooError};
+use std::{fs, io::Cursor, path::Path};
+use tempfile::tempdir;
+
+fn read_path(path: &Path) -> MooTestFile {
+    MooTestFile::read(&mut Cursor::new(fs::read(path).unwrap())).expect("Failed to read file")
+}
+
+#[test]
+pub fn test_transform_corpus() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let in_dir = temp_dir.path().join("in");
+    let out_dir = temp_dir.path().join("out");
+
+    let mut moo = read_path(&input_file);
+    let options = MooWriteOptions::new().with_preserve_hash(true);
+    moo.write_to_path(in_dir.join("00.MOO"), &options).unwrap();
+    moo.write_to_path(in_dir.join("nested/01.MOO.gz"), &options).unwrap();
+    moo.write_to_path(in_dir.join("02.MOO"), &options).unwrap();
+    fs::write(in_dir.join("03.MOO"), b"not a moo file").unwrap();
+
+    let transform = MooCorpusTransform::new(MooFileSearch::new(&in_dir).with_recursion(), &out_dir);
+    let summary = transform
+        .run(|path, moo| -> Result<bool, MooError> {
+            if path.ends_with("02.MOO") {
+                return Ok(false);
+            }
+            moo.tests_mut()[0].rename("transformed");
+            Ok(true)
+        })
+        .expect("Failed to search corpus");
+
+    assert_eq!(summary.files_read, 3);
+    assert_eq!(summary.files_written, 2);
+    assert_eq!(summary.failures.len(), 1);
+    assert!(summary.failures[0].path.ends_with("03.MOO"));
+
+    // Layout and compression follow the input, and unmodified files are not written.
+    assert!(!out_dir.join("02.MOO").exists());
+    let plain = read_path(&out_dir.join("00.MOO"));
+    assert!(!plain.compressed());
+    assert_eq!(plain.tests()[0].name(), "transformed");
+    assert!(plain.tests()[0].verify_hashes(0).unwrap());
+    let gz = read_path(&out_dir.join("nested/01.MOO.gz"));
+    assert!(gz.compressed());
+    assert_eq!(gz.tests()[0].name(), "transformed");
+
+    // An explicit compression applies to every output file.
+    let forced = transform.with_write_options(options.with_compression(MooCompressionKind::Gzip));
+    assert_eq!(
+        forced.output_path(&in_dir.join("00.MOO"), MooCompressionKind::Uncompressed),
+        out_dir.join("00.MOO.gz")
+    );
+    assert_eq!(
+        forced.output_path(&in_dir.join("nested/01.MOO.gz"), MooCompressionKind::Gzip),
+        out_dir.join("nested/01.MOO.gz")
+    );
+}
@@ -0,0 +1,91 @@
+use moo::prelude::*;
+use std::{io::Cursor, path::Path};
+
+fn load() -> MooTestFile {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file")
+}
+
+#[test]
+pub fn test_trim_idle() {
+    let file = load();
+    let original = &file.tests()[0];
+
+    // Pad the trace with idle cycles on both ends.
+    let mut cycles = vec![MooCycleState::default(); 2];
+    cycles.extend_from_slice(original.cycles());
+    cycles.extend_from_slice(&[MooCycleState::default(); 3]);
+    let mut test = MooTest::new(
+        original.name().to_string(),
+        None,
+        original.bytes(),
+        original.initial_state().clone(),
+        original.final_state().clone(),
+        &cycles,
+        None,
+        None,
+    );
+    test.set_anchor(MooAnchorKind::FirstExecution, 5);
+
+    let trimmed = test.trim(MooTrimPolicy::Idle).expect("Idle trim failed");
+    assert_eq!(trimmed.leading.len(), 2);
+    assert_eq!(trimmed.trailing.len(), 3);
+    assert_eq!(trimmed.to_string(), "trimmed 2 leading and 3 trailing cycles");
+    assert_eq!(test.cycles().len(), original.cycles().len());
+    assert_eq!(test.anchor(MooAnchorKind::FirstExecution), Some(3));
+    assert!(test.is_hash_dirty());
+    assert!(test.compare_aligned(original, DEFAULT_ALIGN_BAND).is_empty());
+
+    // A second pass has nothing left to remove.
+    assert!(test.trim(MooTrimPolicy::Idle).unwrap().is_empty());
+}
+
+#[test]
+pub fn test_trim_refuses_cuts() {
+    let mut file = load();
+    let test = &mut file.tests_mut()[0];
+    let len = test.cycles().len();
+
+    for policy in [
+        MooTrimPolicy::Count {
+            leading:  1,
+            trailing: 0,
+        },
+        MooTrimPolicy::Count {
+            leading:  0,
+            trailing: 1,
+        },
+        MooTrimPolicy::Count {
+            leading:  len,
+            trailing: 1,
+        },
+    ] {
+        assert!(test.trim(policy).is_err(), "{:?} was not refused", policy);
+        assert_eq!(test.cycles().len(), len);
+    }
+
+    test.set_anchor(MooAnchorKind::LastExecution, len + 4);
+    assert!(test.trim(MooTrimPolicy::Anchors).is_err());
+}
+
+#[test]
+pub fn test_trim_exception_frame() {
+    let mut file = load();
+    let test = file
+        .tests_mut()
+        .iter_mut()
+        .find(|t| t.exception().is_some())
+        .expect("No exception test");
+    let len = test.cycles().len();
+
+    // Cutting into the frame pushes is reported as an exception frame cut.
+    let err = test
+        .trim(MooTrimPolicy::Count {
+            leading:  0,
+            trailing: len - 58,
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("exception frame"), "{}", err);
+    assert_eq!(test.cycles().len(), len);
+}
@@ -0,0 +1,46 @@
+use moo::{prelude::*, types::chunks::MooChunkType};
+use std::{io::Cursor, path::Path};
+
+/// Return the offsets of the top-level chunks following the file header.
+fn top_level_chunks(data: &[u8]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut pos = 8 + u32::from_le_bytes(data[4..8].try_into().unwrap()) as usize;
+    while pos < data.len() {
+        offsets.push(pos);
+        pos += 8 + u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap()) as usize;
+    }
+    offsets
+}
+
+#[test]
+pub fn test_skip_unknown_top_level_chunks() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let data = std::fs::read(input_file).expect("Failed to read input file");
+    let original = MooTestFile::read(&mut Cursor::new(&data)).unwrap();
+
+    // Insert a chunk of an unrecognized type between the first and second tests.
+    let second_test = top_level_chunks(&data)
+        .into_iter()
+        .filter(|&pos| &data[pos..pos + 4] == b"TEST")
+        .nth(1)
+        .expect("Expected at least two tests");
+    let mut patched = data[..second_test].to_vec();
+    patched.extend(b"ZNEW");
+    patched.extend(6u32.to_le_bytes());
+    patched.extend([1, 2, 3, 4, 5, 6]);
+    patched.extend(&data[second_test..]);
+
+    let test_file = MooTestFile::read(&mut Cursor::new(patched)).expect("Failed to read file");
+    assert_eq!(test_file.test_ct(), original.test_ct());
+    assert_eq!(
+        test_file.tests().last().unwrap().name(),
+        original.tests().last().unwrap().name()
+    );
+
+    // A file cut off between tests is reported as truncated.
+    let truncated = &data[..second_test];
+    assert!(MooTestFile::read(&mut Cursor::new(truncated)).is_err());
+
+    let chunk_type = MooChunkType::Unknown(*b"ZNEW");
+    assert_eq!(chunk_type.raw_id(), Some(*b"ZNEW"));
+}
@@ -0,0 +1,68 @@
+use moo::{
+    test_file::MooTestFile,
+    types::vendor::{MooVendorChunk, MooVendorChunkRegistry},
+};
+use std::{io::Cursor, path::Path};
+
+#[test]
+pub fn test_vendor_chunks_preserved() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+
+    assert!(MooVendorChunk::new(*b"LACP", vec![]).is_err());
+    let file_chunk = MooVendorChunk::new(*b"lacp", vec![1, 2, 3, 4]).unwrap();
+    let test_chunk = MooVendorChunk::new(*b"xtra", vec![0xAA; 7]).unwrap();
+    test_file.vendor_chunks_mut().push(file_chunk.clone());
+    test_file.tests_mut()[0].vendor_chunks_mut().push(test_chunk.clone());
+    test_file.tests_mut()[0]
+        .initial_state_mut()
+        .vendor_chunks
+        .push(test_chunk.clone());
+
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, true).expect("Failed to write output file");
+    let read_back = MooTestFile::read(&mut Cursor::new(out.into_inner())).expect("Failed to parse output file");
+
+    assert_eq!(read_back.vendor_chunks(), std::slice::from_ref(&file_chunk));
+    assert_eq!(read_back.tests()[0].vendor_chunks(), std::slice::from_ref(&test_chunk));
+    assert_eq!(read_back.tests()[0].initial_state().vendor_chunks, vec![test_chunk]);
+    // Vendor chunks are not hashed, even inside a state chunk.
+    assert!(test_file.tests()[0].is_hash_dirty());
+    let hash = *test_file.tests_mut()[0].recompute_hash(0).unwrap();
+    assert_eq!(read_back.tests()[0].hash(), Some(&hash));
+    assert!(read_back.verify_hashes().unwrap().is_empty());
+
+    // Only registered chunks are decoded.
+    let mut registry = MooVendorChunkRegistry::new();
+    registry
+        .register(*b"lacp", |data| {
+            Ok(Box::new(data.iter().map(|b| *b as u32).sum::<u32>()))
+        })
+        .unwrap();
+    let decoded = registry.decode(&read_back.vendor_chunks()[0]).unwrap().unwrap();
+    assert_eq!(decoded.downcast_ref::<u32>(), Some(&10));
+    assert!(registry.decode(&read_back.tests()[0].vendor_chunks()[0]).is_none());
+}
+
+#[test]
+pub fn test_vendor_chunks_not_hashed() {
+    let input_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/test_data/00.MOO");
+    let bytes = std::fs::read(input_file).expect("Failed to read input file");
+    let mut test_file = MooTestFile::read(&mut Cursor::new(bytes)).expect("Failed to parse input file");
+    let hash = test_file.tests()[0].hash_string();
+
+    let chunk = MooVendorChunk::new(*b"xtra", vec![0x55; 3]).unwrap();
+    let test = &mut test_file.tests_mut()[0];
+    test.vendor_chunks_mut().push(chunk.clone());
+    test.initial_state_mut().vendor_chunks.push(chunk.clone());
+    test.final_state_mut().vendor_chunks.push(chunk);
+
+    // Recalculate every hash on write.
+    let mut out = Cursor::new(Vec::new());
+    test_file.write(&mut out, false).expect("Failed to write output file");
+    let read_back = MooTestFile::read(&mut Cursor::new(out.into_inner())).expect("Failed to parse output file");
+    assert_eq!(read_back.tests()[0].final_state().vendor_chunks.len(), 1);
+    assert_eq!(read_back.tests()[0].hash_string(), hash);
+    assert!(read_back.verify_hashes().unwrap().is_empty());
+}
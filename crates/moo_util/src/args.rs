@@ -23,37 +23,90 @@
 use std::{
     fmt::{Display, Formatter},
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
-use crate::commands::{
-    check::args::{check_parser, CheckParams},
-    display::args::{display_parser, DisplayParams},
-    edit::args::{edit_parser, EditParams},
-    find::args::{find_parser, FindParams},
+use crate::{
+    commands::{
+        check::args::{check_parser, CheckParams},
+        completions::args::{completions_parser, CompletionShell},
+        coverage::args::{coverage_parser, CoverageParams},
+        diff::args::{diff_parser, DiffParams},
+        display::args::{display_parser, DisplayParams},
+        edit::args::{edit_parser, EditParams},
+        find::args::{find_parser, FindParams},
+        gen::args::{gen_parser, mutate_parser, regen_parser, GenParams, MutateParams, RegenParams},
+        grep_cycles::args::{grep_cycles_parser, GrepCyclesParams},
+        inspect::args::{head_parser, show_parser, tail_parser, InspectParams},
+        meta::args::{meta_parser, MetaParams},
+        outliers::args::{outliers_parser, OutliersParams},
+        replay::args::{replay_parser, ReplayParams},
+        report::args::{report_parser, ReportParams},
+        results::args::{results_parser, ResultsParams},
+        verify::args::{verify_parser, VerifyParams},
+        verify_against::args::{verify_against_parser, VerifyAgainstParams},
+    },
+    config::config,
+    output::CollisionPolicy,
 };
 
-use bpaf::{construct, long, pure, Parser};
+use bpaf::{construct, long, pure, OptionParser, Parser};
+use moo::prelude::{MooFileSearch, MooGlob};
 
 #[derive(Clone, Debug)]
 pub(crate) enum Command {
     Version,
+    HelpMan,
+    Completions(CompletionShell),
     Display(DisplayParams),
     //Dump(DumpParams),
     Find(FindParams),
+    Gen(GenParams),
+    Mutate(MutateParams),
+    Regen(RegenParams),
+    GrepCycles(GrepCyclesParams),
+    Head(InspectParams),
+    Tail(InspectParams),
+    Show(InspectParams),
+    Outliers(OutliersParams),
+    Replay(ReplayParams),
+    Report(ReportParams),
+    Results(ResultsParams),
     Check(CheckParams),
+    Coverage(CoverageParams),
+    Diff(DiffParams),
     Edit(EditParams),
+    Meta(MetaParams),
+    Verify(VerifyParams),
+    VerifyAgainst(VerifyAgainstParams),
 }
 
 impl Display for Command {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Command::Version => write!(f, "version"),
+            Command::HelpMan => write!(f, "--help-man"),
+            Command::Completions(_) => write!(f, "completions"),
             Command::Display(_) => write!(f, "display"),
             //Command::Dump(_) => write!(f, "dump"),
             Command::Find(_) => write!(f, "find"),
+            Command::Gen(_) | Command::Mutate(_) => write!(f, "gen"),
+            Command::Regen(_) => write!(f, "regen"),
+            Command::GrepCycles(_) => write!(f, "grep-cycles"),
+            Command::Head(_) => write!(f, "head"),
+            Command::Tail(_) => write!(f, "tail"),
+            Command::Show(_) => write!(f, "show"),
+            Command::Outliers(_) => write!(f, "outliers"),
+            Command::Replay(_) => write!(f, "replay"),
+            Command::Report(_) => write!(f, "report"),
+            Command::Results(_) => write!(f, "results"),
             Command::Check(_) => write!(f, "check"),
+            Command::Coverage(_) => write!(f, "coverage"),
+            Command::Diff(_) => write!(f, "diff"),
             Command::Edit(_) => write!(f, "edit"),
+            Command::Meta(_) => write!(f, "meta"),
+            Command::Verify(_) => write!(f, "verify"),
+            Command::VerifyAgainst(_) => write!(f, "verify-against"),
         }
     }
 }
@@ -67,6 +120,8 @@ pub(crate) struct AppParams {
 #[derive(Debug)]
 pub struct GlobalOptions {
     pub silent: bool,
+    pub jobs: Option<usize>,
+    pub max_memory: Option<u64>,
 }
 
 impl GlobalOptions {
@@ -82,8 +137,44 @@ pub fn global_options_parser() -> impl Parser<GlobalOptions> {
     let silent = long("silent")
         .help("Suppress all output except required output")
         .switch(); // Switch returns a bool, true if the flag is present
+    let jobs = long("jobs")
+        .short('j')
+        .argument::<usize>("N")
+        .help("Number of files to process in parallel (default: one per CPU)")
+        .guard(|n| *n > 0, "--jobs must be at least 1")
+        .optional()
+        .map(|jobs| jobs.or(config().jobs));
+    let max_memory = long("max-memory")
+        .argument::<String>("SIZE")
+        .help("Approximate memory limit for files decoded at once, e.g. 512M or 4G")
+        .parse(|s| parse_memory_size(&s))
+        .optional();
 
-    construct!(GlobalOptions { silent })
+    construct!(GlobalOptions {
+        silent,
+        jobs,
+        max_memory
+    })
+}
+
+/// Parse a size in bytes with an optional binary K, M, G or T suffix.
+pub(crate) fn parse_memory_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let upper = s.to_ascii_uppercase();
+    let digits = upper.trim_end_matches("IB").trim_end_matches('B');
+    let (digits, shift) = match digits.chars().last() {
+        Some('K') => (&digits[..digits.len() - 1], 10),
+        Some('M') => (&digits[..digits.len() - 1], 20),
+        Some('G') => (&digits[..digits.len() - 1], 30),
+        Some('T') => (&digits[..digits.len() - 1], 40),
+        _ => (digits, 0),
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| format!("Invalid memory size: '{}'", s))
 }
 
 pub(crate) fn in_path_parser() -> impl Parser<PathBuf> {
@@ -92,16 +183,120 @@ pub(crate) fn in_path_parser() -> impl Parser<PathBuf> {
         .help("Path to input file or directory")
 }
 
-pub(crate) fn in_schema_parser() -> impl Parser<PathBuf> {
+/// Options controlling which files below an input directory are selected.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TraversalOptions {
+    pub(crate) recursive: bool,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) globs: Vec<String>,
+    pub(crate) excludes: Vec<String>,
+}
+
+impl TraversalOptions {
+    pub(crate) fn search(&self, root: &Path) -> MooFileSearch {
+        let max_depth = match (self.recursive, self.max_depth) {
+            (_, Some(depth)) => Some(depth),
+            (true, None) => None,
+            (false, None) => Some(1),
+        };
+        MooFileSearch::new(root)
+            .with_max_depth(max_depth)
+            .with_include(self.globs.iter().map(|g| MooGlob::new(g)))
+            .with_exclude(self.excludes.iter().map(|g| MooGlob::new(g)))
+    }
+}
+
+pub(crate) fn traversal_parser() -> impl Parser<TraversalOptions> {
+    let recursive = long("recursive")
+        .short('r')
+        .help("Search subdirectories of the input directory")
+        .switch();
+    let max_depth = long("max-depth")
+        .argument::<usize>("DEPTH")
+        .help("Search at most DEPTH directory levels, where 1 is the input directory itself")
+        .guard(|d| *d > 0, "--max-depth must be at least 1")
+        .optional();
+    let globs = long("glob")
+        .argument::<String>("PATTERN")
+        .help("Only select files matching a glob pattern, e.g. \"**/8088/*.moo.gz\". May be repeated")
+        .many();
+    let excludes = long("exclude")
+        .argument::<String>("PATTERN")
+        .help("Skip files and directories matching a glob pattern. May be repeated")
+        .many();
+
+    construct!(TraversalOptions {
+        recursive,
+        max_depth,
+        globs,
+        excludes
+    })
+}
+
+/// `--schema`, falling back to `schema` in the config file.
+pub(crate) fn in_schema_parser() -> impl Parser<Option<PathBuf>> {
     long("schema")
         .argument::<PathBuf>("SCHEMA_PATH")
         .help("Path to CSV schema file for operation")
+        .optional()
+        .map(|path| path.or_else(|| config().schema.clone()))
 }
 
-pub(crate) fn out_path_parser() -> impl Parser<PathBuf> {
+/// `--output`, falling back to `output` in the config file.
+pub(crate) fn opt_out_path_parser() -> impl Parser<Option<PathBuf>> {
     long("output")
         .argument::<PathBuf>("OUTPUT_PATH")
         .help("Path to output file or directory")
+        .optional()
+        .map(|path| path.or_else(|| config().output.clone()))
+}
+
+/// Like [opt_out_path_parser], but fails if neither the flag nor the config file gives a path.
+pub(crate) fn out_path_parser() -> impl Parser<PathBuf> {
+    opt_out_path_parser().parse(|path| {
+        path.ok_or_else(|| "expected --output OUTPUT_PATH, or an `output` entry in moo_util.toml".to_string())
+    })
+}
+
+/// `--compress` or `--no-compress`, falling back to `compress` in the config file.
+pub(crate) fn compress_parser() -> impl Parser<bool> {
+    let compress = long("compress").help("Compress the output file(s)").req_flag(true);
+    let no_compress = long("no-compress")
+        .help("Don't compress the output file(s), overriding the config file")
+        .req_flag(false);
+
+    construct!([compress, no_compress])
+        .optional()
+        .map(|compress| compress.or(config().compress).unwrap_or(false))
+}
+
+/// Options shared by commands that modify MOO files.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct MutationOptions {
+    pub(crate) dry_run: bool,
+    pub(crate) summary_only: bool,
+}
+
+pub(crate) fn mutation_parser() -> impl Parser<MutationOptions> {
+    let dry_run = long("dry-run")
+        .help("Make the changes in memory and report them, without writing any files")
+        .switch();
+    let summary_only = long("summary-only")
+        .help("Report only the totals, not the changes to each file and test")
+        .switch();
+
+    construct!(MutationOptions { dry_run, summary_only })
+}
+
+pub(crate) fn collision_parser() -> impl Parser<CollisionPolicy> {
+    let overwrite = long("overwrite")
+        .help("Overwrite output files that already exist")
+        .req_flag(CollisionPolicy::Overwrite);
+    let suffix = long("suffix")
+        .help("Add a numeric suffix to output file names that already exist")
+        .req_flag(CollisionPolicy::Suffix);
+
+    construct!([overwrite, suffix]).fallback(CollisionPolicy::Error)
 }
 
 pub(crate) fn command_parser() -> impl Parser<AppParams> {
@@ -112,6 +307,15 @@ pub(crate) fn command_parser() -> impl Parser<AppParams> {
         .command("version")
         .help("Display version information and exit");
 
+    let help_man = long("help-man")
+        .help("Print a man page for all commands, in roff format, and exit")
+        .req_flag(Command::HelpMan);
+
+    let completions = construct!(Command::Completions(completions_parser()))
+        .to_options()
+        .command("completions")
+        .help("Print a shell completion script for bash, zsh, fish or elvish");
+
     let display = construct!(Command::Display(display_parser()))
         .to_options()
         .command("display")
@@ -120,23 +324,130 @@ pub(crate) fn command_parser() -> impl Parser<AppParams> {
     let find = construct!(Command::Find(find_parser()))
         .to_options()
         .command("find")
-        .help("Find a test given its hash");
+        .help("Find tests given a hash or tag");
+
+    let mutate = construct!(Command::Mutate(mutate_parser()));
+    let gen = construct!(Command::Gen(gen_parser()));
+    let gen = construct!([mutate, gen])
+        .to_options()
+        .command("gen")
+        .help("Generate MOO test files by executing random tests on an emulator");
+
+    let regen = construct!(Command::Regen(regen_parser()))
+        .to_options()
+        .command("regen")
+        .help("Regenerate the initial state of a generated test from its seed and compare it");
+
+    let grep_cycles = construct!(Command::GrepCycles(grep_cycles_parser()))
+        .to_options()
+        .command("grep-cycles")
+        .help("Find tests whose cycles match a bus transaction pattern");
+
+    let head = construct!(Command::Head(head_parser()))
+        .to_options()
+        .command("head")
+        .help("Print the first tests of a file");
+
+    let tail = construct!(Command::Tail(tail_parser()))
+        .to_options()
+        .command("tail")
+        .help("Print the last tests of a file");
+
+    let show = construct!(Command::Show(show_parser()))
+        .to_options()
+        .command("show")
+        .help("Print a single test of a file by index");
+
+    let outliers = construct!(Command::Outliers(outliers_parser()))
+        .to_options()
+        .command("outliers")
+        .help("List tests with outlying cycle counts for manual review");
+
+    let replay = construct!(Command::Replay(replay_parser()))
+        .to_options()
+        .command("replay")
+        .help("Replay tests against an external emulator over JSON-RPC");
+
+    let report = construct!(Command::Report(report_parser()))
+        .to_options()
+        .command("report")
+        .help("Write an HTML or CSV report of per-file statistics");
+
+    let results = construct!(Command::Results(results_parser()))
+        .to_options()
+        .command("results")
+        .help("Summarize or compare MOO-R result files");
 
     let check = construct!(Command::Check(check_parser()))
         .to_options()
         .command("check")
         .help("Check integrity of MOO test files");
 
+    let coverage = construct!(Command::Coverage(coverage_parser()))
+        .to_options()
+        .command("coverage")
+        .help("Find clusters of redundant tests that exercise the same behavior");
+
+    let diff = construct!(Command::Diff(diff_parser()))
+        .to_options()
+        .command("diff")
+        .help("Compare the tests of two MOO files, optionally aligning their cycle traces");
+
     let edit = construct!(Command::Edit(edit_parser()))
         .to_options()
         .command("edit")
         .help("Edit properties of MOO test files");
 
-    let command = construct!([version, display, find, check, edit]);
+    let meta = construct!(Command::Meta(meta_parser()))
+        .to_options()
+        .command("meta")
+        .help("Set file metadata such as the mnemonic, opcode, seed or set version");
+
+    let verify = construct!(Command::Verify(verify_parser()))
+        .to_options()
+        .command("verify")
+        .help("Verify that stored test hashes match the test contents");
+
+    let verify_against = construct!(Command::VerifyAgainst(verify_against_parser()))
+        .to_options()
+        .command("verify-against")
+        .help("Compare a directory of MOO files with a golden corpus, failing on any difference");
+
+    let command = construct!([
+        version,
+        help_man,
+        completions,
+        display,
+        find,
+        gen,
+        regen,
+        grep_cycles,
+        head,
+        tail,
+        show,
+        outliers,
+        replay,
+        report,
+        results,
+        check,
+        coverage,
+        diff,
+        edit,
+        meta,
+        verify,
+        verify_against
+    ]);
 
     construct!(AppParams { global, command })
 }
 
+/// The top-level [OptionParser], shared by argument parsing, completions and the man page.
+pub(crate) fn app_options() -> OptionParser<AppParams> {
+    command_parser()
+        .to_options()
+        .descr("A general-purpose utility for working with MOO files")
+}
+
 pub(crate) fn hash_parser() -> impl Parser<String> {
     long("hash")
         .short('h')
@@ -0,0 +1,115 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Reporting of the changes made by commands that modify MOO files.
+//!
+//! Test changes are taken from each file's edit journal, via [MooTestFile::journal_summary], so
+//! the report counts exactly the edits that were applied, whether or not the file is then written.
+//! File-level changes, which the journal doesn't cover, are described by the command.
+
+use std::path::PathBuf;
+
+use moo::{prelude::MooTestFile, test_file::journal::MooJournalSummary};
+
+use crate::args::MutationOptions;
+
+/// The changes made to one file.
+#[derive(Clone, Debug)]
+pub(crate) struct FileChanges {
+    pub(crate) path: PathBuf,
+    /// Descriptions of changes to the file header, metadata or other file-level chunks.
+    pub(crate) file_edits: Vec<String>,
+    pub(crate) tests: MooJournalSummary,
+}
+
+impl FileChanges {
+    pub(crate) fn new(path: impl Into<PathBuf>, moo: &MooTestFile, file_edits: Vec<String>) -> Self {
+        Self {
+            path: path.into(),
+            file_edits,
+            tests: moo.journal_summary(),
+        }
+    }
+
+    pub(crate) fn changed(&self) -> bool {
+        !self.file_edits.is_empty() || self.tests.changed()
+    }
+}
+
+/// The changes made across a working set, built up per file and combined.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ChangeReport {
+    files_seen: usize,
+    files: Vec<FileChanges>,
+}
+
+impl ChangeReport {
+    pub(crate) fn from_file(changes: FileChanges) -> Self {
+        let changed = changes.changed();
+        Self {
+            files_seen: 1,
+            files: if changed { vec![changes] } else { Vec::new() },
+        }
+    }
+
+    pub(crate) fn combine(mut self, other: ChangeReport) -> ChangeReport {
+        self.files_seen += other.files_seen;
+        self.files.extend(other.files);
+        self
+    }
+
+    /// Print the changes to each file, unless `summary_only` is set, followed by the totals.
+    pub(crate) fn print(&self, options: &MutationOptions) {
+        let mut files: Vec<&FileChanges> = self.files.iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        if !options.summary_only {
+            for file in &files {
+                println!("{}: {}", file.path.display(), file.tests);
+                for edit in &file.file_edits {
+                    println!("  file: {}", edit);
+                }
+                for (index, edits) in file.tests.edited_tests() {
+                    println!("  test {}: {} edit{}", index, edits, if edits == 1 { "" } else { "s" });
+                }
+            }
+        }
+
+        let sum = |f: fn(&FileChanges) -> usize| files.iter().map(|file| f(file)).sum::<usize>();
+        println!(
+            "{} {}/{} files: {} tests edited ({} edits), {} inserted, {} removed, {} moved, {} file-level changes",
+            if options.dry_run { "Would change" } else { "Changed" },
+            files.len(),
+            self.files_seen,
+            sum(|file| file.tests.tests_edited()),
+            sum(|file| file.tests.edits()),
+            sum(|file| file.tests.tests_inserted),
+            sum(|file| file.tests.tests_removed),
+            sum(|file| file.tests.tests_moved),
+            sum(|file| file.file_edits.len()),
+        );
+        if options.dry_run {
+            println!("Dry run: no files were written.");
+        }
+    }
+}
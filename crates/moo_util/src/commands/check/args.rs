@@ -22,13 +22,30 @@
 */
 use std::path::PathBuf;
 
-use crate::args::{hash_parser, in_path_parser, index_parser, out_path_parser};
+use crate::{
+    args::{
+        collision_parser,
+        compress_parser,
+        hash_parser,
+        in_path_parser,
+        index_parser,
+        mutation_parser,
+        opt_out_path_parser,
+        traversal_parser,
+        MutationOptions,
+        TraversalOptions,
+    },
+    config::config,
+    output::CollisionPolicy,
+};
 
-use bpaf::{construct, Parser};
+use bpaf::{construct, long, Parser};
+use moo::registers::descriptor_table::MooTableRegister;
 
 #[derive(Clone, Debug)]
 pub(crate) struct CheckParams {
     pub(crate) in_path: PathBuf,
+    pub(crate) traversal: TraversalOptions,
     pub(crate) out_path: Option<PathBuf>,
     pub(crate) hash: Option<String>,
     pub(crate) index: Option<usize>,
@@ -36,11 +53,15 @@ pub(crate) struct CheckParams {
     pub(crate) check_disassembly: bool,
     pub(crate) update_disassembly: bool,
     pub(crate) compress: bool,
+    pub(crate) collision: CollisionPolicy,
+    pub(crate) mutation: MutationOptions,
+    pub(crate) gdt: Option<MooTableRegister>,
 }
 
 pub(crate) fn check_parser() -> impl Parser<CheckParams> {
     let in_path = in_path_parser();
-    let out_path = out_path_parser().optional();
+    let traversal = traversal_parser();
+    let out_path = opt_out_path_parser();
     let hash = hash_parser().optional();
     let index = index_parser().optional();
     let fix = bpaf::long("fix")
@@ -48,14 +69,25 @@ pub(crate) fn check_parser() -> impl Parser<CheckParams> {
         .switch();
     let check_disassembly = bpaf::long("check-disassembly")
         .help("Check the disassembly for issues")
-        .switch();
+        .switch()
+        .map(|check| check || config().lint.check_disassembly);
     let update_disassembly = bpaf::long("update-disassembly")
         .help("Update the disassembly when fixing issues")
-        .switch();
-    let compress = bpaf::long("compress").help("Compress the output file(s)").switch();
+        .switch()
+        .map(|update| update || config().lint.update_disassembly);
+    let compress = compress_parser();
+    let collision = collision_parser();
+    let mutation = mutation_parser();
+    let gdt = long("gdt")
+        .argument::<String>("BASE:LIMIT")
+        .help("Hexadecimal GDTR base and limit. Protected-mode descriptor caches are checked against the GDT entries in initial RAM")
+        .optional()
+        .map(|gdt| gdt.or_else(|| config().lint.gdt.clone()))
+        .parse(|gdt| gdt.map(|s| parse_table_register(&s)).transpose());
 
     construct!(CheckParams {
         in_path,
+        traversal,
         out_path,
         hash,
         index,
@@ -63,16 +95,28 @@ pub(crate) fn check_parser() -> impl Parser<CheckParams> {
         check_disassembly,
         update_disassembly,
         compress,
+        collision,
+        mutation,
+        gdt,
     })
     .guard(
         |p| {
-            if p.fix {
+            if p.fix && !p.mutation.dry_run {
                 p.out_path.is_some()
             }
             else {
                 true
             }
         },
-        "--output is required if --fix is specified",
+        "--output is required if --fix is specified without --dry-run",
     )
 }
+
+fn parse_table_register(s: &str) -> Result<MooTableRegister, String> {
+    let (base, limit) = s
+        .split_once(':')
+        .ok_or_else(|| format!("Expected BASE:LIMIT, got '{}'", s))?;
+    let base = u32::from_str_radix(base, 16).map_err(|_| format!("Invalid table base: '{}'", base))?;
+    let limit = u16::from_str_radix(limit, 16).map_err(|_| format!("Invalid table limit: '{}'", limit))?;
+    Ok(MooTableRegister::new(base, limit))
+}
@@ -23,20 +23,16 @@
 
 use crate::{
     args::GlobalOptions,
+    changes::{ChangeReport, FileChanges},
     commands::check::args::CheckParams,
     enums::CheckErrorDetail,
     functions::check::check_test,
+    output::OutputPathPolicy,
     working_set::WorkingSet,
 };
-use std::{
-    collections::HashMap,
-    ffi::{OsStr, OsString},
-    fs,
-    io::Cursor,
-    path::PathBuf,
-};
+use std::{collections::HashMap, fs, io::Cursor, path::PathBuf};
 
-use crate::functions::check::check_metadata;
+use crate::functions::check::{check_arch, check_metadata};
 use anyhow::Error;
 use moo::prelude::*;
 use rayon::prelude::*;
@@ -49,6 +45,7 @@ struct CheckStats {
     errors_found: usize,
     read_errors: usize,
     test_errors: HashMap<PathBuf, Vec<CheckErrorDetail>>,
+    changes: ChangeReport,
 }
 
 impl CheckStats {
@@ -58,6 +55,7 @@ impl CheckStats {
         self.files_with_errors += other.files_with_errors;
         self.read_errors += other.read_errors;
         self.errors_found += other.errors_found;
+        self.changes = self.changes.combine(other.changes);
         // Merge test errors
         for (pb, v_other) in other.test_errors {
             self.test_errors
@@ -72,16 +70,21 @@ impl CheckStats {
     }
 }
 
-pub fn run(_global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
-    let working_set = WorkingSet::from_path(&params.in_path, None)?;
+pub fn run(global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
 
     if working_set.is_empty() {
         return Err(Error::msg("No files selected"));
     }
 
+    let output_policy = params
+        .out_path
+        .as_ref()
+        .map(|dir| OutputPathPolicy::new(dir, params.compress, params.collision));
+
     let check_stats = working_set
-        .par_iter()
-        .map(|path| {
+        .par_map(|path| {
             let mut s = CheckStats {
                 files_checked: 1,
                 ..Default::default()
@@ -92,9 +95,13 @@ pub fn run(_global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
                     let mut reader = Cursor::new(data);
                     match MooTestFile::read(&mut reader) {
                         Ok(mut moo) => {
+                            let mut file_edits = Vec::new();
+                            let mut md_errors = check_arch(&mut moo, params.fix);
                             let metadata = match moo.metadata_mut() {
                                 Some(md) => {
-                                    let md_errors = check_metadata(md, path, params.fix);
+                                    md_errors.extend(check_metadata(md, path, params.fix));
+                                    file_edits
+                                        .extend(md_errors.iter().filter(|e| e.fixed).map(|e| e.e_type.to_string()));
                                     if !md_errors.is_empty() {
                                         s.read_errors += 1;
                                         s.files_with_errors = 1;
@@ -114,8 +121,15 @@ pub fn run(_global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
                                 }
                             };
 
-                            for (ti, test) in moo.tests_mut().iter_mut().enumerate() {
-                                match check_test(ti, test, &metadata, params) {
+                            for ti in 0..moo.test_ct() {
+                                // Fixes are applied through the edit journal, which the change
+                                // report is built from.
+                                let mut fixes = Vec::new();
+                                let result = check_test(ti, &moo.tests()[ti], &metadata, params, &mut fixes);
+                                for fix in fixes {
+                                    moo.edit_test(ti, fix);
+                                }
+                                match result {
                                     Ok(Some(detail)) => {
                                         // Record error
                                         s.errors_found += 1; // counting failing tests
@@ -134,21 +148,18 @@ pub fn run(_global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
                             s.tests_checked = moo.test_ct();
 
                             // Write fixed file if needed
-                            let tests_fixed = s
-                                .test_errors
-                                .values()
-                                .flat_map(|v| v.iter())
-                                .map(|d| d.errors().iter().filter(|e| e.fixed).count())
-                                .sum::<usize>();
-
-                            if params.fix && tests_fixed > 0 {
-                                let out_path = get_fixed_path(path, params);
-                                let mut out_file = fs::File::create(out_path).unwrap();
-
-                                // Set compression flag
-                                moo.set_compressed(params.compress);
-
-                                match moo.write(&mut out_file, true) {
+                            let changes = FileChanges::new(path, &moo, file_edits);
+
+                            if let Some(policy) = output_policy
+                                .as_ref()
+                                .filter(|_| params.fix && changes.changed() && !params.mutation.dry_run)
+                            {
+                                let options = MooWriteOptions::new().with_preserve_hash(true);
+                                let written = policy
+                                    .path_for_input(path)
+                                    .and_then(|out_path| Ok(moo.write_to_path(&out_path, &options)?));
+
+                                match written {
                                     Ok(_) => {
                                         log::info!("Wrote fixed file for {}", path.display());
                                     }
@@ -157,6 +168,7 @@ pub fn run(_global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
                                     }
                                 }
                             }
+                            s.changes = ChangeReport::from_file(changes);
                         }
                         Err(e) => {
                             log::warn!("Parse error in {}: {}", path.display(), e);
@@ -189,6 +201,11 @@ pub fn run(_global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
         }
     });
 
+    // With --summary-only, only the totals below are printed.
+    if params.mutation.summary_only {
+        sorted_errors.clear();
+    }
+
     for (test_path, details) in sorted_errors {
         println!("Errors in file {}:", test_path.display());
         for err in details {
@@ -241,33 +258,9 @@ pub fn run(_global: &GlobalOptions, params: &CheckParams) -> Result<(), Error> {
 
     println!("  {}/{} errors reported fixed.", total_fixed, total_errors);
 
-    Ok(())
-}
-
-pub fn get_fixed_path(original: &PathBuf, params: &CheckParams) -> PathBuf {
-    //let parent = original.parent().unwrap();
-    let filename = original.file_stem().unwrap();
-    let extension = original.extension().unwrap_or_else(|| OsStr::new("MOO"));
-
-    if extension == "gz" && !params.compress {
-        // Special case: original file is .MOO.gz, but we are not compressing output
-        let filename = OsStr::new(filename);
-        let filename = PathBuf::from(filename);
-        let filename = filename.file_stem().unwrap();
-        return params
-            .out_path
-            .as_ref()
-            .unwrap()
-            .join(join_filename_ext(filename, OsStr::new("MOO")));
+    if params.fix {
+        check_stats.changes.print(&params.mutation);
     }
 
-    let out_path = params.out_path.as_ref().unwrap().clone();
-    out_path.join(join_filename_ext(filename, extension))
-}
-
-fn join_filename_ext(filename: &OsStr, extension: &OsStr) -> OsString {
-    let mut result = OsString::from(filename);
-    result.push(".");
-    result.push(extension);
-    result
+    Ok(())
 }
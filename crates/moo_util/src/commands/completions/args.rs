@@ -0,0 +1,68 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::str::FromStr;
+
+use bpaf::{positional, Parser};
+
+/// A shell for which `completions` can write a completion script.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+}
+
+impl CompletionShell {
+    /// The bpaf option that prints the completion script for this shell.
+    pub(crate) fn bpaf_style(&self) -> &'static str {
+        match self {
+            CompletionShell::Bash => "--bpaf-complete-style-bash",
+            CompletionShell::Zsh => "--bpaf-complete-style-zsh",
+            CompletionShell::Fish => "--bpaf-complete-style-fish",
+            CompletionShell::Elvish => "--bpaf-complete-style-elvish",
+        }
+    }
+}
+
+impl FromStr for CompletionShell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "bash" => Ok(CompletionShell::Bash),
+            "zsh" => Ok(CompletionShell::Zsh),
+            "fish" => Ok(CompletionShell::Fish),
+            "elvish" => Ok(CompletionShell::Elvish),
+            _ => Err(format!(
+                "Unknown shell '{}'. Valid shells are: bash, zsh, fish, elvish",
+                s
+            )),
+        }
+    }
+}
+
+pub(crate) fn completions_parser() -> impl Parser<CompletionShell> {
+    positional::<CompletionShell>("SHELL").help("Shell to write a completion script for: bash, zsh, fish or elvish")
+}
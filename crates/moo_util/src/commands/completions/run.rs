@@ -0,0 +1,36 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use super::args::CompletionShell;
+use crate::args::app_options;
+use anyhow::{bail, Error};
+use bpaf::Args;
+
+/// Print the completion script for `shell`. The script calls back into the binary named `name`
+/// to complete each word, so completions follow the installed version's commands and options.
+pub fn run(name: &str, shell: CompletionShell) -> Result<(), Error> {
+    // bpaf prints the script and exits when it sees the style option.
+    let args = [shell.bpaf_style()];
+    _ = app_options().run_inner(Args::from(&args[..]).set_name(name));
+    bail!("Failed to generate a completion script for {:?}", shell)
+}
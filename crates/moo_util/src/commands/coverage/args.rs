@@ -0,0 +1,69 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::args::{in_path_parser, traversal_parser, TraversalOptions};
+use bpaf::{construct, long, Parser};
+use moo::stats::DEFAULT_CYCLE_BUCKET;
+
+#[derive(Clone, Debug)]
+pub(crate) struct CoverageParams {
+    pub(crate) in_path: PathBuf,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) bucket: usize,
+    pub(crate) keep: usize,
+    pub(crate) list: bool,
+}
+
+pub(crate) fn coverage_parser() -> impl Parser<CoverageParams> {
+    let in_path = in_path_parser();
+    let traversal = traversal_parser();
+
+    let bucket = long("bucket")
+        .short('b')
+        .argument::<usize>("CYCLES")
+        .help("Width of the cycle count ranges tests are grouped by (default: 4)")
+        .guard(|b| *b > 0, "--bucket must be at least 1")
+        .fallback(DEFAULT_CYCLE_BUCKET);
+
+    let keep = long("keep")
+        .short('k')
+        .argument::<usize>("N")
+        .help("Number of tests to keep per cluster; larger clusters are reported (default: 16)")
+        .guard(|k| *k > 0, "--keep must be at least 1")
+        .fallback(16);
+
+    let list = long("list")
+        .short('l')
+        .help("List the indices of the redundant tests in each reported cluster")
+        .switch();
+
+    construct!(CoverageParams {
+        in_path,
+        traversal,
+        bucket,
+        keep,
+        list
+    })
+}
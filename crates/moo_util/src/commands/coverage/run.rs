@@ -0,0 +1,116 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::{
+    args::GlobalOptions,
+    commands::coverage::args::CoverageParams,
+    file::read_moo_file,
+    working_set::WorkingSet,
+};
+use anyhow::Error;
+use moo::prelude::*;
+use rayon::prelude::*;
+
+struct FileCoverage {
+    file: PathBuf,
+    tests: usize,
+    clusters: usize,
+    redundant_clusters: Vec<MooCoverageCluster>,
+}
+
+pub fn run(global: &GlobalOptions, params: &CoverageParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
+
+    if working_set.is_empty() {
+        return Err(Error::msg("No files selected"));
+    }
+
+    let mut results: Vec<FileCoverage> = working_set
+        .par_map(|path| match read_moo_file(path) {
+            Ok(moo) => {
+                let clusters = moo.coverage_clusters(params.bucket);
+                Some(FileCoverage {
+                    file: path.clone(),
+                    tests: moo.test_ct(),
+                    clusters: clusters.len(),
+                    redundant_clusters: clusters.into_iter().filter(|c| c.tests.len() > params.keep).collect(),
+                })
+            }
+            Err(e) => {
+                log::warn!("Error reading {}: {}", path.display(), e);
+                None
+            }
+        })
+        .flatten()
+        .collect();
+
+    results.sort_by(|a, b| a.file.cmp(&b.file));
+
+    let mut total_tests = 0;
+    let mut total_redundant = 0;
+    for file in &results {
+        let redundant: usize = file
+            .redundant_clusters
+            .iter()
+            .map(|c| c.redundant(params.keep).len())
+            .sum();
+        if redundant > 0 {
+            println!(
+                "{}: {} of {} tests redundant ({} clusters)",
+                file.file.display(),
+                redundant,
+                file.tests,
+                file.clusters
+            );
+        }
+        for cluster in &file.redundant_clusters {
+            println!("    {} tests: {}", cluster.tests.len(), cluster.key);
+            if params.list {
+                let indices = cluster
+                    .redundant(params.keep)
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!("        redundant: {}", indices);
+            }
+        }
+        total_tests += file.tests;
+        total_redundant += redundant;
+    }
+
+    global.loud(|| {
+        println!(
+            "{} of {} tests redundant keeping {} per cluster (searched {} files)",
+            total_redundant,
+            total_tests,
+            params.keep,
+            results.len()
+        )
+    });
+
+    Ok(())
+}
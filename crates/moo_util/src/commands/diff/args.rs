@@ -0,0 +1,60 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::args::{hash_parser, in_path_parser};
+use bpaf::{construct, long, Parser};
+use moo::prelude::DEFAULT_ALIGN_BAND;
+
+#[derive(Clone, Debug)]
+pub(crate) struct DiffParams {
+    pub(crate) in_path: PathBuf,
+    pub(crate) against: PathBuf,
+    pub(crate) hash:    Option<String>,
+    pub(crate) align:   bool,
+    pub(crate) band:    usize,
+}
+
+pub(crate) fn diff_parser() -> impl Parser<DiffParams> {
+    let in_path = in_path_parser();
+    let against = long("against")
+        .argument::<PathBuf>("OTHER_PATH")
+        .help("MOO file to compare the input against");
+    let hash = hash_parser().optional();
+    let align = long("align")
+        .help("Align bus transactions so that timing differences are not reported as mismatches")
+        .switch();
+    let band = long("band")
+        .argument::<usize>("N")
+        .help("With --align, the maximum number of transactions by which the traces may drift (default: 8)")
+        .fallback(DEFAULT_ALIGN_BAND);
+
+    construct!(DiffParams {
+        in_path,
+        against,
+        hash,
+        align,
+        band
+    })
+}
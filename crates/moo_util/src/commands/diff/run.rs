@@ -0,0 +1,98 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::HashMap;
+
+use crate::{args::GlobalOptions, commands::diff::args::DiffParams, file::read_moo_file};
+use anyhow::Error;
+use moo::{prelude::*, types::MooComparison};
+
+pub fn run(global: &GlobalOptions, params: &DiffParams) -> Result<(), Error> {
+    let moo = read_moo_file(&params.in_path)?;
+    let against = read_moo_file(&params.against)?;
+
+    let by_hash: HashMap<String, &MooTest> = against.tests().iter().map(|t| (t.hash_string(), t)).collect();
+
+    let mut compared = 0;
+    let mut differing = 0;
+    let mut unmatched = 0;
+    for test in moo.tests() {
+        let hash = test.hash_string();
+        if params.hash.as_ref().is_some_and(|h| !h.eq_ignore_ascii_case(&hash)) {
+            continue;
+        }
+        let Some(other) = by_hash.get(&hash)
+        else {
+            unmatched += 1;
+            global.loud(|| println!("{} ({}): not found in {}", test.name(), hash, params.against.display()));
+            continue;
+        };
+        compared += 1;
+
+        let mut lines: Vec<String> = Vec::new();
+        if params.align {
+            // Cycle differences are reported by the alignment instead.
+            lines.extend(
+                test.compare(other, false)
+                    .iter()
+                    .filter(|c| {
+                        !matches!(
+                            c,
+                            MooComparison::CycleCountMismatch(..)
+                                | MooComparison::CycleAddressMismatch(..)
+                                | MooComparison::CycleBusMismatch(..)
+                                | MooComparison::ALEMismatch(..)
+                        )
+                    })
+                    .map(|c| c.to_string()),
+            );
+            lines.extend(test.compare_aligned(other, params.band).iter().map(|d| d.to_string()));
+        }
+        else {
+            lines.extend(test.compare(other, false).iter().map(|c| c.to_string()));
+        }
+
+        if !lines.is_empty() {
+            differing += 1;
+            println!("{} ({}):", test.name(), hash);
+            for line in &lines {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    global.loud(|| {
+        println!(
+            "{} of {} compared test(s) differ, {} not found in {}",
+            differing,
+            compared,
+            unmatched,
+            params.against.display()
+        )
+    });
+
+    if differing > 0 {
+        return Err(anyhow::anyhow!("{} test(s) differ", differing));
+    }
+    Ok(())
+}
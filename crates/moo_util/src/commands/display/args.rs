@@ -28,8 +28,15 @@ use bpaf::{construct, Parser};
 #[derive(Clone, Debug)]
 pub(crate) struct DisplayParams {
     pub(crate) in_path: PathBuf,
-    pub(crate) hash:    Option<String>,
-    pub(crate) index:   Option<usize>,
+    pub(crate) hash: Option<String>,
+    pub(crate) index: Option<usize>,
+    pub(crate) mem_diff: bool,
+}
+
+pub(crate) fn mem_diff_parser() -> impl Parser<bool> {
+    bpaf::long("mem-diff")
+        .help("Show memory as a hexdump of the changes between the initial and final states")
+        .switch()
 }
 
 pub(crate) fn display_parser() -> impl Parser<DisplayParams> {
@@ -39,8 +46,15 @@ pub(crate) fn display_parser() -> impl Parser<DisplayParams> {
         .help("Index of the test to display")
         .argument("INDEX")
         .optional();
+    let mem_diff = mem_diff_parser();
 
-    construct!(DisplayParams { in_path, hash, index }).guard(
+    construct!(DisplayParams {
+        in_path,
+        hash,
+        index,
+        mem_diff
+    })
+    .guard(
         |p| p.hash.is_some() || p.index.is_some(),
         "Either --hash or --index must be provided",
     )
@@ -23,4 +23,6 @@
 
 pub mod args;
 mod run;
+pub(crate) use args::mem_diff_parser;
+pub(crate) use run::print_test;
 pub use run::run;
@@ -51,24 +51,26 @@ pub fn run(_global: &GlobalOptions, params: &DisplayParams) -> Result<(), Error>
 
     let metadata = if moo_in.metadata().is_none() {
         log::warn!("MOO file is missing metadata chunk, synthesizing default metadata");
-        MooFileMetadata {
-            set_version_major: 1,
-            set_version_minor: 0,
-            cpu_type: moo_in.cpu_type(),
-            opcode: 0,
-            test_ct: moo_in.test_ct() as u32,
-            file_seed: 0,
-            extension: 0,
-            ..Default::default()
-        }
+        MooFileMetadata::new(1, 0, moo_in.cpu_type(), 0, Some(0)).with_test_count(moo_in.test_ct() as u32)
     }
     else {
         moo_in.metadata().unwrap().clone()
     };
 
-    if let Some(test_idx) = params.index {
-        let mut indent: usize = DISPLAY_INDENT;
+    if let Some(timing) = &metadata.timing {
+        println!("Captured at: {}", timing);
+    }
+    if let Some(capture) = &metadata.capture {
+        println!("Capture source: {}", capture);
+    }
+    if let Some(license) = moo_in.license() {
+        println!("License: {}", license.identifier);
+        for contributor in &license.contributors {
+            println!("{:indent$}Contributor: {}", "", contributor, indent = DISPLAY_INDENT);
+        }
+    }
 
+    if let Some(test_idx) = params.index {
         // Display a specific test
         if test_idx >= moo_in.test_ct() {
             return Err(anyhow::anyhow!(
@@ -78,80 +80,126 @@ pub fn run(_global: &GlobalOptions, params: &DisplayParams) -> Result<(), Error>
             ));
         }
 
-        let test = &moo_in.tests()[test_idx];
-
-        let initial_regs_printer = MooRegistersPrinter {
-            cpu_type: metadata.cpu_type,
-            regs: &test.initial_state().regs(),
-            diff: None,
-            indent: (indent as u32) * 2,
-        };
-
-        let final_regs_printer = MooRegistersPrinter {
-            cpu_type: metadata.cpu_type,
-            regs: &test.final_state().regs(),
-            diff: Some(&test.initial_state().regs()),
-            indent: (indent as u32) * 2,
-        };
-
-        let banner_msg = format!(
-            "Displaying test {} [#{}/{}]:",
-            test.hash_string(),
+        print_test(
+            &moo_in.tests()[test_idx],
             test_idx,
-            moo_in.test_ct()
+            moo_in.test_ct(),
+            metadata.cpu_type,
+            params.mem_diff,
         );
+    }
 
-        print_banner(banner_msg.as_str());
+    Ok(())
+}
 
-        if let Some(gen_metadata) = test.gen_metadata() {
-            println!("Metadata:");
-            indent += DISPLAY_INDENT;
-            println!("{:indent$}Seed: {:?}", "", gen_metadata.seed,);
-            println!("{:indent$}Generation count: {}", "", gen_metadata.gen_ct,);
-            indent -= DISPLAY_INDENT;
-        }
+/// Print a test in human-readable form: its generator metadata, name and bytes, the initial and
+/// final states, and its cycles. `test_idx` and `test_ct` are shown in the banner. With
+/// `mem_diff`, memory is shown as one hexdump of the changes instead of two entry listings.
+pub(crate) fn print_test(test: &MooTest, test_idx: usize, test_ct: usize, cpu_type: MooCpuType, mem_diff: bool) {
+    let mut indent: usize = DISPLAY_INDENT;
 
-        println!("Name: {}", test.name());
-        println!("Bytes: {:02X?}", test.bytes());
-        println!("Initial state:");
-        println!("{:indent$}Registers:", "");
-        println!("{}", initial_regs_printer);
+    let initial_regs = test.initial_state().regs();
+    let final_regs = test.final_state().regs();
+
+    let initial_regs_printer = MooRegistersPrinter::new(initial_regs, cpu_type).with_indent((indent as u32) * 2);
+
+    let final_regs_printer = MooRegistersPrinter::new(final_regs, cpu_type)
+        .with_diff(initial_regs)
+        .with_indent((indent as u32) * 2);
+
+    let banner_msg = format!("Displaying test {} [#{}/{}]:", test.hash_string(), test_idx, test_ct);
+
+    print_banner(banner_msg.as_str());
+
+    if let Some(gen_metadata) = test.gen_metadata() {
+        println!("Generator metadata:");
+        indent += DISPLAY_INDENT;
+        println!("{:indent$}Seed: {:016X}", "", gen_metadata.seed());
+        println!("{:indent$}Generation count: {}", "", gen_metadata.gen_ct());
+        indent -= DISPLAY_INDENT;
+    }
+
+    println!("Name: {}", test.name());
+    println!("Bytes: {:02X?}", test.bytes());
+    println!("Initial state:");
+    println!("{:indent$}Registers:", "");
+    println!("{}", initial_regs_printer);
+    if !mem_diff {
         println!("{:indent$}Memory:", "");
         indent += DISPLAY_INDENT;
         for ram_entry in test.initial_state().ram() {
             println!("{:indent$}{:06X}: {:02X}", "", ram_entry.address, ram_entry.value);
         }
         indent -= DISPLAY_INDENT;
-        println!("Final state:");
-        println!("{:indent$}Registers:", "");
-        println!("{}", final_regs_printer);
+    }
+    let ivt = test.initial_state().ivt();
+    if !ivt.is_empty() {
+        println!("{:indent$}IVT:", "");
+        indent += DISPLAY_INDENT;
+        for entry in ivt.entries() {
+            println!("{:indent$}{}", "", entry);
+        }
+        indent -= DISPLAY_INDENT;
+    }
+    println!("Final state:");
+    println!("{:indent$}Registers:", "");
+    println!("{}", final_regs_printer);
+    let flags_diff = test.diff_flags();
+    if !flags_diff.is_empty() {
+        println!("{:indent$}Flags: {} ({})", "", flags_diff, flags_diff.to_long_string());
+    }
+    if mem_diff {
+        println!("Memory diff:");
+        print!("{}", test.ram_diff_printer().with_indent(indent as u32));
+    }
+    else {
         println!("{:indent$}Memory:", "");
         indent += DISPLAY_INDENT;
         for ram_entry in test.final_state().ram() {
             println!("{:indent$}{:06X}: {:02X}", "", ram_entry.address, ram_entry.value);
         }
         indent -= DISPLAY_INDENT;
-
-        let mut printer = MooCycleStatePrinter {
-            cpu_type: metadata.cpu_type,
-            address_latch: 0,
-            state: MooCycleState::default(),
-            show_cycle_num: true,
-            cycle_num: 0,
-        };
-
-        println!();
-        println!("{:indent$}Cycles ({}):", "", test.cycles().len());
+    }
+    if !test.io_stimulus().is_empty() {
+        println!("IO stimulus:");
         indent += DISPLAY_INDENT;
-        for (_cycle_idx, cycle) in test.cycles().iter().enumerate() {
-            if cycle.ale() {
-                printer.address_latch = cycle.address_bus;
-            }
-            printer.state = *cycle;
-            println!("{:indent$}{}", "", printer);
-            printer.cycle_num = printer.cycle_num.wrapping_add(1);
+        for stimulus in test.io_stimulus() {
+            println!("{:indent$}{}", "", stimulus);
         }
+        indent -= DISPLAY_INDENT;
+    }
+    if let Some(context) = test.exception_context(cpu_type) {
+        println!("Exception: {}", context);
+    }
+    if let Some(frame) = test.stack_frame(cpu_type) {
+        println!("Stack: {}", frame);
+        indent += DISPLAY_INDENT;
+        for slot in &frame.slots {
+            println!("{:indent$}{}", "", slot);
+        }
+        indent -= DISPLAY_INDENT;
+    }
+    if let Some(mismatch) = test.exception_vector_mismatch(cpu_type) {
+        println!("Vector mismatch: {}", mismatch);
     }
 
-    Ok(())
+    let mut printer = MooCycleStatePrinter {
+        cpu_type,
+        address_latch: 0,
+        state: MooCycleState::default(),
+        show_cycle_num: true,
+        cycle_num: 0,
+    };
+
+    println!();
+    println!("{:indent$}Cycles ({}):", "", test.cycles().len());
+    indent += DISPLAY_INDENT;
+    for cycle in test.cycles() {
+        if cycle.ale() {
+            printer.address_latch = cycle.address_bus;
+        }
+        printer.state = *cycle;
+        println!("{:indent$}{}", "", printer);
+        printer.cycle_num = printer.cycle_num.wrapping_add(1);
+    }
 }
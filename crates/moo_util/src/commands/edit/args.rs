@@ -22,29 +22,56 @@
 */
 use std::path::PathBuf;
 
-use crate::args::{hash_parser, in_path_parser, in_schema_parser, out_path_parser};
+use crate::{
+    args::{
+        collision_parser,
+        compress_parser,
+        hash_parser,
+        in_path_parser,
+        in_schema_parser,
+        mutation_parser,
+        opt_out_path_parser,
+        traversal_parser,
+        MutationOptions,
+        TraversalOptions,
+    },
+    functions::names::NameStyle,
+    output::CollisionPolicy,
+};
 use bpaf::{construct, Parser};
+use moo::{
+    test_file::{normalize::MooNormalizeProfile, redact::MooRedaction},
+    types::hash::MooHashAlgorithm,
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct EditParams {
     pub(crate) in_path: PathBuf,
-    pub(crate) out_path: PathBuf,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) out_path: Option<PathBuf>,
     pub(crate) schema_path: Option<PathBuf>,
     pub(crate) hash: Option<String>,
     pub(crate) index: Option<usize>,
     pub(crate) add_global_mask: bool,
     pub(crate) compress: bool,
+    pub(crate) collision: CollisionPolicy,
     pub(crate) trim: bool,
     pub(crate) set_major_version: Option<u8>,
     pub(crate) set_minor_version: Option<u8>,
     pub(crate) set_metadata_major_version: Option<u8>,
     pub(crate) set_metadata_minor_version: Option<u8>,
+    pub(crate) rename_tests: Option<NameStyle>,
+    pub(crate) digest: Option<MooHashAlgorithm>,
+    pub(crate) redact: Vec<MooRedaction>,
+    pub(crate) normalize: Option<MooNormalizeProfile>,
+    pub(crate) mutation: MutationOptions,
 }
 
 pub(crate) fn edit_parser() -> impl Parser<EditParams> {
     let in_path = in_path_parser();
-    let out_path = out_path_parser();
-    let schema_path = in_schema_parser().optional();
+    let traversal = traversal_parser();
+    let out_path = opt_out_path_parser();
+    let schema_path = in_schema_parser();
     let hash = hash_parser().optional();
     let index = bpaf::long("index")
         .help("Index of the test to edit")
@@ -55,7 +82,8 @@ pub(crate) fn edit_parser() -> impl Parser<EditParams> {
         .help("Add the global register mask from a schema to the tests")
         .switch();
 
-    let compress = bpaf::long("compress").help("Compress the output file(s)").switch();
+    let compress = compress_parser();
+    let collision = collision_parser();
     let trim = bpaf::long("trim")
         .help("Trim test files to count specified in schema")
         .switch();
@@ -80,20 +108,57 @@ pub(crate) fn edit_parser() -> impl Parser<EditParams> {
         .argument::<u8>("METADATA_MINOR_VERSION")
         .optional();
 
+    let rename_tests = bpaf::long("rename-tests")
+        .help("Regenerate all test names from disassembly in the given style (nasm or iced)")
+        .argument::<String>("STYLE")
+        .parse(|s| s.parse::<NameStyle>())
+        .optional();
+
+    let digest = bpaf::long("digest")
+        .help("Add an alternate digest of each test alongside its SHA-1 hash (sha256)")
+        .argument::<String>("ALGORITHM")
+        .parse(|s| s.parse::<MooHashAlgorithm>())
+        .optional();
+
+    let redact = bpaf::long("redact")
+        .help("Remove a chunk (GMET, DGST, TAGS, ANCH, IOST, LICN or a vendor ID) or field (GMET.seed, META.file_seed, META.board_id, META.capture_tool); may be repeated")
+        .argument::<String>("SELECTOR")
+        .parse(|s| s.parse::<MooRedaction>())
+        .many();
+
+    let normalize = bpaf::long("normalize")
+        .help("Canonicalize nondeterministic fields: 'seeds' zeroes generator and file seeds, 'release' also blanks capture details and sorts tests by content hash")
+        .argument::<String>("PROFILE")
+        .parse(|s| s.parse::<MooNormalizeProfile>())
+        .optional();
+
+    let mutation = mutation_parser();
+
     construct!(EditParams {
         in_path,
+        traversal,
         out_path,
         schema_path,
         hash,
         index,
         add_global_mask,
         compress,
+        collision,
         trim,
         set_major_version,
         set_minor_version,
         set_metadata_major_version,
         set_metadata_minor_version,
+        rename_tests,
+        digest,
+        redact,
+        normalize,
+        mutation,
     })
+    .guard(
+        |p| p.out_path.is_some() || p.mutation.dry_run,
+        "--output is required unless --dry-run is specified",
+    )
     .guard(
         |p| {
             if p.add_global_mask {
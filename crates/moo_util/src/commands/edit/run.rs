@@ -21,30 +21,23 @@
     DEALINGS IN THE SOFTWARE.
 */
 
-use std::{
-    collections::HashMap,
-    ffi::{OsStr, OsString},
-    fs,
-    io::Cursor,
-    path::PathBuf,
-};
+use std::{collections::HashMap, fs, io::Cursor, path::PathBuf};
 
 use crate::{
     args::GlobalOptions,
+    changes::{ChangeReport, FileChanges},
     commands::edit::args::EditParams,
     enums::EditErrorDetail,
-    functions::{add_masks::add_global_mask, trim::trim_test},
-    schema_db::{EditSchemaRecord, SchemaDb},
+    output::OutputPathPolicy,
     working_set::WorkingSet,
 };
 use anyhow::Error;
-use moo::{prelude::MooTestFile, types::MooCpuType};
+use moo::prelude::{MooSchema, MooTestFile, MooWriteOptions};
 use rayon::iter::ParallelIterator;
 
 #[derive(Debug, Default)]
 struct EditStats {
-    files_edited: usize,
-    tests_edited: usize,
+    changes: ChangeReport,
     files_with_errors: usize,
     read_errors: usize,
     test_errors: HashMap<PathBuf, Vec<EditErrorDetail>>,
@@ -52,8 +45,7 @@ struct EditStats {
 
 impl EditStats {
     fn combine(mut self, other: EditStats) -> EditStats {
-        self.files_edited += other.files_edited;
-        self.tests_edited += other.tests_edited;
+        self.changes = self.changes.combine(other.changes);
         self.files_with_errors += other.files_with_errors;
         self.read_errors += other.read_errors;
         // Merge edit errors
@@ -70,35 +62,35 @@ impl EditStats {
     }
 }
 
-pub fn run(_global: &GlobalOptions, params: &EditParams) -> Result<(), Error> {
-    let working_set = WorkingSet::from_path(&params.in_path, None)?;
+pub fn run(global: &GlobalOptions, params: &EditParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
 
     if working_set.is_empty() {
         return Err(Error::msg("No files selected"));
     }
 
+    let output_policy = params
+        .out_path
+        .as_ref()
+        .map(|dir| OutputPathPolicy::new(dir, params.compress, params.collision));
+
     let mut load_schema = false;
     if params.add_global_mask || params.trim {
         load_schema = true;
     }
 
-    let schema_db = if load_schema {
+    let schema = if load_schema {
         // Load schema csv file
-        let schema: SchemaDb<EditSchemaRecord> =
-            SchemaDb::from_file(MooCpuType::Intel80386Ex, &params.schema_path.as_ref().unwrap())?;
-        Some(schema)
+        Some(MooSchema::from_path(params.schema_path.as_ref().unwrap())?)
     }
     else {
         None
     };
 
     let edit_stats = working_set
-        .par_iter()
-        .map(|path| {
-            let mut s = EditStats {
-                files_edited: 0,
-                ..Default::default()
-            };
+        .par_map(|path| {
+            let mut s = EditStats::default();
 
             match fs::read(path) {
                 Ok(data) => {
@@ -115,57 +107,96 @@ pub fn run(_global: &GlobalOptions, params: &EditParams) -> Result<(), Error> {
                                 }
                             };
 
-                            // Do per-file edits here
+                            // Do per-file edits here. Changes to tests go through the edit
+                            // journal; file-level changes are described in file_edits.
+                            let mut file_edits = Vec::new();
                             if let Some(major_version) = params.set_major_version {
                                 moo.set_version(Some(major_version), None);
-                                s.files_edited = 1;
+                                file_edits.push(format!("set major version to {}", major_version));
                             }
                             if let Some(minor_version) = params.set_minor_version {
                                 moo.set_version(None, Some(minor_version));
-                                s.files_edited = 1;
+                                file_edits.push(format!("set minor version to {}", minor_version));
                             }
 
-                            if params.add_global_mask {
-                                match add_global_mask(&mut moo, &metadata, schema_db.as_ref().unwrap(), params) {
-                                    Ok(edited) => {
-                                        if edited {
-                                            log::info!("Added global mask to file {}", path.display());
-                                            s.files_edited = 1;
-                                        }
-                                    }
-                                    Err(_) => {
-                                        // TODO: handle error
-                                    }
+                            if params.add_global_mask && schema.as_ref().unwrap().apply_flag_mask(&mut moo) {
+                                log::info!("Added global mask to file {}", path.display());
+                                file_edits.push("set global register mask".to_string());
+                            }
+
+                            if params.trim && schema.as_ref().unwrap().apply_trim(&mut moo) {
+                                log::debug!(
+                                    "Trimmed {} to {} tests for opcode {:02X}",
+                                    path.display(),
+                                    moo.test_ct(),
+                                    metadata.opcode
+                                );
+                            }
+
+                            if !params.redact.is_empty() {
+                                let summary = moo.redact(&params.redact);
+                                if summary.changed() {
+                                    log::info!("{}: {}", path.display(), summary);
+                                }
+                                if summary.file_redacted {
+                                    file_edits.push("redacted file-level chunks or fields".to_string());
                                 }
                             }
 
-                            if params.trim {
-                                match trim_test(&mut moo, &metadata, schema_db.as_ref().unwrap(), params) {
-                                    Ok(edited) => {
-                                        if edited {
-                                            s.files_edited = 1;
-                                        }
+                            if let Some(profile) = params.normalize {
+                                match moo.normalize(profile) {
+                                    Ok(summary) if summary.changed() => {
+                                        log::info!("{}: {}", path.display(), summary);
+                                        file_edits.push(summary.to_string());
                                     }
-                                    Err(_) => {
-                                        // TODO: handle error
+                                    Ok(_) => {}
+                                    Err(e) => {
+                                        log::error!("Error normalizing {}: {}", path.display(), e);
                                     }
                                 }
                             }
 
-                            for (ti, test) in moo.tests_mut().iter_mut().enumerate() {
-                                // Do per-test edits here
+                            if let Some(style) = params.rename_tests {
+                                let summary = moo.regenerate_names(&mut style.formatter());
+                                if !summary.failed.is_empty() {
+                                    log::warn!("{}: could not name tests {:?}", path.display(), summary.failed);
+                                }
+                                if summary.changed() {
+                                    log::info!("{}: {}", path.display(), summary);
+                                    file_edits.push(summary.to_string());
+                                }
                             }
 
-                            // Write edited file if needed
+                            // Check digests after the other edits, which may leave them stale.
+                            if let Some(algorithm) = params.digest {
+                                let needs_digest = moo.tests().iter().enumerate().any(|(ti, test)| {
+                                    test.compute_digest(ti, algorithm)
+                                        .map_or(true, |digest| test.digest() != Some(&digest))
+                                });
+                                if needs_digest {
+                                    file_edits.push(format!("add {} digests", algorithm));
+                                }
+                            }
 
-                            if s.files_edited > 0 || s.tests_edited > 0 {
-                                let out_path = get_edited_path(path, params);
-                                let mut out_file = fs::File::create(out_path).unwrap();
+                            for (ti, test) in moo.tests_mut().iter_mut().enumerate() {
+                                // Do per-test edits here
+                            }
 
-                                // Set compression flag
-                                moo.set_compressed(params.compress);
+                            let changes = FileChanges::new(path, &moo, file_edits);
 
-                                match moo.write(&mut out_file, true) {
+                            // Write edited file if needed
+                            if let Some(policy) = output_policy
+                                .as_ref()
+                                .filter(|_| changes.changed() && !params.mutation.dry_run)
+                            {
+                                let options = MooWriteOptions::new()
+                                    .with_preserve_hash(true)
+                                    .with_digest(params.digest);
+                                let written = policy
+                                    .path_for_input(path)
+                                    .and_then(|out_path| Ok(moo.write_to_path(&out_path, &options)?));
+
+                                match written {
                                     Ok(_) => {
                                         log::info!("Wrote edited file for {}", path.display());
                                     }
@@ -174,6 +205,7 @@ pub fn run(_global: &GlobalOptions, params: &EditParams) -> Result<(), Error> {
                                     }
                                 }
                             }
+                            s.changes = ChangeReport::from_file(changes);
                         }
                         Err(e) => {
                             log::warn!("Parse error in {}: {}", path.display(), e);
@@ -193,29 +225,13 @@ pub fn run(_global: &GlobalOptions, params: &EditParams) -> Result<(), Error> {
         })
         .reduce(EditStats::default, EditStats::combine);
 
-    Ok(())
-}
-
-pub fn get_edited_path(original: &PathBuf, params: &EditParams) -> PathBuf {
-    //let parent = original.parent().unwrap();
-    let filename = original.file_stem().unwrap();
-    let extension = original.extension().unwrap_or_else(|| OsStr::new("MOO"));
-
-    if extension == "gz" && !params.compress {
-        // Special case: original file is .MOO.gz, but we are not compressing output
-        let filename = OsStr::new(filename);
-        let filename = PathBuf::from(filename);
-        let filename = filename.file_stem().unwrap();
-        return params.out_path.join(join_filename_ext(filename, OsStr::new("MOO")));
+    // A dry run's report is its only output, so it is printed even with --silent.
+    if params.mutation.dry_run || !global.silent {
+        edit_stats.changes.print(&params.mutation);
+        if edit_stats.read_errors > 0 {
+            println!("  {} file read errors.", edit_stats.read_errors);
+        }
     }
 
-    let out_path = params.out_path.clone();
-    out_path.join(join_filename_ext(filename, extension))
-}
-
-fn join_filename_ext(filename: &OsStr, extension: &OsStr) -> OsString {
-    let mut result = OsString::from(filename);
-    result.push(".");
-    result.push(extension);
-    result
+    Ok(())
 }
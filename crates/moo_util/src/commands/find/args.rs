@@ -22,21 +22,60 @@
 */
 use std::path::PathBuf;
 
-use crate::args::{hash_parser, in_path_parser};
-use bpaf::{construct, Parser};
+use crate::args::{hash_parser, in_path_parser, traversal_parser, TraversalOptions};
+use bpaf::{construct, long, Parser};
+use moo::types::MooCaptureSource;
 
 #[derive(Clone, Debug)]
 pub(crate) struct FindParams {
     pub(crate) in_path: PathBuf,
-    pub(crate) hash:    Option<String>,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) hash: Option<String>,
+    pub(crate) tag: Option<String>,
+    pub(crate) lock: bool,
+    pub(crate) source: Option<MooCaptureSource>,
+    pub(crate) opcode: Option<String>,
 }
 
 pub(crate) fn find_parser() -> impl Parser<FindParams> {
     //let path = positional::<String>("PATH").help("Path to the file to dump");
 
     let in_path = in_path_parser();
+    let traversal = traversal_parser();
 
     let hash = hash_parser().optional();
 
-    construct!(FindParams { in_path, hash }).guard(|p| p.hash.is_some(), "--hash must be provided")
+    let tag = long("tag")
+        .argument::<String>("TAG")
+        .help("Find all tests with the specified tag")
+        .optional();
+
+    let lock = long("lock")
+        .help("Find all tests whose instruction is locked, by LOCK prefix or implicitly")
+        .switch();
+
+    let source = long("source")
+        .help("Only search files with the specified capture source (hardware, emulator or synthesized)")
+        .argument::<String>("SOURCE")
+        .parse(|s| s.parse::<MooCaptureSource>())
+        .optional();
+
+    let opcode = long("opcode")
+        .help("Only search files testing the specified opcode, e.g. D2.1 or 0F01.4")
+        .argument::<String>("OPCODE")
+        .optional();
+
+    construct!(FindParams {
+        in_path,
+        traversal,
+        hash,
+        tag,
+        lock,
+        source,
+        opcode
+    })
+    .guard(
+        |p| p.hash.is_some() || p.tag.is_some() || p.lock || p.source.is_some() || p.opcode.is_some(),
+        "--hash, --tag, --lock, --source or --opcode must be provided",
+    )
 }
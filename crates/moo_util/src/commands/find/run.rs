@@ -21,48 +21,97 @@
     DEALINGS IN THE SOFTWARE.
 */
 
-use std::{fs, io::Cursor, path::PathBuf};
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
 
 use crate::{args::GlobalOptions, commands::find::args::FindParams, working_set::WorkingSet};
 use anyhow::Error;
-use moo::prelude::*;
+use moo::{prelude::*, types::MooCpuFamily};
 use rayon::prelude::*;
 
 #[derive(Debug)]
 pub struct FindMatch {
     file:  PathBuf,
-    index: usize,
+    /// The index of the matching test, or None if the whole file matched.
+    index: Option<usize>,
 }
 
 #[derive(Debug, Default)]
 struct SearchStats {
     searched: usize,
     errors:   usize,
-    found:    Option<FindMatch>,
+    found:    Vec<FindMatch>,
 }
 
 impl SearchStats {
-    fn combine(mut self, other: SearchStats) -> SearchStats {
+    fn combine(mut self, mut other: SearchStats) -> SearchStats {
         self.searched += other.searched;
         self.errors += other.errors;
-        // keep the first found match if any
-        if self.found.is_none() {
-            self.found = other.found;
-        }
+        self.found.append(&mut other.found);
         self
     }
 }
 
-pub fn run(_global: &GlobalOptions, params: &FindParams) -> Result<(), Error> {
-    let working_set = WorkingSet::from_path(&params.in_path, None)?;
+/// Returns true if the file matches the file-level search criteria provided in `params`.
+fn file_matches(path: &Path, moo: &MooTestFile, params: &FindParams) -> Result<bool, Error> {
+    let metadata = moo.metadata();
+    if let Some(source) = params.source {
+        // Files without metadata have no recorded source.
+        let file_source = metadata.map_or(Some(MooCaptureSource::Unspecified), |m| m.capture_source());
+        if file_source != Some(source) {
+            return Ok(false);
+        }
+    }
+    if let Some(opcode) = &params.opcode {
+        let cpu_family = MooCpuFamily::from(moo.cpu_type());
+        let wanted = MooOpcodeId::parse(opcode, cpu_family)?;
+        // Fall back to the file name for files without an opcode in their metadata.
+        let id = match metadata.and_then(|m| m.opcode_id()) {
+            Some(id) => Some(id),
+            None => MooOpcodeId::from_file_name(path, cpu_family).ok(),
+        };
+        if id.as_ref() != Some(&wanted) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Returns true if the test matches all the search criteria provided in `params`.
+fn test_matches(test: &MooTest, cpu_type: MooCpuType, params: &FindParams) -> bool {
+    if let Some(hash) = &params.hash {
+        if test.hash_string() != *hash {
+            return false;
+        }
+    }
+    if let Some(tag) = &params.tag {
+        if !test.has_tag(tag) {
+            return false;
+        }
+    }
+    if params.lock && !test.is_locked(cpu_type) {
+        return false;
+    }
+    true
+}
+
+pub fn run(global: &GlobalOptions, params: &FindParams) -> Result<(), Error> {
+    if let Some(opcode) = &params.opcode {
+        // Reject malformed opcodes up front rather than once per file.
+        MooOpcodeId::parse(opcode, MooCpuFamily::Intel80386)?;
+    }
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
 
     if working_set.is_empty() {
         return Err(Error::msg("No files selected"));
     }
 
     let stats: SearchStats = working_set
-        .par_iter()
-        .map(|path| {
+        .par_map(|path| {
             let mut s = SearchStats {
                 searched: 1,
                 ..Default::default()
@@ -73,13 +122,31 @@ pub fn run(_global: &GlobalOptions, params: &FindParams) -> Result<(), Error> {
                     let mut reader = Cursor::new(data);
                     match MooTestFile::read(&mut reader) {
                         Ok(moo) => {
-                            if let Some(hash) = &params.hash {
-                                for (t_idx, test) in moo.tests().iter().enumerate() {
-                                    if test.hash_string() == *hash {
-                                        s.found = Some(FindMatch {
-                                            file:  PathBuf::from(path),
-                                            index: t_idx,
-                                        });
+                            match file_matches(path, &moo, params) {
+                                Ok(true) => {}
+                                Ok(false) => return s,
+                                Err(e) => {
+                                    log::warn!("{}: {}", path.display(), e);
+                                    s.errors += 1;
+                                    return s;
+                                }
+                            }
+                            if params.hash.is_none() && params.tag.is_none() && !params.lock {
+                                // Only filtering by file; report the file itself.
+                                s.found.push(FindMatch {
+                                    file:  PathBuf::from(path),
+                                    index: None,
+                                });
+                                return s;
+                            }
+                            for (t_idx, test) in moo.tests().iter().enumerate() {
+                                if test_matches(test, moo.cpu_type(), params) {
+                                    s.found.push(FindMatch {
+                                        file:  PathBuf::from(path),
+                                        index: Some(t_idx),
+                                    });
+                                    if params.tag.is_none() && !params.lock {
+                                        // Hashes are unique; stop at the first match.
                                         break;
                                     }
                                 }
@@ -102,19 +169,24 @@ pub fn run(_global: &GlobalOptions, params: &FindParams) -> Result<(), Error> {
         .reduce(SearchStats::default, SearchStats::combine);
 
     // report summary
-    match stats.found {
-        Some(m) => {
-            println!(
-                "Found in {} at index {} (searched {} files, {} read errors)",
-                m.file.display(),
-                m.index,
-                stats.searched,
-                stats.errors
-            );
-        }
-        None => {
-            println!("No match in {} files ({} read errors)", stats.searched, stats.errors);
+    if stats.found.is_empty() {
+        println!("No match in {} files ({} read errors)", stats.searched, stats.errors);
+    }
+    else {
+        let mut found = stats.found;
+        found.sort_by(|a, b| a.file.cmp(&b.file).then(a.index.cmp(&b.index)));
+        for m in &found {
+            match m.index {
+                Some(index) => println!("Found in {} at index {}", m.file.display(), index),
+                None => println!("Found {}", m.file.display()),
+            }
         }
+        println!(
+            "{} match(es) (searched {} files, {} read errors)",
+            found.len(),
+            stats.searched,
+            stats.errors
+        );
     }
 
     Ok(())
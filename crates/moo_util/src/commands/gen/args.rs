@@ -0,0 +1,163 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::{
+    args::{collision_parser, compress_parser, in_path_parser, out_path_parser},
+    output::CollisionPolicy,
+};
+
+use bpaf::{construct, long, Parser};
+use moo::prelude::*;
+
+#[derive(Clone, Debug)]
+pub(crate) struct GenParams {
+    pub(crate) cpu_type: MooCpuType,
+    pub(crate) opcodes: Vec<String>,
+    pub(crate) seed: u64,
+    pub(crate) count: usize,
+    pub(crate) emulator: String,
+    pub(crate) hardware: Option<String>,
+    pub(crate) disagreements_path: Option<PathBuf>,
+    pub(crate) out_path: PathBuf,
+    pub(crate) compress: bool,
+    pub(crate) collision: CollisionPolicy,
+}
+
+/// Parse a CPU type as given on the command line, e.g. `8088` or `286`.
+pub(crate) fn parse_cpu_type(s: &str) -> Result<MooCpuType, String> {
+    MooCpuType::from_str(&format!("{:<4}", s.to_ascii_uppercase()))
+}
+
+pub(crate) fn gen_parser() -> impl Parser<GenParams> {
+    let cpu_type = long("cpu")
+        .argument::<String>("CPU")
+        .help("CPU type to generate tests for, e.g. 8088, 286 or 386E")
+        .parse(|s| parse_cpu_type(&s));
+    let opcodes = long("opcodes")
+        .argument::<String>("OPCODES")
+        .help("Comma-separated list of opcodes to generate, e.g. 00,D2.1,0FBA.4")
+        .map(|s| s.split(',').map(|op| op.trim().to_string()).collect::<Vec<_>>());
+    let seed = long("seed")
+        .argument::<u64>("SEED")
+        .help("Seed for the test generator")
+        .fallback(0);
+    let count = long("count")
+        .argument::<usize>("COUNT")
+        .help("Number of tests to generate per opcode")
+        .fallback(500);
+    let emulator = long("emulator")
+        .short('e')
+        .argument::<String>("COMMAND")
+        .help("Command line of the emulator process to drive over JSON-RPC");
+    let hardware = long("hardware")
+        .argument::<String>("COMMAND")
+        .help("Command line of a hardware capture server speaking the same protocol. Tests are captured from hardware and kept only if the emulator agrees")
+        .optional();
+    let disagreements_path = long("disagreements")
+        .argument::<PathBuf>("DISAGREEMENTS_PATH")
+        .help("Directory to write tests where hardware and emulator disagree")
+        .optional();
+    let out_path = out_path_parser();
+    let compress = compress_parser();
+    let collision = collision_parser();
+
+    construct!(GenParams {
+        cpu_type,
+        opcodes,
+        seed,
+        count,
+        emulator,
+        hardware,
+        disagreements_path,
+        out_path,
+        compress,
+        collision,
+    })
+    .guard(
+        |p| p.disagreements_path.is_none() || p.hardware.is_some(),
+        "--disagreements requires --hardware",
+    )
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MutateParams {
+    pub(crate) in_path: PathBuf,
+    pub(crate) seed: u64,
+    pub(crate) count: usize,
+    pub(crate) out_path: PathBuf,
+    pub(crate) compress: bool,
+    pub(crate) collision: CollisionPolicy,
+}
+
+pub(crate) fn mutate_parser() -> impl Parser<MutateParams> {
+    let in_path = long("mutate-from")
+        .argument::<PathBuf>("INPUT_PATH")
+        .help("Derive mutated variants of the tests in a MOO file instead of running an emulator");
+    let seed = long("seed")
+        .argument::<u64>("SEED")
+        .help("Seed for choosing mutations")
+        .fallback(0);
+    let count = long("count")
+        .argument::<usize>("COUNT")
+        .help("Number of mutated variants to derive from each test")
+        .fallback(1);
+    let out_path = out_path_parser();
+    let compress = compress_parser();
+    let collision = collision_parser();
+
+    construct!(MutateParams {
+        in_path,
+        seed,
+        count,
+        out_path,
+        compress,
+        collision,
+    })
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct RegenParams {
+    pub(crate) in_path:  PathBuf,
+    pub(crate) index:    usize,
+    pub(crate) emulator: String,
+}
+
+pub(crate) fn regen_parser() -> impl Parser<RegenParams> {
+    let in_path = in_path_parser();
+    let index = long("index")
+        .short('i')
+        .argument::<usize>("INDEX")
+        .help("Index of the test to regenerate");
+    let emulator = long("emulator")
+        .short('e')
+        .argument::<String>("COMMAND")
+        .help("Command line of the emulator process to drive over JSON-RPC");
+
+    construct!(RegenParams {
+        in_path,
+        index,
+        emulator
+    })
+}
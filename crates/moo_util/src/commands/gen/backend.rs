@@ -0,0 +1,174 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Backends that execute a single instruction from a generated initial state.
+//!
+//! The generator only needs an initial state in and a final state and cycle trace out, so any
+//! source of execution can drive it by implementing [TestBackend].
+
+use std::collections::BTreeSet;
+
+use crate::commands::replay::rpc::{EmulatorClient, EmulatorCycle, EmulatorState, GetStateParams, SetStateParams};
+use anyhow::Error;
+use moo::{
+    prelude::*,
+    types::{MooBusState, MooDataWidth, MooRamEntry, MooTestState},
+};
+use serde_json::Value;
+
+/// The outcome of executing one instruction on a [TestBackend].
+pub(crate) struct BackendOutput {
+    /// The full register file after execution.
+    pub(crate) regs:   MooRegisters,
+    /// The value of each initial RAM address and each address written during execution.
+    pub(crate) ram:    Vec<MooRamEntry>,
+    /// The cycles executed.
+    pub(crate) cycles: Vec<MooCycleState>,
+}
+
+/// A source of instruction execution for the test generator.
+pub(crate) trait TestBackend {
+    /// A short name for the backend, recorded as the capture tool of generated files.
+    fn name(&self) -> &str;
+
+    /// Load `initial`, execute the instruction in `bytes` and return the resulting state.
+    /// The addresses in `read_ram` are read back along with the initial RAM and any written
+    /// addresses.
+    fn execute(
+        &mut self,
+        cpu_type: MooCpuType,
+        bytes: &[u8],
+        initial: &MooTestState,
+        read_ram: &[u32],
+    ) -> Result<BackendOutput, Error>;
+}
+
+/// A [TestBackend] driving an external process over JSON-RPC, using the protocol described in
+/// [crate::commands::replay::rpc]. The process may be an emulator or a hardware capture server.
+pub(crate) struct RpcBackend {
+    name:   String,
+    client: EmulatorClient,
+    cycles: bool,
+}
+
+impl RpcBackend {
+    pub(crate) fn spawn(command: &str) -> Result<Self, Error> {
+        let name = command.split_whitespace().next().unwrap_or_default();
+        // Use the program's file name, without any leading directories.
+        let name = name.rsplit(['/', '\\']).next().unwrap_or(name).to_string();
+        Ok(Self {
+            name,
+            client: EmulatorClient::spawn(command)?,
+            cycles: true,
+        })
+    }
+
+    /// Set whether cycles are requested from the process. Without cycles, the output has an empty
+    /// cycle trace and only the initial RAM and `read_ram` addresses are read back.
+    pub(crate) fn with_cycles(mut self, cycles: bool) -> Self {
+        self.cycles = cycles;
+        self
+    }
+}
+
+impl TestBackend for RpcBackend {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn execute(
+        &mut self,
+        cpu_type: MooCpuType,
+        bytes: &[u8],
+        initial: &MooTestState,
+        read_ram: &[u32],
+    ) -> Result<BackendOutput, Error> {
+        let (_, initial_regs) = initial.regs().named_values();
+        let _: Value = self.client.call(
+            "set_state",
+            SetStateParams {
+                cpu_type: cpu_type.to_str().trim(),
+                bytes,
+                regs: initial_regs.into_iter().collect(),
+                queue: initial.queue(),
+                ram: initial.ram().iter().map(|e| [e.address, e.value as u32]).collect(),
+            },
+        )?;
+        let _: Value = self.client.call("step", ())?;
+
+        let cycles: Vec<MooCycleState> = if self.cycles {
+            let cycles: Vec<EmulatorCycle> = self.client.call("get_cycles", ())?;
+            cycles.into_iter().map(MooCycleState::from).collect()
+        }
+        else {
+            Vec::new()
+        };
+
+        let mut addresses: BTreeSet<u32> = initial.ram().iter().map(|e| e.address).collect();
+        addresses.extend(read_ram);
+        addresses.extend(written_addresses(cpu_type, &cycles));
+
+        let state: EmulatorState = self.client.call(
+            "get_state",
+            GetStateParams {
+                ram: addresses.into_iter().collect(),
+            },
+        )?;
+
+        let regs = MooRegisters::from_named_values(
+            matches!(initial.regs(), MooRegisters::ThirtyTwo(_)),
+            state.regs.iter().map(|(name, value)| (name.as_str(), *value)),
+        )?;
+        let ram = state
+            .ram
+            .iter()
+            .map(|[address, value]| MooRamEntry {
+                address: *address,
+                value:   *value as u8,
+            })
+            .collect();
+
+        Ok(BackendOutput { regs, ram, cycles })
+    }
+}
+
+/// Return the byte addresses accessed by memory transactions of the given bus states.
+pub(crate) fn accessed_addresses(cpu_type: MooCpuType, cycles: &[MooCycleState], states: &[MooBusState]) -> Vec<u32> {
+    let mut addresses = Vec::new();
+    for transaction in cycles.iter().copied().transactions() {
+        if !states.contains(&transaction.bus_state(cpu_type)) {
+            continue;
+        }
+        match transaction.data_width(cpu_type) {
+            MooDataWidth::Sixteen => addresses.extend([transaction.address, transaction.address.wrapping_add(1)]),
+            MooDataWidth::EightLow | MooDataWidth::EightHigh => addresses.push(transaction.address),
+            MooDataWidth::Invalid => {}
+        }
+    }
+    addresses
+}
+
+/// Return the byte addresses written by memory write transactions.
+pub(crate) fn written_addresses(cpu_type: MooCpuType, cycles: &[MooCycleState]) -> Vec<u32> {
+    accessed_addresses(cpu_type, cycles, &[MooBusState::MEMW])
+}
@@ -0,0 +1,263 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Random initial state generation and test assembly.
+
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use crate::{
+    commands::gen::backend::{accessed_addresses, written_addresses, BackendOutput, TestBackend},
+    functions::disasm::MartyDisassembler,
+};
+use anyhow::{anyhow, Error};
+use moo::{
+    prelude::*,
+    test_file::seed::MooSplitMix64,
+    types::{MooBusState, MooComparison, MooRamEntry, MooStateType, MooTestState},
+};
+
+/// The number of random bytes following the opcode and ModR/M byte, enough for the longest
+/// displacement and immediate operands.
+const OPERAND_BYTES: usize = 12;
+/// The maximum number of times a test is executed while discovering the memory it reads.
+const MAX_PASSES: u16 = 8;
+
+/// Return the mask of address lines present on the given [MooCpuType].
+fn address_mask(cpu_type: MooCpuType) -> u32 {
+    match MooCpuFamily::from(cpu_type) {
+        MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186 => 0x000F_FFFF,
+        MooCpuFamily::Intel80286 => 0x00FF_FFFF,
+        MooCpuFamily::Intel80386 => 0x03FF_FFFF,
+    }
+}
+
+/// Build random real-mode registers for the given [MooCpuType]. The trap flag is kept clear so
+/// that the instruction runs to completion.
+fn random_regs(cpu_type: MooCpuType, rng: &mut MooSplitMix64) -> Result<MooRegisters, Error> {
+    let mut flags = MooCpuFlags::from_bits(rng.next_u16() as u32);
+    flags.clear(MooCpuFlag::TF);
+    let flags = flags.normalize(cpu_type).bits();
+
+    let regs = match MooRegisters::default_opt(cpu_type) {
+        MooRegisters::Sixteen(_) => {
+            let mut values: Vec<(&str, u32)> = MooRegisters::NAMES16[..13]
+                .iter()
+                .map(|name| (*name, rng.next_u16() as u32))
+                .collect();
+            values.push(("flags", flags));
+            MooRegisters::from_named_values(false, values)?
+        }
+        MooRegisters::ThirtyTwo(_) => {
+            let values = MooRegisters::NAMES32.iter().map(|name| {
+                let value = match *name {
+                    "cr0" | "cr3" | "dr6" | "dr7" => 0,
+                    "cs" | "ds" | "es" | "fs" | "gs" | "ss" | "esp" | "eip" => rng.next_u16() as u32,
+                    "eflags" => flags,
+                    _ => rng.next_u32(),
+                };
+                (*name, value)
+            });
+            MooRegisters::from_named_values(true, values.collect::<Vec<_>>())?
+        }
+    };
+    Ok(regs)
+}
+
+/// Build random instruction bytes for `opcode`, decode them for `cpu_type`, and return the bytes of
+/// the decoded instruction along with its disassembly.
+fn random_instruction(
+    cpu_type: MooCpuType,
+    opcode: &MooOpcodeId,
+    ip: u32,
+    rng: &mut MooSplitMix64,
+) -> Result<(Vec<u8>, String), Error> {
+    let mut bytes: Vec<u8> = opcode.prefixes().iter().chain(opcode.opcode()).copied().collect();
+    let mut modrm = rng.next_u8();
+    if let Some(extension) = opcode.extension() {
+        modrm = (modrm & 0b1100_0111) | (extension << 3);
+    }
+    bytes.push(modrm);
+    bytes.extend((0..OPERAND_BYTES).map(|_| rng.next_u8()));
+
+    let instr = MartyDisassembler::new(true).decode(cpu_type, &bytes, ip)?;
+    bytes.truncate(instr.length);
+    Ok((bytes, instr.text))
+}
+
+/// Generate a single test of `opcode` on `backend` from the given per-test seed.
+///
+/// The instruction is placed at `CS:IP` and executed. Any other memory the backend reads is
+/// filled with random bytes and the instruction is executed again, until a pass reads nothing new.
+pub(crate) fn generate_test(
+    backend: &mut dyn TestBackend,
+    cpu_type: MooCpuType,
+    opcode: &MooOpcodeId,
+    seed: u64,
+) -> Result<MooTest, Error> {
+    let mut rng = MooSplitMix64::new(seed);
+    let mask = address_mask(cpu_type);
+
+    let regs = random_regs(cpu_type, &mut rng)?;
+    let csip = regs
+        .csip_linear_real()
+        .ok_or_else(|| anyhow!("Generated registers have no CS:IP"))?;
+    let (bytes, name) = random_instruction(cpu_type, opcode, csip, &mut rng)?;
+
+    let mut ram: BTreeMap<u32, u8> = BTreeMap::new();
+    for (i, byte) in bytes.iter().enumerate() {
+        ram.insert(csip.wrapping_add(i as u32) & mask, *byte);
+    }
+
+    let mut gen_ct = 0;
+    let (initial, output) = loop {
+        gen_ct += 1;
+        let initial = MooTestState {
+            s_type: MooStateType::Initial,
+            regs: regs.clone(),
+            ram: ram
+                .iter()
+                .map(|(&address, &value)| MooRamEntry { address, value })
+                .collect(),
+            ..Default::default()
+        };
+        let output = backend.execute(cpu_type, &bytes, &initial, &[])?;
+
+        let reads = accessed_addresses(cpu_type, &output.cycles, &[MooBusState::CODE, MooBusState::MEMR]);
+        let mut discovered = false;
+        for address in reads {
+            if let Entry::Vacant(e) = ram.entry(address & mask) {
+                e.insert(rng.next_u8());
+                discovered = true;
+            }
+        }
+        if !discovered {
+            break (initial, output);
+        }
+        if gen_ct >= MAX_PASSES {
+            return Err(anyhow!(
+                "Memory reads of '{}' did not settle after {} passes",
+                name,
+                gen_ct
+            ));
+        }
+    };
+
+    Ok(assemble_test(
+        cpu_type,
+        name,
+        Some(MooTestGenMetadata::new(seed, gen_ct)),
+        &bytes,
+        initial,
+        output,
+    ))
+}
+
+/// Execute `test`'s initial state on `backend`, returning a test with the same name, bytes and
+/// initial state, and the final state and cycles reported by the backend.
+pub(crate) fn reexecute_test(
+    backend: &mut dyn TestBackend,
+    cpu_type: MooCpuType,
+    test: &MooTest,
+) -> Result<MooTest, Error> {
+    let output = backend.execute(cpu_type, test.bytes(), test.initial_state(), &[])?;
+    Ok(assemble_test(
+        cpu_type,
+        test.name().to_string(),
+        test.gen_metadata().cloned(),
+        test.bytes(),
+        test.initial_state().clone(),
+        output,
+    ))
+}
+
+/// Adapts a [TestBackend] to [MooStateGenerator], so that a single test of a generated file can
+/// be reproduced from its seed.
+pub(crate) struct BackendStateGenerator<'a>(pub(crate) &'a mut dyn TestBackend);
+
+impl MooStateGenerator for BackendStateGenerator<'_> {
+    type Error = Error;
+
+    fn initial_state(&mut self, cpu_type: MooCpuType, opcode: &MooOpcodeId, seed: u64) -> Result<MooTestState, Error> {
+        Ok(generate_test(self.0, cpu_type, opcode, seed)?.initial_state().clone())
+    }
+}
+
+/// Build a [MooTest] from an initial state and the [BackendOutput] of executing it. The final
+/// state holds only the registers and memory that changed.
+fn assemble_test(
+    cpu_type: MooCpuType,
+    name: String,
+    gen_metadata: Option<MooTestGenMetadata>,
+    bytes: &[u8],
+    initial: MooTestState,
+    output: BackendOutput,
+) -> MooTest {
+    let written = written_addresses(cpu_type, &output.cycles);
+    let final_ram = output
+        .ram
+        .into_iter()
+        .filter(|e| {
+            written.contains(&e.address)
+                || !initial
+                    .ram()
+                    .iter()
+                    .any(|i| i.address == e.address && i.value == e.value)
+        })
+        .collect();
+    let final_state = MooTestState {
+        s_type: MooStateType::Final,
+        regs: initial.regs().delta(&output.regs),
+        ram: final_ram,
+        ..Default::default()
+    };
+
+    MooTest::new(
+        name,
+        gen_metadata,
+        bytes,
+        initial,
+        final_state,
+        &output.cycles,
+        None,
+        None,
+    )
+}
+
+/// Compare two executions of the same initial state. In addition to the differences found by
+/// [MooTest::compare], final memory is compared.
+pub(crate) fn compare_executions(expected: &MooTest, actual: &MooTest) -> Vec<MooComparison> {
+    let mut differences = expected.compare(actual, false);
+    for entry in expected.final_state().ram() {
+        let actual_entry = actual
+            .final_state()
+            .ram()
+            .iter()
+            .find(|e| e.address == entry.address)
+            .copied()
+            .unwrap_or_default();
+        if actual_entry.value != entry.value || actual_entry.address != entry.address {
+            differences.push(MooComparison::MemoryValueMismatch(*entry, actual_entry));
+        }
+    }
+    differences
+}
@@ -0,0 +1,462 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+use crate::{
+    args::GlobalOptions,
+    commands::gen::{
+        args::{GenParams, MutateParams, RegenParams},
+        backend::{RpcBackend, TestBackend},
+        generator::{compare_executions, generate_test, reexecute_test, BackendStateGenerator},
+    },
+    file::read_moo_file,
+    output::OutputPathPolicy,
+};
+use anyhow::{anyhow, Context, Error};
+use moo::{prelude::*, test_file::seed::derive_test_seed, MOO_MAJOR_VERSION, MOO_MINOR_VERSION};
+
+/// Tag applied to tests written to the disagreements file.
+const DISAGREEMENT_TAG: &str = "emulator-disagreement";
+/// In differential mode, give up on an opcode after this many attempts per requested test.
+const MAX_ATTEMPTS_PER_TEST: usize = 4;
+
+pub fn run(global: &GlobalOptions, params: &GenParams) -> Result<(), Error> {
+    let mut emulator = RpcBackend::spawn(&params.emulator)?;
+    let mut hardware = params.hardware.as_deref().map(RpcBackend::spawn).transpose()?;
+    write_files(
+        global,
+        params,
+        &mut emulator,
+        hardware.as_mut().map(|h| h as &mut dyn TestBackend),
+    )
+}
+
+/// Generate a file for each opcode in `params` and write it to the output directory. With a
+/// `hardware` backend, tests are captured from hardware and checked against `emulator`.
+fn write_files(
+    global: &GlobalOptions,
+    params: &GenParams,
+    emulator: &mut dyn TestBackend,
+    mut hardware: Option<&mut dyn TestBackend>,
+) -> Result<(), Error> {
+    let family = MooCpuFamily::from(params.cpu_type);
+    let opcodes = params
+        .opcodes
+        .iter()
+        .map(|op| MooOpcodeId::parse(op, family).map_err(|e| anyhow!("Invalid opcode '{}': {}", op, e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let output_policy = OutputPathPolicy::new(&params.out_path, params.compress, params.collision);
+    let disagreements_policy = params
+        .disagreements_path
+        .as_ref()
+        .map(|dir| OutputPathPolicy::new(dir, params.compress, params.collision));
+
+    for opcode in &opcodes {
+        let (mut moo, disagreements) = match hardware.as_deref_mut() {
+            Some(hardware) => generate_differential(hardware, emulator, params, opcode)?,
+            None => (generate_file(emulator, params, opcode)?, Vec::new()),
+        };
+        let out_path = output_policy.path_for_stem(&opcode.to_string())?;
+        moo.write_to_path(&out_path, &MooWriteOptions::new())?;
+        global.loud(|| println!("Wrote {} tests to {}", moo.test_ct(), out_path.display()));
+
+        if let Some(policy) = &disagreements_policy {
+            if !disagreements.is_empty() {
+                let mut d_moo = MooTestFile::new(
+                    MOO_MAJOR_VERSION,
+                    MOO_MINOR_VERSION,
+                    params.cpu_type,
+                    disagreements.len(),
+                );
+                for test in disagreements {
+                    d_moo.add_test(test);
+                }
+                d_moo.set_metadata(file_metadata(
+                    &d_moo,
+                    params,
+                    opcode,
+                    MooCaptureSource::Hardware,
+                    hardware.as_deref().map(|h| h.name()).unwrap_or_default(),
+                )?);
+                let out_path = policy.path_for_stem(&opcode.to_string())?;
+                d_moo.write_to_path(&out_path, &MooWriteOptions::new())?;
+                global.loud(|| println!("Wrote {} disagreements to {}", d_moo.test_ct(), out_path.display()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Regenerate the initial state of one test of a generated file from its seed, and report whether
+/// it matches the stored initial state.
+pub fn regen(global: &GlobalOptions, params: &RegenParams) -> Result<(), Error> {
+    let moo = read_moo_file(&params.in_path)?;
+    let test = moo
+        .tests()
+        .get(params.index)
+        .ok_or_else(|| anyhow!("Test index {} is out of range", params.index))?;
+    let seed = moo
+        .test_seed(params.index)
+        .ok_or_else(|| anyhow!("No seed is available for test {}", params.index))?;
+    global.loud(|| {
+        println!(
+            "Regenerating test {} ({}) from seed {:016X}",
+            params.index,
+            test.name(),
+            seed
+        )
+    });
+
+    let mut emulator = RpcBackend::spawn(&params.emulator)?;
+    let regenerated = moo.regenerate_initial_state(params.index, &mut BackendStateGenerator(&mut emulator))?;
+
+    let stored = test.initial_state();
+    let mut mismatches = 0;
+    if regenerated.regs() != stored.regs() {
+        println!("Initial registers differ");
+        mismatches += 1;
+    }
+    for entry in stored.ram() {
+        let value = regenerated
+            .ram()
+            .iter()
+            .find(|e| e.address == entry.address)
+            .map(|e| e.value);
+        if value != Some(entry.value) {
+            println!(
+                "Memory at {:06X}: stored {:02X}, regenerated {:02X?}",
+                entry.address, entry.value, value
+            );
+            mismatches += 1;
+        }
+    }
+    if regenerated.ram().len() != stored.ram().len() {
+        println!(
+            "Memory entry count differs: stored {}, regenerated {}",
+            stored.ram().len(),
+            regenerated.ram().len()
+        );
+        mismatches += 1;
+    }
+
+    if mismatches > 0 {
+        return Err(anyhow!(
+            "Regenerated initial state of test {} does not match",
+            params.index
+        ));
+    }
+    println!("Regenerated initial state of test {} matches", params.index);
+    Ok(())
+}
+
+/// Write a file of mutated variants of the tests in `params.in_path`, for fuzzing emulators. The
+/// file is marked as synthesized, and each test is tagged with the mutation applied to it.
+pub fn mutate(global: &GlobalOptions, params: &MutateParams) -> Result<(), Error> {
+    let source = read_moo_file(&params.in_path)?;
+    let cpu_type = source.cpu_type();
+    let metadata = source
+        .metadata()
+        .ok_or_else(|| anyhow!("{} has no metadata", params.in_path.display()))?
+        .clone();
+
+    let mut mutator = MooMutator::new(params.seed);
+    let mut moo = MooTestFile::new(
+        MOO_MAJOR_VERSION,
+        MOO_MINOR_VERSION,
+        cpu_type,
+        source.test_ct() * params.count,
+    );
+    for (index, test) in source.tests().iter().enumerate() {
+        for _ in 0..params.count {
+            match mutator.mutate(cpu_type, test) {
+                Some((mutation, mutated)) => {
+                    log::debug!("Test {} ({}): {}", index, test.name(), mutation);
+                    moo.add_test(mutated);
+                }
+                None => log::warn!("No mutation applies to test {} ({})", index, test.name()),
+            }
+        }
+    }
+
+    moo.set_metadata(
+        metadata
+            .with_test_count(moo.test_ct() as u32)
+            .with_file_seed(params.seed)
+            .with_capture(MooCaptureInfo::new(MooCaptureSource::Synthesized, "moo_util mutate")),
+    );
+    let out_path =
+        OutputPathPolicy::new(&params.out_path, params.compress, params.collision).path_for_input(&params.in_path)?;
+    moo.write_to_path(&out_path, &MooWriteOptions::new())?;
+    global.loud(|| println!("Wrote {} mutated tests to {}", moo.test_ct(), out_path.display()));
+    Ok(())
+}
+
+/// Generate a complete [MooTestFile] for `opcode`, with metadata describing how it was produced.
+pub(crate) fn generate_file(
+    backend: &mut dyn TestBackend,
+    params: &GenParams,
+    opcode: &MooOpcodeId,
+) -> Result<MooTestFile, Error> {
+    let mut moo = MooTestFile::new(MOO_MAJOR_VERSION, MOO_MINOR_VERSION, params.cpu_type, params.count);
+    for index in 0..params.count {
+        let seed = derive_test_seed(params.seed, opcode, index);
+        let test = generate_test(backend, params.cpu_type, opcode, seed)
+            .with_context(|| format!("Failed to generate test {} of {}", index, opcode))?;
+        moo.add_test(test);
+    }
+    moo.set_metadata(file_metadata(
+        &moo,
+        params,
+        opcode,
+        MooCaptureSource::Emulator,
+        backend.name(),
+    )?);
+    Ok(moo)
+}
+
+/// Generate a [MooTestFile] for `opcode` from `hardware`, keeping only tests that `emulator`
+/// reproduces exactly. Tests where the two disagree are returned separately, tagged with
+/// [DISAGREEMENT_TAG].
+pub(crate) fn generate_differential(
+    hardware: &mut dyn TestBackend,
+    emulator: &mut dyn TestBackend,
+    params: &GenParams,
+    opcode: &MooOpcodeId,
+) -> Result<(MooTestFile, Vec<MooTest>), Error> {
+    let mut moo = MooTestFile::new(MOO_MAJOR_VERSION, MOO_MINOR_VERSION, params.cpu_type, params.count);
+    let mut disagreements = Vec::new();
+
+    let max_attempts = params.count.saturating_mul(MAX_ATTEMPTS_PER_TEST);
+    let mut index = 0;
+    while moo.test_ct() < params.count {
+        if index >= max_attempts {
+            return Err(anyhow!(
+                "Only {} of {} tests of {} agreed after {} attempts",
+                moo.test_ct(),
+                params.count,
+                opcode,
+                index
+            ));
+        }
+        let seed = derive_test_seed(params.seed, opcode, index);
+        let mut test = generate_test(hardware, params.cpu_type, opcode, seed)
+            .with_context(|| format!("Failed to capture test {} of {}", index, opcode))?;
+        let emulated = reexecute_test(emulator, params.cpu_type, &test)
+            .with_context(|| format!("Failed to emulate test {} of {}", index, opcode))?;
+
+        let differences = compare_executions(&test, &emulated);
+        match differences.first() {
+            None => moo.add_test(test),
+            Some(difference) => {
+                log::warn!(
+                    "Test {} of {} ({}) disagrees: {}",
+                    index,
+                    opcode,
+                    test.name(),
+                    difference
+                );
+                test.add_tag(DISAGREEMENT_TAG);
+                disagreements.push(test);
+            }
+        }
+        index += 1;
+    }
+
+    moo.set_metadata(file_metadata(
+        &moo,
+        params,
+        opcode,
+        MooCaptureSource::Hardware,
+        hardware.name(),
+    )?);
+    Ok((moo, disagreements))
+}
+
+/// Build the [MooFileMetadata] for a generated file. The mnemonic is taken from the disassembly
+/// of the first test.
+fn file_metadata(
+    moo: &MooTestFile,
+    params: &GenParams,
+    opcode: &MooOpcodeId,
+    source: MooCaptureSource,
+    tool: &str,
+) -> Result<MooFileMetadata, Error> {
+    let mnemonic = moo
+        .tests()
+        .first()
+        .and_then(|test| test.name().split_whitespace().next())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut metadata = MooFileMetadata::new(1, 0, params.cpu_type, 0, None)
+        .with_test_count(moo.test_ct() as u32)
+        .with_file_seed(params.seed)
+        .with_mnemonic(mnemonic)
+        .with_capture(MooCaptureInfo::new(source, tool));
+    metadata.set_opcode_id(opcode)?;
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_files, DISAGREEMENT_TAG};
+    use crate::{
+        args::GlobalOptions,
+        commands::gen::{
+            args::GenParams,
+            backend::{BackendOutput, TestBackend},
+            generator::BackendStateGenerator,
+        },
+        file::read_moo_file,
+        output::CollisionPolicy,
+    };
+    use anyhow::Error;
+    use moo::{prelude::*, test_file::seed::derive_test_seed, types::MooTestState};
+    use std::path::Path;
+
+    /// A [TestBackend] that leaves the CPU state unchanged. If `disagree` is set, every second
+    /// execution inverts AX instead.
+    struct MockBackend {
+        name: &'static str,
+        disagree: bool,
+        calls: usize,
+    }
+
+    impl MockBackend {
+        fn new(name: &'static str) -> Self {
+            Self {
+                name,
+                disagree: false,
+                calls: 0,
+            }
+        }
+    }
+
+    impl TestBackend for MockBackend {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn execute(
+            &mut self,
+            _cpu_type: MooCpuType,
+            _bytes: &[u8],
+            initial: &MooTestState,
+            _read_ram: &[u32],
+        ) -> Result<BackendOutput, Error> {
+            let mut regs = initial.regs().clone();
+            if self.disagree && self.calls % 2 == 1 {
+                if let MooRegisters::Sixteen(r) = &mut regs {
+                    r.set_ax(!r.ax);
+                }
+            }
+            self.calls += 1;
+            Ok(BackendOutput {
+                regs,
+                ram: initial.ram().to_vec(),
+                cycles: Vec::new(),
+            })
+        }
+    }
+
+    fn params(out_path: &Path) -> GenParams {
+        GenParams {
+            cpu_type: MooCpuType::Intel8088,
+            opcodes: vec!["00".to_string(), "D2.1".to_string()],
+            seed: 1,
+            count: 4,
+            emulator: String::new(),
+            hardware: None,
+            disagreements_path: None,
+            out_path: out_path.to_path_buf(),
+            compress: false,
+            collision: CollisionPolicy::Error,
+        }
+    }
+
+    fn global() -> GlobalOptions {
+        GlobalOptions {
+            silent: true,
+            jobs: None,
+            max_memory: None,
+        }
+    }
+
+    #[test]
+    fn writes_generated_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let params = params(tmp.path());
+        let mut emulator = MockBackend::new("mock");
+        write_files(&global(), &params, &mut emulator, None).unwrap();
+
+        for opcode in &params.opcodes {
+            let moo = read_moo_file(tmp.path().join(format!("{}.MOO", opcode))).unwrap();
+            assert_eq!(moo.test_ct(), params.count);
+            assert!(moo.verify_hashes().unwrap().is_empty());
+
+            let metadata = moo.metadata().unwrap();
+            assert_eq!(metadata.file_seed, params.seed);
+            let capture = metadata.capture.as_ref().unwrap();
+            assert!(matches!(capture.source(), Some(MooCaptureSource::Emulator)));
+            assert_eq!(capture.tool().as_deref(), Some("mock"));
+
+            // Each test records its seed, from which its initial state can be regenerated.
+            let opcode = MooOpcodeId::parse(opcode, MooCpuFamily::Intel8086).unwrap();
+            for (index, test) in moo.tests().iter().enumerate() {
+                let seed = derive_test_seed(params.seed, &opcode, index);
+                assert_eq!(test.gen_metadata().unwrap().seed, seed);
+                let regenerated = moo
+                    .regenerate_initial_state(index, &mut BackendStateGenerator(&mut emulator))
+                    .unwrap();
+                assert!(regenerated.regs() == test.initial_state().regs());
+            }
+        }
+
+        // Existing files are not overwritten by default.
+        assert!(write_files(&global(), &params, &mut emulator, None).is_err());
+    }
+
+    #[test]
+    fn writes_disagreements() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut params = params(&tmp.path().join("out"));
+        params.opcodes.truncate(1);
+        params.disagreements_path = Some(tmp.path().join("disagreements"));
+        let mut hardware = MockBackend::new("hardware");
+        let mut emulator = MockBackend {
+            disagree: true,
+            ..MockBackend::new("emulator")
+        };
+        write_files(&global(), &params, &mut emulator, Some(&mut hardware)).unwrap();
+
+        let moo = read_moo_file(tmp.path().join("out/00.MOO")).unwrap();
+        assert_eq!(moo.test_ct(), params.count);
+        let capture = moo.metadata().unwrap().capture.as_ref().unwrap();
+        assert!(matches!(capture.source(), Some(MooCaptureSource::Hardware)));
+        assert_eq!(capture.tool().as_deref(), Some("hardware"));
+
+        // Every second attempt disagrees, so four agreeing tests take seven attempts.
+        let disagreements = read_moo_file(tmp.path().join("disagreements/00.MOO")).unwrap();
+        assert_eq!(disagreements.test_ct(), 3);
+        assert!(disagreements.tests().iter().all(|t| t.has_tag(DISAGREEMENT_TAG)));
+    }
+}
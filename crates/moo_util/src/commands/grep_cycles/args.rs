@@ -0,0 +1,52 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::args::{in_path_parser, traversal_parser, TraversalOptions};
+use bpaf::{construct, long, Parser};
+use moo::types::cycle_pattern::MooCyclePattern;
+
+#[derive(Clone, Debug)]
+pub(crate) struct GrepCyclesParams {
+    pub(crate) in_path:   PathBuf,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) pattern:   MooCyclePattern,
+}
+
+pub(crate) fn grep_cycles_parser() -> impl Parser<GrepCyclesParams> {
+    let in_path = in_path_parser();
+    let traversal = traversal_parser();
+
+    let pattern = long("pattern")
+        .short('p')
+        .argument::<String>("PATTERN")
+        .help("Cycle pattern to search for, e.g. \"MEMR@0xB8000 then IOW@0x3D4 within 10\"")
+        .parse(|s| s.parse::<MooCyclePattern>());
+
+    construct!(GrepCyclesParams {
+        in_path,
+        traversal,
+        pattern
+    })
+}
@@ -0,0 +1,122 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fs, io::Cursor, path::PathBuf};
+
+use crate::{args::GlobalOptions, commands::grep_cycles::args::GrepCyclesParams, working_set::WorkingSet};
+use anyhow::Error;
+use moo::prelude::*;
+use rayon::prelude::*;
+
+#[derive(Debug)]
+struct CycleMatch {
+    file:   PathBuf,
+    index:  usize,
+    name:   String,
+    cycles: Vec<usize>,
+}
+
+#[derive(Debug, Default)]
+struct GrepStats {
+    searched: usize,
+    tests:    usize,
+    errors:   usize,
+    found:    Vec<CycleMatch>,
+}
+
+impl GrepStats {
+    fn combine(mut self, mut other: GrepStats) -> GrepStats {
+        self.searched += other.searched;
+        self.tests += other.tests;
+        self.errors += other.errors;
+        self.found.append(&mut other.found);
+        self
+    }
+}
+
+pub fn run(global: &GlobalOptions, params: &GrepCyclesParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
+
+    if working_set.is_empty() {
+        return Err(Error::msg("No files selected"));
+    }
+
+    let stats: GrepStats = working_set
+        .par_map(|path| {
+            let mut s = GrepStats {
+                searched: 1,
+                ..Default::default()
+            };
+
+            let moo = match fs::read(path) {
+                Ok(data) => MooTestFile::read(&mut Cursor::new(data)),
+                Err(e) => {
+                    log::warn!("I/O error reading {}: {}", path.display(), e);
+                    s.errors += 1;
+                    return s;
+                }
+            };
+
+            match moo {
+                Ok(moo) => {
+                    s.tests = moo.test_ct();
+                    for (t_idx, test) in moo.tests().iter().enumerate() {
+                        if let Some(cycles) = params.pattern.find(test, moo.cpu_type()) {
+                            s.found.push(CycleMatch {
+                                file: PathBuf::from(path),
+                                index: t_idx,
+                                name: test.name().to_string(),
+                                cycles,
+                            });
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Parse error in {}: {}", path.display(), e);
+                    s.errors += 1;
+                }
+            }
+            s
+        })
+        .reduce(GrepStats::default, GrepStats::combine);
+
+    let mut found = stats.found;
+    found.sort_by(|a, b| a.file.cmp(&b.file).then(a.index.cmp(&b.index)));
+    for m in &found {
+        let cycles = m.cycles.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(", ");
+        println!("{} [{}] {}: cycles {}", m.file.display(), m.index, m.name, cycles);
+    }
+
+    global.loud(|| {
+        println!(
+            "{} match(es) in {} tests (searched {} files, {} read errors)",
+            found.len(),
+            stats.tests,
+            stats.searched,
+            stats.errors
+        )
+    });
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::{args::in_path_parser, commands::display::mem_diff_parser};
+use bpaf::{construct, long, positional, Parser};
+
+/// Which tests of a file `head`, `tail` and `show` print.
+#[derive(Clone, Debug)]
+pub(crate) enum InspectSelection {
+    /// The first N tests.
+    Head(usize),
+    /// The last N tests.
+    Tail(usize),
+    /// A single test by index.
+    Index(usize),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct InspectParams {
+    pub(crate) selection: InspectSelection,
+    pub(crate) mem_diff:  bool,
+    pub(crate) in_path:   PathBuf,
+}
+
+/// The input file, given with --input or positionally.
+fn inspect_path_parser() -> impl Parser<PathBuf> {
+    let input = in_path_parser();
+    let input_file = positional::<PathBuf>("FILE").help("Input file, as an alternative to --input");
+    construct!([input, input_file])
+}
+
+fn count_parser() -> impl Parser<usize> {
+    long("count")
+        .short('n')
+        .argument::<usize>("N")
+        .help("Number of tests to print (default: 10)")
+        .fallback(10)
+}
+
+pub(crate) fn head_parser() -> impl Parser<InspectParams> {
+    let selection = count_parser().map(InspectSelection::Head);
+    let mem_diff = mem_diff_parser();
+    let in_path = inspect_path_parser();
+    construct!(InspectParams {
+        selection,
+        mem_diff,
+        in_path
+    })
+}
+
+pub(crate) fn tail_parser() -> impl Parser<InspectParams> {
+    let selection = count_parser().map(InspectSelection::Tail);
+    let mem_diff = mem_diff_parser();
+    let in_path = inspect_path_parser();
+    construct!(InspectParams {
+        selection,
+        mem_diff,
+        in_path
+    })
+}
+
+pub(crate) fn show_parser() -> impl Parser<InspectParams> {
+    let selection = long("index")
+        .argument::<usize>("INDEX")
+        .help("Index of the test to print")
+        .map(InspectSelection::Index);
+    let mem_diff = mem_diff_parser();
+    let in_path = inspect_path_parser();
+    construct!(InspectParams {
+        selection,
+        mem_diff,
+        in_path
+    })
+}
@@ -0,0 +1,62 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fs::File, io::BufReader};
+
+use super::args::{InspectParams, InspectSelection};
+use crate::{args::GlobalOptions, commands::display::print_test};
+use anyhow::{bail, Context, Error};
+use moo::prelude::*;
+
+/// Print the selected tests of a file. Only the selected tests are decoded, and reading stops
+/// after the last of them.
+pub fn run(_global: &GlobalOptions, params: &InspectParams) -> Result<(), Error> {
+    let open = || -> Result<BufReader<File>, Error> {
+        let file =
+            File::open(&params.in_path).with_context(|| format!("Error opening file: {}", params.in_path.display()))?;
+        Ok(BufReader::new(file))
+    };
+
+    let header = MooTestFile::read_header(&mut open()?)?;
+    let test_ct = header.test_count as usize;
+
+    let range = match params.selection {
+        InspectSelection::Head(n) => 0..n.min(test_ct),
+        InspectSelection::Tail(n) => test_ct.saturating_sub(n)..test_ct,
+        InspectSelection::Index(index) => {
+            if index >= test_ct {
+                bail!("Test index {} is out of range (file has {} tests)", index, test_ct);
+            }
+            index..index + 1
+        }
+    };
+
+    let options = MooReadOptions::new().with_test_range(range.clone());
+    let test_file = MooTestFile::read_with_options(&mut open()?, &options)?;
+
+    for (index, test) in range.zip(test_file.tests()) {
+        print_test(test, index, test_ct, test_file.cpu_type(), params.mem_diff);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::{
+    args::{
+        collision_parser,
+        compress_parser,
+        in_path_parser,
+        mutation_parser,
+        opt_out_path_parser,
+        traversal_parser,
+        MutationOptions,
+        TraversalOptions,
+    },
+    output::CollisionPolicy,
+};
+use bpaf::{construct, long, Parser};
+use moo::prelude::{MooCpuFamily, MooOpcodeId};
+
+#[derive(Clone, Debug)]
+pub(crate) enum MetaParams {
+    Set(MetaSetParams),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct MetaSetParams {
+    pub(crate) in_path: PathBuf,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) out_path: Option<PathBuf>,
+    pub(crate) compress: bool,
+    pub(crate) collision: CollisionPolicy,
+    pub(crate) mnemonic: Option<String>,
+    /// The opcode id, parsed per file as its CPU family decides which bytes are prefixes.
+    pub(crate) opcode: Option<String>,
+    pub(crate) seed: Option<u64>,
+    pub(crate) set_version: Option<(u8, u8)>,
+    pub(crate) mutation: MutationOptions,
+}
+
+/// Parse a set version given as `MAJOR.MINOR`, e.g. `1.2`.
+fn parse_version(s: &str) -> Result<(u8, u8), String> {
+    let err = || format!("invalid version '{}', expected MAJOR.MINOR", s);
+    let (major, minor) = s.split_once('.').ok_or_else(err)?;
+    Ok((major.parse().map_err(|_| err())?, minor.parse().map_err(|_| err())?))
+}
+
+pub(crate) fn meta_parser() -> impl Parser<MetaParams> {
+    let set = {
+        let in_path = in_path_parser();
+        let traversal = traversal_parser();
+        let out_path = opt_out_path_parser();
+        let compress = compress_parser();
+        let collision = collision_parser();
+        let mnemonic = long("mnemonic")
+            .argument::<String>("MNEMONIC")
+            .help("Set the mnemonic of the tested instruction")
+            .optional();
+        let opcode = long("opcode")
+            .argument::<String>("OPCODE")
+            .help("Set the opcode of the tested instruction, e.g. 0x00, D2.1 or 0F01.4. Every test must execute it")
+            .parse(|s| {
+                let s = s
+                    .strip_prefix("0x")
+                    .or_else(|| s.strip_prefix("0X"))
+                    .unwrap_or(&s)
+                    .to_string();
+                // Reject malformed opcodes up front rather than once per file.
+                MooOpcodeId::parse(&s, MooCpuFamily::Intel80386).map(|_| s)
+            })
+            .optional();
+        let seed = long("seed")
+            .argument::<u64>("SEED")
+            .help("Set the file seed")
+            .optional();
+        let set_version = long("set-version")
+            .argument::<String>("MAJOR.MINOR")
+            .help("Set the version of the test collection the file belongs to, e.g. 1.2")
+            .parse(|s| parse_version(&s))
+            .optional();
+        let mutation = mutation_parser();
+
+        construct!(MetaSetParams {
+            in_path,
+            traversal,
+            out_path,
+            compress,
+            collision,
+            mnemonic,
+            opcode,
+            seed,
+            set_version,
+            mutation,
+        })
+        .guard(
+            |p| p.mnemonic.is_some() || p.opcode.is_some() || p.seed.is_some() || p.set_version.is_some(),
+            "--mnemonic, --opcode, --seed or --set-version must be provided",
+        )
+        .guard(
+            |p| p.out_path.is_some() || p.mutation.dry_run,
+            "--output is required unless --dry-run is given",
+        )
+        .map(MetaParams::Set)
+        .to_options()
+        .command("set")
+        .help("Set metadata fields, checking them against the tests of each file")
+    };
+
+    construct!([set])
+}
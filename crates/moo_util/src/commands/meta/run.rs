@@ -0,0 +1,135 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use crate::{
+    args::GlobalOptions,
+    changes::{ChangeReport, FileChanges},
+    commands::meta::args::{MetaParams, MetaSetParams},
+    file::read_moo_file,
+    output::OutputPathPolicy,
+    working_set::WorkingSet,
+};
+use anyhow::{bail, Error};
+use moo::prelude::*;
+use rayon::iter::ParallelIterator;
+
+#[derive(Debug, Default)]
+struct MetaStats {
+    changes: ChangeReport,
+    /// Files that could not be read, failed validation or could not be written.
+    errors:  usize,
+}
+
+impl MetaStats {
+    fn combine(mut self, other: MetaStats) -> MetaStats {
+        self.changes = self.changes.combine(other.changes);
+        self.errors += other.errors;
+        self
+    }
+}
+
+pub fn run(global: &GlobalOptions, params: &MetaParams) -> Result<(), Error> {
+    match params {
+        MetaParams::Set(params) => set(global, params),
+    }
+}
+
+fn set(global: &GlobalOptions, params: &MetaSetParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
+
+    if working_set.is_empty() {
+        return Err(Error::msg("No files selected"));
+    }
+
+    let output_policy = params
+        .out_path
+        .as_ref()
+        .map(|dir| OutputPathPolicy::new(dir, params.compress, params.collision));
+
+    let stats = working_set
+        .par_map(|path| {
+            let mut s = MetaStats::default();
+            let mut moo = match read_moo_file(path) {
+                Ok(moo) => moo,
+                Err(e) => {
+                    log::error!("Error reading {}: {}", path.display(), e);
+                    s.errors = 1;
+                    return s;
+                }
+            };
+
+            let mut edit = MooMetadataEdit::new();
+            edit.mnemonic = params.mnemonic.clone();
+            edit.file_seed = params.seed;
+            edit.set_version = params.set_version;
+            if let Some(opcode) = &params.opcode {
+                match MooOpcodeId::parse(opcode, MooCpuFamily::from(moo.cpu_type())) {
+                    Ok(id) => edit.opcode = Some(id),
+                    Err(e) => {
+                        log::error!("{}: {}", path.display(), e);
+                        s.errors = 1;
+                        s.changes = ChangeReport::from_file(FileChanges::new(path, &moo, Vec::new()));
+                        return s;
+                    }
+                }
+            }
+
+            let summary = match moo.edit_metadata(&edit) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    log::error!("{}: {}", path.display(), e);
+                    s.errors = 1;
+                    s.changes = ChangeReport::from_file(FileChanges::new(path, &moo, Vec::new()));
+                    return s;
+                }
+            };
+
+            let changes = FileChanges::new(path, &moo, summary.changes);
+            if let Some(policy) = output_policy
+                .as_ref()
+                .filter(|_| changes.changed() && !params.mutation.dry_run)
+            {
+                let options = MooWriteOptions::new().with_preserve_hash(true);
+                let written = policy
+                    .path_for_input(path)
+                    .and_then(|out_path| Ok(moo.write_to_path(&out_path, &options)?));
+                if let Err(e) = written {
+                    log::error!("Error writing {}: {}", path.display(), e);
+                    s.errors = 1;
+                }
+            }
+            s.changes = ChangeReport::from_file(changes);
+            s
+        })
+        .reduce(MetaStats::default, MetaStats::combine);
+
+    // A dry run's report is its only output, so it is printed even with --silent.
+    if params.mutation.dry_run || !global.silent {
+        stats.changes.print(&params.mutation);
+    }
+    if stats.errors > 0 {
+        bail!("{} file(s) could not be read, validated or written", stats.errors);
+    }
+    Ok(())
+}
@@ -22,6 +22,19 @@
 */
 
 pub mod check;
+pub mod completions;
+pub mod coverage;
+pub mod diff;
 pub mod display;
 pub mod edit;
 pub mod find;
+pub mod gen;
+pub mod grep_cycles;
+pub mod inspect;
+pub mod meta;
+pub mod outliers;
+pub mod replay;
+pub mod report;
+pub mod results;
+pub mod verify;
+pub mod verify_against;
@@ -0,0 +1,100 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::{
+    args::GlobalOptions,
+    commands::outliers::args::OutliersParams,
+    file::read_moo_file,
+    working_set::WorkingSet,
+};
+use anyhow::Error;
+use moo::prelude::*;
+use rayon::prelude::*;
+
+struct FileOutliers {
+    file: PathBuf,
+    tests: usize,
+    outliers: Vec<(String, MooCycleOutlier)>,
+}
+
+pub fn run(global: &GlobalOptions, params: &OutliersParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
+
+    if working_set.is_empty() {
+        return Err(Error::msg("No files selected"));
+    }
+
+    let mut results: Vec<FileOutliers> = working_set
+        .par_map(|path| match read_moo_file(path) {
+            Ok(moo) => Some(FileOutliers {
+                file: path.clone(),
+                tests: moo.test_ct(),
+                outliers: moo
+                    .cycle_outliers(params.sigma)
+                    .into_iter()
+                    .map(|o| (moo.tests()[o.index].name().to_string(), o))
+                    .collect(),
+            }),
+            Err(e) => {
+                log::warn!("Error reading {}: {}", path.display(), e);
+                None
+            }
+        })
+        .flatten()
+        .collect();
+
+    results.sort_by(|a, b| a.file.cmp(&b.file));
+
+    let mut total = 0;
+    for file in &results {
+        for (name, o) in &file.outliers {
+            println!(
+                "{} [{}] {}: {} cycles ({}, {} tests, mean {:.1}, {:+.1} sigma)",
+                file.file.display(),
+                o.index,
+                name,
+                o.cycles,
+                o.form,
+                o.group_size,
+                o.mean,
+                o.sigma
+            );
+        }
+        total += file.outliers.len();
+    }
+
+    global.loud(|| {
+        println!(
+            "{} outlier(s) beyond {} sigma in {} tests (searched {} files)",
+            total,
+            params.sigma,
+            results.iter().map(|f| f.tests).sum::<usize>(),
+            results.len()
+        )
+    });
+
+    Ok(())
+}
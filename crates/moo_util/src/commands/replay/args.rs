@@ -0,0 +1,77 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::args::{in_path_parser, traversal_parser, TraversalOptions};
+use bpaf::{construct, long, Parser};
+
+#[derive(Clone, Debug)]
+pub(crate) struct ReplayParams {
+    pub(crate) in_path: PathBuf,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) emulator: String,
+    pub(crate) skip_cycles: bool,
+    pub(crate) results_path: Option<PathBuf>,
+    pub(crate) emulator_version: String,
+    pub(crate) xfail_path: Option<PathBuf>,
+}
+
+pub(crate) fn replay_parser() -> impl Parser<ReplayParams> {
+    let in_path = in_path_parser();
+    let traversal = traversal_parser();
+
+    let emulator = long("emulator")
+        .short('e')
+        .argument::<String>("COMMAND")
+        .help("Command line of the emulator process to drive over JSON-RPC");
+
+    let skip_cycles = long("skip-cycles")
+        .help("Compare only the final state, without requesting cycles from the emulator")
+        .switch();
+
+    let results_path = long("results")
+        .argument::<PathBuf>("RESULTS_PATH")
+        .help("Write a MOO-R result file recording the outcome of each test")
+        .optional();
+
+    let emulator_version = long("emulator-version")
+        .argument::<String>("VERSION")
+        .help("Emulator version to record in the result file")
+        .fallback(String::new());
+
+    let xfail_path = long("xfail")
+        .argument::<PathBuf>("XFAIL_PATH")
+        .help("Expected-failure list; fail only on divergences not listed, and report stale entries")
+        .optional();
+
+    construct!(ReplayParams {
+        in_path,
+        traversal,
+        emulator,
+        skip_cycles,
+        results_path,
+        emulator_version,
+        xfail_path,
+    })
+}
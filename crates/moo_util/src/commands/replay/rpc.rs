@@ -0,0 +1,201 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! A minimal JSON-RPC 2.0 client for driving an external emulator over its stdin and stdout.
+//!
+//! Each request and response is a single line of JSON. The emulator must implement the
+//! following methods:
+//!
+//! - `set_state`: load the CPU state for a test. Params: `cpu_type` (e.g. `"8088"`), `bytes`
+//!   (the instruction bytes), `regs` (an object of lowercase register names to values), `queue`
+//!   (the initial prefetch queue) and `ram` (an array of `[address, value]` pairs).
+//! - `step`: execute one instruction. Params: none.
+//! - `get_state`: return the CPU state. Params: `ram`, an array of addresses to read.
+//!   Result: `regs`, an object of register names to values, and `ram`, an array of
+//!   `[address, value]` pairs.
+//! - `get_cycles`: return the cycles executed by the last `step`. Result: an array of cycle
+//!   objects with the field names of `MooCycleState`. Missing fields default to zero.
+
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use anyhow::{anyhow, bail, Context, Error};
+use moo::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize)]
+struct RpcRequest<'a, P: Serialize> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: P,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    id: Option<u64>,
+    result: Option<Value>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code:    i64,
+    message: String,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SetStateParams<'a> {
+    pub(crate) cpu_type: &'a str,
+    pub(crate) bytes: &'a [u8],
+    pub(crate) regs: BTreeMap<&'static str, u32>,
+    pub(crate) queue: &'a [u8],
+    pub(crate) ram: Vec<[u32; 2]>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct GetStateParams {
+    pub(crate) ram: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EmulatorState {
+    pub(crate) regs: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub(crate) ram:  Vec<[u32; 2]>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct EmulatorCycle {
+    pins0: u8,
+    address_bus: u32,
+    segment: u8,
+    memory_status: u8,
+    io_status: u8,
+    pins1: u8,
+    data_bus: u16,
+    bus_state: u8,
+    t_state: u8,
+    queue_op: u8,
+    queue_byte: u8,
+}
+
+impl From<EmulatorCycle> for MooCycleState {
+    fn from(c: EmulatorCycle) -> Self {
+        MooCycleState {
+            pins0: c.pins0,
+            address_bus: c.address_bus,
+            segment: c.segment,
+            memory_status: c.memory_status,
+            io_status: c.io_status,
+            pins1: c.pins1,
+            data_bus: c.data_bus,
+            bus_state: c.bus_state,
+            t_state: c.t_state,
+            queue_op: c.queue_op,
+            queue_byte: c.queue_byte,
+        }
+    }
+}
+
+/// A running emulator process, spoken to over JSON-RPC.
+pub(crate) struct EmulatorClient {
+    child:   Child,
+    stdin:   ChildStdin,
+    stdout:  BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl EmulatorClient {
+    /// Spawn the emulator. `command` is split on whitespace into a program and its arguments.
+    pub(crate) fn spawn(command: &str) -> Result<Self, Error> {
+        let mut parts = command.split_whitespace();
+        let program = parts.next().ok_or_else(|| anyhow!("Empty emulator command"))?;
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start emulator '{}'", program))?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("Emulator stdin unavailable"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("Emulator stdout unavailable"))?;
+        Ok(Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            next_id: 1,
+        })
+    }
+
+    /// Send a request and wait for its response.
+    pub(crate) fn call<P: Serialize, R: DeserializeOwned>(&mut self, method: &str, params: P) -> Result<R, Error> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let request = RpcRequest {
+            jsonrpc: "2.0",
+            id,
+            method,
+            params,
+        };
+        let mut line = serde_json::to_string(&request)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())?;
+        self.stdin.flush()?;
+
+        let mut line = String::new();
+        if self.stdout.read_line(&mut line)? == 0 {
+            bail!("Emulator closed its output during '{}'", method);
+        }
+        let response: RpcResponse =
+            serde_json::from_str(&line).with_context(|| format!("Malformed response to '{}'", method))?;
+
+        if response.id != Some(id) {
+            bail!("Response id {:?} does not match request id {}", response.id, id);
+        }
+        if let Some(e) = response.error {
+            bail!("Emulator error {} in '{}': {}", e.code, method, e.message);
+        }
+        let result = response.result.unwrap_or(Value::Null);
+        serde_json::from_value(result).with_context(|| format!("Unexpected result from '{}'", method))
+    }
+}
+
+impl Drop for EmulatorClient {
+    fn drop(&mut self) {
+        _ = self.child.kill();
+        _ = self.child.wait();
+    }
+}
@@ -0,0 +1,261 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Cursor},
+    path::Path,
+};
+
+use crate::{
+    args::GlobalOptions,
+    commands::{
+        gen::backend::{RpcBackend, TestBackend},
+        replay::args::ReplayParams,
+    },
+    working_set::WorkingSet,
+};
+use anyhow::Error;
+use moo::{
+    prelude::*,
+    results::{expected::MooExpectedFailures, MooMismatchCategory, MooResultFile, MooTestResult},
+    types::{hash::hex_string, MooCompareOptions, MooComparison},
+};
+
+/// A single divergence between a test and the emulator.
+struct Divergence {
+    comparison: MooComparison,
+    detail: Option<String>,
+}
+
+pub fn run(global: &GlobalOptions, params: &ReplayParams) -> Result<(), Error> {
+    let working_set = WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?;
+    if working_set.is_empty() {
+        return Err(Error::msg("No files selected"));
+    }
+
+    let expected = match &params.xfail_path {
+        Some(path) => Some(MooExpectedFailures::parse(BufReader::new(File::open(path)?))?),
+        None => None,
+    };
+
+    let mut emulator = RpcBackend::spawn(&params.emulator)?.with_cycles(!params.skip_cycles);
+    let mut results = MooResultFile::new(emulator.name(), params.emulator_version.as_str());
+
+    let mut replayed = 0;
+    let mut divergent = 0;
+    for path in working_set.iter() {
+        let moo = MooTestFile::read(&mut Cursor::new(fs::read(path)?))?;
+        for (t_idx, test) in moo.tests().iter().enumerate() {
+            let divergences = replay_test(&mut emulator, moo.cpu_type(), test, params.skip_cycles)?;
+            replayed += 1;
+            if let Some(hash) = test.hash() {
+                results.insert(match divergences.first() {
+                    None => MooTestResult::pass(*hash),
+                    Some(d) => MooTestResult::fail(*hash, MooMismatchCategory::from(&d.comparison))
+                        .with_detail(d.detail.clone().unwrap_or_else(|| format!("{:?}", d.comparison))),
+                });
+            }
+            if !divergences.is_empty() {
+                divergent += 1;
+                let xfail = match (&expected, test.hash()) {
+                    (Some(expected), Some(hash)) => expected.reason(hash),
+                    _ => None,
+                };
+                report(path, t_idx, test, &divergences, xfail);
+            }
+        }
+    }
+
+    if let Some(results_path) = &params.results_path {
+        let mut writer = BufWriter::new(File::create(results_path)?);
+        results.write(&mut writer)?;
+    }
+
+    global.loud(|| println!("{} tests replayed, {} divergent", replayed, divergent));
+
+    if let Some(expected) = &expected {
+        let baseline = expected.check(&results);
+        for hash in &baseline.stale {
+            println!("stale xfail entry (now passing): {}", hex_string(hash));
+        }
+        global.loud(|| {
+            println!(
+                "{} unexpected failures, {} expected failures, {} stale entries, {} entries not run",
+                baseline.unexpected_failures.len(),
+                baseline.expected_failures.len(),
+                baseline.stale.len(),
+                baseline.missing.len()
+            )
+        });
+        if !baseline.is_ok() {
+            return Err(anyhow::anyhow!(
+                "{} unexpected failures",
+                baseline.unexpected_failures.len()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Execute `test` on `backend` and compare the result with the test's final state. Cycles are
+/// not compared if `skip_cycles` is set.
+fn replay_test(
+    backend: &mut dyn TestBackend,
+    cpu_type: MooCpuType,
+    test: &MooTest,
+    skip_cycles: bool,
+) -> Result<Vec<Divergence>, Error> {
+    let final_addresses: Vec<u32> = test.final_state().ram().iter().map(|e| e.address).collect();
+    let output = backend.execute(cpu_type, test.bytes(), test.initial_state(), &final_addresses)?;
+    let cycles = (!skip_cycles).then_some(output.cycles.as_slice());
+    let (actual_regs, actual_ram) = (&output.regs, &output.ram);
+
+    let comparisons = test.compare_final_state(actual_regs, actual_ram, cycles, &MooCompareOptions::new());
+    // Registers the backend did not report are left out of the register mask.
+    let reported = |reg: MooRegister| actual_regs.is_set(reg);
+
+    Ok(comparisons
+        .into_iter()
+        .map(|comparison| {
+            let detail = match comparison {
+                MooComparison::RegisterMismatch(reg, ..) if !reported(reg) => Some(format!("{:?}: not reported", reg)),
+                MooComparison::MemoryValueMismatch(entry, _)
+                    if !actual_ram.iter().any(|e| e.address == entry.address) =>
+                {
+                    Some(format!("{:06X}: not reported", entry.address))
+                }
+                _ => None,
+            };
+            Divergence { comparison, detail }
+        })
+        .collect())
+}
+
+fn report(path: &Path, index: usize, test: &MooTest, divergences: &[Divergence], xfail: Option<&str>) {
+    match xfail {
+        Some(reason) => println!(
+            "{} [{}] {}: (expected failure: {})",
+            path.display(),
+            index,
+            test.name(),
+            reason
+        ),
+        None => println!("{} [{}] {}:", path.display(), index, test.name()),
+    }
+    for d in divergences {
+        match &d.detail {
+            Some(detail) => println!("  {} ({})", d.comparison, detail),
+            None => println!("  {}", d.comparison),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::replay_test;
+    use crate::commands::gen::backend::{BackendOutput, TestBackend};
+    use anyhow::Error;
+    use moo::{
+        prelude::*,
+        types::{MooComparison, MooTestState},
+    };
+    use std::io::Cursor;
+
+    /// A [TestBackend] that reports the final state and cycles of a known test, with the bits of
+    /// AX inverted if `corrupt_ax` is set.
+    struct MockBackend<'a> {
+        test: &'a MooTest,
+        corrupt_ax: bool,
+    }
+
+    impl TestBackend for MockBackend<'_> {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        fn execute(
+            &mut self,
+            _cpu_type: MooCpuType,
+            bytes: &[u8],
+            initial: &MooTestState,
+            read_ram: &[u32],
+        ) -> Result<BackendOutput, Error> {
+            assert_eq!(bytes, self.test.bytes());
+            let mut regs = self.test.final_state().regs().rehydrate(initial.regs());
+            if self.corrupt_ax {
+                match &mut regs {
+                    MooRegisters::Sixteen(r) => r.set_ax(!r.ax),
+                    MooRegisters::ThirtyTwo(r) => r.set_ax(!(r.eax as u16)),
+                }
+            }
+            let ram = self
+                .test
+                .final_state()
+                .ram()
+                .iter()
+                .filter(|e| read_ram.contains(&e.address))
+                .copied()
+                .collect();
+            Ok(BackendOutput {
+                regs,
+                ram,
+                cycles: self.test.cycles().to_vec(),
+            })
+        }
+    }
+
+    fn read_input() -> MooTestFile {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../moo/tests/test_data/00.MOO");
+        MooTestFile::read(&mut Cursor::new(std::fs::read(path).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn replays_matching_backend() {
+        let moo = read_input();
+        for test in moo.tests() {
+            let mut backend = MockBackend {
+                test,
+                corrupt_ax: false,
+            };
+            assert!(replay_test(&mut backend, moo.cpu_type(), test, false)
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn reports_divergent_register() {
+        let moo = read_input();
+        let test = &moo.tests()[0];
+        let mut backend = MockBackend { test, corrupt_ax: true };
+
+        let divergences = replay_test(&mut backend, moo.cpu_type(), test, true).unwrap();
+        assert_eq!(divergences.len(), 1);
+        assert!(matches!(
+            divergences[0].comparison,
+            MooComparison::RegisterMismatch(MooRegister::AX | MooRegister::EAX, ..)
+        ));
+        assert!(divergences[0].detail.is_none());
+    }
+}
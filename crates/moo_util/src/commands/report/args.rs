@@ -0,0 +1,89 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::{
+    args::{in_path_parser, traversal_parser, TraversalOptions},
+    commands::report::html::{parse_columns, TABLE_COLUMNS},
+};
+
+use bpaf::{construct, long, positional, Parser};
+
+#[derive(Clone, Debug)]
+pub(crate) struct ReportParams {
+    pub(crate) in_path: PathBuf,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) out_path: PathBuf,
+    pub(crate) cycle_subtract: usize,
+    pub(crate) embed_js: bool,
+    pub(crate) no_js: bool,
+    pub(crate) columns: Vec<usize>,
+    pub(crate) svg_traces: bool,
+}
+
+pub(crate) fn report_parser() -> impl Parser<ReportParams> {
+    let traversal = traversal_parser();
+    let out_path = long("output")
+        .short('o')
+        .argument::<PathBuf>("OUTPUT_PATH")
+        .help("Path to the report file. The format is selected by its .html or .csv extension")
+        .fallback(PathBuf::from("moo_report.html"));
+    let cycle_subtract = long("cycle-subtract")
+        .argument::<usize>("CYCLES")
+        .help("Cycles spent in fetching, subtracted from each test's cycle count")
+        .fallback(0);
+    let embed_js = long("embed-js")
+        .help("Inline the Plotly.js bundle in the HTML report so it can be viewed offline")
+        .switch();
+    let no_js = long("no-js")
+        .help("Write the HTML report as a plain table, without charts or scripts")
+        .switch();
+    let columns = long("columns")
+        .argument::<String>("COLUMNS")
+        .help("Comma-separated list of columns to show in the HTML table, e.g. opcode,mnemonic,avg_cycles")
+        .parse(|s| parse_columns(&s))
+        .fallback_with(|| Ok::<_, String>((0..TABLE_COLUMNS.len()).collect()));
+    let svg_traces = long("svg-traces")
+        .help("Add an SVG timing diagram of each cycle count outlier's trace to the HTML report")
+        .switch();
+    // The input directory may also be given positionally, as the standalone moo_report accepted it.
+    let input = in_path_parser();
+    let input_dir = positional::<PathBuf>("INPUT_DIR").help("Input directory, as an alternative to --input");
+    let in_path = construct!([input, input_dir]);
+
+    construct!(ReportParams {
+        traversal,
+        out_path,
+        cycle_subtract,
+        embed_js,
+        no_js,
+        columns,
+        svg_traces,
+        in_path,
+    })
+    .guard(
+        |p| !(p.embed_js && p.no_js),
+        "--embed-js and --no-js cannot be used together",
+    )
+}
@@ -0,0 +1,633 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::Path;
+
+use crate::commands::report::rows::FileRow;
+use moo::{
+    prelude::{MooCycleClass, MooCycleOutlier, MooGroupStats},
+    types::exception::vector_mnemonic,
+};
+
+use chrono::Local;
+use plotly::{
+    common::Title,
+    layout::{BarMode, Layout},
+    Bar,
+    Pie,
+    Plot,
+};
+
+/// Columns of the per-file statistics table, as (name for --columns, header) pairs.
+pub(crate) const TABLE_COLUMNS: [(&str, &str); 24] = [
+    ("file", "file"),
+    ("opcode", "opcode"),
+    ("mnemonic", "mnemonic"),
+    ("timing", "timing"),
+    ("source", "source"),
+    ("regs_modified", "regs mod"),
+    ("total_cycles", "total cyc"),
+    ("min_cycles", "min cyc"),
+    ("max_cycles", "max cyc"),
+    ("avg_cycles", "avg cyc"),
+    ("min_mem_reads", "min mr"),
+    ("max_mem_reads", "max mr"),
+    ("min_mem_writes", "min mw"),
+    ("max_mem_writes", "max mw"),
+    ("code_fetches", "code fetches"),
+    ("io_reads", "io reads"),
+    ("io_writes", "io writes"),
+    ("max_ram", "max ram"),
+    ("flags_modified", "f modified"),
+    ("flags_always_set", "f always set"),
+    ("flags_always_cleared", "f always clr"),
+    ("exceptions", "exceptions"),
+    ("exceptions_total", "exc_total"),
+    ("tags", "tags"),
+];
+
+/// Parse a comma-separated list of column names from [TABLE_COLUMNS] into column indices.
+pub(crate) fn parse_columns(s: &str) -> Result<Vec<usize>, String> {
+    s.split(',')
+        .map(|name| {
+            let name = name.trim();
+            TABLE_COLUMNS
+                .iter()
+                .position(|(key, _)| key.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    let keys: Vec<&str> = TABLE_COLUMNS.iter().map(|(key, _)| *key).collect();
+                    format!("Unknown column '{}'. Valid columns are: {}", name, keys.join(", "))
+                })
+        })
+        .collect()
+}
+
+/// Where the HTML report loads Plotly.js from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum JsSource {
+    /// Load Plotly.js from its CDN.
+    Cdn,
+    /// Inline the Plotly.js bundle, so the report works offline.
+    #[cfg(feature = "embed_js")]
+    Embedded,
+}
+
+impl JsSource {
+    fn script_tags(&self) -> String {
+        match self {
+            JsSource::Cdn => r#"<script src="https://cdn.plot.ly/plotly-2.35.2.min.js"></script>"#.to_string(),
+            #[cfg(feature = "embed_js")]
+            JsSource::Embedded => Plot::offline_js_sources(),
+        }
+    }
+}
+
+/// Build the cells of the per-file statistics table, one vector per column of [TABLE_COLUMNS],
+/// along with a background color for each row.
+/// Label an exception vector with its mnemonic where it has one, e.g. `13 #GP`.
+fn exception_label(code: u8) -> String {
+    match vector_mnemonic(code) {
+        Some(mnemonic) => format!("{} {}", code, mnemonic),
+        None => format!("INT {}", code),
+    }
+}
+
+fn table_columns(rows: &[FileRow]) -> (Vec<Vec<String>>, Vec<String>) {
+    let file_names: Vec<String> = rows.iter().map(|r| r.file_name.clone()).collect();
+    let opcodes: Vec<String> = rows.iter().map(|r| r.opcode_string()).collect();
+    let mnemonics: Vec<String> = rows.iter().map(|r| r.mnemonic.clone()).collect();
+    let regs_modified: Vec<String> = rows.iter().map(|r| r.regs_modified.join(", ")).collect();
+    let total_cycles: Vec<String> = rows.iter().map(|r| r.total_cycles.to_string()).collect();
+    let min_cycles: Vec<String> = rows.iter().map(|r| r.min_cycles.to_string()).collect();
+    let max_cycles: Vec<String> = rows.iter().map(|r| r.max_cycles.to_string()).collect();
+    let avg_cycles: Vec<String> = rows.iter().map(|r| format!("{:.2}", r.avg_cycles)).collect();
+    //let mem_reads: Vec<String> = rows.iter().map(|r| r.mem_reads.to_string()).collect();
+    //let mem_writes: Vec<String> = rows.iter().map(|r| r.mem_writes.to_string()).collect();
+
+    let min_mr: Vec<String> = rows.iter().map(|r| r.min_mem_reads.to_string()).collect();
+    let max_mr: Vec<String> = rows.iter().map(|r| r.max_mem_reads.to_string()).collect();
+    let min_mw: Vec<String> = rows.iter().map(|r| r.min_mem_writes.to_string()).collect();
+    let max_mw: Vec<String> = rows.iter().map(|r| r.max_mem_writes.to_string()).collect();
+    let code_fetches: Vec<String> = rows.iter().map(|r| r.code_fetches.to_string()).collect();
+    let io_reads: Vec<String> = rows.iter().map(|r| r.io_reads.to_string()).collect();
+    let io_writes: Vec<String> = rows.iter().map(|r| r.io_writes.to_string()).collect();
+    let max_ram: Vec<String> = rows.iter().map(|r| r.max_ram_footprint.to_string()).collect();
+    //let waits: Vec<String> = rows.iter().map(|r| r.wait_states.to_string()).collect();
+    let flags_modified: Vec<String> = rows.iter().map(|r| r.flags_modified.clone()).collect();
+    let flags_always_set: Vec<String> = rows.iter().map(|r| r.flags_always_set.clone()).collect();
+    let flags_always_cleared: Vec<String> = rows.iter().map(|r| r.flags_always_cleared.clone()).collect();
+
+    let excs: Vec<String> = rows
+        .iter()
+        .map(|r| {
+            if r.exceptions_total == 0 {
+                "-".to_string()
+            }
+            else {
+                r.exceptions_hist
+                    .iter()
+                    .map(|(code, count)| {
+                        let pct = (*count as f64) * 100.0 / (r.exceptions_total as f64);
+                        format!("{} ({pct:.0}%)", exception_label(*code))
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        })
+        .collect();
+
+    // per-test % for the total column
+    let exc_totals: Vec<String> = rows
+        .iter()
+        .map(|r| {
+            if r.total_tests == 0 {
+                r.exceptions_total.to_string()
+            }
+            else {
+                let pct = (r.exceptions_total as f64) * 100.0 / (r.total_tests as f64);
+                format!("{} ({:.1}%)", r.exceptions_total, pct)
+            }
+        })
+        .collect();
+
+    let tags: Vec<String> = rows.iter().map(|r| r.tags_string()).collect();
+    let timings: Vec<String> = rows.iter().map(|r| r.timing.clone()).collect();
+    let sources: Vec<String> = rows.iter().map(|r| r.source.clone()).collect();
+
+    let cols: Vec<Vec<String>> = vec![
+        file_names,
+        opcodes,
+        mnemonics,
+        timings,
+        sources,
+        regs_modified,
+        total_cycles,
+        min_cycles,
+        max_cycles,
+        avg_cycles,
+        min_mr,
+        max_mr,
+        min_mw,
+        max_mw,
+        code_fetches,
+        io_reads,
+        io_writes,
+        max_ram,
+        flags_modified,
+        flags_always_set,
+        flags_always_cleared,
+        excs,
+        exc_totals,
+        tags,
+    ];
+
+    let row_colors: Vec<String> = rows
+        .iter()
+        .map(|r| {
+            let pct = if r.total_tests == 0 {
+                0.0
+            }
+            else {
+                (r.exceptions_total as f64) * 100.0 / (r.total_tests as f64)
+            };
+            if pct > 50.00 {
+                "rgba(255,210,210,1)".to_string() // light pink
+            }
+            else if pct > 33.0 {
+                "rgba(255,255,210,1)".to_string() // light yellow
+            }
+            else {
+                "rgba(255,255,255,1)".to_string() // white
+            }
+        })
+        .collect();
+
+    (cols, row_colors)
+}
+
+pub(crate) fn build_dual_pies(rows: &[FileRow], totals: &MooGroupStats) -> anyhow::Result<Plot> {
+    let (reads, writes, fetches, io_r, io_w, waits) = rows.iter().fold((0, 0, 0, 0, 0, 0), |acc, r| {
+        (
+            acc.0 + r.mem_reads,
+            acc.1 + r.mem_writes,
+            acc.2 + r.code_fetches,
+            acc.3 + r.io_reads,
+            acc.4 + r.io_writes,
+            acc.5 + r.wait_states,
+        )
+    });
+    let op_labels = vec![
+        "Mem Reads",
+        "Mem Writes",
+        "Code Fetches",
+        "IO Reads",
+        "IO Writes",
+        "Wait States",
+    ];
+    let op_values = vec![reads, writes, fetches, io_r, io_w, waits]
+        .into_iter()
+        .map(|v| v as f64)
+        .collect::<Vec<_>>();
+
+    let op_pie = Pie::new(op_values)
+        .labels(op_labels)
+        .name("Operation Mix")
+        .domain(plotly::common::Domain::new().x(&[0.0, 0.48]).y(&[0.0, 1.0]));
+
+    // Exceptions pie chart - includes only INT 0-31
+    let pairs: Vec<(u8, usize)> = totals
+        .exceptions
+        .iter()
+        .filter(|(c, _)| **c < 32)
+        .map(|(c, ct)| (*c, *ct))
+        .collect();
+    let (exc_labels, exc_values): (Vec<String>, Vec<f64>) = if pairs.is_empty() {
+        (vec!["none".into()], vec![1.0])
+    }
+    else {
+        (
+            pairs.iter().map(|(c, _)| exception_label(*c)).collect(),
+            pairs.iter().map(|(_, ct)| *ct as f64).collect(),
+        )
+    };
+    let exc_pie = Pie::new(exc_values)
+        .labels(exc_labels)
+        .name("Exceptions")
+        .domain(plotly::common::Domain::new().x(&[0.52, 1.0]).y(&[0.0, 1.0]));
+
+    // Create the combined plot
+    let mut plot = Plot::new();
+    plot.add_trace(op_pie);
+    plot.add_trace(exc_pie);
+    plot.set_layout(
+        Layout::new()
+            .title(Title::with_text("Operation Mix vs Exceptions"))
+            .auto_size(true)
+            .height(500),
+    );
+    Ok(plot)
+}
+
+/// Build overall operation-mix pie + per-file cycles bar.
+pub(crate) fn build_summary_plots(rows: &[FileRow]) -> anyhow::Result<(Plot, Plot)> {
+    // Count all bus operation types and accumulate in 'acc'
+    let (reads, writes, fetches, io_r, io_w, waits) = rows.iter().fold((0, 0, 0, 0, 0, 0), |acc, r| {
+        (
+            acc.0 + r.mem_reads,
+            acc.1 + r.mem_writes,
+            acc.2 + r.code_fetches,
+            acc.3 + r.io_reads,
+            acc.4 + r.io_writes,
+            acc.5 + r.wait_states,
+        )
+    });
+
+    // Bus Operations for pie chart
+    let labels = vec![
+        "Mem Reads",
+        "Mem Writes",
+        "Code Fetches",
+        "IO Reads",
+        "IO Writes",
+        "Wait States",
+    ];
+    let values = vec![reads, writes, fetches, io_r, io_w, waits]
+        .into_iter()
+        .map(|v| v as f64)
+        .collect::<Vec<_>>();
+
+    // Pie chart: overall operation mix
+    let mut pie_plot = Plot::new();
+    let pie = Pie::new(values).labels(labels).name("Operation Mix");
+    pie_plot.add_trace(pie);
+    pie_plot.set_layout(
+        Layout::new()
+            .title(Title::with_text("Overall Operation Mix"))
+            .auto_size(true),
+    );
+
+    // Bar chart: total cycles per file
+    let x = rows.iter().map(|r| r.file_name.clone()).collect::<Vec<_>>();
+    let y = rows.iter().map(|r| r.total_cycles as f64).collect::<Vec<_>>();
+    let mut bar_plot = Plot::new();
+    let bar = Bar::new(x, y).name("Total Cycles");
+    bar_plot.add_trace(bar);
+    bar_plot.set_layout(
+        Layout::new()
+            .title(Title::with_text("Total Cycles per File"))
+            .auto_size(true),
+    );
+
+    Ok((pie_plot, bar_plot))
+}
+
+/// Build a stacked bar chart of each file's cycles, split by [MooCycleClass].
+pub(crate) fn build_timing_plot(rows: &[FileRow]) -> Plot {
+    let x = rows.iter().map(|r| r.file_name.clone()).collect::<Vec<_>>();
+    let mut plot = Plot::new();
+    for class in MooCycleClass::ALL {
+        let y = rows
+            .iter()
+            .map(|r| r.timing_breakdown.percent(class))
+            .collect::<Vec<_>>();
+        plot.add_trace(Bar::new(x.clone(), y).name(class.as_str()));
+    }
+    plot.set_layout(
+        Layout::new()
+            .title(Title::with_text("Cycle Breakdown per File (%)"))
+            .bar_mode(BarMode::Stack)
+            .auto_size(true),
+    );
+    plot
+}
+
+/// Collect the distinct license strings of all files, in first-seen order.
+pub(crate) fn distinct_licenses(rows: &[FileRow]) -> Vec<String> {
+    let mut licenses: Vec<String> = Vec::new();
+    for license in rows.iter().filter_map(|r| r.license.as_ref()) {
+        if !licenses.contains(license) {
+            licenses.push(license.clone());
+        }
+    }
+    licenses
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Build the page heading: generation time, source directory and any licenses.
+fn report_heading(input_dir: &Path, licenses: &[String]) -> String {
+    let now = Local::now();
+    let mut heading = format!(
+        "MOO Report &mdash; {}<br><small>Source directory: {}</small>",
+        now.format("%Y-%m-%d %H:%M:%S"),
+        escape_html(&input_dir.display().to_string())
+    );
+    if !licenses.is_empty() {
+        let licenses: Vec<String> = licenses.iter().map(|l| escape_html(l)).collect();
+        heading.push_str(&format!("<br><small>License: {}</small>", licenses.join("; ")));
+    }
+    heading
+}
+
+/// An outlier test's cycle trace, rendered for the report by `--svg-traces`.
+pub(crate) struct OutlierTrace {
+    pub(crate) file_name: String,
+    pub(crate) name: String,
+    pub(crate) outlier: MooCycleOutlier,
+    pub(crate) svg: String,
+}
+
+/// Render the outlier traces section: a caption and timing diagram for each outlier test.
+fn outlier_traces_html(traces: &[OutlierTrace]) -> String {
+    let mut html = String::from("<h2>Outlier traces</h2>\n");
+    if traces.is_empty() {
+        html.push_str("<p>No outlier tests found.</p>\n");
+    }
+    for trace in traces {
+        let o = &trace.outlier;
+        html.push_str(&format!(
+            "<div class=\"trace\"><p>{} [{}] {}: {} cycles ({}, {} tests, mean {:.1}, {:+.1} sigma)</p>\n{}</div>\n",
+            escape_html(&trace.file_name),
+            o.index,
+            escape_html(&trace.name),
+            o.cycles,
+            escape_html(&o.form.to_string()),
+            o.group_size,
+            o.mean,
+            o.sigma,
+            trace.svg
+        ));
+    }
+    html
+}
+
+/// Compose one HTML page with the statistics table and all figures, loading Plotly.js from `js`.
+/// Only the table columns listed in `columns` are shown. If `traces` is given, an outlier traces
+/// section follows the figures.
+pub(crate) fn compose_html_report(
+    input_dir: &Path,
+    licenses: &[String],
+    rows: &[FileRow],
+    columns: &[usize],
+    figures: &[(&str, Plot)],
+    traces: Option<&[OutlierTrace]>,
+    js: JsSource,
+) -> String {
+    let heading = report_heading(input_dir, licenses);
+
+    let mut divs_and_scripts = String::new();
+    for (i, (id, plot)) in figures.iter().enumerate() {
+        let div_id = format!("{}_{}", id, i);
+        let json = plot.to_json();
+        divs_and_scripts.push_str(&format!(
+            r#"<div id="{div_id}" class="plot-wrap"></div>
+<script>(function(){{
+  var fig = {json};
+  // make sure layout is autosized (in case a trace didn't set it)
+  if (!fig.layout) fig.layout = {{}};
+  fig.layout.autosize = true;
+
+  // merge any existing config with responsive:true
+  var cfg = Object.assign({{responsive:true}}, fig.config || {{}});
+  Plotly.newPlot('{div_id}', fig.data, fig.layout, cfg);
+}})();</script>
+"#,
+        ));
+    }
+
+    let mut body = format!("{}\n<hr/>\n{}", stats_table(rows, columns, true), divs_and_scripts);
+    if let Some(traces) = traces {
+        body.push_str(&format!("<hr/>\n{}", outlier_traces_html(traces)));
+    }
+    html_page(&js.script_tags(), &heading, &body)
+}
+
+/// Compose an HTML page with the per-file statistics as a plain table, without any scripts. The
+/// outlier traces are plain SVG, so they are included if given.
+pub(crate) fn compose_static_html_report(
+    input_dir: &Path,
+    licenses: &[String],
+    rows: &[FileRow],
+    columns: &[usize],
+    traces: Option<&[OutlierTrace]>,
+) -> String {
+    let heading = report_heading(input_dir, licenses);
+    let mut body = stats_table(rows, columns, false);
+    if let Some(traces) = traces {
+        body.push_str(&format!("<hr/>\n{}", outlier_traces_html(traces)));
+    }
+    html_page("", &heading, &body)
+}
+
+/// Render the statistics table as HTML. If `interactive` is set, the table is preceded by a text
+/// filter and column toggles, and followed by a script that also sorts the table by a clicked
+/// column header.
+fn stats_table(rows: &[FileRow], columns: &[usize], interactive: bool) -> String {
+    let (cols, row_colors) = table_columns(rows);
+    let mut html = String::new();
+
+    if interactive {
+        html.push_str(
+            "<div class=\"controls\">\n<input id=\"stats-filter\" type=\"search\" placeholder=\"Filter rows\"/>\n",
+        );
+        html.push_str("<span id=\"stats-columns\">");
+        for (i, &col) in columns.iter().enumerate() {
+            html.push_str(&format!(
+                "<label><input type=\"checkbox\" data-col=\"{}\" checked/>{}</label>",
+                i,
+                escape_html(TABLE_COLUMNS[col].1)
+            ));
+        }
+        html.push_str("</span>\n</div>\n");
+    }
+
+    html.push_str("<table id=\"stats\" class=\"stats\">\n<thead><tr>");
+    for &col in columns {
+        html.push_str(&format!("<th>{}</th>", escape_html(TABLE_COLUMNS[col].1)));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for (row, color) in row_colors.iter().enumerate() {
+        html.push_str(&format!("<tr style=\"background: {}\">", color));
+        for &col in columns {
+            html.push_str(&format!("<td>{}</td>", escape_html(&cols[col][row])));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    if interactive {
+        html.push_str("<script>");
+        html.push_str(TABLE_SCRIPT);
+        html.push_str("</script>\n");
+    }
+    html
+}
+
+/// Sorting, filtering and column toggles for the table rendered by [stats_table].
+/// A column sorts numerically if every cell other than "-" starts with a number.
+const TABLE_SCRIPT: &str = r#"(function(){
+  var table = document.getElementById('stats');
+  var tbody = table.tBodies[0];
+  var headers = table.tHead.rows[0].cells;
+  var numberRe = /^-?\d+(\.\d+)?(\s|$)/;
+  function isNumeric(col) {
+    return Array.prototype.every.call(tbody.rows, function(r) {
+      var t = r.cells[col].textContent;
+      return t === '-' || numberRe.test(t);
+    });
+  }
+  Array.prototype.forEach.call(headers, function(th, col) {
+    th.addEventListener('click', function() {
+      var asc = th.dataset.sort !== 'asc';
+      Array.prototype.forEach.call(headers, function(h) { delete h.dataset.sort; });
+      th.dataset.sort = asc ? 'asc' : 'desc';
+      var numeric = isNumeric(col);
+      var rows = Array.prototype.slice.call(tbody.rows);
+      rows.sort(function(a, b) {
+        var x = a.cells[col].textContent, y = b.cells[col].textContent;
+        var d = numeric ? (parseFloat(x) || 0) - (parseFloat(y) || 0) : x.localeCompare(y);
+        return asc ? d : -d;
+      });
+      rows.forEach(function(r) { tbody.appendChild(r); });
+    });
+  });
+  document.getElementById('stats-filter').addEventListener('input', function(e) {
+    var q = e.target.value.toLowerCase();
+    Array.prototype.forEach.call(tbody.rows, function(r) {
+      r.style.display = r.textContent.toLowerCase().indexOf(q) >= 0 ? '' : 'none';
+    });
+  });
+  document.querySelectorAll('#stats-columns input').forEach(function(box) {
+    box.addEventListener('change', function() {
+      var col = +box.dataset.col;
+      Array.prototype.forEach.call(table.rows, function(r) {
+        r.cells[col].style.display = box.checked ? '' : 'none';
+      });
+    });
+  });
+})();
+"#;
+
+fn html_page(scripts: &str, heading: &str, body: &str) -> String {
+    format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8"/>
+<meta name="viewport" content="width=device-width, initial-scale=1"/>
+<title>MOO Report</title>
+{scripts}
+<style>
+body {{
+  font-family: system-ui, -apple-system, Segoe UI, Roboto, Helvetica, Arial, sans-serif;
+  margin: 24px;
+  background: #0f1115;
+  color: #e6e6e6;
+}}
+h1 {{ font-weight: 700; font-size: 20px; margin: 0 0 16px 0; }}
+.card {{
+  background: #151923; border-radius: 12px; padding: 16px 20px;
+  box-shadow: 0 0 0 1px #242b3a inset;
+}}
+hr {{ border: none; border-top: 1px solid #242b3a; margin: 24px 0; }}
+.small {{ color: #9aa2b2; }}
+table.stats {{ border-collapse: collapse; color: black; font-size: 13px; }}
+table.stats th {{ background: rgba(230,230,230,1.0); position: sticky; top: 0; cursor: pointer; }}
+table.stats th[data-sort=asc]::after {{ content: " \25B2"; }}
+table.stats th[data-sort=desc]::after {{ content: " \25BC"; }}
+.controls {{ margin-bottom: 12px; }}
+.controls label {{ margin-right: 10px; white-space: nowrap; font-size: 13px; }}
+.controls input[type=search] {{ margin-right: 16px; }}
+table.stats th, table.stats td {{ border: 1px solid #c8c8c8; padding: 4px 6px; text-align: left; }}
+h2 {{ font-weight: 700; font-size: 16px; }}
+.trace {{ overflow-x: auto; margin-bottom: 16px; font-size: 13px; }}
+</style>
+</head>
+<body>
+  <div class="card">
+    <h1>{heading}</h1>
+    <div class="small">Generated by moo-report</div>
+  </div>
+  <hr/>
+  {body}
+</body>
+</html>"#
+    )
+}
+
+/// Tiny HTML if no files found
+pub(crate) fn empty_report_html(input_dir: &Path) -> String {
+    format!(
+        r#"<!doctype html>
+<html><head><meta charset="utf-8"/><title>MOO Report</title>
+<style>body{{font-family:system-ui;margin:24px}}</style></head>
+<body>
+<h1>No MOO files found</h1>
+<p>Searched: <code>{}</code></p>
+<p>Expected <code>.moo</code> or <code>.moo.gz</code>.</p>
+</body></html>"#,
+        input_dir.display()
+    )
+}
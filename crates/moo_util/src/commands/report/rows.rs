@@ -0,0 +1,282 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{io::Write, path::PathBuf};
+
+use moo::prelude::*;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct FileRow {
+    pub(crate) file_name: String,
+    #[serde(skip)]
+    pub(crate) opcode_id: Option<MooOpcodeId>,
+    pub(crate) mnemonic: String,
+    pub(crate) timing: String,
+    pub(crate) source: String,
+    pub(crate) license: Option<String>,
+    pub(crate) regs_modified: Vec<String>,
+    pub(crate) total_cycles: usize,
+    pub(crate) min_cycles: usize,
+    pub(crate) max_cycles: usize,
+    pub(crate) avg_cycles: f64,
+    pub(crate) mem_reads: usize,
+    pub(crate) min_mem_reads: usize,
+    pub(crate) max_mem_reads: usize,
+    pub(crate) mem_writes: usize,
+    pub(crate) min_mem_writes: usize,
+    pub(crate) max_mem_writes: usize,
+    pub(crate) code_fetches: usize,
+    pub(crate) io_reads: usize,
+    pub(crate) io_writes: usize,
+    pub(crate) wait_states: usize,
+    pub(crate) avg_ram_seeded: f64,
+    pub(crate) avg_ram_touched: f64,
+    pub(crate) max_ram_footprint: usize,
+    #[serde(skip)]
+    pub(crate) timing_breakdown: MooTimingBreakdown,
+    pub(crate) flags_modified: String,
+    pub(crate) flags_always_set: String,
+    pub(crate) flags_always_cleared: String,
+    pub(crate) exceptions_seen: Vec<u8>,
+    pub(crate) exceptions_hist: Vec<(u8, usize)>, // NEW: [(exception, count)] sorted by exception
+    pub(crate) exceptions_total: usize,           // NEW: total occurrences for percentage calc
+    pub(crate) tag_counts: Vec<(String, usize)>,
+    pub(crate) total_tests: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FileRowCsv {
+    file_name: String,
+    test_ct: String,
+    opcode: String,
+    mnemonic: String,
+    timing: String,
+    source: String,
+    regs_modified: String,
+    total_cycles: String,
+    min_cycles: String,
+    max_cycles: String,
+    avg_cycles: String,
+    mem_reads: String,
+    min_mem_reads: String,
+    max_mem_reads: String,
+    mem_writes: String,
+    min_mem_writes: String,
+    max_mem_writes: String,
+    code_fetches: String,
+    io_reads: String,
+    io_writes: String,
+    avg_ram_seeded: String,
+    avg_ram_touched: String,
+    max_ram_footprint: String,
+    flags_modified: String,
+    flags_always_set: String,
+    flags_always_cleared: String,
+    exceptions_seen: String,
+    exceptions_total: String,
+    tags: String,
+    license: Option<String>,
+}
+
+impl From<&FileRow> for FileRowCsv {
+    fn from(row: &FileRow) -> Self {
+        Self {
+            file_name: row.file_name.clone(),
+            test_ct: row.total_tests.to_string(),
+            opcode: row.opcode_string(),
+            mnemonic: row.mnemonic.clone(),
+            timing: row.timing.clone(),
+            source: row.source.clone(),
+            regs_modified: if row.regs_modified.is_empty() {
+                "-".to_string()
+            }
+            else {
+                row.regs_modified.join(",")
+            },
+            total_cycles: row.total_cycles.to_string(),
+            min_cycles: row.min_cycles.to_string(),
+            max_cycles: row.max_cycles.to_string(),
+            avg_cycles: format!("{:.2}", row.avg_cycles),
+            mem_reads: row.mem_reads.to_string(),
+            min_mem_reads: row.min_mem_reads.to_string(),
+            max_mem_reads: row.max_mem_reads.to_string(),
+            mem_writes: row.mem_writes.to_string(),
+            min_mem_writes: row.min_mem_writes.to_string(),
+            max_mem_writes: row.max_mem_writes.to_string(),
+            code_fetches: row.code_fetches.to_string(),
+            io_reads: row.io_reads.to_string(),
+            io_writes: row.io_writes.to_string(),
+            avg_ram_seeded: format!("{:.2}", row.avg_ram_seeded),
+            avg_ram_touched: format!("{:.2}", row.avg_ram_touched),
+            max_ram_footprint: row.max_ram_footprint.to_string(),
+            //wait_states: row.wait_states.to_string(),
+            flags_modified: row.flags_modified.clone(),
+            flags_always_set: row.flags_always_set.clone(),
+            flags_always_cleared: row.flags_always_cleared.clone(),
+            exceptions_seen: if row.exceptions_seen.is_empty() {
+                "-".to_string()
+            }
+            else {
+                row.exceptions_seen
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            },
+            exceptions_total: if row.total_tests == 0 {
+                row.exceptions_total.to_string()
+            }
+            else {
+                let pct = (row.exceptions_total as f64) * 100.0 / (row.total_tests as f64);
+                format!("{} ({:.1}%)", row.exceptions_total, pct)
+            },
+            tags: row.tags_string(),
+            license: row.license.clone(),
+        }
+    }
+}
+
+impl FileRow {
+    pub(crate) fn opcode_string(&self) -> String {
+        self.opcode_id
+            .as_ref()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    pub(crate) fn from_stats(path: PathBuf, mnemonic: String, s: MooTestFileStats) -> Self {
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("<unknown>")
+            .to_string();
+
+        // histogram for percentages
+        let mut group = MooGroupStats::default();
+        group.add(&s);
+        let exceptions_total = group.exception_count;
+        let exceptions_hist: Vec<(u8, usize)> = group.exceptions.into_iter().filter(|(k, _)| *k < 32).collect();
+
+        // display list: dedup + sort
+        let mut exceptions_seen: Vec<u8> = s.exceptions_seen;
+        exceptions_seen.sort_unstable();
+        exceptions_seen.dedup();
+
+        let mut regs_modified = s.registers_modified.clone();
+        regs_modified.retain(|r| !matches!(r, MooRegister::EFLAGS | MooRegister::EIP));
+
+        Self {
+            file_name,
+            opcode_id: None,
+            mnemonic,
+            timing: "-".to_string(),
+            source: "-".to_string(),
+            license: None,
+            regs_modified: regs_modified.iter().map(|r| format!("{r:?}")).collect(),
+            total_cycles: s.total_cycles,
+            min_cycles: s.min_cycles,
+            max_cycles: s.max_cycles,
+            avg_cycles: s.avg_cycles,
+            mem_reads: s.mem_reads.total,
+            min_mem_reads: s.mem_reads.min,
+            max_mem_reads: s.mem_reads.max,
+            mem_writes: s.mem_writes.total,
+            min_mem_writes: s.mem_writes.min,
+            max_mem_writes: s.mem_writes.max,
+            code_fetches: s.code_fetches.total,
+            io_reads: s.io_reads.total,
+            io_writes: s.io_writes.total,
+            wait_states: s.wait_states,
+            avg_ram_seeded: s.ram_seeded.avg(),
+            avg_ram_touched: s.ram_touched.avg(),
+            max_ram_footprint: s.ram_footprint.max,
+            timing_breakdown: s.timing,
+            flags_modified: s.flags_modified.to_string(),
+            flags_always_set: s.flags_always_set.to_string(),
+            flags_always_cleared: s.flags_always_cleared.to_string(),
+            exceptions_seen,
+            exceptions_hist,
+            exceptions_total,
+            tag_counts: s.tag_counts,
+            total_tests: s.test_count,
+        }
+    }
+
+    /// Format the per-tag test counts as a comma-separated list, or "-" if no tests are tagged.
+    pub(crate) fn tags_string(&self) -> String {
+        if self.tag_counts.is_empty() {
+            "-".to_string()
+        }
+        else {
+            self.tag_counts
+                .iter()
+                .map(|(tag, count)| format!("{tag} ({count})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    }
+}
+
+pub(crate) fn build_csv<W: Write>(rows: &[FileRow], writer: W) -> anyhow::Result<csv::Writer<W>> {
+    let mut wtr = csv::WriterBuilder::new().has_headers(false).from_writer(writer);
+
+    wtr.write_record([
+        "file",
+        "test_ct",
+        "opcode",
+        "mnemonic",
+        "timing",
+        "source",
+        "regs mod",
+        "total cyc",
+        "min cyc",
+        "max cyc",
+        "avg cyc",
+        "total mr",
+        "min mr",
+        "max mr",
+        "total mw",
+        "min mw",
+        "max mw",
+        "code fetches",
+        "io reads",
+        "io writes",
+        "avg ram seeded",
+        "avg ram touched",
+        "max ram",
+        "f modified",
+        "f always set",
+        "f always clr",
+        "exceptions",
+        "exc_total",
+        "tags",
+        "license",
+    ])?;
+
+    for row in rows {
+        wtr.serialize(FileRowCsv::from(row))?;
+    }
+    wtr.flush()?;
+    Ok(wtr)
+}
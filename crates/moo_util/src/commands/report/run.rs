@@ -0,0 +1,242 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fs, fs::File, path::PathBuf};
+
+use crate::{
+    args::GlobalOptions,
+    commands::report::{
+        args::ReportParams,
+        html::{
+            build_dual_pies,
+            build_summary_plots,
+            build_timing_plot,
+            compose_html_report,
+            compose_static_html_report,
+            distinct_licenses,
+            empty_report_html,
+            JsSource,
+            OutlierTrace,
+        },
+        rows::{build_csv, FileRow},
+    },
+    file::read_moo_file,
+    working_set::WorkingSet,
+};
+use anyhow::{bail, Error};
+use moo::{prelude::*, stats::DEFAULT_OUTLIER_SIGMA};
+use rayon::prelude::*;
+
+/// Outlier traces longer than this are cut off, to keep the report a manageable size.
+const MAX_TRACE_CYCLES: usize = 256;
+
+pub enum ReportFormat {
+    Html,
+    Csv,
+}
+
+pub fn run(global: &GlobalOptions, params: &ReportParams) -> Result<(), Error> {
+    let mut report_format = ReportFormat::Html;
+    if let Some(extension) = params.out_path.extension() {
+        let ext_lower = extension.to_ascii_lowercase();
+
+        if ext_lower == "csv" {
+            report_format = ReportFormat::Csv;
+        }
+        else if ext_lower == "html" || ext_lower == "htm" {
+            report_format = ReportFormat::Html;
+        }
+        else {
+            bail!("Unrecognized output extension '{}'", ext_lower.display());
+        }
+    }
+    if params.svg_traces && matches!(report_format, ReportFormat::Csv) {
+        bail!("--svg-traces requires an HTML report");
+    }
+
+    // 1) Collect MOO files
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
+    if working_set.is_empty() {
+        fs::write(&params.out_path, empty_report_html(&params.in_path))?;
+        eprintln!("No MOO files found; wrote {}", params.out_path.display());
+        return Ok(());
+    }
+
+    // 2) Read the MOOs and calculate stats
+    let results: Vec<(FileRow, MooTestFileStats, Vec<OutlierTrace>)> = working_set
+        .par_map(|path| match read_moo_file(path) {
+            Ok(tf) => {
+                let (row, stats) = file_row(path.clone(), &tf, params.cycle_subtract);
+                let traces = match params.svg_traces {
+                    true => outlier_traces(&row.file_name, &tf),
+                    false => Vec::new(),
+                };
+                Some((row, stats, traces))
+            }
+            Err(e) => {
+                eprintln!("Failed to read {}: {e}", path.display());
+                None
+            }
+        })
+        .flatten()
+        .collect();
+
+    // Aggregate the per-file statistics for the summary plots.
+    let mut summary = MooCorpusSummary::new();
+    let mut rows: Vec<FileRow> = Vec::with_capacity(results.len());
+    let mut traces: Vec<OutlierTrace> = Vec::new();
+    for (row, stats, file_traces) in results {
+        summary.add_stats(Some(&row.mnemonic), row.opcode_id.as_ref(), &stats);
+        rows.push(row);
+        traces.extend(file_traces);
+    }
+    traces.sort_by(|a, b| (&a.file_name, a.outlier.index).cmp(&(&b.file_name, b.outlier.index)));
+    let traces = params.svg_traces.then_some(traces.as_slice());
+
+    if rows.is_empty() {
+        fs::write(&params.out_path, empty_report_html(&params.in_path))?;
+        eprintln!("All reads failed; wrote {}", params.out_path.display());
+        return Ok(());
+    }
+
+    // Order rows by opcode map position. Files without a known opcode sort last, by name.
+    rows.sort_by(|a, b| match (&a.opcode_id, &b.opcode_id) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.file_name.cmp(&b.file_name),
+    });
+
+    match report_format {
+        ReportFormat::Html if params.no_js => {
+            let html = compose_static_html_report(
+                &params.in_path,
+                &distinct_licenses(&rows),
+                &rows,
+                &params.columns,
+                traces,
+            );
+            fs::write(&params.out_path, html)?;
+        }
+        ReportFormat::Html => {
+            let js = if params.embed_js {
+                embedded_js_source()?
+            }
+            else {
+                JsSource::Cdn
+            };
+
+            // 3) Build the plots
+            let (_ops_pie, cycles_bar) = build_summary_plots(&rows)?;
+            let dual_pies = build_dual_pies(&rows, &summary.total)?;
+            let timing_bar = build_timing_plot(&rows);
+
+            // 4) Compose HTML
+            let html = compose_html_report(
+                &params.in_path,
+                &distinct_licenses(&rows),
+                &rows,
+                &params.columns,
+                &[
+                    ("dual_pies", dual_pies),
+                    ("cycles_bar", cycles_bar),
+                    ("timing_bar", timing_bar),
+                ],
+                traces,
+                js,
+            );
+
+            // 5) Write out the result
+            fs::write(&params.out_path, html)?;
+        }
+        ReportFormat::Csv => {
+            let file = File::create(&params.out_path)?;
+            let wtr = std::io::BufWriter::new(file);
+            let _csv_writer = build_csv(&rows, wtr)?;
+        }
+    }
+
+    global.loud(|| println!("Report written to {}", params.out_path.display()));
+    Ok(())
+}
+
+#[cfg(feature = "embed_js")]
+fn embedded_js_source() -> Result<JsSource, Error> {
+    Ok(JsSource::Embedded)
+}
+
+#[cfg(not(feature = "embed_js"))]
+fn embedded_js_source() -> Result<JsSource, Error> {
+    bail!("--embed-js requires moo_util to be built with the `embed_js` feature")
+}
+
+/// Render the cycles of each outlier test in `tf` as an SVG timing diagram.
+fn outlier_traces(file_name: &str, tf: &MooTestFile) -> Vec<OutlierTrace> {
+    let options = MooSvgOptions::new(tf.cpu_type()).with_max_cycles(MAX_TRACE_CYCLES);
+    tf.cycle_outliers(DEFAULT_OUTLIER_SIGMA)
+        .into_iter()
+        .map(|outlier| {
+            let test = &tf.tests()[outlier.index];
+            OutlierTrace {
+                file_name: file_name.to_string(),
+                name: test.name().to_string(),
+                svg: test.render_svg(&options),
+                outlier,
+            }
+        })
+        .collect()
+}
+
+/// Build the report row for a single file, returning it along with the file's statistics.
+fn file_row(path: PathBuf, tf: &MooTestFile, cycle_subtract: usize) -> (FileRow, MooTestFileStats) {
+    let mnemonic = if let Some(metadata) = tf.metadata() {
+        metadata.mnemonic()
+    }
+    else {
+        "<unknown>".to_string()
+    };
+    let timing = tf
+        .metadata()
+        .and_then(|m| m.timing.as_ref())
+        .map(|t| t.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let source = tf
+        .metadata()
+        .and_then(|m| m.capture.as_ref())
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let license = tf.license().map(|l| l.to_string());
+    let opcode_id = match tf.metadata().and_then(|m| m.opcode_id()) {
+        Some(id) => Some(id),
+        None => MooOpcodeId::from_file_name(&path, tf.cpu_type().into()).ok(),
+    };
+
+    let s = tf.calc_stats(cycle_subtract);
+    let mut row = FileRow::from_stats(path, mnemonic, s.clone());
+    row.timing = timing;
+    row.source = source;
+    row.license = license;
+    row.opcode_id = opcode_id;
+    (row, s)
+}
@@ -0,0 +1,56 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::args::in_path_parser;
+use bpaf::{construct, long, Parser};
+
+#[derive(Clone, Debug)]
+pub(crate) enum ResultsParams {
+    Summarize { in_path: PathBuf },
+    Diff { in_path: PathBuf, against: PathBuf },
+}
+
+pub(crate) fn results_parser() -> impl Parser<ResultsParams> {
+    let summarize = {
+        let in_path = in_path_parser();
+        construct!(ResultsParams::Summarize { in_path })
+            .to_options()
+            .command("summarize")
+            .help("Summarize a MOO-R result file")
+    };
+
+    let diff = {
+        let in_path = in_path_parser();
+        let against = long("against")
+            .argument::<PathBuf>("NEW_PATH")
+            .help("Newer MOO-R result file to compare against the input");
+        construct!(ResultsParams::Diff { in_path, against })
+            .to_options()
+            .command("diff")
+            .help("List tests fixed, regressed or changed between two MOO-R result files")
+    };
+
+    construct!([summarize, diff])
+}
@@ -0,0 +1,85 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fs, io::Cursor, path::Path};
+
+use crate::{args::GlobalOptions, commands::results::args::ResultsParams};
+use anyhow::Error;
+use moo::results::{MooResultFile, MooTestResult};
+
+pub fn run(global: &GlobalOptions, params: &ResultsParams) -> Result<(), Error> {
+    match params {
+        ResultsParams::Summarize { in_path } => {
+            let results = read_results(in_path)?;
+            let summary = results.summary();
+            println!("{} {}", results.emulator(), results.emulator_version());
+            println!(
+                "{} tests: {} passed, {} failed, {} skipped",
+                summary.total, summary.passed, summary.failed, summary.skipped
+            );
+            for (category, count) in &summary.categories {
+                println!("  {:?}: {}", category, count);
+            }
+        }
+        ResultsParams::Diff { in_path, against } => {
+            let old = read_results(in_path)?;
+            let new = read_results(against)?;
+            let diff = old.diff(&new);
+
+            let print_list = |label: &str, hashes: &[[u8; 20]], results: &MooResultFile| {
+                for hash in hashes {
+                    let result = results.get(hash).cloned().unwrap_or_else(|| MooTestResult::pass(*hash));
+                    println!(
+                        "{} {} {:?} {}",
+                        label,
+                        result.hash_string(),
+                        result.category,
+                        result.detail
+                    );
+                }
+            };
+            print_list("fixed", &diff.fixed, &new);
+            print_list("regressed", &diff.regressed, &new);
+            print_list("changed", &diff.changed, &new);
+            print_list("added", &diff.added, &new);
+            print_list("removed", &diff.removed, &old);
+
+            global.loud(|| {
+                println!(
+                    "{} fixed, {} regressed, {} changed, {} added, {} removed",
+                    diff.fixed.len(),
+                    diff.regressed.len(),
+                    diff.changed.len(),
+                    diff.added.len(),
+                    diff.removed.len()
+                )
+            });
+        }
+    }
+    Ok(())
+}
+
+fn read_results(path: &Path) -> Result<MooResultFile, Error> {
+    let data = fs::read(path)?;
+    Ok(MooResultFile::read(&mut Cursor::new(data))?)
+}
@@ -0,0 +1,92 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::{args::GlobalOptions, commands::verify::args::VerifyParams, file::read_moo_file, working_set::WorkingSet};
+use anyhow::Error;
+use moo::prelude::*;
+use rayon::prelude::*;
+
+struct FileMismatches {
+    file: PathBuf,
+    tests: usize,
+    mismatches: Vec<(String, MooHashMismatch)>,
+}
+
+pub fn run(global: &GlobalOptions, params: &VerifyParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
+
+    if working_set.is_empty() {
+        return Err(Error::msg("No files selected"));
+    }
+
+    let mut results: Vec<FileMismatches> = working_set
+        .par_map(|path| {
+            let verified = read_moo_file(path).and_then(|moo| {
+                let mismatches = moo.verify_hashes()?;
+                Ok(FileMismatches {
+                    file: path.clone(),
+                    tests: moo.test_ct(),
+                    mismatches: mismatches
+                        .into_iter()
+                        .map(|m| (moo.tests()[m.index].name().to_string(), m))
+                        .collect(),
+                })
+            });
+            match verified {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    log::warn!("Error verifying {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .flatten()
+        .collect();
+
+    results.sort_by(|a, b| a.file.cmp(&b.file));
+
+    let mut total = 0;
+    for file in &results {
+        for (name, m) in &file.mismatches {
+            println!("{}: {} ({})", file.file.display(), m, name);
+        }
+        total += file.mismatches.len();
+    }
+
+    global.loud(|| {
+        println!(
+            "{} stale hash(es) in {} tests (searched {} files)",
+            total,
+            results.iter().map(|f| f.tests).sum::<usize>(),
+            results.len()
+        )
+    });
+
+    if total > 0 {
+        return Err(anyhow::anyhow!("{} stale hash(es) found", total));
+    }
+    Ok(())
+}
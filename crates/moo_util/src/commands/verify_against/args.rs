@@ -0,0 +1,53 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::PathBuf;
+
+use crate::args::{in_path_parser, traversal_parser, TraversalOptions};
+use bpaf::{construct, long, Parser};
+
+#[derive(Clone, Debug)]
+pub(crate) struct VerifyAgainstParams {
+    pub(crate) in_path: PathBuf,
+    pub(crate) traversal: TraversalOptions,
+    pub(crate) golden_path: PathBuf,
+    pub(crate) allow_added: bool,
+}
+
+pub(crate) fn verify_against_parser() -> impl Parser<VerifyAgainstParams> {
+    let in_path = in_path_parser();
+    let traversal = traversal_parser();
+    let golden_path = long("golden")
+        .argument::<PathBuf>("GOLDEN_PATH")
+        .help("Golden MOO file or directory to compare the input against");
+    let allow_added = long("allow-added")
+        .help("Don't fail on tests or files that are only present in the input")
+        .switch();
+
+    construct!(VerifyAgainstParams {
+        in_path,
+        traversal,
+        golden_path,
+        allow_added
+    })
+}
@@ -0,0 +1,229 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    args::GlobalOptions,
+    commands::verify_against::args::VerifyAgainstParams,
+    file::read_moo_file,
+    working_set::WorkingSet,
+};
+use anyhow::Error;
+use moo::prelude::*;
+use rayon::prelude::*;
+
+/// The outcome of comparing one input file with its golden counterpart.
+enum FileStatus {
+    Compared {
+        added:   usize,
+        removed: usize,
+        changed: usize,
+    },
+    /// The file is only in the input.
+    NewFile {
+        tests: usize,
+    },
+    /// The file is only in the golden directory.
+    MissingFile {
+        tests: usize,
+    },
+    Error(String),
+}
+
+impl FileStatus {
+    fn differs(&self, allow_added: bool) -> bool {
+        match self {
+            FileStatus::Compared {
+                added,
+                removed,
+                changed,
+            } => *removed > 0 || *changed > 0 || (*added > 0 && !allow_added),
+            FileStatus::NewFile { .. } => !allow_added,
+            FileStatus::MissingFile { .. } | FileStatus::Error(_) => true,
+        }
+    }
+}
+
+impl Display for FileStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileStatus::Compared {
+                added,
+                removed,
+                changed,
+            } => {
+                write!(f, "{} added, {} removed, {} changed", added, removed, changed)
+            }
+            FileStatus::NewFile { tests } => write!(f, "not in golden corpus ({} tests added)", tests),
+            FileStatus::MissingFile { tests } => write!(f, "missing from input ({} tests removed)", tests),
+            FileStatus::Error(e) => write!(f, "error: {}", e),
+        }
+    }
+}
+
+/// Return the path of `path` relative to `root`, used to pair input and golden files.
+fn relative_path(root: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(root) {
+        Ok(relative) if !relative.as_os_str().is_empty() => relative.to_path_buf(),
+        _ => path
+            .file_name()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| path.to_path_buf()),
+    }
+}
+
+/// Compare the tests of `input` with those of `golden`, pairing them by hash. A test with the same
+/// hash in both files is counted as changed if its contents differ.
+fn compare_files(input: &MooTestFile, golden: &MooTestFile) -> FileStatus {
+    let golden_tests: HashMap<String, &MooTest> = golden.tests().iter().map(|t| (t.hash_string(), t)).collect();
+
+    let mut added = 0;
+    let mut changed = 0;
+    let mut matched = BTreeSet::new();
+    for test in input.tests() {
+        let hash = test.hash_string();
+        match golden_tests.get(&hash) {
+            Some(other) => {
+                if !test.compare(other, false).is_empty() {
+                    changed += 1;
+                }
+                matched.insert(hash);
+            }
+            None => added += 1,
+        }
+    }
+    FileStatus::Compared {
+        added,
+        removed: golden_tests.len() - matched.len(),
+        changed,
+    }
+}
+
+pub fn run(global: &GlobalOptions, params: &VerifyAgainstParams) -> Result<(), Error> {
+    let working_set =
+        WorkingSet::from_search(&params.traversal.search(&params.in_path), None)?.with_memory_limit(global.max_memory);
+    if working_set.is_empty() {
+        return Err(Error::msg("No files selected"));
+    }
+
+    // A single input file is compared with a single golden file, or with the file of the same name
+    // in a golden directory.
+    let golden_for = |relative: &Path| {
+        if params.golden_path.is_file() {
+            params.golden_path.clone()
+        }
+        else {
+            params.golden_path.join(relative)
+        }
+    };
+
+    let mut results: Vec<(PathBuf, FileStatus)> = working_set
+        .par_map(|path| {
+            let relative = relative_path(&params.in_path, path);
+            let golden_path = golden_for(&relative);
+            let status = match read_moo_file(path) {
+                Ok(input) if golden_path.is_file() => match read_moo_file(&golden_path) {
+                    Ok(golden) => compare_files(&input, &golden),
+                    Err(e) => FileStatus::Error(format!("{}: {}", golden_path.display(), e)),
+                },
+                Ok(input) => FileStatus::NewFile { tests: input.test_ct() },
+                Err(e) => FileStatus::Error(e.to_string()),
+            };
+            (relative, status)
+        })
+        .collect();
+
+    // Golden files with no input counterpart were removed.
+    if params.golden_path.is_dir() {
+        let seen: BTreeSet<PathBuf> = results.iter().map(|(relative, _)| relative.clone()).collect();
+        let golden_set = WorkingSet::from_search(&params.traversal.search(&params.golden_path), None)?;
+        for path in golden_set.files() {
+            let relative = relative_path(&params.golden_path, path);
+            if !seen.contains(&relative) {
+                let status = match read_moo_file(path) {
+                    Ok(golden) => FileStatus::MissingFile {
+                        tests: golden.test_ct(),
+                    },
+                    Err(e) => FileStatus::Error(e.to_string()),
+                };
+                results.push((relative, status));
+            }
+        }
+    }
+
+    results.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut differing = 0;
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+    for (relative, status) in &results {
+        match status {
+            FileStatus::Compared {
+                added: a,
+                removed: r,
+                changed: c,
+            } => {
+                added += a;
+                removed += r;
+                changed += c;
+            }
+            FileStatus::NewFile { tests } => added += tests,
+            FileStatus::MissingFile { tests } => removed += tests,
+            FileStatus::Error(_) => {}
+        }
+        if status.differs(params.allow_added) {
+            differing += 1;
+        }
+        if !matches!(
+            status,
+            FileStatus::Compared {
+                added:   0,
+                removed: 0,
+                changed: 0,
+            }
+        ) {
+            println!("{}: {}", relative.display(), status);
+        }
+    }
+
+    global.loud(|| {
+        println!(
+            "{} of {} file(s) differ from {}: {} test(s) added, {} removed, {} changed",
+            differing,
+            results.len(),
+            params.golden_path.display(),
+            added,
+            removed,
+            changed
+        )
+    });
+
+    if differing > 0 {
+        return Err(anyhow::anyhow!("{} file(s) differ from the golden corpus", differing));
+    }
+    Ok(())
+}
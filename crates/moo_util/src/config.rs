@@ -0,0 +1,189 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Defaults for command-line options, read from a `moo_util.toml` file.
+//!
+//! The file is looked up in the current directory first, then in `$XDG_CONFIG_HOME/moo_util/`
+//! (or `~/.config/moo_util/`). The `MOO_UTIL_CONFIG` environment variable names a file to use
+//! instead; set it to an empty string to ignore config files entirely.
+//!
+//! Values from the file only apply when the corresponding flag is not given on the command line.
+//!
+//! ```toml
+//! output = "fixed"        # --output
+//! compress = true         # --compress / --no-compress
+//! schema = "schema.csv"   # --schema
+//! jobs = 8                # --jobs
+//!
+//! # Options of the `check` command. `[check]` is accepted as another name for this table.
+//! [lint]
+//! check_disassembly = true
+//! update_disassembly = true
+//! gdt = "00010000:0FFF"   # --gdt
+//! ```
+//!
+//! Relative paths are resolved against the directory containing the config file.
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Error};
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+
+pub(crate) const CONFIG_FILE_NAME: &str = "moo_util.toml";
+pub(crate) const CONFIG_ENV_VAR: &str = "MOO_UTIL_CONFIG";
+
+static CONFIG: OnceCell<UtilConfig> = OnceCell::new();
+
+/// Option defaults read from `moo_util.toml`. Every field is optional.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct UtilConfig {
+    pub(crate) output: Option<PathBuf>,
+    pub(crate) compress: Option<bool>,
+    pub(crate) schema: Option<PathBuf>,
+    pub(crate) jobs: Option<usize>,
+    #[serde(alias = "check")]
+    pub(crate) lint: LintConfig,
+}
+
+/// Defaults for the `check` command, from the `[lint]` table.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct LintConfig {
+    pub(crate) check_disassembly: bool,
+    pub(crate) update_disassembly: bool,
+    /// The GDTR base and limit, in the `BASE:LIMIT` form taken by `--gdt`.
+    pub(crate) gdt: Option<String>,
+}
+
+/// Return the loaded config, or an empty one if [load_config] has not been called.
+pub(crate) fn config() -> &'static UtilConfig {
+    CONFIG.get_or_init(UtilConfig::default)
+}
+
+/// Find and load the config file. Must be called before command-line arguments are parsed, since
+/// the argument parsers fall back to the values it holds.
+pub(crate) fn load_config() -> Result<&'static UtilConfig, Error> {
+    let config = match find_config_file() {
+        Some(path) => {
+            log::debug!("Using config file {}", path.display());
+            UtilConfig::from_path(&path)?
+        }
+        None => UtilConfig::default(),
+    };
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+fn find_config_file() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV_VAR) {
+        return (!path.is_empty()).then(|| PathBuf::from(path));
+    }
+
+    let local = PathBuf::from(CONFIG_FILE_NAME);
+    if local.is_file() {
+        return Some(local);
+    }
+
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    let global = config_home.join("moo_util").join(CONFIG_FILE_NAME);
+    global.is_file().then_some(global)
+}
+
+impl UtilConfig {
+    pub(crate) fn from_path(path: &Path) -> Result<Self, Error> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read config file {}", path.display()))?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        let config =
+            Self::parse(&text, base_dir).with_context(|| format!("Failed to parse config file {}", path.display()))?;
+        Ok(config)
+    }
+
+    /// Parse the text of a config file. Relative paths are joined to `base_dir`.
+    pub(crate) fn parse(text: &str, base_dir: &Path) -> Result<Self, Error> {
+        let mut config: UtilConfig = toml::from_str(text)?;
+        if config.jobs == Some(0) {
+            bail!("jobs must be at least 1");
+        }
+        config.output = config.output.map(|path| base_dir.join(path));
+        config.schema = config.schema.map(|path| base_dir.join(path));
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_all_keys() {
+        let text = r#"
+            # Corpus maintenance defaults
+            output = "fixed"   # relative to the config file
+            compress = true
+            schema = '/data/schema.csv'
+            jobs = 4
+
+            [lint]
+            check_disassembly = true
+            gdt = "00010000:0FFF"
+        "#;
+        let config = UtilConfig::parse(text, Path::new("/corpus")).unwrap();
+        assert_eq!(config.output, Some(PathBuf::from("/corpus/fixed")));
+        assert_eq!(config.compress, Some(true));
+        assert_eq!(config.schema, Some(PathBuf::from("/data/schema.csv")));
+        assert_eq!(config.jobs, Some(4));
+        assert!(config.lint.check_disassembly);
+        assert!(!config.lint.update_disassembly);
+        assert_eq!(config.lint.gdt.as_deref(), Some("00010000:0FFF"));
+    }
+
+    #[test]
+    fn accepts_check_table() {
+        let config = UtilConfig::parse("[check]\nupdate_disassembly = true", Path::new("")).unwrap();
+        assert!(config.lint.update_disassembly);
+    }
+
+    #[test]
+    fn keeps_hash_inside_strings() {
+        let config = UtilConfig::parse(r#"output = "out#1" # comment"#, Path::new("")).unwrap();
+        assert_eq!(config.output, Some(PathBuf::from("out#1")));
+    }
+
+    #[test]
+    fn rejects_unknown_keys_and_bad_types() {
+        assert!(UtilConfig::parse("outptu = \"x\"", Path::new("")).is_err());
+        assert!(UtilConfig::parse("jobs = \"8\"", Path::new("")).is_err());
+        assert!(UtilConfig::parse("jobs = 0", Path::new("")).is_err());
+        assert!(UtilConfig::parse("[lnit]", Path::new("")).is_err());
+        assert!(UtilConfig::parse("[lint]\nfix = true", Path::new("")).is_err());
+        assert!(UtilConfig::parse("compress = yes", Path::new("")).is_err());
+    }
+}
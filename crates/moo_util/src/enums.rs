@@ -35,6 +35,9 @@ pub enum CheckErrorType {
     CycleStateError(String),
     BadMetadata(String),
     DisassemblyError(String),
+    AddressWrapError(String),
+    VectorMismatch(String),
+    DescriptorCacheError(String),
 }
 
 impl Display for CheckErrorType {
@@ -61,6 +64,15 @@ impl Display for CheckErrorType {
             CheckErrorType::DisassemblyError(e) => {
                 write!(f, "Disassembly error: {}", e)
             }
+            CheckErrorType::AddressWrapError(e) => {
+                write!(f, "Address wrap error: {}", e)
+            }
+            CheckErrorType::VectorMismatch(e) => {
+                write!(f, "Exception vector mismatch: {}", e)
+            }
+            CheckErrorType::DescriptorCacheError(e) => {
+                write!(f, "Descriptor cache does not match GDT: {}", e)
+            }
         }
     }
 }
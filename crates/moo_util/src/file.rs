@@ -21,7 +21,16 @@
     DEALINGS IN THE SOFTWARE.
 */
 
-use std::path::Path;
+use std::{fs, io::Cursor, path::Path};
+
+use anyhow::Error;
+use moo::prelude::MooTestFile;
+
+/// Read a [MooTestFile] from `path`, which may be gzip-compressed.
+pub fn read_moo_file(path: impl AsRef<Path>) -> Result<MooTestFile, Error> {
+    let data = fs::read(path)?;
+    Ok(MooTestFile::read(&mut Cursor::new(data))?)
+}
 
 pub fn group_extension_from_path(path: impl AsRef<Path>) -> Option<u8> {
     path.as_ref().file_name().and_then(|os| os.to_str()).and_then(|name| {
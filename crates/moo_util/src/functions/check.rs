@@ -26,25 +26,67 @@ use crate::{
     enums::{CheckErrorDetail, CheckErrorType},
     structs::CheckErrorStatus,
 };
-use std::{io::Cursor, path::Path};
+use std::{collections::BTreeMap, path::Path};
 
-use crate::file::group_extension_from_path;
+use crate::{file::group_extension_from_path, functions::disasm::MartyDisassembler};
 use anyhow::Result;
 use moo::{
     prelude::*,
-    types::{MooBusState, MooCpuFamily, MooCpuMode, MooRamEntries},
+    registers::descriptor_table::MooTableRegister,
+    test_file::journal::MooTestEdit,
+    types::{
+        cycle_iter::MooCycleIterExt,
+        MooA20Policy,
+        MooAddressWrap,
+        MooBusState,
+        MooCpuDataBusWidth,
+        MooCpuFamily,
+        MooCpuMode,
+        MooDataWidth,
+        MooRamEntries,
+    },
 };
 
+/// Check that the architecture tag of the file header names the CPU type of the file, taken from
+/// the metadata. With `fix`, the tag is rewritten to match the metadata.
+pub fn check_arch(moo: &mut MooTestFile, fix: bool) -> Vec<CheckErrorStatus> {
+    if moo.arch_matches_cpu_type() {
+        return Vec::new();
+    }
+    let error = CheckErrorType::BadMetadata(format!(
+        "Header architecture tag '{}' does not match metadata CPU type {:?}",
+        moo.arch(),
+        moo.cpu_type()
+    ));
+    let fixed = fix && moo.repair_arch();
+    vec![error.fixed(fixed)]
+}
+
 pub fn check_metadata(metadata: &mut MooFileMetadata, file_path: impl AsRef<Path>, fix: bool) -> Vec<CheckErrorStatus> {
     let mut errors: Vec<CheckErrorStatus> = Vec::new();
 
     // Check that the CPU type is valid.
-    let mnemonic_str = String::from_utf8_lossy(&metadata.mnemonic).trim().to_string();
+    let mnemonic_str = metadata.mnemonic();
 
     if mnemonic_str.is_empty() {
         errors.push(CheckErrorType::BadMetadata("Empty mnemonic in metadata!".to_string()).fixed(false));
     }
 
+    // CPUs with only 20 address lines cannot reach the high memory area.
+    let twenty_bit = matches!(
+        MooCpuFamily::from(metadata.cpu_type),
+        MooCpuFamily::Intel8086 | MooCpuFamily::NecV30 | MooCpuFamily::Intel80186
+    );
+    if twenty_bit && metadata.a20_policy() == Some(MooA20Policy::Enabled) {
+        errors.push(
+            CheckErrorType::BadMetadata(format!(
+                "A20 policy is Enabled, but {:?} has only 20 address lines",
+                metadata.cpu_type
+            ))
+            .fixed(false),
+        );
+    }
+
     // Additional metadata checks can go here.
     let extension = group_extension_from_path(&file_path);
 
@@ -68,23 +110,26 @@ pub fn check_metadata(metadata: &mut MooFileMetadata, file_path: impl AsRef<Path
     errors
 }
 
+/// Check a test, reporting any errors found. With `opts.fix` set, fixes are not made to `test`
+/// directly but pushed to `fixes`, to be applied through the file's edit journal.
 pub fn check_test(
     index: usize,
-    test: &mut MooTest,
+    test: &MooTest,
     metadata: &MooFileMetadata,
     opts: &CheckParams,
+    fixes: &mut Vec<MooTestEdit>,
 ) -> Result<Option<CheckErrorDetail>> {
     let mut errors: Vec<CheckErrorStatus> = Vec::new();
 
-    check_test_universal(test, metadata, opts, &mut errors)?;
+    check_test_universal(test, metadata, opts, &mut errors, fixes)?;
 
     let mode = test.cpu_mode(metadata.cpu_type);
     match mode {
         MooCpuMode::RealMode => {
-            check_test_real(test, metadata, opts.fix, &mut errors)?;
+            check_test_real(test, metadata, opts.fix, &mut errors, fixes)?;
         }
         MooCpuMode::ProtectedMode => {
-            check_test_protected(test, metadata, opts.fix, &mut errors)?;
+            check_test_protected(test, metadata, opts.gdt, &mut errors)?;
         }
         _ => {
             log::warn!("Unsupported CPU mode for test check: {:?}", mode);
@@ -104,12 +149,13 @@ pub fn check_test(
 }
 
 pub fn check_test_universal(
-    test: &mut MooTest,
+    test: &MooTest,
     metadata: &MooFileMetadata,
     opts: &CheckParams,
     errors: &mut Vec<CheckErrorStatus>,
+    fixes: &mut Vec<MooTestEdit>,
 ) -> Result<()> {
-    check_disassembly(test, metadata, opts, errors)?;
+    check_disassembly(test, metadata, opts, errors, fixes)?;
 
     if test.cycles().is_empty() {
         errors.push(CheckErrorType::CycleStateError("No cycle states present!".to_string()).fixed(false));
@@ -128,7 +174,7 @@ pub fn check_test_universal(
         }
 
         // the first cycle should be a code fetch at CS:IP.
-        if let Some(csip) = test.initial_state().regs().csip_linear_real() {
+        if let Some(csip) = test.initial_state().csip_linear() {
             if csip != test.cycles().first().unwrap().address_bus {
                 errors.push(
                     CheckErrorType::CycleStateError(format!(
@@ -142,7 +188,48 @@ pub fn check_test_universal(
         }
         else {
             // Not having a valid CS:IP is an error!
-            errors.push(CheckErrorType::BadInitialState("No valid CS:IP in real mode".to_string()).fixed(false));
+            errors.push(CheckErrorType::BadInitialState("No valid CS:IP".to_string()).fixed(false));
+        }
+    }
+
+    if matches!(MooCpuDataBusWidth::from(metadata.cpu_type), MooCpuDataBusWidth::Sixteen) {
+        check_test_byte_enables(test, metadata, errors);
+    }
+
+    for glitch in test.detect_glitches(metadata.cpu_type) {
+        errors.push(CheckErrorType::CycleStateError(format!("Capture glitch at {}", glitch)).fixed(false));
+    }
+
+    if let Some(ea) = test.initial_state().ea() {
+        if !ea.is_consistent() {
+            errors.push(
+                CheckErrorType::BadInitialState(format!(
+                    "EA linear address 0x{:08X} does not match base 0x{:08X} + offset 0x{:08X}",
+                    ea.linear(),
+                    ea.base_address,
+                    ea.offset
+                ))
+                .fixed(false),
+            );
+        }
+
+        // The EA base should agree with the segment base of the initial state.
+        let initial_state = test.initial_state();
+        if let Some(base) = initial_state
+            .regs()
+            .segment_base(ea.segment(), initial_state.descriptors.as_ref())
+        {
+            if base != ea.base_address {
+                errors.push(
+                    CheckErrorType::BadInitialState(format!(
+                        "EA base 0x{:08X} does not match {:?} base 0x{:08X}",
+                        ea.base_address,
+                        ea.segment(),
+                        base
+                    ))
+                    .fixed(false),
+                );
+            }
         }
     }
 
@@ -153,10 +240,12 @@ pub fn check_test_universal(
         MooCpuFamily::Intel80286 => {
             // 286-specific universal checks can go here.
             must_halt = true;
+            check_test_lock(test, metadata, errors);
         }
         MooCpuFamily::Intel80386 => {
             // 386+ specific universal checks can go here.
             must_halt = true;
+            check_test_lock(test, metadata, errors);
         }
         _ => {}
     }
@@ -174,14 +263,21 @@ pub fn check_test_universal(
 }
 
 pub fn check_test_real(
-    test: &mut MooTest,
+    test: &MooTest,
     metadata: &MooFileMetadata,
     fix: bool,
     errors: &mut Vec<CheckErrorStatus>,
+    fixes: &mut Vec<MooTestEdit>,
 ) -> Result<()> {
     let initial_queue = test.initial_state().queue();
     if initial_queue.is_empty() {}
 
+    check_test_a20(test, metadata, errors);
+
+    if let Some(mismatch) = test.exception_vector_mismatch(metadata.cpu_type) {
+        errors.push(CheckErrorType::VectorMismatch(mismatch.to_string()).fixed(false));
+    }
+
     let family = MooCpuFamily::from(metadata.cpu_type);
     match family {
         MooCpuFamily::Intel80286 => {
@@ -192,9 +288,9 @@ pub fn check_test_real(
                 // If not, we can fix it by clearing them (if --fix is enabled).
                 let mut fixed = false;
                 if fix {
-                    let regs = test.initial_state_mut().regs_mut();
+                    let mut initial_state = test.initial_state().clone();
 
-                    match regs {
+                    match initial_state.regs_mut() {
                         MooRegisters::Sixteen(regs16) => {
                             log::trace!(
                                 "Fixing initial flags for real mode test by clearing top four bits: {:04X} -> {:04X}",
@@ -202,6 +298,7 @@ pub fn check_test_real(
                                 (initial_flags as u16) & 0x0FFF,
                             );
                             regs16.set_flags((initial_flags as u16) & 0x0FFF);
+                            fixes.push(MooTestEdit::SetInitialState(initial_state));
                             fixed = true;
                         }
                         _ => log::error!("Unsupported register set for real mode flag fixup"),
@@ -219,7 +316,7 @@ pub fn check_test_real(
 
             // Check that the flag address for an exception is valid.
             let sp_linear_real = test.initial_state().regs().sp_linear_real();
-            if let Some(exception) = test.exception_mut() {
+            if let Some(exception) = test.exception() {
                 let flag_addr = exception.flag_address;
 
                 if let Some(sp_addr) = sp_linear_real {
@@ -231,7 +328,9 @@ pub fn check_test_real(
                                 flag_addr,
                                 sp_addr - 2
                             );
+                            let mut exception = exception.clone();
                             exception.flag_address = sp_addr - 2;
+                            fixes.push(MooTestEdit::SetException(Some(exception)));
                             fixed = true;
                         }
 
@@ -263,23 +362,196 @@ pub fn check_test_real(
     Ok(())
 }
 
+/// Check that each write on a 16-bit data bus enables a valid set of byte lanes for its address
+/// via A0 and BHE, and that the bytes written on the enabled lanes agree with the final RAM state.
+pub fn check_test_byte_enables(test: &MooTest, metadata: &MooFileMetadata, errors: &mut Vec<CheckErrorStatus>) {
+    let cpu_type = metadata.cpu_type;
+
+    // The last byte written to each address, and the cycle of the write.
+    let mut written: BTreeMap<u32, (u8, usize)> = BTreeMap::new();
+
+    for t in test.cycles_iter().transactions() {
+        let bus_state = t.bus_state(cpu_type);
+        if !matches!(bus_state, MooBusState::MEMW | MooBusState::IOW) {
+            continue;
+        }
+        let Some(data) = t.data_bus
+        else {
+            continue;
+        };
+        let [lo, hi] = data.to_le_bytes();
+
+        match t.data_width(cpu_type) {
+            MooDataWidth::Invalid => {
+                errors.push(
+                    CheckErrorType::CycleStateError(format!(
+                        "{} to odd address 0x{:06X} (cycle {}) with BHE inactive enables no data lanes",
+                        bus_state.to_string().trim(),
+                        t.address,
+                        t.start
+                    ))
+                    .fixed(false),
+                );
+            }
+            _ if bus_state == MooBusState::IOW => {}
+            MooDataWidth::Sixteen => {
+                written.insert(t.address, (lo, t.start));
+                written.insert(t.address + 1, (hi, t.start));
+            }
+            MooDataWidth::EightLow => {
+                written.insert(t.address, (lo, t.start));
+            }
+            MooDataWidth::EightHigh => {
+                written.insert(t.address, (hi, t.start));
+            }
+        }
+    }
+
+    for entry in test.final_state().ram() {
+        if let Some(&(value, cycle)) = written.get(&entry.address) {
+            if value != entry.value {
+                errors.push(
+                    CheckErrorType::CycleStateError(format!(
+                        "Byte 0x{:02X} written to 0x{:06X} (cycle {}) does not match final RAM value 0x{:02X}",
+                        value, entry.address, cycle, entry.value
+                    ))
+                    .fixed(false),
+                );
+            }
+        }
+    }
+}
+
+/// Check that LOCK is asserted on the memory cycles of locked instructions, and only there.
+/// Only the 286 and 386 record the LOCK pin. The locked read-modify-write cycles these CPUs run
+/// on their own behalf are not attributed to the instruction.
+pub fn check_test_lock(test: &MooTest, metadata: &MooFileMetadata, errors: &mut Vec<CheckErrorStatus>) {
+    let cpu_type = metadata.cpu_type;
+
+    if test.is_locked(cpu_type) {
+        let locked_memory_cycle = test
+            .cycles_iter()
+            .transactions()
+            .any(|t| t.locked && matches!(t.bus_state(cpu_type), MooBusState::MEMR | MooBusState::MEMW));
+        if !locked_memory_cycle {
+            let reason = if test.has_lock_prefix(cpu_type) {
+                "LOCK prefix"
+            }
+            else {
+                "implicitly locked XCHG"
+            };
+            errors.push(
+                CheckErrorType::CycleStateError(format!(
+                    "Instruction has {} but no memory bus cycle asserted LOCK",
+                    reason
+                ))
+                .fixed(false),
+            );
+        }
+    }
+    else {
+        // Interrupt acknowledge cycles are locked by the CPU itself, as are the read-modify-write
+        // cycles the 286 and 386 use to set a descriptor's accessed bit or a page table entry's
+        // accessed and dirty bits: a locked read followed by a locked write to the same address.
+        let transactions: Vec<_> = test.cycles_iter().transactions().collect();
+        let mut system_rmw = vec![false; transactions.len()];
+        for (i, pair) in transactions.windows(2).enumerate() {
+            if pair.iter().all(|t| t.locked)
+                && pair[0].address == pair[1].address
+                && pair[0].bus_state(cpu_type) == MooBusState::MEMR
+                && pair[1].bus_state(cpu_type) == MooBusState::MEMW
+            {
+                system_rmw[i] = true;
+                system_rmw[i + 1] = true;
+            }
+        }
+
+        for (t, _) in transactions.iter().zip(system_rmw).filter(|(_, rmw)| !rmw) {
+            let bus_state = t.bus_state(cpu_type);
+            if t.locked && bus_state != MooBusState::INTA {
+                errors.push(
+                    CheckErrorType::CycleStateError(format!(
+                        "Spurious LOCK on {} cycle at 0x{:06X} (cycle {}) for unlocked instruction",
+                        bus_state.to_string().trim(),
+                        t.address,
+                        t.start
+                    ))
+                    .fixed(false),
+                );
+            }
+        }
+    }
+}
+
+/// Check a real-mode test's accesses across the 1MB boundary against the file's A20 policy.
+pub fn check_test_a20(test: &MooTest, metadata: &MooFileMetadata, errors: &mut Vec<CheckErrorStatus>) {
+    let wraps = test.address_wraps(metadata.cpu_type);
+
+    match metadata.a20_policy() {
+        None | Some(MooA20Policy::Unspecified) => {}
+        Some(MooA20Policy::Masked) => {
+            // With A20 masked, nothing should ever appear on the bus at or above 1MB.
+            for wrap in &wraps {
+                if let MooAddressWrap::HighMemoryAccess { cycle, address } = wrap {
+                    errors.push(
+                        CheckErrorType::AddressWrapError(format!(
+                            "Bus access at 0x{:06X} (cycle {}) is above 1MB, but A20 is masked",
+                            address, cycle
+                        ))
+                        .fixed(false),
+                    );
+                }
+            }
+        }
+        Some(MooA20Policy::Enabled) => {
+            // With A20 enabled, an operand past 1MB should not be accessed at its wrapped address.
+            for wrap in &wraps {
+                if let MooAddressWrap::EffectiveAddress { linear_address } = wrap {
+                    let wrapped = linear_address & 0xF_FFFF;
+                    let wrapped_access = test.cycles_iter().transactions().find(|t| {
+                        t.address == wrapped
+                            && matches!(t.bus_state(metadata.cpu_type), MooBusState::MEMR | MooBusState::MEMW)
+                    });
+                    if let Some(t) = wrapped_access {
+                        errors.push(
+                            CheckErrorType::AddressWrapError(format!(
+                                "Operand at 0x{:06X} was accessed at wrapped address 0x{:05X} (cycle {}), but A20 is enabled",
+                                linear_address, wrapped, t.start
+                            ))
+                            .fixed(false),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Check protected-mode tests. With a GDTR given by `--gdt`, the initial descriptor caches are
+/// compared with the GDT entries in the initial RAM.
 pub fn check_test_protected(
-    _test: &MooTest,
-    _metadata: &MooFileMetadata,
-    _fix: bool,
-    _errors: &mut Vec<CheckErrorStatus>,
+    test: &MooTest,
+    metadata: &MooFileMetadata,
+    gdt: Option<MooTableRegister>,
+    errors: &mut Vec<CheckErrorStatus>,
 ) -> Result<()> {
+    if let Some(gdtr) = gdt {
+        let initial = test.initial_state();
+        let table = initial.descriptor_table(gdtr, MooCpuFamily::from(metadata.cpu_type));
+        for mismatch in initial.descriptor_cache_mismatches(&table) {
+            errors.push(CheckErrorType::DescriptorCacheError(mismatch.to_string()).fixed(false));
+        }
+    }
     Ok(())
 }
 
 pub fn check_disassembly(
-    test: &mut MooTest,
-    _metadata: &MooFileMetadata,
+    test: &MooTest,
+    metadata: &MooFileMetadata,
     opts: &CheckParams,
     errors: &mut Vec<CheckErrorStatus>,
+    fixes: &mut Vec<MooTestEdit>,
 ) -> Result<()> {
-    use marty_dasm::prelude::*;
-
     // Check disassembly
     let test_name = test.name().to_string();
     let test_name_trimmed = test_name.trim();
@@ -299,13 +571,11 @@ pub fn check_disassembly(
         return Ok(());
     }
 
-    let decoder_opts = DecoderOptions {
-        cpu: CpuType::Intel80386,
-        ..Default::default()
-    };
-    let mut decoder = Decoder::new(Cursor::new(&decode_vec), decoder_opts);
-
-    let decode_result = decoder.decode_next();
+    // Decode with the instruction set of the file's CPU.
+    let mut disassembler = MartyDisassembler::new(true);
+    let cpu_type = metadata.cpu_type;
+    let ip = test.initial_state().csip_linear().unwrap_or(0);
+    let decode_result = disassembler.decode(cpu_type, &decode_vec, ip);
 
     let log_decode_err = |test: &MooTest, e: &mut Vec<CheckErrorStatus>, fixed: bool| {
         e.push(
@@ -318,14 +588,7 @@ pub fn check_disassembly(
         );
     };
 
-    let mut output = String::new();
-    let options = FormatOptions {
-        ip: test.initial_state().regs().csip_linear_real().unwrap_or(0),
-        iced_mnemonics: true,
-        ..FormatOptions::default()
-    };
-
-    let marty_i = match decode_result {
+    let decoded = match decode_result {
         Ok(instr) => instr,
         Err(_e) => {
             // Decode failed, probably due to insufficient bytes.
@@ -337,15 +600,13 @@ pub fn check_disassembly(
                 if let Some(inst_offset) = ram_entries.find(test.bytes()) {
                     let fetches = ram_entries.get_consecutive_bytes(inst_offset);
 
-                    let mut decoder = Decoder::new(Cursor::new(&fetches), decoder_opts);
-                    match decoder.decode_next() {
+                    match disassembler.decode(cpu_type, &fetches, ip) {
                         Ok(instr) => {
                             log_decode_err(test, errors, true);
-                            *test.bytes_mut() = instr.instruction_bytes.clone();
-
-                            let mut output = String::new();
-                            NasmFormatter.format_instruction(&instr, &options, &mut output);
-                            *test.name_mut() = output;
+                            fixes.push(MooTestEdit::SetBytes(
+                                fetches[..instr.length.min(fetches.len())].to_vec(),
+                            ));
+                            fixes.push(MooTestEdit::SetName(instr.text.clone()));
 
                             instr
                         }
@@ -368,14 +629,16 @@ pub fn check_disassembly(
     };
 
     if opts.check_disassembly {
-        NasmFormatter.format_instruction(&marty_i, &options, &mut output);
+        let output = decoded.text;
 
         if test_name_trimmed != output {
             // Disassembly does not match test name.
             let mut fixed = false;
 
             if opts.fix && opts.update_disassembly {
-                *test.name_mut() = output.clone();
+                // Replace any rename queued above, rather than renaming twice.
+                fixes.retain(|fix| !matches!(fix, MooTestEdit::SetName(_)));
+                fixes.push(MooTestEdit::SetName(output.clone()));
                 fixed = true;
             }
 
@@ -395,3 +658,57 @@ pub fn check_disassembly(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::check_test_lock;
+    use moo::{prelude::*, types::MooTestState};
+
+    /// Build a 286 bus cycle with ALE asserted. LOCK is active low.
+    fn ale_cycle(address: u32, status: u8, locked: bool) -> MooCycleState {
+        let lock = if locked { 0 } else { MooCycleState::PIN_LOCK };
+        MooCycleState {
+            pins0: MooCycleState::PIN_ALE | lock,
+            address_bus: address,
+            bus_state: status,
+            ..Default::default()
+        }
+    }
+
+    fn lock_errors(cycles: &[MooCycleState]) -> usize {
+        // mov al,[bx]
+        let test = MooTest::new(
+            String::new(),
+            None,
+            &[0x8A, 0x07],
+            MooTestState::default(),
+            MooTestState::default(),
+            cycles,
+            None,
+            None,
+        );
+        let metadata = MooFileMetadata::new(1, 0, MooCpuType::Intel80286, 0, Some(0));
+        let mut errors = Vec::new();
+        check_test_lock(&test, &metadata, &mut errors);
+        errors.len()
+    }
+
+    #[test]
+    fn ignores_locked_read_modify_write() {
+        // Setting a descriptor's accessed bit: a locked read and write of the same word.
+        let cycles = [
+            ale_cycle(0x1005, 0b0101, true),
+            ale_cycle(0x1005, 0b0110, true),
+            ale_cycle(0x2000, 0b0101, false),
+        ];
+        assert_eq!(lock_errors(&cycles), 0);
+    }
+
+    #[test]
+    fn flags_other_locked_cycles() {
+        assert_eq!(lock_errors(&[ale_cycle(0x2000, 0b0101, true)]), 1);
+        // A locked write to a different address is not a read-modify-write.
+        let cycles = [ale_cycle(0x1005, 0b0101, true), ale_cycle(0x1006, 0b0110, true)];
+        assert_eq!(lock_errors(&cycles), 2);
+    }
+}
@@ -0,0 +1,106 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! [MooDisassembler] adapters for the disassemblers available to moo_util.
+
+use std::io::Cursor;
+
+use moo::{
+    prelude::{MooCpuFamily, MooCpuType, MooDecodedInstruction, MooDisassembler},
+    types::errors::MooError,
+};
+
+/// A [MooDisassembler] backed by marty_dasm, formatting in NASM syntax.
+pub struct MartyDisassembler {
+    iced_mnemonics: bool,
+}
+
+impl MartyDisassembler {
+    /// Create a [MartyDisassembler]. With `iced_mnemonics`, mnemonics are written the way
+    /// iced-x86 writes them, as `gen` has always named tests.
+    pub fn new(iced_mnemonics: bool) -> Self {
+        Self { iced_mnemonics }
+    }
+
+    /// Select the marty_dasm instruction set for a [MooCpuType]. The 8086 family has its own
+    /// decoder, so that opcodes such as `0F` (`pop cs`) decode as the 8088 executes them; later
+    /// CPUs are decoded with the 80386 instruction set.
+    fn decoder_cpu(cpu_type: MooCpuType) -> marty_dasm::prelude::CpuType {
+        use marty_dasm::prelude::CpuType;
+        match MooCpuFamily::from(cpu_type) {
+            MooCpuFamily::Intel8086 => CpuType::Intel8088,
+            _ => CpuType::Intel80386,
+        }
+    }
+}
+
+impl MooDisassembler for MartyDisassembler {
+    fn decode(&mut self, cpu_type: MooCpuType, bytes: &[u8], ip: u32) -> Result<MooDecodedInstruction, MooError> {
+        use marty_dasm::prelude::*;
+
+        let decoder_opts = DecoderOptions {
+            cpu: Self::decoder_cpu(cpu_type),
+            ..Default::default()
+        };
+        let mut decoder = Decoder::new(Cursor::new(bytes), decoder_opts);
+        let instr = decoder
+            .decode_next()
+            .map_err(|e| MooError::DecodeError(format!("{:02X?}: {:?}", bytes, e)))?;
+
+        let options = FormatOptions {
+            ip,
+            iced_mnemonics: self.iced_mnemonics,
+            ..FormatOptions::default()
+        };
+        let mut text = String::new();
+        NasmFormatter.format_instruction(&instr, &options, &mut text);
+        Ok(MooDecodedInstruction::from_text(instr.instruction_bytes.len(), text))
+    }
+}
+
+/// A [MooDisassembler] backed by iced-x86, formatting in NASM syntax. iced-x86 decodes the 80386
+/// and later instruction sets for every CPU type.
+#[cfg(feature = "iced")]
+#[derive(Default)]
+pub struct IcedDisassembler;
+
+#[cfg(feature = "iced")]
+impl MooDisassembler for IcedDisassembler {
+    fn decode(&mut self, _cpu_type: MooCpuType, bytes: &[u8], ip: u32) -> Result<MooDecodedInstruction, MooError> {
+        use iced_x86::{Decoder, DecoderOptions, Formatter, NasmFormatter};
+
+        let mut decoder = Decoder::with_ip(16, bytes, ip as u64, DecoderOptions::NONE);
+        let instr = decoder.decode();
+        if instr.is_invalid() {
+            return Err(MooError::DecodeError(format!("{:02X?}: invalid instruction", bytes)));
+        }
+
+        let mut text = String::new();
+        NasmFormatter::new().format(&instr, &mut text);
+        Ok(MooDecodedInstruction {
+            length: instr.len(),
+            mnemonic: format!("{:?}", instr.mnemonic()).to_ascii_lowercase(),
+            text,
+        })
+    }
+}
@@ -0,0 +1,64 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::functions::disasm::MartyDisassembler;
+use moo::disasm::MooDisassemblyNamer;
+
+/// The disassembly syntax used to name tests.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NameStyle {
+    /// NASM syntax with NASM mnemonics.
+    Nasm,
+    /// NASM syntax with the mnemonics used by iced-x86, as written by `gen`.
+    Iced,
+}
+
+impl NameStyle {
+    /// Returns a [MooNameFormatter](moo::prelude::MooNameFormatter) that names tests in this style.
+    pub fn formatter(self) -> MooDisassemblyNamer<MartyDisassembler> {
+        MooDisassemblyNamer::new(MartyDisassembler::new(self == NameStyle::Iced), self.to_string())
+    }
+}
+
+impl FromStr for NameStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "nasm" => Ok(NameStyle::Nasm),
+            "iced" => Ok(NameStyle::Iced),
+            _ => Err(format!("Unknown name style '{}', expected 'nasm' or 'iced'", s)),
+        }
+    }
+}
+
+impl Display for NameStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameStyle::Nasm => write!(f, "nasm"),
+            NameStyle::Iced => write!(f, "iced"),
+        }
+    }
+}
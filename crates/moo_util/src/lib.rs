@@ -0,0 +1,108 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! The commands behind the `moo_util` binary, and its `moo_report` alias.
+
+mod args;
+mod changes;
+mod commands;
+mod config;
+mod enums;
+mod file;
+mod functions;
+mod output;
+mod structs;
+mod util;
+mod working_set;
+
+use std::ffi::OsString;
+
+use crate::args::{app_options, Command};
+
+use anyhow::Error;
+use bpaf::{doc::Section, Args};
+
+/// Parse `args`, which exclude the program name, and run the selected command.
+/// `name` is the program name shown in usage and help messages.
+pub fn run(name: &str, args: &[OsString]) -> Result<(), Error> {
+    // Parsers fall back to config file values, so load the config first.
+    config::load_config()?;
+
+    let app_params = match app_options().run_inner(Args::from(args).set_name(name)) {
+        Ok(app_params) => app_params,
+        Err(e) => {
+            e.print_message(100);
+            std::process::exit(e.exit_code());
+        }
+    };
+
+    if let Some(jobs) = app_params.global.jobs {
+        rayon::ThreadPoolBuilder::new().num_threads(jobs).build_global()?;
+    }
+
+    let command_result = match &app_params.command {
+        Command::Version => {
+            println!("mootility v{}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+        Command::HelpMan => {
+            print!(
+                "{}",
+                app_options().render_manpage(name, Section::General, None, None, Some("MOO utilities"))
+            );
+            Ok(())
+        }
+        Command::Completions(shell) => commands::completions::run(name, *shell),
+        Command::Display(params) => commands::display::run(&app_params.global, params),
+        Command::Head(params) | Command::Tail(params) | Command::Show(params) => {
+            commands::inspect::run(&app_params.global, params)
+        }
+        Command::Find(params) => commands::find::run(&app_params.global, params),
+        Command::Gen(params) => commands::gen::run(&app_params.global, params),
+        Command::Mutate(params) => commands::gen::mutate(&app_params.global, params),
+        Command::Regen(params) => commands::gen::regen(&app_params.global, params),
+        Command::GrepCycles(params) => commands::grep_cycles::run(&app_params.global, params),
+        Command::Outliers(params) => commands::outliers::run(&app_params.global, params),
+        Command::Replay(params) => commands::replay::run(&app_params.global, params),
+        Command::Results(params) => commands::results::run(&app_params.global, params),
+        Command::Check(params) => commands::check::run(&app_params.global, params),
+        Command::Coverage(params) => commands::coverage::run(&app_params.global, params),
+        Command::Diff(params) => commands::diff::run(&app_params.global, params),
+        Command::Edit(params) => commands::edit::run(&app_params.global, params),
+        Command::Meta(params) => commands::meta::run(&app_params.global, params),
+        Command::Verify(params) => commands::verify::run(&app_params.global, params),
+        Command::VerifyAgainst(params) => commands::verify_against::run(&app_params.global, params),
+        Command::Report(params) => commands::report::run(&app_params.global, params),
+    };
+
+    match command_result {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            eprintln!("Command '{}' failed: {}", app_params.command, e);
+            for cause in e.chain().skip(1) {
+                eprintln!("Caused by: {}", cause);
+            }
+            std::process::exit(1);
+        }
+    }
+}
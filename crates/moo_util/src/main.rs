@@ -20,45 +20,14 @@
     FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
     DEALINGS IN THE SOFTWARE.
 */
-mod args;
-mod commands;
-mod enums;
-mod file;
-mod functions;
-mod schema_db;
-mod structs;
-mod util;
-mod working_set;
 
-use crate::args::{command_parser, Command};
+use std::ffi::OsString;
 
 use anyhow::Error;
-use bpaf::Parser;
 
 fn main() -> Result<(), Error> {
     env_logger::init();
 
-    let app_params = command_parser().run();
-
-    let command_result = match &app_params.command {
-        Command::Version => {
-            println!("mootility v{}", env!("CARGO_PKG_VERSION"));
-            Ok(())
-        }
-        Command::Display(params) => commands::display::run(&app_params.global, params),
-        Command::Find(params) => commands::find::run(&app_params.global, params),
-        Command::Check(params) => commands::check::run(&app_params.global, params),
-        Command::Edit(params) => commands::edit::run(&app_params.global, params),
-    };
-
-    match command_result {
-        Ok(_) => Ok(()),
-        Err(e) => {
-            eprintln!("Command '{}' failed: {}", app_params.command, e);
-            for cause in e.chain().skip(1) {
-                eprintln!("Caused by: {}", cause);
-            }
-            std::process::exit(1);
-        }
-    }
+    let args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    moo_util::run("moo_util", &args)
 }
@@ -0,0 +1,130 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! Output path handling shared by commands that write MOO files.
+//!
+//! An [OutputPathPolicy] maps an input file name to a file name in the output directory,
+//! selecting `.MOO` or `.MOO.gz` depending on whether compression was requested, and decides
+//! what to do when the output file already exists.
+
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Error};
+
+/// What to do when an output file already exists.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) enum CollisionPolicy {
+    /// Refuse to write the file.
+    #[default]
+    Error,
+    /// Replace the existing file.
+    Overwrite,
+    /// Write to the first free name of the form `NAME_1.MOO`, `NAME_2.MOO`, ...
+    Suffix,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct OutputPathPolicy {
+    out_dir:   PathBuf,
+    compress:  bool,
+    collision: CollisionPolicy,
+}
+
+impl OutputPathPolicy {
+    pub(crate) fn new(out_dir: impl Into<PathBuf>, compress: bool, collision: CollisionPolicy) -> Self {
+        Self {
+            out_dir: out_dir.into(),
+            compress,
+            collision,
+        }
+    }
+
+    /// Return the path to write the output for `input` to, creating the output directory if needed.
+    /// The input's base name and extension are kept, apart from the `.gz` suffix which is added or
+    /// removed according to the compression setting.
+    pub(crate) fn path_for_input(&self, input: &Path) -> Result<PathBuf, Error> {
+        let file_name = input
+            .file_name()
+            .with_context(|| format!("Input path {} has no file name", input.display()))?;
+        let (stem, extension) = split_file_name(file_name);
+        self.resolve(&stem, &extension)
+    }
+
+    /// Return the path to write a file named `stem` with a MOO extension to, creating the output
+    /// directory if needed.
+    pub(crate) fn path_for_stem(&self, stem: &str) -> Result<PathBuf, Error> {
+        self.resolve(stem, "MOO")
+    }
+
+    fn resolve(&self, stem: &str, extension: &str) -> Result<PathBuf, Error> {
+        std::fs::create_dir_all(&self.out_dir)
+            .with_context(|| format!("Failed to create output directory {}", self.out_dir.display()))?;
+
+        let path = self.out_dir.join(self.file_name(stem, extension));
+        if !path.exists() {
+            return Ok(path);
+        }
+        match self.collision {
+            CollisionPolicy::Overwrite => Ok(path),
+            CollisionPolicy::Error => bail!(
+                "Output file {} already exists (use --overwrite or --suffix)",
+                path.display()
+            ),
+            CollisionPolicy::Suffix => {
+                let path = (1..)
+                    .map(|n| self.out_dir.join(self.file_name(&format!("{}_{}", stem, n), extension)))
+                    .find(|p| !p.exists())
+                    .unwrap();
+                Ok(path)
+            }
+        }
+    }
+
+    fn file_name(&self, stem: &str, extension: &str) -> String {
+        if self.compress {
+            format!("{}.{}.gz", stem, extension)
+        }
+        else {
+            format!("{}.{}", stem, extension)
+        }
+    }
+}
+
+/// Split a file name such as `D2.1.MOO.gz` into its stem (`D2.1`) and MOO extension (`MOO`).
+/// A missing extension is taken to be `MOO`.
+fn split_file_name(file_name: &OsStr) -> (String, String) {
+    let path = Path::new(file_name);
+    let path = match path.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("gz") => Path::new(path.file_stem().unwrap_or_default()),
+        _ => path,
+    };
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "MOO".to_string());
+    (stem, extension)
+}
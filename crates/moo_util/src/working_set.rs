@@ -21,16 +21,74 @@
     DEALINGS IN THE SOFTWARE.
 */
 use std::{
+    fs,
     io,
     path::{Path, PathBuf},
+    sync::{Condvar, Mutex},
 };
 
+use moo::prelude::MooFileSearch;
 use once_cell::sync::Lazy;
-use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator};
+use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use regex::Regex;
 
 pub static MOO_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\.moo(\.gz)?$").expect("valid regex"));
 
+/// Decoded tests take up several times the space of the file they were read from.
+const DECODE_OVERHEAD: u64 = 4;
+/// Typical compression ratio of a gzipped MOO file.
+const GZIP_RATIO: u64 = 10;
+
+/// Limits the estimated memory in use by files being decoded concurrently.
+#[derive(Debug)]
+struct MemoryBudget {
+    limit:    u64,
+    in_use:   Mutex<u64>,
+    released: Condvar,
+}
+
+impl MemoryBudget {
+    fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            in_use: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Block until `size` bytes are available. A file larger than the whole budget is let through
+    /// once nothing else is in flight.
+    fn acquire(&self, size: u64) -> MemoryPermit<'_> {
+        let size = size.min(self.limit);
+        let mut in_use = self.in_use.lock().unwrap();
+        while *in_use + size > self.limit {
+            in_use = self.released.wait(in_use).unwrap();
+        }
+        *in_use += size;
+        MemoryPermit { budget: self, size }
+    }
+}
+
+struct MemoryPermit<'a> {
+    budget: &'a MemoryBudget,
+    size:   u64,
+}
+
+impl Drop for MemoryPermit<'_> {
+    fn drop(&mut self) {
+        *self.budget.in_use.lock().unwrap() -= self.size;
+        self.budget.released.notify_all();
+    }
+}
+
+/// Rough estimate of the memory needed to read and decode the file at `path`.
+fn decoded_size_estimate(path: &Path) -> u64 {
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let compressed = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+    let ratio = if compressed { GZIP_RATIO } else { 1 };
+    size.saturating_mul(ratio * DECODE_OVERHEAD)
+}
+
 /// Collect files and read them one-by-one into an internal buffer.
 ///
 /// Behavior:
@@ -40,7 +98,8 @@ pub static MOO_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\.moo(\.gz)?$"
 /// - Files are sorted by file name (UTF-8) for deterministic iteration.
 #[derive(Debug)]
 pub struct WorkingSet {
-    files: Vec<PathBuf>,
+    files:  Vec<PathBuf>,
+    budget: Option<MemoryBudget>,
 }
 
 impl WorkingSet {
@@ -48,8 +107,18 @@ impl WorkingSet {
         self.files.len()
     }
 
+    /// Build a working set from the MOO files directly inside `path`, or from `path` itself if it
+    /// is a file.
     pub fn from_path<P: AsRef<Path>>(path: P, limit: Option<usize>) -> io::Result<Self> {
-        WorkingSet::from_path_regex(path, None, limit)
+        WorkingSet::from_search(&MooFileSearch::new(path.as_ref()), limit)
+    }
+
+    /// Build a working set from the files found by a [MooFileSearch], keeping at most `limit`.
+    pub fn from_search(search: &MooFileSearch, limit: Option<usize>) -> io::Result<Self> {
+        let mut files = search.collect()?;
+        log::debug!("Found {} MOO file(s) under {}", files.len(), search.root().display());
+        files.truncate(limit.unwrap_or(usize::MAX));
+        Ok(Self { files, budget: None })
     }
 
     /// Build a working set from a path and a regex.
@@ -93,7 +162,14 @@ impl WorkingSet {
             });
         }
 
-        Ok(Self { files })
+        Ok(Self { files, budget: None })
+    }
+
+    /// Limit the estimated memory used by files processed concurrently by [WorkingSet::par_map]
+    /// to `limit` bytes. `None` removes the limit.
+    pub fn with_memory_limit(mut self, limit: Option<u64>) -> Self {
+        self.budget = limit.map(MemoryBudget::new);
+        self
     }
 
     /// Total number of files.
@@ -131,6 +207,22 @@ impl WorkingSet {
         self.files.par_iter()
     }
 
+    /// Map `f` over the files in parallel. If a memory limit is set, a file is not handed to `f`
+    /// until its estimated decoded size fits in the budget alongside the files already in flight.
+    pub fn par_map<'a, R, F>(&'a self, f: F) -> impl ParallelIterator<Item = R> + 'a
+    where
+        R: Send,
+        F: Fn(&PathBuf) -> R + Sync + Send + 'a,
+    {
+        self.files.par_iter().map(move |path| {
+            let _permit = self
+                .budget
+                .as_ref()
+                .map(|budget| budget.acquire(decoded_size_estimate(path)));
+            f(path)
+        })
+    }
+
     pub fn into_par_iter(self) -> rayon::vec::IntoIter<PathBuf> {
         use rayon::prelude::*;
         self.files.into_par_iter()
@@ -191,4 +283,17 @@ mod tests {
         assert_eq!(ws.total(), 0);
         Ok(())
     }
+
+    #[test]
+    fn par_map_with_memory_limit() -> io::Result<()> {
+        let tmp = tempfile::tempdir()?;
+        for i in 0..8 {
+            fs::write(tmp.path().join(format!("{:02X}.MOO", i)), vec![0u8; 256])?;
+        }
+        // Each file is larger than the budget, so they must be processed one at a time.
+        let ws = WorkingSet::from_path(tmp.path(), None)?.with_memory_limit(Some(16));
+        let sizes: Vec<u64> = ws.par_map(|p| fs::metadata(p).unwrap().len()).collect();
+        assert_eq!(sizes, vec![256; 8]);
+        Ok(())
+    }
 }
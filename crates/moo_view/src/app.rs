@@ -0,0 +1,190 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use eframe::egui;
+use moo::prelude::*;
+
+use crate::test_view::TestView;
+
+struct LoadedFile {
+    path: PathBuf,
+    file: MooTestFile,
+}
+
+/// The viewer window: a path bar, a searchable list of the tests in the open file, and the
+/// selected test.
+#[derive(Default)]
+pub struct MooViewApp {
+    loaded: Option<LoadedFile>,
+    path_input: String,
+    error: Option<String>,
+    search: String,
+    /// The indices of the tests matching `search`.
+    matches: Vec<usize>,
+    selected: Option<usize>,
+    view: TestView,
+}
+
+impl MooViewApp {
+    /// Open the MOO file at `path`, replacing the current one. Gzipped files are decompressed.
+    pub fn open(&mut self, path: PathBuf) {
+        self.path_input = path.display().to_string();
+        match read_file(&path) {
+            Ok(file) => {
+                log::debug!("Read {} tests from {}", file.test_ct(), path.display());
+                self.selected = (file.test_ct() > 0).then_some(0);
+                self.loaded = Some(LoadedFile { path, file });
+                self.error = None;
+                self.view = TestView::default();
+                self.update_matches();
+            }
+            Err(e) => self.error = Some(format!("Error opening {}: {}", path.display(), e)),
+        }
+    }
+
+    fn update_matches(&mut self) {
+        let query = self.search.trim().to_lowercase();
+        self.matches = match &self.loaded {
+            Some(loaded) => loaded
+                .file
+                .tests()
+                .iter()
+                .enumerate()
+                .filter(|(index, test)| test_matches(test, *index, &query))
+                .map(|(index, _)| index)
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    fn path_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("File:");
+            let response = ui.add(egui::TextEdit::singleline(&mut self.path_input).desired_width(600.0));
+            let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("Open").clicked() || entered {
+                self.open(PathBuf::from(self.path_input.trim()));
+            }
+            if let Some(loaded) = &self.loaded {
+                ui.separator();
+                ui.label(format!(
+                    "{}: {} tests",
+                    loaded.file.cpu_type().to_str(),
+                    loaded.file.test_ct()
+                ));
+            }
+        });
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+
+    fn test_list(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            if ui.text_edit_singleline(&mut self.search).changed() {
+                self.update_matches();
+            }
+        });
+        ui.label(format!("{} matching", self.matches.len()));
+        ui.separator();
+
+        let Some(loaded) = &self.loaded
+        else {
+            return;
+        };
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show_rows(
+            ui,
+            row_height,
+            self.matches.len(),
+            |ui, rows| {
+                for &index in &self.matches[rows] {
+                    let test = &loaded.file.tests()[index];
+                    let label = egui::RichText::new(format!("{:>5} {}", index, test.name())).monospace();
+                    if ui.selectable_label(self.selected == Some(index), label).clicked() {
+                        self.selected = Some(index);
+                    }
+                }
+            },
+        );
+    }
+}
+
+impl eframe::App for MooViewApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let dropped = ctx.input(|i| i.raw.dropped_files.first().and_then(|f| f.path.clone()));
+        if let Some(path) = dropped {
+            self.open(path);
+        }
+
+        egui::TopBottomPanel::top("path_bar").show(ctx, |ui| self.path_bar(ui));
+        egui::SidePanel::left("test_list")
+            .default_width(320.0)
+            .show(ctx, |ui| self.test_list(ui));
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let Some(loaded) = &self.loaded
+            else {
+                ui.label("Open a MOO file to inspect its tests.");
+                return;
+            };
+            match self.selected {
+                Some(index) => self
+                    .view
+                    .show(ui, &loaded.file.tests()[index], index, loaded.file.cpu_type()),
+                None => {
+                    ui.label(format!("{} has no tests.", loaded.path.display()));
+                }
+            }
+        });
+    }
+}
+
+fn read_file(path: &Path) -> anyhow::Result<MooTestFile> {
+    let mut reader = BufReader::new(File::open(path)?);
+    Ok(MooTestFile::read(&mut reader)?)
+}
+
+/// Returns true if `query`, already lowercased, matches the test's index, a substring of its name,
+/// a prefix of its hash, or a run of its instruction bytes in hex. An empty query matches all.
+fn test_matches(test: &MooTest, index: usize, query: &str) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+    let bytes = test
+        .bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    index.to_string() == query
+        || test.name().to_lowercase().contains(query)
+        || test.hash_string().to_lowercase().starts_with(query)
+        || bytes.contains(query)
+}
@@ -0,0 +1,56 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+//! `moo_view` is a graphical inspector for MOO files. Open a file by passing its path on the
+//! command line, entering it in the path bar, or dropping it on the window, e.g.
+//! `moo_view tests/8088/00.MOO.gz`.
+
+mod app;
+mod test_view;
+
+use std::path::PathBuf;
+
+use app::MooViewApp;
+
+fn main() -> eframe::Result<()> {
+    env_logger::init();
+
+    let path = std::env::args_os().nth(1).map(PathBuf::from);
+    let options = eframe::NativeOptions {
+        viewport: eframe::egui::ViewportBuilder::default()
+            .with_inner_size([1200.0, 800.0])
+            .with_drag_and_drop(true),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "MOO Viewer",
+        options,
+        Box::new(move |_cc| {
+            let mut app = MooViewApp::default();
+            if let Some(path) = path {
+                app.open(path);
+            }
+            Ok(Box::new(app))
+        }),
+    )
+}
@@ -0,0 +1,191 @@
+/*
+    MOO-rs Copyright 2025 Daniel Balsom
+    https://github.com/dbalsom/moo
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the “Software”),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+*/
+
+use eframe::egui::{self, RichText};
+use moo::{prelude::*, types::MooCycleStatePrinter};
+
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+enum Tab {
+    #[default]
+    Registers,
+    Memory,
+    Cycles,
+}
+
+/// The panel showing a single test: its registers before and after, the memory it touched, and
+/// its cycle trace.
+#[derive(Default)]
+pub struct TestView {
+    tab: Tab,
+    cycle_search: String,
+}
+
+impl TestView {
+    pub fn show(&mut self, ui: &mut egui::Ui, test: &MooTest, index: usize, cpu_type: MooCpuType) {
+        ui.heading(format!("#{} {}", index, test.name()));
+        ui.monospace(format!("Bytes: {:02X?}", test.bytes()));
+        ui.monospace(format!("Hash:  {}", test.hash_string()));
+        if let Some(context) = test.exception_context(cpu_type) {
+            ui.label(format!("Exception: {}", context));
+        }
+        if let Some(mismatch) = test.exception_vector_mismatch(cpu_type) {
+            ui.colored_label(ui.visuals().warn_fg_color, format!("Vector mismatch: {}", mismatch));
+        }
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.tab, Tab::Registers, "Registers");
+            ui.selectable_value(&mut self.tab, Tab::Memory, "Memory");
+            ui.selectable_value(&mut self.tab, Tab::Cycles, format!("Cycles ({})", test.cycles().len()));
+        });
+        ui.separator();
+
+        match self.tab {
+            Tab::Registers => registers(ui, test),
+            Tab::Memory => memory(ui, test),
+            Tab::Cycles => self.cycles(ui, test, cpu_type),
+        }
+    }
+
+    fn cycles(&mut self, ui: &mut egui::Ui, test: &MooTest, cpu_type: MooCpuType) {
+        let mut printer = MooCycleStatePrinter {
+            cpu_type,
+            address_latch: 0,
+            state: MooCycleState::default(),
+            show_cycle_num: true,
+            cycle_num: 0,
+        };
+        let query = self.cycle_search.trim().to_lowercase();
+        let mut lines = Vec::new();
+        for (cycle_idx, cycle) in test.cycles().iter().enumerate() {
+            if cycle.ale() {
+                printer.address_latch = cycle.address_bus;
+            }
+            printer.state = *cycle;
+            printer.cycle_num = cycle_idx;
+            let line = printer.to_string();
+            if query.is_empty() || line.to_lowercase().contains(&query) {
+                lines.push((cycle.ale(), line));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Search:");
+            ui.text_edit_singleline(&mut self.cycle_search);
+            ui.label(format!("{} of {} cycles", lines.len(), test.cycles().len()));
+        });
+        ui.separator();
+
+        // Cycles that begin a bus transaction stand out from the rest.
+        let ale_color = ui.visuals().strong_text_color();
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, lines.len(), |ui, rows| {
+                for (ale, line) in &lines[rows] {
+                    let text = RichText::new(line).monospace();
+                    ui.label(if *ale { text.color(ale_color) } else { text });
+                }
+            });
+    }
+}
+
+fn registers(ui: &mut egui::Ui, test: &MooTest) {
+    let initial_regs = test.initial_state().regs();
+    let final_state = test.full_final_state();
+    let width = match initial_regs {
+        MooRegisters::Sixteen(_) => 4,
+        MooRegisters::ThirtyTwo(_) => 8,
+    };
+    let (_, initial) = initial_regs.named_values();
+    let (_, final_values) = final_state.regs().named_values();
+    let changed_color = ui.visuals().warn_fg_color;
+
+    egui::Grid::new("registers").striped(true).show(ui, |ui| {
+        ui.strong("Register");
+        ui.strong("Initial");
+        ui.strong("Final");
+        ui.end_row();
+        for (name, before) in &initial {
+            let after = final_values.iter().find(|(n, _)| n == name).map(|(_, v)| *v);
+            ui.monospace(*name);
+            ui.monospace(format!("{:0width$X}", before));
+            match after {
+                Some(after) if after != *before => {
+                    ui.label(
+                        RichText::new(format!("{:0width$X}", after))
+                            .monospace()
+                            .color(changed_color),
+                    );
+                }
+                Some(after) => {
+                    ui.monospace(format!("{:0width$X}", after));
+                }
+                None => {
+                    ui.monospace("-");
+                }
+            }
+            ui.end_row();
+        }
+    });
+
+    let flags_diff = test.diff_flags();
+    if !flags_diff.is_empty() {
+        ui.label(format!("Flags: {} ({})", flags_diff, flags_diff.to_long_string()));
+    }
+}
+
+fn memory(ui: &mut egui::Ui, test: &MooTest) {
+    let regions = ram_diff(test.initial_state().ram(), test.final_state().ram());
+    let changed_color = ui.visuals().warn_fg_color;
+    let byte_str = |value: Option<u8>| value.map_or("--".to_string(), |v| format!("{:02X}", v));
+
+    egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        for (region_idx, region) in regions.iter().enumerate() {
+            ui.strong(format!(
+                "{:06X}-{:06X}: {} changed",
+                region.start,
+                region.end - 1,
+                region.changed_ct()
+            ));
+            egui::Grid::new(("memory", region_idx)).striped(true).show(ui, |ui| {
+                ui.strong("Address");
+                ui.strong("Initial");
+                ui.strong("Final");
+                ui.end_row();
+                for byte in &region.bytes {
+                    ui.monospace(format!("{:06X}", byte.address));
+                    ui.monospace(byte_str(byte.before));
+                    let after = RichText::new(byte_str(byte.after)).monospace();
+                    ui.label(if byte.changed() {
+                        after.color(changed_color)
+                    }
+                    else {
+                        after
+                    });
+                    ui.end_row();
+                }
+            });
+            ui.add_space(8.0);
+        }
+    });
+}